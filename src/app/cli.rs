@@ -0,0 +1,501 @@
+//! Command-line arguments of the app binary.
+//!
+//! `postman-gpui https://api.example.com/users --method POST --header "A: b"`
+//! launches with a pre-filled request, `.pgreq` paths are opened as tabs,
+//! `--import -` opens a curl command or `.http` requests piped to stdin,
+//! `--viewer` opens everything read-only, `--data-dir` opens another data
+//! directory (someone else's copied workspace, say) and `--completions <shell>`
+//! prints a completion script for bash, zsh or fish.
+
+use crate::errors::AppError;
+use crate::models::{HttpMethod, Request, RequestFile};
+use crate::utils::http_file::parse_request_text;
+use std::ffi::OsString;
+use std::io::Read;
+use std::path::PathBuf;
+
+/// Name the binary is installed as, used in help and completion scripts
+pub const BINARY_NAME: &str = "postman-gpui";
+
+/// What the command line asks for
+#[derive(Debug, Clone)]
+pub enum CliCommand {
+    Launch(LaunchArgs),
+    Help,
+    Version,
+    Completions(Shell),
+}
+
+/// Files and request to open when the window appears
+#[derive(Debug, Clone, Default)]
+pub struct LaunchArgs {
+    pub files: Vec<PathBuf>,
+    pub request: Option<Request>,
+    /// curl command or `.http` file whose requests open in new tabs
+    pub import: Option<ImportSource>,
+    /// Open the app in viewer mode (see `AppConfig::read_only`)
+    pub viewer: bool,
+    /// Data directory to use instead of the default one
+    pub data_dir: Option<PathBuf>,
+}
+
+/// Where `--import` reads from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ImportSource {
+    Stdin,
+    File(PathBuf),
+}
+
+impl ImportSource {
+    /// Read the source and parse its requests
+    pub fn read(&self) -> Result<Vec<Request>, AppError> {
+        let text = match self {
+            Self::Stdin => {
+                let mut text = String::new();
+                std::io::stdin()
+                    .read_to_string(&mut text)
+                    .map_err(|e| AppError::FileError(format!("Could not read stdin: {e}")))?;
+                text
+            }
+            Self::File(path) => std::fs::read_to_string(path).map_err(|e| {
+                AppError::FileError(format!("Could not read {}: {e}", path.display()))
+            })?,
+        };
+        parse_request_text(&text)
+    }
+}
+
+/// Shells a completion script can be generated for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 3] = [Shell::Bash, Shell::Zsh, Shell::Fish];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Result<Self, AppError> {
+        Self::ALL
+            .into_iter()
+            .find(|shell| shell.name() == name)
+            .ok_or_else(|| {
+                AppError::ValidationError(format!(
+                    "Unknown shell \"{name}\" (expected bash, zsh or fish)"
+                ))
+            })
+    }
+}
+
+struct Flag {
+    short: Option<char>,
+    long: &'static str,
+    /// Name of the flag's value, `None` for switches
+    value: Option<&'static str>,
+    /// The value is a path, completed as a file name
+    path_value: bool,
+    help: &'static str,
+}
+
+const FLAGS: [Flag; 9] = [
+    Flag {
+        short: Some('X'),
+        long: "method",
+        value: Some("METHOD"),
+        path_value: false,
+        help: "HTTP method of the pre-filled request",
+    },
+    Flag {
+        short: Some('H'),
+        long: "header",
+        value: Some("\"NAME: VALUE\""),
+        path_value: false,
+        help: "Header of the pre-filled request (repeatable)",
+    },
+    Flag {
+        short: Some('d'),
+        long: "data",
+        value: Some("BODY"),
+        path_value: false,
+        help: "Body of the pre-filled request",
+    },
+    Flag {
+        short: None,
+        long: "import",
+        value: Some("FILE"),
+        path_value: true,
+        help: "Open a curl command or .http file in new tabs (- reads stdin)",
+    },
+    Flag {
+        short: None,
+        long: "viewer",
+        value: None,
+        path_value: false,
+        help: "Open read-only, without editing or saving anything",
+    },
+    Flag {
+        short: None,
+        long: "data-dir",
+        value: Some("DIR"),
+        path_value: true,
+        help: "Use another data directory (with --viewer, only read)",
+    },
+    Flag {
+        short: None,
+        long: "completions",
+        value: Some("SHELL"),
+        path_value: false,
+        help: "Print a completion script for bash, zsh or fish",
+    },
+    Flag {
+        short: Some('h'),
+        long: "help",
+        value: None,
+        path_value: false,
+        help: "Print this help",
+    },
+    Flag {
+        short: Some('V'),
+        long: "version",
+        value: None,
+        path_value: false,
+        help: "Print the version",
+    },
+];
+
+// Values offered by completion scripts for a flag's value
+fn value_choices(long: &str) -> Vec<String> {
+    match long {
+        "method" => HttpMethod::all().iter().map(|m| m.to_string()).collect(),
+        "completions" => Shell::ALL.iter().map(|s| s.name().to_string()).collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse the arguments after the program name
+pub fn parse_args(args: impl IntoIterator<Item = OsString>) -> Result<CliCommand, AppError> {
+    let mut args = args.into_iter();
+    let mut files = Vec::new();
+    let mut url: Option<String> = None;
+    let mut method: Option<HttpMethod> = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut body: Option<String> = None;
+    let mut import: Option<ImportSource> = None;
+    let mut viewer = false;
+    let mut data_dir: Option<PathBuf> = None;
+
+    while let Some(arg) = args.next() {
+        let Some(text) = arg.to_str() else {
+            // Only file names can be outside UTF-8
+            files.push(PathBuf::from(arg));
+            continue;
+        };
+        // Process serial number older macOS versions pass to apps opened from Finder
+        if text.starts_with("-psn_") {
+            continue;
+        }
+        if !text.starts_with('-') {
+            let path = PathBuf::from(text);
+            if RequestFile::is_request_file(&path) {
+                files.push(path);
+            } else if url.replace(text.to_string()).is_some() {
+                return Err(AppError::ValidationError(format!(
+                    "Only one URL can be given (got another: {text})"
+                )));
+            }
+            continue;
+        }
+
+        let (name, inline_value) = match text.split_once('=') {
+            Some((name, value)) if name.starts_with("--") => (name, Some(value.to_string())),
+            _ => (text, None),
+        };
+        let flag = FLAGS
+            .iter()
+            .find(|flag| {
+                name.strip_prefix("--") == Some(flag.long)
+                    || flag.short.is_some_and(|short| name == format!("-{short}"))
+            })
+            .ok_or_else(|| AppError::ValidationError(format!("Unknown option {name}")))?;
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next().and_then(|value| value.into_string().ok()))
+                .ok_or_else(|| AppError::ValidationError(format!("{name} needs a value")))
+        };
+        match flag.long {
+            "method" => {
+                method = Some(HttpMethod::from_str(&value()?).map_err(AppError::ParseError)?)
+            }
+            "header" => {
+                let header = value()?;
+                let (key, value) = header.split_once(':').ok_or_else(|| {
+                    AppError::ValidationError(format!(
+                        "Header \"{header}\" should look like \"Name: value\""
+                    ))
+                })?;
+                headers.push((key.trim().to_string(), value.trim().to_string()));
+            }
+            "data" => body = Some(value()?),
+            "import" => {
+                import = Some(match value()?.as_str() {
+                    "-" => ImportSource::Stdin,
+                    path => ImportSource::File(PathBuf::from(path)),
+                })
+            }
+            "viewer" => viewer = true,
+            "data-dir" => data_dir = Some(PathBuf::from(value()?)),
+            "completions" => return Ok(CliCommand::Completions(Shell::from_name(&value()?)?)),
+            "help" => return Ok(CliCommand::Help),
+            _ => return Ok(CliCommand::Version),
+        }
+    }
+
+    let request = match url {
+        Some(url) => {
+            // Like curl, a body without a method is POSTed
+            let method = method.unwrap_or(if body.is_some() {
+                HttpMethod::POST
+            } else {
+                HttpMethod::GET
+            });
+            let mut request = Request::new(method, url);
+            for (key, value) in &headers {
+                request.add_header(key, value);
+            }
+            if let Some(body) = &body {
+                request.set_body(body);
+            }
+            Some(request)
+        }
+        None if method.is_some() || !headers.is_empty() || body.is_some() => {
+            return Err(AppError::ValidationError(
+                "--method, --header and --data need a URL".to_string(),
+            ));
+        }
+        None => None,
+    };
+    Ok(CliCommand::Launch(LaunchArgs {
+        files,
+        request,
+        import,
+        viewer,
+        data_dir,
+    }))
+}
+
+/// Text printed by `--help`
+pub fn help_text() -> String {
+    let mut text = format!(
+        "Postman GPUI {}\n\nUsage: {BINARY_NAME} [OPTIONS] [URL] [FILE.pgreq...]\n\nOptions:\n",
+        env!("CARGO_PKG_VERSION")
+    );
+    for flag in &FLAGS {
+        let short = flag.short.map(|s| format!("-{s}, ")).unwrap_or_default();
+        let value = flag.value.map(|v| format!(" {v}")).unwrap_or_default();
+        text.push_str(&format!(
+            "  {:<32} {}\n",
+            format!("{short}--{}{value}", flag.long),
+            flag.help
+        ));
+    }
+    text
+}
+
+/// Completion script for `shell`, printed by `--completions`
+pub fn completion_script(shell: Shell) -> String {
+    match shell {
+        Shell::Bash => bash_completion(),
+        Shell::Zsh => zsh_completion(),
+        Shell::Fish => fish_completion(),
+    }
+}
+
+fn bash_completion() -> String {
+    let function = format!("_{}", BINARY_NAME.replace('-', "_"));
+    let mut all_flags = Vec::new();
+    let mut value_cases = String::new();
+    for flag in &FLAGS {
+        let mut names = vec![format!("--{}", flag.long)];
+        names.extend(flag.short.map(|short| format!("-{short}")));
+        all_flags.extend(names.iter().cloned());
+        if flag.value.is_some() {
+            let choices = value_choices(flag.long);
+            let reply = if flag.path_value {
+                "            COMPREPLY=($(compgen -f -- \"$cur\"))\n".to_string()
+            } else if choices.is_empty() {
+                String::new()
+            } else {
+                format!(
+                    "            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n",
+                    choices.join(" ")
+                )
+            };
+            value_cases.push_str(&format!(
+                "        {})\n{reply}            return ;;\n",
+                names.join("|")
+            ));
+        }
+    }
+    format!(
+        "{function}() {{\n    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\"\n    local prev=\"${{COMP_WORDS[COMP_CWORD-1]}}\"\n    case \"$prev\" in\n{value_cases}    esac\n    if [[ \"$cur\" == -* ]]; then\n        COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n    else\n        COMPREPLY=($(compgen -o plusdirs -f -X '!*.pgreq' -- \"$cur\"))\n    fi\n}}\ncomplete -o filenames -F {function} {BINARY_NAME}\n",
+        all_flags.join(" ")
+    )
+}
+
+fn zsh_completion() -> String {
+    let mut script = format!("#compdef {BINARY_NAME}\n\n_arguments \\\n");
+    for flag in &FLAGS {
+        let repeat = if flag.long == "header" { "'*'" } else { "" };
+        let names = match flag.short {
+            Some(short) => format!("{repeat}{{-{short},--{}}}'", flag.long),
+            None => format!("'--{}", flag.long),
+        };
+        // The message after `:` is shown while completing the value
+        let value = match flag.value {
+            Some(_) => {
+                let choices = value_choices(flag.long);
+                let action = if flag.path_value {
+                    "_files".to_string()
+                } else if choices.is_empty() {
+                    String::new()
+                } else {
+                    format!("({})", choices.join(" "))
+                };
+                format!(":{}:{action}", flag.long)
+            }
+            None => String::new(),
+        };
+        script.push_str(&format!("  {names}[{}]{value}' \\\n", flag.help));
+    }
+    script.push_str("  '*:URL or request file:_files -g \"*.pgreq\"'\n");
+    script
+}
+
+fn fish_completion() -> String {
+    let mut script = String::new();
+    for flag in &FLAGS {
+        let mut line = format!("complete -c {BINARY_NAME}");
+        if let Some(short) = flag.short {
+            line.push_str(&format!(" -s {short}"));
+        }
+        line.push_str(&format!(" -l {}", flag.long));
+        if flag.path_value {
+            line.push_str(" -r -F");
+        } else if flag.value.is_some() {
+            line.push_str(" -x");
+            let choices = value_choices(flag.long);
+            if !choices.is_empty() {
+                line.push_str(&format!(" -a \"{}\"", choices.join(" ")));
+            }
+        }
+        line.push_str(&format!(" -d \"{}\"\n", flag.help));
+        script.push_str(&line);
+    }
+    script.push_str(&format!(
+        "complete -c {BINARY_NAME} -k -a \"(__fish_complete_suffix .pgreq)\"\n"
+    ));
+    script
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliCommand, AppError> {
+        parse_args(args.iter().map(OsString::from))
+    }
+
+    fn launch(args: &[&str]) -> LaunchArgs {
+        match parse(args).unwrap() {
+            CliCommand::Launch(launch) => launch,
+            other => panic!("expected a launch, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_prefilled_request() {
+        let args = launch(&[
+            "https://api.example.com/users",
+            "--method",
+            "post",
+            "-H",
+            "Authorization: Bearer abc",
+            "--header=Accept: application/json",
+            "saved.pgreq",
+        ]);
+        let request = args.request.unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(request.url, "https://api.example.com/users");
+        assert_eq!(
+            request.headers,
+            vec![
+                ("Authorization".to_string(), "Bearer abc".to_string()),
+                ("Accept".to_string(), "application/json".to_string()),
+            ]
+        );
+        assert_eq!(args.files, vec![PathBuf::from("saved.pgreq")]);
+
+        let request = launch(&["https://example.com", "-d", "{}"])
+            .request
+            .unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(request.body.as_deref(), Some("{}"));
+
+        let args = launch(&["-psn_0_12345"]);
+        assert!(args.request.is_none() && args.files.is_empty());
+        assert!(!args.viewer);
+
+        let args = launch(&["--viewer", "shared.pgreq"]);
+        assert!(args.viewer);
+        assert_eq!(args.files, vec![PathBuf::from("shared.pgreq")]);
+        assert_eq!(args.data_dir, None);
+
+        let args = launch(&["--viewer", "--data-dir", "exported-workspace"]);
+        assert!(args.viewer);
+        assert_eq!(args.data_dir, Some(PathBuf::from("exported-workspace")));
+
+        assert_eq!(launch(&["--import", "-"]).import, Some(ImportSource::Stdin));
+        assert_eq!(
+            launch(&["--import=requests.http"]).import,
+            Some(ImportSource::File(PathBuf::from("requests.http")))
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_and_commands() {
+        assert!(parse(&["--bogus"]).is_err());
+        assert!(parse(&["https://a.example", "https://b.example"]).is_err());
+        assert!(parse(&["--header", "A: b"]).is_err());
+        assert!(parse(&["https://a.example", "-H", "no colon"]).is_err());
+        assert!(parse(&["https://a.example", "-X"]).is_err());
+        assert!(matches!(parse(&["-h"]).unwrap(), CliCommand::Help));
+        assert!(matches!(
+            parse(&["--completions", "zsh"]).unwrap(),
+            CliCommand::Completions(Shell::Zsh)
+        ));
+        assert!(parse(&["--completions", "tcsh"]).is_err());
+    }
+
+    #[test]
+    fn test_completion_scripts_list_flags() {
+        for shell in Shell::ALL {
+            let script = completion_script(shell);
+            assert!(script.contains(BINARY_NAME));
+            for flag in ["method", "header", "data", "completions"] {
+                assert!(script.contains(flag), "{} lacks --{flag}", shell.name());
+            }
+            assert!(script.contains("PATCH"));
+        }
+        assert!(help_text().contains("-X, --method METHOD"));
+    }
+}
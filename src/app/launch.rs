@@ -0,0 +1,193 @@
+// src/app/launch.rs
+use crate::{
+    app::{
+        cli::{self, CliCommand, BINARY_NAME},
+        postman_app::{SendAndDuplicate, SwitchToNextProfile},
+        PostmanApp,
+    },
+    config::AppConfig,
+    plugins::{Plugin, PluginRegistry},
+};
+use gpui::{
+    actions, px, size, App, AppContext, Application, Bounds, KeyBinding, Menu, MenuItem,
+    WindowBounds, WindowOptions,
+};
+use std::path::PathBuf;
+
+// 定义退出动作
+actions!(postman, [Quit]);
+
+/// 处理退出应用的函数
+fn quit(_: &Quit, cx: &mut App) {
+    tracing::info!("🚪 Postman GPUI - 应用正在退出...");
+    cx.quit();
+}
+
+/// 启动应用，`plugins` 会在内置插件之后注册
+///
+/// 自定义构建可以在自己的 `main` 中调用此函数来添加插件，无需修改本项目
+pub fn run(plugins: Vec<Box<dyn Plugin>>) {
+    // 命令行参数：URL 和 --method/--header/--data 预填请求，.pgreq 文件在新标签页打开
+    // （Linux / Windows 文件关联），--viewer 以只读方式打开，--data-dir 使用另一个数据目录，
+    // --help/--version/--completions 输出后直接退出
+    let launch_args = match cli::parse_args(std::env::args_os().skip(1)) {
+        Ok(CliCommand::Launch(args)) => args,
+        Ok(CliCommand::Help) => {
+            print!("{}", cli::help_text());
+            return;
+        }
+        Ok(CliCommand::Version) => {
+            println!("{BINARY_NAME} {}", env!("CARGO_PKG_VERSION"));
+            return;
+        }
+        Ok(CliCommand::Completions(shell)) => {
+            print!("{}", cli::completion_script(shell));
+            return;
+        }
+        Err(e) => {
+            eprintln!("{BINARY_NAME}: {e}\nTry `{BINARY_NAME} --help` for the available options");
+            std::process::exit(2);
+        }
+    };
+    // --import 的请求（`-` 表示从 stdin 读取，例如 `cat request.curl | postman-gpui --import -`）
+    let imported = match launch_args.import.as_ref().map(|source| source.read()) {
+        None => Vec::new(),
+        Some(Ok(requests)) => requests,
+        Some(Err(e)) => {
+            eprintln!("{BINARY_NAME}: {e}");
+            std::process::exit(2);
+        }
+    };
+
+    // 初始化 tracing
+    tracing_subscriber::fmt()
+        .with_env_filter("postman_gpui=debug")
+        .with_target(false)
+        .with_thread_ids(true)
+        .with_line_number(true)
+        .init();
+
+    // 系统打开的 .pgreq 文件（macOS 文件关联）
+    let (open_tx, mut open_rx) = tokio::sync::mpsc::unbounded_channel::<Vec<PathBuf>>();
+    let app = Application::new();
+    app.on_open_urls(move |urls| {
+        let paths: Vec<PathBuf> = urls
+            .iter()
+            .filter_map(|url| reqwest::Url::parse(url).ok()?.to_file_path().ok())
+            .collect();
+        tracing::info!("📂 Postman GPUI - 系统请求打开文件: {:?}", paths);
+        let _ = open_tx.send(paths);
+    });
+
+    app.run(move |cx: &mut App| {
+        // 激活应用（使菜单栏在前台显示）
+        cx.activate(true);
+
+        // 注册插件（须在创建窗口之前）
+        cx.set_global(PluginRegistry::with_plugins(&plugins));
+
+        // 注册退出动作处理函数
+        cx.on_action(quit);
+
+        // 绑定快捷键 Cmd-Q (macOS) / Ctrl-Q (其他平台)
+        #[cfg(target_os = "macos")]
+        cx.bind_keys([KeyBinding::new("cmd-q", Quit, None)]);
+        #[cfg(not(target_os = "macos"))]
+        cx.bind_keys([KeyBinding::new("ctrl-q", Quit, None)]);
+
+        // 发送并复制当前请求: Cmd-Shift-Enter / Ctrl-Shift-Enter
+        #[cfg(target_os = "macos")]
+        cx.bind_keys([KeyBinding::new("cmd-shift-enter", SendAndDuplicate, None)]);
+        #[cfg(not(target_os = "macos"))]
+        cx.bind_keys([KeyBinding::new("ctrl-shift-enter", SendAndDuplicate, None)]);
+
+        // 设置应用菜单
+        cx.set_menus(vec![
+            Menu {
+                name: "Postman GPUI".into(),
+                items: vec![
+                    MenuItem::action("About Postman GPUI", Quit), // 可以后续替换为 About 动作
+                    MenuItem::separator(),
+                    #[cfg(target_os = "macos")]
+                    MenuItem::action("Hide Postman GPUI", Quit), // 可以后续替换为 Hide 动作
+                    #[cfg(target_os = "macos")]
+                    MenuItem::separator(),
+                    MenuItem::action("Quit Postman GPUI", Quit),
+                ],
+            },
+            Menu {
+                name: "Profile".into(),
+                items: vec![MenuItem::action(
+                    "Switch to Next Profile",
+                    SwitchToNextProfile,
+                )],
+            },
+            Menu {
+                name: "Request".into(),
+                items: vec![MenuItem::action("Send and Duplicate", SendAndDuplicate)],
+            },
+        ]);
+
+        let bounds = Bounds::centered(None, size(px(1600.), px(1200.0)), cx);
+        let option = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(bounds)),
+            ..Default::default()
+        };
+
+        let config = AppConfig {
+            read_only: launch_args.viewer,
+            data_dir: launch_args.data_dir.clone(),
+            ..AppConfig::new()
+        };
+        let window = cx
+            .open_window(option, |_window, cx| {
+                // 创建视图
+                let postman_app = PostmanApp::new(config, cx);
+                cx.new(|_| postman_app)
+            })
+            .expect("failed to open window");
+
+        // 订阅查询编辑器和响应查看器，恢复保存的设置
+        window
+            .update(cx, |app, window, cx| {
+                app.watch_components(cx);
+                app.restore_settings(window, cx);
+            })
+            .ok();
+
+        // 应用菜单切换 profile
+        cx.on_action(move |_: &SwitchToNextProfile, cx| {
+            window
+                .update(cx, |app, _window, cx| app.switch_to_next_profile(cx))
+                .ok();
+        });
+
+        cx.on_action(move |_: &SendAndDuplicate, cx| {
+            window
+                .update(cx, |app, _window, cx| app.send_and_duplicate(cx))
+                .ok();
+        });
+
+        if !launch_args.files.is_empty() {
+            window
+                .update(cx, |app, _window, cx| {
+                    app.open_request_files(&launch_args.files, cx)
+                })
+                .ok();
+        }
+        for request in launch_args.request.iter().chain(&imported) {
+            window
+                .update(cx, |app, _window, cx| app.open_request(request.clone(), cx))
+                .ok();
+        }
+
+        cx.spawn(async move |cx| {
+            while let Some(paths) = open_rx.recv().await {
+                window
+                    .update(cx, |app, _window, cx| app.open_request_files(&paths, cx))
+                    .ok();
+            }
+        })
+        .detach();
+    });
+}
@@ -1,4 +1,8 @@
 // src/app/mod.rs
+pub mod cli;
+pub mod launch;
 pub mod postman_app;
+pub mod request_tab;
+pub mod response_window;
 
 pub use postman_app::PostmanApp;
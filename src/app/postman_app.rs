@@ -1,22 +1,118 @@
 use crate::{
-    http::executor::RequestExecutor,
-    models::{HttpMethod, Request, RequestHistory},
+    app::{
+        request_tab::{index_after_close, RequestTab},
+        response_window::ResponseWindow,
+    },
+    config::AppConfig,
+    errors::AppError,
+    http::{
+        bulk::{parse_url_list, results_csv, run_bulk, BulkOutcome, BULK_CONCURRENCY},
+        client::StreamEvent,
+        credentials::same_origin,
+        diagnostics::{diagnose, parse_nameserver, DEFAULT_FALLBACK_NAMESERVER},
+        executor::{RequestExecutor, RequestResult},
+        grpc::{grpc_uri, GrpcCall, GrpcEvent},
+        health::{check_health, Health, HEALTH_CHECK_INTERVAL, HEALTH_CHECK_TIMEOUT},
+        mock::{routes_from_examples, MockServer, DEFAULT_MOCK_PORT},
+        pagination::{fetch_pages, join_pages, list_items, next_page_url, Page},
+        prepare::{prepare_request, PrepareOptions, PreparedRequest, Protection},
+        raw::{
+            hex_dump, normalize_line_endings, send_raw, RawResponse, RawTarget, DEFAULT_TIMEOUT,
+        },
+        replay::{parse_time_bound, replay_summary, run_replay, ReplayStep, StatusChange},
+        response::header_value,
+        scenario::{run_scenario, run_summary, PreparedStep, StepOutcome},
+        size::{RequestSize, DEFAULT_SIZE_LIMIT},
+        socket::SocketTarget,
+        websocket::{WebSocketConnection, WebSocketEvent, WebSocketTarget},
+    },
+    models::{
+        ActivityEntry, ActivityKind, ActivityLog, BaselineStore, CertificateStore, DomainDefaults,
+        DomainSettings, Environment, ExampleLibrary, Feature, FormatRules, HistoryEntry, Hook,
+        HttpMethod, ProfileRegistry, ProfileStorage, Request, RequestFile, RequestHooks,
+        ResponseExample, SavedResponse, SnippetLibrary, Stage, TrustedHooks, UsageStats, Vault,
+        VaultHeader,
+    },
+    plugins::{builtin::ProtobufRenderer, PluginRegistry},
+    state::AppState,
+    storage::Autosave,
     ui::components::{
-        body_input::{setup_body_input_key_bindings, BodyInput, BodyType},
+        api_explorer::{ApiExplorer, ApiExplorerEvent},
+        body_input::{setup_body_input_key_bindings, BodyInput, BodyInputEvent, BodyType},
+        certificates_page::{CertificatesPage, CertificatesPageEvent},
+        checkbox::{master_checkbox, setup_checkbox_key_bindings, MasterCheckState, ToggleRow},
+        csv_grid::CsvGrid,
+        domains_page::{DomainsPage, DomainsPageEvent},
+        drag_row::{drag_handle, DraggedRow, RowTable},
+        dropdown::{Dropdown, DropdownEvent},
+        graphql_docs::GraphqlDocs,
         header_input::{setup_header_input_key_bindings, HeaderInput},
         history_list::{HistoryList, HistoryListEvent},
+        message_log::{MessageLog, MessageLogKind},
         method_selector::{MethodSelector, MethodSelectorEvent},
-        response_viewer::{setup_response_viewer_key_bindings, ResponseViewer},
+        ndjson_viewer::NdjsonViewer,
+        response_viewer::{
+            setup_response_viewer_key_bindings, ResponseState, ResponseViewer, ResponseViewerEvent,
+        },
+        snippets_page::{SnippetsPage, SnippetsPageEvent},
         url_input::{setup_url_input_key_bindings, UrlInput, UrlInputEvent},
     },
+    utils::{
+        assertions::AssertionContext,
+        clipboard::{detect_import, ClipboardImport},
+        conditional::{ConditionalHeader, Validators},
+        csv::is_csv_content_type,
+        export_encryption::ExportRecipients,
+        format_rules::{apply_rules, Highlight, Placement},
+        formatter::{format_body, format_count, format_response_body, format_size},
+        graphql::{self, introspection_body, is_subscription, Schema},
+        graphql_ws::{self, websocket_url, ClientMessage, ServerMessage},
+        hooks::{run_hook, substitute, HOOK_TIMEOUT},
+        jose::{BodyProtection, JOSE_CONTENT_TYPE},
+        links::origin,
+        multipart::{self, PartPreview, PreviewKind},
+        name_template::{self, NameFields, DEFAULT_HISTORY_NAME_TEMPLATE},
+        ndjson::is_ndjson_content_type,
+        openapi::ApiSpec,
+        pdf::TextDocument,
+        protobuf::{DescriptorPool, MethodDescriptor},
+        range::ByteRange,
+        reorder::move_item,
+        response_diff::{diff_responses, Change},
+        sniff::{detect, sniff, BodyKind},
+        spec_infer::infer_spec,
+        status_codes::reason_phrase,
+    },
 };
+use chrono::{DateTime, Local, Utc};
 use gpui::{
-    div, px, rgb, App, AppContext, Context, Entity, FontWeight, InteractiveElement, IntoElement,
-    ParentElement, Render, StatefulInteractiveElement, Styled, Window,
+    actions, div, img, prelude::FluentBuilder, px, rgb, size, AnyView, App, AppContext, Bounds,
+    Context, Entity, ExternalPaths, FocusHandle, FontWeight, InteractiveElement, IntoElement,
+    ParentElement, Render, StatefulInteractiveElement, Styled, Task, TitlebarOptions, Window,
+    WindowBounds, WindowHandle, WindowOptions,
 };
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+actions!(profile, [SwitchToNextProfile]);
+actions!(request, [SendAndDuplicate]);
+
+// Client certificate choice that leaves it to the domain defaults
+const DOMAIN_CERTIFICATE: &str = "Domain default";
+
+// Body protection choice that sends the body as is
+const NO_PROTECTION: &str = "None";
 
-// Maximum length for URL display in history
-const MAX_HISTORY_URL_LENGTH: usize = 40;
+// How often the clipboard is checked while watching
+const CLIPBOARD_POLL_INTERVAL: Duration = Duration::from_millis(800);
+
+// Lines of a body shown in the split-screen compare pane
+const MAX_COMPARE_LINES: usize = 500;
 
 // UI Color constants
 const COLOR_CHECKBOX_ENABLED_BG: u32 = 0x0000_7acc;
@@ -28,66 +124,831 @@ const COLOR_HEADER_ENABLED_BG: u32 = 0x00ff_ffff;
 const COLOR_HEADER_ENABLED_BORDER: u32 = 0x0028_a745;
 const COLOR_HEADER_DISABLED_BG: u32 = 0x00f8_f9fa;
 const COLOR_HEADER_DISABLED_BORDER: u32 = 0x00cc_cccc;
+const COLOR_HEADER_FOCUSED_BORDER: u32 = 0x0000_7acc;
 const COLOR_TEXT_ENABLED: u32 = 0x0000_0000;
 const COLOR_TEXT_DISABLED: u32 = 0x006c_757d;
 
+// 由自动保存写入的文件（历史记录和场景由 AppState 保存）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum SavedFile {
+    Settings,
+    Profiles,
+    Activity,
+    Usage,
+    TrustedHooks,
+    Baselines,
+    Examples,
+    FormatRules,
+    Domains,
+    Certificates,
+    Snippets,
+}
+
+impl SavedFile {
+    // 保存失败时提示中的名称
+    fn label(self) -> &'static str {
+        match self {
+            SavedFile::Settings => "settings",
+            SavedFile::Profiles => "profiles",
+            SavedFile::Activity => "activity log",
+            SavedFile::Usage => "usage statistics",
+            SavedFile::TrustedHooks => "allowed hooks",
+            SavedFile::Baselines => "golden baselines",
+            SavedFile::Examples => "response examples",
+            SavedFile::FormatRules => "formatting rules",
+            SavedFile::Domains => "domain defaults",
+            SavedFile::Certificates => "certificates",
+            SavedFile::Snippets => "assertion snippets",
+        }
+    }
+}
+
+// 决定请求大小估算的输入 - 与上次相同时不再重新估算
+#[derive(PartialEq)]
+struct SizeInputs {
+    method: HttpMethod,
+    url: String,
+    headers: Vec<(String, String)>,
+    graphql: bool,
+    body_type: BodyType,
+    multipart: bool,
+    body_len: u64,
+    parts: Vec<PartPreview>,
+    auth: Option<(String, String)>,
+    protection: Option<(BodyProtection, Option<String>)>,
+    body_serializer: Option<String>,
+    domain: Option<DomainDefaults>,
+}
+
+// form-data / GraphQL 请求未设置 Content-Type 时自动添加的值：附加文件或
+// 指定了各部分类型的表单以 multipart 发送
+fn default_content_type(
+    graphql: bool,
+    method: HttpMethod,
+    body_type: &BodyType,
+    as_multipart: bool,
+) -> Option<String> {
+    if graphql {
+        Some("application/json".to_string())
+    } else if method == HttpMethod::POST && *body_type == BodyType::FormData {
+        Some(if as_multipart {
+            format!(
+                "multipart/form-data; boundary={}",
+                multipart::new_boundary()
+            )
+        } else {
+            "application/x-www-form-urlencoded".to_string()
+        })
+    } else {
+        None
+    }
+}
+
+// 进行中的 gRPC 调用（丢弃即取消）
+struct GrpcSession {
+    call: GrpcCall,
+    pool: DescriptorPool,
+    method: MethodDescriptor,
+    _task: Task<()>,
+}
+
+// 分页列表响应的下一页 - 用相同的 headers 获取其余页面
+struct NextPage {
+    request: Request,
+    headers: Vec<(String, String)>,
+    first: Page,
+    url: String,
+}
+
+// 场景的一次运行 - 每个步骤的阶段、"METHOD url" 和结果（None 表示尚未完成）
+struct ScenarioRun {
+    scenario: String,
+    steps: Vec<(Stage, String)>,
+    outcomes: Vec<Option<StepOutcome>>,
+}
+
+// 会话重放的一次运行 - 每个请求的 "METHOD url"、记录的状态码和本次结果
+// （None 表示尚未完成）
+struct ReplayRun {
+    requests: Vec<String>,
+    recorded: Vec<Option<u16>>,
+    outcomes: Vec<Option<BulkOutcome>>,
+}
+
+// 进行中的 GraphQL subscription（graphql-ws 协议，丢弃即停止接收）
+struct GraphqlSubscription {
+    connection: WebSocketConnection,
+    // 服务器确认连接（connection_ack）后发送
+    subscribe: ClientMessage,
+    _task: Task<()>,
+}
+
 pub struct PostmanApp {
     method_selector: Entity<MethodSelector>,
     url_input: Entity<UrlInput>,
+    // Optional Unix socket / named pipe target
+    socket_input: Entity<HeaderInput>,
+    // Imported client certificate to present, or the domain default
+    certificate_selector: Entity<Dropdown>,
+    // Request hooks - local commands run before sending and after the response
+    pre_hook_input: Entity<HeaderInput>,
+    pre_hook_variable_input: Entity<HeaderInput>,
+    post_hook_input: Entity<HeaderInput>,
+    post_hook_variable_input: Entity<HeaderInput>,
+    show_hooks: bool,
+    // Hook output by variable name, substituted into requests as {{name}}
+    hook_variables: HashMap<String, String>,
+    // Hook commands the active profile allows to run
+    trusted_hooks: TrustedHooks,
+    // Commands waiting for confirmation, and those allowed for the next send only
+    pending_hooks: Option<Vec<String>>,
+    hooks_approved_once: Vec<String>,
+    // Pre-request hook running in the background; the request is sent when it
+    // finishes
+    hook_task: Option<Task<()>>,
 
     // Headers - (enabled, key, value)
     headers: Vec<(bool, String, String)>,
 
     // Body - 使用BodyInput组件替代字符串
     body_input: Entity<BodyInput>,
+    // Previews of the parts when form data attaches files (sent as multipart)
+    part_previews: Vec<PartPreview>,
+    // Last request size estimate and what it was computed from
+    request_size: RefCell<Option<(SizeInputs, RequestSize)>>,
 
     // HTTP Request Executor
     request_executor: RequestExecutor,
 
     // Response viewer component
     response_viewer: Entity<ResponseViewer>,
+    // NDJSON responses are shown one record per row instead
+    ndjson_viewer: Entity<NdjsonViewer>,
+    show_records: bool,
+    // CSV responses are shown as a grid
+    csv_grid: Entity<CsvGrid>,
+    show_grid: bool,
+    // Streaming mode - the response is read chunk by chunk in the background
+    // (dropping the task cancels the request)
+    stream_response: bool,
+    stream_task: Option<Task<()>>,
+
+    // Raw socket mode - a hand-written request is sent as-is over TCP/TLS
+    raw_mode: bool,
+    raw_address_input: Entity<HeaderInput>,
+    raw_request_input: Entity<BodyInput>,
+    // Convert `\n` to `\r\n` before sending (off to send bare LFs on purpose)
+    raw_crlf: bool,
+    raw_hex: bool,
+    raw_response: Option<Result<RawResponse, String>>,
+    raw_task: Option<Task<()>>,
+
+    // Pipe response - the response body is written to a command's stdin and
+    // its output is shown in a pane above the response
+    show_pipe: bool,
+    pipe_input: Entity<HeaderInput>,
+    pipe_output: Option<Result<String, String>>,
+    pipe_task: Option<Task<()>>,
+
+    // Pagination - offered when the shown list response has a next page; the
+    // items of every page are shown together
+    next_page: Option<NextPage>,
+    pages_task: Option<Task<()>>,
+
+    // Diagnostics - offered when a request fails before a response arrives
+    failed_url: Option<String>,
+    diagnostics: Option<Result<String, String>>,
+    diagnostics_task: Option<Task<()>>,
+    diagnose_traceroute: bool,
+    // Name server asked when the system's can't be found (empty: none)
+    diagnose_nameserver_input: Entity<HeaderInput>,
+
+    // Golden baselines - responses pinned per request (method + URL) that
+    // later responses to the same request are compared with
+    baselines: BaselineStore,
+    baseline_ignore_input: Entity<HeaderInput>,
+    // Request of the shown response, and how it differs from its baseline
+    baseline_request: Option<(HttpMethod, String)>,
+    baseline_changes: Option<Vec<Change>>,
+
+    // Response examples - responses saved (or written by hand) per request
+    // (method + URL) and opened as reference tabs
+    examples: ExampleLibrary,
+    example_name_input: Entity<HeaderInput>,
+    example_status_input: Entity<HeaderInput>,
+    example_body_input: Entity<BodyInput>,
+    show_example_editor: bool,
+    // Name of the example the active tab shows instead of a live response
+    shown_example: Option<String>,
+    // Local mock server answering with the examples
+    mock_server: Option<MockServer>,
+    mock_port_input: Entity<HeaderInput>,
+
+    // Conditional formatting - rules of the active profile that put banners
+    // above the response and badges on its tab
+    format_rules: FormatRules,
+    format_rules_input: Entity<BodyInput>,
+    show_format_rules: bool,
+    format_rules_error: Option<String>,
+    highlights: Vec<Highlight>,
+    // Time the shown response took, checked by `latency` rules
+    response_latency: Option<Duration>,
+
+    // gRPC mode - message types come from the Protobuf descriptor set, the
+    // enabled headers are sent as metadata
+    grpc_mode: bool,
+    grpc_server_input: Entity<HeaderInput>,
+    grpc_method_input: Entity<HeaderInput>,
+    grpc_message_input: Entity<BodyInput>,
+    grpc_log: Entity<MessageLog>,
+    grpc_session: Option<GrpcSession>,
+
+    // GraphQL mode - the query is POSTed to the URL above; schemas fetched by
+    // introspection are cached per endpoint for completion and validation
+    graphql_mode: bool,
+    graphql_query_input: Entity<BodyInput>,
+    graphql_variables_input: Entity<BodyInput>,
+    graphql_schemas: HashMap<String, Schema>,
+    graphql_docs: Entity<GraphqlDocs>,
+    // Subscriptions run over a WebSocket; their events replace the response
+    // viewer until the next query is sent
+    graphql_log: Entity<MessageLog>,
+    graphql_subscription: Option<GraphqlSubscription>,
+    show_graphql_log: bool,
+
+    // API explorer - endpoints of an imported OpenAPI spec, tried out
+    // through the normal request flow
+    api_spec_input: Entity<HeaderInput>,
+    api_explorer: Entity<ApiExplorer>,
+    show_api_explorer: bool,
+
+    // Per-domain defaults (headers, auth, proxy, TLS) of the active profile
+    domains_page: Entity<DomainsPage>,
+    show_domains: bool,
+    // Client certificates imported into the active profile
+    certificates_page: Entity<CertificatesPage>,
+    show_certificates: bool,
+    // Assertion snippets of the active profile, attached to requests by id
+    snippets_page: Entity<SnippetsPage>,
+    show_snippets: bool,
+    // Structural changes to the active profile (shares, imports, settings)
+    activity_log: ActivityLog,
+    show_activity: bool,
+    activity_since_yesterday: bool,
+    // Local-only counters of the features used in the active profile
+    usage_stats: UsageStats,
+    show_usage: bool,
+    // Bulk URL runner - GETs every URL of a pasted list, a few at a time
+    show_bulk: bool,
+    bulk_input: Entity<BodyInput>,
+    bulk_urls: Vec<String>,
+    bulk_outcomes: Vec<Option<BulkOutcome>>,
+    bulk_task: Option<Task<()>>,
+
+    // Scenarios - setup, main and teardown requests run together and
+    // reported as one unit, with the last run of the selected scenario
+    show_scenarios: bool,
+    scenario_name_input: Entity<HeaderInput>,
+    selected_scenario: Option<usize>,
+    scenario_run: Option<ScenarioRun>,
+    scenario_task: Option<Task<()>>,
+
+    // Collections - named groups of saved requests, opened in a new tab
+    show_collections: bool,
+    collection_name_input: Entity<HeaderInput>,
+    selected_collection: Option<usize>,
+
+    // Environments - variables substituted as {{name}} while one is active,
+    // and the profile's cookies
+    show_environments: bool,
+    environment_name_input: Entity<HeaderInput>,
+    environment_variables_input: Entity<BodyInput>,
+    selected_environment: Option<usize>,
+
+    // Session replay - the history requests of a time window sent again,
+    // each new status compared with the recorded one
+    show_replay: bool,
+    replay_from_input: Entity<HeaderInput>,
+    replay_to_input: Entity<HeaderInput>,
+    replay_run: Option<ReplayRun>,
+    replay_task: Option<Task<()>>,
 
     // Headers输入组件
     header_key_input: Entity<HeaderInput>,
     header_value_input: Entity<HeaderInput>,
+    // Focus for the headers table - the focused row can be toggled with a shortcut
+    headers_focus: FocusHandle,
+    focused_header: Option<usize>,
+    // Range header builder - in suffix mode the end field is the byte count
+    range_start_input: Entity<HeaderInput>,
+    range_end_input: Entity<HeaderInput>,
+    range_suffix: bool,
 
-    // Request history
-    request_history: RequestHistory,
+    // History and scenarios of the active profile, shared with the views
+    // that show them
+    state: Entity<AppState>,
     history_list: Entity<HistoryList>,
+    // History entry the displayed response belongs to (annotations are saved there)
+    response_entry_id: Option<u64>,
+
+    // Request tabs - the active tab is edited through the form components
+    tabs: Vec<RequestTab>,
+    active_tab: usize,
+    // Tab shown read-only beside the active one (split-screen compare)
+    compare_tab: Option<usize>,
+    // Response panel popped out into its own window
+    response_window: Option<WindowHandle<ResponseWindow>>,
+
+    // Status message shown under the title (e.g. file opened or exported)
+    notice: Option<String>,
+
+    // Settings
+    config: AppConfig,
+    // Files changed since they were last written - the autosave writes them
+    // once changes stop (history and scenarios are saved by `state`)
+    unsaved: HashSet<SavedFile>,
+    autosave: Autosave,
+
+    // Profiles - each has its own storage directory under `data_root`
+    profiles: ProfileRegistry,
+    data_root: Option<PathBuf>,
+    new_profile_input: Entity<HeaderInput>,
+    // Base URL of the active profile, and the last health check of each
+    // profile's base URL by directory name
+    base_url_input: Entity<HeaderInput>,
+    // Template the active profile names history entries with
+    history_name_input: Entity<HeaderInput>,
+    profile_health: HashMap<String, Health>,
+    health_task: Option<Task<()>>,
+
+    // Plugins - names of the selected body serializer and auth provider
+    // (looked up in the global `PluginRegistry` when sending)
+    body_serializer: Option<String>,
+    auth_provider: Option<String>,
+    auth_credential_input: Entity<HeaderInput>,
+    // Body signing / encryption - JWS or JWE with a user-provided key
+    protection_selector: Entity<Dropdown>,
+    protection_key_input: Entity<HeaderInput>,
+    protection_key_id_input: Entity<HeaderInput>,
+    // Protobuf responses - descriptor set file and message type to decode as
+    proto_descriptor_input: Entity<HeaderInput>,
+    proto_message_input: Entity<HeaderInput>,
+    proto_message: Option<String>,
+
+    // Encryption at rest - with a vault header on disk the app starts locked
+    // until the passphrase is entered
+    vault: Option<Vault>,
+    locked: bool,
+    passphrase_input: Entity<HeaderInput>,
+    // age / GPG recipients shared request files are encrypted for
+    share_recipients_input: Entity<HeaderInput>,
+
+    // Clipboard watcher - dropping the task stops it
+    clipboard_watch: Option<Task<()>>,
+    last_clipboard_text: Option<String>,
+    clipboard_offer: Option<ClipboardImport>,
 }
 
 impl PostmanApp {
-    pub fn new(cx: &mut App) -> Self {
+    pub fn new(config: AppConfig, cx: &mut App) -> Self {
         // 设置键盘绑定 - 在创建组件之前
         cx.bind_keys(setup_url_input_key_bindings());
         cx.bind_keys(setup_header_input_key_bindings());
         cx.bind_keys(setup_body_input_key_bindings());
         cx.bind_keys(setup_response_viewer_key_bindings());
+        cx.bind_keys(setup_checkbox_key_bindings());
+
+        // 未通过 launch::run 启动时（例如测试）只使用内置插件
+        if !cx.has_global::<PluginRegistry>() {
+            cx.set_global(PluginRegistry::with_plugins(&[]));
+        }
 
         let method_selector = cx.new(MethodSelector::new);
         let url_input = cx.new(|cx| UrlInput::new(cx).with_placeholder("Enter request URL..."));
-        let header_key_input =
-            cx.new(|cx| HeaderInput::new(cx).with_placeholder("Header Key (e.g., Authorization)"));
+        let socket_input = cx.new(|cx| {
+            HeaderInput::new(cx)
+                .with_placeholder("Unix socket or named pipe (optional, e.g. /var/run/docker.sock)")
+        });
+        let header_key_input = cx.new(|cx| {
+            HeaderInput::new(cx)
+                .cleans_pastes()
+                .with_placeholder("Header Key (e.g., Authorization)")
+        });
         let header_value_input = cx.new(|cx| {
-            HeaderInput::new(cx).with_placeholder("Header Value (e.g., Bearer token123)")
+            HeaderInput::new(cx)
+                .cleans_pastes()
+                .with_placeholder("Header Value (e.g., Bearer token123)")
         });
         let body_input = cx.new(|cx| {
             BodyInput::new(cx).with_placeholder("Enter request body (JSON, form data, etc.)...")
         });
-        let response_viewer = cx.new(ResponseViewer::new);
-        let history_list = cx.new(|_cx| HistoryList::new());
+        let range_start_input = cx.new(|cx| HeaderInput::new(cx).with_placeholder("Start byte"));
+        let range_end_input = cx.new(|cx| HeaderInput::new(cx).with_placeholder("End byte"));
+        let response_viewer = cx.new(|cx| ResponseViewer::new(cx).read_only(config.read_only));
+        let ndjson_viewer = cx.new(NdjsonViewer::new);
+        let csv_grid = cx.new(CsvGrid::new);
+        let pipe_input = cx.new(|cx| {
+            HeaderInput::new(cx).with_placeholder("Command, e.g. jq '.items[].id' or grep -i error")
+        });
+        let baseline_ignore_input = cx.new(|cx| {
+            HeaderInput::new(cx)
+                .with_placeholder("Ignored paths, e.g. $.requestId, $.items[].updatedAt")
+        });
+        let example_name_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("Example name, e.g. Not found"));
+        let example_status_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("Status, e.g. 404"));
+        let example_body_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Raw, cx);
+            input
+        });
+        let mock_port_input = cx
+            .new(|cx| HeaderInput::new(cx).with_placeholder(format!("Port ({DEFAULT_MOCK_PORT})")));
+        let bulk_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Raw, cx);
+            input
+        });
+        let scenario_name_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("New scenario, e.g. Create a user"));
+        let collection_name_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("New collection, e.g. Users API"));
+        let environment_name_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("New environment, e.g. Staging"));
+        let environment_variables_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Raw, cx);
+            input
+        });
+        let replay_from_input = cx.new(|cx| {
+            HeaderInput::new(cx).with_placeholder("From, e.g. 09:00 or 2024-05-01 09:00")
+        });
+        let replay_to_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("To (empty for now)"));
+        let format_rules_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Raw, cx);
+            input
+        });
+        let raw_address_input = cx.new(|cx| {
+            HeaderInput::new(cx).with_placeholder("host:port, tls://host:port or https://host")
+        });
+        let raw_request_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Raw, cx);
+            input.set_content(
+                "GET / HTTP/1.1\nHost: example.com\nConnection: close\n\n",
+                cx,
+            );
+            input
+        });
+        let grpc_server_input = cx.new(|cx| {
+            HeaderInput::new(cx).with_placeholder("Server (localhost:50051 or https://host)")
+        });
+        let grpc_method_input = cx.new(|cx| {
+            HeaderInput::new(cx).with_placeholder("Method (e.g. package.Service/Method)")
+        });
+        let grpc_message_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Json, cx);
+            input.set_content("{}", cx);
+            input
+        });
+        let grpc_log = cx.new(MessageLog::new);
+        let graphql_query_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Json, cx);
+            input.set_content("query {\n  \n}", cx);
+            input
+        });
+        let graphql_variables_input = cx.new(|cx| {
+            let mut input = BodyInput::new(cx);
+            input.set_type(BodyType::Json, cx);
+            input
+        });
+        let graphql_docs = cx.new(GraphqlDocs::new);
+        let graphql_log = cx.new(|cx| {
+            MessageLog::new(cx).with_placeholder("Send a subscription to see its events")
+        });
+        let api_spec_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("OpenAPI spec (.json / .yaml)"));
+        let api_explorer = cx.new(ApiExplorer::new);
+        let new_profile_input = cx.new(|cx| HeaderInput::new(cx).with_placeholder("New profile"));
+
+        // 加载 profile 列表及当前 profile 的历史记录
+        let data_root = config
+            .data_dir
+            .clone()
+            .or_else(ProfileRegistry::default_root);
+        let profiles = data_root
+            .as_deref()
+            .map(ProfileRegistry::load)
+            .transpose()
+            .unwrap_or_else(|e| {
+                tracing::info!("❌ PostmanApp - 加载 profiles 失败: {e}");
+                None
+            })
+            .unwrap_or_default();
+        // 保存的开关设置，查看模式和数据目录由命令行决定
+        let config = match data_root.as_deref().map(AppConfig::load).transpose() {
+            Ok(Some(saved)) => AppConfig {
+                read_only: config.read_only,
+                data_dir: config.data_dir.clone(),
+                ..saved
+            },
+            Ok(None) => config,
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 加载设置失败: {e}");
+                config
+            }
+        };
+        let base_url = profiles.active().base_url.clone().unwrap_or_default();
+        let diagnose_nameserver_input = cx.new(|cx| {
+            let mut input = HeaderInput::new(cx).with_placeholder("Fallback name server");
+            input.set_content(DEFAULT_FALLBACK_NAMESERVER, cx);
+            input
+        });
+        let base_url_input = cx.new(|cx| {
+            let mut input = HeaderInput::new(cx).with_placeholder("Base URL to health-check");
+            input.set_content(base_url, cx);
+            input
+        });
+        let history_name = Self::history_name_template_of(&profiles).to_string();
+        let history_name_input = cx.new(|cx| {
+            let mut input =
+                HeaderInput::new(cx).with_placeholder("History name, e.g. {method} {path}");
+            input.set_content(history_name, cx);
+            input
+        });
+        let passphrase_input =
+            cx.new(|cx| HeaderInput::new(cx).masked().with_placeholder("Passphrase"));
+        let share_recipients_input = cx.new(|cx| {
+            HeaderInput::new(cx).with_placeholder("Encrypt shares for (age1… / GPG ids)")
+        });
+        let auth_credential_input =
+            cx.new(|cx| HeaderInput::new(cx).masked().with_placeholder("Credential"));
+        let protection_selector = cx.new(|cx| {
+            Dropdown::new("protection-dropdown", cx).with_options(
+                std::iter::once(NO_PROTECTION.to_string())
+                    .chain(BodyProtection::all().iter().map(BodyProtection::label))
+                    .collect(),
+            )
+        });
+        let protection_key_input = cx.new(|cx| HeaderInput::new(cx).masked());
+        let protection_key_id_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("Key id (kid, optional)"));
+        let proto_descriptor_input = cx.new(|cx| {
+            HeaderInput::new(cx).with_placeholder("Descriptor set (protoc --descriptor_set_out)")
+        });
+        let proto_message_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("Message type (e.g. pkg.User)"));
+        let pre_hook_input = cx.new(|cx| {
+            HeaderInput::new(cx)
+                .with_placeholder("Before sending, e.g. gcloud auth print-access-token")
+        });
+        let post_hook_input = cx.new(|cx| {
+            HeaderInput::new(cx)
+                .with_placeholder("After the response (body on stdin), e.g. jq -r .token")
+        });
+        let pre_hook_variable_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("Variable, e.g. token"));
+        let post_hook_variable_input =
+            cx.new(|cx| HeaderInput::new(cx).with_placeholder("Variable"));
+        let locked = data_root.as_deref().is_some_and(|root| {
+            VaultHeader::load(root)
+                .unwrap_or_else(|e| {
+                    tracing::info!("❌ PostmanApp - 读取加密配置失败: {e}");
+                    None
+                })
+                .is_some()
+        });
+        // 加密的数据在输入密码解锁后才加载
+        let (
+            state,
+            domains,
+            certificates,
+            activity_log,
+            snippets,
+            trusted_hooks,
+            baselines,
+            examples,
+            format_rules,
+            usage_stats,
+        ) = if locked {
+            (
+                AppState::new(),
+                DomainSettings::new(),
+                CertificateStore::new(),
+                ActivityLog::new(),
+                SnippetLibrary::new(),
+                TrustedHooks::new(),
+                BaselineStore::new(),
+                ExampleLibrary::new(),
+                FormatRules::new(),
+                UsageStats::new(),
+            )
+        } else {
+            let storage = data_root
+                .as_deref()
+                .map(|root| profiles.active().storage(root).read_only(config.read_only));
+            (
+                AppState::load(storage.clone()),
+                Self::load_domains_from(storage.clone()),
+                Self::load_certificates_from(storage.clone()),
+                Self::load_activity_from(storage.clone()),
+                Self::load_snippets_from(storage.clone()),
+                Self::load_trusted_hooks_from(storage.clone()),
+                Self::load_baselines_from(storage.clone()),
+                Self::load_examples_from(storage.clone()),
+                Self::load_format_rules_from(storage.clone()),
+                Self::load_usage_from(storage),
+            )
+        };
+        let certificate_names = certificates.names();
+        let certificate_selector = cx.new(|cx| {
+            Dropdown::new("certificate-dropdown", cx).with_options(
+                std::iter::once(DOMAIN_CERTIFICATE.to_string())
+                    .chain(certificate_names.clone())
+                    .collect(),
+            )
+        });
+        let domains_page = cx.new(|cx| {
+            let mut page = DomainsPage::new(cx);
+            page.set_settings(domains, cx);
+            page.set_certificate_names(certificate_names, cx);
+            page
+        });
+        let certificates_page = cx.new(|cx| {
+            let mut page = CertificatesPage::new(cx);
+            page.set_store(certificates, cx);
+            page
+        });
+        let snippets_page = cx.new(|cx| {
+            let mut page = SnippetsPage::new(cx);
+            page.set_library(snippets, cx);
+            page
+        });
+        let state = cx.new(|cx| {
+            state.flush_on_quit(cx);
+            state
+        });
+        let history_list = cx.new(|cx| HistoryList::new(state.clone(), cx));
 
         PostmanApp {
             method_selector,
             url_input,
+            socket_input,
+            certificate_selector,
+            pre_hook_input,
+            pre_hook_variable_input,
+            post_hook_input,
+            post_hook_variable_input,
+            show_hooks: false,
+            hook_variables: HashMap::new(),
+            trusted_hooks,
+            pending_hooks: None,
+            hooks_approved_once: Vec::new(),
+            hook_task: None,
             headers: Vec::new(),
             body_input,
+            part_previews: Vec::new(),
+            request_size: RefCell::new(None),
             request_executor: RequestExecutor::new(),
             response_viewer,
+            ndjson_viewer,
+            show_records: false,
+            csv_grid,
+            show_grid: false,
+            stream_response: false,
+            stream_task: None,
+            raw_mode: false,
+            raw_address_input,
+            raw_request_input,
+            raw_crlf: true,
+            raw_hex: false,
+            raw_response: None,
+            raw_task: None,
+            show_pipe: false,
+            pipe_input,
+            pipe_output: None,
+            pipe_task: None,
+            next_page: None,
+            pages_task: None,
+            failed_url: None,
+            diagnostics: None,
+            diagnostics_task: None,
+            diagnose_traceroute: false,
+            diagnose_nameserver_input,
+            baselines,
+            baseline_ignore_input,
+            baseline_request: None,
+            baseline_changes: None,
+            examples,
+            example_name_input,
+            example_status_input,
+            example_body_input,
+            show_example_editor: false,
+            shown_example: None,
+            mock_server: None,
+            mock_port_input,
+            format_rules,
+            format_rules_input,
+            show_format_rules: false,
+            format_rules_error: None,
+            highlights: Vec::new(),
+            response_latency: None,
+            grpc_mode: false,
+            grpc_server_input,
+            grpc_method_input,
+            grpc_message_input,
+            grpc_log,
+            grpc_session: None,
+            graphql_mode: false,
+            graphql_query_input,
+            graphql_variables_input,
+            graphql_schemas: HashMap::new(),
+            graphql_docs,
+            graphql_log,
+            graphql_subscription: None,
+            show_graphql_log: false,
+            api_spec_input,
+            api_explorer,
+            show_api_explorer: false,
+            domains_page,
+            show_domains: false,
+            certificates_page,
+            show_certificates: false,
+            snippets_page,
+            show_snippets: false,
+            activity_log,
+            show_activity: false,
+            show_bulk: false,
+            bulk_input,
+            bulk_urls: Vec::new(),
+            bulk_outcomes: Vec::new(),
+            bulk_task: None,
+            show_scenarios: false,
+            scenario_name_input,
+            selected_scenario: None,
+            scenario_run: None,
+            scenario_task: None,
+            show_collections: false,
+            collection_name_input,
+            selected_collection: None,
+            show_environments: false,
+            environment_name_input,
+            environment_variables_input,
+            selected_environment: None,
+            show_replay: false,
+            replay_from_input,
+            replay_to_input,
+            replay_run: None,
+            replay_task: None,
+            activity_since_yesterday: false,
+            usage_stats,
+            show_usage: false,
             header_key_input,
             header_value_input,
-            request_history: RequestHistory::new(),
+            headers_focus: cx.focus_handle(),
+            focused_header: None,
+            range_start_input,
+            range_end_input,
+            range_suffix: false,
+            state,
             history_list,
+            response_entry_id: None,
+            tabs: vec![RequestTab::new()],
+            active_tab: 0,
+            compare_tab: None,
+            response_window: None,
+            notice: None,
+            config,
+            unsaved: HashSet::new(),
+            autosave: Autosave::new(),
+            profiles,
+            data_root,
+            new_profile_input,
+            base_url_input,
+            history_name_input,
+            profile_health: HashMap::new(),
+            health_task: None,
+            body_serializer: None,
+            auth_provider: None,
+            auth_credential_input,
+            protection_selector,
+            protection_key_input,
+            protection_key_id_input,
+            proto_descriptor_input,
+            proto_message_input,
+            proto_message: None,
+            vault: None,
+            locked,
+            passphrase_input,
+            share_recipients_input,
+            clipboard_watch: None,
+            last_clipboard_text: None,
+            clipboard_offer: None,
         }
     }
 
@@ -183,19 +1044,66 @@ impl PostmanApp {
 
     // 发送请求
     fn send_request(&mut self, cx: &mut Context<Self>) {
-        let method = self
-            .method_selector
-            .update(cx, |selector, cx| selector.selected_method(cx));
+        if self.graphql_mode {
+            let query = self.graphql_query_input.read(cx).get_content();
+            if is_subscription(&query) {
+                self.start_graphql_subscription(cx);
+                return;
+            }
+            self.show_graphql_log = false;
+        }
+
+        // hook 会在本机执行命令 - 没有允许过的命令先请用户确认
+        let hooks = match self.current_hooks(cx) {
+            Ok(hooks) => hooks,
+            Err(e) => {
+                self.response_viewer.update(cx, |viewer, cx| {
+                    viewer.set_error(e.to_string(), cx);
+                });
+                cx.notify();
+                return;
+            }
+        };
+        let approved_once = std::mem::take(&mut self.hooks_approved_once);
+        let untrusted: Vec<String> = self
+            .trusted_hooks
+            .untrusted(&hooks)
+            .into_iter()
+            .filter(|command| !approved_once.contains(command))
+            .collect();
+        if !untrusted.is_empty() {
+            tracing::info!("🪝 PostmanApp - 等待确认 {} 个 hook 命令", untrusted.len());
+            self.pending_hooks = Some(untrusted);
+            cx.notify();
+            return;
+        }
+
+        // GraphQL 模式下总是 POST 查询和变量
+        let method = if self.graphql_mode {
+            HttpMethod::POST
+        } else {
+            self.method_selector
+                .update(cx, |selector, cx| selector.selected_method(cx))
+        };
         let url = self.url_input.read(cx).get_url().to_string();
 
-        // Get body type and content
-        let body_type = self.body_input.read(cx).get_current_type().clone();
-        let body = if method == HttpMethod::POST {
+        // Get body content
+        let body = if self.graphql_mode {
+            match self.graphql_payload(cx) {
+                Ok(payload) => Some(payload),
+                Err(e) => {
+                    self.response_viewer.update(cx, |viewer, cx| {
+                        viewer.set_error(e.to_string(), cx);
+                    });
+                    cx.notify();
+                    return;
+                }
+            }
+        } else if method == HttpMethod::POST {
             Some(self.body_input.read(cx).get_content().to_string())
         } else {
             None
         };
-
         // Only include enabled headers
         let mut headers: Vec<(String, String)> = self
             .headers
@@ -204,20 +1112,24 @@ impl PostmanApp {
             .map(|(_, key, value)| (key.clone(), value.clone()))
             .collect();
 
-        // Auto-add Content-Type header for form-data if not already present
-        if method == HttpMethod::POST && body_type == BodyType::FormData {
+        // Auto-add Content-Type header for form-data / GraphQL if not already present
+        if let Some(content_type) = self.default_content_type(method, cx) {
             let has_content_type = headers
                 .iter()
                 .any(|(key, _)| key.to_lowercase() == "content-type");
             if !has_content_type {
-                headers.push((
-                    "Content-Type".to_string(),
-                    "application/x-www-form-urlencoded".to_string(),
-                ));
-                tracing::info!("📝 PostmanApp - Auto-added Content-Type header for form-data: application/x-www-form-urlencoded");
+                headers.push(("Content-Type".to_string(), content_type.clone()));
+                tracing::info!("📝 PostmanApp - Auto-added Content-Type header: {content_type}");
             }
         }
 
+        // 保存当前响应的批注，再显示新的响应
+        self.sync_response_annotations(cx);
+        self.response_entry_id = None;
+        self.show_records = false;
+        self.show_grid = false;
+        self.stream_task = None;
+
         // 设置加载状态
         self.response_viewer.update(cx, |viewer, cx| {
             viewer.set_loading(cx);
@@ -232,32 +1144,140 @@ impl PostmanApp {
         if let Some(body_content) = &body {
             request.set_body(body_content);
         }
+        request.set_socket(self.socket_input.read(cx).get_content());
+        request.client_certificate = self.selected_certificate(cx);
+        request.assertions = self.snippets_page.read(cx).attached();
+        request.hooks = hooks;
 
-        // 执行请求
-        let result = self.request_executor.execute(method, &url, headers, body);
+        // 前置 hook 在后台执行，完成后再发送
+        let Some(hook) = request.hooks.pre.clone() else {
+            self.send_resolved_request(request, cx);
+            return;
+        };
+        let task = self.run_request_hook(hook, None, cx, |this, result, cx| {
+            this.hook_task = None;
+            match result {
+                Ok(()) => this.send_resolved_request(request, cx),
+                Err(e) => {
+                    tracing::info!("❌ PostmanApp - 前置 hook 失败: {e}");
+                    this.response_viewer.update(cx, |viewer, cx| {
+                        viewer.set_error(format!("Pre-request hook failed: {e}"), cx);
+                    });
+                }
+            }
+        });
+        self.hook_task = Some(task);
+    }
 
-        // 处理结果
-        match result {
-            Ok(request_result) => {
-                // Add to history on success
-                let url_display = if url.len() > MAX_HISTORY_URL_LENGTH {
-                    let truncated: String = url.chars().take(MAX_HISTORY_URL_LENGTH).collect();
-                    format!("{}...", truncated)
-                } else {
-                    url.clone()
-                };
-                self.request_history.add(request, url_display);
+    // send_request 的后半部分：把 {{变量}} 替换为 hook 的输出（历史记录中保留
+    // 变量），应用插件后发送
+    fn send_resolved_request(&mut self, request: Request, cx: &mut Context<Self>) {
+        let method = request.method;
+        let resolved = self.resolve_variables(&request, cx);
+        let url = resolved.url.clone();
+        let socket = match resolved
+            .socket
+            .as_deref()
+            .map(SocketTarget::parse)
+            .transpose()
+        {
+            Ok(socket) => socket,
+            Err(e) => {
+                self.response_viewer.update(cx, |viewer, cx| {
+                    viewer.set_error(e.to_string(), cx);
+                });
+                cx.notify();
+                return;
+            }
+        };
 
-                // Update history list UI
-                self.history_list.update(cx, |list, cx| {
-                    list.set_entries(self.request_history.entries().to_vec(), cx);
+        // 应用插件（认证、请求体序列化）- 历史记录中保存的是未附加凭据的请求
+        let (mut headers, body) = match self.apply_plugins(&resolved, cx) {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 插件处理请求失败: {e}");
+                self.response_viewer.update(cx, |viewer, cx| {
+                    viewer.set_error(e.to_string(), cx);
                 });
+                cx.notify();
+                return;
+            }
+        };
+        // 请求没有自己的 Cookie header 时带上 profile 中保存的 cookies
+        if header_value(&headers, "Cookie").is_none() {
+            if let Some(cookies) = self.state.read(cx).cookies().header_for(&url, Utc::now()) {
+                headers.push(("Cookie".to_string(), cookies));
+            }
+        }
 
+        let transport_executor = match self.transport_executor(&resolved, cx) {
+            Ok(executor) => executor,
+            Err(e) => {
                 self.response_viewer.update(cx, |viewer, cx| {
-                    viewer.set_success(request_result.status, request_result.body, cx);
+                    viewer.set_error(e.to_string(), cx);
+                });
+                cx.notify();
+                return;
+            }
+        };
+        let executor = transport_executor
+            .as_ref()
+            .unwrap_or(&self.request_executor);
+
+        // 流式响应只支持普通 TCP
+        if self.stream_response && socket.is_none() {
+            let events = executor.execute_streaming(method, &url, headers, body);
+            self.send_streaming_request(request, url, events, cx);
+            return;
+        }
+
+        // 执行请求
+        self.failed_url = None;
+        self.diagnostics = None;
+        self.diagnostics_task = None;
+        self.baseline_changes = None;
+        self.highlights.clear();
+        self.next_page = None;
+        self.pages_task = None;
+        // 只有普通 GET 请求的列表响应会继续获取其余页面
+        let page_request = (method == HttpMethod::GET && socket.is_none())
+            .then(|| (resolved.clone(), headers.clone()));
+        let started = Instant::now();
+        let result = match &socket {
+            Some(socket) => executor.execute_socket(socket, method, &url, headers, body),
+            None => executor.execute_bytes(method, &url, headers, body),
+        };
+
+        // 处理结果
+        match result {
+            Ok(request_result) => {
+                let elapsed = started.elapsed();
+                self.next_page = page_request.and_then(|(page_request, headers)| {
+                    let next = next_page_url(&url, &request_result.headers, &request_result.body)?;
+                    let items = list_items(&request_result.body)?;
+                    tracing::info!("📄 PostmanApp - 响应有下一页: {next}");
+                    Some(NextPage {
+                        request: page_request,
+                        headers,
+                        first: Page {
+                            url: url.clone(),
+                            items,
+                        },
+                        url: next,
+                    })
                 });
+                self.finish_request(request, &url, request_result, elapsed, false, cx);
             }
             Err(error_message) => {
+                // 网络错误时提供诊断（DNS、TCP 连接、traceroute）
+                if socket.is_none()
+                    && matches!(
+                        error_message,
+                        AppError::NetworkError(_) | AppError::HttpError(_)
+                    )
+                {
+                    self.failed_url = Some(url.clone());
+                }
                 self.response_viewer.update(cx, |viewer, cx| {
                     viewer.set_error(error_message.to_string(), cx);
                 });
@@ -267,570 +1287,7873 @@ impl PostmanApp {
         cx.notify();
     }
 
-    // 处理 Send 按钮点击
-    fn on_send_clicked(
+    // 表单中填写的 hook（变量名无效时报错）
+    fn current_hooks(&self, cx: &App) -> Result<RequestHooks, AppError> {
+        let content = |input: &Entity<HeaderInput>| input.read(cx).get_content().to_string();
+        Ok(RequestHooks {
+            pre: Hook::parse(
+                &content(&self.pre_hook_input),
+                &content(&self.pre_hook_variable_input),
+            )?,
+            post: Hook::parse(
+                &content(&self.post_hook_input),
+                &content(&self.post_hook_variable_input),
+            )?,
+        })
+    }
+
+    // 在后台执行 hook 命令（最长 HOOK_TIMEOUT，不阻塞界面），设置了变量名时
+    // 保存输出，再调用 `then`（输出可能是凭据，不写日志；RequestExecutor 记录
+    // headers 时会隐去 Authorization 等凭据 header 的值）
+    fn run_request_hook(
         &mut self,
-        _event: &gpui::MouseUpEvent,
-        _window: &mut gpui::Window,
+        hook: Hook,
+        stdin: Option<Vec<u8>>,
         cx: &mut Context<Self>,
-    ) {
+        then: impl FnOnce(&mut Self, Result<(), AppError>, &mut Context<Self>) + 'static,
+    ) -> Task<()> {
+        tracing::info!("🪝 PostmanApp - 执行 hook: {}", hook.command);
+        let command = hook.command.clone();
+        let running = cx
+            .background_executor()
+            .spawn(async move { run_hook(&command, stdin.as_deref(), HOOK_TIMEOUT) });
+        cx.spawn(async move |this, cx| {
+            let output = running.await;
+            let _ = this.update(cx, |this, cx| {
+                let result = output.map(|output| {
+                    if let Some(variable) = &hook.variable {
+                        tracing::info!("🪝 PostmanApp - hook 输出已保存到变量 {variable}");
+                        this.hook_variables.insert(variable.clone(), output);
+                    }
+                });
+                then(this, result, cx);
+                cx.notify();
+            });
+        })
+    }
+
+    // 把请求 URL、headers 和请求体中的 {{变量}} 替换为当前环境的变量和 hook 的
+    // 输出（同名时使用 hook 的输出）
+    fn resolve_variables(&self, request: &Request, cx: &App) -> Request {
+        let mut resolved = request.clone();
+        let mut variables = self.state.read(cx).environments().variables();
+        variables.extend(
+            self.hook_variables
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone())),
+        );
+        if variables.is_empty() {
+            return resolved;
+        }
+        let variables = &variables;
+        resolved.url = substitute(&request.url, variables);
+        for (key, value) in &mut resolved.headers {
+            *key = substitute(key, variables);
+            *value = substitute(value, variables);
+        }
+        resolved.body = request
+            .body
+            .as_deref()
+            .map(|body| substitute(body, variables));
+        resolved
+    }
+
+    // 只在这次发送中允许执行待确认的 hook 命令
+    fn run_pending_hooks_once(&mut self, cx: &mut Context<Self>) {
+        let Some(commands) = self.pending_hooks.take() else {
+            return;
+        };
+        self.hooks_approved_once = commands;
         self.send_request(cx);
     }
 
-    // 添加header
-    fn add_header(&mut self, cx: &mut Context<Self>) {
-        let key = self
-            .header_key_input
-            .read(cx)
-            .get_content()
-            .trim()
-            .to_string();
-        let value = self
-            .header_value_input
-            .read(cx)
-            .get_content()
-            .trim()
-            .to_string();
+    // 永久允许待确认的 hook 命令（保存在当前 profile 中）后发送
+    fn trust_pending_hooks(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(commands) = self.pending_hooks.take() else {
+            return;
+        };
+        for command in commands {
+            tracing::info!("🪝 PostmanApp - 已允许 hook 命令: {command}");
+            self.trusted_hooks.trust(&command);
+            self.record_activity(ActivityKind::HooksChanged, format!("Allowed {command}"), cx);
+        }
+        self.mark_unsaved(SavedFile::TrustedHooks, cx);
+        self.send_request(cx);
+    }
 
-        tracing::info!("🎯 PostmanApp - 尝试添加header:");
-        tracing::info!("   Key: '{key}'");
-        tracing::info!("   Value: '{value}'");
+    fn cancel_pending_hooks(&mut self, cx: &mut Context<Self>) {
+        self.pending_hooks = None;
+        self.notice = Some("The request was not sent - its hooks were not allowed".to_string());
+        cx.notify();
+    }
 
-        if !key.is_empty() && !value.is_empty() {
-            // 检查是否已存在相同的key
-            let existing_index = self.headers.iter().position(|(_, k, _)| k == &key);
+    // 撤销之前允许的 hook 命令，下次执行时重新确认
+    fn revoke_trusted_hook(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        if let Some(command) = self.trusted_hooks.revoke(index) {
+            tracing::info!("🪝 PostmanApp - 已撤销 hook 命令: {command}");
+            self.record_activity(ActivityKind::HooksChanged, format!("Revoked {command}"), cx);
+            self.mark_unsaved(SavedFile::TrustedHooks, cx);
+            cx.notify();
+        }
+    }
 
-            if let Some(index) = existing_index {
-                let old_value = self.headers[index].2.clone(); // 克隆旧值避免借用冲突
-                self.headers[index].2 = value.clone();
-                tracing::info!("🔄 PostmanApp - 更新已存在的header:");
-                tracing::info!("   Key: {key}");
-                tracing::info!("   旧值: {old_value}");
-                tracing::info!("   新值: {value}");
-            } else {
-                self.headers.push((true, key.clone(), value.clone())); // enabled by default
-                tracing::info!("✅ PostmanApp - 成功添加新header:");
-                tracing::info!("   Key: {key}");
-                tracing::info!("   Value: {value}");
-                tracing::info!("   当前headers总数: {}", self.headers.len());
-            }
+    // form-data / GraphQL 请求未设置 Content-Type 时自动添加的值
+    fn default_content_type(&self, method: HttpMethod, cx: &App) -> Option<String> {
+        default_content_type(
+            self.graphql_mode,
+            method,
+            self.body_input.read(cx).get_current_type(),
+            self.form_is_multipart(cx),
+        )
+    }
 
-            // 清空输入框
-            self.header_key_input
-                .update(cx, |input, cx| input.clear(cx));
-            self.header_value_input
-                .update(cx, |input, cx| input.clear(cx));
+    // 决定请求大小估算的输入，每次渲染时读取（只取请求体的长度）
+    fn size_inputs(&self, cx: &App) -> SizeInputs {
+        let method = self.method_selector.read(cx).current_method(cx);
+        let url = self.url_input.read(cx).get_url().to_string();
+        let body_input = self.body_input.read(cx);
+        SizeInputs {
+            method,
+            headers: self
+                .headers
+                .iter()
+                .filter(|(enabled, _, _)| *enabled)
+                .map(|(_, key, value)| (key.clone(), value.clone()))
+                .collect(),
+            graphql: self.graphql_mode,
+            body_type: body_input.get_current_type().clone(),
+            multipart: self.form_is_multipart(cx),
+            body_len: body_input.content_len() as u64,
+            parts: self.part_previews.clone(),
+            auth: self.auth_provider.as_ref().map(|name| {
+                (
+                    name.clone(),
+                    self.auth_credential_input
+                        .read(cx)
+                        .get_content()
+                        .to_string(),
+                )
+            }),
+            protection: self.selected_protection(cx).map(|protection| {
+                let key_id = self.protection_key_id_input.read(cx).get_content().trim();
+                (protection, (!key_id.is_empty()).then(|| key_id.to_string()))
+            }),
+            body_serializer: self.body_serializer.clone(),
+            domain: self.domains_page.read(cx).settings().for_url(&url).cloned(),
+            url,
+        }
+    }
 
-            // 打印当前所有headers
-            tracing::info!("📋 PostmanApp - 当前所有headers:");
-            for (i, (enabled, k, v)) in self.headers.iter().enumerate() {
-                tracing::info!(
-                    "   {}. [{}] {} = {}",
-                    i + 1,
-                    if *enabled { "✓" } else { " " },
-                    k,
-                    v
-                );
+    // 估算发送时的请求大小（已应用域名默认值、认证和请求体插件）；
+    // 只按长度计算，不组装 multipart 请求体、不签名或加密，multipart 文件按
+    // 预览中的文件大小计算。输入不变时沿用上次的估算
+    fn estimate_request_size(&self, cx: &App) -> RequestSize {
+        let inputs = self.size_inputs(cx);
+        if let Some((cached, size)) = &*self.request_size.borrow() {
+            if *cached == inputs {
+                return *size;
             }
+        }
+        let size = self.compute_request_size(&inputs, cx);
+        *self.request_size.borrow_mut() = Some((inputs, size));
+        size
+    }
 
-            cx.notify();
-        } else {
-            tracing::info!("⚠️ PostmanApp - 添加header失败:");
-            if key.is_empty() {
-                tracing::info!("   原因: Header key不能为空");
+    fn compute_request_size(&self, inputs: &SizeInputs, cx: &App) -> RequestSize {
+        let registry = cx.global::<PluginRegistry>();
+        let mut request = Request::new(inputs.method, &inputs.url);
+        for (key, value) in &inputs.headers {
+            request.add_header(key, value);
+        }
+        let content_type = default_content_type(
+            inputs.graphql,
+            inputs.method,
+            &inputs.body_type,
+            inputs.multipart,
+        );
+        if let Some(content_type) = content_type {
+            if header_value(&request.headers, "Content-Type").is_none() {
+                request.add_header("Content-Type", &content_type);
             }
-            if value.is_empty() {
-                tracing::info!("   原因: Header value不能为空");
+        }
+        if let Some(domain) = &inputs.domain {
+            domain.apply_headers(&mut request);
+        }
+        let auth = inputs.auth.clone().or_else(|| {
+            let auth = inputs.domain.as_ref()?.auth.clone()?;
+            Some((auth.provider, auth.credential))
+        });
+        if let Some((name, credential)) = auth {
+            // 插件无法处理时（例如缺少凭据）按没有认证估算
+            if let Some(provider) = registry.auth_provider(&name) {
+                let _ = provider.apply(&mut request, &credential);
             }
-            tracing::info!("   请确保key和value都有内容");
         }
-    }
 
-    // 通过输入框设置header值
-    fn set_header_input_values(&mut self, key: &str, value: &str, cx: &mut Context<Self>) {
-        tracing::info!("🎯 PostmanApp - 预设header到输入框:");
-        tracing::info!("   预设Key: {key}");
-        tracing::info!("   预设Value: {value}");
-
-        self.header_key_input.update(cx, |input, cx| {
-            input.set_content(key.to_string(), cx);
-        });
-        self.header_value_input.update(cx, |input, cx| {
-            input.set_content(value.to_string(), cx);
-        });
-        tracing::info!("✅ PostmanApp - 预设header已填入输入框，请点击Add按钮添加");
+        let multipart_boundary = header_value(&request.headers, "Content-Type")
+            .and_then(multipart::boundary_of)
+            .map(str::to_string)
+            .filter(|_| !inputs.parts.is_empty());
+        let body = match &multipart_boundary {
+            Some(boundary) => multipart::encoded_len(&inputs.parts, boundary),
+            None if inputs.method == HttpMethod::POST && inputs.body_len > 0 => {
+                let has_content_type = header_value(&request.headers, "Content-Type").is_some();
+                if let Some((protection, key_id)) = &inputs.protection {
+                    if !has_content_type {
+                        request.add_header("Content-Type", JOSE_CONTENT_TYPE);
+                    }
+                    protection.encoded_len(inputs.body_len, key_id.as_deref())
+                } else {
+                    // 序列化后的大小未知，按文本长度估算
+                    let serializer = inputs
+                        .body_serializer
+                        .as_deref()
+                        .and_then(|name| registry.body_serializer(name));
+                    if let Some(serializer) = serializer.filter(|_| !has_content_type) {
+                        request.add_header("Content-Type", serializer.content_type());
+                    }
+                    inputs.body_len
+                }
+            }
+            None => 0,
+        };
+        RequestSize::estimate(
+            &inputs.method.to_string(),
+            &inputs.url,
+            &request.headers,
+            body,
+        )
     }
 
-    // 删除header
-    fn remove_header(&mut self, index: usize, cx: &mut Context<Self>) {
-        tracing::info!("🗑️ PostmanApp - 尝试删除header，索引: {index}");
-
-        if index < self.headers.len() {
-            let removed = self.headers.remove(index);
-            tracing::info!("✅ PostmanApp - 成功删除header:");
-            tracing::info!("   Enabled: {}", removed.0);
-            tracing::info!("   Key: {}", removed.1);
-            tracing::info!("   Value: {}", removed.2);
-            tracing::info!("   剩余headers数量: {}", self.headers.len());
+    // 目标域名设置的请求大小上限，未设置时使用默认值
+    fn request_size_limit(&self, cx: &App) -> u64 {
+        let url = self.url_input.read(cx).get_url();
+        self.domains_page
+            .read(cx)
+            .settings()
+            .for_url(url)
+            .and_then(|domain| domain.max_request_size)
+            .unwrap_or(DEFAULT_SIZE_LIMIT)
+    }
 
-            // 打印剩余的headers
-            if self.headers.is_empty() {
-                tracing::info!("📋 PostmanApp - 当前无headers");
+    // Send 按钮旁的请求大小估算，超过上限时显示警告
+    fn render_request_size(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let size = self.estimate_request_size(cx).total();
+        let limit = self.request_size_limit(cx);
+        let too_large = size > limit;
+        div()
+            .flex()
+            .items_center()
+            .text_size(px(12.0))
+            .text_color(rgb(if too_large { 0x00dc_3545 } else { 0x006c_757d }))
+            .child(if too_large {
+                format!(
+                    "⚠️ ≈ {} exceeds the {} limit",
+                    format_size(size),
+                    format_size(limit)
+                )
             } else {
-                tracing::info!("📋 PostmanApp - 剩余headers:");
-                for (i, (enabled, k, v)) in self.headers.iter().enumerate() {
-                    tracing::info!(
-                        "   {}. [{}] {} = {}",
-                        i + 1,
-                        if *enabled { "✓" } else { " " },
-                        k,
-                        v
-                    );
-                }
-            }
+                format!("≈ {}", format_size(size))
+            })
+    }
 
-            cx.notify();
-        } else {
-            tracing::info!("❌ PostmanApp - 删除header失败:");
-            tracing::info!(
-                "   原因: 索引 {} 超出范围 (当前headers数量: {})",
-                index,
-                self.headers.len()
-            );
-        }
+    // 请求选择的客户端证书（None 表示使用域名默认值）
+    fn selected_certificate(&self, cx: &App) -> Option<String> {
+        let selected = self.certificate_selector.read(cx).selected_value();
+        (selected != DOMAIN_CERTIFICATE).then(|| selected.to_string())
     }
 
-    // Toggle header enabled state
-    fn toggle_header(&mut self, index: usize, cx: &mut Context<Self>) {
-        tracing::info!("🔄 PostmanApp - 切换header状态，索引: {index}");
-        if index < self.headers.len() {
-            let current_state = self.headers[index].0;
-            self.headers[index].0 = !current_state;
-            tracing::info!("✅ PostmanApp - 成功切换header状态:");
-            tracing::info!("   Key: {}", self.headers[index].1);
-            tracing::info!("   从 {} 切换到 {}", current_state, !current_state);
+    // 保存的请求发送时使用的执行器（域名的代理 / TLS 设置和客户端证书）和 socket
+    fn stored_transport(
+        &self,
+        request: &Request,
+        cx: &App,
+    ) -> Result<(Option<RequestExecutor>, Option<SocketTarget>), AppError> {
+        let executor = self.transport_executor(request, cx)?;
+        let socket = request
+            .socket
+            .as_deref()
+            .map(SocketTarget::parse)
+            .transpose()?;
+        Ok((executor, socket))
+    }
 
-            cx.notify();
-        } else {
-            tracing::info!("❌ PostmanApp - 切换header失败:");
-            tracing::info!(
-                "   原因: 索引 {} 超出范围 (当前headers数量: {})",
-                index,
-                self.headers.len()
-            );
+    // 目标域名配置了代理、TLS 选项或客户端证书（或请求自己选择了证书）时使用单独的客户端
+    fn transport_executor(
+        &self,
+        request: &Request,
+        cx: &App,
+    ) -> Result<Option<RequestExecutor>, AppError> {
+        let mut transport = self
+            .domains_page
+            .read(cx)
+            .settings()
+            .for_url(&request.url)
+            .map(|domain| domain.transport.clone())
+            .unwrap_or_default();
+        if request.client_certificate.is_some() {
+            transport.client_certificate = request.client_certificate.clone();
         }
+        if transport.is_default() {
+            return Ok(None);
+        }
+
+        let store = self.certificates_page.read(cx).store();
+        let certificate = transport
+            .client_certificate
+            .as_deref()
+            .map(|name| {
+                store.get(name).ok_or_else(|| {
+                    AppError::ValidationError(format!("Client certificate not found: {name}"))
+                })
+            })
+            .transpose()?;
+        if let Some(certificate) = certificate {
+            tracing::info!("🪪 PostmanApp - 使用客户端证书: {}", certificate.name);
+        }
+        RequestExecutor::with_transport(&transport, certificate).map(Some)
     }
 
-    // Handle history item selection
-    fn on_history_selected(
+    // 流式发送请求 - 响应体逐块到达，NDJSON 记录边接收边显示
+    fn send_streaming_request(
         &mut self,
-        _history_list: gpui::Entity<HistoryList>,
-        event: &HistoryListEvent,
+        request: Request,
+        url: String,
+        mut events: UnboundedReceiver<StreamEvent>,
         cx: &mut Context<Self>,
     ) {
-        match event {
-            HistoryListEvent::RequestSelected(request) => {
-                tracing::info!("📋 PostmanApp - 从历史记录加载请求:");
-                tracing::info!("   Method: {}", request.method);
-                tracing::info!("   URL: {}", request.url);
-                tracing::info!("   Headers Count: {}", request.headers.len());
+        let started = Instant::now();
+        self.stream_task = Some(cx.spawn(async move |this, cx| {
+            let mut status = 0;
+            let mut response_headers = Vec::new();
+            let mut raw_body = Vec::new();
+            let mut ndjson = false;
 
-                // Log query parameters if present in URL
-                if request.url.contains('?') {
-                    if let Some(query_str) = request.url.split('?').nth(1) {
-                        tracing::info!("   Query parameters: {}", query_str);
+            while let Some(event) = events.recv().await {
+                match event {
+                    StreamEvent::Head {
+                        status: code,
+                        headers,
+                    } => {
+                        ndjson = header_value(&headers, "Content-Type")
+                            .is_some_and(is_ndjson_content_type);
+                        status = code;
+                        response_headers = headers;
+                        if ndjson {
+                            let _ = this.update(cx, |this, cx| {
+                                this.show_records = true;
+                                this.ndjson_viewer
+                                    .update(cx, |viewer, cx| viewer.start_stream(cx));
+                                cx.notify();
+                            });
+                        }
+                    }
+                    StreamEvent::Chunk(chunk) => {
+                        if ndjson {
+                            let _ = this.update(cx, |this, cx| {
+                                this.ndjson_viewer
+                                    .update(cx, |viewer, cx| viewer.push_chunk(&chunk, cx));
+                            });
+                        }
+                        raw_body.extend(chunk);
+                    }
+                    StreamEvent::Done => {
+                        let text = String::from_utf8_lossy(&raw_body).into_owned();
+                        let kind =
+                            detect(header_value(&response_headers, "Content-Type"), &raw_body);
+                        let request_result = RequestResult {
+                            status,
+                            headers: response_headers,
+                            body: format_body(kind, &text),
+                            raw_body,
+                        };
+                        let _ = this.update(cx, |this, cx| {
+                            if ndjson {
+                                this.ndjson_viewer
+                                    .update(cx, |viewer, cx| viewer.finish_stream(cx));
+                            }
+                            let elapsed = started.elapsed();
+                            this.finish_request(request, &url, request_result, elapsed, ndjson, cx);
+                            tracing::info!("🏁 PostmanApp - 流式请求处理完成");
+                            cx.notify();
+                        });
+                        break;
+                    }
+                    StreamEvent::Error(e) => {
+                        let _ = this.update(cx, |this, cx| {
+                            if ndjson {
+                                // 保留已收到的记录
+                                this.ndjson_viewer
+                                    .update(cx, |viewer, cx| viewer.finish_stream(cx));
+                                this.notice = Some(format!("Stream interrupted: {e}"));
+                            } else {
+                                this.response_viewer.update(cx, |viewer, cx| {
+                                    viewer.set_error(e, cx);
+                                });
+                            }
+                            cx.notify();
+                        });
+                        break;
                     }
                 }
+            }
+        }));
+    }
 
-                // Log body info
-                if let Some(ref body) = request.body {
-                    tracing::info!("   Body length: {} bytes", body.len());
-                }
-
-                // Update method selector - normalize method to uppercase
-                let method = request.method;
-                self.method_selector.update(cx, |selector, cx| {
-                    selector.set_selected_method(method, cx);
-                });
-
-                // Update URL input
-                self.url_input.update(cx, |input, cx| {
-                    input.set_url(&request.url, cx);
-                });
-
-                // Update headers - convert from Vec<(String, String)> to Vec<(bool, String, String)>
-                self.headers = request
-                    .headers
-                    .iter()
-                    .map(|(key, value)| (true, key.clone(), value.clone()))
-                    .collect();
+    // 原始 socket 模式 - 在后台线程发送，避免读取超时期间界面卡住
+    fn send_raw_request(&mut self, cx: &mut Context<Self>) {
+        let address = self.raw_address_input.read(cx).get_content().to_string();
+        let target = match RawTarget::parse(&address) {
+            Ok(target) => target,
+            Err(e) => {
+                self.raw_response = Some(Err(e.to_string()));
+                cx.notify();
+                return;
+            }
+        };
+        let text = self.raw_request_input.read(cx).get_content();
+        let payload = if self.raw_crlf {
+            normalize_line_endings(&text)
+        } else {
+            text
+        }
+        .into_bytes();
 
-                // Update body
-                if let Some(body) = &request.body {
-                    self.body_input.update(cx, |input, cx| {
-                        // 检测 body 类型
-                        let body_type = Self::detect_body_type(body);
+        self.raw_response = None;
+        let sending = cx.background_executor().spawn(async move {
+            send_raw(&target, &payload, DEFAULT_TIMEOUT).map_err(|e| e.to_string())
+        });
+        self.raw_task = Some(cx.spawn(async move |this, cx| {
+            let result = sending.await;
+            let _ = this.update(cx, |this, cx| {
+                if let Err(e) = &result {
+                    tracing::info!("❌ PostmanApp - 原始请求失败: {e}");
+                }
+                this.raw_response = Some(result);
+                this.raw_task = None;
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
 
-                        // 设置 body 类型
-                        input.set_type(body_type.clone(), cx);
+    // 把当前响应体写入用户输入的命令（jq、grep、脚本等），在后台执行并显示输出
+    fn pipe_response(&mut self, cx: &mut Context<Self>) {
+        let command = self.pipe_input.read(cx).get_content().trim().to_string();
+        if command.is_empty() {
+            self.pipe_output = Some(Err("Enter a command to pipe the response to".to_string()));
+            cx.notify();
+            return;
+        }
+        let ResponseState::Success { body, .. } = self.response_viewer.read(cx).get_state() else {
+            self.pipe_output = Some(Err(
+                "Send a request first - there is no response to pipe".to_string()
+            ));
+            cx.notify();
+            return;
+        };
+        let body = body.clone().into_bytes();
 
-                        // 根据类型设置内容
-                        match body_type {
-                            BodyType::FormData => {
-                                // 解析 form data
-                                Self::parse_and_set_form_data(input, body, cx);
-                            }
-                            _ => {
-                                // JSON 或 Raw 直接设置内容
-                                input.set_content(body.clone(), cx);
-                            }
-                        }
-                    });
-                } else {
-                    self.body_input.update(cx, |input, cx| {
-                        input.clear(cx);
-                    });
+        tracing::info!("🪈 PostmanApp - 管道命令: {command}");
+        self.pipe_output = None;
+        let running = cx.background_executor().spawn(async move {
+            run_hook(&command, Some(&body), HOOK_TIMEOUT).map_err(|e| e.to_string())
+        });
+        self.pipe_task = Some(cx.spawn(async move |this, cx| {
+            let result = running.await;
+            let _ = this.update(cx, |this, cx| {
+                if let Err(e) = &result {
+                    tracing::info!("❌ PostmanApp - 管道命令失败: {e}");
                 }
+                this.pipe_output = Some(result);
+                this.pipe_task = None;
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
 
-                tracing::info!("🏁 PostmanApp - 请求从历史记录加载完成");
-                tracing::info!("   URL已加载到URL输入框");
-                tracing::info!("   Headers数量: {}", request.headers.len());
-                if request.body.is_some() {
-                    tracing::info!("   请求体已加载");
+    // 在后台诊断失败请求的主机：DNS 记录、TCP 连接测试，可选 traceroute
+    fn run_diagnostics(&mut self, cx: &mut Context<Self>) {
+        let Some(url) = self.failed_url.clone() else {
+            return;
+        };
+        let traceroute = self.diagnose_traceroute;
+        // 找不到系统 DNS 服务器时询问的服务器，留空则只用系统解析
+        let fallback = match self.diagnose_nameserver_input.read(cx).get_content().trim() {
+            "" => None,
+            text => match parse_nameserver(text) {
+                Ok(nameserver) => Some(nameserver),
+                Err(e) => {
+                    self.diagnostics = Some(Err(e.to_string()));
+                    cx.notify();
+                    return;
                 }
-
+            },
+        };
+        tracing::info!("🩺 PostmanApp - 开始诊断: {url}");
+        self.diagnostics = None;
+        let running = cx.background_executor().spawn(async move {
+            diagnose(&url, traceroute, fallback)
+                .map(|report| report.to_text())
+                .map_err(|e| e.to_string())
+        });
+        self.diagnostics_task = Some(cx.spawn(async move |this, cx| {
+            let result = running.await;
+            let _ = this.update(cx, |this, cx| {
+                this.diagnostics = Some(result);
+                this.diagnostics_task = None;
                 cx.notify();
-            }
-        }
+            });
+        }));
+        cx.notify();
     }
 
-    // Helper function to get checkbox background color
-    fn checkbox_bg_color(enabled: bool) -> u32 {
-        if enabled {
-            COLOR_CHECKBOX_ENABLED_BG
-        } else {
-            COLOR_CHECKBOX_DISABLED_BG
+    // 将显示的响应固定为请求的黄金基线（替换之前的基线，保留忽略的路径）
+    fn pin_baseline(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
         }
+        let Some((method, url)) = self.baseline_request.clone() else {
+            return;
+        };
+        let ResponseState::Success { status, body, .. } = self.response_viewer.read(cx).get_state()
+        else {
+            return;
+        };
+        let (status, body) = (*status, body.clone());
+        tracing::info!("🥇 PostmanApp - 已固定黄金基线: {method} {url}");
+        self.baselines.pin(method, &url, status, &body);
+        self.count_usage(Feature::BaselinePinned, cx);
+        self.record_activity(
+            ActivityKind::BaselinesChanged,
+            format!("Pinned {method} {url}"),
+            cx,
+        );
+        self.mark_unsaved(SavedFile::Baselines, cx);
+        self.refresh_baseline_changes(cx);
+        cx.notify();
     }
 
-    // Helper function to get checkbox hover background color
-    fn checkbox_hover_bg_color(enabled: bool) -> u32 {
-        if enabled {
-            COLOR_CHECKBOX_ENABLED_HOVER
-        } else {
-            COLOR_CHECKBOX_DISABLED_HOVER
+    fn unpin_baseline(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some((method, url)) = self.baseline_request.clone() else {
+            return;
+        };
+        if self.baselines.unpin(method, &url).is_some() {
+            tracing::info!("🥇 PostmanApp - 已移除黄金基线: {method} {url}");
+            self.record_activity(
+                ActivityKind::BaselinesChanged,
+                format!("Unpinned {method} {url}"),
+                cx,
+            );
+            self.mark_unsaved(SavedFile::Baselines, cx);
+            self.refresh_baseline_changes(cx);
+            cx.notify();
         }
     }
 
-    // Helper function to get header cell background color
-    fn header_cell_bg_color(enabled: bool) -> u32 {
-        if enabled {
-            COLOR_HEADER_ENABLED_BG
-        } else {
-            COLOR_HEADER_DISABLED_BG
+    // 保存对比时忽略的 JSON 路径（逗号分隔）并重新对比
+    fn apply_baseline_ignored(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some((method, url)) = self.baseline_request.clone() else {
+            return;
+        };
+        let ignored: Vec<String> = self
+            .baseline_ignore_input
+            .read(cx)
+            .get_content()
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+            .map(str::to_string)
+            .collect();
+        if self.baselines.set_ignored(method, &url, ignored) {
+            self.record_activity(
+                ActivityKind::BaselinesChanged,
+                format!("Changed ignored paths of {method} {url}"),
+                cx,
+            );
+            self.mark_unsaved(SavedFile::Baselines, cx);
+            self.refresh_baseline_changes(cx);
+            cx.notify();
         }
     }
 
-    // Helper function to get header cell border color
-    fn header_cell_border_color(enabled: bool) -> u32 {
-        if enabled {
-            COLOR_HEADER_ENABLED_BORDER
-        } else {
-            COLOR_HEADER_DISABLED_BORDER
-        }
+    // 对比显示的响应与其请求的黄金基线，并显示基线忽略的路径
+    fn refresh_baseline_changes(&mut self, cx: &mut Context<Self>) {
+        let baseline = self
+            .baseline_request
+            .as_ref()
+            .and_then(|(method, url)| self.baselines.get(*method, url));
+        let ignored = baseline
+            .map(|baseline| baseline.ignored.join(", "))
+            .unwrap_or_default();
+        self.baseline_changes = match (baseline, self.response_viewer.read(cx).get_state()) {
+            (Some(baseline), ResponseState::Success { status, body, .. }) => Some(diff_responses(
+                baseline.status,
+                &baseline.body,
+                *status,
+                body,
+                &baseline.ignored,
+            )),
+            _ => None,
+        };
+        self.baseline_ignore_input
+            .update(cx, |input, cx| input.set_content(ignored, cx));
     }
 
-    // Helper function to get header text color
-    fn header_text_color(enabled: bool) -> u32 {
-        if enabled {
-            COLOR_TEXT_ENABLED
-        } else {
-            COLOR_TEXT_DISABLED
-        }
+    // 表单中当前请求的方法和 URL（响应示例按此归属）
+    fn example_request(&self, cx: &App) -> (HttpMethod, String) {
+        (
+            self.method_selector.read(cx).current_method(cx),
+            self.url_input.read(cx).get_url().trim().to_string(),
+        )
     }
 
-    // 检测 body 类型
-    fn detect_body_type(body: &str) -> BodyType {
-        // 尝试解析为 JSON
-        if body.trim_start().starts_with('{') || body.trim_start().starts_with('[') {
-            if serde_json::from_str::<serde_json::Value>(body).is_ok() {
-                return BodyType::Json;
-            }
+    // 把显示的响应（含 headers 和批注）保存为请求的示例，未填名称时用状态码命名
+    fn save_response_as_example(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
         }
+        let ResponseState::Success {
+            status,
+            headers,
+            body,
+        } = self.response_viewer.read(cx).get_state()
+        else {
+            return;
+        };
+        let mut response = SavedResponse::new(*status, body.clone()).with_headers(headers.clone());
+        response.annotations = self.response_viewer.read(cx).annotations().to_vec();
+        self.add_example(response, cx);
+    }
 
-        // 检测是否是 URL encoded form data (key1=value1&key2=value2 格式)
-        if body.contains('=') && (body.contains('&') || !body.contains('\n')) {
-            // 简单检测：包含 = 且包含 & 或没有换行符
-            return BodyType::FormData;
+    // 手写的示例：状态码和响应体来自示例编辑器
+    fn add_written_example(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
         }
-
-        // 默认为 Raw
-        BodyType::Raw
+        let status = self.example_status_input.read(cx).get_content().to_string();
+        let status = match status.trim().parse::<u16>() {
+            Ok(status) if (100..=599).contains(&status) => status,
+            _ => {
+                self.notice = Some(format!(
+                    "\"{}\" is not a status code - enter a number from 100 to 599",
+                    status.trim()
+                ));
+                cx.notify();
+                return;
+            }
+        };
+        let body = self.example_body_input.read(cx).get_content();
+        self.add_example(SavedResponse::new(status, body), cx);
+        self.show_example_editor = false;
+        self.example_status_input
+            .update(cx, |input, cx| input.set_content(String::new(), cx));
+        self.example_body_input
+            .update(cx, |input, cx| input.clear(cx));
     }
 
-    // 解析并设置 FormData
-    fn parse_and_set_form_data(input: &mut BodyInput, body: &str, cx: &mut Context<BodyInput>) {
-        use crate::ui::components::body_input::FormDataEntry;
-        use form_urlencoded;
-
-        // 解析 URL encoded form data
-        let parsed = form_urlencoded::parse(body.as_bytes());
+    fn add_example(&mut self, response: SavedResponse, cx: &mut Context<Self>) {
+        let (method, url) = self.example_request(cx);
+        if url.is_empty() {
+            self.notice = Some("Enter the request URL before saving an example".to_string());
+            cx.notify();
+            return;
+        }
+        let name = match self.example_name_input.read(cx).get_content().trim() {
+            "" => format!("{} {}", response.status, reason_phrase(response.status)),
+            name => name.to_string(),
+        };
+        let name = self
+            .examples
+            .add(method, &url, ResponseExample::new(name, response));
+        tracing::info!("📎 PostmanApp - 已保存响应示例 \"{name}\": {method} {url}");
+        self.count_usage(Feature::ExampleSaved, cx);
+        self.record_activity(
+            ActivityKind::ExamplesChanged,
+            format!("Saved \"{name}\" for {method} {url}"),
+            cx,
+        );
+        self.mark_unsaved(SavedFile::Examples, cx);
+        self.refresh_mock_routes();
+        self.example_name_input
+            .update(cx, |input, cx| input.set_content(String::new(), cx));
+        self.notice = Some(format!("Saved example \"{name}\""));
+        cx.notify();
+    }
 
-        let mut entries: Vec<FormDataEntry> = Vec::new();
+    // 在参考标签页中打开示例（发送请求后变回普通标签页）
+    fn open_example(&mut self, index: usize, cx: &mut Context<Self>) {
+        let (method, url) = self.example_request(cx);
+        let Some(example) = self.examples.for_request(method, &url).get(index).cloned() else {
+            return;
+        };
+        let request = self.snapshot_active_tab(cx).to_request();
+        self.open_in_new_tab(RequestTab::from_example(&request, &example), cx);
+    }
 
-        for (key, value) in parsed {
-            entries.push(FormDataEntry {
-                key: key.to_string(),
-                value: value.to_string(),
-                enabled: true,
-            });
+    fn remove_example(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
         }
-
-        // 如果没有解析到任何条目，至少添加一个空条目
-        if entries.is_empty() {
-            entries.push(FormDataEntry {
-                key: String::new(),
-                value: String::new(),
-                enabled: true,
-            });
+        let (method, url) = self.example_request(cx);
+        if let Some(example) = self.examples.remove(method, &url, index) {
+            tracing::info!("📎 PostmanApp - 已删除响应示例 \"{}\"", example.name);
+            self.record_activity(
+                ActivityKind::ExamplesChanged,
+                format!("Removed \"{}\" from {method} {url}", example.name),
+                cx,
+            );
+            self.mark_unsaved(SavedFile::Examples, cx);
+            self.refresh_mock_routes();
+            cx.notify();
         }
-
-        // 设置 FormData 条目
-        input.set_form_data_entries(entries, cx);
     }
 
-    fn render_headers_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
-        div()
-            .flex()
-            .flex_col()
-            .gap_3()
-            .child(
-                div()
-                    .child(format!(
-                        "Headers ({})",
-                        self.headers
-                            .iter()
-                            .filter(|(enabled, _, _)| *enabled)
-                            .count()
+    // 启动或停止用所有响应示例作答的本地 mock 服务
+    fn toggle_mock_server(&mut self, cx: &mut Context<Self>) {
+        if let Some(server) = self.mock_server.take() {
+            server.stop();
+            self.notice = Some("Mock server stopped".to_string());
+            cx.notify();
+            return;
+        }
+        let routes = routes_from_examples(&self.examples);
+        if routes.is_empty() {
+            self.notice =
+                Some("Save a response example first - each one becomes a mock route".to_string());
+            cx.notify();
+            return;
+        }
+        let port = self
+            .mock_port_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        let port = match port.as_str() {
+            "" => DEFAULT_MOCK_PORT,
+            port => match port.parse::<u16>() {
+                Ok(port) => port,
+                Err(_) => {
+                    self.notice = Some(format!("\"{port}\" is not a port number"));
+                    cx.notify();
+                    return;
+                }
+            },
+        };
+        match MockServer::start(port, routes) {
+            Ok(server) => {
+                self.notice = Some(format!(
+                    "Mock server running at {} with {}",
+                    server.url(),
+                    Self::route_count(server.routes())
+                ));
+                self.mock_server = Some(server);
+                self.count_usage(Feature::MockServer, cx);
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 启动 mock 服务失败: {e}");
+                self.notice = Some(format!("Could not start the mock server: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    fn route_count(routes: usize) -> String {
+        match routes {
+            1 => "1 route".to_string(),
+            routes => format!("{routes} routes"),
+        }
+    }
+
+    // 示例变化后 mock 服务立即按新的示例作答
+    fn refresh_mock_routes(&self) {
+        if let Some(server) = &self.mock_server {
+            server.set_routes(routes_from_examples(&self.examples));
+        }
+    }
+
+    // 在后台获取其余页面，完成后把所有页面的条目合并显示（批注标出每页的范围）
+    fn fetch_all_pages(&mut self, cx: &mut Context<Self>) {
+        if self.pages_task.is_some() {
+            return;
+        }
+        let Some(next_page) = self.next_page.take() else {
+            return;
+        };
+        let executor = match self.transport_executor(&next_page.request, cx) {
+            Ok(executor) => executor.unwrap_or_else(RequestExecutor::new),
+            Err(e) => {
+                self.notice = Some(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        tracing::info!("📄 PostmanApp - 开始获取其余页面: {}", next_page.url);
+        self.count_usage(Feature::PagesFetched, cx);
+        let NextPage {
+            headers,
+            first,
+            url,
+            ..
+        } = next_page;
+        let fetching = cx
+            .background_executor()
+            .spawn(async move { fetch_pages(&executor, first, url, &headers) });
+        self.pages_task = Some(cx.spawn(async move |this, cx| {
+            let (pages, error) = fetching.await;
+            let _ = this.update(cx, |this, cx| {
+                this.pages_task = None;
+                let items: usize = pages.iter().map(|page| page.items.len()).sum();
+                tracing::info!("📄 PostmanApp - 已获取 {} 页，共 {items} 条", pages.len());
+                let (body, annotations) = join_pages(&pages);
+                // 合并的响应不是历史记录中的响应，批注不再写回历史记录
+                this.sync_response_annotations(cx);
+                this.response_entry_id = None;
+                let (status, headers) = match this.response_viewer.read(cx).get_state() {
+                    ResponseState::Success {
+                        status, headers, ..
+                    } => (*status, headers.clone()),
+                    _ => (200, Vec::new()),
+                };
+                this.response_viewer.update(cx, |viewer, cx| {
+                    viewer.set_success(status, headers, body, cx);
+                    viewer.set_annotations(annotations, cx);
+                });
+                let fetched = format!("Fetched {} pages with {items} items", pages.len());
+                this.notice = Some(match error {
+                    Some(e) => format!("{fetched}, then stopped: {e}"),
+                    None => fetched,
+                });
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
+
+    // 有下一页时的“Fetch all pages”操作
+    fn render_pagination(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let fetching = self.pages_task.is_some();
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child(if fetching {
+                        "Fetching the remaining pages...".to_string()
+                    } else {
+                        let items = self
+                            .next_page
+                            .as_ref()
+                            .map_or(0, |next_page| next_page.first.items.len());
+                        format!("This list continues after these {items} items")
+                    }),
+            )
+            .when(!fetching, |row| {
+                row.child(self.render_grpc_button(
+                    "Fetch all pages",
+                    0x0000_7acc,
+                    0x0000_56b3,
+                    cx,
+                    Self::fetch_all_pages,
+                ))
+            })
+    }
+
+    fn toggle_format_rules(&mut self, cx: &mut Context<Self>) {
+        self.show_format_rules = !self.show_format_rules;
+        if self.show_format_rules {
+            let script = self.format_rules.script.clone();
+            self.format_rules_input
+                .update(cx, |input, cx| input.set_content(script, cx));
+        }
+        self.format_rules_error = None;
+        cx.notify();
+    }
+
+    // 保存条件格式规则（有错误时不保存）并重新检查显示的响应
+    fn save_format_rules(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let script = self.format_rules_input.read(cx).get_content();
+        let rules = FormatRules { script };
+        let count = match rules.rules() {
+            Ok(parsed) => parsed.len(),
+            Err(e) => {
+                self.format_rules_error = Some(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        tracing::info!("🎨 PostmanApp - 已保存 {count} 条条件格式规则");
+        self.format_rules = rules;
+        self.format_rules_error = None;
+        self.record_activity(
+            ActivityKind::FormatRulesChanged,
+            match count {
+                1 => "Saved 1 rule".to_string(),
+                count => format!("Saved {count} rules"),
+            },
+            cx,
+        );
+        self.mark_unsaved(SavedFile::FormatRules, cx);
+        self.refresh_highlights(cx);
+        cx.notify();
+    }
+
+    // 用条件格式规则检查显示的响应
+    fn refresh_highlights(&mut self, cx: &mut Context<Self>) {
+        let ResponseState::Success {
+            status,
+            headers,
+            body,
+        } = self.response_viewer.read(cx).get_state()
+        else {
+            self.highlights.clear();
+            return;
+        };
+        let mut context = AssertionContext::new(*status, headers, body);
+        if let Some(latency) = self.response_latency {
+            context = context.with_latency(latency);
+        }
+        self.highlights = match self.format_rules.rules() {
+            Ok(rules) => apply_rules(&rules, &context),
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 条件格式规则有误: {e}");
+                Vec::new()
+            }
+        };
+    }
+
+    fn toggle_pipe(&mut self, cx: &mut Context<Self>) {
+        self.show_pipe = !self.show_pipe;
+        if !self.show_pipe {
+            self.pipe_task = None;
+            self.pipe_output = None;
+        }
+        cx.notify();
+    }
+
+    // 开始 gRPC 调用 - 消息编辑区的内容作为第一条消息发送，
+    // 客户端不是流的方法发送后立即关闭客户端流
+    fn start_grpc_call(&mut self, cx: &mut Context<Self>) {
+        if let Some(mut session) = self.grpc_session.take() {
+            session.call.cancel();
+        }
+        self.grpc_log.update(cx, |log, cx| log.clear(cx));
+
+        let prepared = self.prepare_grpc_call(cx).and_then(|(pool, method, uri)| {
+            let first = self.encode_grpc_message(&pool, &method, cx)?;
+            Ok((pool, method, uri, first))
+        });
+        let (pool, method, uri, (first, first_text)) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 无法开始 gRPC 调用: {e}");
+                self.log_grpc(MessageLogKind::Status { ok: false }, e.to_string(), cx);
+                return;
+            }
+        };
+        self.count_usage(Feature::GrpcCall, cx);
+        let metadata: Vec<(String, String)> = self
+            .headers
+            .iter()
+            .filter(|(enabled, _, _)| *enabled)
+            .map(|(_, key, value)| (key.clone(), value.clone()))
+            .collect();
+
+        let (mut call, mut events) = GrpcCall::start(uri, metadata);
+        let kind = match (method.client_streaming, method.server_streaming) {
+            (false, false) => "unary",
+            (false, true) => "server streaming",
+            (true, false) => "client streaming",
+            (true, true) => "bidirectional streaming",
+        };
+        self.log_grpc(
+            MessageLogKind::Info,
+            format!("{} ({kind})", method.path),
+            cx,
+        );
+        if call.send(first).is_ok() {
+            self.log_grpc(MessageLogKind::Sent, first_text, cx);
+        }
+        if !method.client_streaming {
+            call.end_stream();
+        }
+
+        let task = cx.spawn(async move |this, cx| {
+            while let Some(event) = events.recv().await {
+                let done = matches!(event, GrpcEvent::Finished { .. } | GrpcEvent::Error(_));
+                let _ = this.update(cx, |this, cx| this.on_grpc_event(event, cx));
+                if done {
+                    break;
+                }
+            }
+        });
+        self.grpc_session = Some(GrpcSession {
+            call,
+            pool,
+            method,
+            _task: task,
+        });
+        cx.notify();
+    }
+
+    // 读取 descriptor set 并查找方法
+    fn prepare_grpc_call(
+        &self,
+        cx: &App,
+    ) -> Result<(DescriptorPool, MethodDescriptor, hyper::Uri), AppError> {
+        let descriptor = self
+            .proto_descriptor_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        if descriptor.is_empty() {
+            return Err(AppError::ValidationError(
+                "Enter the path of a descriptor set (protoc --include_imports --descriptor_set_out)"
+                    .to_string(),
+            ));
+        }
+        let pool = DescriptorPool::from_descriptor_set(&std::fs::read(&descriptor)?)?;
+        let name = self.grpc_method_input.read(cx).get_content();
+        let method = pool.method(&name).cloned().ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "Method '{}' is not in the descriptor set",
+                name.trim()
+            ))
+        })?;
+        let uri = grpc_uri(&self.grpc_server_input.read(cx).get_content(), &method.path)?;
+        Ok((pool, method, uri))
+    }
+
+    // 把消息编辑区的 JSON 编码为方法的请求消息，同时返回紧凑的 JSON 用于日志
+    fn encode_grpc_message(
+        &self,
+        pool: &DescriptorPool,
+        method: &MethodDescriptor,
+        cx: &App,
+    ) -> Result<(Vec<u8>, String), AppError> {
+        let text = self.grpc_message_input.read(cx).get_content();
+        let value: serde_json::Value = serde_json::from_str(&text)
+            .map_err(|e| AppError::ValidationError(format!("Message is not valid JSON: {e}")))?;
+        let bytes = pool.encode(&method.input_type, &value)?;
+        Ok((bytes, value.to_string()))
+    }
+
+    // 在客户端流上发送消息编辑区中的下一条消息
+    fn send_grpc_message(&mut self, cx: &mut Context<Self>) {
+        let result = match &self.grpc_session {
+            Some(session) => self
+                .encode_grpc_message(&session.pool, &session.method, cx)
+                .and_then(|(bytes, text)| session.call.send(bytes).map(|()| text)),
+            None => return,
+        };
+        match result {
+            Ok(text) => self.log_grpc(MessageLogKind::Sent, text, cx),
+            Err(e) => {
+                self.notice = Some(format!("Could not send message: {e}"));
+                cx.notify();
+            }
+        }
+    }
+
+    // 关闭客户端流（服务端仍可继续发送）
+    fn end_grpc_stream(&mut self, cx: &mut Context<Self>) {
+        if let Some(session) = &mut self.grpc_session {
+            session.call.end_stream();
+            self.log_grpc(MessageLogKind::Info, "Client stream closed", cx);
+        }
+    }
+
+    fn cancel_grpc_call(&mut self, cx: &mut Context<Self>) {
+        if let Some(session) = &mut self.grpc_session {
+            session.call.cancel();
+        }
+        cx.notify();
+    }
+
+    fn on_grpc_event(&mut self, event: GrpcEvent, cx: &mut Context<Self>) {
+        match event {
+            GrpcEvent::Headers(headers) => {
+                let content_type = header_value(&headers, "Content-Type")
+                    .unwrap_or("no content type")
+                    .to_string();
+                self.log_grpc(
+                    MessageLogKind::Info,
+                    format!("Response headers ({content_type})"),
+                    cx,
+                );
+            }
+            GrpcEvent::Message(bytes) => {
+                let Some(session) = &self.grpc_session else {
+                    return;
+                };
+                let text = match session.pool.decode(&session.method.output_type, &bytes) {
+                    Ok(value) => value.to_json_pretty(),
+                    Err(e) => format!("Could not decode {} bytes: {e}", bytes.len()),
+                };
+                self.log_grpc(MessageLogKind::Received, text, cx);
+            }
+            GrpcEvent::Finished { status, .. } => {
+                tracing::info!("🏁 PostmanApp - gRPC 调用结束: {status}");
+                self.log_grpc(
+                    MessageLogKind::Status { ok: status.is_ok() },
+                    status.to_string(),
+                    cx,
+                );
+                self.grpc_session = None;
+            }
+            GrpcEvent::Error(e) => {
+                self.log_grpc(MessageLogKind::Status { ok: false }, e, cx);
+                self.grpc_session = None;
+            }
+        }
+        cx.notify();
+    }
+
+    fn log_grpc(&self, kind: MessageLogKind, text: impl Into<String>, cx: &mut Context<Self>) {
+        let text = text.into();
+        self.grpc_log.update(cx, |log, cx| log.push(kind, text, cx));
+    }
+
+    // 窗口创建后订阅子组件：查询编辑器内容或光标变化时刷新 GraphQL 补全和校验结果，
+    // 响应中的链接在新标签页中打开，API explorer 的请求通过正常流程发送，
+    // 域名默认值、客户端证书和断言片段修改后保存，共享状态变化时重新渲染，
+    // 退出时写入还未自动保存的修改
+    pub fn watch_components(&mut self, cx: &mut Context<Self>) {
+        cx.observe(&self.graphql_query_input, |_this, _input, cx| cx.notify())
+            .detach();
+        cx.observe(&self.state, |_this, _state, cx| cx.notify())
+            .detach();
+        cx.subscribe(&self.response_viewer, Self::on_response_viewer_event)
+            .detach();
+        cx.subscribe(&self.api_explorer, Self::on_api_explorer_event)
+            .detach();
+        cx.subscribe(&self.domains_page, Self::on_domains_page_event)
+            .detach();
+        cx.subscribe(&self.certificates_page, Self::on_certificates_page_event)
+            .detach();
+        cx.subscribe(&self.snippets_page, Self::on_snippets_page_event)
+            .detach();
+        cx.subscribe(&self.protection_selector, Self::on_protection_selected)
+            .detach();
+        cx.subscribe(&self.body_input, Self::on_body_changed)
+            .detach();
+        cx.on_app_quit(|this, cx| {
+            this.flush_unsaved(cx);
+            async {}
+        })
+        .detach();
+    }
+
+    /// 窗口创建后按保存的设置开始健康检查和剪贴板监听
+    pub fn restore_settings(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.config.health_checks {
+            self.start_health_checks(cx);
+        }
+        if self.config.watch_clipboard {
+            self.start_clipboard_watch(window, cx);
+        }
+    }
+
+    // 表单以 multipart 发送时刷新各部分的预览
+    fn on_body_changed(
+        &mut self,
+        _input: Entity<BodyInput>,
+        _event: &BodyInputEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let previews = if self.form_is_multipart(cx) {
+            self.body_input
+                .read(cx)
+                .form_parts()
+                .iter()
+                .map(PartPreview::new)
+                .collect()
+        } else {
+            Vec::new()
+        };
+        self.part_previews = previews;
+        cx.notify();
+    }
+
+    // form-data 中有 `@path` 文件条目或指定了 Content-Type 的条目时以 multipart 发送
+    fn form_is_multipart(&self, cx: &App) -> bool {
+        let input = self.body_input.read(cx);
+        *input.get_current_type() == BodyType::FormData
+            && input.form_parts().iter().any(|part| {
+                multipart::file_path(&part.value).is_some() || part.content_type.is_some()
+            })
+    }
+
+    // 切换签名/加密算法时提示需要输入的密钥
+    fn on_protection_selected(
+        &mut self,
+        _selector: Entity<Dropdown>,
+        event: &DropdownEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let DropdownEvent::SelectionChanged(label) = event;
+        if let Some(protection) = BodyProtection::from_label(label) {
+            self.protection_key_input.update(cx, |input, cx| {
+                input.set_placeholder(protection.key_hint(), cx);
+            });
+        }
+        cx.notify();
+    }
+
+    // 选中的请求体签名/加密方式（None 表示原样发送）
+    fn selected_protection(&self, cx: &App) -> Option<BodyProtection> {
+        BodyProtection::from_label(self.protection_selector.read(cx).selected_value())
+    }
+
+    // 保存当前 profile 的域名默认值（与历史记录一样经 vault 加密）
+    fn on_domains_page_event(
+        &mut self,
+        _page: Entity<DomainsPage>,
+        event: &DomainsPageEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let DomainsPageEvent::Changed(change) = event;
+        self.record_activity(ActivityKind::DomainsChanged, change.clone(), cx);
+        self.mark_unsaved(SavedFile::Domains, cx);
+    }
+
+    // 保存当前 profile 的客户端证书并刷新证书下拉框
+    fn on_certificates_page_event(
+        &mut self,
+        _page: Entity<CertificatesPage>,
+        event: &CertificatesPageEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let CertificatesPageEvent::Changed(change) = event;
+        self.record_activity(ActivityKind::CertificatesChanged, change.clone(), cx);
+        self.refresh_certificate_choices(cx);
+        self.mark_unsaved(SavedFile::Certificates, cx);
+        cx.notify();
+    }
+
+    // 保存当前 profile 的断言片段
+    fn on_snippets_page_event(
+        &mut self,
+        _page: Entity<SnippetsPage>,
+        event: &SnippetsPageEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let SnippetsPageEvent::Changed(change) = event;
+        self.record_activity(ActivityKind::SnippetsChanged, change.clone(), cx);
+        self.mark_unsaved(SavedFile::Snippets, cx);
+    }
+
+    // 导入 OpenAPI spec 并在 explorer 中显示
+    fn import_api_spec(&mut self, cx: &mut Context<Self>) {
+        let path = self
+            .api_spec_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        if path.is_empty() {
+            self.notice = Some("Enter the path of an OpenAPI spec".to_string());
+            cx.notify();
+            return;
+        }
+        match ApiSpec::load(Path::new(&path)) {
+            Ok(spec) => {
+                tracing::info!(
+                    "🧭 PostmanApp - 已导入 OpenAPI spec: {} ({} 个操作)",
+                    spec.title,
+                    spec.operations.len()
+                );
+                self.notice = Some(format!(
+                    "Imported {} - {} operations",
+                    spec.title,
+                    spec.operations.len()
+                ));
+                self.record_activity(
+                    ActivityKind::SpecImported,
+                    format!("{} ({path})", spec.title),
+                    cx,
+                );
+                self.api_explorer
+                    .update(cx, |explorer, cx| explorer.set_spec(Some(spec), cx));
+                self.show_api_explorer = true;
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 导入 OpenAPI spec 失败: {e}");
+                self.notice = Some(format!("Failed to import spec: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // Try it：把生成的请求载入 HTTP 表单再发送，同源时保留当前的认证 headers
+    fn on_api_explorer_event(
+        &mut self,
+        _explorer: Entity<ApiExplorer>,
+        event: &ApiExplorerEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let ApiExplorerEvent::TryIt(request) = event;
+        let mut request = request.clone();
+        let current = self.snapshot_active_tab(cx);
+        let credentials = if same_origin(&current.url, &request.url) {
+            current.credential_headers()
+        } else {
+            Vec::new()
+        };
+        for (_, key, value) in credentials {
+            if !request
+                .headers
+                .iter()
+                .any(|(existing, _)| existing.eq_ignore_ascii_case(&key))
+            {
+                request.add_header(key, value);
+            }
+        }
+        self.raw_mode = false;
+        self.grpc_mode = false;
+        self.graphql_mode = false;
+        self.load_request(&request, cx);
+        self.count_usage(Feature::ApiExplorerTry, cx);
+        self.send_request(cx);
+    }
+
+    // GraphQL 请求体：{"query": ..., "variables": ...}，变量为空时省略
+    fn graphql_payload(&self, cx: &App) -> Result<String, AppError> {
+        let (query, variables) = self.graphql_operation(cx)?;
+        let mut payload = serde_json::json!({ "query": query });
+        if let Some(variables) = variables {
+            payload["variables"] = variables;
+        }
+        Ok(payload.to_string())
+    }
+
+    // 编辑器中的查询和变量
+    fn graphql_operation(&self, cx: &App) -> Result<(String, Option<serde_json::Value>), AppError> {
+        let query = self.graphql_query_input.read(cx).get_content();
+        if query.trim().is_empty() {
+            return Err(AppError::ValidationError(
+                "Enter a GraphQL query".to_string(),
+            ));
+        }
+        let variables = self.graphql_variables_input.read(cx).get_content();
+        let variables = if variables.trim().is_empty() {
+            None
+        } else {
+            Some(serde_json::from_str(&variables).map_err(|e| {
+                AppError::ValidationError(format!("Variables are not valid JSON: {e}"))
+            })?)
+        };
+        Ok((query, variables))
+    }
+
+    // 通过 WebSocket 开始 subscription - 已启用的 headers（含认证插件添加的）
+    // 既随握手发送，也作为 connection_init 的 payload
+    fn start_graphql_subscription(&mut self, cx: &mut Context<Self>) {
+        self.stop_graphql_subscription(cx);
+        self.graphql_log.update(cx, |log, cx| log.clear(cx));
+        self.show_graphql_log = true;
+        self.count_usage(Feature::GraphqlSubscription, cx);
+
+        let url = websocket_url(self.url_input.read(cx).get_url());
+        let prepared = WebSocketTarget::parse(&url).and_then(|target| {
+            let (query, variables) = self.graphql_operation(cx)?;
+            let mut request = Request::new(HttpMethod::GET, &url);
+            for (_, key, value) in self.headers.iter().filter(|(enabled, _, _)| *enabled) {
+                request.add_header(key, value);
+            }
+            let (headers, _) = self.apply_plugins(&request, cx)?;
+            Ok((target, query, variables, headers))
+        });
+        let (target, query, variables, headers) = match prepared {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 无法开始 subscription: {e}");
+                self.log_graphql(MessageLogKind::Status { ok: false }, e.to_string(), cx);
+                cx.notify();
+                return;
+            }
+        };
+
+        let init_payload = (!headers.is_empty()).then(|| {
+            serde_json::Value::Object(
+                headers
+                    .iter()
+                    .map(|(key, value)| (key.clone(), serde_json::Value::from(value.as_str())))
+                    .collect(),
+            )
+        });
+        let (connection, mut events) = WebSocketConnection::connect(
+            target,
+            vec![graphql_ws::SUBPROTOCOL.to_string()],
+            headers,
+        );
+        let _ = connection.send_text(ClientMessage::ConnectionInit(init_payload).encode());
+        self.log_graphql(MessageLogKind::Info, format!("Connecting to {url}"), cx);
+
+        let task = cx.spawn(async move |this, cx| {
+            while let Some(event) = events.recv().await {
+                let done = matches!(
+                    event,
+                    WebSocketEvent::Closed { .. } | WebSocketEvent::Error(_)
+                );
+                let _ = this.update(cx, |this, cx| this.on_graphql_ws_event(event, cx));
+                if done {
+                    break;
+                }
+            }
+        });
+        self.graphql_subscription = Some(GraphqlSubscription {
+            connection,
+            subscribe: ClientMessage::Subscribe {
+                id: "1".to_string(),
+                query,
+                variables,
+            },
+            _task: task,
+        });
+        cx.notify();
+    }
+
+    // 停止 subscription：发送 complete 后关闭连接
+    fn stop_graphql_subscription(&mut self, cx: &mut Context<Self>) {
+        if let Some(subscription) = &self.graphql_subscription {
+            if let ClientMessage::Subscribe { id, .. } = &subscription.subscribe {
+                let _ = subscription
+                    .connection
+                    .send_text(ClientMessage::Complete(id.clone()).encode());
+            }
+            subscription.connection.close();
+            self.log_graphql(MessageLogKind::Info, "Subscription stopped", cx);
+        }
+        cx.notify();
+    }
+
+    fn on_graphql_ws_event(&mut self, event: WebSocketEvent, cx: &mut Context<Self>) {
+        match event {
+            WebSocketEvent::Opened { protocol } => {
+                if protocol.as_deref() != Some(graphql_ws::SUBPROTOCOL) {
+                    self.log_graphql(
+                        MessageLogKind::Info,
+                        format!(
+                            "Server did not confirm the {} subprotocol",
+                            graphql_ws::SUBPROTOCOL
+                        ),
+                        cx,
+                    );
+                }
+                self.log_graphql(
+                    MessageLogKind::Info,
+                    "Connected, waiting for connection_ack",
+                    cx,
+                );
+            }
+            WebSocketEvent::Text(text) => self.on_graphql_ws_message(&text, cx),
+            WebSocketEvent::Binary(data) => {
+                self.log_graphql(
+                    MessageLogKind::Info,
+                    format!("Ignored a {} byte binary message", data.len()),
+                    cx,
+                );
+            }
+            WebSocketEvent::Closed { code, reason } => {
+                tracing::info!("🏁 PostmanApp - subscription 连接关闭: {code:?} {reason}");
+                let text = match code {
+                    Some(code) => format!("Connection closed ({code}) {reason}"),
+                    None => format!("Connection closed {reason}"),
+                };
+                self.log_graphql(
+                    MessageLogKind::Status {
+                        ok: matches!(code, Some(1000) | None),
+                    },
+                    text.trim_end().to_string(),
+                    cx,
+                );
+                self.graphql_subscription = None;
+            }
+            WebSocketEvent::Error(e) => {
+                self.log_graphql(MessageLogKind::Status { ok: false }, e, cx);
+                self.graphql_subscription = None;
+            }
+        }
+        cx.notify();
+    }
+
+    fn on_graphql_ws_message(&mut self, text: &str, cx: &mut Context<Self>) {
+        let Some(subscription) = &self.graphql_subscription else {
+            return;
+        };
+        let message = match ServerMessage::decode(text) {
+            Ok(message) => message,
+            Err(e) => {
+                self.log_graphql(MessageLogKind::Info, format!("{e}: {text}"), cx);
+                return;
+            }
+        };
+        match message {
+            ServerMessage::ConnectionAck(_) => {
+                let subscribe = subscription.subscribe.encode();
+                if subscription.connection.send_text(subscribe.clone()).is_ok() {
+                    self.log_graphql(MessageLogKind::Sent, subscribe, cx);
+                }
+            }
+            ServerMessage::Next { payload, .. } => {
+                let text =
+                    serde_json::to_string_pretty(&payload).unwrap_or_else(|_| payload.to_string());
+                self.log_graphql(MessageLogKind::Received, text, cx);
+            }
+            ServerMessage::Error { errors, .. } => {
+                self.log_graphql(MessageLogKind::Status { ok: false }, errors.to_string(), cx);
+                subscription.connection.close();
+            }
+            ServerMessage::Complete(_) => {
+                self.log_graphql(
+                    MessageLogKind::Status { ok: true },
+                    "Server completed the subscription",
+                    cx,
+                );
+                subscription.connection.close();
+            }
+            ServerMessage::Ping(_) => {
+                let _ = subscription
+                    .connection
+                    .send_text(ClientMessage::Pong.encode());
+            }
+            ServerMessage::Pong(_) => {}
+        }
+    }
+
+    fn log_graphql(&self, kind: MessageLogKind, text: impl Into<String>, cx: &mut Context<Self>) {
+        let text = text.into();
+        self.graphql_log
+            .update(cx, |log, cx| log.push(kind, text, cx));
+    }
+
+    // 当前 URL 对应的已缓存 schema
+    fn graphql_schema(&self, cx: &App) -> Option<&Schema> {
+        self.graphql_schemas
+            .get(self.url_input.read(cx).get_url().trim())
+    }
+
+    // 运行 introspection 查询并缓存 schema（认证插件和已启用的 headers 同样生效）
+    fn fetch_graphql_schema(&mut self, cx: &mut Context<Self>) {
+        let url = self.url_input.read(cx).get_url().trim().to_string();
+        if url.is_empty() {
+            self.notice = Some("Enter the GraphQL endpoint URL".to_string());
+            cx.notify();
+            return;
+        }
+
+        let mut request = Request::new(HttpMethod::POST, &url);
+        for (_, key, value) in self.headers.iter().filter(|(enabled, _, _)| *enabled) {
+            request.add_header(key, value);
+        }
+        if !request
+            .headers
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+        {
+            request.add_header("Content-Type", "application/json");
+        }
+        request.set_body(introspection_body());
+
+        tracing::info!("🔎 PostmanApp - 获取 GraphQL schema: {url}");
+        let schema = self
+            .apply_plugins(&request, cx)
+            .and_then(|(headers, body)| {
+                self.request_executor
+                    .execute_bytes(HttpMethod::POST, &url, headers, body)
+            })
+            .and_then(|result| {
+                if !(200..300).contains(&result.status) {
+                    // 关闭 introspection 的服务器通常仍返回 GraphQL errors
+                    return Schema::from_introspection(&result.body).map_err(|_| {
+                        AppError::NetworkError(format!(
+                            "Introspection request failed with HTTP {}",
+                            result.status
+                        ))
+                    });
+                }
+                Schema::from_introspection(&result.body)
+            });
+        match schema {
+            Ok(schema) => {
+                tracing::info!(
+                    "✅ PostmanApp - GraphQL schema 已加载: {} 个类型",
+                    schema.type_count()
+                );
+                self.notice = Some(format!(
+                    "Schema loaded: {} types from {url}",
+                    schema.type_count()
+                ));
+                self.graphql_docs
+                    .update(cx, |docs, cx| docs.set_schema(Some(schema.clone()), cx));
+                self.graphql_schemas.insert(url, schema);
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 获取 GraphQL schema 失败: {e}");
+                self.notice = Some(format!("Could not fetch the schema: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    fn toggle_graphql_mode(&mut self, cx: &mut Context<Self>) {
+        self.graphql_mode = !self.graphql_mode;
+        if self.graphql_mode {
+            self.raw_mode = false;
+            self.grpc_mode = false;
+            // 切换回来时显示当前 URL 已缓存的 schema
+            let schema = self.graphql_schema(cx).cloned();
+            if schema.is_some() {
+                self.graphql_docs
+                    .update(cx, |docs, cx| docs.set_schema(schema, cx));
+            }
+        }
+        tracing::info!("🕸️ PostmanApp - GraphQL 模式: {}", self.graphql_mode);
+        cx.notify();
+    }
+
+    // 请求成功 - 解码响应体、写入历史记录并显示响应
+    // （`streamed` 为 true 时 NDJSON 记录已在接收过程中显示）
+    fn finish_request(
+        &mut self,
+        request: Request,
+        url: &str,
+        mut request_result: RequestResult,
+        elapsed: Duration,
+        streamed: bool,
+        cx: &mut Context<Self>,
+    ) {
+        let post_hook = request.hooks.post.clone();
+        if let Some(body) = self.render_response_body(&request_result, cx) {
+            request_result.body = body;
+        }
+
+        self.show_records = header_value(&request_result.headers, "Content-Type")
+            .is_some_and(is_ndjson_content_type);
+        if self.show_records && !streamed {
+            let text = String::from_utf8_lossy(&request_result.raw_body).into_owned();
+            self.ndjson_viewer
+                .update(cx, |viewer, cx| viewer.set_body(&text, cx));
+        }
+        self.show_csv_grid(&request_result.headers, &request_result.body, cx);
+
+        // 请求对应 spec 中的操作时，对比响应与文档中的 schema
+        let drift = self.api_explorer.update(cx, |explorer, cx| {
+            explorer.check_response(
+                request.method,
+                url,
+                request_result.status,
+                &request_result.body,
+                cx,
+            )
+        });
+        if drift > 0 {
+            let differences = match drift {
+                1 => "1 difference".to_string(),
+                drift => format!("{drift} differences"),
+            };
+            self.notice = Some(format!(
+                "The response has {differences} from the spec - see the drift report"
+            ));
+        }
+
+        // 检查请求附带的断言片段
+        let failed = self.snippets_page.update(cx, |page, cx| {
+            page.check_response(
+                request_result.status,
+                &request_result.headers,
+                &request_result.body,
+                elapsed,
+                cx,
+            )
+        });
+        if failed > 0 {
+            tracing::info!("🧪 PostmanApp - {failed} 个断言失败");
+            self.notice = Some(match failed {
+                1 => "1 assertion failed - see Assertions".to_string(),
+                failed => format!("{failed} assertions failed - see Assertions"),
+            });
+        }
+
+        let baseline_request = (request.method, request.url.clone());
+
+        // Add to history on success, named with the profile's template
+        let method = request.method.to_string();
+        let name = name_template::render(
+            Self::history_name_template_of(&self.profiles),
+            &NameFields {
+                method: &method,
+                url,
+                status: request_result.status,
+                duration_ms: elapsed.as_millis(),
+                time: Local::now(),
+            },
+        );
+        let response = SavedResponse::new(request_result.status, request_result.body.clone())
+            .with_headers(request_result.headers.clone());
+        let entry_id = self.state.update(cx, |state, cx| {
+            state.update_history(cx, |history| {
+                let entry_id = history.add(request, name);
+                if let Some(entry) = history.find_mut(entry_id) {
+                    entry.response = Some(response);
+                }
+                entry_id
+            })
+        });
+        self.response_entry_id = Some(entry_id);
+        // 保存响应设置的 cookies，之后发往同一站点的请求会带上
+        let stored = self.state.update(cx, |state, cx| {
+            state.update_cookies(cx, |cookies| {
+                cookies.store(url, &request_result.headers, Utc::now())
+            })
+        });
+        if stored {
+            tracing::info!("🍪 PostmanApp - 已保存响应设置的 cookies");
+        }
+        // 参考标签页收到实际响应后变回普通标签页
+        if self.shown_example.take().is_some() {
+            self.tabs[self.active_tab].name = None;
+        }
+
+        // 后置 hook 在后台从 stdin 读取响应体，输出保存为变量供之后的请求使用
+        if let Some(hook) = post_hook {
+            let stdin = Some(request_result.raw_body.clone());
+            self.run_request_hook(hook, stdin, cx, |this, result, _| {
+                if let Err(e) = result {
+                    tracing::info!("❌ PostmanApp - 后置 hook 失败: {e}");
+                    this.notice = Some(format!("Post-response hook failed: {e}"));
+                }
+            })
+            .detach();
+        }
+
+        let kind = detect(
+            header_value(&request_result.headers, "Content-Type"),
+            &request_result.raw_body,
+        );
+        self.response_viewer.update(cx, |viewer, cx| {
+            viewer.set_success(
+                request_result.status,
+                request_result.headers,
+                request_result.body,
+                cx,
+            );
+        });
+        if kind == BodyKind::Image {
+            self.show_response_image(entry_id, &request_result.raw_body, cx);
+        }
+
+        // 条件格式规则：横幅显示在响应上方，徽章显示在标签页上
+        self.response_latency = Some(elapsed);
+        self.refresh_highlights(cx);
+
+        // 请求有黄金基线时对比响应
+        self.baseline_request = Some(baseline_request);
+        self.refresh_baseline_changes(cx);
+        let changed = self.baseline_changes.as_ref().map_or(0, Vec::len);
+        if changed > 0 {
+            tracing::info!("🥇 PostmanApp - 响应与基线有 {changed} 处不同");
+            self.notice = Some(match changed {
+                1 => "The response differs from its golden baseline in 1 place".to_string(),
+                changed => {
+                    format!("The response differs from its golden baseline in {changed} places")
+                }
+            });
+        }
+    }
+
+    // 图片响应写入临时文件，供响应查看器预览
+    fn show_response_image(&mut self, entry_id: u64, body: &[u8], cx: &mut Context<Self>) {
+        let extension = sniff(body).strip_prefix("image/").unwrap_or("img");
+        let path = std::env::temp_dir().join(format!(
+            "postman-gpui-response-{}-{entry_id}.{extension}",
+            std::process::id()
+        ));
+        match std::fs::write(&path, body) {
+            Ok(()) => self
+                .response_viewer
+                .update(cx, |viewer, cx| viewer.set_image(path, cx)),
+            Err(e) => tracing::info!("❌ PostmanApp - 写入图片响应失败: {e}"),
+        }
+    }
+
+    // 使用选中的认证插件和请求体序列化插件生成实际发送的 headers 和请求体；
+    // 请求目标域名配置了默认值时补上请求本身没有设置的 headers 和认证。
+    // 编辑器中的请求使用当前选择的认证、表单、签名 / 加密和序列化插件
+    fn apply_plugins(&self, request: &Request, cx: &App) -> Result<PreparedRequest, AppError> {
+        let auth = self.auth_provider.as_ref().map(|name| {
+            (
+                name.clone(),
+                self.auth_credential_input
+                    .read(cx)
+                    .get_content()
+                    .to_string(),
+            )
+        });
+        let body_input = self.body_input.read(cx);
+        let form_parts =
+            (*body_input.get_current_type() == BodyType::FormData).then(|| body_input.form_parts());
+        let protection = self.selected_protection(cx).map(|protection| {
+            let key_id = self.protection_key_id_input.read(cx).get_content().trim();
+            Protection {
+                protection,
+                key: self.protection_key_input.read(cx).get_content().to_string(),
+                key_id: (!key_id.is_empty()).then(|| key_id.to_string()),
+            }
+        });
+        let options = PrepareOptions {
+            domain: self.domains_page.read(cx).settings().for_url(&request.url),
+            auth,
+            form_parts,
+            protection,
+            body_serializer: self.body_serializer.clone(),
+        };
+        prepare_request(request, options, cx.global::<PluginRegistry>())
+    }
+
+    // 历史记录和场景中保存的请求：只使用它自己的 headers 和请求体，
+    // 再加上当前的域名默认值和域名认证，不使用编辑器中的设置
+    fn prepare_stored_request(
+        &self,
+        request: &Request,
+        cx: &App,
+    ) -> Result<PreparedRequest, AppError> {
+        let domain = self.domains_page.read(cx).settings().for_url(&request.url);
+        let options = PrepareOptions::stored(request, domain);
+        prepare_request(request, options, cx.global::<PluginRegistry>())
+    }
+
+    // CSV 响应以表格显示
+    fn show_csv_grid(&mut self, headers: &[(String, String)], body: &str, cx: &mut Context<Self>) {
+        self.show_grid = header_value(headers, "Content-Type").is_some_and(is_csv_content_type);
+        if self.show_grid {
+            self.csv_grid.update(cx, |grid, cx| grid.set_body(body, cx));
+        }
+    }
+
+    // 二进制响应（MessagePack / CBOR / Protobuf 等）由响应渲染插件解码为 JSON
+    fn render_response_body(&mut self, result: &RequestResult, cx: &App) -> Option<String> {
+        let content_type = header_value(&result.headers, "Content-Type")?;
+        let renderer = cx
+            .global::<PluginRegistry>()
+            .response_renderer(content_type)?;
+        match renderer.render(&result.raw_body) {
+            Ok(text) => {
+                tracing::info!("🧩 PostmanApp - 响应体已由 {} 解码", renderer.name());
+                Some(text)
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - {} 解码失败: {e}", renderer.name());
+                self.notice = Some(format!("{} decoding failed: {e}", renderer.name()));
+                None
+            }
+        }
+    }
+
+    // 加载 Protobuf descriptor set，之后的 Protobuf 响应按所选消息类型解码
+    fn load_proto_descriptor(&mut self, cx: &mut Context<Self>) {
+        let path = self
+            .proto_descriptor_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        let message = self
+            .proto_message_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        if path.is_empty() || message.is_empty() {
+            self.notice = Some("Enter a descriptor set path and a message type".to_string());
+            cx.notify();
+            return;
+        }
+
+        let renderer = std::fs::read(&path)
+            .map_err(AppError::from)
+            .and_then(|data| DescriptorPool::from_descriptor_set(&data))
+            .and_then(|pool| ProtobufRenderer::with_schema(pool, &message));
+        match renderer {
+            Ok(renderer) => {
+                let full = renderer.message().unwrap_or_default().to_string();
+                tracing::info!("📄 PostmanApp - 已加载 Protobuf descriptor: {path} ({full})");
+                cx.global_mut::<PluginRegistry>()
+                    .register_response_renderer(renderer);
+                self.notice = Some(format!("Protobuf responses decode as {full}"));
+                self.proto_message = Some(full);
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 加载 Protobuf descriptor 失败: {e}");
+                self.notice = Some(format!("Failed to load descriptor: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // 恢复无 schema 的 Protobuf 解码（字段以编号显示）
+    fn clear_proto_descriptor(&mut self, cx: &mut Context<Self>) {
+        cx.global_mut::<PluginRegistry>()
+            .register_response_renderer(ProtobufRenderer::raw());
+        self.proto_message = None;
+        cx.notify();
+    }
+
+    fn select_auth_provider(&mut self, name: Option<String>, cx: &mut Context<Self>) {
+        let hint = name
+            .as_deref()
+            .and_then(|name| cx.global::<PluginRegistry>().auth_provider(name))
+            .map(|provider| provider.credential_hint().to_string())
+            .unwrap_or_else(|| "Credential".to_string());
+        self.auth_credential_input.update(cx, |input, cx| {
+            input.set_placeholder(hint, cx);
+        });
+        self.auth_provider = name;
+        cx.notify();
+    }
+
+    fn select_body_serializer(&mut self, name: Option<String>, cx: &mut Context<Self>) {
+        self.body_serializer = name;
+        cx.notify();
+    }
+
+    // 处理 Send 按钮点击
+    fn on_send_clicked(
+        &mut self,
+        _event: &gpui::MouseUpEvent,
+        _window: &mut gpui::Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.count_usage(Feature::RequestSent, cx);
+        self.send_request(cx);
+    }
+
+    // 添加header
+    fn add_header(&mut self, cx: &mut Context<Self>) {
+        let key = self
+            .header_key_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        let value = self
+            .header_value_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+
+        tracing::info!("🎯 PostmanApp - 尝试添加header:");
+        tracing::info!("   Key: '{key}'");
+        tracing::info!("   Value: '{value}'");
+
+        if !key.is_empty() && !value.is_empty() {
+            // 检查是否已存在相同的key
+            let existing_index = self.headers.iter().position(|(_, k, _)| k == &key);
+
+            if let Some(index) = existing_index {
+                let old_value = self.headers[index].2.clone(); // 克隆旧值避免借用冲突
+                self.headers[index].2 = value.clone();
+                tracing::info!("🔄 PostmanApp - 更新已存在的header:");
+                tracing::info!("   Key: {key}");
+                tracing::info!("   旧值: {old_value}");
+                tracing::info!("   新值: {value}");
+            } else {
+                self.headers.push((true, key.clone(), value.clone())); // enabled by default
+                tracing::info!("✅ PostmanApp - 成功添加新header:");
+                tracing::info!("   Key: {key}");
+                tracing::info!("   Value: {value}");
+                tracing::info!("   当前headers总数: {}", self.headers.len());
+            }
+
+            // 清空输入框
+            self.header_key_input
+                .update(cx, |input, cx| input.clear(cx));
+            self.header_value_input
+                .update(cx, |input, cx| input.clear(cx));
+
+            // 打印当前所有headers
+            tracing::info!("📋 PostmanApp - 当前所有headers:");
+            for (i, (enabled, k, v)) in self.headers.iter().enumerate() {
+                tracing::info!(
+                    "   {}. [{}] {} = {}",
+                    i + 1,
+                    if *enabled { "✓" } else { " " },
+                    k,
+                    v
+                );
+            }
+
+            cx.notify();
+        } else {
+            tracing::info!("⚠️ PostmanApp - 添加header失败:");
+            if key.is_empty() {
+                tracing::info!("   原因: Header key不能为空");
+            }
+            if value.is_empty() {
+                tracing::info!("   原因: Header value不能为空");
+            }
+            tracing::info!("   请确保key和value都有内容");
+        }
+    }
+
+    // 通过输入框设置header值
+    fn set_header_input_values(&mut self, key: &str, value: &str, cx: &mut Context<Self>) {
+        tracing::info!("🎯 PostmanApp - 预设header到输入框:");
+        tracing::info!("   预设Key: {key}");
+        tracing::info!("   预设Value: {value}");
+
+        self.header_key_input.update(cx, |input, cx| {
+            input.set_content(key.to_string(), cx);
+        });
+        self.header_value_input.update(cx, |input, cx| {
+            input.set_content(value.to_string(), cx);
+        });
+        tracing::info!("✅ PostmanApp - 预设header已填入输入框，请点击Add按钮添加");
+    }
+
+    // 设置 header（已存在同名 header 时更新并启用）
+    fn upsert_header(&mut self, key: &str, value: String) {
+        if let Some(header) = self
+            .headers
+            .iter_mut()
+            .find(|(_, k, _)| k.eq_ignore_ascii_case(key))
+        {
+            header.0 = true;
+            header.2 = value;
+        } else {
+            self.headers.push((true, key.to_string(), value));
+        }
+    }
+
+    fn toggle_stream_response(&mut self, cx: &mut Context<Self>) {
+        self.stream_response = !self.stream_response;
+        tracing::info!("🌊 PostmanApp - 流式响应: {}", self.stream_response);
+        cx.notify();
+    }
+
+    // 切换 Range 构建器的模式（起止字节 / 末尾字节数）
+    fn toggle_range_suffix(&mut self, cx: &mut Context<Self>) {
+        self.range_suffix = !self.range_suffix;
+        let placeholder = if self.range_suffix {
+            "Last N bytes"
+        } else {
+            "End byte"
+        };
+        self.range_end_input.update(cx, |input, cx| {
+            input.set_placeholder(placeholder, cx);
+        });
+        cx.notify();
+    }
+
+    // 根据 Range 构建器的输入设置 Range header
+    fn apply_range_header(&mut self, cx: &mut Context<Self>) {
+        let start = self.range_start_input.read(cx).get_content().to_string();
+        let end = self.range_end_input.read(cx).get_content().to_string();
+
+        match ByteRange::from_inputs(&start, &end, self.range_suffix) {
+            Ok(range) => {
+                let value = range.header_value();
+                tracing::info!("📐 PostmanApp - 设置 Range header: {value}");
+                self.upsert_header("Range", value);
+                self.notice = None;
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - Range 无效: {e}");
+                self.notice = Some(e.to_string());
+            }
+        }
+        cx.notify();
+    }
+
+    // 添加条件请求 header（值来自同一 URL 上次响应的 ETag / Last-Modified）
+    fn apply_conditional_header(
+        &mut self,
+        header: ConditionalHeader,
+        value: String,
+        cx: &mut Context<Self>,
+    ) {
+        tracing::info!(
+            "🔒 PostmanApp - 设置条件请求 header: {} = {value}",
+            header.name()
+        );
+        self.upsert_header(header.name(), value);
+        cx.notify();
+    }
+
+    // 删除header
+    fn remove_header(&mut self, index: usize, cx: &mut Context<Self>) {
+        tracing::info!("🗑️ PostmanApp - 尝试删除header，索引: {index}");
+
+        if index < self.headers.len() {
+            let removed = self.headers.remove(index);
+            self.focused_header = None;
+            tracing::info!("✅ PostmanApp - 成功删除header:");
+            tracing::info!("   Enabled: {}", removed.0);
+            tracing::info!("   Key: {}", removed.1);
+            tracing::info!("   Value: {}", removed.2);
+            tracing::info!("   剩余headers数量: {}", self.headers.len());
+
+            // 打印剩余的headers
+            if self.headers.is_empty() {
+                tracing::info!("📋 PostmanApp - 当前无headers");
+            } else {
+                tracing::info!("📋 PostmanApp - 剩余headers:");
+                for (i, (enabled, k, v)) in self.headers.iter().enumerate() {
+                    tracing::info!(
+                        "   {}. [{}] {} = {}",
+                        i + 1,
+                        if *enabled { "✓" } else { " " },
+                        k,
+                        v
+                    );
+                }
+            }
+
+            cx.notify();
+        } else {
+            tracing::info!("❌ PostmanApp - 删除header失败:");
+            tracing::info!(
+                "   原因: 索引 {} 超出范围 (当前headers数量: {})",
+                index,
+                self.headers.len()
+            );
+        }
+    }
+
+    // Toggle header enabled state
+    fn toggle_header(&mut self, index: usize, cx: &mut Context<Self>) {
+        tracing::info!("🔄 PostmanApp - 切换header状态，索引: {index}");
+        if index < self.headers.len() {
+            let current_state = self.headers[index].0;
+            self.headers[index].0 = !current_state;
+            tracing::info!("✅ PostmanApp - 成功切换header状态:");
+            tracing::info!("   Key: {}", self.headers[index].1);
+            tracing::info!("   从 {} 切换到 {}", current_state, !current_state);
+
+            cx.notify();
+        } else {
+            tracing::info!("❌ PostmanApp - 切换header失败:");
+            tracing::info!(
+                "   原因: 索引 {} 超出范围 (当前headers数量: {})",
+                index,
+                self.headers.len()
+            );
+        }
+    }
+
+    // 拖动调整 header 顺序
+    fn move_header(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        if move_item(&mut self.headers, from, to) {
+            tracing::info!("↕️ PostmanApp - 移动 header: {from} -> {to}");
+            if self.focused_header == Some(from) {
+                self.focused_header = Some(to);
+            }
+            cx.notify();
+        }
+    }
+
+    // 启用或禁用所有 headers
+    fn set_all_headers_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        tracing::info!(
+            "🔄 PostmanApp - {}所有 headers",
+            if enabled { "启用" } else { "禁用" }
+        );
+        for header in &mut self.headers {
+            header.0 = enabled;
+        }
+        cx.notify();
+    }
+
+    // 快捷键切换当前选中的 header
+    fn toggle_focused_header(
+        &mut self,
+        _: &ToggleRow,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if let Some(index) = self.focused_header {
+            self.toggle_header(index, cx);
+        }
+    }
+
+    // 选中 header 行
+    fn focus_header(&mut self, index: usize, window: &mut Window, cx: &mut Context<Self>) {
+        window.focus(&self.headers_focus);
+        self.focused_header = Some(index);
+        cx.notify();
+    }
+
+    // Handle history item selection
+    fn on_history_selected(
+        &mut self,
+        _history_list: gpui::Entity<HistoryList>,
+        event: &HistoryListEvent,
+        cx: &mut Context<Self>,
+    ) {
+        match event {
+            HistoryListEvent::RequestSelected(request) => {
+                tracing::info!("📋 PostmanApp - 从历史记录加载请求:");
+                tracing::info!("   Method: {}", request.method);
+                tracing::info!("   URL: {}", request.url);
+                tracing::info!("   Headers Count: {}", request.headers.len());
+
+                // Log query parameters if present in URL
+                if request.url.contains('?') {
+                    if let Some(query_str) = request.url.split('?').nth(1) {
+                        tracing::info!("   Query parameters: {}", query_str);
+                    }
+                }
+
+                // Log body info
+                if let Some(ref body) = request.body {
+                    tracing::info!("   Body length: {} bytes", body.len());
+                }
+
+                self.sync_response_annotations(cx);
+                self.load_request(request, cx);
+
+                // 恢复保存的响应及批注
+                let saved = self
+                    .history_list
+                    .read(cx)
+                    .selected_entry(cx)
+                    .and_then(|entry| entry.response.clone().map(|response| (entry.id, response)));
+                self.show_records = false;
+                self.show_grid = false;
+                if let Some((id, response)) = saved {
+                    self.response_entry_id = Some(id);
+                    self.show_records = header_value(&response.headers, "Content-Type")
+                        .is_some_and(is_ndjson_content_type);
+                    if self.show_records {
+                        self.ndjson_viewer
+                            .update(cx, |viewer, cx| viewer.set_body(&response.body, cx));
+                    }
+                    self.show_csv_grid(&response.headers, &response.body, cx);
+                    self.response_viewer.update(cx, |viewer, cx| {
+                        viewer.set_state(
+                            ResponseState::Success {
+                                status: response.status,
+                                headers: response.headers,
+                                body: response.body,
+                            },
+                            cx,
+                        );
+                        viewer.set_annotations(response.annotations, cx);
+                    });
+                }
+
+                tracing::info!("🏁 PostmanApp - 请求从历史记录加载完成");
+                tracing::info!("   URL已加载到URL输入框");
+                tracing::info!("   Headers数量: {}", request.headers.len());
+                if request.body.is_some() {
+                    tracing::info!("   请求体已加载");
+                }
+
+                cx.notify();
+            }
+            HistoryListEvent::ShareRequested(entry) => {
+                self.share_history_entry(entry, cx);
+            }
+            HistoryListEvent::SpecRequested(entry) => {
+                self.export_inferred_spec(entry, cx);
+            }
+        }
+    }
+
+    // 将请求加载到表单中
+    fn load_request(&mut self, request: &Request, cx: &mut Context<Self>) {
+        // Update method selector - normalize method to uppercase
+        let method = request.method;
+        self.method_selector.update(cx, |selector, cx| {
+            selector.set_selected_method(method, cx);
+        });
+
+        // Update URL input
+        self.url_input.update(cx, |input, cx| {
+            input.set_url(&request.url, cx);
+        });
+        let socket = request.socket.clone().unwrap_or_default();
+        self.socket_input.update(cx, |input, cx| {
+            input.set_content(socket, cx);
+        });
+        let certificate = request
+            .client_certificate
+            .clone()
+            .unwrap_or_else(|| DOMAIN_CERTIFICATE.to_string());
+        self.certificate_selector.update(cx, |selector, cx| {
+            selector.set_selected(certificate.clone(), cx);
+            if selector.selected_value() != certificate {
+                selector.set_selected(DOMAIN_CERTIFICATE, cx);
+            }
+        });
+        let assertions = request.assertions.clone();
+        self.snippets_page
+            .update(cx, |page, cx| page.set_attached(assertions, cx));
+        // 带 hook 的请求总是展开 hook 区域，避免命令在不知情时执行
+        let (pre, post) = (request.hooks.pre.clone(), request.hooks.post.clone());
+        for (hook, command_input, variable_input) in [
+            (pre, &self.pre_hook_input, &self.pre_hook_variable_input),
+            (post, &self.post_hook_input, &self.post_hook_variable_input),
+        ] {
+            let (command, variable) = hook
+                .map(|hook| (hook.command, hook.variable.unwrap_or_default()))
+                .unwrap_or_default();
+            command_input.update(cx, |input, cx| input.set_content(command, cx));
+            variable_input.update(cx, |input, cx| input.set_content(variable, cx));
+        }
+        if !request.hooks.is_empty() {
+            self.show_hooks = true;
+        }
+
+        // Update headers - convert from Vec<(String, String)> to Vec<(bool, String, String)>
+        self.headers = request
+            .headers
+            .iter()
+            .map(|(key, value)| (true, key.clone(), value.clone()))
+            .collect();
+
+        // Update body
+        if let Some(body) = &request.body {
+            self.body_input.update(cx, |input, cx| {
+                // 检测 body 类型
+                let body_type = Self::detect_body_type(body);
+
+                // 设置 body 类型
+                input.set_type(body_type.clone(), cx);
+
+                // 根据类型设置内容
+                match body_type {
+                    BodyType::FormData => {
+                        // 解析 form data
+                        Self::parse_and_set_form_data(input, body, cx);
+                    }
+                    _ => {
+                        // JSON 或 Raw 直接设置内容
+                        input.set_content(body.clone(), cx);
+                    }
+                }
+            });
+        } else {
+            self.body_input.update(cx, |input, cx| {
+                input.clear(cx);
+            });
+        }
+    }
+
+    // 将响应批注保存到对应的历史记录
+    fn sync_response_annotations(&mut self, cx: &mut Context<Self>) {
+        let Some(id) = self.response_entry_id else {
+            return;
+        };
+        let saved = self
+            .state
+            .read(cx)
+            .history()
+            .find(id)
+            .is_some_and(|entry| entry.response.is_some());
+        if !saved {
+            return;
+        }
+        let annotations = self.response_viewer.read(cx).annotations().to_vec();
+        self.state.update(cx, |state, cx| {
+            state.update_history(cx, |history| {
+                if let Some(response) = history
+                    .find_mut(id)
+                    .and_then(|entry| entry.response.as_mut())
+                {
+                    response.annotations = annotations;
+                }
+            });
+        });
+    }
+
+    // 当前 profile 的存储（已解锁时加密，查看模式下只读）
+    fn active_storage(&self) -> Option<ProfileStorage> {
+        let root = self.data_root.as_deref()?;
+        Some(
+            self.profiles
+                .active()
+                .storage(root)
+                .with_vault(self.vault.clone())
+                .read_only(self.config.read_only),
+        )
+    }
+
+    // 读取 profile 的域名默认值（没有数据目录或读取失败时为空）
+    fn load_domains_from(storage: Option<ProfileStorage>) -> DomainSettings {
+        let Some(storage) = storage else {
+            return DomainSettings::new();
+        };
+        storage.load_domains().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载域名默认值失败: {e}");
+            DomainSettings::new()
+        })
+    }
+
+    // 读取 profile 导入的客户端证书（没有数据目录或读取失败时为空）
+    fn load_certificates_from(storage: Option<ProfileStorage>) -> CertificateStore {
+        let Some(storage) = storage else {
+            return CertificateStore::new();
+        };
+        storage.load_certificates().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载客户端证书失败: {e}");
+            CertificateStore::new()
+        })
+    }
+
+    // 读取 profile 允许执行的 hook 命令（没有数据目录或读取失败时为空）
+    fn load_trusted_hooks_from(storage: Option<ProfileStorage>) -> TrustedHooks {
+        let Some(storage) = storage else {
+            return TrustedHooks::new();
+        };
+        storage.load_trusted_hooks().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载允许的 hook 命令失败: {e}");
+            TrustedHooks::new()
+        })
+    }
+
+    // 读取 profile 的条件格式规则（没有数据目录或读取失败时为空）
+    fn load_format_rules_from(storage: Option<ProfileStorage>) -> FormatRules {
+        let Some(storage) = storage else {
+            return FormatRules::new();
+        };
+        storage.load_format_rules().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载条件格式规则失败: {e}");
+            FormatRules::new()
+        })
+    }
+
+    // 读取 profile 的黄金基线（没有数据目录或读取失败时为空）
+    fn load_baselines_from(storage: Option<ProfileStorage>) -> BaselineStore {
+        let Some(storage) = storage else {
+            return BaselineStore::new();
+        };
+        storage.load_baselines().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载黄金基线失败: {e}");
+            BaselineStore::new()
+        })
+    }
+
+    // 读取 profile 的响应示例（没有数据目录或读取失败时为空）
+    fn load_examples_from(storage: Option<ProfileStorage>) -> ExampleLibrary {
+        let Some(storage) = storage else {
+            return ExampleLibrary::new();
+        };
+        storage.load_examples().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载响应示例失败: {e}");
+            ExampleLibrary::new()
+        })
+    }
+
+    // 读取 profile 的断言片段（没有数据目录或读取失败时为空）
+    fn load_snippets_from(storage: Option<ProfileStorage>) -> SnippetLibrary {
+        let Some(storage) = storage else {
+            return SnippetLibrary::new();
+        };
+        storage.load_snippets().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载断言片段失败: {e}");
+            SnippetLibrary::new()
+        })
+    }
+
+    // 读取 profile 的活动日志（没有数据目录或读取失败时为空）
+    fn load_activity_from(storage: Option<ProfileStorage>) -> ActivityLog {
+        let Some(storage) = storage else {
+            return ActivityLog::new();
+        };
+        storage.load_activity().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载活动日志失败: {e}");
+            ActivityLog::new()
+        })
+    }
+
+    // 重新加载当前 profile 的历史记录、场景、集合、环境、cookies、域名默认值、客户端证书、断言片段和活动日志（解锁或切换 profile 后）
+    fn reload_profile_settings(&mut self, cx: &mut Context<Self>) {
+        let storage = self.active_storage();
+        self.state.update(cx, |state, cx| state.reload(storage, cx));
+        let base_url = self.profiles.active().base_url.clone().unwrap_or_default();
+        self.base_url_input
+            .update(cx, |input, cx| input.set_content(base_url, cx));
+        let history_name = Self::history_name_template_of(&self.profiles).to_string();
+        self.history_name_input
+            .update(cx, |input, cx| input.set_content(history_name, cx));
+        self.activity_log = Self::load_activity_from(self.active_storage());
+        self.usage_stats = Self::load_usage_from(self.active_storage());
+        self.trusted_hooks = Self::load_trusted_hooks_from(self.active_storage());
+        self.pending_hooks = None;
+        self.baselines = Self::load_baselines_from(self.active_storage());
+        self.refresh_baseline_changes(cx);
+        self.examples = Self::load_examples_from(self.active_storage());
+        self.selected_scenario = None;
+        self.scenario_run = None;
+        self.scenario_task = None;
+        self.selected_collection = None;
+        self.selected_environment = None;
+        self.refresh_mock_routes();
+        self.format_rules = Self::load_format_rules_from(self.active_storage());
+        self.format_rules_error = None;
+        let script = self.format_rules.script.clone();
+        self.format_rules_input
+            .update(cx, |input, cx| input.set_content(script, cx));
+        let snippets = Self::load_snippets_from(self.active_storage());
+        self.snippets_page
+            .update(cx, |page, cx| page.set_library(snippets, cx));
+        let domains = Self::load_domains_from(self.active_storage());
+        self.domains_page
+            .update(cx, |page, cx| page.set_settings(domains, cx));
+        let certificates = Self::load_certificates_from(self.active_storage());
+        self.certificates_page
+            .update(cx, |page, cx| page.set_store(certificates, cx));
+        self.refresh_certificate_choices(cx);
+    }
+
+    // 证书列表变化后更新请求和域名设置中的证书下拉框
+    fn refresh_certificate_choices(&mut self, cx: &mut Context<Self>) {
+        let names = self.certificates_page.read(cx).store().names();
+        let options = std::iter::once(DOMAIN_CERTIFICATE.to_string())
+            .chain(names.clone())
+            .collect();
+        self.certificate_selector
+            .update(cx, |selector, cx| selector.set_options(options, cx));
+        self.domains_page
+            .update(cx, |page, cx| page.set_certificate_names(names, cx));
+    }
+
+    // 查看模式下拒绝修改数据的操作并提示 - 界面隐藏了编辑按钮，但快捷键、
+    // 组件事件等其他入口也会调用这些操作
+    fn refuse_in_viewer_mode(&mut self, cx: &mut Context<Self>) -> bool {
+        if !self.config.read_only {
+            return false;
+        }
+        self.notice = Some("Viewer mode - this workspace is read-only".to_string());
+        cx.notify();
+        true
+    }
+
+    // 记录一次结构性操作并保存当前 profile 的活动日志
+    fn record_activity(
+        &mut self,
+        kind: ActivityKind,
+        detail: impl Into<String>,
+        cx: &mut Context<Self>,
+    ) {
+        self.activity_log.record(kind, detail);
+        self.mark_unsaved(SavedFile::Activity, cx);
+    }
+
+    // 读取 profile 的使用统计（没有数据目录或读取失败时从现在开始计数）
+    fn load_usage_from(storage: Option<ProfileStorage>) -> UsageStats {
+        let Some(storage) = storage else {
+            return UsageStats::new();
+        };
+        storage.load_usage().unwrap_or_else(|e| {
+            tracing::info!("❌ PostmanApp - 加载使用统计失败: {e}");
+            UsageStats::new()
+        })
+    }
+
+    // 记一次功能使用并保存（只保存在本机，不会发送到任何地方）
+    fn count_usage(&mut self, feature: Feature, cx: &mut Context<Self>) {
+        self.usage_stats.record(feature);
+        self.mark_unsaved(SavedFile::Usage, cx);
+    }
+
+    // 标记文件有未保存的修改 - 修改停止 AUTOSAVE_DELAY 后一次写入
+    fn mark_unsaved(&mut self, file: SavedFile, cx: &mut Context<Self>) {
+        self.unsaved.insert(file);
+        self.autosave
+            .schedule(cx, |this, cx| this.flush_unsaved(cx));
+    }
+
+    // 立即写入未保存的修改（自动保存、切换 profile、更改加密和退出时）
+    fn flush_unsaved(&mut self, cx: &mut Context<Self>) {
+        self.autosave.cancel();
+        let unsaved = std::mem::take(&mut self.unsaved);
+        if unsaved.contains(&SavedFile::Settings) {
+            self.save_settings();
+        }
+        if unsaved.contains(&SavedFile::Profiles) {
+            self.save_profiles();
+        }
+        // 锁定时不写入，避免覆盖加密的数据
+        if self.locked {
+            return;
+        }
+        let Some(storage) = self.active_storage() else {
+            return;
+        };
+        for file in unsaved {
+            let result = match file {
+                SavedFile::Settings | SavedFile::Profiles => continue,
+                SavedFile::Activity => storage.save_activity(&self.activity_log),
+                SavedFile::Usage => storage.save_usage(&self.usage_stats),
+                SavedFile::TrustedHooks => storage.save_trusted_hooks(&self.trusted_hooks),
+                SavedFile::Baselines => storage.save_baselines(&self.baselines),
+                SavedFile::Examples => storage.save_examples(&self.examples),
+                SavedFile::FormatRules => storage.save_format_rules(&self.format_rules),
+                SavedFile::Domains => storage.save_domains(self.domains_page.read(cx).settings()),
+                SavedFile::Certificates => {
+                    storage.save_certificates(self.certificates_page.read(cx).store())
+                }
+                SavedFile::Snippets => storage.save_snippets(self.snippets_page.read(cx).library()),
+            };
+            if let Err(e) = result {
+                tracing::info!("❌ PostmanApp - 保存 {} 失败: {e}", file.label());
+                self.notice = Some(format!("Failed to save the {}: {e}", file.label()));
+                cx.notify();
+            }
+        }
+    }
+
+    // 保存开关设置（查看模式下不写入数据目录）
+    fn save_settings(&self) {
+        let Some(root) = &self.data_root else {
+            return;
+        };
+        if self.config.read_only {
+            return;
+        }
+        if let Err(e) = self.config.save(root) {
+            tracing::info!("❌ PostmanApp - 保存设置失败: {e}");
+        }
+    }
+
+    // 用户点击导出时才把使用统计写成 JSON 文件
+    fn export_usage(&mut self, cx: &mut Context<Self>) {
+        let dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+        let path = dir.join(format!(
+            "usage-{}-{}.json",
+            self.profiles.active().dir_name,
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let result = serde_json::to_string_pretty(&self.usage_stats)
+            .map_err(|e| AppError::ParseError(e.to_string()))
+            .and_then(|json| std::fs::write(&path, json).map_err(AppError::from));
+        match result {
+            Ok(()) => {
+                tracing::info!("📤 PostmanApp - 使用统计已导出: {}", path.display());
+                self.notice = Some(format!("Usage statistics saved to {}", path.display()));
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 导出使用统计失败: {e}");
+                self.notice = Some(format!("Could not export the usage statistics: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // 清零使用统计，从现在重新计数
+    fn reset_usage(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        self.usage_stats.reset();
+        self.mark_unsaved(SavedFile::Usage, cx);
+        cx.notify();
+    }
+
+    // 当前筛选下的活动记录（开启时只显示昨天 0 点以来的记录）
+    fn visible_activity(&self) -> Vec<&ActivityEntry> {
+        if !self.activity_since_yesterday {
+            return self.activity_log.entries().iter().collect();
+        }
+        let cutoff = Local::now()
+            .date_naive()
+            .pred_opt()
+            .and_then(|yesterday| yesterday.and_hms_opt(0, 0, 0))
+            .and_then(|start| start.and_local_timezone(Local).earliest())
+            .map_or_else(
+                || Utc::now() - chrono::Duration::days(1),
+                |start| start.with_timezone(&Utc),
+            );
+        self.activity_log.since(cutoff).collect()
+    }
+
+    // 将当前筛选下的活动日志导出为文本文件
+    fn export_activity(&mut self, cx: &mut Context<Self>) {
+        let text = ActivityLog::to_text(self.visible_activity());
+        let dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+        let path = dir.join(format!(
+            "activity-{}-{}.txt",
+            self.profiles.active().dir_name,
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        match std::fs::write(&path, text) {
+            Ok(()) => {
+                tracing::info!("📤 PostmanApp - 活动日志已导出: {}", path.display());
+                self.notice = Some(format!("Activity log saved to {}", path.display()));
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 导出活动日志失败: {e}");
+                self.notice = Some(format!("Could not export the activity log: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // 批量 GET 粘贴的 URL 列表（运行中再次点击则停止）
+    fn run_bulk_urls(&mut self, cx: &mut Context<Self>) {
+        if self.bulk_task.take().is_some() {
+            tracing::info!("🛑 PostmanApp - 已停止批量请求");
+            cx.notify();
+            return;
+        }
+        let urls = parse_url_list(&self.bulk_input.read(cx).get_content());
+        if urls.is_empty() {
+            self.notice = Some("Paste the URLs to request, one per line".to_string());
+            cx.notify();
+            return;
+        }
+        tracing::info!("🚦 PostmanApp - 开始批量请求 {} 个 URL", urls.len());
+        self.count_usage(Feature::BulkRun, cx);
+        self.bulk_outcomes = vec![None; urls.len()];
+        self.bulk_urls = urls.clone();
+        let mut outcomes = run_bulk(urls, BULK_CONCURRENCY);
+        self.bulk_task = Some(cx.spawn(async move |this, cx| {
+            while let Some((index, outcome)) = outcomes.recv().await {
+                let updated = this.update(cx, |this, cx| {
+                    if let Some(slot) = this.bulk_outcomes.get_mut(index) {
+                        *slot = Some(outcome);
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            let _ = this.update(cx, |this, cx| {
+                tracing::info!("🏁 PostmanApp - 批量请求完成");
+                this.bulk_task = None;
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
+
+    // 用输入的名称新建场景并选中
+    fn add_scenario(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let name = self.scenario_name_input.read(cx).get_content().to_string();
+        let added = self.state.update(cx, |state, cx| {
+            state.update_scenarios(cx, |scenarios| {
+                scenarios
+                    .add(&name)
+                    .map(|index| (index, scenarios.scenarios()[index].name.clone()))
+            })
+        });
+        match added {
+            Ok((index, name)) => {
+                tracing::info!("🎬 PostmanApp - 新建场景: {name}");
+                self.scenario_name_input
+                    .update(cx, |input, cx| input.clear(cx));
+                self.select_scenario(index, cx);
+                self.record_activity(
+                    ActivityKind::ScenariosChanged,
+                    format!("Created {name}"),
+                    cx,
+                );
+            }
+            Err(e) => self.notice = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn select_scenario(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.selected_scenario != Some(index) {
+            self.selected_scenario = Some(index);
+            self.scenario_run = None;
+            self.scenario_task = None;
+        }
+        cx.notify();
+    }
+
+    fn delete_scenario(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(index) = self.selected_scenario.take() else {
+            return;
+        };
+        let Some(scenario) = self.state.update(cx, |state, cx| {
+            state.update_scenarios(cx, |scenarios| scenarios.remove(index))
+        }) else {
+            return;
+        };
+        tracing::info!("🗑️ PostmanApp - 删除场景: {}", scenario.name);
+        self.scenario_run = None;
+        self.scenario_task = None;
+        self.record_activity(
+            ActivityKind::ScenariosChanged,
+            format!("Deleted {}", scenario.name),
+            cx,
+        );
+        cx.notify();
+    }
+
+    // 把编辑器中的请求加到选中场景的某个阶段末尾
+    fn add_to_scenario(&mut self, stage: Stage, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let request = self.snapshot_active_tab(cx).to_request();
+        if request.url.trim().is_empty() {
+            self.notice = Some("Enter the request's URL first".to_string());
+            cx.notify();
+            return;
+        }
+        let Some(index) = self.selected_scenario else {
+            return;
+        };
+        let Some(detail) = self.state.update(cx, |state, cx| {
+            state.update_scenarios(cx, |scenarios| {
+                let scenario = scenarios.get_mut(index)?;
+                let detail = format!(
+                    "{} {} added to {} of {}",
+                    request.method,
+                    request.url,
+                    stage.label(),
+                    scenario.name
+                );
+                scenario.add(stage, request);
+                Some(detail)
+            })
+        }) else {
+            return;
+        };
+        tracing::info!("🎬 PostmanApp - {detail}");
+        self.record_activity(ActivityKind::ScenariosChanged, detail, cx);
+        cx.notify();
+    }
+
+    fn remove_from_scenario(&mut self, stage: Stage, index: usize, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(selected) = self.selected_scenario else {
+            return;
+        };
+        let Some(detail) = self.state.update(cx, |state, cx| {
+            state.update_scenarios(cx, |scenarios| {
+                let scenario = scenarios.get_mut(selected)?;
+                let request = scenario.remove(stage, index)?;
+                Some(format!(
+                    "{} {} removed from {} of {}",
+                    request.method,
+                    request.url,
+                    stage.label(),
+                    scenario.name
+                ))
+            })
+        }) else {
+            return;
+        };
+        self.record_activity(ActivityKind::ScenariosChanged, detail, cx);
+        cx.notify();
+    }
+
+    // 用输入的名称新建集合并选中
+    fn add_collection(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let name = self
+            .collection_name_input
+            .read(cx)
+            .get_content()
+            .to_string();
+        let added = self.state.update(cx, |state, cx| {
+            state.update_collections(cx, |collections| {
+                collections
+                    .add(&name)
+                    .map(|index| (index, collections.collections()[index].name.clone()))
+            })
+        });
+        match added {
+            Ok((index, name)) => {
+                tracing::info!("📚 PostmanApp - 新建集合: {name}");
+                self.collection_name_input
+                    .update(cx, |input, cx| input.clear(cx));
+                self.selected_collection = Some(index);
+                self.record_activity(
+                    ActivityKind::CollectionsChanged,
+                    format!("Created {name}"),
+                    cx,
+                );
+            }
+            Err(e) => self.notice = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn delete_collection(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(index) = self.selected_collection.take() else {
+            return;
+        };
+        let Some(collection) = self.state.update(cx, |state, cx| {
+            state.update_collections(cx, |collections| collections.remove(index))
+        }) else {
+            return;
+        };
+        tracing::info!("🗑️ PostmanApp - 删除集合: {}", collection.name);
+        self.record_activity(
+            ActivityKind::CollectionsChanged,
+            format!("Deleted {}", collection.name),
+            cx,
+        );
+        cx.notify();
+    }
+
+    // 把编辑器中的请求加到选中的集合末尾
+    fn add_to_collection(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let request = self.snapshot_active_tab(cx).to_request();
+        if request.url.trim().is_empty() {
+            self.notice = Some("Enter the request's URL first".to_string());
+            cx.notify();
+            return;
+        }
+        let Some(index) = self.selected_collection else {
+            return;
+        };
+        let Some(detail) = self.state.update(cx, |state, cx| {
+            state.update_collections(cx, |collections| {
+                let collection = collections.get_mut(index)?;
+                let detail = format!(
+                    "{} {} added to {}",
+                    request.method, request.url, collection.name
+                );
+                collection.add_request(request);
+                Some(detail)
+            })
+        }) else {
+            return;
+        };
+        tracing::info!("📚 PostmanApp - {detail}");
+        self.record_activity(ActivityKind::CollectionsChanged, detail, cx);
+        cx.notify();
+    }
+
+    fn remove_from_collection(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(selected) = self.selected_collection else {
+            return;
+        };
+        let Some(detail) = self.state.update(cx, |state, cx| {
+            state.update_collections(cx, |collections| {
+                let collection = collections.get_mut(selected)?;
+                let request = collection.get_request(index)?.clone();
+                collection.remove_request(index);
+                Some(format!(
+                    "{} {} removed from {}",
+                    request.method, request.url, collection.name
+                ))
+            })
+        }) else {
+            return;
+        };
+        self.record_activity(ActivityKind::CollectionsChanged, detail, cx);
+        cx.notify();
+    }
+
+    // 在新标签页中打开集合中的请求
+    fn open_from_collection(&mut self, index: usize, cx: &mut Context<Self>) {
+        let request = self
+            .selected_collection
+            .and_then(|selected| self.state.read(cx).collections().get(selected))
+            .and_then(|collection| collection.get_request(index))
+            .cloned();
+        if let Some(request) = request {
+            self.open_in_new_tab(RequestTab::from_request(&request, None), cx);
+        }
+    }
+
+    // 用输入的名称新建环境并选中
+    fn add_environment(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let name = self
+            .environment_name_input
+            .read(cx)
+            .get_content()
+            .to_string();
+        let added = self.state.update(cx, |state, cx| {
+            state.update_environments(cx, |environments| environments.add(&name))
+        });
+        match added {
+            Ok(index) => {
+                tracing::info!("🌍 PostmanApp - 新建环境: {}", name.trim());
+                self.environment_name_input
+                    .update(cx, |input, cx| input.clear(cx));
+                self.select_environment(index, cx);
+                self.record_activity(
+                    ActivityKind::EnvironmentsChanged,
+                    format!("Created {}", name.trim()),
+                    cx,
+                );
+            }
+            Err(e) => self.notice = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    // 选中环境，在编辑框中显示它的变量
+    fn select_environment(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(text) = self
+            .state
+            .read(cx)
+            .environments()
+            .environments()
+            .get(index)
+            .map(Environment::variables_text)
+        else {
+            return;
+        };
+        self.selected_environment = Some(index);
+        self.environment_variables_input
+            .update(cx, |input, cx| input.set_content(text, cx));
+        cx.notify();
+    }
+
+    // 保存编辑框中的变量（name=value，每行一个）到选中的环境
+    fn save_environment_variables(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(index) = self.selected_environment else {
+            return;
+        };
+        let text = self.environment_variables_input.read(cx).get_content();
+        let saved = self.state.update(cx, |state, cx| {
+            state.update_environments(cx, |environments| {
+                let environment = environments.get_mut(index)?;
+                Some(
+                    environment
+                        .set_variables_from_text(&text)
+                        .map(|()| environment.name.clone()),
+                )
+            })
+        });
+        match saved {
+            Some(Ok(name)) => {
+                self.notice = Some(format!("Saved the variables of {name}"));
+                self.record_activity(
+                    ActivityKind::EnvironmentsChanged,
+                    format!("Changed the variables of {name}"),
+                    cx,
+                );
+            }
+            Some(Err(e)) => self.notice = Some(e.to_string()),
+            None => {}
+        }
+        cx.notify();
+    }
+
+    // 使用选中的环境（None 表示不使用环境）
+    fn activate_environment(&mut self, index: Option<usize>, cx: &mut Context<Self>) {
+        let name = self.state.update(cx, |state, cx| {
+            state.update_environments(cx, |environments| {
+                environments.set_active(index);
+                environments
+                    .active()
+                    .map(|environment| environment.name.clone())
+            })
+        });
+        let detail = match name {
+            Some(name) => format!("Using {name}"),
+            None => "No environment".to_string(),
+        };
+        tracing::info!("🌍 PostmanApp - {detail}");
+        self.record_activity(ActivityKind::EnvironmentsChanged, detail, cx);
+        cx.notify();
+    }
+
+    fn delete_environment(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(index) = self.selected_environment.take() else {
+            return;
+        };
+        let Some(environment) = self.state.update(cx, |state, cx| {
+            state.update_environments(cx, |environments| environments.remove(index))
+        }) else {
+            return;
+        };
+        tracing::info!("🗑️ PostmanApp - 删除环境: {}", environment.name);
+        self.environment_variables_input
+            .update(cx, |input, cx| input.set_content("", cx));
+        self.record_activity(
+            ActivityKind::EnvironmentsChanged,
+            format!("Deleted {}", environment.name),
+            cx,
+        );
+        cx.notify();
+    }
+
+    // 删除当前 profile 保存的所有 cookies
+    fn clear_cookies(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        self.state.update(cx, |state, cx| {
+            state.update_cookies(cx, |cookies| {
+                let changed = !cookies.is_empty();
+                cookies.clear();
+                changed
+            })
+        });
+        tracing::info!("🍪 PostmanApp - 已清除 cookies");
+        cx.notify();
+    }
+
+    // 运行选中的场景（再次点击停止）- 请求在发送前应用域名默认值、认证和请求体插件，
+    // hook 需要已被信任
+    fn run_selected_scenario(&mut self, cx: &mut Context<Self>) {
+        if self.scenario_task.take().is_some() {
+            tracing::info!("🛑 PostmanApp - 已停止场景");
+            cx.notify();
+            return;
+        }
+        let Some(scenario) = self
+            .selected_scenario
+            .and_then(|index| self.state.read(cx).scenarios().get(index))
+            .cloned()
+        else {
+            return;
+        };
+        if scenario.is_empty() {
+            self.notice = Some("Add requests to the scenario first".to_string());
+            cx.notify();
+            return;
+        }
+        let mut untrusted: Vec<String> = Vec::new();
+        for (_, request) in scenario.steps() {
+            for command in self.trusted_hooks.untrusted(&request.hooks) {
+                if !untrusted.contains(&command) {
+                    untrusted.push(command);
+                }
+            }
+        }
+        if !untrusted.is_empty() {
+            self.notice = Some(format!(
+                "Send the requests with these hooks once and trust them first: {}",
+                untrusted.join(", ")
+            ));
+            cx.notify();
+            return;
+        }
+
+        let snippets = self.snippets_page.read(cx).library().clone();
+        let mut steps = Vec::new();
+        for (stage, request) in scenario.steps() {
+            let resolved = self.resolve_variables(request, cx);
+            let prepared =
+                self.prepare_stored_request(&resolved, cx)
+                    .and_then(|(headers, body)| {
+                        let (executor, socket) = self.stored_transport(&resolved, cx)?;
+                        Ok((headers, body, executor, socket))
+                    });
+            let (headers, body, executor, socket) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    self.notice = Some(format!("{} {}: {e}", request.method, request.url));
+                    cx.notify();
+                    return;
+                }
+            };
+            steps.push(PreparedStep {
+                stage,
+                method: resolved.method,
+                url: resolved.url,
+                headers,
+                body,
+                executor,
+                socket,
+                hooks: request.hooks.clone(),
+                scripts: request
+                    .assertions
+                    .iter()
+                    .filter_map(|id| snippets.get(*id))
+                    .map(|snippet| snippet.script.clone())
+                    .collect(),
+            });
+        }
+
+        tracing::info!(
+            "🎬 PostmanApp - 运行场景 {}（{} 个请求）",
+            scenario.name,
+            steps.len()
+        );
+        self.count_usage(Feature::ScenarioRun, cx);
+        self.scenario_run = Some(ScenarioRun {
+            scenario: scenario.name.clone(),
+            steps: scenario
+                .steps()
+                .into_iter()
+                .map(|(stage, request)| (stage, format!("{} {}", request.method, request.url)))
+                .collect(),
+            outcomes: vec![None; steps.len()],
+        });
+        let mut outcomes = run_scenario(steps);
+        self.scenario_task = Some(cx.spawn(async move |this, cx| {
+            while let Some((index, outcome)) = outcomes.recv().await {
+                let updated = this.update(cx, |this, cx| {
+                    if let Some(slot) = this
+                        .scenario_run
+                        .as_mut()
+                        .and_then(|run| run.outcomes.get_mut(index))
+                    {
+                        *slot = Some(outcome);
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            let _ = this.update(cx, |this, cx| {
+                if let Some(run) = &this.scenario_run {
+                    tracing::info!(
+                        "🏁 PostmanApp - 场景 {}: {}",
+                        run.scenario,
+                        run_summary(&run.outcomes)
+                    );
+                }
+                this.scenario_task = None;
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
+
+    // 重放时间窗口内的历史请求：使用记录的 headers 和请求体，加上当前的域名默认值、
+    // 域名认证、传输设置和变量（运行中再次点击则停止）
+    fn replay_history(&mut self, cx: &mut Context<Self>) {
+        if self.replay_task.take().is_some() {
+            tracing::info!("🛑 PostmanApp - 已停止会话重放");
+            cx.notify();
+            return;
+        }
+        let entries: Vec<HistoryEntry> = match self.replay_bounds(cx) {
+            Ok((from, to)) => self
+                .state
+                .read(cx)
+                .history()
+                .sent_between(from, to)
+                .into_iter()
+                .cloned()
+                .collect(),
+            Err(e) => {
+                self.notice = Some(e.to_string());
+                cx.notify();
+                return;
+            }
+        };
+        if entries.is_empty() {
+            self.notice = Some("No requests in history were sent in that time window".to_string());
+            cx.notify();
+            return;
+        }
+
+        let mut steps = Vec::new();
+        for entry in &entries {
+            let resolved = self.resolve_variables(&entry.request, cx);
+            let prepared =
+                self.prepare_stored_request(&resolved, cx)
+                    .and_then(|(headers, body)| {
+                        let (executor, socket) = self.stored_transport(&resolved, cx)?;
+                        Ok((headers, body, executor, socket))
+                    });
+            let (headers, body, executor, socket) = match prepared {
+                Ok(prepared) => prepared,
+                Err(e) => {
+                    self.notice = Some(format!(
+                        "{} {}: {e}",
+                        entry.request.method, entry.request.url
+                    ));
+                    cx.notify();
+                    return;
+                }
+            };
+            steps.push(ReplayStep {
+                method: resolved.method,
+                url: resolved.url,
+                headers,
+                body,
+                executor,
+                socket,
+            });
+        }
+
+        tracing::info!("⏪ PostmanApp - 重放 {} 个历史请求", steps.len());
+        self.count_usage(Feature::SessionReplay, cx);
+        self.replay_run = Some(ReplayRun {
+            requests: entries
+                .iter()
+                .map(|entry| format!("{} {}", entry.request.method, entry.request.url))
+                .collect(),
+            recorded: entries
+                .iter()
+                .map(|entry| entry.response.as_ref().map(|response| response.status))
+                .collect(),
+            outcomes: vec![None; steps.len()],
+        });
+        let mut outcomes = run_replay(steps);
+        self.replay_task = Some(cx.spawn(async move |this, cx| {
+            while let Some((index, outcome)) = outcomes.recv().await {
+                let updated = this.update(cx, |this, cx| {
+                    if let Some(slot) = this
+                        .replay_run
+                        .as_mut()
+                        .and_then(|run| run.outcomes.get_mut(index))
+                    {
+                        *slot = Some(outcome);
+                    }
+                    cx.notify();
+                });
+                if updated.is_err() {
+                    return;
+                }
+            }
+            let _ = this.update(cx, |this, cx| {
+                if let Some(run) = &this.replay_run {
+                    tracing::info!(
+                        "🏁 PostmanApp - 会话重放: {}",
+                        replay_summary(&run.recorded, &run.outcomes)
+                    );
+                }
+                this.replay_task = None;
+                cx.notify();
+            });
+        }));
+        cx.notify();
+    }
+
+    // 重放时间窗口的起止时间（按本地时间输入，留空表示不限）
+    fn replay_bounds(
+        &self,
+        cx: &App,
+    ) -> Result<(Option<DateTime<Utc>>, Option<DateTime<Utc>>), AppError> {
+        let now = Local::now();
+        let from = parse_time_bound(self.replay_from_input.read(cx).get_content(), &now)?;
+        let to = parse_time_bound(self.replay_to_input.read(cx).get_content(), &now)?;
+        Ok((from, to))
+    }
+
+    // 根据发往同一主机的历史请求推断 OpenAPI 骨架并导出
+    fn export_inferred_spec(&mut self, entry: &HistoryEntry, cx: &mut Context<Self>) {
+        let Some(origin) = origin(&entry.request.url) else {
+            self.notice = Some("The request URL has no host to describe".to_string());
+            cx.notify();
+            return;
+        };
+        let spec = infer_spec(origin, self.state.read(cx).history().entries());
+        let host = origin
+            .split_once("://")
+            .map_or(origin, |(_, host)| host)
+            .replace(':', "-");
+        let dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+        let path = dir.join(format!(
+            "openapi-{host}-{}.json",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let result = spec
+            .to_json()
+            .and_then(|json| std::fs::write(&path, json).map_err(AppError::from));
+        match result {
+            Ok(()) => {
+                let count = spec.operation_count();
+                self.count_usage(Feature::SpecInferred, cx);
+                tracing::info!(
+                    "📐 PostmanApp - 已推断 {count} 个操作的 OpenAPI 骨架: {}",
+                    path.display()
+                );
+                self.notice = Some(format!(
+                    "OpenAPI skeleton with {count} {} saved to {}",
+                    if count == 1 {
+                        "operation"
+                    } else {
+                        "operations"
+                    },
+                    path.display()
+                ));
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 导出 OpenAPI 骨架失败: {e}");
+                self.notice = Some(format!("Could not export the OpenAPI skeleton: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // 将显示的响应（状态、headers 和按查看方式格式化后的 body）导出为 PDF
+    fn export_response_pdf(&mut self, cx: &mut Context<Self>) {
+        let viewer = self.response_viewer.read(cx);
+        let (
+            ResponseState::Success {
+                status, headers, ..
+            },
+            Some(body),
+        ) = (viewer.get_state(), viewer.shown_body())
+        else {
+            return;
+        };
+        let mut document = TextDocument::new(self.response_window_title(cx));
+        document.heading("Status");
+        document.text(&format!("{status} {}", reason_phrase(*status)));
+        document.heading("Headers");
+        for (name, value) in headers {
+            document.text(&format!("{name}: {value}"));
+        }
+        document.heading("Body");
+        document.text(body);
+
+        let dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+        let path = dir.join(format!(
+            "response-{status}-{}.pdf",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        let result = document
+            .to_pdf()
+            .and_then(|pdf| std::fs::write(&path, pdf).map_err(AppError::from));
+        match result {
+            Ok(()) => {
+                tracing::info!(
+                    "📄 PostmanApp - 响应已导出为 PDF ({} 页): {}",
+                    document.page_count(),
+                    path.display()
+                );
+                self.notice = Some(format!("Response saved to {}", path.display()));
+                self.count_usage(Feature::PdfExported, cx);
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 导出 PDF 失败: {e}");
+                self.notice = Some(format!("Could not export the response: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // 将批量请求的结果导出为 CSV
+    fn export_bulk_results(&mut self, cx: &mut Context<Self>) {
+        let csv = results_csv(&self.bulk_urls, &self.bulk_outcomes);
+        let dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+        let path = dir.join(format!(
+            "bulk-run-{}.csv",
+            Local::now().format("%Y%m%d-%H%M%S")
+        ));
+        match std::fs::write(&path, csv) {
+            Ok(()) => {
+                tracing::info!("📤 PostmanApp - 批量请求结果已导出: {}", path.display());
+                self.notice = Some(format!("Results saved to {}", path.display()));
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 导出批量请求结果失败: {e}");
+                self.notice = Some(format!("Could not export the results: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // 清空当前 profile 的活动日志
+    fn clear_activity(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        self.activity_log.clear();
+        self.mark_unsaved(SavedFile::Activity, cx);
+        cx.notify();
+    }
+
+    // 输入密码解锁加密的数据
+    fn unlock(&mut self, cx: &mut Context<Self>) {
+        let Some(root) = self.data_root.clone() else {
+            return;
+        };
+        let passphrase = self.passphrase_input.read(cx).get_content().to_string();
+        let result = VaultHeader::load(&root).and_then(|header| match header {
+            Some(header) => Vault::unlock(&passphrase, &header).map(Some),
+            None => Ok(None),
+        });
+
+        match result {
+            Ok(vault) => {
+                tracing::info!("🔓 PostmanApp - 数据已解锁");
+                self.vault = vault;
+                self.locked = false;
+                self.notice = None;
+                self.passphrase_input
+                    .update(cx, |input, cx| input.clear(cx));
+                self.reload_profile_settings(cx);
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 解锁失败: {e}");
+                self.notice = Some(e.to_string());
+            }
+        }
+        cx.notify();
+    }
+
+    // 用输入的密码加密所有 profile 的数据
+    fn enable_encryption(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let Some(root) = self.data_root.clone() else {
+            return;
+        };
+        let passphrase = self.passphrase_input.read(cx).get_content().to_string();
+        // 先写入未保存的修改，再迁移所有 profile 的文件
+        self.flush_unsaved(cx);
+        let result = Vault::create(&passphrase).and_then(|(vault, header)| {
+            header.save(&root)?;
+            for profile in self.profiles.profiles() {
+                let storage = profile.storage(&root).with_vault(Some(vault.clone()));
+                storage.migrate_to(&storage)?;
+            }
+            Ok(vault)
+        });
+
+        match result {
+            Ok(vault) => {
+                tracing::info!("🔒 PostmanApp - 已启用数据加密");
+                self.vault = Some(vault);
+                let storage = self.active_storage();
+                self.state.update(cx, |state, _| state.set_storage(storage));
+                self.record_activity(
+                    ActivityKind::EncryptionChanged,
+                    "Enabled encryption at rest",
+                    cx,
+                );
+                self.passphrase_input
+                    .update(cx, |input, cx| input.clear(cx));
+                self.notice = Some(
+                    "Saved data is now encrypted - the passphrase is asked for at startup"
+                        .to_string(),
+                );
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 启用加密失败: {e}");
+                self.notice = Some(e.to_string());
+            }
+        }
+        cx.notify();
+    }
+
+    // 解密所有 profile 的数据并关闭加密
+    fn disable_encryption(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let (Some(root), Some(vault)) = (self.data_root.clone(), self.vault.clone()) else {
+            return;
+        };
+        self.flush_unsaved(cx);
+        let result = self
+            .profiles
+            .profiles()
+            .iter()
+            .try_for_each(|profile| {
+                let plain = profile.storage(&root);
+                plain
+                    .clone()
+                    .with_vault(Some(vault.clone()))
+                    .migrate_to(&plain)
+            })
+            .and_then(|_| VaultHeader::remove(&root));
+
+        match result {
+            Ok(()) => {
+                tracing::info!("🔓 PostmanApp - 已关闭数据加密");
+                self.vault = None;
+                let storage = self.active_storage();
+                self.state.update(cx, |state, _| state.set_storage(storage));
+                self.record_activity(
+                    ActivityKind::EncryptionChanged,
+                    "Disabled encryption at rest",
+                    cx,
+                );
+                self.notice = Some("Saved data is no longer encrypted".to_string());
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 关闭加密失败: {e}");
+                self.notice = Some(e.to_string());
+            }
+        }
+        cx.notify();
+    }
+
+    fn save_profiles(&self) {
+        let Some(root) = &self.data_root else {
+            return;
+        };
+        // 查看模式下不写入数据目录
+        if self.config.read_only {
+            return;
+        }
+        if let Err(e) = self.profiles.save(root) {
+            tracing::info!("❌ PostmanApp - 保存 profiles 失败: {e}");
+        }
+    }
+
+    // 切换 profile - 保存当前数据，加载新 profile 的数据并重置标签页
+    fn switch_profile(&mut self, index: usize, cx: &mut Context<Self>) {
+        if self.locked {
+            return;
+        }
+        self.sync_response_annotations(cx);
+        let previous = self.profiles.active().name.clone();
+        // 未保存的修改先写入当前 profile
+        self.flush_unsaved(cx);
+        if !self.profiles.switch_to(index) {
+            return;
+        }
+        self.mark_unsaved(SavedFile::Profiles, cx);
+        tracing::info!(
+            "👤 PostmanApp - 切换到 profile: {}",
+            self.profiles.active().name
+        );
+
+        self.reload_profile_settings(cx);
+        self.record_activity(
+            ActivityKind::ProfileSwitched,
+            format!("From {previous}"),
+            cx,
+        );
+        self.response_entry_id = None;
+        self.tabs = vec![RequestTab::new()];
+        self.active_tab = 0;
+        self.compare_tab = None;
+        self.load_tab(RequestTab::new(), cx);
+        self.notice = Some(format!(
+            "Switched to profile {}",
+            self.profiles.active().name
+        ));
+        cx.notify();
+    }
+
+    /// 切换到下一个 profile（应用菜单）
+    pub fn switch_to_next_profile(&mut self, cx: &mut Context<Self>) {
+        self.switch_profile(self.profiles.next_index(), cx);
+    }
+
+    // 保存当前 profile 的 base URL（留空则不再检查）
+    fn save_base_url(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let url = self.base_url_input.read(cx).get_content().to_string();
+        let index = self.profiles.active_index();
+        match self.profiles.set_base_url(index, &url) {
+            Ok(()) => {
+                let profile = self.profiles.active();
+                tracing::info!(
+                    "🩺 PostmanApp - profile {} 的 base URL: {:?}",
+                    profile.name,
+                    profile.base_url
+                );
+                self.profile_health.remove(&profile.dir_name);
+                self.mark_unsaved(SavedFile::Profiles, cx);
+                if self.config.health_checks {
+                    self.start_health_checks(cx);
+                }
+            }
+            Err(e) => self.notice = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    // 当前 profile 的历史记录命名模板
+    fn history_name_template_of(profiles: &ProfileRegistry) -> &str {
+        profiles
+            .active()
+            .history_name_template
+            .as_deref()
+            .unwrap_or(DEFAULT_HISTORY_NAME_TEMPLATE)
+    }
+
+    // 保存当前 profile 的历史记录命名模板（留空则恢复默认）
+    fn save_history_name_template(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let template = self.history_name_input.read(cx).get_content().to_string();
+        let index = self.profiles.active_index();
+        match self.profiles.set_history_name_template(index, &template) {
+            Ok(()) => {
+                let template = Self::history_name_template_of(&self.profiles).to_string();
+                tracing::info!("🏷️ PostmanApp - 历史记录命名模板: {template}");
+                self.history_name_input
+                    .update(cx, |input, cx| input.set_content(template, cx));
+                self.mark_unsaved(SavedFile::Profiles, cx);
+                self.notice = Some("New history entries will use this name".to_string());
+            }
+            Err(e) => self.notice = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    // 开关后台健康检查
+    fn toggle_health_checks(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        self.config.health_checks = !self.config.health_checks;
+        tracing::info!(
+            "🩺 PostmanApp - 健康检查: {}",
+            if self.config.health_checks {
+                "开启"
+            } else {
+                "关闭"
+            }
+        );
+        if self.config.health_checks {
+            self.start_health_checks(cx);
+        } else {
+            self.health_task = None;
+            self.profile_health.clear();
+        }
+        self.mark_unsaved(SavedFile::Settings, cx);
+        cx.notify();
+    }
+
+    // 每隔 HEALTH_CHECK_INTERVAL 在后台检查所有设置了 base URL 的 profile
+    fn start_health_checks(&mut self, cx: &mut Context<Self>) {
+        self.health_task = Some(cx.spawn(async move |this, cx| loop {
+            let Ok(targets) = this.update(cx, |this, _cx| {
+                this.profiles
+                    .profiles()
+                    .iter()
+                    .filter_map(|profile| {
+                        Some((profile.dir_name.clone(), profile.base_url.clone()?))
+                    })
+                    .collect::<Vec<_>>()
+            }) else {
+                break;
+            };
+            let results = cx
+                .background_executor()
+                .spawn(async move {
+                    targets
+                        .into_iter()
+                        .map(|(dir_name, url)| (dir_name, check_health(&url, HEALTH_CHECK_TIMEOUT)))
+                        .collect::<HashMap<_, _>>()
+                })
+                .await;
+            let result = this.update(cx, |this, cx| {
+                this.profile_health = results;
+                cx.notify();
+            });
+            if result.is_err() {
+                break;
+            }
+            cx.background_executor().timer(HEALTH_CHECK_INTERVAL).await;
+        }));
+    }
+
+    // 根据输入框创建新 profile 并切换过去
+    fn add_profile(&mut self, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        let name = self.new_profile_input.read(cx).get_content().to_string();
+        match self.profiles.add(&name) {
+            Ok(index) => {
+                tracing::info!("👤 PostmanApp - 新建 profile: {}", name.trim());
+                self.record_activity(ActivityKind::ProfileCreated, name.trim(), cx);
+                self.new_profile_input
+                    .update(cx, |input, cx| input.clear(cx));
+                self.switch_profile(index, cx);
+            }
+            Err(e) => {
+                self.notice = Some(e.to_string());
+                cx.notify();
+            }
+        }
+    }
+
+    // 获取当前表单的快照（活动标签页）
+    fn snapshot_active_tab(&self, cx: &mut Context<Self>) -> RequestTab {
+        let body = {
+            let body_input = self.body_input.read(cx);
+            if body_input.is_empty() {
+                None
+            } else {
+                Some(body_input.get_content())
+            }
+        };
+
+        RequestTab {
+            name: self.tabs[self.active_tab].name.clone(),
+            method: self.method_selector.read(cx).current_method(cx),
+            url: self.url_input.read(cx).get_url().to_string(),
+            headers: self.headers.clone(),
+            body,
+            socket: Some(self.socket_input.read(cx).get_content().trim().to_string())
+                .filter(|socket| !socket.is_empty()),
+            client_certificate: self.selected_certificate(cx),
+            assertions: self.snippets_page.read(cx).attached(),
+            hooks: self.current_hooks(cx).unwrap_or_default(),
+            response: self.response_viewer.read(cx).get_state().clone(),
+            annotations: self.response_viewer.read(cx).annotations().to_vec(),
+            history_id: self.response_entry_id,
+            highlights: self.highlights.clone(),
+            example: self.shown_example.clone(),
+        }
+    }
+
+    // 将标签页快照加载到表单中
+    fn load_tab(&mut self, tab: RequestTab, cx: &mut Context<Self>) {
+        self.load_request(&tab.to_request(), cx);
+        // 保留禁用的 headers
+        self.headers = tab.headers;
+        self.response_entry_id = tab.history_id;
+        // 诊断和分页只针对当前标签页刚收到的响应
+        self.next_page = None;
+        self.pages_task = None;
+        self.failed_url = None;
+        self.diagnostics = None;
+        self.diagnostics_task = None;
+        self.response_viewer.update(cx, |viewer, cx| {
+            viewer.set_state(tab.response, cx);
+            viewer.set_annotations(tab.annotations, cx);
+        });
+        self.baseline_request = Some((tab.method, tab.url.clone()));
+        self.refresh_baseline_changes(cx);
+        // 响应时间不随标签页保存，`latency` 规则只在收到响应时检查
+        self.highlights = tab.highlights;
+        self.shown_example = tab.example;
+        self.response_latency = None;
+    }
+
+    // 在新标签页中打开
+    fn open_in_new_tab(&mut self, tab: RequestTab, cx: &mut Context<Self>) {
+        self.sync_response_annotations(cx);
+        self.tabs[self.active_tab] = self.snapshot_active_tab(cx);
+        self.tabs.push(tab.clone());
+        self.active_tab = self.tabs.len() - 1;
+        tracing::info!(
+            "🗂️ PostmanApp - 打开新标签页: {} (共 {} 个)",
+            tab.title(),
+            self.tabs.len()
+        );
+        self.load_tab(tab, cx);
+        cx.notify();
+    }
+
+    // 发送请求，同时在当前标签页之后打开一份副本，方便逐个修改参数；
+    // 响应仍显示在当前标签页
+    pub fn send_and_duplicate(&mut self, cx: &mut Context<Self>) {
+        let copy = self.snapshot_active_tab(cx).duplicate();
+        self.count_usage(Feature::SendAndDuplicate, cx);
+        self.send_request(cx);
+        self.tabs.insert(self.active_tab + 1, copy);
+        if let Some(compare) = self
+            .compare_tab
+            .filter(|compare| *compare > self.active_tab)
+        {
+            self.compare_tab = Some(compare + 1);
+        }
+        tracing::info!(
+            "🗂️ PostmanApp - 发送并复制标签页: {} (共 {} 个)",
+            self.tabs[self.active_tab + 1].title(),
+            self.tabs.len()
+        );
+        self.notice = Some("Sent - the copy is in the next tab".to_string());
+        cx.notify();
+    }
+
+    // 按响应中的链接新建 GET 标签页，相对链接以当前 URL 为基准，同源时沿用认证 headers
+    fn on_response_viewer_event(
+        &mut self,
+        _viewer: Entity<ResponseViewer>,
+        event: &ResponseViewerEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let ResponseViewerEvent::OpenLink(link) = event;
+        let current = self.snapshot_active_tab(cx);
+        let Some(url) = link.resolve(&current.url) else {
+            self.notice = Some(
+                "Links in a response are followed relative to an absolute request URL".to_string(),
+            );
+            cx.notify();
+            return;
+        };
+        tracing::info!("🔗 PostmanApp - 跟随响应中的链接: {url}");
+        self.open_in_new_tab(current.follow_link(url.clone()), cx);
+        self.notice = Some(format!("Opened {url} in a new tab"));
+        cx.notify();
+    }
+
+    // 切换标签页
+    fn switch_tab(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+        self.sync_response_annotations(cx);
+        self.tabs[self.active_tab] = self.snapshot_active_tab(cx);
+        // 切换到对比中的标签页时两侧互换
+        if self.compare_tab == Some(index) {
+            self.compare_tab = Some(self.active_tab);
+        }
+        self.active_tab = index;
+        tracing::info!("🗂️ PostmanApp - 切换到标签页 {index}");
+        self.load_tab(self.tabs[index].clone(), cx);
+        cx.notify();
+    }
+
+    // 关闭标签页
+    fn close_tab(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index >= self.tabs.len() {
+            return;
+        }
+        tracing::info!("🗂️ PostmanApp - 关闭标签页 {index}");
+        self.sync_response_annotations(cx);
+        self.compare_tab = self
+            .compare_tab
+            .and_then(|compare| index_after_close(compare, index));
+
+        if self.tabs.len() == 1 {
+            // 最后一个标签页 - 重置为空白标签页
+            self.tabs[0] = RequestTab::new();
+            self.load_tab(RequestTab::new(), cx);
+        } else if index == self.active_tab {
+            self.tabs.remove(index);
+            self.active_tab = index.min(self.tabs.len() - 1);
+            self.load_tab(self.tabs[self.active_tab].clone(), cx);
+        } else {
+            self.tabs.remove(index);
+            if index < self.active_tab {
+                self.active_tab -= 1;
+            }
+        }
+        if self.compare_tab == Some(self.active_tab) {
+            self.compare_tab = None;
+        }
+        cx.notify();
+    }
+
+    // 将标签页固定在活动标签页旁边对比，再次点击取消
+    fn toggle_compare_tab(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index == self.active_tab || index >= self.tabs.len() {
+            return;
+        }
+        if self.compare_tab == Some(index) {
+            self.compare_tab = None;
+        } else {
+            tracing::info!("🪟 PostmanApp - 分屏对比标签页 {index}");
+            self.compare_tab = Some(index);
+            self.count_usage(Feature::TabCompared, cx);
+        }
+        cx.notify();
+    }
+
+    /// The view showing the response body (records, grid or text), or `None`
+    /// while a raw, gRPC or GraphQL log view replaces it
+    pub fn response_body_view(&self) -> Option<AnyView> {
+        if self.raw_mode || self.grpc_mode || (self.graphql_mode && self.show_graphql_log) {
+            None
+        } else if self.show_records {
+            Some(self.ndjson_viewer.clone().into())
+        } else if self.show_grid {
+            Some(self.csv_grid.clone().into())
+        } else {
+            Some(self.response_viewer.clone().into())
+        }
+    }
+
+    /// Method and URL of the active tab, shown above the popped-out response
+    pub fn response_window_title(&self, cx: &App) -> String {
+        let (method, url) = self.example_request(cx);
+        if url.is_empty() {
+            format!("{method} (no URL)")
+        } else {
+            format!("{method} {url}")
+        }
+    }
+
+    // 把响应面板放到单独的窗口中（再次点击放回主窗口）
+    fn toggle_response_window(&mut self, cx: &mut Context<Self>) {
+        if let Some(window) = self.response_window.take() {
+            window
+                .update(cx, |_view, window, _cx| window.remove_window())
+                .ok();
+            cx.notify();
+            return;
+        }
+
+        let app = cx.entity();
+        let options = WindowOptions {
+            window_bounds: Some(WindowBounds::Windowed(Bounds::centered(
+                None,
+                size(px(900.), px(1000.)),
+                cx,
+            ))),
+            titlebar: Some(TitlebarOptions {
+                title: Some("Response".into()),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        match cx.open_window(options, |_window, cx| {
+            cx.new(|cx| ResponseWindow::new(app, cx))
+        }) {
+            Ok(window) => {
+                tracing::info!("🪟 PostmanApp - 响应面板已移到单独窗口");
+                self.response_window = Some(window);
+                self.count_usage(Feature::ResponsePoppedOut, cx);
+            }
+            Err(e) => {
+                tracing::error!("❌ PostmanApp - 打开响应窗口失败: {e}");
+                self.notice = Some(format!("Could not open the response window: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    /// The popped-out response window was closed; show the response in the
+    /// main window again
+    pub fn on_response_window_closed(&mut self, cx: &mut Context<Self>) {
+        if self.response_window.take().is_some() {
+            tracing::info!("🪟 PostmanApp - 响应窗口已关闭，放回主窗口");
+        }
+        cx.notify();
+    }
+
+    // 在新标签页中打开命令行预填的请求
+    pub fn open_request(&mut self, request: Request, cx: &mut Context<Self>) {
+        tracing::info!(
+            "🖥️ PostmanApp - 打开命令行请求: {} {}",
+            request.method,
+            request.url
+        );
+        let tab = RequestTab::from_request(&request, None);
+        self.open_in_new_tab(tab, cx);
+        cx.notify();
+    }
+
+    // 打开 .pgreq 文件（拖放或系统文件关联）
+    pub fn open_request_files(&mut self, paths: &[PathBuf], cx: &mut Context<Self>) {
+        for path in paths {
+            if !RequestFile::is_request_file(path) {
+                tracing::info!("⚠️ PostmanApp - 忽略非 .pgreq 文件: {}", path.display());
+                continue;
+            }
+            match RequestFile::load(path) {
+                Ok(file) => {
+                    tracing::info!("📂 PostmanApp - 打开请求文件: {}", path.display());
+                    let imported = self.examples.import(&file.request);
+                    if imported > 0 {
+                        tracing::info!("📎 PostmanApp - 导入了 {imported} 个响应示例");
+                        self.mark_unsaved(SavedFile::Examples, cx);
+                        self.refresh_mock_routes();
+                    }
+                    let tab = RequestTab::from_request(&file.request, Some(file.name))
+                        .with_response(file.response);
+                    self.open_in_new_tab(tab, cx);
+                    self.notice = Some(format!("Opened {}", path.display()));
+                    self.record_activity(
+                        ActivityKind::RequestOpened,
+                        path.display().to_string(),
+                        cx,
+                    );
+                }
+                Err(e) => {
+                    tracing::info!("❌ PostmanApp - 打开请求文件失败: {e}");
+                    self.notice = Some(format!("Could not open {}: {e}", path.display()));
+                }
+            }
+        }
+        cx.notify();
+    }
+
+    // 将历史记录导出为 .pgreq 文件
+    fn share_history_entry(&mut self, entry: &HistoryEntry, cx: &mut Context<Self>) {
+        // 导出时包含最新的响应批注
+        self.sync_response_annotations(cx);
+        let response = self
+            .state
+            .read(cx)
+            .history()
+            .find(entry.id)
+            .and_then(|entry| entry.response.clone());
+        // 请求保存过的响应示例随文件一起导出
+        let mut request = entry.request.clone();
+        self.examples.attach(&mut request);
+        let file = RequestFile::new(entry.name.clone(), request).with_response(response);
+        let dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+
+        // 填写了接收者时用 age / GPG 加密导出的文件
+        let recipients = self.share_recipients_input.read(cx).get_content();
+        let result = ExportRecipients::parse(recipients).and_then(|recipients| match recipients {
+            Some(recipients) => {
+                let path = dir.join(format!(
+                    "{}.{}",
+                    file.file_name(),
+                    recipients.cipher.extension()
+                ));
+                file.save_encrypted(&path, &recipients)?;
+                tracing::info!(
+                    "🔐 PostmanApp - 已用 {} 为 {} 个接收者加密",
+                    recipients.cipher.program(),
+                    recipients.recipients.len()
+                );
+                Ok(path)
+            }
+            None => {
+                let path = dir.join(file.file_name());
+                file.save(&path)?;
+                Ok(path)
+            }
+        });
+
+        match result {
+            Ok(path) => {
+                tracing::info!("📤 PostmanApp - 请求已导出: {}", path.display());
+                self.notice = Some(format!("Shared request saved to {}", path.display()));
+                self.record_activity(ActivityKind::RequestShared, path.display().to_string(), cx);
+                self.count_usage(Feature::RequestShared, cx);
+            }
+            Err(e) => {
+                tracing::info!("❌ PostmanApp - 导出请求失败: {e}");
+                self.notice = Some(format!("Could not share request: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    // 开关剪贴板监听
+    fn toggle_clipboard_watch(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        if self.refuse_in_viewer_mode(cx) {
+            return;
+        }
+        self.config.watch_clipboard = !self.config.watch_clipboard;
+        tracing::info!(
+            "📋 PostmanApp - 剪贴板监听: {}",
+            if self.config.watch_clipboard {
+                "开启"
+            } else {
+                "关闭"
+            }
+        );
+        self.mark_unsaved(SavedFile::Settings, cx);
+
+        if !self.config.watch_clipboard {
+            self.clipboard_watch = None;
+            self.clipboard_offer = None;
+            cx.notify();
+            return;
+        }
+        self.start_clipboard_watch(window, cx);
+    }
+
+    // 开始监听剪贴板 - 已在剪贴板中的内容不提示，只提示之后复制的内容
+    fn start_clipboard_watch(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.last_clipboard_text = cx.read_from_clipboard().and_then(|item| item.text());
+        self.clipboard_watch = Some(cx.spawn_in(window, async move |this, cx| loop {
+            cx.background_executor()
+                .timer(CLIPBOARD_POLL_INTERVAL)
+                .await;
+            let result = this.update_in(cx, |this, window, cx| {
+                // 只在窗口获得焦点时检查
+                if window.is_window_active() {
+                    this.check_clipboard(cx);
+                }
+            });
+            if result.is_err() {
+                break;
+            }
+        }));
+        cx.notify();
+    }
+
+    // 检查剪贴板中是否有可导入的 curl 命令或 URL
+    fn check_clipboard(&mut self, cx: &mut Context<Self>) {
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+        if self.last_clipboard_text.as_deref() == Some(text.as_str()) {
+            return;
+        }
+
+        if let Some(offer) = detect_import(&text) {
+            tracing::info!("📋 PostmanApp - 剪贴板中发现可导入内容: {}", offer.label());
+            self.clipboard_offer = Some(offer);
+            cx.notify();
+        }
+        self.last_clipboard_text = Some(text);
+    }
+
+    // 将剪贴板内容导入到新标签页
+    fn import_clipboard_offer(&mut self, cx: &mut Context<Self>) {
+        if let Some(offer) = self.clipboard_offer.take() {
+            let tab = RequestTab::from_request(offer.request(), None);
+            self.open_in_new_tab(tab, cx);
+        }
+    }
+
+    fn render_clipboard_toast(
+        &self,
+        offer: &ClipboardImport,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .absolute()
+            .bottom_4()
+            .right_4()
+            .max_w(px(420.0))
+            .flex()
+            .items_center()
+            .gap_3()
+            .px_4()
+            .py_2()
+            .rounded_md()
+            .shadow_md()
+            .bg(rgb(0x0021_2529))
+            .text_color(rgb(0x00ff_ffff))
+            .text_size(px(12.0))
+            .child(div().flex_1().overflow_hidden().child(offer.label()))
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x0000_7acc))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x0000_56b3)))
+                    .child("Import")
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.import_clipboard_offer(cx);
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .cursor_pointer()
+                    .text_color(rgb(0x00ad_b5bd))
+                    .hover(|style| style.text_color(rgb(0x00ff_ffff)))
+                    .child("✕")
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.clipboard_offer = None;
+                            cx.notify();
+                        }),
+                    ),
+            )
+    }
+
+    // 执行未允许过的 hook 命令前的确认框
+    fn render_hook_confirmation(
+        &self,
+        commands: &[String],
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .absolute()
+            .top_16()
+            .right_4()
+            .max_w(px(480.0))
+            .flex()
+            .flex_col()
+            .gap_2()
+            .px_4()
+            .py_3()
+            .rounded_md()
+            .shadow_md()
+            .bg(rgb(0x0021_2529))
+            .text_color(rgb(0x00ff_ffff))
+            .text_size(px(12.0))
+            .child(
+                div()
+                    .font_weight(FontWeight::MEDIUM)
+                    .child("This request runs commands on your computer"),
+            )
+            .children(commands.iter().map(|command| {
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x0034_3a40))
+                    .font_family("monospace")
+                    .child(command.clone())
+            }))
+            .child(
+                div()
+                    .text_color(rgb(0x00ad_b5bd))
+                    .child("Shared and imported requests can contain hooks. Only allow commands you recognise."),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(self.render_grpc_button(
+                        "Run once",
+                        0x0000_7acc,
+                        0x0000_56b3,
+                        cx,
+                        Self::run_pending_hooks_once,
+                    ))
+                    .child(self.render_grpc_button(
+                        "Always allow",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::trust_pending_hooks,
+                    ))
+                    .child(self.render_grpc_button(
+                        "Cancel",
+                        0x00dc_3545,
+                        0x00c8_2333,
+                        cx,
+                        Self::cancel_pending_hooks,
+                    )),
+            )
+    }
+
+    // Profile 切换栏
+    fn render_profile_switcher(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(div().text_color(rgb(0x006c_757d)).child("Profile:"))
+            .children(
+                self.profiles
+                    .profiles()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, profile)| {
+                        let active = index == self.profiles.active_index();
+                        div()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(rgb(0x00ce_d4da))
+                            .when(active, |chip| {
+                                chip.bg(rgb(0x0000_7acc)).text_color(rgb(0x00ff_ffff))
+                            })
+                            .when(!active, |chip| {
+                                chip.bg(rgb(0x00ff_ffff))
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                            })
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .when(
+                                self.config.health_checks && profile.base_url.is_some(),
+                                |chip| {
+                                    // 绿色可访问，红色不可访问，灰色尚未检查
+                                    let color = match self.profile_health.get(&profile.dir_name) {
+                                        Some(health) if health.is_up() => 0x0028_a745,
+                                        Some(_) => 0x00dc_3545,
+                                        None => 0x00ad_b5bd,
+                                    };
+                                    chip.child(div().w_2().h_2().rounded_full().bg(rgb(color)))
+                                },
+                            )
+                            .child(profile.name.clone())
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    this.switch_profile(index, cx);
+                                }),
+                            )
+                    }),
+            )
+            // 查看模式下只能切换 profile，不能添加或修改
+            .when(!self.config.read_only, |row| {
+                row.child(div().w_32().child(self.new_profile_input.clone()))
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x006c_757d))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x005a_6268)))
+                            .child("+")
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.add_profile(cx);
+                                }),
+                            ),
+                    )
+                    .child(div().w_48().child(self.base_url_input.clone()))
+                    .child(self.render_grpc_button(
+                        "Save",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::save_base_url,
+                    ))
+                    .child(div().w_56().child(self.history_name_input.clone()))
+                    .child(self.render_grpc_button(
+                        "Save",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::save_history_name_template,
+                    ))
+            })
+            .child(self.render_option_toggle(
+                "Health checks",
+                self.config.health_checks,
+                cx,
+                Self::toggle_health_checks,
+            ))
+            .children(
+                self.profile_health
+                    .get(&self.profiles.active().dir_name)
+                    .filter(|_| self.config.health_checks)
+                    .map(|health| {
+                        div()
+                            .text_color(rgb(if health.is_up() {
+                                0x0028_a745
+                            } else {
+                                0x00dc_3545
+                            }))
+                            .child(health.summary())
+                    }),
+            )
+    }
+
+    // 加密设置 - 设置或移除密码
+    fn render_encryption_controls(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let encrypted = self.vault.is_some();
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(div().w_64().child(self.share_recipients_input.clone()))
+            .when(!encrypted, |row| {
+                row.child(div().w_32().child(self.passphrase_input.clone()))
+            })
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .bg(rgb(0x006c_757d))
+                    .text_color(rgb(0x00ff_ffff))
+                    .rounded_md()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x005a_6268)))
+                    .child(if encrypted {
+                        "🔒 Remove encryption"
+                    } else {
+                        "🔒 Encrypt data"
+                    })
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            if encrypted {
+                                this.disable_encryption(cx);
+                            } else {
+                                this.enable_encryption(cx);
+                            }
+                        }),
+                    ),
+            )
+    }
+
+    // 启动时的解锁界面
+    fn render_lock_screen(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .items_center()
+            .justify_center()
+            .gap_3()
+            .size_full()
+            .bg(rgb(0x00f0_f0f0))
+            .child(
+                div()
+                    .child("🔒 Workspace locked")
+                    .text_size(px(20.0))
+                    .font_weight(FontWeight::BOLD),
+            )
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("Enter your passphrase to decrypt your history and saved data"),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .w_96()
+                    .child(self.passphrase_input.clone())
+                    .child(
+                        div()
+                            .px_3()
+                            .py_2()
+                            .bg(rgb(0x0000_7acc))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x0000_56b3)))
+                            .child("Unlock")
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.unlock(cx);
+                                }),
+                            ),
+                    ),
+            )
+            .children(self.notice.clone().map(|notice| {
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x00dc_3545))
+                    .child(notice)
+            }))
+    }
+
+    fn render_tab_strip(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let active_title = RequestTab {
+            name: self.tabs[self.active_tab].name.clone(),
+            method: self.method_selector.read(cx).current_method(cx),
+            url: self.url_input.read(cx).get_url().to_string(),
+            ..RequestTab::new()
+        }
+        .title();
+
+        div()
+            .flex()
+            .gap_1()
+            .border_b_1()
+            .border_color(rgb(0x00cc_cccc))
+            .children(self.tabs.iter().enumerate().map(|(index, tab)| {
+                let is_active = index == self.active_tab;
+                let title = if is_active {
+                    active_title.clone()
+                } else {
+                    tab.title()
+                };
+                let highlights = if is_active {
+                    &self.highlights
+                } else {
+                    &tab.highlights
+                };
+                let badges = highlights
+                    .iter()
+                    .filter(|highlight| highlight.placement == Placement::Badge)
+                    .map(|highlight| {
+                        div()
+                            .px_1()
+                            .rounded_sm()
+                            .bg(rgb(highlight.color.background()))
+                            .text_color(rgb(highlight.color.foreground()))
+                            .text_size(px(10.0))
+                            .child(highlight.text.clone())
+                    });
+
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .px_3()
+                    .py_1()
+                    .rounded_t_md()
+                    .cursor_pointer()
+                    .bg(if is_active {
+                        rgb(0x00ff_ffff)
+                    } else {
+                        rgb(0x00e9_ecef)
+                    })
+                    .hover(|style| style.bg(rgb(0x00ff_ffff)))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .when(is_active, |div| div.font_weight(FontWeight::MEDIUM))
+                            .child(title),
+                    )
+                    .children(badges)
+                    .when(!is_active, |item| {
+                        let compared = self.compare_tab == Some(index);
+                        item.child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(if compared {
+                                    rgb(0x0000_7acc)
+                                } else {
+                                    rgb(0x00ad_b5bd)
+                                })
+                                .hover(|style| style.text_color(rgb(0x0000_7acc)))
+                                .child("⧉")
+                                .on_mouse_up(
+                                    gpui::MouseButton::Left,
+                                    cx.listener(move |this, _event, _window, cx| {
+                                        cx.stop_propagation();
+                                        this.toggle_compare_tab(index, cx);
+                                    }),
+                                ),
+                        )
+                    })
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                            .child("×")
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    cx.stop_propagation();
+                                    this.close_tab(index, cx);
+                                }),
+                            ),
+                    )
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            this.switch_tab(index, cx);
+                        }),
+                    )
+            }))
+            .child(
+                div()
+                    .px_3()
+                    .py_1()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                    .child("+")
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.open_in_new_tab(RequestTab::new(), cx);
+                        }),
+                    ),
+            )
+    }
+
+    // 分屏对比：固定在旁边的标签页只读显示请求和响应，并与活动标签页的响应比较；
+    // Edit & send 切换过去编辑，当前标签页换到这一侧
+    fn render_compare_pane(&self, index: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let tab = &self.tabs[index];
+        let headers = tab
+            .headers
+            .iter()
+            .filter(|(enabled, _, _)| *enabled)
+            .map(|(_, key, value)| div().child(format!("{key}: {value}")));
+        let section = |title: &'static str| {
+            div()
+                .text_size(px(12.0))
+                .font_weight(FontWeight::MEDIUM)
+                .text_color(rgb(0x006c_757d))
+                .child(title)
+        };
+
+        let response = match &tab.response {
+            ResponseState::NotSent => div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x00ad_b5bd))
+                .child("Not sent yet")
+                .into_any_element(),
+            ResponseState::Loading => div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x006c_757d))
+                .child("Sending...")
+                .into_any_element(),
+            ResponseState::Error { message } => div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x00dc_3545))
+                .child(message.clone())
+                .into_any_element(),
+            ResponseState::Success { status, body, .. } => {
+                let status_color = match status {
+                    200..=299 => 0x0028_a745,
+                    400..=599 => 0x00dc_3545,
+                    _ => 0x006c_757d,
+                };
+                let changes = match self.response_viewer.read(cx).get_state() {
+                    ResponseState::Success {
+                        status: active_status,
+                        body: active_body,
+                        ..
+                    } => Some(diff_responses(
+                        *active_status,
+                        active_body,
+                        *status,
+                        body,
+                        &[],
+                    )),
+                    _ => None,
+                };
+                let formatted = format_response_body(body);
+                let line_count = formatted.lines().count();
+
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(13.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(rgb(status_color))
+                            .child(format!("{status} {}", reason_phrase(*status))),
+                    )
+                    .when_some(changes, |pane, changes| {
+                        let (summary, color) = match changes.len() {
+                            0 => ("Same response as the active tab".to_string(), 0x0028_a745),
+                            1 => ("1 difference from the active tab".to_string(), 0x00dc_3545),
+                            n => (format!("{n} differences from the active tab"), 0x00dc_3545),
+                        };
+                        pane.child(
+                            div()
+                                .text_size(px(12.0))
+                                .text_color(rgb(color))
+                                .child(summary),
+                        )
+                        .when(!changes.is_empty(), |pane| {
+                            pane.child(
+                                div()
+                                    .id("compare-changes")
+                                    .max_h(px(160.0))
+                                    .overflow_scroll()
+                                    .p_2()
+                                    .rounded_md()
+                                    .border_1()
+                                    .border_color(rgb(0x00e9_ecef))
+                                    .bg(rgb(0x00f8_f9fa))
+                                    .text_size(px(12.0))
+                                    .font_family("monospace")
+                                    .children(
+                                        changes.iter().map(|change| div().child(change.describe())),
+                                    ),
+                            )
+                        })
+                    })
+                    .child(
+                        div()
+                            .id("compare-body")
+                            .flex_1()
+                            .overflow_scroll()
+                            .p_2()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .text_size(px(12.0))
+                            .font_family("monospace")
+                            .children(
+                                formatted
+                                    .lines()
+                                    .take(MAX_COMPARE_LINES)
+                                    .map(|line| div().child(line.to_string())),
+                            )
+                            .when(line_count > MAX_COMPARE_LINES, |body| {
+                                body.child(div().text_color(rgb(0x00ad_b5bd)).child(format!(
+                                    "... {} more lines",
+                                    line_count - MAX_COMPARE_LINES
+                                )))
+                            }),
+                    )
+                    .into_any_element()
+            }
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .flex_1()
+            .gap_4()
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_1()
+            .border_color(rgb(0x00cc_cccc))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_size(px(14.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(format!("Compare: {}", tab.title())),
+                    )
+                    .child(self.render_grpc_button(
+                        "Edit & send",
+                        0x0000_7acc,
+                        0x0000_56b3,
+                        cx,
+                        move |this, cx| this.switch_tab(index, cx),
+                    ))
+                    .child(self.render_grpc_button(
+                        "Close",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        |this, cx| {
+                            this.compare_tab = None;
+                            cx.notify();
+                        },
+                    )),
+            )
+            .child(
+                div()
+                    .p_2()
+                    .rounded_md()
+                    .bg(rgb(0x00f8_f9fa))
+                    .text_size(px(12.0))
+                    .font_family("monospace")
+                    .child(format!("{} {}", tab.method, tab.url))
+                    .children(headers)
+                    .when_some(tab.body.as_ref(), |request, body| {
+                        request.child(
+                            div().mt_2().text_color(rgb(0x006c_757d)).children(
+                                body.lines()
+                                    .take(MAX_COMPARE_LINES)
+                                    .map(|line| div().child(line.to_string())),
+                            ),
+                        )
+                    }),
+            )
+            .child(section("Response"))
+            .child(response)
+    }
+
+    // Helper function to get checkbox background color
+    fn checkbox_bg_color(enabled: bool) -> u32 {
+        if enabled {
+            COLOR_CHECKBOX_ENABLED_BG
+        } else {
+            COLOR_CHECKBOX_DISABLED_BG
+        }
+    }
+
+    // Helper function to get checkbox hover background color
+    fn checkbox_hover_bg_color(enabled: bool) -> u32 {
+        if enabled {
+            COLOR_CHECKBOX_ENABLED_HOVER
+        } else {
+            COLOR_CHECKBOX_DISABLED_HOVER
+        }
+    }
+
+    // Helper function to get header cell background color
+    fn header_cell_bg_color(enabled: bool) -> u32 {
+        if enabled {
+            COLOR_HEADER_ENABLED_BG
+        } else {
+            COLOR_HEADER_DISABLED_BG
+        }
+    }
+
+    // Helper function to get header cell border color
+    fn header_cell_border_color(enabled: bool) -> u32 {
+        if enabled {
+            COLOR_HEADER_ENABLED_BORDER
+        } else {
+            COLOR_HEADER_DISABLED_BORDER
+        }
+    }
+
+    // Helper function to get header text color
+    fn header_text_color(enabled: bool) -> u32 {
+        if enabled {
+            COLOR_TEXT_ENABLED
+        } else {
+            COLOR_TEXT_DISABLED
+        }
+    }
+
+    // 检测 body 类型
+    fn detect_body_type(body: &str) -> BodyType {
+        // 尝试解析为 JSON
+        if body.trim_start().starts_with('{') || body.trim_start().starts_with('[') {
+            if serde_json::from_str::<serde_json::Value>(body).is_ok() {
+                return BodyType::Json;
+            }
+        }
+
+        // 检测是否是 URL encoded form data (key1=value1&key2=value2 格式)
+        if body.contains('=') && (body.contains('&') || !body.contains('\n')) {
+            // 简单检测：包含 = 且包含 & 或没有换行符
+            return BodyType::FormData;
+        }
+
+        // 默认为 Raw
+        BodyType::Raw
+    }
+
+    // 解析并设置 FormData
+    fn parse_and_set_form_data(input: &mut BodyInput, body: &str, cx: &mut Context<BodyInput>) {
+        use crate::ui::components::body_input::FormDataEntry;
+        use form_urlencoded;
+
+        // 解析 URL encoded form data
+        let parsed = form_urlencoded::parse(body.as_bytes());
+
+        let mut entries: Vec<FormDataEntry> = Vec::new();
+
+        for (key, value) in parsed {
+            entries.push(FormDataEntry::new(key, value));
+        }
+
+        // 如果没有解析到任何条目，至少添加一个空条目
+        if entries.is_empty() {
+            entries.push(FormDataEntry::new("", ""));
+        }
+
+        // 设置 FormData 条目
+        input.set_form_data_entries(entries, cx);
+    }
+
+    // 请求 hook：前置命令的输出可作为 {{变量}}，后置命令从 stdin 读取响应体
+    fn render_hooks_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let hook_row =
+            |label: &'static str, command: &Entity<HeaderInput>, variable: &Entity<HeaderInput>| {
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .w_24()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(label),
+                    )
+                    .child(div().flex_1().child(command.clone()))
+                    .child(div().text_size(px(12.0)).child("→"))
+                    .child(div().w_32().child(variable.clone()))
+            };
+        let mut variables: Vec<&String> = self.hook_variables.keys().collect();
+        variables.sort();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .p_3()
+            .rounded_md()
+            .border_1()
+            .border_color(rgb(0x00e9_ecef))
+            .child(
+                div()
+                    .child("Hooks")
+                    .text_size(px(16.0))
+                    .font_weight(FontWeight::MEDIUM),
+            )
+            .child(hook_row(
+                "Before send",
+                &self.pre_hook_input,
+                &self.pre_hook_variable_input,
+            ))
+            .child(hook_row(
+                "After response",
+                &self.post_hook_input,
+                &self.post_hook_variable_input,
+            ))
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("A command's output is stored in its variable and replaces {{name}} in the URL, headers and body. Commands run in the system shell and must be allowed before their first run."),
+            )
+            .when(!variables.is_empty(), |panel| {
+                panel.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child(format!(
+                            "Variables: {}",
+                            variables
+                                .iter()
+                                .map(|name| format!("{{{{{name}}}}}"))
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                        )),
+                )
+            })
+            .when(!self.trusted_hooks.commands().is_empty(), |panel| {
+                panel
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child("Allowed commands"),
+                    )
+                    .children(self.trusted_hooks.commands().iter().enumerate().map(
+                        |(index, command)| {
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap_2()
+                                .text_size(px(12.0))
+                                .child(
+                                    div()
+                                        .flex_1()
+                                        .overflow_hidden()
+                                        .font_family("monospace")
+                                        .child(command.clone()),
+                                )
+                                .child(
+                                    div()
+                                        .cursor_pointer()
+                                        .text_color(rgb(0x00dc_3545))
+                                        .hover(|style| style.text_color(rgb(0x00c8_2333)))
+                                        .child("✕")
+                                        .on_mouse_up(
+                                            gpui::MouseButton::Left,
+                                            cx.listener(move |this, _event, _window, cx| {
+                                                this.revoke_trusted_hook(index, cx);
+                                            }),
+                                        ),
+                                )
+                        },
+                    ))
+            })
+    }
+
+    // 查看模式下的请求 - 方法、URL、headers 和请求体只显示不能修改，仍然可以发送
+    fn render_request_view(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let method = self.method_selector.read(cx).current_method(cx);
+        let url = self.url_input.read(cx).get_url().to_string();
+        let body = self.body_input.read(cx).get_content();
+        let field = || {
+            div()
+                .px_3()
+                .py_2()
+                .rounded_md()
+                .bg(rgb(0x00f8_f9fa))
+                .border_1()
+                .border_color(rgb(0x00cc_cccc))
+        };
+        let label = |text: String| {
+            div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x006c_757d))
+                .child(text)
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_4()
+                    .child(
+                        field()
+                            .w_32()
+                            .font_weight(FontWeight::BOLD)
+                            .child(method.to_string()),
+                    )
+                    .child(field().flex_1().overflow_hidden().child(url))
+                    .child(
+                        div()
+                            .child("Send")
+                            .bg(rgb(0x0000_7acc))
+                            .text_color(rgb(0x00ff_ffff))
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x0000_56b3)))
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(Self::on_send_clicked),
+                            ),
+                    )
+                    .child(self.render_request_size(cx)),
+            )
+            .child(label(format!(
+                "Headers ({})",
+                self.headers
+                    .iter()
+                    .filter(|(enabled, _, _)| *enabled)
+                    .count()
+            )))
+            .children(self.headers.iter().map(|(enabled, key, value)| {
+                div()
+                    .flex()
+                    .gap_2()
+                    .text_size(px(12.0))
+                    .font_family("monospace")
+                    .text_color(rgb(if *enabled { 0x0021_2529 } else { 0x00ad_b5bd }))
+                    .child(format!("{key}: {value}"))
+                    .when(!enabled, |row| row.child("(off)"))
+            }))
+            .when(!body.is_empty(), |view| {
+                view.child(label("Body".to_string())).child(
+                    field()
+                        .id("viewer-body")
+                        .max_h(px(320.0))
+                        .overflow_scroll()
+                        .text_size(px(12.0))
+                        .font_family("monospace")
+                        .child(body),
+                )
+            })
+    }
+
+    fn render_headers_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let check_state =
+            MasterCheckState::from_flags(self.headers.iter().map(|(enabled, _, _)| *enabled));
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_3()
+            .track_focus(&self.headers_focus)
+            .on_action(cx.listener(Self::toggle_focused_header))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_3()
+                    .child(
+                        div()
+                            .child(format!(
+                                "Headers ({})",
+                                self.headers
+                                    .iter()
+                                    .filter(|(enabled, _, _)| *enabled)
+                                    .count()
+                            ))
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM),
+                    )
+                    .when(check_state != MasterCheckState::Empty, |row| {
+                        row.child(
+                            div()
+                                .flex()
+                                .items_center()
+                                .gap_1()
+                                .cursor_pointer()
+                                .text_size(px(12.0))
+                                .text_color(rgb(COLOR_TEXT_DISABLED))
+                                .child(master_checkbox(check_state))
+                                .child(check_state.action_label())
+                                .on_mouse_up(
+                                    gpui::MouseButton::Left,
+                                    cx.listener(move |this, _event, _window, cx| {
+                                        this.set_all_headers_enabled(
+                                            check_state.toggled_value(),
+                                            cx,
+                                        );
+                                    }),
+                                ),
+                        )
+                    }),
+            )
+            // 现有headers列表
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .children(if self.headers.is_empty() {
+                        vec![div()
+                            .flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .w_8()
+                                    .px_2()
+                                    .py_2()
+                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
+                                    .border_1()
+                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
+                                    .child(""),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .px_3()
+                                    .py_2()
+                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
+                                    .border_1()
+                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
+                                    .child("No headers set"),
+                            )
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .px_3()
+                                    .py_2()
+                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
+                                    .border_1()
+                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
+                                    .child(""),
+                            )
+                            .child(
+                                div()
+                                    .w_16()
+                                    .px_3()
+                                    .py_2()
+                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
+                                    .border_1()
+                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
+                                    .child(""),
+                            )]
+                    } else {
+                        self.headers
+                            .iter()
+                            .enumerate()
+                            .map(|(index, (enabled, key, value))| {
+                                let border_color = if self.focused_header == Some(index) {
+                                    COLOR_HEADER_FOCUSED_BORDER
+                                } else {
+                                    Self::header_cell_border_color(*enabled)
+                                };
+
+                                div()
+                                    .flex()
+                                    .gap_2()
+                                    .items_center()
+                                    // 拖动排序
+                                    .drag_over::<DraggedRow>(|style, dragged, _, _| {
+                                        if dragged.table == RowTable::Headers {
+                                            style.bg(rgb(0x00e7_f1ff))
+                                        } else {
+                                            style
+                                        }
+                                    })
+                                    .on_drop(cx.listener(
+                                        move |this, dragged: &DraggedRow, _window, cx| {
+                                            if dragged.table == RowTable::Headers {
+                                                this.move_header(dragged.index, index, cx);
+                                            }
+                                        },
+                                    ))
+                                    .child(drag_handle().id(("header-drag", index)).on_drag(
+                                        DraggedRow {
+                                            table: RowTable::Headers,
+                                            index,
+                                            label: format!("{key}: {value}").into(),
+                                        },
+                                        |dragged, _offset, _window, cx| cx.new(|_| dragged.clone()),
+                                    ))
+                                    .child(
+                                        // Checkbox column
+                                        div()
+                                            .w_8()
+                                            .h_8()
+                                            .flex()
+                                            .items_center()
+                                            .justify_center()
+                                            .bg(rgb(Self::checkbox_bg_color(*enabled)))
+                                            .border_1()
+                                            .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
+                                            .rounded_sm()
+                                            .cursor_pointer()
+                                            .hover(|style| {
+                                                style.bg(rgb(Self::checkbox_hover_bg_color(
+                                                    *enabled,
+                                                )))
+                                            })
+                                            .child(if *enabled { "✓" } else { "" })
+                                            .text_color(rgb(COLOR_CHECKBOX_TEXT))
+                                            .on_mouse_up(
+                                                gpui::MouseButton::Left,
+                                                cx.listener(move |this, _event, _window, cx| {
+                                                    this.toggle_header(index, cx);
+                                                }),
+                                            ),
+                                    )
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .px_3()
+                                            .py_2()
+                                            .bg(rgb(Self::header_cell_bg_color(*enabled)))
+                                            .border_1()
+                                            .border_color(rgb(border_color))
+                                            .text_color(rgb(Self::header_text_color(*enabled)))
+                                            .cursor_pointer()
+                                            .child(key.clone())
+                                            .on_mouse_up(
+                                                gpui::MouseButton::Left,
+                                                cx.listener(move |this, _event, window, cx| {
+                                                    this.focus_header(index, window, cx);
+                                                }),
+                                            ),
+                                    )
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .px_3()
+                                            .py_2()
+                                            .bg(rgb(Self::header_cell_bg_color(*enabled)))
+                                            .border_1()
+                                            .border_color(rgb(border_color))
+                                            .text_color(rgb(Self::header_text_color(*enabled)))
+                                            .cursor_pointer()
+                                            .child(value.clone())
+                                            .on_mouse_up(
+                                                gpui::MouseButton::Left,
+                                                cx.listener(move |this, _event, window, cx| {
+                                                    this.focus_header(index, window, cx);
+                                                }),
+                                            ),
+                                    )
+                                    .child(
+                                        div()
+                                            .w_16()
+                                            .px_2()
+                                            .py_1()
+                                            .bg(rgb(0x00dc_3545))
+                                            .text_color(rgb(0x00ff_ffff))
+                                            .rounded_md()
+                                            .cursor_pointer()
+                                            .hover(|style| style.bg(rgb(0x00c8_2333)))
+                                            .child("Delete")
+                                            .on_mouse_up(
+                                                gpui::MouseButton::Left,
+                                                cx.listener(move |this, _event, _window, cx| {
+                                                    this.remove_header(index, cx);
+                                                }),
+                                            ),
+                                    )
+                            })
+                            .collect()
+                    }),
+            )
+            // 添加新header的输入框
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        // Empty checkbox column for alignment
+                        div().w_8(),
+                    )
+                    .child(self.header_key_input.clone())
+                    .child(self.header_value_input.clone())
+                    .child(
+                        div()
+                            .w_16()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x0028_a745))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x0021_8838)))
+                            .child("Add")
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.add_header(cx);
+                                }),
+                            ),
+                    ),
+            )
+            // 快速添加预设headers
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child("Quick add: "),
+                    )
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x006c_757d))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x005a_6268)))
+                            .child("JSON")
+                            .text_size(px(12.0))
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.set_header_input_values(
+                                        "Content-Type",
+                                        "application/json",
+                                        cx,
+                                    );
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x006c_757d))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x005a_6268)))
+                            .child("Auth")
+                            .text_size(px(12.0))
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.set_header_input_values("Authorization", "Bearer ", cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x006c_757d))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x005a_6268)))
+                            .child("CORS")
+                            .text_size(px(12.0))
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.set_header_input_values(
+                                        "Access-Control-Allow-Origin",
+                                        "*",
+                                        cx,
+                                    );
+                                }),
+                            ),
+                    ),
+            )
+            .child(self.render_auth_selector(cx))
+            .child(self.render_range_builder(cx))
+            .child(self.render_conditional_builder(cx))
+            // 统计信息
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child(format!(
+                    "Total headers: {} | Enabled: {} | Add headers by typing key and value above",
+                    self.headers.len(),
+                    self.headers
+                        .iter()
+                        .filter(|(enabled, _, _)| *enabled)
+                        .count()
+                )),
+            )
+    }
+
+    // 插件选项按钮 - 选中的高亮显示
+    fn render_plugin_choice(
+        &self,
+        label: String,
+        selected: bool,
+        on_select: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let (bg, hover) = if selected {
+            (COLOR_CHECKBOX_ENABLED_BG, COLOR_CHECKBOX_ENABLED_HOVER)
+        } else {
+            (0x006c_757d, 0x005a_6268)
+        };
+        div()
+            .px_2()
+            .py_1()
+            .bg(rgb(bg))
+            .text_color(rgb(0x00ff_ffff))
+            .rounded_md()
+            .cursor_pointer()
+            .hover(move |style| style.bg(rgb(hover)))
+            .child(label)
+            .text_size(px(12.0))
+            .on_mouse_up(
+                gpui::MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| on_select(this, cx)),
+            )
+    }
+
+    // 认证插件选择
+    fn render_auth_selector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let names: Vec<String> = cx
+            .global::<PluginRegistry>()
+            .auth_providers()
+            .iter()
+            .map(|provider| provider.name().to_string())
+            .collect();
+
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("Auth: "),
+            )
+            .child(self.render_plugin_choice(
+                "None".to_string(),
+                self.auth_provider.is_none(),
+                |this, cx| this.select_auth_provider(None, cx),
+                cx,
+            ))
+            .children(names.into_iter().map(|name| {
+                let selected = self.auth_provider.as_deref() == Some(name.as_str());
+                let choice = name.clone();
+                self.render_plugin_choice(
+                    name,
+                    selected,
+                    move |this, cx| this.select_auth_provider(Some(choice.clone()), cx),
+                    cx,
+                )
+            }))
+            .when(self.auth_provider.is_some(), |row| {
+                row.child(div().w_64().child(self.auth_credential_input.clone()))
+            })
+    }
+
+    // 请求体序列化插件选择
+    fn render_serializer_selector(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let names: Vec<String> = cx
+            .global::<PluginRegistry>()
+            .body_serializers()
+            .iter()
+            .map(|serializer| serializer.name().to_string())
+            .collect();
+
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("Send as: "),
+            )
+            .child(self.render_plugin_choice(
+                "Text".to_string(),
+                self.body_serializer.is_none(),
+                |this, cx| this.select_body_serializer(None, cx),
+                cx,
+            ))
+            .children(names.into_iter().map(|name| {
+                let selected = self.body_serializer.as_deref() == Some(name.as_str());
+                let choice = name.clone();
+                self.render_plugin_choice(
+                    name,
+                    selected,
+                    move |this, cx| this.select_body_serializer(Some(choice.clone()), cx),
+                    cx,
+                )
+            }))
+    }
+
+    // multipart 各部分的预览：缩略图或文本片段、大小和 Content-Type
+    fn render_part_previews(&self) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .text_size(px(12.0))
+            .children(self.part_previews.iter().map(|preview| {
+                let thumbnail = div()
+                    .size(px(40.0))
+                    .flex_none()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .rounded_md()
+                    .bg(rgb(0x00f8_f9fa))
+                    .border_1()
+                    .border_color(rgb(0x00dd_dddd));
+                let thumbnail = match &preview.kind {
+                    PreviewKind::Image(path) => {
+                        thumbnail.child(img(path.clone()).size(px(38.0)).rounded_md())
+                    }
+                    PreviewKind::Text(_) => thumbnail.child("📝"),
+                    PreviewKind::Binary => thumbnail.child("📦"),
+                    PreviewKind::Unreadable(_) => thumbnail.child("⚠️"),
+                };
+                let mut summary = vec![format_size(preview.size), preview.content_type.clone()];
+                if let Some(file_name) = &preview.file_name {
+                    summary.insert(0, file_name.clone());
+                }
+                let detail = match &preview.kind {
+                    PreviewKind::Text(snippet) => Some((snippet.clone(), rgb(0x006c_757d))),
+                    PreviewKind::Unreadable(error) => Some((error.clone(), rgb(0x00dc_3545))),
+                    _ => None,
+                };
+
+                div().flex().items_center().gap_2().child(thumbnail).child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .flex_1()
+                        .overflow_hidden()
+                        .child(
+                            div()
+                                .flex()
+                                .gap_2()
+                                .child(
+                                    div()
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .child(preview.name.clone()),
+                                )
+                                .child(
+                                    div()
+                                        .text_color(rgb(0x006c_757d))
+                                        .child(summary.join(" · ")),
+                                ),
+                        )
+                        .children(detail.map(|(text, color)| {
+                            div().text_size(px(11.0)).text_color(color).child(text)
+                        })),
+                )
+            }))
+    }
+
+    // 请求体签名/加密设置
+    fn render_protection_settings(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("Sign / encrypt: "),
+            )
+            .child(div().w_48().child(self.protection_selector.clone()))
+            .when(self.selected_protection(cx).is_some(), |row| {
+                row.child(div().flex_1().child(self.protection_key_input.clone()))
+                    .child(div().w_40().child(self.protection_key_id_input.clone()))
+            })
+    }
+
+    // Protobuf 响应解码设置
+    fn render_protobuf_settings(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child(match &self.proto_message {
+                        Some(message) => format!("Protobuf: {message}"),
+                        None => "Protobuf: raw fields".to_string(),
+                    }),
+            )
+            .child(div().flex_1().child(self.proto_descriptor_input.clone()))
+            .child(div().w_48().child(self.proto_message_input.clone()))
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .bg(rgb(0x006c_757d))
+                    .text_color(rgb(0x00ff_ffff))
+                    .rounded_md()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x005a_6268)))
+                    .child("Load")
+                    .text_size(px(12.0))
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.load_proto_descriptor(cx);
+                        }),
+                    ),
+            )
+            .when(self.proto_message.is_some(), |row| {
+                row.child(
+                    div()
+                        .px_2()
+                        .py_1()
+                        .bg(rgb(0x00dc_3545))
+                        .text_color(rgb(0x00ff_ffff))
+                        .rounded_md()
+                        .cursor_pointer()
+                        .hover(|style| style.bg(rgb(0x00c8_2333)))
+                        .child("Clear")
+                        .text_size(px(12.0))
+                        .on_mouse_up(
+                            gpui::MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.clear_proto_descriptor(cx);
+                            }),
+                        ),
+                )
+            })
+    }
+
+    // 右侧的 API explorer：spec 导入行和 explorer 本身
+    fn render_api_explorer(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .id("api-explorer")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(380.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().flex_1().child(self.api_spec_input.clone()))
+                    .child(self.render_grpc_button(
+                        "Import",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::import_api_spec,
+                    )),
+            )
+            .child(self.api_explorer.clone())
+    }
+
+    // 域名默认值设置页（右侧栏）
+    fn render_domains(&self) -> impl IntoElement {
+        div()
+            .id("domains")
+            .flex()
+            .flex_col()
+            .w(px(380.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(self.domains_page.clone())
+    }
+
+    // 客户端证书管理（右侧栏）
+    fn render_certificates(&self) -> impl IntoElement {
+        div()
+            .id("certificates")
+            .flex()
+            .flex_col()
+            .w(px(380.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(self.certificates_page.clone())
+    }
+
+    // 断言片段库（右侧栏）
+    fn render_snippets(&self) -> impl IntoElement {
+        div()
+            .id("snippets")
+            .flex()
+            .flex_col()
+            .w(px(380.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(self.snippets_page.clone())
+    }
+
+    // 活动日志（右侧栏）
+    fn render_activity(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self.visible_activity();
+        div()
+            .id("activity")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(380.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .font_weight(FontWeight::BOLD)
+                            .child("Activity"),
+                    )
+                    .child(self.render_option_toggle(
+                        "Since yesterday",
+                        self.activity_since_yesterday,
+                        cx,
+                        |this, cx| {
+                            this.activity_since_yesterday = !this.activity_since_yesterday;
+                            cx.notify();
+                        },
+                    ))
+                    .child(self.render_grpc_button(
+                        "Export",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::export_activity,
+                    ))
+                    .child(self.render_grpc_button(
+                        "Clear",
+                        0x00dc_3545,
+                        0x00c8_2333,
+                        cx,
+                        Self::clear_activity,
+                    )),
+            )
+            .when(entries.is_empty(), |panel| {
+                panel.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child("Nothing recorded yet"),
+                )
+            })
+            .children(entries.into_iter().map(|entry| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(rgb(0x00e9_ecef))
+                    .text_size(px(12.0))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .text_color(rgb(0x006c_757d))
+                                    .child(entry.formatted_time()),
+                            )
+                            .child(
+                                div()
+                                    .font_weight(FontWeight::BOLD)
+                                    .child(entry.kind.label()),
+                            ),
+                    )
+                    .child(div().child(entry.detail.clone()))
+            }))
+    }
+
+    // 使用统计面板：各功能的使用次数，只保存在本机
+    fn render_usage(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let ranked = self.usage_stats.ranked();
+        div()
+            .id("usage")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(320.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().flex_1().font_weight(FontWeight::BOLD).child("Usage"))
+                    .child(self.render_grpc_button(
+                        "Export",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::export_usage,
+                    ))
+                    .child(self.render_grpc_button(
+                        "Reset",
+                        0x00dc_3545,
+                        0x00c8_2333,
+                        cx,
+                        Self::reset_usage,
+                    )),
+            )
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child(format!(
+                        "Counted since {} on this computer only - nothing is sent \
+                         anywhere. Export saves the counts to a file you can share",
+                        self.usage_stats.formatted_since()
+                    )),
+            )
+            .when(ranked.is_empty(), |panel| {
+                panel.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child("Nothing counted yet"),
+                )
+            })
+            .children(ranked.into_iter().map(|(feature, count)| {
+                div()
+                    .flex()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(rgb(0x00e9_ecef))
+                    .text_size(px(12.0))
+                    .child(div().flex_1().child(feature.label()))
+                    .child(
+                        div()
+                            .font_weight(FontWeight::BOLD)
+                            .child(format_count(count as usize)),
+                    )
+            }))
+    }
+
+    // 场景面板 - 场景列表、选中场景的三个阶段及最近一次运行的结果
+    fn render_scenarios(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.scenario_task.is_some();
+        let scenarios = self.state.read(cx).scenarios().clone();
+        let selected = self
+            .selected_scenario
+            .and_then(|index| scenarios.get(index));
+        let note = |text: String| {
+            div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x006c_757d))
+                .child(text)
+        };
+
+        div()
+            .id("scenarios")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(520.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .font_weight(FontWeight::BOLD)
+                            .child("Scenarios"),
+                    )
+                    .when(selected.is_some(), |row| {
+                        row.child(if running {
+                            self.render_grpc_button(
+                                "Stop",
+                                0x00dc_3545,
+                                0x00c8_2333,
+                                cx,
+                                Self::run_selected_scenario,
+                            )
+                        } else {
+                            self.render_grpc_button(
+                                "Run",
+                                0x0000_7acc,
+                                0x0000_56b3,
+                                cx,
+                                Self::run_selected_scenario,
+                            )
+                        })
+                    }),
+            )
+            .child(note(
+                "Setup requests run first, then the main requests, then teardown. Teardown \
+                 always runs, so what setup created is cleaned up even when a request fails; \
+                 hook variables carry values such as a created id to later requests."
+                    .to_string(),
+            ))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().flex_1().child(self.scenario_name_input.clone()))
+                    .child(self.render_grpc_button(
+                        "New",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::add_scenario,
+                    )),
+            )
+            .child(
+                div().flex().flex_wrap().gap_1().children(
+                    scenarios
+                        .scenarios()
+                        .iter()
+                        .enumerate()
+                        .map(|(index, scenario)| {
+                            let active = self.selected_scenario == Some(index);
+                            div()
+                                .px_2()
+                                .py_1()
+                                .rounded_md()
+                                .border_1()
+                                .border_color(rgb(0x00ce_d4da))
+                                .text_size(px(12.0))
+                                .when(active, |chip| {
+                                    chip.bg(rgb(0x0000_7acc)).text_color(rgb(0x00ff_ffff))
+                                })
+                                .when(!active, |chip| {
+                                    chip.bg(rgb(0x00ff_ffff))
+                                        .cursor_pointer()
+                                        .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                                })
+                                .child(format!("{} ({})", scenario.name, scenario.steps().len()))
+                                .on_mouse_up(
+                                    gpui::MouseButton::Left,
+                                    cx.listener(move |this, _event, _window, cx| {
+                                        this.select_scenario(index, cx);
+                                    }),
+                                )
+                        }),
+                ),
+            )
+            .when(scenarios.scenarios().is_empty(), |panel| {
+                panel.child(note(
+                    "No scenarios yet - name one above, then add the request in the editor \
+                     to its stages"
+                        .to_string(),
+                ))
+            })
+            .children(selected.map(|scenario| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .children(Stage::all().into_iter().map(|stage| {
+                        let requests = scenario.requests(stage);
+                        div()
+                            .flex()
+                            .flex_col()
+                            .gap_1()
+                            .child(
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .pt_2()
+                                    .border_b_1()
+                                    .border_color(rgb(0x00e9_ecef))
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .text_size(px(13.0))
+                                            .font_weight(FontWeight::MEDIUM)
+                                            .child(format!(
+                                                "{} ({})",
+                                                stage.label(),
+                                                requests.len()
+                                            )),
+                                    )
+                                    .child(self.render_grpc_button(
+                                        "Add current request",
+                                        0x006c_757d,
+                                        0x005a_6268,
+                                        cx,
+                                        move |this, cx| this.add_to_scenario(stage, cx),
+                                    )),
+                            )
+                            .children(requests.iter().enumerate().map(|(index, request)| {
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .text_size(px(12.0))
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .overflow_hidden()
+                                            .child(format!("{} {}", request.method, request.url)),
+                                    )
+                                    .child(
+                                        div()
+                                            .px_1()
+                                            .text_color(rgb(0x00dc_3545))
+                                            .cursor_pointer()
+                                            .hover(|style| style.bg(rgb(0x00f8_d7da)))
+                                            .rounded_sm()
+                                            .child("×")
+                                            .on_mouse_up(
+                                                gpui::MouseButton::Left,
+                                                cx.listener(move |this, _event, _window, cx| {
+                                                    this.remove_from_scenario(stage, index, cx);
+                                                }),
+                                            ),
+                                    )
+                            }))
+                    }))
+                    .child(div().flex().justify_end().child(self.render_grpc_button(
+                        "Delete scenario",
+                        0x00dc_3545,
+                        0x00c8_2333,
+                        cx,
+                        Self::delete_scenario,
+                    )))
+            }))
+            .children(self.scenario_run.as_ref().map(|run| {
+                let summary = run_summary(&run.outcomes);
+                let failed = run.outcomes.iter().flatten().any(StepOutcome::failed);
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .pt_2()
+                    .child(
+                        div()
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(rgb(if running {
+                                0x0021_2529
+                            } else if failed {
+                                0x00dc_3545
+                            } else {
+                                0x0028_a745
+                            }))
+                            .child(format!("{}: {summary}", run.scenario)),
+                    )
+                    .children(run.steps.iter().zip(&run.outcomes).map(
+                        |((stage, label), outcome)| {
+                            let (text, color) = match outcome {
+                                None if running => ("Waiting...".to_string(), 0x00ad_b5bd),
+                                None => ("Stopped".to_string(), 0x00ad_b5bd),
+                                Some(StepOutcome::Passed { status, elapsed }) => (
+                                    format!("{status} in {} ms", elapsed.as_millis()),
+                                    0x0028_a745,
+                                ),
+                                Some(StepOutcome::Failed(reason)) => (reason.clone(), 0x00dc_3545),
+                                Some(StepOutcome::Skipped) => ("Skipped".to_string(), 0x006c_757d),
+                            };
+                            div()
+                                .flex()
+                                .gap_2()
+                                .py_1()
+                                .border_b_1()
+                                .border_color(rgb(0x00e9_ecef))
+                                .text_size(px(12.0))
+                                .child(
+                                    div()
+                                        .w(px(64.0))
+                                        .flex_none()
+                                        .text_color(rgb(0x006c_757d))
+                                        .child(stage.label()),
+                                )
+                                .child(div().flex_1().overflow_hidden().child(label.clone()))
+                                .child(
+                                    div()
+                                        .w(px(180.0))
+                                        .flex_none()
+                                        .text_color(rgb(color))
+                                        .child(text),
+                                )
+                        },
+                    ))
+            }))
+    }
+
+    // 集合面板：新建集合，把编辑器中的请求加入选中的集合，点击请求在新标签页打开
+    fn render_collections(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let viewer = self.config.read_only;
+        let collections = self.state.read(cx).collections().clone();
+        let selected = self
+            .selected_collection
+            .and_then(|index| collections.get(index));
+        let note = |text: &'static str| {
+            div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x006c_757d))
+                .child(text)
+        };
+
+        div()
+            .id("collections")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(420.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(div().font_weight(FontWeight::BOLD).child("Collections"))
+            .when(!viewer, |panel| {
+                panel.child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .child(div().flex_1().child(self.collection_name_input.clone()))
+                        .child(self.render_grpc_button(
+                            "New",
+                            0x006c_757d,
+                            0x005a_6268,
+                            cx,
+                            Self::add_collection,
+                        )),
+                )
+            })
+            .child(
+                div().flex().flex_wrap().gap_1().children(
+                    collections
+                        .collections()
+                        .iter()
+                        .enumerate()
+                        .map(|(index, collection)| {
+                            let active = self.selected_collection == Some(index);
+                            div()
+                                .px_2()
+                                .py_1()
+                                .rounded_md()
+                                .border_1()
+                                .border_color(rgb(0x00ce_d4da))
+                                .text_size(px(12.0))
+                                .when(active, |chip| {
+                                    chip.bg(rgb(0x0000_7acc)).text_color(rgb(0x00ff_ffff))
+                                })
+                                .when(!active, |chip| {
+                                    chip.bg(rgb(0x00ff_ffff))
+                                        .cursor_pointer()
+                                        .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                                })
+                                .child(format!(
+                                    "{} ({})",
+                                    collection.name,
+                                    collection.requests.len()
+                                ))
+                                .on_mouse_up(
+                                    gpui::MouseButton::Left,
+                                    cx.listener(move |this, _event, _window, cx| {
+                                        this.selected_collection = Some(index);
+                                        cx.notify();
+                                    }),
+                                )
+                        }),
+                ),
+            )
+            .when(collections.collections().is_empty(), |panel| {
+                panel.child(note(if viewer {
+                    "This workspace has no collections"
+                } else {
+                    "No collections yet - name one above, then add the request in the editor \
+                     to it"
+                }))
+            })
+            .children(selected.map(|collection| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .pt_2()
+                            .border_b_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .child(
+                                div()
+                                    .flex_1()
+                                    .text_size(px(13.0))
+                                    .font_weight(FontWeight::MEDIUM)
+                                    .child(collection.name.clone()),
+                            )
+                            .when(!viewer, |row| {
+                                row.child(self.render_grpc_button(
+                                    "Add current request",
+                                    0x006c_757d,
+                                    0x005a_6268,
+                                    cx,
+                                    Self::add_to_collection,
+                                ))
+                            }),
+                    )
+                    .children(
+                        collection
+                            .requests
+                            .iter()
+                            .enumerate()
+                            .map(|(index, request)| {
+                                div()
+                                    .flex()
+                                    .items_center()
+                                    .gap_2()
+                                    .text_size(px(12.0))
+                                    .child(
+                                        div()
+                                            .flex_1()
+                                            .overflow_hidden()
+                                            .cursor_pointer()
+                                            .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                                            .child(format!("{} {}", request.method, request.url))
+                                            .on_mouse_up(
+                                                gpui::MouseButton::Left,
+                                                cx.listener(move |this, _event, _window, cx| {
+                                                    this.open_from_collection(index, cx);
+                                                }),
+                                            ),
+                                    )
+                                    .when(!viewer, |row| {
+                                        row.child(
+                                            div()
+                                                .px_1()
+                                                .text_color(rgb(0x00dc_3545))
+                                                .cursor_pointer()
+                                                .hover(|style| style.bg(rgb(0x00f8_d7da)))
+                                                .rounded_sm()
+                                                .child("×")
+                                                .on_mouse_up(
+                                                    gpui::MouseButton::Left,
+                                                    cx.listener(
+                                                        move |this, _event, _window, cx| {
+                                                            this.remove_from_collection(index, cx);
+                                                        },
+                                                    ),
+                                                ),
+                                        )
+                                    })
+                            }),
+                    )
+                    .when(!viewer, |list| {
+                        list.child(div().flex().justify_end().child(self.render_grpc_button(
+                            "Delete collection",
+                            0x00dc_3545,
+                            0x00c8_2333,
+                            cx,
+                            Self::delete_collection,
+                        )))
+                    })
+            }))
+    }
+
+    // 环境面板：新建和选择环境、编辑选中环境的变量，以及当前 profile 的 cookies
+    fn render_environments(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let viewer = self.config.read_only;
+        let state = self.state.read(cx);
+        let environments = state.environments().clone();
+        let cookies = state.cookies().cookies().to_vec();
+        let active = environments.active_index();
+        let note = |text: String| {
+            div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x006c_757d))
+                .child(text)
+        };
+        let chip = |label: String, selected: bool| {
+            div()
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .border_1()
+                .border_color(rgb(0x00ce_d4da))
+                .text_size(px(12.0))
+                .when(selected, |chip| {
+                    chip.bg(rgb(0x0000_7acc)).text_color(rgb(0x00ff_ffff))
+                })
+                .when(!selected, |chip| {
+                    chip.bg(rgb(0x00ff_ffff))
+                        .cursor_pointer()
+                        .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                })
+                .child(label)
+        };
+
+        div()
+            .id("environments")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(420.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(div().font_weight(FontWeight::BOLD).child("Environments"))
+            .child(note(
+                "The active environment's variables replace {{name}} in the URL, headers and \
+                 body; hook variables with the same name win."
+                    .to_string(),
+            ))
+            .when(!viewer, |panel| {
+                panel.child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .child(div().flex_1().child(self.environment_name_input.clone()))
+                        .child(self.render_grpc_button(
+                            "New",
+                            0x006c_757d,
+                            0x005a_6268,
+                            cx,
+                            Self::add_environment,
+                        )),
+                )
+            })
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .gap_1()
+                    .child(
+                        chip("No environment".to_string(), active.is_none()).on_mouse_up(
+                            gpui::MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| {
+                                this.activate_environment(None, cx);
+                            }),
+                        ),
+                    )
+                    .children(environments.environments().iter().enumerate().map(
+                        |(index, environment)| {
+                            let label = if self.selected_environment == Some(index) {
+                                format!("✎ {}", environment.name)
+                            } else {
+                                environment.name.clone()
+                            };
+                            chip(label, active == Some(index)).on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    this.activate_environment(Some(index), cx);
+                                    this.select_environment(index, cx);
+                                }),
+                            )
+                        },
+                    )),
+            )
+            .children(
+                self.selected_environment
+                    .filter(|_| viewer)
+                    .and_then(|index| environments.environments().get(index))
+                    .map(|environment| {
+                        div()
+                            .font_family("monospace")
+                            .text_size(px(12.0))
+                            .child(environment.variables_text())
+                    }),
+            )
+            .when(self.selected_environment.is_some() && !viewer, |panel| {
+                panel
+                    .child(note("Variables, one name=value per line".to_string()))
+                    .child(
+                        div()
+                            .h(px(140.0))
+                            .child(self.environment_variables_input.clone()),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .justify_end()
+                            .gap_2()
+                            .child(self.render_grpc_button(
+                                "Save variables",
+                                0x0000_7acc,
+                                0x0000_56b3,
+                                cx,
+                                Self::save_environment_variables,
+                            ))
+                            .child(self.render_grpc_button(
+                                "Delete environment",
+                                0x00dc_3545,
+                                0x00c8_2333,
+                                cx,
+                                Self::delete_environment,
+                            )),
+                    )
+            })
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .pt_2()
+                    .border_b_1()
+                    .border_color(rgb(0x00e9_ecef))
+                    .child(
+                        div()
+                            .flex_1()
+                            .text_size(px(13.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(format!("Cookies ({})", cookies.len())),
+                    )
+                    .when(!cookies.is_empty() && !viewer, |row| {
+                        row.child(self.render_grpc_button(
+                            "Clear",
+                            0x006c_757d,
+                            0x005a_6268,
+                            cx,
+                            Self::clear_cookies,
+                        ))
+                    }),
+            )
+            .when(cookies.is_empty(), |panel| {
+                panel.child(note(
+                    "Cookies set by responses are kept here and sent with later requests to \
+                     the same site"
+                        .to_string(),
+                ))
+            })
+            .children(cookies.iter().map(|cookie| {
+                div()
+                    .flex()
+                    .gap_2()
+                    .text_size(px(12.0))
+                    .child(
+                        div()
+                            .flex_1()
+                            .overflow_hidden()
+                            .child(format!("{}={}", cookie.name, cookie.value)),
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!("{}{}", cookie.domain, cookie.path)),
+                    )
+            }))
+    }
+
+    // 批量请求面板：URL 列表和每个 URL 的状态码、耗时、大小
+    fn render_bulk_runner(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.bulk_task.is_some();
+        let done = self.bulk_outcomes.iter().flatten().count();
+        let failed = self
+            .bulk_outcomes
+            .iter()
+            .flatten()
+            .filter(|outcome| match outcome {
+                BulkOutcome::Response { status, .. } => *status >= 400,
+                BulkOutcome::Failed(_) => true,
+            })
+            .count();
+        let cell = |width: f32| div().w(px(width)).flex_none();
+
+        div()
+            .id("bulk-runner")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(520.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .font_weight(FontWeight::BOLD)
+                            .child("Bulk run"),
+                    )
+                    .when(!self.bulk_urls.is_empty() && !running, |row| {
+                        row.child(self.render_grpc_button(
+                            "Export CSV",
+                            0x006c_757d,
+                            0x005a_6268,
+                            cx,
+                            Self::export_bulk_results,
+                        ))
+                    })
+                    .child(if running {
+                        self.render_grpc_button(
+                            "Stop",
+                            0x00dc_3545,
+                            0x00c8_2333,
+                            cx,
+                            Self::run_bulk_urls,
+                        )
+                    } else {
+                        self.render_grpc_button(
+                            "Run",
+                            0x0000_7acc,
+                            0x0000_56b3,
+                            cx,
+                            Self::run_bulk_urls,
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child(format!(
+                    "One URL per line - each is requested with GET, {BULK_CONCURRENCY} at a time"
+                )),
+            )
+            .child(self.bulk_input.clone())
+            .when(!self.bulk_urls.is_empty(), |panel| {
+                panel
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!(
+                                "{done} of {} done, {failed} failed",
+                                self.bulk_urls.len()
+                            )),
+                    )
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .text_size(px(12.0))
+                            .font_weight(FontWeight::BOLD)
+                            .child(div().flex_1().child("URL"))
+                            .child(cell(56.0).child("Status"))
+                            .child(cell(72.0).child("Latency"))
+                            .child(cell(72.0).child("Size")),
+                    )
+                    .children(self.bulk_urls.iter().zip(&self.bulk_outcomes).map(
+                        |(url, outcome)| {
+                            let row = div()
+                                .flex()
+                                .gap_2()
+                                .py_1()
+                                .border_b_1()
+                                .border_color(rgb(0x00e9_ecef))
+                                .text_size(px(12.0))
+                                .child(div().flex_1().overflow_hidden().child(url.clone()));
+                            match outcome {
+                                None => {
+                                    row.child(
+                                        cell(200.0)
+                                            .text_color(rgb(0x00ad_b5bd))
+                                            .child(if running { "Waiting..." } else { "Stopped" }),
+                                    )
+                                }
+                                Some(BulkOutcome::Failed(e)) => row.child(
+                                    cell(200.0).text_color(rgb(0x00dc_3545)).child(e.clone()),
+                                ),
+                                Some(BulkOutcome::Response {
+                                    status,
+                                    elapsed,
+                                    size,
+                                }) => row
+                                    .child(
+                                        cell(56.0)
+                                            .text_color(rgb(if *status < 400 {
+                                                0x0028_a745
+                                            } else {
+                                                0x00dc_3545
+                                            }))
+                                            .child(status.to_string()),
+                                    )
+                                    .child(cell(72.0).child(format!("{} ms", elapsed.as_millis())))
+                                    .child(cell(72.0).child(format_size(*size as u64))),
+                            }
+                        },
+                    ))
+            })
+    }
+
+    // 会话重放面板：时间窗口、窗口内的请求数，以及每个请求记录的和本次的状态码
+    fn render_replay(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.replay_task.is_some();
+        let window = match self.replay_bounds(cx) {
+            Ok((from, to)) => match self.state.read(cx).history().sent_between(from, to).len() {
+                1 => "1 request in this window".to_string(),
+                count => format!("{count} requests in this window"),
+            },
+            Err(e) => e.to_string(),
+        };
+        let note = |text: String| {
+            div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x006c_757d))
+                .child(text)
+        };
+        let cell = |width: f32| div().w(px(width)).flex_none();
+
+        div()
+            .id("session-replay")
+            .flex()
+            .flex_col()
+            .gap_2()
+            .w(px(520.0))
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .border_l_1()
+            .border_color(rgb(0x00cc_cccc))
+            .overflow_scroll()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .font_weight(FontWeight::BOLD)
+                            .child("Session replay"),
+                    )
+                    .child(if running {
+                        self.render_grpc_button(
+                            "Stop",
+                            0x00dc_3545,
+                            0x00c8_2333,
+                            cx,
+                            Self::replay_history,
+                        )
+                    } else {
+                        self.render_grpc_button(
+                            "Replay",
+                            0x0000_7acc,
+                            0x0000_56b3,
+                            cx,
+                            Self::replay_history,
+                        )
+                    }),
+            )
+            .child(note(
+                "Sends the requests from history sent in the time window again, oldest first, \
+                 with the current domain defaults, auth and variables, and compares each \
+                 status with the recorded one. Hooks and assertions don't run."
+                    .to_string(),
+            ))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(div().flex_1().child(self.replay_from_input.clone()))
+                    .child(div().flex_1().child(self.replay_to_input.clone())),
+            )
+            .child(note(window))
+            .when_some(self.replay_run.as_ref(), |panel, run| {
+                panel
+                    .child(note(replay_summary(&run.recorded, &run.outcomes)))
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .text_size(px(12.0))
+                            .font_weight(FontWeight::BOLD)
+                            .child(div().flex_1().child("Request"))
+                            .child(cell(56.0).child("Before"))
+                            .child(cell(160.0).child("Now")),
+                    )
+                    .children(
+                        run.requests
+                            .iter()
+                            .zip(&run.recorded)
+                            .zip(&run.outcomes)
+                            .map(|((request, recorded), outcome)| {
+                                let row = div()
+                                    .flex()
+                                    .gap_2()
+                                    .py_1()
+                                    .border_b_1()
+                                    .border_color(rgb(0x00e9_ecef))
+                                    .text_size(px(12.0))
+                                    .child(div().flex_1().overflow_hidden().child(request.clone()))
+                                    .child(
+                                        cell(56.0).child(
+                                            recorded.map_or("-".to_string(), |status| {
+                                                status.to_string()
+                                            }),
+                                        ),
+                                    );
+                                let Some(outcome) = outcome else {
+                                    return row.child(
+                                        cell(160.0)
+                                            .text_color(rgb(0x00ad_b5bd))
+                                            .child(if running { "Waiting..." } else { "Stopped" }),
+                                    );
+                                };
+                                let color = match StatusChange::of(*recorded, outcome) {
+                                    StatusChange::Same => 0x0028_a745,
+                                    StatusChange::Changed | StatusChange::Failed => 0x00dc_3545,
+                                    StatusChange::Unrecorded => 0x006c_757d,
+                                };
+                                let now = match outcome {
+                                    BulkOutcome::Response { status, .. } => status.to_string(),
+                                    BulkOutcome::Failed(e) => e.clone(),
+                                };
+                                row.child(cell(160.0).text_color(rgb(color)).child(now))
+                            }),
+                    )
+            })
+    }
+
+    // 小号复选框开关（例如 Send 按钮旁的流式响应开关）
+    fn render_option_toggle(
+        &self,
+        label: &'static str,
+        checked: bool,
+        cx: &mut Context<Self>,
+        on_toggle: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+    ) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_1()
+            .cursor_pointer()
+            .text_size(px(12.0))
+            .text_color(rgb(COLOR_TEXT_DISABLED))
+            .child(
+                div()
+                    .w_4()
+                    .h_4()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .bg(rgb(Self::checkbox_bg_color(checked)))
+                    .border_1()
+                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
+                    .rounded_sm()
+                    .text_color(rgb(COLOR_CHECKBOX_TEXT))
+                    .child(if checked { "✓" } else { "" }),
+            )
+            .child(label)
+            .on_mouse_up(
+                gpui::MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| on_toggle(this, cx)),
+            )
+    }
+
+    // 原始 socket 模式的请求编辑区
+    fn render_raw_request_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_4()
+                    .child(div().flex_1().child(self.raw_address_input.clone()))
+                    .child(
+                        div()
+                            .child(if self.raw_task.is_some() {
+                                "Sending..."
+                            } else {
+                                "Send Raw"
+                            })
+                            .bg(rgb(0x0000_7acc))
+                            .text_color(rgb(0x00ff_ffff))
+                            .px_4()
+                            .py_2()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x0000_56b3)))
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.send_raw_request(cx);
+                                }),
+                            ),
+                    )
+                    .child(self.render_option_toggle(
+                        "LF → CRLF",
+                        self.raw_crlf,
+                        cx,
+                        |this, cx| {
+                            this.raw_crlf = !this.raw_crlf;
+                            cx.notify();
+                        },
+                    )),
+            )
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("Request line, headers and body are sent exactly as typed"),
+            )
+            .child(self.raw_request_input.clone())
+    }
+
+    // 请求失败时的诊断操作和结果
+    fn render_diagnostics(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let running = self.diagnostics_task.is_some();
+        let output = match &self.diagnostics {
+            None if running => Some(div().text_color(rgb(0x006c_757d)).child(
+                if self.diagnose_traceroute {
+                    "Resolving, connecting and tracing the route (up to a minute)..."
+                } else {
+                    "Resolving and connecting..."
+                },
+            )),
+            None => None,
+            Some(Err(e)) => Some(div().text_color(rgb(0x00dc_3545)).child(e.clone())),
+            Some(Ok(report)) => Some(div().font_family("monospace").child(report.clone())),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child("No response arrived - find out where the connection fails"),
+                    )
+                    .child(self.render_grpc_button(
+                        if running { "Diagnosing..." } else { "Diagnose" },
+                        0x0000_7acc,
+                        0x0000_56b3,
+                        cx,
+                        Self::run_diagnostics,
                     ))
-                    .text_size(px(16.0))
-                    .font_weight(FontWeight::MEDIUM),
+                    .child(self.render_option_toggle(
+                        "Include traceroute",
+                        self.diagnose_traceroute,
+                        cx,
+                        |this, cx| {
+                            this.diagnose_traceroute = !this.diagnose_traceroute;
+                            cx.notify();
+                        },
+                    ))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child("DNS fallback"),
+                    )
+                    .child(
+                        div()
+                            .w(px(140.0))
+                            .child(self.diagnose_nameserver_input.clone()),
+                    ),
             )
-            // 现有headers列表
+            .children(output.map(|output| {
+                div()
+                    .id("diagnostics-output")
+                    .max_h(px(320.0))
+                    .overflow_scroll()
+                    .p_2()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(rgb(0x00e9_ecef))
+                    .bg(rgb(0x00f8_f9fa))
+                    .text_size(px(12.0))
+                    .child(output)
+            }))
+    }
+
+    // 条件格式规则编辑器（每行一条规则）
+    fn render_format_rules_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(self.render_option_toggle(
+                "Formatting rules",
+                self.show_format_rules,
+                cx,
+                Self::toggle_format_rules,
+            ))
+            .when(self.show_format_rules, |panel| {
+                panel
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(
+                                "One rule per line - a check, => and banner or badge with an \
+                                 optional color and text, e.g. \
+                                 json error.message exists => banner red {value} or \
+                                 latency > 1s => badge yellow Slow",
+                            ),
+                    )
+                    .child(self.format_rules_input.clone())
+                    .child(
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(self.render_grpc_button(
+                                "Save rules",
+                                0x0000_7acc,
+                                0x0000_56b3,
+                                cx,
+                                Self::save_format_rules,
+                            ))
+                            .when_some(self.format_rules_error.clone(), |row, error| {
+                                row.child(
+                                    div()
+                                        .text_size(px(12.0))
+                                        .text_color(rgb(0x00dc_3545))
+                                        .child(error),
+                                )
+                            }),
+                    )
+            })
+    }
+
+    // 黄金基线：固定显示的响应，或显示它与基线的差异
+    fn render_baseline_bar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let baseline = self
+            .baseline_request
+            .as_ref()
+            .and_then(|(method, url)| self.baselines.get(*method, url));
+        let row = div().flex().items_center().gap_2();
+        let Some(baseline) = baseline else {
+            return div().flex().flex_col().gap_2().child(
+                row.child(self.render_grpc_button(
+                    "Pin as golden baseline",
+                    0x0000_7acc,
+                    0x0000_56b3,
+                    cx,
+                    Self::pin_baseline,
+                ))
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child("Later responses to this request are compared with it"),
+                ),
+            );
+        };
+
+        let changes = self.baseline_changes.as_deref().unwrap_or_default();
+        let (summary, color) = match changes.len() {
+            0 => ("Matches the golden baseline".to_string(), 0x0028_a745),
+            1 => (
+                "1 difference from the golden baseline".to_string(),
+                0x00dc_3545,
+            ),
+            n => (
+                format!("{n} differences from the golden baseline"),
+                0x00dc_3545,
+            ),
+        };
+        let pinned = baseline
+            .pinned_at
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M");
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                row.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(color))
+                        .child(summary),
+                )
+                .child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x00ad_b5bd))
+                        .child(format!("pinned {pinned}")),
+                )
+                .child(self.render_grpc_button(
+                    "Re-pin",
+                    0x006c_757d,
+                    0x005a_6268,
+                    cx,
+                    Self::pin_baseline,
+                ))
+                .child(self.render_grpc_button(
+                    "Unpin",
+                    0x00dc_3545,
+                    0x00c8_2333,
+                    cx,
+                    Self::unpin_baseline,
+                ))
+                .child(div().flex_1().child(self.baseline_ignore_input.clone()))
+                .child(self.render_grpc_button(
+                    "Ignore",
+                    0x0000_7acc,
+                    0x0000_56b3,
+                    cx,
+                    Self::apply_baseline_ignored,
+                )),
+            )
+            .when(!changes.is_empty(), |panel| {
+                panel.child(
+                    div()
+                        .id("baseline-changes")
+                        .max_h(px(200.0))
+                        .overflow_scroll()
+                        .p_2()
+                        .rounded_md()
+                        .border_1()
+                        .border_color(rgb(0x00e9_ecef))
+                        .bg(rgb(0x00f8_f9fa))
+                        .text_size(px(12.0))
+                        .font_family("monospace")
+                        .children(changes.iter().map(|change| div().child(change.describe()))),
+                )
+            })
+    }
+
+    // 请求的响应示例：点击在参考标签页中打开，可保存显示的响应或手写示例
+    // 响应在单独窗口中显示时主窗口里的占位
+    fn render_response_window_note(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_4()
+            .px_3()
+            .py_2()
+            .rounded_md()
+            .bg(rgb(0x00f8_f9fa))
+            .border_1()
+            .border_color(rgb(0x00e9_ecef))
+            .child(
+                div()
+                    .flex_1()
+                    .text_size(px(13.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("The response is shown in its own window"),
+            )
+            .child(self.render_grpc_button(
+                "Bring back",
+                0x0000_7acc,
+                0x0000_56b3,
+                cx,
+                Self::toggle_response_window,
+            ))
+    }
+
+    fn render_examples_bar(&self, has_response: bool, cx: &mut Context<Self>) -> impl IntoElement {
+        let (method, url) = self.example_request(cx);
+        let examples = self.examples.for_request(method, &url);
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
             .child(
                 div()
                     .flex()
-                    .flex_col()
+                    .flex_wrap()
+                    .items_center()
                     .gap_2()
-                    .children(if self.headers.is_empty() {
-                        vec![div()
+                    .text_size(px(12.0))
+                    .child(
+                        div()
+                            .font_weight(FontWeight::MEDIUM)
+                            .text_color(rgb(0x006c_757d))
+                            .child("Examples"),
+                    )
+                    .when(examples.is_empty(), |row| {
+                        row.child(
+                            div()
+                                .text_color(rgb(0x00ad_b5bd))
+                                .child("None saved for this request"),
+                        )
+                    })
+                    .children(examples.iter().enumerate().map(|(index, example)| {
+                        div()
+                            .id(("example", index))
                             .flex()
+                            .items_center()
                             .gap_2()
-                            .child(
-                                div()
-                                    .w_8()
-                                    .px_2()
-                                    .py_2()
-                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
-                                    .border_1()
-                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
-                                    .child(""),
-                            )
-                            .child(
-                                div()
-                                    .flex_1()
-                                    .px_3()
-                                    .py_2()
-                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
-                                    .border_1()
-                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
-                                    .child("No headers set"),
-                            )
-                            .child(
-                                div()
-                                    .flex_1()
-                                    .px_3()
-                                    .py_2()
-                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
-                                    .border_1()
-                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
-                                    .child(""),
-                            )
-                            .child(
-                                div()
-                                    .w_16()
-                                    .px_3()
-                                    .py_2()
-                                    .bg(rgb(COLOR_HEADER_DISABLED_BG))
-                                    .border_1()
-                                    .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
-                                    .child(""),
-                            )]
-                    } else {
-                        self.headers
-                            .iter()
-                            .enumerate()
-                            .map(|(index, (enabled, key, value))| {
-                                div()
-                                    .flex()
-                                    .gap_2()
-                                    .child(
-                                        // Checkbox column
-                                        div()
-                                            .w_8()
-                                            .h_8()
-                                            .flex()
-                                            .items_center()
-                                            .justify_center()
-                                            .bg(rgb(Self::checkbox_bg_color(*enabled)))
-                                            .border_1()
-                                            .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
-                                            .rounded_sm()
-                                            .cursor_pointer()
-                                            .hover(|style| {
-                                                style.bg(rgb(Self::checkbox_hover_bg_color(
-                                                    *enabled,
-                                                )))
-                                            })
-                                            .child(if *enabled { "✓" } else { "" })
-                                            .text_color(rgb(COLOR_CHECKBOX_TEXT))
-                                            .on_mouse_up(
-                                                gpui::MouseButton::Left,
-                                                cx.listener(move |this, _event, _window, cx| {
-                                                    this.toggle_header(index, cx);
-                                                }),
-                                            ),
-                                    )
-                                    .child(
-                                        div()
-                                            .flex_1()
-                                            .px_3()
-                                            .py_2()
-                                            .bg(rgb(Self::header_cell_bg_color(*enabled)))
-                                            .border_1()
-                                            .border_color(rgb(Self::header_cell_border_color(
-                                                *enabled,
-                                            )))
-                                            .text_color(rgb(Self::header_text_color(*enabled)))
-                                            .child(key.clone()),
-                                    )
-                                    .child(
-                                        div()
-                                            .flex_1()
-                                            .px_3()
-                                            .py_2()
-                                            .bg(rgb(Self::header_cell_bg_color(*enabled)))
-                                            .border_1()
-                                            .border_color(rgb(Self::header_cell_border_color(
-                                                *enabled,
-                                            )))
-                                            .text_color(rgb(Self::header_text_color(*enabled)))
-                                            .child(value.clone()),
-                                    )
-                                    .child(
-                                        div()
-                                            .w_16()
-                                            .px_2()
-                                            .py_1()
-                                            .bg(rgb(0x00dc_3545))
-                                            .text_color(rgb(0x00ff_ffff))
-                                            .rounded_md()
-                                            .cursor_pointer()
-                                            .hover(|style| style.bg(rgb(0x00c8_2333)))
-                                            .child("Delete")
-                                            .on_mouse_up(
-                                                gpui::MouseButton::Left,
-                                                cx.listener(move |this, _event, _window, cx| {
-                                                    this.remove_header(index, cx);
-                                                }),
-                                            ),
-                                    )
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .border_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .bg(rgb(0x00f8_f9fa))
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x00e7_f1ff)))
+                            .child(format!("{} · {}", example.response.status, example.name))
+                            .when(!self.config.read_only, |chip| {
+                                chip.child(
+                                    div()
+                                        .id(("example-remove", index))
+                                        .text_color(rgb(0x006c_757d))
+                                        .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                                        .child("✕")
+                                        .on_mouse_up(
+                                            gpui::MouseButton::Left,
+                                            cx.listener(move |this, _event, _window, cx| {
+                                                cx.stop_propagation();
+                                                this.remove_example(index, cx);
+                                            }),
+                                        ),
+                                )
                             })
-                            .collect()
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    this.open_example(index, cx)
+                                }),
+                            )
+                    })),
+            )
+            // 查看模式下只能查看已保存的示例
+            .when(!self.config.read_only, |bar| {
+                bar.child(
+                    div()
+                        .flex()
+                        .items_center()
+                        .gap_2()
+                        .child(div().flex_1().child(self.example_name_input.clone()))
+                        .when(has_response, |row| {
+                            row.child(self.render_grpc_button(
+                                "Save response as example",
+                                0x0000_7acc,
+                                0x0000_56b3,
+                                cx,
+                                Self::save_response_as_example,
+                            ))
+                        })
+                        .child(self.render_grpc_button(
+                            if self.show_example_editor {
+                                "Cancel"
+                            } else {
+                                "Write example"
+                            },
+                            0x006c_757d,
+                            0x005a_6268,
+                            cx,
+                            |this, cx| {
+                                this.show_example_editor = !this.show_example_editor;
+                                cx.notify();
+                            },
+                        )),
+                )
+            })
+            .child(match &self.mock_server {
+                Some(server) => div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x0028_a745))
+                            .child(format!(
+                                "Mock server running at {} with {}",
+                                server.url(),
+                                Self::route_count(server.routes())
+                            )),
+                    )
+                    .child(self.render_grpc_button(
+                        "Stop mock server",
+                        0x00dc_3545,
+                        0x00c8_2333,
+                        cx,
+                        Self::toggle_mock_server,
+                    )),
+                None => div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().w(px(120.0)).child(self.mock_port_input.clone()))
+                    .child(self.render_grpc_button(
+                        "Mock all examples",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::toggle_mock_server,
+                    ))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child("Serves every saved example on 127.0.0.1"),
+                    ),
+            })
+            .when(self.show_example_editor && !self.config.read_only, |bar| {
+                bar.child(
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_2()
+                        .child(div().w(px(160.0)).child(self.example_status_input.clone()))
+                        .child(self.example_body_input.clone())
+                        .child(div().flex().child(self.render_grpc_button(
+                            "Add example",
+                            0x0028_a745,
+                            0x0021_8838,
+                            cx,
+                            Self::add_written_example,
+                        ))),
+                )
+            })
+    }
+
+    // "Pipe response to…"：命令输入框和命令输出
+    fn render_response_pipe(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let output = match &self.pipe_output {
+            None if self.pipe_task.is_some() => {
+                Some(div().text_color(rgb(0x006c_757d)).child("Running..."))
+            }
+            None => None,
+            Some(Err(e)) => Some(div().text_color(rgb(0x00dc_3545)).child(e.clone())),
+            Some(Ok(text)) if text.is_empty() => Some(
+                div()
+                    .text_color(rgb(0x006c_757d))
+                    .child("The command printed nothing"),
+            ),
+            Some(Ok(text)) => Some(div().font_family("monospace").child(text.clone())),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(self.render_option_toggle(
+                        "Pipe response to…",
+                        self.show_pipe,
+                        cx,
+                        Self::toggle_pipe,
+                    ))
+                    .when(self.show_pipe, |row| {
+                        row.child(div().flex_1().child(self.pipe_input.clone()))
+                            .child(self.render_grpc_button(
+                                if self.pipe_task.is_some() {
+                                    "Running..."
+                                } else {
+                                    "Run"
+                                },
+                                0x0000_7acc,
+                                0x0000_56b3,
+                                cx,
+                                Self::pipe_response,
+                            ))
                     }),
             )
-            // 添加新header的输入框
+            .when_some(output.filter(|_| self.show_pipe), |panel, output| {
+                panel.child(
+                    div()
+                        .id("pipe-output")
+                        .max_h(px(240.0))
+                        .overflow_scroll()
+                        .p_2()
+                        .rounded_md()
+                        .border_1()
+                        .border_color(rgb(0x00e9_ecef))
+                        .bg(rgb(0x00f8_f9fa))
+                        .text_size(px(12.0))
+                        .child(output),
+                )
+            })
+    }
+
+    // 原始 socket 模式的响应 - 文本（显示 CR）或十六进制
+    fn render_raw_response(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = match &self.raw_response {
+            None if self.raw_task.is_some() => div().child("Waiting for response..."),
+            None => div()
+                .text_color(rgb(0x006c_757d))
+                .child("Send a raw request to see the bytes the server returns"),
+            Some(Err(e)) => div().text_color(rgb(0x00dc_3545)).child(e.clone()),
+            Some(Ok(response)) => {
+                let text = if self.raw_hex {
+                    hex_dump(&response.bytes)
+                } else {
+                    response.text().replace('\r', "␍")
+                };
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!(
+                                "{} bytes in {} ms | {}",
+                                response.bytes.len(),
+                                response.elapsed.as_millis(),
+                                if response.closed {
+                                    "closed by server"
+                                } else {
+                                    "connection left open"
+                                }
+                            )),
+                    )
+                    .child(
+                        div().font_family("monospace").text_size(px(12.0)).children(
+                            text.split('\n')
+                                .map(|line| line.to_string())
+                                .map(|line| div().child(line)),
+                        ),
+                    )
+            }
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
             .child(
                 div()
                     .flex()
-                    .gap_2()
+                    .items_center()
+                    .gap_4()
                     .child(
-                        // Empty checkbox column for alignment
-                        div().w_8(),
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child("Raw Response"),
                     )
-                    .child(self.header_key_input.clone())
-                    .child(self.header_value_input.clone())
+                    .child(
+                        self.render_option_toggle("Hex", self.raw_hex, cx, |this, cx| {
+                            this.raw_hex = !this.raw_hex;
+                            cx.notify();
+                        }),
+                    ),
+            )
+            .child(content)
+    }
+
+    // gRPC 调用编辑区
+    fn render_grpc_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let client_stream_open = self
+            .grpc_session
+            .as_ref()
+            .map(|session| session.call.is_client_stream_open());
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_4()
+                    .child(div().flex_1().child(self.grpc_server_input.clone()))
+                    .child(div().flex_1().child(self.grpc_method_input.clone()))
                     .child(
                         div()
-                            .w_16()
-                            .px_2()
-                            .py_1()
-                            .bg(rgb(0x0028_a745))
+                            .child("Start call")
+                            .bg(rgb(0x0000_7acc))
                             .text_color(rgb(0x00ff_ffff))
+                            .px_4()
+                            .py_2()
                             .rounded_md()
                             .cursor_pointer()
-                            .hover(|style| style.bg(rgb(0x0021_8838)))
-                            .child("Add")
+                            .hover(|style| style.bg(rgb(0x0000_56b3)))
                             .on_mouse_up(
                                 gpui::MouseButton::Left,
                                 cx.listener(|this, _event, _window, cx| {
-                                    this.add_header(cx);
+                                    this.start_grpc_call(cx);
                                 }),
                             ),
                     ),
             )
-            // 快速添加预设headers
             .child(
                 div()
                     .flex()
+                    .items_center()
                     .gap_2()
                     .child(
                         div()
                             .text_size(px(12.0))
                             .text_color(rgb(0x006c_757d))
-                            .child("Quick add: "),
+                            .child("Descriptor"),
                     )
+                    .child(div().flex_1().child(self.proto_descriptor_input.clone())),
+            )
+            .child(self.render_headers_editor(cx))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
                     .child(
                         div()
-                            .px_2()
-                            .py_1()
-                            .bg(rgb(0x006c_757d))
-                            .text_color(rgb(0x00ff_ffff))
-                            .rounded_md()
-                            .cursor_pointer()
-                            .hover(|style| style.bg(rgb(0x005a_6268)))
-                            .child("JSON")
                             .text_size(px(12.0))
-                            .on_mouse_up(
-                                gpui::MouseButton::Left,
-                                cx.listener(|this, _event, _window, cx| {
-                                    this.set_header_input_values(
-                                        "Content-Type",
-                                        "application/json",
-                                        cx,
-                                    );
-                                }),
+                            .text_color(rgb(0x006c_757d))
+                            .child(
+                                "Message (JSON) - sent when the call starts and by Send message",
                             ),
                     )
+                    .child(div().flex_1())
+                    .when(client_stream_open == Some(true), |row| {
+                        row.child(self.render_grpc_button(
+                            "Send message",
+                            0x006c_757d,
+                            0x005a_6268,
+                            cx,
+                            Self::send_grpc_message,
+                        ))
+                        .child(self.render_grpc_button(
+                            "End stream",
+                            0x006c_757d,
+                            0x005a_6268,
+                            cx,
+                            Self::end_grpc_stream,
+                        ))
+                    })
+                    .when(client_stream_open.is_some(), |row| {
+                        row.child(self.render_grpc_button(
+                            "Cancel",
+                            0x00dc_3545,
+                            0x00c8_2333,
+                            cx,
+                            Self::cancel_grpc_call,
+                        ))
+                    }),
+            )
+            .child(self.grpc_message_input.clone())
+    }
+
+    fn render_grpc_button(
+        &self,
+        label: &'static str,
+        color: u32,
+        hover_color: u32,
+        cx: &mut Context<Self>,
+        on_click: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+    ) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .bg(rgb(color))
+            .text_color(rgb(0x00ff_ffff))
+            .rounded_md()
+            .cursor_pointer()
+            .hover(move |style| style.bg(rgb(hover_color)))
+            .child(label)
+            .text_size(px(12.0))
+            .on_mouse_up(
+                gpui::MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| on_click(this, cx)),
+            )
+    }
+
+    // GraphQL 编辑区 - 查询编辑器下方显示补全建议和校验结果，右侧为 schema 文档
+    fn render_graphql_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let query_input = self.graphql_query_input.read(cx);
+        let query = query_input.get_content();
+        let cursor = query_input.cursor_offset();
+        let schema = self.graphql_schema(cx);
+        let (replace_len, completions) = schema
+            .map(|schema| graphql::completions(schema, &query, cursor))
+            .unwrap_or_default();
+        let diagnostics = schema
+            .map(|schema| graphql::validate(schema, &query))
+            .unwrap_or_default();
+        let schema_status = match schema {
+            Some(schema) => format!("Schema: {} types", schema.type_count()),
+            None => "No schema - Fetch schema to enable completion".to_string(),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_4()
+                    .child(self.url_input.clone())
                     .child(
                         div()
-                            .px_2()
-                            .py_1()
-                            .bg(rgb(0x006c_757d))
+                            .child("Send")
+                            .bg(rgb(0x0000_7acc))
                             .text_color(rgb(0x00ff_ffff))
+                            .px_4()
+                            .py_2()
                             .rounded_md()
                             .cursor_pointer()
-                            .hover(|style| style.bg(rgb(0x005a_6268)))
-                            .child("Auth")
-                            .text_size(px(12.0))
+                            .hover(|style| style.bg(rgb(0x0000_56b3)))
                             .on_mouse_up(
                                 gpui::MouseButton::Left,
-                                cx.listener(|this, _event, _window, cx| {
-                                    this.set_header_input_values("Authorization", "Bearer ", cx);
-                                }),
+                                cx.listener(Self::on_send_clicked),
                             ),
                     )
+                    .child(self.render_grpc_button(
+                        "Fetch schema",
+                        0x006c_757d,
+                        0x005a_6268,
+                        cx,
+                        Self::fetch_graphql_schema,
+                    ))
+                    .when(self.graphql_subscription.is_some(), |row| {
+                        row.child(self.render_grpc_button(
+                            "Stop",
+                            0x00dc_3545,
+                            0x00c8_2333,
+                            cx,
+                            Self::stop_graphql_subscription,
+                        ))
+                    }),
+            )
+            .child(self.render_headers_editor(cx))
+            .child(
+                div()
+                    .flex()
+                    .gap_4()
+                    .child(
+                        div()
+                            .flex()
+                            .flex_col()
+                            .flex_1()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .flex()
+                                    .gap_2()
+                                    .text_size(px(12.0))
+                                    .text_color(rgb(0x006c_757d))
+                                    .child("Query")
+                                    .child(div().flex_1())
+                                    .child(schema_status),
+                            )
+                            .child(self.graphql_query_input.clone())
+                            .when(!completions.is_empty(), |editor| {
+                                editor.child(div().flex().flex_wrap().gap_1().children(
+                                    completions.into_iter().take(12).enumerate().map(
+                                        |(index, completion)| {
+                                            let insert = completion.insert.clone();
+                                            div()
+                                                .id(("graphql-completion", index))
+                                                .flex()
+                                                .gap_1()
+                                                .px_2()
+                                                .py_1()
+                                                .rounded_md()
+                                                .border_1()
+                                                .border_color(rgb(0x00ce_d4da))
+                                                .cursor_pointer()
+                                                .hover(|style| style.bg(rgb(0x00e7_f1ff)))
+                                                .text_size(px(12.0))
+                                                .child(completion.label)
+                                                .child(
+                                                    div()
+                                                        .text_color(rgb(0x006c_757d))
+                                                        .child(completion.detail),
+                                                )
+                                                .on_mouse_up(
+                                                    gpui::MouseButton::Left,
+                                                    cx.listener(
+                                                        move |this, _event, _window, cx| {
+                                                            this.graphql_query_input.update(
+                                                                cx,
+                                                                |input, cx| {
+                                                                    input.replace_before_cursor(
+                                                                        replace_len,
+                                                                        &insert,
+                                                                        cx,
+                                                                    );
+                                                                },
+                                                            );
+                                                        },
+                                                    ),
+                                                )
+                                        },
+                                    ),
+                                ))
+                            })
+                            .children(diagnostics.into_iter().map(|diagnostic| {
+                                let (line, column) = diagnostic.line_column(&query);
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(rgb(0x00dc_3545))
+                                    .child(format!("{line}:{column} {}", diagnostic.message))
+                            }))
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(rgb(0x006c_757d))
+                                    .child("Variables (JSON, optional)"),
+                            )
+                            .child(self.graphql_variables_input.clone()),
+                    )
+                    .child(
+                        div()
+                            .w(px(320.0))
+                            .flex_none()
+                            .p_2()
+                            .border_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .rounded_md()
+                            .child(self.graphql_docs.clone()),
+                    ),
+            )
+    }
+
+    // Range header 构建器
+    fn render_range_builder(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("Byte range: "),
+            )
+            .when(!self.range_suffix, |row| {
+                row.child(div().w_32().child(self.range_start_input.clone()))
+                    .child(div().text_size(px(12.0)).child("-"))
+            })
+            .child(div().w_32().child(self.range_end_input.clone()))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .cursor_pointer()
+                    .text_size(px(12.0))
+                    .text_color(rgb(COLOR_TEXT_DISABLED))
                     .child(
+                        div()
+                            .w_4()
+                            .h_4()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .bg(rgb(Self::checkbox_bg_color(self.range_suffix)))
+                            .border_1()
+                            .border_color(rgb(COLOR_HEADER_DISABLED_BORDER))
+                            .rounded_sm()
+                            .text_color(rgb(COLOR_CHECKBOX_TEXT))
+                            .child(if self.range_suffix { "✓" } else { "" }),
+                    )
+                    .child("Suffix")
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.toggle_range_suffix(cx);
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .px_2()
+                    .py_1()
+                    .bg(rgb(0x006c_757d))
+                    .text_color(rgb(0x00ff_ffff))
+                    .rounded_md()
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x005a_6268)))
+                    .child("Set Range")
+                    .text_size(px(12.0))
+                    .on_mouse_up(
+                        gpui::MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.apply_range_header(cx);
+                        }),
+                    ),
+            )
+    }
+
+    // 条件请求构建器 - 使用同一 URL 上次响应中的校验值
+    fn render_conditional_builder(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let url = self.url_input.read(cx).get_url().trim().to_string();
+        let validators = self
+            .state
+            .read(cx)
+            .history()
+            .last_response_for(&url)
+            .map(|response| Validators::from_headers(&response.headers))
+            .unwrap_or_default();
+
+        div()
+            .flex()
+            .gap_2()
+            .items_center()
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child("Conditional: "),
+            )
+            .when(validators.is_empty(), |row| {
+                row.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child("No ETag or Last-Modified captured for this URL yet"),
+                )
+            })
+            .children(
+                validators
+                    .conditional_headers()
+                    .into_iter()
+                    .map(|(header, value)| {
+                        let label = format!("{}: {value}", header.name());
                         div()
                             .px_2()
                             .py_1()
@@ -839,34 +9162,24 @@ impl PostmanApp {
                             .rounded_md()
                             .cursor_pointer()
                             .hover(|style| style.bg(rgb(0x005a_6268)))
-                            .child("CORS")
+                            .child(label)
                             .text_size(px(12.0))
                             .on_mouse_up(
                                 gpui::MouseButton::Left,
-                                cx.listener(|this, _event, _window, cx| {
-                                    this.set_header_input_values(
-                                        "Access-Control-Allow-Origin",
-                                        "*",
-                                        cx,
-                                    );
+                                cx.listener(move |this, _event, _window, cx| {
+                                    this.apply_conditional_header(header, value.clone(), cx);
                                 }),
-                            ),
-                    ),
-            )
-            // 统计信息
-            .child(
-                div()
-                    .text_size(px(12.0))
-                    .text_color(rgb(0x006c_757d))
-                    .child(format!(
-                    "Total headers: {} | Enabled: {} | Add headers by typing key and value above",
-                    self.headers.len(),
-                    self.headers
-                        .iter()
-                        .filter(|(enabled, _, _)| *enabled)
-                        .count()
-                )),
+                            )
+                    }),
             )
+            .when(validators.has_weak_etag(), |row| {
+                row.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x00dc_3545))
+                        .child("Weak ETag - If-Match will fail with 412"),
+                )
+            })
     }
 
     fn render_body_editor(&self, cx: &mut Context<Self>) -> impl IntoElement {
@@ -881,31 +9194,11 @@ impl PostmanApp {
                     .font_weight(FontWeight::MEDIUM),
             )
             .child(self.body_input.clone())
-            .child(
-                div()
-                    .text_size(px(12.0))
-                    .text_color(rgb(0x006c_757d))
-                    .child(match self.body_input.read(cx).get_current_type() {
-                        crate::ui::components::body_input::BodyType::Json => {
-                            format!(
-                                "JSON body length: {} characters",
-                                self.body_input.read(cx).get_json_content().len()
-                            )
-                        }
-                        crate::ui::components::body_input::BodyType::FormData => {
-                            format!(
-                                "Form data entries: {}",
-                                self.body_input.read(cx).get_form_data_entries().len()
-                            )
-                        }
-                        crate::ui::components::body_input::BodyType::Raw => {
-                            format!(
-                                "Raw body length: {} characters",
-                                self.body_input.read(cx).get_content().len()
-                            )
-                        }
-                    }),
-            )
+            .when(!self.part_previews.is_empty(), |editor| {
+                editor.child(self.render_part_previews())
+            })
+            .child(self.render_serializer_selector(cx))
+            .child(self.render_protection_settings(cx))
             .child(
                 div()
                     .flex()
@@ -973,11 +9266,27 @@ impl Render for PostmanApp {
         cx.subscribe(&history_list_clone, Self::on_history_selected)
             .detach();
 
+        if self.locked {
+            return self.render_lock_screen(cx).into_any_element();
+        }
+        let has_response = matches!(
+            self.response_viewer.read(cx).get_state(),
+            ResponseState::Success { .. }
+        );
+        // 查看模式 - 隐藏编辑请求、修改设置和写入数据的控件
+        let viewer = self.config.read_only;
+
         div()
             .id("main-container")
             .flex()
             .bg(rgb(0x00f0_f0f0))
             .size_full()
+            .relative()
+            // 拖放 .pgreq 文件在新标签页中打开
+            .drag_over::<ExternalPaths>(|style, _, _, _| style.bg(rgb(0x00e7_f1ff)))
+            .on_drop(cx.listener(|this, paths: &ExternalPaths, _window, cx| {
+                this.open_request_files(paths.paths(), cx);
+            }))
             .child(
                 // Left sidebar - History List
                 self.history_list.clone(),
@@ -993,10 +9302,236 @@ impl Render for PostmanApp {
                     .child(
                         // Header
                         div()
-                            .child("Postman GPUI")
-                            .text_size(px(24.0))
-                            .font_weight(FontWeight::BOLD),
+                            .flex()
+                            .items_center()
+                            .justify_between()
+                            .child(
+                                div()
+                                    .child("Postman GPUI")
+                                    .text_size(px(24.0))
+                                    .font_weight(FontWeight::BOLD),
+                            )
+                            .child(self.render_profile_switcher(cx))
+                            .when(viewer, |row| {
+                                row.child(
+                                    div()
+                                        .px_2()
+                                        .py_1()
+                                        .rounded_md()
+                                        .bg(rgb(0x00ff_f3cd))
+                                        .text_size(px(12.0))
+                                        .child("Viewer mode - read-only, nothing is saved"),
+                                )
+                            })
+                            .when(!viewer, |row| {
+                                row.child(self.render_encryption_controls(cx))
+                                .child(self.render_option_toggle(
+                                    "Raw socket",
+                                    self.raw_mode,
+                                    cx,
+                                    |this, cx| {
+                                        this.raw_mode = !this.raw_mode;
+                                        this.grpc_mode &= !this.raw_mode;
+                                        this.graphql_mode &= !this.raw_mode;
+                                        tracing::info!(
+                                            "🔌 PostmanApp - 原始 socket 模式: {}",
+                                            this.raw_mode
+                                        );
+                                        cx.notify();
+                                    },
+                                ))
+                                .child(self.render_option_toggle(
+                                    "gRPC",
+                                    self.grpc_mode,
+                                    cx,
+                                    |this, cx| {
+                                        this.grpc_mode = !this.grpc_mode;
+                                        this.raw_mode &= !this.grpc_mode;
+                                        this.graphql_mode &= !this.grpc_mode;
+                                        tracing::info!("📡 PostmanApp - gRPC 模式: {}", this.grpc_mode);
+                                        cx.notify();
+                                    },
+                                ))
+                                .child(self.render_option_toggle(
+                                    "GraphQL",
+                                    self.graphql_mode,
+                                    cx,
+                                    Self::toggle_graphql_mode,
+                                ))
+                            })
+                            .child(self.render_option_toggle(
+                                "API explorer",
+                                self.show_api_explorer,
+                                cx,
+                                |this, cx| {
+                                    this.show_api_explorer = !this.show_api_explorer;
+                                    cx.notify();
+                                },
+                            ))
+                            .when(!viewer, |row| {
+                                row.child(self.render_option_toggle(
+                                    "Domains",
+                                    self.show_domains,
+                                    cx,
+                                    |this, cx| {
+                                        this.show_domains = !this.show_domains;
+                                        cx.notify();
+                                    },
+                                ))
+                                .child(self.render_option_toggle(
+                                    "Certificates",
+                                    self.show_certificates,
+                                    cx,
+                                    |this, cx| {
+                                        this.show_certificates = !this.show_certificates;
+                                        cx.notify();
+                                    },
+                                ))
+                                .child(self.render_option_toggle(
+                                    "Assertions",
+                                    self.show_snippets,
+                                    cx,
+                                    |this, cx| {
+                                        this.show_snippets = !this.show_snippets;
+                                        cx.notify();
+                                    },
+                                ))
+                            })
+                            .child(self.render_option_toggle(
+                                "Activity",
+                                self.show_activity,
+                                cx,
+                                |this, cx| {
+                                    this.show_activity = !this.show_activity;
+                                    cx.notify();
+                                },
+                            ))
+                            .child(self.render_option_toggle(
+                                "Usage",
+                                self.show_usage,
+                                cx,
+                                |this, cx| {
+                                    this.show_usage = !this.show_usage;
+                                    cx.notify();
+                                },
+                            ))
+                            .child(self.render_option_toggle(
+                                "Bulk run",
+                                self.show_bulk,
+                                cx,
+                                |this, cx| {
+                                    this.show_bulk = !this.show_bulk;
+                                    cx.notify();
+                                },
+                            ))
+                            .when(!viewer, |row| {
+                                row.child(self.render_option_toggle(
+                                    "Scenarios",
+                                    self.show_scenarios,
+                                    cx,
+                                    |this, cx| {
+                                        this.show_scenarios = !this.show_scenarios;
+                                        cx.notify();
+                                    },
+                                ))
+                            })
+                            .child(self.render_option_toggle(
+                                "Collections",
+                                self.show_collections,
+                                cx,
+                                |this, cx| {
+                                    this.show_collections = !this.show_collections;
+                                    cx.notify();
+                                },
+                            ))
+                            .child(self.render_option_toggle(
+                                "Environments",
+                                self.show_environments,
+                                cx,
+                                |this, cx| {
+                                    this.show_environments = !this.show_environments;
+                                    cx.notify();
+                                },
+                            ))
+                            .child(self.render_option_toggle(
+                                "Replay",
+                                self.show_replay,
+                                cx,
+                                |this, cx| {
+                                    this.show_replay = !this.show_replay;
+                                    cx.notify();
+                                },
+                            ))
+                            .when(!viewer, |row| {
+                                row.child(
+                                    div()
+                                        .flex()
+                                        .items_center()
+                                        .gap_2()
+                                        .cursor_pointer()
+                                        .text_size(px(12.0))
+                                        .text_color(rgb(0x006c_757d))
+                                        .child(
+                                            div()
+                                                .w_4()
+                                                .h_4()
+                                                .flex()
+                                                .items_center()
+                                                .justify_center()
+                                                .rounded_sm()
+                                                .border_1()
+                                                .border_color(rgb(0x00ce_d4da))
+                                                .text_color(rgb(0x00ff_ffff))
+                                                .bg(rgb(Self::checkbox_bg_color(
+                                                    self.config.watch_clipboard,
+                                                )))
+                                                .when(self.config.watch_clipboard, |div| {
+                                                    div.child("✓")
+                                                }),
+                                        )
+                                        .child("Watch clipboard")
+                                        .on_mouse_up(
+                                            gpui::MouseButton::Left,
+                                            cx.listener(|this, _event, window, cx| {
+                                                this.toggle_clipboard_watch(window, cx);
+                                            }),
+                                        ),
+                                )
+                            }),
                     )
+                    .children(self.notice.clone().map(|notice| {
+                        div()
+                            .flex()
+                            .gap_2()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(notice)
+                            .child(
+                                div()
+                                    .cursor_pointer()
+                                    .hover(|style| style.text_color(rgb(0x0021_2529)))
+                                    .child("✕")
+                                    .on_mouse_up(
+                                        gpui::MouseButton::Left,
+                                        cx.listener(|this, _event, _window, cx| {
+                                            this.notice = None;
+                                            cx.notify();
+                                        }),
+                                    ),
+                            )
+                    }))
+                    .child(self.render_tab_strip(cx))
+                    .child(
+                        div()
+                            .flex()
+                            .flex_1()
+                            .gap_4()
+                            .child(
+                                div()
+                                    .flex()
+                                    .flex_col()
+                                    .flex_1()
+                                    .gap_4()
                     .child(
                         // Request Panel
                         div()
@@ -1007,31 +9542,99 @@ impl Render for PostmanApp {
                             .bg(rgb(0x00ff_ffff))
                             .border_1()
                             .border_color(rgb(0x00cc_cccc))
-                            .child(
-                                // Method and URL row
-                                div()
-                                    .flex()
-                                    .gap_4()
-                                    .child(self.method_selector.clone())
-                                    .child(self.url_input.clone()) // 使用 UrlInput 组件替代 render_url_input
-                                    .child(
-                                        div()
-                                            .child("Send")
-                                            .bg(rgb(0x0000_7acc))
-                                            .text_color(rgb(0x00ff_ffff))
-                                            .px_4()
-                                            .py_2()
-                                            .rounded_md()
-                                            .cursor_pointer()
-                                            .hover(|style| style.bg(rgb(0x0000_56b3)))
-                                            .on_mouse_up(
-                                                gpui::MouseButton::Left,
-                                                cx.listener(Self::on_send_clicked),
-                                            ),
-                                    ),
-                            )
-                            .child(self.render_headers_editor(cx))
-                            .child(self.render_body_editor(cx)),
+                            .when(self.raw_mode, |panel| {
+                                panel.child(self.render_raw_request_editor(cx))
+                            })
+                            .when(self.grpc_mode, |panel| {
+                                panel.child(self.render_grpc_editor(cx))
+                            })
+                            .when(self.graphql_mode, |panel| {
+                                panel.child(self.render_graphql_editor(cx))
+                            })
+                            .when(viewer, |panel| panel.child(self.render_request_view(cx)))
+                            .when(
+                                !viewer && !self.raw_mode && !self.grpc_mode && !self.graphql_mode,
+                                |panel| {
+                                    panel
+                                        .child(
+                                            // Method and URL row
+                                            div()
+                                                .flex()
+                                                .gap_4()
+                                                .child(self.method_selector.clone())
+                                                .child(self.url_input.clone()) // 使用 UrlInput 组件替代 render_url_input
+                                                .child(
+                                                    div()
+                                                        .child("Send")
+                                                        .bg(rgb(0x0000_7acc))
+                                                        .text_color(rgb(0x00ff_ffff))
+                                                        .px_4()
+                                                        .py_2()
+                                                        .rounded_md()
+                                                        .cursor_pointer()
+                                                        .hover(|style| style.bg(rgb(0x0000_56b3)))
+                                                        .on_mouse_up(
+                                                            gpui::MouseButton::Left,
+                                                            cx.listener(Self::on_send_clicked),
+                                                        ),
+                                                )
+                                                .child(self.render_grpc_button(
+                                                    "Send & Duplicate",
+                                                    0x006c_757d,
+                                                    0x005a_6268,
+                                                    cx,
+                                                    Self::send_and_duplicate,
+                                                ))
+                                                .child(self.render_request_size(cx))
+                                                .child(self.render_option_toggle(
+                                                    "Stream",
+                                                    self.stream_response,
+                                                    cx,
+                                                    Self::toggle_stream_response,
+                                                )),
+                                        )
+                                        .child(
+                                            div()
+                                                .flex()
+                                                .items_center()
+                                                .gap_2()
+                                                .child(
+                                                    div()
+                                                        .text_size(px(12.0))
+                                                        .text_color(rgb(0x006c_757d))
+                                                        .child("Socket"),
+                                                )
+                                                .child(
+                                                    div().flex_1().child(self.socket_input.clone()),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .text_size(px(12.0))
+                                                        .text_color(rgb(0x006c_757d))
+                                                        .child("Certificate"),
+                                                )
+                                                .child(
+                                                    div()
+                                                        .w_48()
+                                                        .child(self.certificate_selector.clone()),
+                                                )
+                                                .child(self.render_option_toggle(
+                                                    "Hooks",
+                                                    self.show_hooks,
+                                                    cx,
+                                                    |this, cx| {
+                                                        this.show_hooks = !this.show_hooks;
+                                                        cx.notify();
+                                                    },
+                                                )),
+                                        )
+                                        .when(self.show_hooks, |panel| {
+                                            panel.child(self.render_hooks_editor(cx))
+                                        })
+                                        .child(self.render_headers_editor(cx))
+                                        .child(self.render_body_editor(cx))
+                                },
+                            ),
                     )
                     .child(
                         // Response Panel
@@ -1045,8 +9648,152 @@ impl Render for PostmanApp {
                             .bg(rgb(0x00ff_ffff))
                             .border_1()
                             .border_color(rgb(0x00cc_cccc))
-                            .child(self.response_viewer.clone()),
+                            .when(self.raw_mode, |panel| {
+                                panel.child(self.render_raw_response(cx))
+                            })
+                            .when(self.grpc_mode, |panel| panel.child(self.grpc_log.clone()))
+                            .when(self.graphql_mode && self.show_graphql_log, |panel| {
+                                panel.child(self.graphql_log.clone())
+                            })
+                            .when(
+                                !self.raw_mode
+                                    && !self.grpc_mode
+                                    && !(self.graphql_mode && self.show_graphql_log),
+                                |panel| {
+                                    panel
+                                        .when_some(self.shown_example.as_ref(), |panel, name| {
+                                            panel.child(
+                                                div()
+                                                    .px_3()
+                                                    .py_2()
+                                                    .rounded_md()
+                                                    .bg(rgb(0x00e9_ecef))
+                                                    .text_color(rgb(0x0021_2529))
+                                                    .text_size(px(13.0))
+                                                    .child(format!(
+                                                        "Example \"{name}\" - a saved response \
+                                                         shown for reference, Send to get a live one"
+                                                    )),
+                                            )
+                                        })
+                                        .children(
+                                            self.highlights
+                                                .iter()
+                                                .filter(|highlight| {
+                                                    highlight.placement == Placement::Banner
+                                                })
+                                                .map(|highlight| {
+                                                    div()
+                                                        .px_3()
+                                                        .py_2()
+                                                        .rounded_md()
+                                                        .bg(rgb(highlight.color.background()))
+                                                        .text_color(rgb(highlight
+                                                            .color
+                                                            .foreground()))
+                                                        .text_size(px(13.0))
+                                                        .child(highlight.text.clone())
+                                                }),
+                                        )
+                                        .when(self.failed_url.is_some(), |panel| {
+                                            panel.child(self.render_diagnostics(cx))
+                                        })
+                                        .when(
+                                            self.next_page.is_some() || self.pages_task.is_some(),
+                                            |panel| panel.child(self.render_pagination(cx)),
+                                        )
+                                        .when(has_response && !viewer, |panel| {
+                                            panel.child(self.render_baseline_bar(cx))
+                                        })
+                                        .child(self.render_examples_bar(has_response, cx))
+                                        .when(!viewer, |panel| {
+                                            panel
+                                                .child(self.render_format_rules_editor(cx))
+                                                .child(self.render_response_pipe(cx))
+                                        })
+                                        .child(self.render_protobuf_settings(cx))
+                                        .child(
+                                            div()
+                                                .flex()
+                                                .items_center()
+                                                .gap_2()
+                                                .when(self.response_window.is_none(), |row| {
+                                                    row.child(self.render_grpc_button(
+                                                        "Pop out response",
+                                                        0x006c_757d,
+                                                        0x005a_6268,
+                                                        cx,
+                                                        Self::toggle_response_window,
+                                                    ))
+                                                })
+                                                .when(has_response, |row| {
+                                                    row.child(self.render_grpc_button(
+                                                        "Export as PDF",
+                                                        0x006c_757d,
+                                                        0x005a_6268,
+                                                        cx,
+                                                        Self::export_response_pdf,
+                                                    ))
+                                                }),
+                                        )
+                                        .when(self.response_window.is_none(), |panel| {
+                                            panel.children(self.response_body_view())
+                                        })
+                                        .when(self.response_window.is_some(), |panel| {
+                                            panel.child(self.render_response_window_note(cx))
+                                        })
+                                },
+                            ),
                     ),
+                            )
+                            .when_some(self.compare_tab, |row, index| {
+                                row.child(self.render_compare_pane(index, cx))
+                            }),
+                    ),
+            )
+            .when(self.show_api_explorer, |container| {
+                container.child(self.render_api_explorer(cx))
+            })
+            .when(self.show_domains && !viewer, |container| {
+                container.child(self.render_domains())
+            })
+            .when(self.show_certificates && !viewer, |container| {
+                container.child(self.render_certificates())
+            })
+            .when(self.show_snippets && !viewer, |container| {
+                container.child(self.render_snippets())
+            })
+            .when(self.show_activity, |container| {
+                container.child(self.render_activity(cx))
+            })
+            .when(self.show_usage, |container| {
+                container.child(self.render_usage(cx))
+            })
+            .when(self.show_bulk, |container| {
+                container.child(self.render_bulk_runner(cx))
+            })
+            .when(self.show_scenarios && !viewer, |container| {
+                container.child(self.render_scenarios(cx))
+            })
+            .when(self.show_collections, |container| {
+                container.child(self.render_collections(cx))
+            })
+            .when(self.show_environments, |container| {
+                container.child(self.render_environments(cx))
+            })
+            .when(self.show_replay, |container| {
+                container.child(self.render_replay(cx))
+            })
+            .children(
+                self.clipboard_offer
+                    .as_ref()
+                    .map(|offer| self.render_clipboard_toast(offer, cx)),
+            )
+            .children(
+                self.pending_hooks
+                    .as_ref()
+                    .map(|commands| self.render_hook_confirmation(commands, cx)),
             )
+            .into_any_element()
     }
 }
@@ -0,0 +1,373 @@
+use crate::http::credentials::{is_credential_header, same_origin};
+use crate::models::{
+    Annotation, HttpMethod, Request, RequestHooks, ResponseExample, SavedResponse,
+};
+use crate::ui::components::response_viewer::ResponseState;
+use crate::utils::format_rules::Highlight;
+use std::cmp::Ordering;
+
+// Maximum length for URL display in tab titles
+const MAX_TAB_TITLE_LENGTH: usize = 24;
+
+/// Snapshot of one request tab.
+///
+/// Only the active tab is edited through the form components; the other tabs
+/// keep their state here until they are switched to.
+#[derive(Debug, Clone)]
+pub struct RequestTab {
+    /// Name given by where the tab came from (e.g. a `.pgreq` file)
+    pub name: Option<String>,
+    pub method: HttpMethod,
+    pub url: String,
+    // Headers - (enabled, key, value)
+    pub headers: Vec<(bool, String, String)>,
+    pub body: Option<String>,
+    /// Unix socket / named pipe the request is sent through
+    pub socket: Option<String>,
+    /// Imported client certificate chosen for this request
+    pub client_certificate: Option<String>,
+    /// Assertion snippets attached to this request
+    pub assertions: Vec<u64>,
+    /// Commands run before sending and after the response
+    pub hooks: RequestHooks,
+    pub response: ResponseState,
+    pub annotations: Vec<Annotation>,
+    /// History entry the response was recorded in
+    pub history_id: Option<u64>,
+    /// Banners and badges the formatting rules gave the response
+    pub highlights: Vec<Highlight>,
+    /// Name of the saved example shown instead of a live response
+    pub example: Option<String>,
+}
+
+impl RequestTab {
+    pub fn new() -> Self {
+        Self {
+            name: None,
+            method: HttpMethod::GET,
+            url: String::new(),
+            headers: Vec::new(),
+            body: None,
+            socket: None,
+            client_certificate: None,
+            assertions: Vec::new(),
+            hooks: RequestHooks::default(),
+            response: ResponseState::NotSent,
+            annotations: Vec::new(),
+            history_id: None,
+            highlights: Vec::new(),
+            example: None,
+        }
+    }
+
+    pub fn from_request(request: &Request, name: Option<String>) -> Self {
+        Self {
+            name,
+            method: request.method,
+            url: request.url.clone(),
+            headers: request
+                .headers
+                .iter()
+                .map(|(key, value)| (true, key.clone(), value.clone()))
+                .collect(),
+            body: request.body.clone(),
+            socket: request.socket.clone(),
+            client_certificate: request.client_certificate.clone(),
+            assertions: request.assertions.clone(),
+            hooks: request.hooks.clone(),
+            response: ResponseState::NotSent,
+            annotations: Vec::new(),
+            history_id: None,
+            highlights: Vec::new(),
+            example: None,
+        }
+    }
+
+    /// Reference tab showing one of the request's saved examples
+    pub fn from_example(request: &Request, example: &ResponseExample) -> Self {
+        let mut tab = Self::from_request(request, Some(format!("Example: {}", example.name)))
+            .with_response(Some(example.response.clone()));
+        tab.example = Some(example.name.clone());
+        tab
+    }
+
+    /// Show a saved response (and its annotations) in the tab
+    pub fn with_response(mut self, response: Option<SavedResponse>) -> Self {
+        if let Some(response) = response {
+            self.response = ResponseState::Success {
+                status: response.status,
+                headers: response.headers,
+                body: response.body,
+            };
+            self.annotations = response.annotations;
+        }
+        self
+    }
+
+    /// Build a request containing only the enabled headers
+    pub fn to_request(&self) -> Request {
+        let mut request = Request::new(self.method, &self.url);
+        for (_, key, value) in self.headers.iter().filter(|(enabled, _, _)| *enabled) {
+            request.add_header(key, value);
+        }
+        if let Some(body) = &self.body {
+            request.set_body(body);
+        }
+        request.socket = self.socket.clone();
+        request.client_certificate = self.client_certificate.clone();
+        request.assertions = self.assertions.clone();
+        request.hooks = self.hooks.clone();
+        request
+    }
+
+    /// New GET tab for a URL found in this tab's response, keeping the
+    /// enabled credential headers (`Authorization`, cookies, API keys) and
+    /// the client certificate when the URL is on the same origin
+    pub fn follow_link(&self, url: impl Into<String>) -> Self {
+        let mut tab = Self::new();
+        tab.url = url.into();
+        if same_origin(&self.url, &tab.url) {
+            tab.headers = self.credential_headers();
+            tab.client_certificate = self.client_certificate.clone();
+        }
+        tab
+    }
+
+    /// Copy of the request form, disabled headers included, without the
+    /// response; a reference tab's copy is an ordinary tab again
+    pub fn duplicate(&self) -> Self {
+        Self {
+            name: self.name.clone().filter(|_| self.example.is_none()),
+            method: self.method,
+            url: self.url.clone(),
+            headers: self.headers.clone(),
+            body: self.body.clone(),
+            socket: self.socket.clone(),
+            client_certificate: self.client_certificate.clone(),
+            assertions: self.assertions.clone(),
+            hooks: self.hooks.clone(),
+            ..Self::new()
+        }
+    }
+
+    /// Enabled headers that carry credentials
+    pub fn credential_headers(&self) -> Vec<(bool, String, String)> {
+        self.headers
+            .iter()
+            .filter(|(enabled, key, _)| *enabled && is_credential_header(key))
+            .cloned()
+            .collect()
+    }
+
+    /// Title shown in the tab strip
+    pub fn title(&self) -> String {
+        if let Some(name) = &self.name {
+            return Self::truncate(name);
+        }
+        if self.url.trim().is_empty() {
+            return "Untitled".to_string();
+        }
+        let url = self
+            .url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        format!("{} {}", self.method, Self::truncate(url))
+    }
+
+    fn truncate(text: &str) -> String {
+        if text.chars().count() > MAX_TAB_TITLE_LENGTH {
+            let truncated: String = text.chars().take(MAX_TAB_TITLE_LENGTH).collect();
+            format!("{truncated}...")
+        } else {
+            text.to_string()
+        }
+    }
+}
+
+/// Where the tab at `index` ends up once the tab at `closed` is closed;
+/// `None` for the closed tab itself
+pub fn index_after_close(index: usize, closed: usize) -> Option<usize> {
+    match index.cmp(&closed) {
+        Ordering::Less => Some(index),
+        Ordering::Equal => None,
+        Ordering::Greater => Some(index - 1),
+    }
+}
+
+impl Default for RequestTab {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Hook;
+
+    #[test]
+    fn test_tab_from_request_round_trip() {
+        let mut request = Request::new("POST", "https://api.example.com/users");
+        request.add_header("Content-Type", "application/json");
+        request.set_body("{}");
+        request.set_socket("/var/run/app.sock");
+        request.client_certificate = Some("Staging".to_string());
+        request.assertions = vec![3, 1];
+        request.hooks.pre = Hook::parse("./token.sh", "token").unwrap();
+
+        let tab = RequestTab::from_request(&request, None);
+        assert_eq!(tab.headers.len(), 1);
+        assert!(tab.headers[0].0);
+        assert_eq!(tab.to_request(), request);
+    }
+
+    #[test]
+    fn test_to_request_skips_disabled_headers() {
+        let mut tab = RequestTab::new();
+        tab.url = "https://api.example.com".to_string();
+        tab.headers = vec![
+            (true, "Accept".to_string(), "application/json".to_string()),
+            (false, "X-Debug".to_string(), "1".to_string()),
+        ];
+
+        let request = tab.to_request();
+        assert_eq!(request.headers.len(), 1);
+        assert_eq!(request.headers[0].0, "Accept");
+    }
+
+    #[test]
+    fn test_tab_with_response() {
+        let mut response = SavedResponse::new(200, "hello");
+        response
+            .add_annotation(0..5, crate::models::HighlightColor::Green, "greeting")
+            .unwrap();
+
+        let tab = RequestTab::new().with_response(Some(response));
+        assert!(matches!(
+            tab.response,
+            ResponseState::Success { status: 200, .. }
+        ));
+        assert_eq!(tab.annotations.len(), 1);
+
+        let tab = RequestTab::new().with_response(None);
+        assert!(matches!(tab.response, ResponseState::NotSent));
+    }
+
+    #[test]
+    fn test_tab_from_example() {
+        let request = Request::new("GET", "https://api.example.com/users/1");
+        let example = ResponseExample::new("Not found", SavedResponse::new(404, "{}"));
+
+        let tab = RequestTab::from_example(&request, &example);
+        assert_eq!(tab.title(), "Example: Not found");
+        assert_eq!(tab.example.as_deref(), Some("Not found"));
+        assert!(matches!(
+            tab.response,
+            ResponseState::Success { status: 404, .. }
+        ));
+        assert_eq!(tab.to_request(), request);
+    }
+
+    #[test]
+    fn test_duplicate_keeps_form_only() {
+        let mut request = Request::new("PUT", "https://api.example.com/users/1");
+        request.set_body(r#"{"name": "Ada"}"#);
+        let mut tab = RequestTab::from_request(&request, Some("Rename".to_string()))
+            .with_response(Some(SavedResponse::new(200, "{}")));
+        tab.headers
+            .push((false, "X-Debug".to_string(), "1".to_string()));
+        tab.history_id = Some(7);
+
+        let copy = tab.duplicate();
+        assert_eq!(copy.title(), "Rename");
+        assert_eq!(copy.headers, tab.headers);
+        assert_eq!(copy.to_request(), tab.to_request());
+        assert!(matches!(copy.response, ResponseState::NotSent));
+        assert_eq!(copy.history_id, None);
+
+        let example = ResponseExample::new("Not found", SavedResponse::new(404, "{}"));
+        let copy = RequestTab::from_example(&request, &example).duplicate();
+        assert_eq!(copy.title(), "PUT api.example.com/users/1");
+        assert_eq!(copy.example, None);
+    }
+
+    #[test]
+    fn test_follow_link_keeps_credentials() {
+        let mut tab = RequestTab::new();
+        tab.method = HttpMethod::POST;
+        tab.url = "https://api.example.com/users".to_string();
+        tab.body = Some("{}".to_string());
+        tab.headers = vec![
+            (true, "Authorization".to_string(), "Bearer abc".to_string()),
+            (true, "X-Api-Key".to_string(), "k".to_string()),
+            (false, "Cookie".to_string(), "old=1".to_string()),
+            (
+                true,
+                "Content-Type".to_string(),
+                "application/json".to_string(),
+            ),
+        ];
+        tab.client_certificate = Some("Staging".to_string());
+
+        let followed = tab.follow_link("https://api.example.com/users/2");
+        assert_eq!(followed.method, HttpMethod::GET);
+        assert_eq!(followed.url, "https://api.example.com/users/2");
+        assert_eq!(followed.body, None);
+        let keys: Vec<&str> = followed
+            .headers
+            .iter()
+            .map(|(_, key, _)| key.as_str())
+            .collect();
+        assert_eq!(keys, ["Authorization", "X-Api-Key"]);
+        assert_eq!(followed.client_certificate.as_deref(), Some("Staging"));
+    }
+
+    #[test]
+    fn test_follow_link_to_another_origin_drops_credentials() {
+        let mut tab = RequestTab::new();
+        tab.url = "https://api.example.com/users".to_string();
+        tab.headers = vec![
+            (true, "Authorization".to_string(), "Bearer abc".to_string()),
+            (true, "Cookie".to_string(), "session=1".to_string()),
+        ];
+        tab.client_certificate = Some("Staging".to_string());
+
+        for url in [
+            "https://collector.example.net/users/2",
+            "http://api.example.com/users/2",
+            "https://api.example.com:8443/users/2",
+        ] {
+            let followed = tab.follow_link(url);
+            assert_eq!(followed.url, url);
+            assert!(followed.headers.is_empty());
+            assert_eq!(followed.client_certificate, None);
+        }
+    }
+
+    #[test]
+    fn test_index_after_close() {
+        assert_eq!(index_after_close(0, 2), Some(0));
+        assert_eq!(index_after_close(2, 2), None);
+        assert_eq!(index_after_close(3, 2), Some(2));
+    }
+
+    #[test]
+    fn test_tab_title() {
+        assert_eq!(RequestTab::new().title(), "Untitled");
+
+        let tab = RequestTab::from_request(&Request::new("GET", "https://example.com/a"), None);
+        assert_eq!(tab.title(), "GET example.com/a");
+
+        let tab = RequestTab::from_request(
+            &Request::new("GET", "https://example.com"),
+            Some("Shared ping".to_string()),
+        );
+        assert_eq!(tab.title(), "Shared ping");
+
+        let tab = RequestTab::from_request(
+            &Request::new("GET", "https://example.com/a/very/long/path/to/a/resource"),
+            None,
+        );
+        assert!(tab.title().ends_with("..."));
+    }
+}
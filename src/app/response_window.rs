@@ -0,0 +1,66 @@
+use crate::app::PostmanApp;
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, Context, Entity, FontWeight, InteractiveElement,
+    IntoElement, ParentElement, Render, StatefulInteractiveElement, Styled, WeakEntity, Window,
+};
+
+/// The response panel popped out into its own window. It renders the
+/// response views owned by the app, so it always shows the active tab's
+/// response; closing the window puts the panel back in the main window
+pub struct ResponseWindow {
+    app: Entity<PostmanApp>,
+}
+
+impl ResponseWindow {
+    pub fn new(app: Entity<PostmanApp>, cx: &mut Context<Self>) -> Self {
+        // 切换标签页或响应模式时刷新
+        cx.observe(&app, |_this, _app, cx| cx.notify()).detach();
+
+        let weak: WeakEntity<PostmanApp> = app.downgrade();
+        cx.on_release(move |_this, cx| {
+            weak.update(cx, |app, cx| app.on_response_window_closed(cx))
+                .ok();
+        })
+        .detach();
+
+        Self { app }
+    }
+}
+
+impl Render for ResponseWindow {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let app = self.app.read(cx);
+        let title = app.response_window_title(cx);
+        let body = app.response_body_view();
+        let in_main_window = body.is_none();
+
+        div()
+            .id("response-window")
+            .size_full()
+            .overflow_scroll()
+            .flex()
+            .flex_col()
+            .gap_4()
+            .p_4()
+            .bg(rgb(0x00ff_ffff))
+            .child(
+                div()
+                    .pb_2()
+                    .border_b_1()
+                    .border_color(rgb(0x00e9_ecef))
+                    .text_size(px(13.0))
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(0x0021_2529))
+                    .child(title),
+            )
+            .children(body)
+            .when(in_main_window, |window| {
+                window.child(
+                    div()
+                        .text_size(px(13.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child("Raw, gRPC and GraphQL log views are shown in the main window"),
+                )
+            })
+    }
+}
@@ -0,0 +1,78 @@
+use crate::errors::AppError;
+use crate::storage::{read_json, write_json};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File in the data directory the settings are saved in
+const SETTINGS_FILE: &str = "settings.json";
+
+/// Application settings that can be changed at runtime
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppConfig {
+    /// Watch the clipboard while the window is focused and offer to import
+    /// copied curl commands or URLs
+    pub watch_clipboard: bool,
+    /// Ping the profiles' base URLs in the background and show whether each
+    /// one is reachable
+    pub health_checks: bool,
+    /// Viewer mode: the data directory is only read, never written, and
+    /// requests, collections, environments and settings can't be changed -
+    /// for kiosks and demos, or to look through someone else's exported
+    /// workspace safely. Set from the command line and never saved
+    #[serde(skip)]
+    pub read_only: bool,
+    /// Data directory to use instead of the default one, e.g. a copy of
+    /// someone else's. Set from the command line and never saved
+    #[serde(skip)]
+    pub data_dir: Option<PathBuf>,
+}
+
+impl AppConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Settings saved in the data directory `root`, the defaults if none
+    /// were saved yet
+    pub fn load(root: &Path) -> Result<Self, AppError> {
+        Ok(read_json(&root.join(SETTINGS_FILE), None)?.unwrap_or_default())
+    }
+
+    pub fn save(&self, root: &Path) -> Result<(), AppError> {
+        write_json(&root.join(SETTINGS_FILE), self, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_default_config() {
+        let config = AppConfig::new();
+        assert!(!config.watch_clipboard);
+        assert!(!config.health_checks);
+        assert!(!config.read_only);
+    }
+
+    #[test]
+    fn test_settings_are_saved_without_viewer_mode() {
+        let root = TempDir::new("config");
+        assert_eq!(AppConfig::load(&root).unwrap(), AppConfig::new());
+
+        let config = AppConfig {
+            watch_clipboard: true,
+            health_checks: true,
+            read_only: true,
+            data_dir: Some(root.to_path_buf()),
+        };
+        config.save(&root).unwrap();
+        let loaded = AppConfig::load(&root).unwrap();
+        assert!(loaded.watch_clipboard);
+        assert!(loaded.health_checks);
+        assert!(!loaded.read_only);
+        assert_eq!(loaded.data_dir, None);
+    }
+}
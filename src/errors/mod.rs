@@ -21,6 +21,8 @@ pub enum AppError {
     NetworkError(String),
     /// UI rendering error
     RenderError(String),
+    /// File read/write error
+    FileError(String),
 }
 
 impl fmt::Display for AppError {
@@ -32,6 +34,7 @@ impl fmt::Display for AppError {
             AppError::UrlEmpty => write!(f, "Error: URL cannot be empty"),
             AppError::NetworkError(msg) => write!(f, "Network Error: {}", msg),
             AppError::RenderError(msg) => write!(f, "Render Error: {}", msg),
+            AppError::FileError(msg) => write!(f, "File Error: {}", msg),
         }
     }
 }
@@ -53,6 +56,13 @@ impl From<reqwest::Error> for AppError {
     }
 }
 
+// Implement From trait for std::io::Error
+impl From<std::io::Error> for AppError {
+    fn from(err: std::io::Error) -> Self {
+        AppError::FileError(err.to_string())
+    }
+}
+
 // Implement From trait for String (for backward compatibility)
 impl From<String> for AppError {
     fn from(msg: String) -> Self {
@@ -90,6 +100,9 @@ mod tests {
 
         let err = AppError::RenderError("Failed to render component".to_string());
         assert_eq!(err.to_string(), "Render Error: Failed to render component");
+
+        let err = AppError::FileError("Permission denied".to_string());
+        assert_eq!(err.to_string(), "File Error: Permission denied");
     }
 
     #[test]
@@ -101,6 +114,13 @@ mod tests {
         assert_eq!(err.to_string(), "Validation Error: another error");
     }
 
+    #[test]
+    fn test_from_io_error() {
+        let io_err = std::io::Error::new(std::io::ErrorKind::NotFound, "missing.pgreq");
+        let err: AppError = io_err.into();
+        assert!(matches!(err, AppError::FileError(_)));
+    }
+
     #[test]
     fn test_error_is_send_and_sync() {
         fn assert_send<T: Send>() {}
@@ -0,0 +1,181 @@
+//! Bulk URL runner: `GET` every URL of a pasted list, a few at a time, and
+//! report status, latency and size per URL.
+
+use crate::http::executor::RequestExecutor;
+use crate::models::HttpMethod;
+use crate::utils::csv::CsvTable;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+/// How many requests are in flight at once
+pub const BULK_CONCURRENCY: usize = 4;
+
+/// What one URL answered
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkOutcome {
+    Response {
+        status: u16,
+        elapsed: Duration,
+        size: usize,
+    },
+    Failed(String),
+}
+
+/// The URLs of pasted text: one per line, blank lines and `#` comments
+/// skipped, `https://` added when the scheme is missing and repeats dropped
+pub fn parse_url_list(text: &str) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let url = if line.contains("://") {
+            line.to_string()
+        } else {
+            format!("https://{line}")
+        };
+        if !urls.contains(&url) {
+            urls.push(url);
+        }
+    }
+    urls
+}
+
+/// Request every URL on `concurrency` threads. Outcomes arrive by index as
+/// the requests finish; dropping the receiver stops the runner after the
+/// requests in flight
+pub fn run_bulk(urls: Vec<String>, concurrency: usize) -> UnboundedReceiver<(usize, BulkOutcome)> {
+    let (outcomes, receiver) = unbounded_channel();
+    let urls = Arc::new(urls);
+    let next = Arc::new(AtomicUsize::new(0));
+    let executor = Arc::new(RequestExecutor::new());
+    for _ in 0..concurrency.clamp(1, urls.len().max(1)) {
+        let (urls, next, executor, outcomes) = (
+            urls.clone(),
+            next.clone(),
+            executor.clone(),
+            outcomes.clone(),
+        );
+        std::thread::spawn(move || loop {
+            let index = next.fetch_add(1, Ordering::SeqCst);
+            let Some(url) = urls.get(index) else {
+                break;
+            };
+            if outcomes.is_closed() {
+                break;
+            }
+            let started = Instant::now();
+            let outcome = match executor.execute_bytes(HttpMethod::GET, url, Vec::new(), None) {
+                Ok(result) => BulkOutcome::Response {
+                    status: result.status,
+                    elapsed: started.elapsed(),
+                    size: result.raw_body.len(),
+                },
+                Err(e) => BulkOutcome::Failed(e.to_string()),
+            };
+            if outcomes.send((index, outcome)).is_err() {
+                break;
+            }
+        });
+    }
+    receiver
+}
+
+/// The results as CSV, `None` standing for a URL that hasn't answered yet
+pub fn results_csv(urls: &[String], outcomes: &[Option<BulkOutcome>]) -> String {
+    let rows: Vec<Vec<String>> = urls
+        .iter()
+        .zip(outcomes)
+        .map(|(url, outcome)| {
+            let cells = match outcome {
+                Some(BulkOutcome::Response {
+                    status,
+                    elapsed,
+                    size,
+                }) => [
+                    status.to_string(),
+                    elapsed.as_millis().to_string(),
+                    size.to_string(),
+                    String::new(),
+                ],
+                Some(BulkOutcome::Failed(e)) => {
+                    [String::new(), String::new(), String::new(), e.clone()]
+                }
+                None => Default::default(),
+            };
+            std::iter::once(url.clone()).chain(cells).collect()
+        })
+        .collect();
+    let table = CsvTable {
+        headers: ["URL", "Status", "Latency (ms)", "Size (bytes)", "Error"]
+            .map(String::from)
+            .to_vec(),
+        rows,
+    };
+    let order: Vec<usize> = (0..table.rows.len()).collect();
+    table.to_csv(&order)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url_list() {
+        let text = "# smoke test\n\
+                    https://example.com/\n\
+                    \n\
+                    example.org/status  \n\
+                    http://localhost:8080/health\n\
+                    https://example.com/\n";
+        assert_eq!(
+            parse_url_list(text),
+            [
+                "https://example.com/",
+                "https://example.org/status",
+                "http://localhost:8080/health",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_results_csv() {
+        let urls = vec![
+            "https://example.com/".to_string(),
+            "https://down.example/".to_string(),
+            "https://slow.example/".to_string(),
+        ];
+        let outcomes = vec![
+            Some(BulkOutcome::Response {
+                status: 200,
+                elapsed: Duration::from_millis(42),
+                size: 1256,
+            }),
+            Some(BulkOutcome::Failed("connection refused, retry".to_string())),
+            None,
+        ];
+        assert_eq!(
+            results_csv(&urls, &outcomes),
+            "URL,Status,Latency (ms),Size (bytes),Error\r\n\
+             https://example.com/,200,42,1256,\r\n\
+             https://down.example/,,,,\"connection refused, retry\"\r\n\
+             https://slow.example/,,,,\r\n"
+        );
+    }
+
+    #[test]
+    fn test_run_bulk_reports_every_url() {
+        // Invalid URLs fail without touching the network
+        let urls = vec![" ".to_string(), "not a url".to_string()];
+        let mut outcomes = run_bulk(urls, 2);
+        let mut seen = Vec::new();
+        while let Some((index, outcome)) = outcomes.blocking_recv() {
+            assert!(matches!(outcome, BulkOutcome::Failed(_)));
+            seen.push(index);
+        }
+        seen.sort();
+        assert_eq!(seen, [0, 1]);
+    }
+}
@@ -1,7 +1,23 @@
 // filepath: /postman-gpui/postman-gpui/src/http/client.rs
 use crate::errors::AppError;
-use reqwest::Client;
-use std::collections::HashMap;
+use crate::http::response::HttpResponse;
+use crate::models::{CertificateData, ClientCertificate, HttpMethod, TransportOptions};
+use crate::utils::base64;
+use reqwest::{Client, Identity, Proxy, RequestBuilder, Response};
+use tokio::sync::mpsc::UnboundedSender;
+
+/// Progress of a streamed response
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamEvent {
+    /// Status line and headers arrived
+    Head {
+        status: u16,
+        headers: Vec<(String, String)>,
+    },
+    Chunk(Vec<u8>),
+    Done,
+    Error(String),
+}
 
 #[derive(Clone)]
 pub struct HttpClient {
@@ -21,48 +37,157 @@ impl HttpClient {
         }
     }
 
+    /// Client connecting through a proxy, accepting invalid certificates
+    /// and/or presenting a client certificate (the one named in `options`)
+    pub fn with_transport(
+        options: &TransportOptions,
+        certificate: Option<&ClientCertificate>,
+    ) -> Result<Self, AppError> {
+        let mut builder =
+            Client::builder().danger_accept_invalid_certs(options.accept_invalid_certs);
+        if let Some(proxy) = &options.proxy {
+            let proxy = Proxy::all(proxy.as_str())
+                .map_err(|e| AppError::ValidationError(format!("Invalid proxy {proxy}: {e}")))?;
+            builder = builder.proxy(proxy);
+        }
+        if let Some(certificate) = certificate {
+            builder = builder.identity(client_identity(certificate)?);
+        }
+        Ok(HttpClient {
+            client: builder.build()?,
+        })
+    }
+
     pub async fn get(&self, url: &str) -> Result<String, AppError> {
-        self.get_with_headers(url, None).await
+        self.get_with_headers(url, Vec::new()).await
     }
 
     pub async fn get_with_headers(
         &self,
         url: &str,
-        headers: Option<HashMap<String, String>>,
+        headers: Vec<(String, String)>,
     ) -> Result<String, AppError> {
-        let mut request = self.client.get(url);
-
-        if let Some(h) = headers {
-            for (key, value) in h {
-                request = request.header(key, value);
-            }
-        }
-
-        let response = request.send().await?;
-        let body = response.text().await?;
-        Ok(body)
+        let response = self.send(HttpMethod::GET, url, headers, None).await?;
+        Ok(response.body)
     }
 
     pub async fn post(
         &self,
         url: &str,
         body: &str,
-        headers: Option<HashMap<String, String>>,
+        headers: Vec<(String, String)>,
     ) -> Result<String, AppError> {
-        let mut request = self.client.post(url).body(body.to_string());
+        let response = self
+            .send(
+                HttpMethod::POST,
+                url,
+                headers,
+                Some(body.as_bytes().to_vec()),
+            )
+            .await?;
+        Ok(response.body)
+    }
+
+    /// Send a request and keep the status code and response headers
+    pub async fn send(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse, AppError> {
+        let response = self.build(method, url, headers, body)?.send().await?;
+        let status = response.status().as_u16();
+        let headers = response_headers(&response);
+        let body = response.bytes().await?.to_vec();
+        Ok(HttpResponse::from_bytes(status, headers, body))
+    }
 
-        if let Some(h) = headers {
-            for (key, value) in h {
-                request = request.header(key, value);
+    /// Send a request and report the response through `events` as it arrives.
+    ///
+    /// Stops early, without an error, once the receiver is dropped.
+    pub async fn send_streaming(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+        events: &UnboundedSender<StreamEvent>,
+    ) -> Result<(), AppError> {
+        let mut response = self.build(method, url, headers, body)?.send().await?;
+        let head = StreamEvent::Head {
+            status: response.status().as_u16(),
+            headers: response_headers(&response),
+        };
+        if events.send(head).is_err() {
+            return Ok(());
+        }
+        while let Some(chunk) = response.chunk().await? {
+            if events.send(StreamEvent::Chunk(chunk.to_vec())).is_err() {
+                return Ok(());
             }
         }
+        let _ = events.send(StreamEvent::Done);
+        Ok(())
+    }
 
-        let response = request.send().await?;
-        let response_body = response.text().await?;
-        Ok(response_body)
+    fn build(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+    ) -> Result<RequestBuilder, AppError> {
+        let method = reqwest::Method::from_bytes(method.to_string().as_bytes())
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        let mut request = self.client.request(method, url);
+
+        // Appended in order, so repeated headers are all sent
+        for (key, value) in headers {
+            request = request.header(key, value);
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        }
+        Ok(request)
     }
 }
 
+/// TLS identity for a client certificate; also used to check a certificate
+/// when it is imported
+pub fn client_identity(certificate: &ClientCertificate) -> Result<Identity, AppError> {
+    let identity = match &certificate.data {
+        CertificateData::Pkcs12 { der, password } => {
+            let der = base64::decode(der).ok_or_else(|| {
+                AppError::ParseError(format!("Certificate {} is corrupted", certificate.name))
+            })?;
+            Identity::from_pkcs12_der(&der, password)
+        }
+        CertificateData::Pem { certificate, key } => {
+            Identity::from_pkcs8_pem(certificate.as_bytes(), key.as_bytes())
+        }
+    };
+    identity.map_err(|e| {
+        AppError::ValidationError(format!(
+            "Certificate {} can't be used: {e}",
+            certificate.name
+        ))
+    })
+}
+
+fn response_headers(response: &Response) -> Vec<(String, String)> {
+    response
+        .headers()
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -80,4 +205,63 @@ mod tests {
         // Verify that default implementation works
         assert!(std::mem::size_of_val(&client) > 0);
     }
+
+    #[test]
+    fn test_headers_keep_their_order_and_repeats() {
+        let headers = vec![
+            ("X-Trace".to_string(), "1".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+            ("Accept".to_string(), "text/plain".to_string()),
+        ];
+        let request = HttpClient::new()
+            .build(
+                HttpMethod::DELETE,
+                "https://example.com/items/1",
+                headers,
+                None,
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        assert_eq!(request.method(), reqwest::Method::DELETE);
+        let sent: Vec<(&str, &str)> = request
+            .headers()
+            .iter()
+            .map(|(key, value)| (key.as_str(), value.to_str().unwrap()))
+            .collect();
+        assert_eq!(
+            sent,
+            [
+                ("x-trace", "1"),
+                ("accept", "application/json"),
+                ("accept", "text/plain"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_client_with_transport() {
+        let options = TransportOptions {
+            proxy: Some("http://127.0.0.1:3128".to_string()),
+            accept_invalid_certs: true,
+            client_certificate: None,
+        };
+        assert!(HttpClient::with_transport(&options, None).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_client_certificate_is_rejected() {
+        let certificate = ClientCertificate {
+            name: "broken".to_string(),
+            source: "broken.p12".to_string(),
+            data: CertificateData::Pkcs12 {
+                der: base64::encode(b"not a bundle"),
+                password: String::new(),
+            },
+        };
+        assert!(matches!(
+            client_identity(&certificate),
+            Err(AppError::ValidationError(_))
+        ));
+    }
 }
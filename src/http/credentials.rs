@@ -0,0 +1,96 @@
+//! Headers that carry credentials, and when they may go along to another
+//! URL: only to the origin (scheme, host and port) they were set up for, so
+//! a link or `next` URL in a response can't collect them for another host.
+
+/// Headers whose values are credentials
+pub const CREDENTIAL_HEADERS: &[&str] = &[
+    "authorization",
+    "proxy-authorization",
+    "cookie",
+    "x-api-key",
+    "x-auth-token",
+];
+
+/// Whether header `key` carries a credential: one of [`CREDENTIAL_HEADERS`]
+/// or any API key / token header
+pub fn is_credential_header(key: &str) -> bool {
+    let key = key.trim().to_ascii_lowercase();
+    CREDENTIAL_HEADERS.contains(&key.as_str())
+        || key.contains("api-key")
+        || key.contains("apikey")
+        || key.contains("token")
+}
+
+/// Whether `a` and `b` have the same scheme, host and port (a default port
+/// counts as given); URLs that can't be parsed never match
+pub fn same_origin(a: &str, b: &str) -> bool {
+    match (reqwest::Url::parse(a.trim()), reqwest::Url::parse(b.trim())) {
+        (Ok(a), Ok(b)) => a.origin().is_tuple() && a.origin() == b.origin(),
+        _ => false,
+    }
+}
+
+/// `headers` to send to `to` when they were set up for `from`: all of them
+/// on the same origin, the ones without credentials otherwise
+pub fn headers_for(from: &str, to: &str, headers: &[(String, String)]) -> Vec<(String, String)> {
+    if same_origin(from, to) {
+        return headers.to_vec();
+    }
+    headers
+        .iter()
+        .filter(|(key, _)| !is_credential_header(key))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_credential_headers() {
+        assert!(is_credential_header("Authorization"));
+        assert!(is_credential_header(" cookie "));
+        assert!(is_credential_header("X-Goog-Api-Key"));
+        assert!(is_credential_header("X-CSRF-Token"));
+        assert!(!is_credential_header("Accept"));
+    }
+
+    #[test]
+    fn test_same_origin() {
+        assert!(same_origin(
+            "https://api.example.com/items",
+            "https://api.example.com:443/items?page=2"
+        ));
+        assert!(!same_origin(
+            "https://api.example.com/items",
+            "http://api.example.com/items"
+        ));
+        assert!(!same_origin(
+            "https://api.example.com/items",
+            "https://evil.example.net/items"
+        ));
+        assert!(!same_origin(
+            "http://localhost:8080/",
+            "http://localhost:8081/"
+        ));
+        assert!(!same_origin("not a url", "not a url"));
+    }
+
+    #[test]
+    fn test_headers_for_another_origin_drop_credentials() {
+        let headers = vec![
+            ("Authorization".to_string(), "Bearer abc".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+        ];
+        let from = "https://api.example.com/items";
+        assert_eq!(
+            headers_for(from, "https://api.example.com/next", &headers),
+            headers
+        );
+        assert_eq!(
+            headers_for(from, "https://collector.example.net/next", &headers),
+            [("Accept".to_string(), "application/json".to_string())]
+        );
+    }
+}
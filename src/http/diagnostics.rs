@@ -0,0 +1,657 @@
+//! Network diagnostics for failed requests - DNS records of the host, a TCP
+//! connect test of every address and optionally a traceroute, to show where
+//! between the name and the server the request stops.
+
+use crate::errors::AppError;
+use crate::utils::hooks::run_command;
+use std::fmt::Write as _;
+use std::io::{Read, Write};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, TcpStream, ToSocketAddrs, UdpSocket};
+use std::process::Command;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// How long to wait for each DNS answer and TCP connection
+pub const DIAGNOSTICS_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// How long traceroute may run
+pub const TRACEROUTE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Name server asked for the records when the system's can't be found (no
+/// `/etc/resolv.conf`, as on Windows and some macOS setups) - can be changed
+/// in the diagnostics pane
+pub const DEFAULT_FALLBACK_NAMESERVER: &str = "1.1.1.1";
+
+/// Record types looked up for the host
+const RECORD_TYPES: [RecordType; 6] = [
+    RecordType::A,
+    RecordType::Aaaa,
+    RecordType::Cname,
+    RecordType::Mx,
+    RecordType::Txt,
+    RecordType::Ns,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordType {
+    A,
+    Aaaa,
+    Cname,
+    Mx,
+    Txt,
+    Ns,
+}
+
+impl RecordType {
+    fn code(self) -> u16 {
+        match self {
+            Self::A => 1,
+            Self::Ns => 2,
+            Self::Cname => 5,
+            Self::Mx => 15,
+            Self::Txt => 16,
+            Self::Aaaa => 28,
+        }
+    }
+
+    fn from_code(code: u16) -> Option<Self> {
+        RECORD_TYPES.into_iter().find(|kind| kind.code() == code)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::A => "A",
+            Self::Aaaa => "AAAA",
+            Self::Cname => "CNAME",
+            Self::Mx => "MX",
+            Self::Txt => "TXT",
+            Self::Ns => "NS",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DnsRecord {
+    pub kind: RecordType,
+    pub value: String,
+    pub ttl: u32,
+}
+
+/// Outcome of connecting to one address of the host
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConnectAttempt {
+    pub address: SocketAddr,
+    pub result: Result<Duration, String>,
+}
+
+/// Everything found out about the host of a failed request
+#[derive(Debug, Clone)]
+pub struct DiagnosticsReport {
+    pub host: String,
+    pub port: u16,
+    /// Name server the records were asked from, `None` when only the
+    /// system resolver could be used
+    pub nameserver: Option<SocketAddr>,
+    /// `nameserver` is the fallback, not the system's name server
+    pub fallback_nameserver: bool,
+    /// Records by type, or why the lookup failed
+    pub records: Vec<(RecordType, Result<Vec<DnsRecord>, String>)>,
+    /// Addresses from the system resolver (what the request itself used)
+    pub resolved: Result<Vec<SocketAddr>, String>,
+    pub connects: Vec<ConnectAttempt>,
+    pub traceroute: Option<Result<String, String>>,
+}
+
+impl DiagnosticsReport {
+    /// Which step fails, in one sentence
+    pub fn verdict(&self) -> String {
+        match &self.resolved {
+            Err(_) => format!("DNS: {} does not resolve to an address", self.host),
+            Ok(addresses) if addresses.is_empty() => {
+                format!("DNS: {} has no addresses", self.host)
+            }
+            Ok(_) if self.connects.iter().all(|attempt| attempt.result.is_err()) => format!(
+                "TCP: no address of {} accepts connections on port {}",
+                self.host, self.port
+            ),
+            Ok(_) => {
+                "DNS and TCP work - the failure is in TLS, a proxy or the HTTP exchange".to_string()
+            }
+        }
+    }
+
+    /// The report as text for the diagnostics pane
+    pub fn to_text(&self) -> String {
+        let mut text = format!(
+            "Diagnostics for {}:{}\n{}\n\nDNS",
+            self.host,
+            self.port,
+            self.verdict()
+        );
+        match self.nameserver {
+            Some(nameserver) if self.fallback_nameserver => {
+                let _ = writeln!(
+                    text,
+                    " (asked the fallback {nameserver} - no system name server was found)"
+                );
+            }
+            Some(nameserver) => {
+                let _ = writeln!(text, " (asked {nameserver})");
+            }
+            None if self.host.parse::<IpAddr>().is_ok() => {
+                text.push_str(" (an IP address, nothing to look up)\n")
+            }
+            None => text.push_str(
+                " (system resolver only - no system name server was found and no fallback \
+                 is set)\n",
+            ),
+        }
+        for (kind, result) in &self.records {
+            match result {
+                Ok(records) if records.is_empty() => {
+                    let _ = writeln!(text, "  {:<6} none", kind.name());
+                }
+                Ok(records) => {
+                    for record in records {
+                        let _ = writeln!(
+                            text,
+                            "  {:<6} {}  (TTL {})",
+                            kind.name(),
+                            record.value,
+                            record.ttl
+                        );
+                    }
+                }
+                Err(e) => {
+                    let _ = writeln!(text, "  {:<6} failed: {e}", kind.name());
+                }
+            }
+        }
+        if let Err(e) = &self.resolved {
+            let _ = writeln!(text, "  System resolver failed: {e}");
+        }
+
+        text.push_str("\nTCP connect\n");
+        if self.connects.is_empty() {
+            text.push_str("  No addresses to connect to\n");
+        }
+        for attempt in &self.connects {
+            let _ = match &attempt.result {
+                Ok(elapsed) => writeln!(
+                    text,
+                    "  {}  connected in {} ms",
+                    attempt.address,
+                    elapsed.as_millis()
+                ),
+                Err(e) => writeln!(text, "  {}  failed: {e}", attempt.address),
+            };
+        }
+
+        if let Some(traceroute) = &self.traceroute {
+            text.push_str("\nTraceroute\n");
+            match traceroute {
+                Ok(output) => text.push_str(output),
+                Err(e) => text.push_str(&format!("  failed: {e}")),
+            }
+            text.push('\n');
+        }
+        text
+    }
+}
+
+/// Diagnose the host of `url`, asking `fallback` for its records when the
+/// system's name server can't be found; blocks for a few seconds (much
+/// longer with `traceroute`), so call it off the UI thread
+pub fn diagnose(
+    url: &str,
+    traceroute: bool,
+    fallback: Option<SocketAddr>,
+) -> Result<DiagnosticsReport, AppError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| AppError::ValidationError(format!("Invalid URL: {e}")))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| AppError::ValidationError("The URL has no host".to_string()))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = parsed.port_or_known_default().unwrap_or(80);
+    tracing::info!("🩺 Diagnostics - 诊断 {host}:{port}");
+
+    // An IP address needs no lookup
+    let (nameserver, fallback_nameserver) = if host.parse::<IpAddr>().is_ok() {
+        (None, false)
+    } else {
+        match system_nameserver() {
+            Some(nameserver) => (Some(nameserver), false),
+            None => (fallback, fallback.is_some()),
+        }
+    };
+    let records = match nameserver {
+        Some(nameserver) => RECORD_TYPES
+            .iter()
+            .map(|&kind| {
+                let result = query(nameserver, &host, kind, DIAGNOSTICS_TIMEOUT)
+                    .map(|records| {
+                        records
+                            .into_iter()
+                            .filter(|record| record.kind == kind)
+                            .collect()
+                    })
+                    .map_err(|e| e.to_string());
+                (kind, result)
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+
+    let resolved = (host.as_str(), port)
+        .to_socket_addrs()
+        .map(|addresses| addresses.collect::<Vec<_>>())
+        .map_err(|e| e.to_string());
+    let connects = resolved
+        .iter()
+        .flatten()
+        .map(|&address| {
+            let started = Instant::now();
+            let result = TcpStream::connect_timeout(&address, DIAGNOSTICS_TIMEOUT)
+                .map(|_| started.elapsed())
+                .map_err(|e| e.to_string());
+            ConnectAttempt { address, result }
+        })
+        .collect();
+
+    let traceroute = traceroute.then(|| run_traceroute(&host).map_err(|e| e.to_string()));
+    Ok(DiagnosticsReport {
+        host,
+        port,
+        nameserver,
+        fallback_nameserver,
+        records,
+        resolved,
+        connects,
+        traceroute,
+    })
+}
+
+/// Whether `host` can be handed to traceroute: an IP address or a host
+/// name of letters, digits, `-` and `.`, so it can't be taken for an option
+pub fn is_traceroute_host(host: &str) -> bool {
+    if host.parse::<IpAddr>().is_ok() {
+        return true;
+    }
+    !host.is_empty()
+        && host.len() <= 253
+        && host.trim_end_matches('.').split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+// Run the system traceroute without name lookups, one probe per hop
+fn run_traceroute(host: &str) -> Result<String, AppError> {
+    if !is_traceroute_host(host) {
+        return Err(AppError::ValidationError(format!(
+            "\"{host}\" is not a host name or IP address traceroute can be run for"
+        )));
+    }
+    let mut command = if cfg!(windows) {
+        // tracert takes no `--`; the checked host can't start with `-`
+        let mut command = Command::new("tracert");
+        command.args(["-d", "-h", "20", "-w", "2000", host]);
+        command
+    } else {
+        let mut command = Command::new("traceroute");
+        command.args(["-n", "-q", "1", "-w", "2", "-m", "20", "--", host]);
+        command
+    };
+    run_command(command, None, TRACEROUTE_TIMEOUT)
+}
+
+/// Name server typed as an IP address, with an optional port (53 by
+/// default): `1.1.1.1`, `9.9.9.9:5353`, `2606:4700::1111` or `[::1]:53`
+pub fn parse_nameserver(text: &str) -> Result<SocketAddr, AppError> {
+    let text = text.trim();
+    text.parse::<SocketAddr>()
+        .or_else(|_| text.parse::<IpAddr>().map(|ip| SocketAddr::new(ip, 53)))
+        .map_err(|_| {
+            AppError::ValidationError(format!(
+                "\"{text}\" is not a name server address (an IP address, optionally with a port)"
+            ))
+        })
+}
+
+// First `nameserver` of /etc/resolv.conf
+fn system_nameserver() -> Option<SocketAddr> {
+    let config = std::fs::read_to_string("/etc/resolv.conf").ok()?;
+    config.lines().find_map(|line| {
+        let address = line.trim().strip_prefix("nameserver")?.trim();
+        Some(SocketAddr::new(address.parse().ok()?, 53))
+    })
+}
+
+/// Ask `nameserver` for the `kind` records of `name` over UDP, retrying
+/// over TCP when the answer is truncated
+pub fn query(
+    nameserver: SocketAddr,
+    name: &str,
+    kind: RecordType,
+    timeout: Duration,
+) -> Result<Vec<DnsRecord>, AppError> {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.subsec_nanos())
+        .unwrap_or_default();
+    let id = (nanos ^ std::process::id()) as u16;
+    let packet = build_query(id, name, kind)?;
+    let network = |e: std::io::Error| AppError::NetworkError(e.to_string());
+
+    let bind = if nameserver.is_ipv4() {
+        SocketAddr::from((Ipv4Addr::UNSPECIFIED, 0))
+    } else {
+        SocketAddr::from((Ipv6Addr::UNSPECIFIED, 0))
+    };
+    let socket = UdpSocket::bind(bind).map_err(network)?;
+    socket.set_read_timeout(Some(timeout)).map_err(network)?;
+    socket.send_to(&packet, nameserver).map_err(network)?;
+    let mut buffer = [0u8; 4096];
+    let read = socket
+        .recv(&mut buffer)
+        .map_err(|e| AppError::NetworkError(format!("No answer from {nameserver}: {e}")))?;
+    match parse_response(&buffer[..read], id) {
+        Err(ResponseError::Truncated) => {
+            let mut stream = TcpStream::connect_timeout(&nameserver, timeout).map_err(network)?;
+            stream.set_read_timeout(Some(timeout)).map_err(network)?;
+            let mut framed = (packet.len() as u16).to_be_bytes().to_vec();
+            framed.extend_from_slice(&packet);
+            stream.write_all(&framed).map_err(network)?;
+            let mut length = [0u8; 2];
+            stream.read_exact(&mut length).map_err(network)?;
+            let mut answer = vec![0u8; u16::from_be_bytes(length) as usize];
+            stream.read_exact(&mut answer).map_err(network)?;
+            parse_response(&answer, id).map_err(|e| e.into_error())
+        }
+        result => result.map_err(|e| e.into_error()),
+    }
+}
+
+/// Why a DNS answer could not be used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResponseError {
+    /// The answer did not fit into UDP - ask again over TCP
+    Truncated,
+    Failed(String),
+}
+
+impl ResponseError {
+    fn into_error(self) -> AppError {
+        AppError::NetworkError(match self {
+            Self::Truncated => "The DNS answer was truncated".to_string(),
+            Self::Failed(message) => message,
+        })
+    }
+}
+
+/// DNS query packet for `name` asking for recursion
+pub fn build_query(id: u16, name: &str, kind: RecordType) -> Result<Vec<u8>, AppError> {
+    let mut packet = Vec::with_capacity(name.len() + 18);
+    packet.extend_from_slice(&id.to_be_bytes());
+    // Recursion desired, one question
+    packet.extend_from_slice(&[0x01, 0x00, 0, 1, 0, 0, 0, 0, 0, 0]);
+    for label in name.trim_end_matches('.').split('.') {
+        if label.is_empty() || label.len() > 63 {
+            return Err(AppError::ValidationError(format!(
+                "\"{name}\" is not a valid host name"
+            )));
+        }
+        packet.push(label.len() as u8);
+        packet.extend_from_slice(label.as_bytes());
+    }
+    packet.push(0);
+    packet.extend_from_slice(&kind.code().to_be_bytes());
+    // Class IN
+    packet.extend_from_slice(&[0, 1]);
+    Ok(packet)
+}
+
+/// Records in the answer section of a response to query `id`
+pub fn parse_response(packet: &[u8], id: u16) -> Result<Vec<DnsRecord>, ResponseError> {
+    let malformed = || ResponseError::Failed("Malformed DNS answer".to_string());
+    let read_u16 = |at: usize| -> Result<u16, ResponseError> {
+        packet
+            .get(at..at + 2)
+            .map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+            .ok_or_else(malformed)
+    };
+    if read_u16(0)? != id {
+        return Err(ResponseError::Failed(
+            "The DNS answer is for another query".to_string(),
+        ));
+    }
+    let flags = read_u16(2)?;
+    if flags & 0x0200 != 0 {
+        return Err(ResponseError::Truncated);
+    }
+    let failure = match flags & 0x000f {
+        0 => None,
+        3 => Some("No such domain (NXDOMAIN)".to_string()),
+        2 => Some("The name server failed (SERVFAIL)".to_string()),
+        5 => Some("The name server refused the query".to_string()),
+        code => Some(format!("DNS error code {code}")),
+    };
+    if let Some(failure) = failure {
+        return Err(ResponseError::Failed(failure));
+    }
+    let questions = read_u16(4)?;
+    let answers = read_u16(6)?;
+
+    let mut at = 12;
+    for _ in 0..questions {
+        at = read_name(packet, at).ok_or_else(malformed)?.1 + 4;
+    }
+    let mut records = Vec::new();
+    for _ in 0..answers {
+        at = read_name(packet, at).ok_or_else(malformed)?.1;
+        let code = read_u16(at)?;
+        let ttl = packet
+            .get(at + 4..at + 8)
+            .map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+            .ok_or_else(malformed)?;
+        let length = read_u16(at + 8)? as usize;
+        let start = at + 10;
+        let data = packet.get(start..start + length).ok_or_else(malformed)?;
+        at = start + length;
+
+        let Some(kind) = RecordType::from_code(code) else {
+            continue;
+        };
+        let value = match kind {
+            RecordType::A if length == 4 => {
+                Ipv4Addr::new(data[0], data[1], data[2], data[3]).to_string()
+            }
+            RecordType::Aaaa if length == 16 => {
+                let mut octets = [0u8; 16];
+                octets.copy_from_slice(data);
+                Ipv6Addr::from(octets).to_string()
+            }
+            RecordType::Cname | RecordType::Ns => read_name(packet, start).ok_or_else(malformed)?.0,
+            RecordType::Mx => {
+                let preference = read_u16(start)?;
+                let exchange = read_name(packet, start + 2).ok_or_else(malformed)?.0;
+                format!("{preference} {exchange}")
+            }
+            RecordType::Txt => {
+                // One or more length-prefixed strings
+                let mut strings = Vec::new();
+                let mut offset = 0;
+                while let Some(&size) = data.get(offset) {
+                    let text = data
+                        .get(offset + 1..offset + 1 + size as usize)
+                        .ok_or_else(malformed)?;
+                    strings.push(String::from_utf8_lossy(text).into_owned());
+                    offset += 1 + size as usize;
+                }
+                format!("\"{}\"", strings.join(""))
+            }
+            _ => return Err(malformed()),
+        };
+        records.push(DnsRecord { kind, value, ttl });
+    }
+    Ok(records)
+}
+
+// Name starting at `at` (following compression pointers) and the offset
+// just past it
+fn read_name(packet: &[u8], mut at: usize) -> Option<(String, usize)> {
+    let mut labels = Vec::new();
+    let mut end = None;
+    // Pointers only point backwards in valid packets; the limit stops loops
+    for _ in 0..128 {
+        let length = *packet.get(at)? as usize;
+        if length == 0 {
+            let name = if labels.is_empty() {
+                ".".to_string()
+            } else {
+                labels.join(".")
+            };
+            return Some((name, end.unwrap_or(at + 1)));
+        }
+        if length & 0xc0 == 0xc0 {
+            let pointer = ((length & 0x3f) << 8) | *packet.get(at + 1)? as usize;
+            end.get_or_insert(at + 2);
+            at = pointer;
+            continue;
+        }
+        let label = packet.get(at + 1..at + 1 + length)?;
+        labels.push(String::from_utf8_lossy(label).into_owned());
+        at += 1 + length;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Answer to an MX query for example.com with an MX, an A and a TXT record
+    fn sample_response() -> Vec<u8> {
+        let mut packet = vec![0x12, 0x34, 0x81, 0x80, 0, 1, 0, 3, 0, 0, 0, 0];
+        packet.extend_from_slice(b"\x07example\x03com\x00\x00\x0f\x00\x01");
+        // MX 10 mail.example.com (name compressed to the question)
+        packet.extend_from_slice(&[0xc0, 12, 0, 15, 0, 1, 0, 0, 0x0e, 0x10, 0, 9, 0, 10]);
+        packet.extend_from_slice(b"\x04mail\xc0\x0c");
+        // A 93.184.216.34
+        packet.extend_from_slice(&[0xc0, 12, 0, 1, 0, 1, 0, 0, 0, 60, 0, 4, 93, 184, 216, 34]);
+        // TXT "v=spf1" "-all"
+        packet.extend_from_slice(&[0xc0, 12, 0, 16, 0, 1, 0, 0, 0, 60, 0, 13]);
+        packet.extend_from_slice(b"\x06v=spf1\x05 -all");
+        packet
+    }
+
+    #[test]
+    fn test_build_query() {
+        let packet = build_query(0x1234, "example.com.", RecordType::Aaaa).unwrap();
+        assert_eq!(&packet[..4], &[0x12, 0x34, 0x01, 0x00]);
+        assert_eq!(&packet[12..], b"\x07example\x03com\x00\x00\x1c\x00\x01");
+        assert!(build_query(1, "bad..name", RecordType::A).is_err());
+    }
+
+    #[test]
+    fn test_traceroute_hosts() {
+        assert!(is_traceroute_host("api.example.com"));
+        assert!(is_traceroute_host("10.0.0.1"));
+        assert!(is_traceroute_host("2001:db8::1"));
+        assert!(!is_traceroute_host("-w9999"));
+        assert!(!is_traceroute_host("api.-n.example"));
+        assert!(!is_traceroute_host("host;reboot"));
+        assert!(!is_traceroute_host(""));
+        assert!(run_traceroute("-fexample.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_nameserver() {
+        assert_eq!(
+            parse_nameserver(DEFAULT_FALLBACK_NAMESERVER).unwrap(),
+            "1.1.1.1:53".parse().unwrap()
+        );
+        assert_eq!(
+            parse_nameserver(" 9.9.9.9:5353 ").unwrap(),
+            "9.9.9.9:5353".parse().unwrap()
+        );
+        assert_eq!(
+            parse_nameserver("2606:4700::1111").unwrap(),
+            "[2606:4700::1111]:53".parse().unwrap()
+        );
+        assert!(parse_nameserver("dns.example.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let records = parse_response(&sample_response(), 0x1234).unwrap();
+        let values: Vec<(&str, &str)> = records
+            .iter()
+            .map(|record| (record.kind.name(), record.value.as_str()))
+            .collect();
+        assert_eq!(
+            values,
+            [
+                ("MX", "10 mail.example.com"),
+                ("A", "93.184.216.34"),
+                ("TXT", "\"v=spf1 -all\""),
+            ]
+        );
+        assert_eq!(records[0].ttl, 3600);
+
+        assert!(matches!(
+            parse_response(&sample_response(), 0x4321),
+            Err(ResponseError::Failed(_))
+        ));
+        let mut nxdomain = sample_response();
+        nxdomain[3] = 0x83;
+        assert_eq!(
+            parse_response(&nxdomain, 0x1234),
+            Err(ResponseError::Failed(
+                "No such domain (NXDOMAIN)".to_string()
+            ))
+        );
+        let mut truncated = sample_response();
+        truncated[2] |= 0x02;
+        assert_eq!(
+            parse_response(&truncated, 0x1234),
+            Err(ResponseError::Truncated)
+        );
+        assert!(parse_response(&sample_response()[..40], 0x1234).is_err());
+    }
+
+    #[test]
+    fn test_verdict() {
+        let address: SocketAddr = "127.0.0.1:443".parse().unwrap();
+        let mut report = DiagnosticsReport {
+            host: "api.example.com".to_string(),
+            port: 443,
+            nameserver: None,
+            fallback_nameserver: false,
+            records: Vec::new(),
+            resolved: Err("failed to lookup address".to_string()),
+            connects: Vec::new(),
+            traceroute: None,
+        };
+        assert!(report.verdict().starts_with("DNS:"));
+
+        report.resolved = Ok(vec![address]);
+        report.connects = vec![ConnectAttempt {
+            address,
+            result: Err("Connection refused".to_string()),
+        }];
+        assert!(report.verdict().starts_with("TCP:"));
+        assert!(report
+            .to_text()
+            .contains("127.0.0.1:443  failed: Connection refused"));
+
+        report.connects[0].result = Ok(Duration::from_millis(3));
+        assert!(report.verdict().starts_with("DNS and TCP work"));
+    }
+}
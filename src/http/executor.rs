@@ -1,34 +1,66 @@
 use crate::errors::AppError;
-use crate::http::client::HttpClient;
-use crate::models::{HttpMethod, Request};
-use crate::utils::formatter::format_response_body;
-use std::collections::HashMap;
+use crate::http::client::{HttpClient, StreamEvent};
+use crate::http::credentials::is_credential_header;
+use crate::http::response::HttpResponse;
+use crate::http::socket::{self, SocketTarget};
+use crate::models::{ClientCertificate, HttpMethod, Request, TransportOptions};
+use crate::utils::formatter::format_body;
+use crate::utils::sniff::detect;
+use std::fmt;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
 
 /// HTTP 请求执行结果
 #[derive(Debug, Clone)]
 pub struct RequestResult {
     pub status: u16,
+    pub headers: Vec<(String, String)>,
     pub body: String,
+    /// 原始响应体（二进制格式由响应渲染插件解码）
+    pub raw_body: Vec<u8>,
 }
 
 impl RequestResult {
     pub fn success(body: String) -> Self {
-        Self { status: 200, body }
+        Self {
+            status: 200,
+            headers: Vec::new(),
+            raw_body: body.clone().into_bytes(),
+            body,
+        }
     }
 
     pub fn error(message: String) -> Self {
         Self {
             status: 0,
+            headers: Vec::new(),
             body: message,
+            raw_body: Vec::new(),
+        }
+    }
+
+    /// 从 HTTP 响应构建（响应体已格式化）
+    pub fn from_response(response: HttpResponse, body: String) -> Self {
+        Self {
+            status: response.status_code,
+            headers: response.headers,
+            body,
+            raw_body: response.raw_body,
         }
     }
 }
 
 /// HTTP 请求执行器
+#[derive(Clone)]
 pub struct RequestExecutor {
     client: HttpClient,
 }
 
+impl fmt::Debug for RequestExecutor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestExecutor").finish_non_exhaustive()
+    }
+}
+
 impl RequestExecutor {
     pub fn new() -> Self {
         Self {
@@ -36,8 +68,27 @@ impl RequestExecutor {
         }
     }
 
+    /// 使用代理 / TLS 选项和客户端证书的执行器（例如按域名配置的默认值）
+    pub fn with_transport(
+        options: &TransportOptions,
+        certificate: Option<&ClientCertificate>,
+    ) -> Result<Self, AppError> {
+        Ok(Self {
+            client: HttpClient::with_transport(options, certificate)?,
+        })
+    }
+
     /// 执行 HTTP 请求（接受统一的 Request 模型）
     pub fn execute_request(&self, request: &Request) -> Result<RequestResult, AppError> {
+        if let Some(path) = &request.socket {
+            return self.execute_socket(
+                &SocketTarget::parse(path)?,
+                request.method,
+                &request.url,
+                request.headers.clone(),
+                request.body.clone().map(String::into_bytes),
+            );
+        }
         self.execute(
             request.method,
             &request.url,
@@ -53,6 +104,41 @@ impl RequestExecutor {
         url: &str,
         headers: Vec<(String, String)>,
         body: Option<String>,
+    ) -> Result<RequestResult, AppError> {
+        self.execute_bytes(method, url, headers, body.map(String::into_bytes))
+    }
+
+    /// 执行 HTTP 请求（请求体为已序列化的字节，例如插件生成的二进制格式）
+    pub fn execute_bytes(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+    ) -> Result<RequestResult, AppError> {
+        self.execute_via(None, method, url, headers, body)
+    }
+
+    /// 通过 Unix socket 或 Windows named pipe 发送 HTTP 请求（例如 Docker daemon）
+    pub fn execute_socket(
+        &self,
+        socket: &SocketTarget,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+    ) -> Result<RequestResult, AppError> {
+        self.execute_via(Some(socket), method, url, headers, body)
+    }
+
+    /// 通过 socket（有时）或普通 TCP 发送 HTTP 请求
+    pub fn execute_via(
+        &self,
+        socket: Option<&SocketTarget>,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
     ) -> Result<RequestResult, AppError> {
         // 验证URL
         if url.trim().is_empty() {
@@ -63,13 +149,16 @@ impl RequestExecutor {
         tracing::info!("📋 RequestExecutor - 请求详情:");
         tracing::info!("   Method: {}", method);
         tracing::info!("   URL: {}", url);
+        if let Some(socket) = socket {
+            tracing::info!("   Socket: {}", socket);
+        }
         tracing::info!("   Headers Count: {}", headers.len());
 
         // 打印所有headers
         if !headers.is_empty() {
             tracing::info!("   Headers:");
             for (i, (key, value)) in headers.iter().enumerate() {
-                tracing::info!("     {}. {} = {}", i + 1, key, value);
+                tracing::info!("     {}. {} = {}", i + 1, key, loggable_value(key, value));
             }
         } else {
             tracing::info!("   Headers: None");
@@ -82,9 +171,12 @@ impl RequestExecutor {
                 tracing::info!(
                     "   Body Preview: {}",
                     if body_content.len() > 200 {
-                        format!("{}... (truncated)", &body_content[..200])
+                        format!(
+                            "{}... (truncated)",
+                            String::from_utf8_lossy(&body_content[..200])
+                        )
                     } else {
-                        body_content.to_string()
+                        String::from_utf8_lossy(body_content).into_owned()
                     }
                 );
             } else {
@@ -95,75 +187,36 @@ impl RequestExecutor {
         // 使用 tokio 的 block_on 来同步执行异步请求
         let rt = tokio::runtime::Runtime::new().unwrap();
 
-        let result = match method {
-            HttpMethod::GET => {
-                // GET 请求
-                let header_map = if headers.is_empty() {
-                    tracing::info!("🔍 RequestExecutor - 执行GET请求，无自定义headers");
-                    None
-                } else {
-                    let map: HashMap<String, String> = headers.iter().cloned().collect();
-                    tracing::info!(
-                        "🔍 RequestExecutor - 执行GET请求，包含{}个自定义headers",
-                        map.len()
-                    );
-                    Some(map)
-                };
-                rt.block_on(self.client.get_with_headers(url, header_map))
-            }
-            HttpMethod::POST => {
-                // POST 请求
-                let header_map = if headers.is_empty() {
-                    tracing::info!("📝 RequestExecutor - 执行POST请求，无自定义headers");
-                    None
-                } else {
-                    let map: HashMap<String, String> = headers.iter().cloned().collect();
-                    tracing::info!(
-                        "📝 RequestExecutor - POST请求，包含{}个自定义headers",
-                        map.len()
-                    );
-                    Some(map)
-                };
-
-                let body_content = body.unwrap_or_default();
-                tracing::info!(
-                    "📝 RequestExecutor - 执行POST请求，Body大小: {} bytes",
-                    body_content.len()
-                );
-                rt.block_on(self.client.post(url, &body_content, header_map))
-            }
-            HttpMethod::PUT
-            | HttpMethod::DELETE
-            | HttpMethod::PATCH
-            | HttpMethod::HEAD
-            | HttpMethod::OPTIONS => {
-                tracing::info!("⚠️ RequestExecutor - 方法 {} 尚未实现", method);
-                tracing::info!("📋 RequestExecutor - 当前支持的方法: GET, POST");
-                return Err(AppError::ValidationError(format!(
-                    "Unsupported HTTP method: {}. Supported methods are: GET, POST",
-                    method
-                )));
-            }
-        };
+        tracing::info!(
+            "📝 RequestExecutor - 执行{}请求，包含{}个自定义headers，Body大小: {} bytes",
+            method,
+            headers.len(),
+            body.as_ref().map_or(0, Vec::len)
+        );
+        let result = rt.block_on(self.dispatch(socket, method, url, headers, body));
 
         match result {
-            Ok(response_body) => {
+            Ok(response) => {
+                let response_body = response.body.clone();
                 tracing::info!("✅ RequestExecutor - {}请求成功!", method);
                 tracing::info!("📊 RequestExecutor - 响应信息:");
-                tracing::info!("   Status: 200 OK");
+                tracing::info!("   Status: {}", response.status_code);
+                tracing::info!("   Headers Count: {}", response.headers.len());
                 tracing::info!("   Response Length: {} bytes", response_body.len());
                 tracing::info!(
                     "   Response Preview: {}",
-                    if response_body.len() > 300 {
-                        format!("{}... (truncated)", &response_body[..300])
-                    } else {
-                        response_body.clone()
+                    // 按字符截断，避免切在多字节字符中间
+                    match response_body.char_indices().nth(300) {
+                        Some((end, _)) => format!("{}... (truncated)", &response_body[..end]),
+                        None => response_body.clone(),
                     }
                 );
-                // Format the response body (pretty-print JSON if applicable)
-                let formatted_body = format_response_body(&response_body);
+                // Format the response body by what it is, which may not be
+                // what its Content-Type says
+                let kind = detect(response.header("Content-Type"), &response.raw_body);
+                let formatted_body = format_body(kind, &response_body);
 
-                Ok(RequestResult::success(formatted_body))
+                Ok(RequestResult::from_response(response, formatted_body))
             }
             Err(e) => {
                 tracing::info!("❌ RequestExecutor - {}请求失败!", method);
@@ -178,6 +231,58 @@ impl RequestExecutor {
             }
         }
     }
+
+    // 有 socket 时绕过 reqwest 直接经 socket 发送
+    async fn dispatch(
+        &self,
+        socket: Option<&SocketTarget>,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+    ) -> Result<HttpResponse, AppError> {
+        match socket {
+            Some(socket) => socket::send(socket, method, url, headers, body).await,
+            None => self.client.send(method, url, headers, body).await,
+        }
+    }
+
+    /// 在后台线程执行请求，响应以 [`StreamEvent`] 逐块返回（例如 NDJSON 流）
+    ///
+    /// 丢弃接收端即可取消请求
+    pub fn execute_streaming(
+        &self,
+        method: HttpMethod,
+        url: &str,
+        headers: Vec<(String, String)>,
+        body: Option<Vec<u8>>,
+    ) -> UnboundedReceiver<StreamEvent> {
+        let (events, receiver) = unbounded_channel();
+        if url.trim().is_empty() {
+            tracing::info!("❌ RequestExecutor - URL不能为空");
+            let _ = events.send(StreamEvent::Error(AppError::UrlEmpty.to_string()));
+            return receiver;
+        }
+        tracing::info!("🌊 RequestExecutor - 开始流式请求: {} {}", method, url);
+
+        let client = self.client.clone();
+        let url = url.to_string();
+        std::thread::spawn(move || {
+            let rt = match tokio::runtime::Runtime::new() {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = events.send(StreamEvent::Error(e.to_string()));
+                    return;
+                }
+            };
+            let result = rt.block_on(client.send_streaming(method, &url, headers, body, &events));
+            if let Err(e) = result {
+                tracing::info!("❌ RequestExecutor - 流式请求失败: {}", e);
+                let _ = events.send(StreamEvent::Error(e.to_string()));
+            }
+        });
+        receiver
+    }
 }
 
 impl Default for RequestExecutor {
@@ -186,6 +291,15 @@ impl Default for RequestExecutor {
     }
 }
 
+/// Header value as written to the log, with credentials redacted
+fn loggable_value<'a>(key: &str, value: &'a str) -> &'a str {
+    if is_credential_header(key) {
+        "<redacted>"
+    } else {
+        value
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -207,6 +321,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_executor_streaming_validates_empty_url() {
+        let executor = RequestExecutor::new();
+        let mut events = executor.execute_streaming(HttpMethod::GET, " ", vec![], None);
+        assert!(matches!(events.try_recv(), Ok(StreamEvent::Error(_))));
+    }
+
+    #[test]
+    fn test_credential_headers_are_redacted_in_logs() {
+        assert_eq!(loggable_value("Authorization", "Bearer abc"), "<redacted>");
+        assert_eq!(loggable_value("x-api-key", "secret"), "<redacted>");
+        assert_eq!(loggable_value("Cookie", "session=1"), "<redacted>");
+        assert_eq!(loggable_value("Accept", "text/plain"), "text/plain");
+    }
+
     #[test]
     fn test_executor_execute_request_model() {
         let executor = RequestExecutor::new();
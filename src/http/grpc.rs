@@ -0,0 +1,273 @@
+//! gRPC calls over HTTP/2 for unary and streaming methods. Each call runs on
+//! its own thread: client messages go in through [`GrpcCall::send`] and
+//! everything the server sends comes back as [`GrpcEvent`]s.
+
+use crate::errors::AppError;
+use crate::utils::grpc::{encode_frame, FrameDecoder, GrpcStatus};
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper::header::HeaderMap;
+use hyper::{Body, Client, Uri};
+use hyper_tls::HttpsConnector;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+
+/// What happened on a call, in order
+#[derive(Debug, Clone, PartialEq)]
+pub enum GrpcEvent {
+    /// Response headers (initial metadata)
+    Headers(Vec<(String, String)>),
+    /// One serialized response message
+    Message(Vec<u8>),
+    /// The server ended the call
+    Finished {
+        status: GrpcStatus,
+        trailers: Vec<(String, String)>,
+    },
+    /// Transport failure or cancellation; no further events follow
+    Error(String),
+}
+
+/// Full request URI: `localhost:50051` is plaintext HTTP/2, `https://`
+/// negotiates HTTP/2 over TLS
+pub fn grpc_uri(server: &str, path: &str) -> Result<Uri, AppError> {
+    let server = server.trim().trim_end_matches('/');
+    if server.is_empty() {
+        return Err(AppError::ValidationError(
+            "Enter a gRPC server address (e.g. localhost:50051)".to_string(),
+        ));
+    }
+    let path = path.trim().trim_start_matches('/');
+    if path.is_empty() {
+        return Err(AppError::ValidationError(
+            "Enter a method (e.g. package.Service/Method)".to_string(),
+        ));
+    }
+    let uri = if server.contains("://") {
+        format!("{server}/{path}")
+    } else {
+        format!("http://{server}/{path}")
+    };
+    uri.parse::<Uri>()
+        .map_err(|e| AppError::ValidationError(format!("Invalid gRPC address {uri}: {e}")))
+}
+
+/// A running call
+pub struct GrpcCall {
+    messages: Option<UnboundedSender<Vec<u8>>>,
+    cancel: Option<oneshot::Sender<()>>,
+}
+
+impl GrpcCall {
+    /// Open a call to `uri`; `metadata` is sent as request headers
+    pub fn start(
+        uri: Uri,
+        metadata: Vec<(String, String)>,
+    ) -> (Self, UnboundedReceiver<GrpcEvent>) {
+        let (events, receiver) = unbounded_channel();
+        let (messages, outgoing) = unbounded_channel();
+        let (cancel, cancelled) = oneshot::channel();
+        tracing::info!("📡 GrpcCall - 开始调用: {uri}");
+
+        std::thread::spawn(move || {
+            let rt = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = events.send(GrpcEvent::Error(e.to_string()));
+                    return;
+                }
+            };
+            rt.block_on(async {
+                tokio::select! {
+                    result = run_call(uri, metadata, outgoing, &events) => {
+                        if let Err(e) = result {
+                            tracing::info!("❌ GrpcCall - 调用失败: {e}");
+                            let _ = events.send(GrpcEvent::Error(e.to_string()));
+                        }
+                    }
+                    _ = cancelled => {
+                        tracing::info!("🛑 GrpcCall - 调用已取消");
+                        let _ = events.send(GrpcEvent::Error("Call cancelled".to_string()));
+                    }
+                }
+            });
+        });
+
+        let call = Self {
+            messages: Some(messages),
+            cancel: Some(cancel),
+        };
+        (call, receiver)
+    }
+
+    /// Queue a serialized message on the client stream
+    pub fn send(&self, message: Vec<u8>) -> Result<(), AppError> {
+        self.messages
+            .as_ref()
+            .ok_or_else(|| {
+                AppError::ValidationError("The client stream is already closed".to_string())
+            })?
+            .send(message)
+            .map_err(|_| AppError::NetworkError("The call has ended".to_string()))
+    }
+
+    /// Half-close: tell the server no more client messages are coming
+    pub fn end_stream(&mut self) {
+        self.messages = None;
+    }
+
+    pub fn is_client_stream_open(&self) -> bool {
+        self.messages.is_some()
+    }
+
+    /// Reset the call; an `Error` event reports the cancellation
+    pub fn cancel(&mut self) {
+        self.messages = None;
+        if let Some(cancel) = self.cancel.take() {
+            let _ = cancel.send(());
+        }
+    }
+}
+
+async fn run_call(
+    uri: Uri,
+    metadata: Vec<(String, String)>,
+    mut outgoing: UnboundedReceiver<Vec<u8>>,
+    events: &UnboundedSender<GrpcEvent>,
+) -> Result<(), AppError> {
+    let mut http = HttpConnector::new();
+    http.enforce_http(false);
+    let tls = native_tls::TlsConnector::builder()
+        .request_alpns(&["h2"])
+        .build()
+        .map_err(|e| AppError::NetworkError(format!("TLS setup failed: {e}")))?;
+    let client: Client<_, Body> = Client::builder()
+        .http2_only(true)
+        .build(HttpsConnector::from((
+            http,
+            tokio_native_tls::TlsConnector::from(tls),
+        )));
+
+    let (mut body, request_body) = Body::channel();
+    let mut builder = hyper::Request::builder()
+        .method("POST")
+        .uri(uri)
+        .header("content-type", "application/grpc")
+        .header("te", "trailers");
+    for (key, value) in metadata {
+        builder = builder.header(key, value);
+    }
+    let request = builder
+        .body(request_body)
+        .map_err(|e| AppError::ValidationError(format!("Invalid gRPC request: {e}")))?;
+
+    // Client messages are written while the response is read; dropping the
+    // body sender ends the request stream
+    tokio::spawn(async move {
+        while let Some(message) = outgoing.recv().await {
+            if body.send_data(encode_frame(&message).into()).await.is_err() {
+                break;
+            }
+        }
+    });
+
+    let response = client
+        .request(request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("gRPC call failed: {e}")))?;
+    let headers = header_pairs(response.headers());
+    if !response.status().is_success() {
+        return Err(AppError::NetworkError(format!(
+            "Server answered HTTP {} instead of a gRPC response",
+            response.status()
+        )));
+    }
+    // Trailers-only response (usually an immediate error)
+    if let Some(status) = GrpcStatus::from_metadata(&headers) {
+        let _ = events.send(GrpcEvent::Finished {
+            status,
+            trailers: headers,
+        });
+        return Ok(());
+    }
+    if events.send(GrpcEvent::Headers(headers)).is_err() {
+        return Ok(());
+    }
+
+    let mut body = response.into_body();
+    let mut decoder = FrameDecoder::new();
+    while let Some(chunk) = body.data().await {
+        let chunk =
+            chunk.map_err(|e| AppError::NetworkError(format!("gRPC stream failed: {e}")))?;
+        for message in decoder.push(&chunk)? {
+            if events.send(GrpcEvent::Message(message)).is_err() {
+                return Ok(());
+            }
+        }
+    }
+    let trailers = body
+        .trailers()
+        .await
+        .map_err(|e| AppError::NetworkError(format!("gRPC stream failed: {e}")))?
+        .map(|trailers| header_pairs(&trailers))
+        .unwrap_or_default();
+    let status = GrpcStatus::from_metadata(&trailers).unwrap_or_else(|| GrpcStatus {
+        code: 2,
+        message: if decoder.has_partial() {
+            "Stream ended in the middle of a message".to_string()
+        } else {
+            "Server closed the stream without a grpc-status".to_string()
+        },
+    });
+    let _ = events.send(GrpcEvent::Finished { status, trailers });
+    Ok(())
+}
+
+fn header_pairs(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grpc_uri() {
+        assert_eq!(
+            grpc_uri("localhost:50051", "/demo.Users/Get")
+                .unwrap()
+                .to_string(),
+            "http://localhost:50051/demo.Users/Get"
+        );
+        assert_eq!(
+            grpc_uri("https://api.example.com/", "demo.Users/Chat")
+                .unwrap()
+                .to_string(),
+            "https://api.example.com/demo.Users/Chat"
+        );
+        assert!(grpc_uri(" ", "/demo.Users/Get").is_err());
+        assert!(grpc_uri("localhost:50051", "/").is_err());
+    }
+
+    #[test]
+    fn test_send_after_end_stream_fails() {
+        let uri = grpc_uri("127.0.0.1:9", "/demo.Users/Get").unwrap();
+        let (mut call, _events) = GrpcCall::start(uri, Vec::new());
+        assert!(call.is_client_stream_open());
+        call.end_stream();
+        assert!(!call.is_client_stream_open());
+        assert!(call.send(Vec::new()).is_err());
+        call.cancel();
+    }
+}
@@ -0,0 +1,117 @@
+//! Reachability checks of the profiles' base URLs - a `HEAD` request whose
+//! answer (any status below 500) counts as healthy.
+
+use crate::http::raw::{send_raw, RawTarget};
+use std::time::Duration;
+
+/// Time between two checks of the same base URL
+pub const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a check waits to connect and for the response
+pub const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of the last check of a base URL
+#[derive(Debug, Clone, PartialEq)]
+pub enum Health {
+    Up { status: u16, elapsed: Duration },
+    Down(String),
+}
+
+impl Health {
+    pub fn is_up(&self) -> bool {
+        matches!(self, Self::Up { .. })
+    }
+
+    /// One line for the indicator's label
+    pub fn summary(&self) -> String {
+        match self {
+            Self::Up { status, elapsed } => {
+                format!("Reachable (HTTP {status} in {} ms)", elapsed.as_millis())
+            }
+            Self::Down(reason) => format!("Unreachable: {reason}"),
+        }
+    }
+}
+
+/// Send a `HEAD` request to `url` and classify the answer
+pub fn check_health(url: &str, timeout: Duration) -> Health {
+    let target = match RawTarget::parse(url) {
+        Ok(target) => target,
+        Err(e) => return Health::Down(e.to_string()),
+    };
+    let request = head_request(url);
+    match send_raw(&target, request.as_bytes(), timeout) {
+        Ok(response) => health_from_response(&response.bytes, response.elapsed),
+        Err(e) => Health::Down(e.to_string()),
+    }
+}
+
+// `HEAD` request for the path of `url`, closing the connection after the answer
+fn head_request(url: &str) -> String {
+    let (host, target) = match reqwest::Url::parse(url.trim()) {
+        Ok(parsed) => {
+            let host = parsed.host_str().unwrap_or_default().to_string();
+            let host = match parsed.port() {
+                Some(port) => format!("{host}:{port}"),
+                None => host,
+            };
+            let target = match parsed.query() {
+                Some(query) => format!("{}?{query}", parsed.path()),
+                None => parsed.path().to_string(),
+            };
+            (host, target)
+        }
+        Err(_) => (String::new(), "/".to_string()),
+    };
+    format!(
+        "HEAD {target} HTTP/1.1\r\nHost: {host}\r\nUser-Agent: postman-gpui health check\r\nConnection: close\r\n\r\n"
+    )
+}
+
+/// Healthy when the bytes start with an HTTP status line below 500
+pub fn health_from_response(bytes: &[u8], elapsed: Duration) -> Health {
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(64)]).into_owned();
+    let status = head
+        .strip_prefix("HTTP/")
+        .and_then(|rest| rest.split_whitespace().nth(1))
+        .and_then(|code| code.parse::<u16>().ok());
+    match status {
+        Some(status) if status < 500 => Health::Up { status, elapsed },
+        Some(status) => Health::Down(format!("HTTP {status}")),
+        None => Health::Down("The server did not answer with HTTP".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_head_request() {
+        assert_eq!(
+            head_request("https://api.example.com:8443/v1/health?deep=1"),
+            "HEAD /v1/health?deep=1 HTTP/1.1\r\nHost: api.example.com:8443\r\n\
+             User-Agent: postman-gpui health check\r\nConnection: close\r\n\r\n"
+        );
+        assert!(head_request("http://example.com")
+            .starts_with("HEAD / HTTP/1.1\r\nHost: example.com\r\n"));
+    }
+
+    #[test]
+    fn test_health_from_response() {
+        let elapsed = Duration::from_millis(12);
+        let health = health_from_response(b"HTTP/1.1 404 Not Found\r\n\r\n", elapsed);
+        assert_eq!(
+            health,
+            Health::Up {
+                status: 404,
+                elapsed
+            }
+        );
+        assert_eq!(health.summary(), "Reachable (HTTP 404 in 12 ms)");
+
+        let health = health_from_response(b"HTTP/2 503\r\n\r\n", elapsed);
+        assert_eq!(health, Health::Down("HTTP 503".to_string()));
+        assert!(!health_from_response(b"SSH-2.0-OpenSSH", elapsed).is_up());
+    }
+}
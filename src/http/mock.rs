@@ -0,0 +1,482 @@
+//! Local mock server built from saved response examples: every request that
+//! has examples becomes a route answering with one of them.
+//!
+//! Path segments written as `{id}`, `:id` or `{{id}}` match any value, and a
+//! number or UUID saved in a URL (`/users/42`) matches any other id, so an
+//! example saved from a real send also answers for other records. The
+//! answering example is the one named by the `X-Mock-Example` header or the
+//! `example` query parameter, else the first with the status asked for in
+//! `X-Mock-Status`, else the first successful one.
+
+use crate::errors::AppError;
+use crate::models::{ExampleLibrary, HttpMethod, ResponseExample};
+use crate::utils::links::looks_like_id;
+use crate::utils::status_codes::reason_phrase;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+/// Port the mock server listens on unless another is entered
+pub const DEFAULT_MOCK_PORT: u16 = 4010;
+
+/// Requests with a longer head are answered with 431
+const MAX_HEAD_BYTES: usize = 64 * 1024;
+
+// How often the accept loop checks whether it was stopped
+const ACCEPT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+// Response headers recomputed for the mock's own body and connection
+const HOP_HEADERS: [&str; 4] = [
+    "content-length",
+    "transfer-encoding",
+    "content-encoding",
+    "connection",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    /// A saved number or UUID; matches itself best, any other id too
+    Id(String),
+    /// `{name}`, `:name` or `{{name}}`
+    Param,
+}
+
+/// The examples of one request, answering requests whose path fits
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockRoute {
+    pub method: HttpMethod,
+    /// Path of the saved URL, e.g. `/users/{id}`
+    pub path: String,
+    segments: Vec<Segment>,
+    pub examples: Vec<ResponseExample>,
+}
+
+impl MockRoute {
+    pub fn new(method: HttpMethod, url: &str, examples: Vec<ResponseExample>) -> Self {
+        let path = route_path(url);
+        let segments = path
+            .split('/')
+            .filter(|segment| !segment.is_empty())
+            .map(|segment| {
+                if (segment.starts_with('{') && segment.ends_with('}'))
+                    || (segment.starts_with(':') && segment.len() > 1)
+                {
+                    Segment::Param
+                } else if looks_like_id(segment) {
+                    Segment::Id(segment.to_string())
+                } else {
+                    Segment::Literal(segment.to_string())
+                }
+            })
+            .collect();
+        Self {
+            method,
+            path,
+            segments,
+            examples,
+        }
+    }
+
+    /// How well `path` fits the route, `None` if it doesn't; exact segments
+    /// count more than parameters
+    fn score(&self, path: &str) -> Option<usize> {
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        if segments.len() != self.segments.len() {
+            return None;
+        }
+        segments
+            .iter()
+            .zip(&self.segments)
+            .map(|(segment, part)| match part {
+                Segment::Literal(literal) | Segment::Id(literal) if literal == segment => Some(2),
+                Segment::Id(_) if looks_like_id(segment) => Some(1),
+                Segment::Param => Some(1),
+                _ => None,
+            })
+            .sum()
+    }
+
+    fn choose(&self, name: Option<&str>, status: Option<u16>) -> Option<&ResponseExample> {
+        name.and_then(|name| {
+            self.examples
+                .iter()
+                .find(|example| example.name.eq_ignore_ascii_case(name))
+        })
+        .or_else(|| {
+            status.and_then(|status| {
+                self.examples
+                    .iter()
+                    .find(|example| example.response.status == status)
+            })
+        })
+        .or_else(|| {
+            self.examples
+                .iter()
+                .find(|example| (200..300).contains(&example.response.status))
+        })
+        .or_else(|| self.examples.first())
+    }
+}
+
+/// One route per request with examples
+pub fn routes_from_examples(library: &ExampleLibrary) -> Vec<MockRoute> {
+    library
+        .requests()
+        .map(|(method, url, examples)| MockRoute::new(method, url, examples.to_vec()))
+        .collect()
+}
+
+/// Path of a saved URL: scheme, host (or a leading `{{baseUrl}}`
+/// variable), query and fragment dropped
+fn route_path(url: &str) -> String {
+    let url = url.trim();
+    let rest = match url.split_once("://") {
+        Some((_, rest)) => rest.find('/').map_or("", |index| &rest[index..]),
+        None if url.starts_with("{{") => url.find("}}").map_or("", |index| &url[index + 2..]),
+        None => url.find('/').map_or("", |index| &url[index..]),
+    };
+    let path = rest.split(['?', '#']).next().unwrap_or_default();
+    if path.is_empty() {
+        "/".to_string()
+    } else {
+        path.to_string()
+    }
+}
+
+/// What the mock answers
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockResponse {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+}
+
+impl MockResponse {
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), "application/json".to_string())],
+            body: serde_json::json!({ "error": message }).to_string(),
+        }
+    }
+
+    fn to_bytes(&self, head_only: bool) -> Vec<u8> {
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\n",
+            self.status,
+            reason_phrase(self.status)
+        );
+        for (key, value) in &self.headers {
+            head.push_str(&format!("{key}: {value}\r\n"));
+        }
+        head.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.body.len()
+        ));
+        let mut bytes = head.into_bytes();
+        if !head_only {
+            bytes.extend_from_slice(self.body.as_bytes());
+        }
+        bytes
+    }
+}
+
+/// Answer a request (`target` is the path with its query) from the routes
+pub fn respond(
+    routes: &[MockRoute],
+    method: &str,
+    target: &str,
+    headers: &[(String, String)],
+) -> MockResponse {
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    // HEAD gets the headers of the GET example
+    let method = match HttpMethod::from_str(method) {
+        Ok(HttpMethod::HEAD) => HttpMethod::GET,
+        Ok(method) => method,
+        Err(_) => return MockResponse::error(501, &format!("Unsupported method {method}")),
+    };
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_string())
+    };
+    let name = header("X-Mock-Example").or_else(|| {
+        form_urlencoded::parse(query.as_bytes())
+            .find(|(key, _)| key == "example")
+            .map(|(_, value)| value.into_owned())
+    });
+    let status = header("X-Mock-Status").and_then(|status| status.parse().ok());
+
+    let route = routes
+        .iter()
+        .filter(|route| route.method == method)
+        .filter_map(|route| route.score(path).map(|score| (score, route)))
+        // The first of the best-fitting routes
+        .fold(
+            None,
+            |best: Option<(usize, &MockRoute)>, (score, route)| match best {
+                Some((best_score, _)) if best_score >= score => best,
+                _ => Some((score, route)),
+            },
+        )
+        .map(|(_, route)| route);
+    let Some(example) = route.and_then(|route| route.choose(name.as_deref(), status)) else {
+        return MockResponse::error(404, &format!("No example saved for {method} {path}"));
+    };
+
+    let mut response_headers: Vec<(String, String)> = example
+        .response
+        .headers
+        .iter()
+        .filter(|(key, _)| !HOP_HEADERS.contains(&key.to_ascii_lowercase().as_str()))
+        .cloned()
+        .collect();
+    if !response_headers
+        .iter()
+        .any(|(key, _)| key.eq_ignore_ascii_case("content-type"))
+    {
+        let content_type =
+            if serde_json::from_str::<serde_json::Value>(&example.response.body).is_ok() {
+                "application/json"
+            } else {
+                "text/plain; charset=utf-8"
+            };
+        response_headers.push(("Content-Type".to_string(), content_type.to_string()));
+    }
+    response_headers.push(("X-Mock-Example".to_string(), example.name.clone()));
+    MockResponse {
+        status: example.response.status,
+        headers: response_headers,
+        body: example.response.body.clone(),
+    }
+}
+
+/// A running mock server on `127.0.0.1`; dropping it stops the server
+#[derive(Debug)]
+pub struct MockServer {
+    port: u16,
+    routes: Arc<RwLock<Vec<MockRoute>>>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl MockServer {
+    /// Listen on `port` (0 picks a free one) and answer from `routes` until
+    /// stopped
+    pub fn start(port: u16, routes: Vec<MockRoute>) -> Result<Self, AppError> {
+        let listener = TcpListener::bind(("127.0.0.1", port))
+            .map_err(|e| AppError::NetworkError(format!("Could not listen on port {port}: {e}")))?;
+        let port = listener.local_addr()?.port();
+        listener.set_nonblocking(true)?;
+        let stopped = Arc::new(AtomicBool::new(false));
+        let count = routes.len();
+        let routes = Arc::new(RwLock::new(routes));
+        let server = Self {
+            port,
+            routes: routes.clone(),
+            stopped: stopped.clone(),
+        };
+
+        std::thread::spawn(move || {
+            while !stopped.load(Ordering::SeqCst) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let routes = routes.clone();
+                        std::thread::spawn(move || {
+                            if let Err(e) = serve(stream, &routes) {
+                                tracing::info!("⚠️ MockServer - 处理请求失败: {e}");
+                            }
+                        });
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => {
+                        std::thread::sleep(ACCEPT_POLL_INTERVAL)
+                    }
+                    Err(e) => {
+                        tracing::info!("⚠️ MockServer - 接受连接失败: {e}");
+                        std::thread::sleep(ACCEPT_POLL_INTERVAL);
+                    }
+                }
+            }
+            tracing::info!("🛑 MockServer - 已停止 (端口 {port})");
+        });
+        tracing::info!("🎭 MockServer - 监听 127.0.0.1:{port}，{count} 个路由");
+        Ok(server)
+    }
+
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Number of routes served
+    pub fn routes(&self) -> usize {
+        self.routes.read().unwrap().len()
+    }
+
+    /// Answer from `routes` from now on, e.g. after an example was saved
+    pub fn set_routes(&self, routes: Vec<MockRoute>) {
+        *self.routes.write().unwrap() = routes;
+    }
+
+    /// Base URL to send requests to
+    pub fn url(&self) -> String {
+        format!("http://127.0.0.1:{}", self.port)
+    }
+
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for MockServer {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+// Read one request from the connection and answer it
+fn serve(mut stream: TcpStream, routes: &RwLock<Vec<MockRoute>>) -> std::io::Result<()> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(Duration::from_secs(10)))?;
+
+    let mut head = Vec::new();
+    let mut buffer = [0u8; 4096];
+    let head_end = loop {
+        if let Some(index) = head.windows(4).position(|window| window == b"\r\n\r\n") {
+            break index;
+        }
+        if head.len() > MAX_HEAD_BYTES {
+            let response = MockResponse::error(431, "Request headers too large");
+            return stream.write_all(&response.to_bytes(false));
+        }
+        match stream.read(&mut buffer)? {
+            0 => return Ok(()),
+            read => head.extend_from_slice(&buffer[..read]),
+        }
+    };
+
+    let text = String::from_utf8_lossy(&head[..head_end]);
+    let mut lines = text.split("\r\n");
+    let mut request_line = lines.next().unwrap_or_default().split_whitespace();
+    let (method, target) = (
+        request_line.next().unwrap_or_default(),
+        request_line.next().unwrap_or("/"),
+    );
+    let headers: Vec<(String, String)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+        .collect();
+
+    // The request body is not needed, but read it so the client sees no reset
+    let length: usize = headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.parse().ok())
+        .unwrap_or(0);
+    let mut remaining = length.saturating_sub(head.len() - head_end - 4);
+    while remaining > 0 {
+        match stream.read(&mut buffer)? {
+            0 => break,
+            read => remaining = remaining.saturating_sub(read),
+        }
+    }
+
+    let response = respond(&routes.read().unwrap(), method, target, &headers);
+    tracing::info!("🎭 MockServer - {method} {target} -> {}", response.status);
+    stream.write_all(&response.to_bytes(method.eq_ignore_ascii_case("HEAD")))?;
+    stream.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::SavedResponse;
+
+    fn example(name: &str, status: u16, body: &str) -> ResponseExample {
+        ResponseExample::new(name, SavedResponse::new(status, body))
+    }
+
+    #[test]
+    fn test_route_paths() {
+        assert_eq!(
+            route_path("https://api.example.com/users/1?x=1"),
+            "/users/1"
+        );
+        assert_eq!(route_path("{{baseUrl}}/users/{id}"), "/users/{id}");
+        assert_eq!(route_path("https://api.example.com"), "/");
+        assert_eq!(route_path("/health#top"), "/health");
+    }
+
+    #[test]
+    fn test_respond_matches_paths_and_picks_examples() {
+        let routes = vec![
+            MockRoute::new(
+                HttpMethod::GET,
+                "https://api.example.com/users/1",
+                vec![
+                    example("Missing", 404, r#"{"error": "not found"}"#),
+                    example("Ada", 200, r#"{"id": 1, "name": "Ada"}"#),
+                ],
+            ),
+            MockRoute::new(
+                HttpMethod::GET,
+                "https://api.example.com/users/me",
+                vec![example("Me", 200, r#"{"id": 7}"#)],
+            ),
+            MockRoute::new(
+                HttpMethod::POST,
+                "{{baseUrl}}/users/:id/avatar",
+                vec![example("Uploaded", 201, "ok")],
+            ),
+        ];
+
+        // Another id matches the saved one; the successful example answers
+        let response = respond(&routes, "GET", "/users/42", &[]);
+        assert_eq!(
+            (response.status, response.body.as_str()),
+            (200, r#"{"id": 1, "name": "Ada"}"#)
+        );
+        assert!(response
+            .headers
+            .contains(&("Content-Type".to_string(), "application/json".to_string())));
+        assert_eq!(
+            respond(&routes, "GET", "/users/me", &[]).body,
+            r#"{"id": 7}"#
+        );
+
+        let by_name = respond(&routes, "GET", "/users/1?example=missing", &[]);
+        assert_eq!(by_name.status, 404);
+        let by_status = vec![("x-mock-status".to_string(), "404".to_string())];
+        assert_eq!(respond(&routes, "GET", "/users/1", &by_status).status, 404);
+
+        let upload = respond(&routes, "POST", "/users/abc/avatar", &[]);
+        assert_eq!(upload.status, 201);
+        assert!(upload
+            .headers
+            .contains(&("X-Mock-Example".to_string(), "Uploaded".to_string())));
+
+        assert_eq!(respond(&routes, "DELETE", "/users/1", &[]).status, 404);
+        assert_eq!(respond(&routes, "GET", "/orders", &[]).status, 404);
+    }
+
+    #[test]
+    fn test_mock_server_answers_over_tcp() {
+        let routes = vec![MockRoute::new(
+            HttpMethod::GET,
+            "/health",
+            vec![example("Up", 200, "ok")],
+        )];
+        let server = MockServer::start(0, routes).unwrap();
+        let mut stream = TcpStream::connect(("127.0.0.1", server.port())).unwrap();
+        stream
+            .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+        assert!(response.ends_with("\r\n\r\nok"));
+        server.stop();
+    }
+}
@@ -1,4 +1,18 @@
 // src/http/mod.rs
+pub mod bulk;
 pub mod client;
+pub mod credentials;
+pub mod diagnostics;
 pub mod executor;
+pub mod grpc;
+pub mod health;
+pub mod mock;
+pub mod pagination;
+pub mod prepare;
+pub mod raw;
+pub mod replay;
 pub mod response;
+pub mod scenario;
+pub mod size;
+pub mod socket;
+pub mod websocket;
@@ -0,0 +1,444 @@
+//! Following paginated list endpoints: finding the next page of a response
+//! (a `Link` header with `rel="next"`, a next URL or page token in the body,
+//! or `page` / `per_page` numbering) and joining the items of every page.
+
+use crate::http::credentials::headers_for;
+use crate::http::executor::RequestExecutor;
+use crate::models::{Annotation, HighlightColor, HttpMethod};
+use serde_json::Value;
+
+/// Stop following after this many pages
+pub const MAX_PAGES: usize = 50;
+
+// Body fields holding the URL of the next page
+const NEXT_URL_FIELDS: [&str; 8] = [
+    "next",
+    "next_url",
+    "nextUrl",
+    "links.next",
+    "_links.next.href",
+    "paging.next",
+    "pagination.next",
+    "meta.next",
+];
+
+// Body fields holding a token for the next page, and the query parameter
+// it is sent back in
+const NEXT_TOKEN_FIELDS: [(&str, &str); 7] = [
+    ("next_page_token", "page_token"),
+    ("nextPageToken", "pageToken"),
+    ("next_cursor", "cursor"),
+    ("nextCursor", "cursor"),
+    ("response_metadata.next_cursor", "cursor"),
+    ("meta.next_cursor", "cursor"),
+    ("pagination.next_cursor", "cursor"),
+];
+
+// Body fields holding the current page number and the number of pages
+const PAGE_FIELDS: [&str; 4] = ["page", "current_page", "meta.page", "meta.current_page"];
+const TOTAL_PAGES_FIELDS: [&str; 5] = [
+    "total_pages",
+    "totalPages",
+    "last_page",
+    "meta.total_pages",
+    "meta.last_page",
+];
+
+// Query parameters limiting the number of items per page
+const PER_PAGE_PARAMS: [&str; 5] = ["per_page", "perPage", "page_size", "pageSize", "limit"];
+
+// Fields of an object response that usually hold the list
+const LIST_FIELDS: [&str; 7] = [
+    "data", "items", "results", "records", "entries", "values", "elements",
+];
+
+/// The items of one fetched page
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page {
+    pub url: String,
+    pub items: Vec<Value>,
+}
+
+/// URL of the page after the response `body` / `headers` fetched from
+/// `url`, `None` on the last page or when the response isn't paginated
+pub fn next_page_url(url: &str, headers: &[(String, String)], body: &str) -> Option<String> {
+    let base = reqwest::Url::parse(url.trim()).ok()?;
+    let from_header = headers
+        .iter()
+        .filter(|(key, _)| key.eq_ignore_ascii_case("link"))
+        .find_map(|(_, value)| next_link(value));
+    if let Some(link) = from_header {
+        return base.join(&link).ok().map(String::from);
+    }
+
+    let json: Value = serde_json::from_str(body).ok()?;
+    for field in NEXT_URL_FIELDS {
+        let Some(Value::String(next)) = lookup(&json, field) else {
+            continue;
+        };
+        // A bare `next` string is more often a cursor than a URL
+        if next.starts_with("http://")
+            || next.starts_with("https://")
+            || next.starts_with('/')
+            || next.starts_with('?')
+        {
+            return base.join(next).ok().map(String::from);
+        }
+    }
+    for (field, param) in NEXT_TOKEN_FIELDS {
+        let token = match lookup(&json, field) {
+            Some(Value::String(token)) if !token.is_empty() => token.clone(),
+            Some(Value::Number(token)) => token.to_string(),
+            _ => continue,
+        };
+        return Some(with_query(&base, param, &token));
+    }
+
+    // `page` numbering - from the URL or the body, stopping at the last page
+    // or a page with fewer items than asked for
+    let items = list_items(body)?;
+    if items.is_empty() {
+        return None;
+    }
+    let query = |name: &str| {
+        base.query_pairs()
+            .find(|(key, _)| key == name)
+            .and_then(|(_, value)| value.parse::<u64>().ok())
+    };
+    let page = query("page").or_else(|| {
+        PAGE_FIELDS
+            .iter()
+            .find_map(|field| lookup(&json, field)?.as_u64())
+    })?;
+    let total_pages = TOTAL_PAGES_FIELDS
+        .iter()
+        .find_map(|field| lookup(&json, field)?.as_u64());
+    match total_pages {
+        Some(total) if page >= total => return None,
+        Some(_) => {}
+        // Without a page count the body has to confirm it is paginated
+        None if query("page").is_none() => return None,
+        None => {}
+    }
+    let per_page = PER_PAGE_PARAMS.iter().find_map(|param| query(param));
+    if per_page.is_some_and(|per_page| (items.len() as u64) < per_page) {
+        return None;
+    }
+    Some(with_query(&base, "page", &(page + 1).to_string()))
+}
+
+/// The items of a list response: a JSON array, or the array field of an
+/// object (`data`, `items`, `results`... or the only array)
+pub fn list_items(body: &str) -> Option<Vec<Value>> {
+    match serde_json::from_str(body).ok()? {
+        Value::Array(items) => Some(items),
+        Value::Object(mut fields) => {
+            for field in LIST_FIELDS {
+                if let Some(Value::Array(items)) = fields.remove(field) {
+                    return Some(items);
+                }
+            }
+            let mut arrays = fields.into_iter().filter(|(_, value)| value.is_array());
+            match (arrays.next(), arrays.next()) {
+                (Some((_, Value::Array(items))), None) => Some(items),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Request the pages after `first` (starting at `next`) with `headers`
+/// until the last one, `MAX_PAGES` or an error, which is returned with the
+/// pages fetched before it. Pages on another origin than `first` get the
+/// headers without credentials
+pub fn fetch_pages(
+    executor: &RequestExecutor,
+    first: Page,
+    next: String,
+    headers: &[(String, String)],
+) -> (Vec<Page>, Option<String>) {
+    let origin = first.url.clone();
+    let mut pages = vec![first];
+    let mut next = Some(next);
+    while let Some(url) = next.take() {
+        if pages.len() >= MAX_PAGES {
+            return (pages, Some(format!("Stopped after {MAX_PAGES} pages")));
+        }
+        // Some APIs point the last page at itself
+        if pages.iter().any(|page| page.url == url) {
+            break;
+        }
+        let headers = headers_for(&origin, &url, headers);
+        let result = match executor.execute_bytes(HttpMethod::GET, &url, headers, None) {
+            Ok(result) => result,
+            Err(e) => return (pages, Some(format!("{url}: {e}"))),
+        };
+        if !(200..300).contains(&result.status) {
+            let error = format!("{url} returned HTTP {}", result.status);
+            return (pages, Some(error));
+        }
+        let Some(items) = list_items(&result.body) else {
+            return (pages, Some(format!("{url} did not return a list")));
+        };
+        next = next_page_url(&url, &result.headers, &result.body);
+        pages.push(Page { url, items });
+    }
+    (pages, None)
+}
+
+/// All items as one pretty-printed JSON array, and annotations marking
+/// where each page's items start and end
+pub fn join_pages(pages: &[Page]) -> (String, Vec<Annotation>) {
+    let mut text = String::from("[");
+    let mut length = 1;
+    let mut annotations = Vec::new();
+    let mut first_item = true;
+    for (number, page) in pages.iter().enumerate() {
+        let mut start = None;
+        for item in &page.items {
+            let separator = if first_item { "\n  " } else { ",\n  " };
+            first_item = false;
+            text.push_str(separator);
+            length += separator.chars().count();
+            start.get_or_insert(length);
+            let pretty = serde_json::to_string_pretty(item)
+                .unwrap_or_default()
+                .replace('\n', "\n  ");
+            length += pretty.chars().count();
+            text.push_str(&pretty);
+        }
+        if let Some(start) = start {
+            annotations.push(Annotation {
+                start,
+                end: length,
+                color: if number % 2 == 0 {
+                    HighlightColor::Blue
+                } else {
+                    HighlightColor::Green
+                },
+                comment: match page.items.len() {
+                    1 => format!("Page {} · 1 item · {}", number + 1, page.url),
+                    count => format!("Page {} · {count} items · {}", number + 1, page.url),
+                },
+            });
+        }
+    }
+    text.push_str(if first_item { "]" } else { "\n]" });
+    (text, annotations)
+}
+
+// Target of the `rel="next"` entry of a `Link` header
+fn next_link(header: &str) -> Option<String> {
+    header.split(',').find_map(|entry| {
+        let (target, params) = entry.trim().split_once(';')?;
+        let is_next = params.split(';').any(|param| {
+            param.trim().strip_prefix("rel=").is_some_and(|rel| {
+                rel.trim_matches('"')
+                    .split_whitespace()
+                    .any(|rel| rel == "next")
+            })
+        });
+        is_next.then(|| {
+            target
+                .trim()
+                .trim_start_matches('<')
+                .trim_end_matches('>')
+                .to_string()
+        })
+    })
+}
+
+// `url` with the query parameter `name` set to `value`
+fn with_query(url: &reqwest::Url, name: &str, value: &str) -> String {
+    let mut url = url.clone();
+    let pairs: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(key, _)| key != name)
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    url.query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs)
+        .append_pair(name, value);
+    url.into()
+}
+
+// Value at a dotted path such as `meta.next_cursor`
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    path.split('.').try_fold(value, |value, key| value.get(key))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_page_from_link_header_and_body() {
+        let headers = vec![(
+            "Link".to_string(),
+            "<https://api.example.com/items?page=1>; rel=\"prev\", \
+             </items?page=3>; rel=\"next\""
+                .to_string(),
+        )];
+        assert_eq!(
+            next_page_url("https://api.example.com/items?page=2", &headers, "[]").as_deref(),
+            Some("https://api.example.com/items?page=3")
+        );
+
+        let url = "https://api.example.com/users?limit=2";
+        assert_eq!(
+            next_page_url(
+                url,
+                &[],
+                r#"{"next": "/users?offset=2", "results": [1, 2]}"#
+            )
+            .as_deref(),
+            Some("https://api.example.com/users?offset=2")
+        );
+        assert_eq!(
+            next_page_url(url, &[], r#"{"items": [1], "nextPageToken": "abc"}"#).as_deref(),
+            Some("https://api.example.com/users?limit=2&pageToken=abc")
+        );
+        assert_eq!(
+            next_page_url(
+                url,
+                &[],
+                r#"{"members": [1], "response_metadata": {"next_cursor": ""}}"#
+            ),
+            None
+        );
+        assert_eq!(
+            next_page_url(url, &[], r#"{"next": "abc", "data": []}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_next_page_from_page_numbers() {
+        let url = "https://api.example.com/users?page=1&per_page=2";
+        assert_eq!(
+            next_page_url(url, &[], r#"[{"id": 1}, {"id": 2}]"#).as_deref(),
+            Some("https://api.example.com/users?per_page=2&page=2")
+        );
+        // Fewer items than asked for, or no items, is the last page
+        assert_eq!(next_page_url(url, &[], r#"[{"id": 1}]"#), None);
+        assert_eq!(next_page_url(url, &[], "[]"), None);
+
+        let url = "https://reqres.in/api/users";
+        let body = r#"{"page": 1, "total_pages": 2, "data": [{"id": 1}]}"#;
+        assert_eq!(
+            next_page_url(url, &[], body).as_deref(),
+            Some("https://reqres.in/api/users?page=2")
+        );
+        let body = r#"{"page": 2, "total_pages": 2, "data": [{"id": 7}]}"#;
+        assert_eq!(next_page_url(url, &[], body), None);
+        // Without a page count a page number in the body alone isn't enough
+        assert_eq!(next_page_url(url, &[], r#"{"page": 1, "data": [1]}"#), None);
+    }
+
+    #[test]
+    fn test_cross_origin_next_page_gets_no_credentials() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        // Answers one request with `response` and returns the request head
+        fn serve_once(listener: TcpListener, response: String) -> std::thread::JoinHandle<String> {
+            std::thread::spawn(move || {
+                let (mut socket, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    let read = socket.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                socket.write_all(response.as_bytes()).unwrap();
+                String::from_utf8_lossy(&request).to_ascii_lowercase()
+            })
+        }
+        let response = |extra: &str, body: &str| {
+            format!(
+                "HTTP/1.1 200 OK\r\n{extra}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        };
+
+        // The second page is on the same origin, its next link points at another port
+        let api = TcpListener::bind("127.0.0.1:0").unwrap();
+        let other = TcpListener::bind("127.0.0.1:0").unwrap();
+        let api_url = format!("http://{}", api.local_addr().unwrap());
+        let other_url = format!("http://{}", other.local_addr().unwrap());
+        let link = format!("Link: <{other_url}/collect>; rel=\"next\"\r\n");
+        let api = serve_once(api, response(&link, "[2]"));
+        let other = serve_once(other, response("", "[]"));
+
+        let headers = vec![
+            ("Authorization".to_string(), "Bearer secret".to_string()),
+            ("X-Api-Key".to_string(), "key".to_string()),
+            ("Accept".to_string(), "application/json".to_string()),
+        ];
+        let first = Page {
+            url: format!("{api_url}/items"),
+            items: vec![Value::from(1)],
+        };
+        let (pages, error) = fetch_pages(
+            &RequestExecutor::new(),
+            first,
+            format!("{api_url}/items?page=2"),
+            &headers,
+        );
+        assert_eq!(error, None);
+        assert_eq!(pages.len(), 3);
+
+        let same_origin = api.join().unwrap();
+        assert!(same_origin.contains("authorization: bearer secret"));
+        let cross_origin = other.join().unwrap();
+        assert!(!cross_origin.contains("authorization"));
+        assert!(!cross_origin.contains("x-api-key"));
+        assert!(cross_origin.contains("accept: application/json"));
+    }
+
+    #[test]
+    fn test_list_items_and_join_pages() {
+        assert_eq!(
+            list_items(r#"{"meta": {}, "data": [1, 2]}"#).unwrap().len(),
+            2
+        );
+        assert_eq!(
+            list_items(r#"{"users": [1], "total": 1}"#).unwrap().len(),
+            1
+        );
+        assert!(list_items(r#"{"a": [1], "b": [2]}"#).is_none());
+        assert!(list_items("not json").is_none());
+
+        let pages = [
+            Page {
+                url: "https://api.example.com/items?page=1".to_string(),
+                items: vec![Value::from(1), Value::from(2)],
+            },
+            Page {
+                url: "https://api.example.com/items?page=2".to_string(),
+                items: vec![serde_json::json!({"id": 3})],
+            },
+        ];
+        let (text, annotations) = join_pages(&pages);
+        assert_eq!(text, "[\n  1,\n  2,\n  {\n    \"id\": 3\n  }\n]");
+        assert_eq!(annotations.len(), 2);
+        let marked = |annotation: &Annotation| -> String {
+            text.chars()
+                .skip(annotation.start)
+                .take(annotation.end - annotation.start)
+                .collect()
+        };
+        assert_eq!(marked(&annotations[0]), "1,\n  2");
+        assert_eq!(marked(&annotations[1]), "{\n    \"id\": 3\n  }");
+        assert_eq!(
+            annotations[1].comment,
+            "Page 2 · 1 item · https://api.example.com/items?page=2"
+        );
+        assert_eq!(join_pages(&[]).0, "[]");
+    }
+}
@@ -0,0 +1,225 @@
+//! Turning a request into the headers and body that are sent: the domain's
+//! default headers, auth, a multipart body, JWS / JWE protection and the
+//! body serializer, in that order.
+//!
+//! Everything besides the request itself is passed in [`PrepareOptions`],
+//! so a request from history or a scenario is prepared from what it stores
+//! (plus the current domain defaults) and not from what the editor shows.
+
+use crate::errors::AppError;
+use crate::models::{DomainDefaults, Request};
+use crate::plugins::PluginRegistry;
+use crate::utils::jose::{BodyProtection, JOSE_CONTENT_TYPE};
+use crate::utils::multipart::{self, FormPart};
+
+/// Headers and body of a prepared request
+pub type PreparedRequest = (Vec<(String, String)>, Option<Vec<u8>>);
+
+/// Signing or encryption of the body and the key it uses
+#[derive(Debug, Clone)]
+pub struct Protection {
+    pub protection: BodyProtection,
+    pub key: String,
+    pub key_id: Option<String>,
+}
+
+/// What is applied to a request besides what it stores
+#[derive(Debug, Clone, Default)]
+pub struct PrepareOptions<'a> {
+    /// Defaults of the domain the request targets
+    pub domain: Option<&'a DomainDefaults>,
+    /// Auth provider and credential; the domain's auth is used when `None`
+    pub auth: Option<(String, String)>,
+    /// Parts sent as `multipart/form-data` when the Content-Type has a
+    /// boundary
+    pub form_parts: Option<Vec<FormPart>>,
+    pub protection: Option<Protection>,
+    /// Name of the body serializer plugin
+    pub body_serializer: Option<String>,
+}
+
+impl<'a> PrepareOptions<'a> {
+    /// Options for a request sent again as it was stored (history replay,
+    /// scenario steps): the domain defaults and auth, and the stored form
+    /// body as the multipart parts
+    pub fn stored(request: &Request, domain: Option<&'a DomainDefaults>) -> Self {
+        Self {
+            domain,
+            form_parts: stored_form_parts(request),
+            ..Self::default()
+        }
+    }
+}
+
+/// Parts of a stored form body (saved `key=value&...` url-encoded), when the
+/// request is sent as multipart
+pub fn stored_form_parts(request: &Request) -> Option<Vec<FormPart>> {
+    request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("Content-Type"))
+        .and_then(|(_, value)| multipart::boundary_of(value))?;
+    let body = request.body.as_deref()?.trim();
+    if body.contains('\n') {
+        return None;
+    }
+    Some(
+        form_urlencoded::parse(body.as_bytes())
+            .filter(|(key, _)| !key.is_empty())
+            .map(|(key, value)| FormPart::new(key, value))
+            .collect(),
+    )
+}
+
+/// Apply `options` to `request` and encode its body
+pub fn prepare_request(
+    request: &Request,
+    options: PrepareOptions,
+    registry: &PluginRegistry,
+) -> Result<PreparedRequest, AppError> {
+    let PrepareOptions {
+        domain,
+        auth,
+        form_parts,
+        protection,
+        body_serializer,
+    } = options;
+    let mut request = request.clone();
+
+    if let Some(domain) = domain {
+        domain.apply_headers(&mut request);
+        tracing::info!("🌐 PrepareRequest - 已应用域名默认值: {}", domain.host);
+    }
+
+    let auth = auth.or_else(|| {
+        domain
+            .and_then(|domain| domain.auth.clone())
+            .map(|auth| (auth.provider, auth.credential))
+    });
+    if let Some((name, credential)) = auth {
+        let provider = registry.auth_provider(&name).ok_or_else(|| {
+            AppError::ValidationError(format!("Auth provider not registered: {name}"))
+        })?;
+        provider.apply(&mut request, &credential)?;
+        tracing::info!("🔑 PrepareRequest - 已应用认证插件: {name}");
+    }
+
+    // multipart 表单：按 boundary 组装各部分，`@path` 条目在发送时读取文件，
+    // 各条目指定的 Content-Type 写入对应部分
+    let boundary = request
+        .headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case("Content-Type"))
+        .and_then(|(_, value)| multipart::boundary_of(value))
+        .map(str::to_string);
+    if let (Some(boundary), Some(parts)) = (boundary, form_parts) {
+        let bytes = multipart::encode(&parts, &boundary)?;
+        tracing::info!(
+            "📎 PrepareRequest - multipart 请求体: {} 个部分, {} bytes",
+            parts.len(),
+            bytes.len()
+        );
+        return Ok((request.headers, Some(bytes)));
+    }
+
+    // JSON 请求体签名为 JWS 或加密为 JWE 后再发送
+    if let Some(Protection {
+        protection,
+        key,
+        key_id,
+    }) = protection
+    {
+        if let Some(text) = request.body.take() {
+            if body_serializer.is_some() {
+                return Err(AppError::ValidationError(
+                    "A signed or encrypted body can't also use a body serializer".to_string(),
+                ));
+            }
+            let token = protection.apply(&text, &key, key_id.as_deref())?;
+            if !has_content_type(&request) {
+                request.add_header("Content-Type", JOSE_CONTENT_TYPE);
+            }
+            tracing::info!("🔏 PrepareRequest - 请求体已处理为 {}", protection.label());
+            request.body = Some(token);
+        }
+    }
+
+    let body = match (body_serializer, request.body.take()) {
+        (Some(name), Some(text)) => {
+            let serializer = registry.body_serializer(&name).ok_or_else(|| {
+                AppError::ValidationError(format!("Body serializer not registered: {name}"))
+            })?;
+            let bytes = serializer.serialize(&text)?;
+            if !has_content_type(&request) {
+                request.add_header("Content-Type", serializer.content_type());
+            }
+            tracing::info!(
+                "📦 PrepareRequest - 请求体已由插件 {name} 序列化: {} bytes",
+                bytes.len()
+            );
+            Some(bytes)
+        }
+        (_, body) => body.map(String::into_bytes),
+    };
+
+    Ok((request.headers, body))
+}
+
+fn has_content_type(request: &Request) -> bool {
+    request
+        .headers
+        .iter()
+        .any(|(key, _)| key.eq_ignore_ascii_case("Content-Type"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::DomainAuth;
+    use crate::plugins::builtin::BEARER_AUTH;
+
+    fn registry() -> PluginRegistry {
+        PluginRegistry::with_plugins(&[])
+    }
+
+    #[test]
+    fn test_stored_request_keeps_its_own_body() {
+        let mut request = Request::new("POST", "https://api.example.com/items");
+        request.add_header("Content-Type", "application/json");
+        request.set_body(r#"{"a": 1}"#);
+        let mut domain = DomainDefaults::new("api.example.com");
+        domain.headers = vec![("X-Team".to_string(), "core".to_string())];
+        domain.auth = Some(DomainAuth {
+            provider: BEARER_AUTH.to_string(),
+            credential: "token".to_string(),
+        });
+
+        let options = PrepareOptions::stored(&request, Some(&domain));
+        let (headers, body) = prepare_request(&request, options, &registry()).unwrap();
+        assert_eq!(body.as_deref(), Some(br#"{"a": 1}"#.as_slice()));
+        assert!(headers.contains(&("X-Team".to_string(), "core".to_string())));
+        assert!(headers
+            .iter()
+            .any(|(key, value)| key == "Authorization" && value == "Bearer token"));
+    }
+
+    #[test]
+    fn test_stored_form_body_is_sent_as_its_own_parts() {
+        let mut request = Request::new("POST", "https://example.com/upload");
+        request.add_header("Content-Type", "multipart/form-data; boundary=xyz");
+        request.set_body("name=John+Doe&note=hi");
+
+        let parts = stored_form_parts(&request).unwrap();
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].value, "John Doe");
+
+        let options = PrepareOptions::stored(&request, None);
+        let (_, body) = prepare_request(&request, options, &registry()).unwrap();
+        let body = String::from_utf8(body.unwrap()).unwrap();
+        assert!(body.starts_with("--xyz\r\n"));
+        assert!(body.contains("John Doe"));
+
+        request.headers.clear();
+        assert!(stored_form_parts(&request).is_none());
+    }
+}
@@ -0,0 +1,328 @@
+//! Raw socket mode - send hand-written request bytes over TCP or TLS and read
+//! back whatever the server sends, without any HTTP parsing in between.
+
+use crate::errors::AppError;
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::time::{Duration, Instant};
+
+/// How long to wait for the connection and for each read
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Stop reading once this much has arrived
+pub const MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Where the raw request is sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawTarget {
+    pub host: String,
+    pub port: u16,
+    pub tls: bool,
+}
+
+impl RawTarget {
+    /// Parse `host:port`, `tcp://host:port`, `tls://host:port` or an
+    /// `http://` / `https://` URL (the default port follows the scheme)
+    pub fn parse(address: &str) -> Result<Self, AppError> {
+        let address = address.trim();
+        if address.is_empty() {
+            return Err(AppError::ValidationError(
+                "Enter a host and port (e.g. example.com:80)".to_string(),
+            ));
+        }
+        let (scheme, rest) = match address.split_once("://") {
+            Some((scheme, rest)) => (Some(scheme.to_ascii_lowercase()), rest),
+            None => (None, address),
+        };
+        let (tls, default_port) = match scheme.as_deref() {
+            None | Some("tcp") => (false, None),
+            Some("http") => (false, Some(80)),
+            Some("tls") => (true, None),
+            Some("https") => (true, Some(443)),
+            Some(other) => {
+                return Err(AppError::ValidationError(format!(
+                    "Unsupported scheme for raw mode: {other}"
+                )))
+            }
+        };
+        // Drop any path of a pasted URL
+        let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+
+        let (host, port) = match split_host_port(authority) {
+            (host, Some(port)) => {
+                let port = port
+                    .parse::<u16>()
+                    .map_err(|_| AppError::ValidationError(format!("Invalid port: {port}")))?;
+                (host, port)
+            }
+            (host, None) => match default_port {
+                Some(port) => (host, port),
+                None => {
+                    return Err(AppError::ValidationError(format!(
+                        "Missing port in {address}"
+                    )))
+                }
+            },
+        };
+        if host.is_empty() {
+            return Err(AppError::ValidationError(format!(
+                "Missing host in {address}"
+            )));
+        }
+        Ok(Self {
+            host: host.to_string(),
+            port,
+            tls,
+        })
+    }
+}
+
+impl std::fmt::Display for RawTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let scheme = if self.tls { "tls" } else { "tcp" };
+        if self.host.contains(':') {
+            write!(f, "{scheme}://[{}]:{}", self.host, self.port)
+        } else {
+            write!(f, "{scheme}://{}:{}", self.host, self.port)
+        }
+    }
+}
+
+/// `[v6]:port`, `host:port` or a bare host
+fn split_host_port(authority: &str) -> (&str, Option<&str>) {
+    if let Some(rest) = authority.strip_prefix('[') {
+        if let Some((host, after)) = rest.split_once(']') {
+            return (host, after.strip_prefix(':'));
+        }
+    }
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !host.contains(':') => (host, Some(port)),
+        _ => (authority, None),
+    }
+}
+
+/// Convert bare `\n` line endings to `\r\n`, since text typed in the editor
+/// only contains `\n` but HTTP wants CRLF
+pub fn normalize_line_endings(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut previous = None;
+    for c in text.chars() {
+        if c == '\n' && previous != Some('\r') {
+            out.push('\r');
+        }
+        out.push(c);
+        previous = Some(c);
+    }
+    out
+}
+
+/// What came back from the socket
+#[derive(Debug, Clone, PartialEq)]
+pub struct RawResponse {
+    pub bytes: Vec<u8>,
+    pub elapsed: Duration,
+    /// The server closed the connection (otherwise reading stopped at the
+    /// read timeout or the size limit)
+    pub closed: bool,
+}
+
+impl RawResponse {
+    /// Bytes as text, with invalid UTF-8 replaced
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.bytes).into_owned()
+    }
+}
+
+/// Send `payload` to `target` and read until the server closes the
+/// connection, goes quiet for `timeout`, or [`MAX_RESPONSE_BYTES`] arrive
+pub fn send_raw(
+    target: &RawTarget,
+    payload: &[u8],
+    timeout: Duration,
+) -> Result<RawResponse, AppError> {
+    tracing::info!("🔌 RawSocket - 连接 {target}，发送 {} bytes", payload.len());
+    let started = Instant::now();
+    let stream = connect(target, timeout)?;
+    stream
+        .set_read_timeout(Some(timeout))
+        .map_err(network_error)?;
+    stream
+        .set_write_timeout(Some(timeout))
+        .map_err(network_error)?;
+
+    let (bytes, closed) = if target.tls {
+        let connector = native_tls::TlsConnector::new()
+            .map_err(|e| AppError::NetworkError(format!("TLS setup failed: {e}")))?;
+        let mut stream = connector
+            .connect(&target.host, stream)
+            .map_err(|e| AppError::NetworkError(format!("TLS handshake failed: {e}")))?;
+        exchange(&mut stream, payload)?
+    } else {
+        let mut stream = stream;
+        exchange(&mut stream, payload)?
+    };
+
+    let response = RawResponse {
+        bytes,
+        elapsed: started.elapsed(),
+        closed,
+    };
+    tracing::info!(
+        "✅ RawSocket - 收到 {} bytes ({:?})",
+        response.bytes.len(),
+        response.elapsed
+    );
+    Ok(response)
+}
+
+fn connect(target: &RawTarget, timeout: Duration) -> Result<TcpStream, AppError> {
+    let addresses = (target.host.as_str(), target.port)
+        .to_socket_addrs()
+        .map_err(|e| AppError::NetworkError(format!("Could not resolve {}: {e}", target.host)))?;
+    let mut last_error = None;
+    for address in addresses {
+        match TcpStream::connect_timeout(&address, timeout) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(AppError::NetworkError(match last_error {
+        Some(e) => format!("Connection to {target} failed: {e}"),
+        None => format!("No addresses found for {}", target.host),
+    }))
+}
+
+/// Write the payload, then read; returns the bytes and whether the peer
+/// closed the connection
+fn exchange<S: Read + Write>(stream: &mut S, payload: &[u8]) -> Result<(Vec<u8>, bool), AppError> {
+    stream.write_all(payload).map_err(network_error)?;
+    stream.flush().map_err(network_error)?;
+
+    let mut bytes = Vec::new();
+    let mut buffer = [0u8; 8192];
+    loop {
+        match stream.read(&mut buffer) {
+            Ok(0) => return Ok((bytes, true)),
+            Ok(read) => {
+                bytes.extend_from_slice(&buffer[..read]);
+                if bytes.len() >= MAX_RESPONSE_BYTES {
+                    bytes.truncate(MAX_RESPONSE_BYTES);
+                    return Ok((bytes, false));
+                }
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            // Keep-alive servers leave the connection open - stop once it goes quiet
+            Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                if bytes.is_empty() {
+                    return Err(AppError::NetworkError(
+                        "No response before the read timeout".to_string(),
+                    ));
+                }
+                return Ok((bytes, false));
+            }
+            // Some servers reset right after answering a malformed request
+            Err(e) if e.kind() == ErrorKind::ConnectionReset && !bytes.is_empty() => {
+                return Ok((bytes, true));
+            }
+            Err(e) => return Err(network_error(e)),
+        }
+    }
+}
+
+fn network_error(err: std::io::Error) -> AppError {
+    AppError::NetworkError(err.to_string())
+}
+
+/// Classic hex dump: offset, 16 bytes in hex, then the printable ASCII
+pub fn hex_dump(bytes: &[u8]) -> String {
+    let mut out = String::new();
+    for (line, chunk) in bytes.chunks(16).enumerate() {
+        out.push_str(&format!("{:08x}  ", line * 16));
+        for i in 0..16 {
+            match chunk.get(i) {
+                Some(byte) => out.push_str(&format!("{byte:02x} ")),
+                None => out.push_str("   "),
+            }
+            if i == 7 {
+                out.push(' ');
+            }
+        }
+        out.push(' ');
+        out.extend(chunk.iter().map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                char::from(*byte)
+            } else {
+                '.'
+            }
+        }));
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[test]
+    fn test_parse_target() {
+        assert_eq!(
+            RawTarget::parse("example.com:8080").unwrap(),
+            RawTarget {
+                host: "example.com".to_string(),
+                port: 8080,
+                tls: false
+            }
+        );
+        let target = RawTarget::parse("https://example.com/path?q=1").unwrap();
+        assert_eq!((target.port, target.tls), (443, true));
+        let target = RawTarget::parse("tls://[::1]:8443").unwrap();
+        assert_eq!(target.host, "::1");
+        assert_eq!(target.to_string(), "tls://[::1]:8443");
+
+        assert!(RawTarget::parse("example.com").is_err());
+        assert!(RawTarget::parse("example.com:99999").is_err());
+        assert!(RawTarget::parse("ftp://example.com:21").is_err());
+        assert!(RawTarget::parse(":80").is_err());
+    }
+
+    #[test]
+    fn test_normalize_line_endings() {
+        assert_eq!(
+            normalize_line_endings("GET / HTTP/1.1\nHost: a\r\n\n"),
+            "GET / HTTP/1.1\r\nHost: a\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_hex_dump() {
+        let dump = hex_dump(b"HTTP/1.1 200 OK\r\n");
+        assert_eq!(
+            dump,
+            "00000000  48 54 54 50 2f 31 2e 31  20 32 30 30 20 4f 4b 0d  HTTP/1.1 200 OK.\n\
+             00000010  0a                                                .\n"
+        );
+    }
+
+    #[test]
+    fn test_send_raw_reads_until_close() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = [0u8; 64];
+            let read = socket.read(&mut request).unwrap();
+            socket
+                .write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n")
+                .unwrap();
+            request[..read].to_vec()
+        });
+
+        let target = RawTarget::parse(&format!("127.0.0.1:{port}")).unwrap();
+        let response = send_raw(&target, b"BROKEN\r\n\r\n", DEFAULT_TIMEOUT).unwrap();
+        assert_eq!(server.join().unwrap(), b"BROKEN\r\n\r\n");
+        assert_eq!(response.text(), "HTTP/1.1 400 Bad Request\r\n\r\n");
+        assert!(response.closed);
+    }
+}
@@ -0,0 +1,216 @@
+//! Session replay: the requests recorded in history during a time window
+//! sent again, in the order they were first sent, with the status each one
+//! gets now compared to the one recorded - e.g. to check an API after a
+//! server upgrade.
+//!
+//! Each request is sent with its own recorded headers and body, plus the
+//! current domain defaults, domain auth and transport (proxy, TLS, client
+//! certificate) and the current hook variables - never the auth, body
+//! plugins or form of the editor. Hooks and assertions don't run and the
+//! replies aren't added to history.
+
+use crate::errors::AppError;
+use crate::http::bulk::BulkOutcome;
+use crate::http::executor::RequestExecutor;
+use crate::http::socket::SocketTarget;
+use crate::models::HttpMethod;
+use chrono::{DateTime, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use std::time::Instant;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+/// A recorded request ready to send again
+#[derive(Debug, Clone)]
+pub struct ReplayStep {
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+    /// Executor with the domain's proxy / TLS options and the client
+    /// certificate, the default one when `None`
+    pub executor: Option<RequestExecutor>,
+    /// Unix socket or named pipe the request is sent through
+    pub socket: Option<SocketTarget>,
+}
+
+/// How the new outcome of a request compares to the recorded status
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatusChange {
+    Same,
+    Changed,
+    /// No response this time
+    Failed,
+    /// No status was recorded to compare with
+    Unrecorded,
+}
+
+impl StatusChange {
+    pub fn of(recorded: Option<u16>, outcome: &BulkOutcome) -> Self {
+        match (recorded, outcome) {
+            (_, BulkOutcome::Failed(_)) => StatusChange::Failed,
+            (None, _) => StatusChange::Unrecorded,
+            (Some(recorded), BulkOutcome::Response { status, .. }) if recorded == *status => {
+                StatusChange::Same
+            }
+            (Some(_), BulkOutcome::Response { .. }) => StatusChange::Changed,
+        }
+    }
+}
+
+/// Send the steps one after another on a background thread. Outcomes
+/// arrive by index as the requests finish; dropping the receiver stops the
+/// replay after the request in flight
+pub fn run_replay(steps: Vec<ReplayStep>) -> UnboundedReceiver<(usize, BulkOutcome)> {
+    let (outcomes, receiver) = unbounded_channel();
+    std::thread::spawn(move || {
+        let executor = RequestExecutor::new();
+        for (index, step) in steps.into_iter().enumerate() {
+            if outcomes.is_closed() {
+                break;
+            }
+            let started = Instant::now();
+            let sent = step.executor.as_ref().unwrap_or(&executor).execute_via(
+                step.socket.as_ref(),
+                step.method,
+                &step.url,
+                step.headers,
+                step.body,
+            );
+            let outcome = match sent {
+                Ok(result) => BulkOutcome::Response {
+                    status: result.status,
+                    elapsed: started.elapsed(),
+                    size: result.raw_body.len(),
+                },
+                Err(e) => BulkOutcome::Failed(e.to_string()),
+            };
+            if outcomes.send((index, outcome)).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+/// One line for the whole replay, `None` standing for a request that
+/// hasn't finished yet, e.g. `12 requests - 2 changed status, 1 failed`
+pub fn replay_summary(recorded: &[Option<u16>], outcomes: &[Option<BulkOutcome>]) -> String {
+    let total = outcomes.len();
+    let requests = if total == 1 { "request" } else { "requests" };
+    let changes: Vec<StatusChange> = recorded
+        .iter()
+        .zip(outcomes)
+        .filter_map(|(recorded, outcome)| Some(StatusChange::of(*recorded, outcome.as_ref()?)))
+        .collect();
+    if changes.len() < total {
+        return format!("Replaying - {} of {total} {requests} done", changes.len());
+    }
+    let count = |change| changes.iter().filter(|c| **c == change).count();
+    let (changed, failed) = (count(StatusChange::Changed), count(StatusChange::Failed));
+    if changed == 0 && failed == 0 {
+        return format!("{total} {requests} - every status is the same");
+    }
+    let mut parts = Vec::new();
+    if changed > 0 {
+        parts.push(format!("{changed} changed status"));
+    }
+    if failed > 0 {
+        parts.push(format!("{failed} failed"));
+    }
+    format!("{total} {requests} - {}", parts.join(", "))
+}
+
+/// The moment a bound of the window stands for: `YYYY-MM-DD HH:MM[:SS]`,
+/// or `HH:MM[:SS]` for today, read in the time zone of `now`. Empty means
+/// no bound
+pub fn parse_time_bound<Tz: TimeZone>(
+    text: &str,
+    now: &DateTime<Tz>,
+) -> Result<Option<DateTime<Utc>>, AppError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    let date_time = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(text, format).ok())
+        .or_else(|| {
+            ["%H:%M:%S", "%H:%M"]
+                .iter()
+                .find_map(|format| NaiveTime::parse_from_str(text, format).ok())
+                .map(|time| now.date_naive().and_time(time))
+        })
+        .ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "Enter a time as HH:MM or YYYY-MM-DD HH:MM, not \"{text}\""
+            ))
+        })?;
+    let moment = now
+        .timezone()
+        .from_local_datetime(&date_time)
+        .earliest()
+        .ok_or_else(|| {
+            AppError::ValidationError(format!("{text} doesn't exist in the local time zone"))
+        })?;
+    Ok(Some(moment.with_timezone(&Utc)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn response(status: u16) -> Option<BulkOutcome> {
+        Some(BulkOutcome::Response {
+            status,
+            elapsed: Duration::from_millis(5),
+            size: 2,
+        })
+    }
+
+    #[test]
+    fn test_status_changes_and_summary() {
+        let failed = BulkOutcome::Failed("connection refused".to_string());
+        assert_eq!(
+            StatusChange::of(Some(200), &response(200).unwrap()),
+            StatusChange::Same
+        );
+        assert_eq!(
+            StatusChange::of(Some(200), &response(502).unwrap()),
+            StatusChange::Changed
+        );
+        assert_eq!(StatusChange::of(Some(200), &failed), StatusChange::Failed);
+        assert_eq!(
+            StatusChange::of(None, &response(200).unwrap()),
+            StatusChange::Unrecorded
+        );
+
+        let recorded = [Some(200), Some(201), None];
+        assert_eq!(
+            replay_summary(&recorded, &[response(200), None, None]),
+            "Replaying - 1 of 3 requests done"
+        );
+        assert_eq!(
+            replay_summary(&recorded, &[response(200), response(201), response(404)]),
+            "3 requests - every status is the same"
+        );
+        assert_eq!(
+            replay_summary(&recorded, &[response(500), Some(failed), response(404)]),
+            "3 requests - 1 changed status, 1 failed"
+        );
+    }
+
+    #[test]
+    fn test_parse_time_bound() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 6, 15, 30, 0).unwrap();
+        assert_eq!(parse_time_bound("  ", &now).unwrap(), None);
+        assert_eq!(
+            parse_time_bound("09:15", &now).unwrap(),
+            Some(Utc.with_ymd_and_hms(2024, 5, 6, 9, 15, 0).unwrap())
+        );
+        assert_eq!(
+            parse_time_bound("2024-05-01 23:59:30", &now).unwrap(),
+            Some(Utc.with_ymd_and_hms(2024, 5, 1, 23, 59, 30).unwrap())
+        );
+        assert!(parse_time_bound("yesterday", &now).is_err());
+    }
+}
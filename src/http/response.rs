@@ -1,7 +1,19 @@
+/// Find a header value by name, ignoring case as HTTP header names do
+pub fn header_value<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| value.as_str())
+}
+
+#[derive(Debug, Clone)]
 pub struct HttpResponse {
     pub status_code: u16,
     pub headers: Vec<(String, String)>,
+    /// Body as text (invalid UTF-8 replaced)
     pub body: String,
+    /// Body exactly as received, for binary formats
+    pub raw_body: Vec<u8>,
 }
 
 impl HttpResponse {
@@ -9,10 +21,21 @@ impl HttpResponse {
         HttpResponse {
             status_code,
             headers,
+            raw_body: body.clone().into_bytes(),
             body,
         }
     }
 
+    /// Response with a body that may not be text
+    pub fn from_bytes(status_code: u16, headers: Vec<(String, String)>, raw_body: Vec<u8>) -> Self {
+        HttpResponse {
+            status_code,
+            headers,
+            body: String::from_utf8_lossy(&raw_body).into_owned(),
+            raw_body,
+        }
+    }
+
     pub fn status(&self) -> u16 {
         self.status_code
     }
@@ -21,10 +44,19 @@ impl HttpResponse {
         &self.headers
     }
 
+    /// Value of the first header named `name` (case-insensitive)
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header_value(&self.headers, name)
+    }
+
     pub fn body(&self) -> &str {
         &self.body
     }
 
+    pub fn raw_body(&self) -> &[u8] {
+        &self.raw_body
+    }
+
     pub fn from_raw_response(raw_response: &str) -> Result<Self, &'static str> {
         let parts: Vec<&str> = raw_response.split("\r\n\r\n").collect();
         if parts.len() != 2 {
@@ -62,3 +94,28 @@ impl HttpResponse {
         ))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_raw_response() {
+        let raw = "HTTP/1.1 206 Partial Content\r\nContent-Range: bytes 0-4/10\r\n\r\nhello";
+        let response = HttpResponse::from_raw_response(raw).unwrap();
+        assert_eq!(response.status(), 206);
+        assert_eq!(response.body(), "hello");
+        assert_eq!(response.header("content-range"), Some("bytes 0-4/10"));
+        assert_eq!(response.header("ETag"), None);
+    }
+
+    #[test]
+    fn test_from_bytes_keeps_binary_body() {
+        let response = HttpResponse::from_bytes(200, Vec::new(), vec![0x81, 0xa1, b'a', 0xff]);
+        assert_eq!(response.raw_body(), [0x81, 0xa1, b'a', 0xff]);
+        assert_eq!(
+            response.body().chars().filter(|c| *c == '\u{fffd}').count(),
+            3
+        );
+    }
+}
@@ -0,0 +1,310 @@
+//! Scenario runner: the setup, main and teardown requests of a scenario
+//! sent one after another and reported as one run.
+//!
+//! Teardown requests always run, so whatever setup created is cleaned up
+//! even when a request fails; the main requests are skipped when setup
+//! fails. Variables set by a step's hooks are substituted into the steps
+//! after it, e.g. the id of a resource created in setup.
+
+use crate::http::executor::RequestExecutor;
+use crate::http::socket::SocketTarget;
+use crate::models::{HttpMethod, RequestHooks, Stage};
+use crate::utils::assertions::{run_script, AssertionContext};
+use crate::utils::hooks::{run_hook, substitute, HOOK_TIMEOUT};
+use crate::utils::status_codes::reason_phrase;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+
+/// A request of the scenario ready to send: prepared from the stored
+/// request with the domain defaults and auth applied, variables not set
+/// yet are still `{{name}}`
+#[derive(Debug, Clone)]
+pub struct PreparedStep {
+    pub stage: Stage,
+    pub method: HttpMethod,
+    pub url: String,
+    pub headers: Vec<(String, String)>,
+    pub body: Option<Vec<u8>>,
+    /// Executor with the domain's proxy / TLS options and the client
+    /// certificate, the default one when `None`
+    pub executor: Option<RequestExecutor>,
+    /// Unix socket or named pipe the request is sent through
+    pub socket: Option<SocketTarget>,
+    pub hooks: RequestHooks,
+    /// Assertion scripts the response has to pass
+    pub scripts: Vec<String>,
+}
+
+/// What one step of a run did
+#[derive(Debug, Clone, PartialEq)]
+pub enum StepOutcome {
+    Passed {
+        status: u16,
+        elapsed: Duration,
+    },
+    /// The request could not be sent, answered with an error status or
+    /// failed an assertion
+    Failed(String),
+    /// Not sent because a setup request failed
+    Skipped,
+}
+
+impl StepOutcome {
+    pub fn failed(&self) -> bool {
+        matches!(self, StepOutcome::Failed(_))
+    }
+}
+
+/// Send the steps in order on a background thread. Outcomes arrive by
+/// index as the steps finish; dropping the receiver stops the run after
+/// the step in flight
+pub fn run_scenario(steps: Vec<PreparedStep>) -> UnboundedReceiver<(usize, StepOutcome)> {
+    let (outcomes, receiver) = unbounded_channel();
+    std::thread::spawn(move || {
+        let executor = RequestExecutor::new();
+        let mut variables = HashMap::new();
+        let mut setup_failed = false;
+        for (index, step) in steps.into_iter().enumerate() {
+            if outcomes.is_closed() {
+                break;
+            }
+            let stage = step.stage;
+            let outcome = match stage {
+                // The rest of setup depends on what failed, so it is skipped too
+                Stage::Setup | Stage::Main if setup_failed => StepOutcome::Skipped,
+                _ => run_step(&executor, step, &mut variables),
+            };
+            if stage == Stage::Setup && outcome.failed() {
+                setup_failed = true;
+            }
+            if outcomes.send((index, outcome)).is_err() {
+                break;
+            }
+        }
+    });
+    receiver
+}
+
+fn run_step(
+    executor: &RequestExecutor,
+    step: PreparedStep,
+    variables: &mut HashMap<String, String>,
+) -> StepOutcome {
+    if let Some(hook) = &step.hooks.pre {
+        match run_hook(&hook.command, None, HOOK_TIMEOUT) {
+            Ok(output) => {
+                if let Some(variable) = &hook.variable {
+                    variables.insert(variable.clone(), output);
+                }
+            }
+            Err(e) => return StepOutcome::Failed(format!("Pre-request hook failed: {e}")),
+        }
+    }
+
+    let url = substitute(&step.url, variables);
+    let headers = step
+        .headers
+        .iter()
+        .map(|(key, value)| (substitute(key, variables), substitute(value, variables)))
+        .collect();
+    // Binary bodies are sent as they are
+    let body = step.body.map(|body| match String::from_utf8(body) {
+        Ok(text) => substitute(&text, variables).into_bytes(),
+        Err(e) => e.into_bytes(),
+    });
+
+    let started = Instant::now();
+    let executor = step.executor.as_ref().unwrap_or(executor);
+    let result = match executor.execute_via(step.socket.as_ref(), step.method, &url, headers, body)
+    {
+        Ok(result) => result,
+        Err(e) => return StepOutcome::Failed(e.to_string()),
+    };
+    let elapsed = started.elapsed();
+
+    if let Some(hook) = &step.hooks.post {
+        match run_hook(&hook.command, Some(&result.raw_body), HOOK_TIMEOUT) {
+            Ok(output) => {
+                if let Some(variable) = &hook.variable {
+                    variables.insert(variable.clone(), output);
+                }
+            }
+            Err(e) => return StepOutcome::Failed(format!("Post-response hook failed: {e}")),
+        }
+    }
+
+    if result.status >= 400 {
+        return StepOutcome::Failed(format!(
+            "{} {}",
+            result.status,
+            reason_phrase(result.status)
+        ));
+    }
+    let context =
+        AssertionContext::new(result.status, &result.headers, &result.body).with_latency(elapsed);
+    for script in &step.scripts {
+        let failure = match run_script(script, &context) {
+            Ok(outcomes) => outcomes
+                .into_iter()
+                .find(|outcome| !outcome.passed())
+                .map(|outcome| {
+                    format!(
+                        "{}: {}",
+                        outcome.source,
+                        outcome.failure.unwrap_or_default()
+                    )
+                }),
+            Err(e) => Some(e.to_string()),
+        };
+        if let Some(failure) = failure {
+            return StepOutcome::Failed(failure);
+        }
+    }
+    StepOutcome::Passed {
+        status: result.status,
+        elapsed,
+    }
+}
+
+/// One line for the whole run, `None` standing for a step that hasn't
+/// finished yet, e.g. `Failed - 1 of 4 steps failed, 2 skipped`
+pub fn run_summary(outcomes: &[Option<StepOutcome>]) -> String {
+    let total = outcomes.len();
+    let pending = outcomes.iter().filter(|outcome| outcome.is_none()).count();
+    let failed = outcomes
+        .iter()
+        .flatten()
+        .filter(|outcome| outcome.failed())
+        .count();
+    let skipped = outcomes
+        .iter()
+        .flatten()
+        .filter(|outcome| **outcome == StepOutcome::Skipped)
+        .count();
+    let steps = if total == 1 { "step" } else { "steps" };
+    if pending > 0 {
+        return format!("Running - {} of {total} {steps} done", total - pending);
+    }
+    if failed == 0 {
+        return format!("Passed - {total} {steps}");
+    }
+    let mut summary = format!("Failed - {failed} of {total} {steps} failed");
+    if skipped > 0 {
+        summary.push_str(&format!(", {skipped} skipped"));
+    }
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn step(stage: Stage, url: &str) -> PreparedStep {
+        PreparedStep {
+            stage,
+            method: HttpMethod::GET,
+            url: url.to_string(),
+            headers: Vec::new(),
+            body: None,
+            executor: None,
+            socket: None,
+            hooks: RequestHooks::default(),
+            scripts: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_teardown_runs_after_failed_setup() {
+        // Invalid URLs fail without touching the network
+        let steps = vec![
+            step(Stage::Setup, "not a url"),
+            step(Stage::Setup, "not a url either"),
+            step(Stage::Main, "https://example.com/"),
+            step(Stage::Teardown, " "),
+        ];
+        let mut receiver = run_scenario(steps);
+        let mut outcomes = vec![None; 4];
+        while let Some((index, outcome)) = receiver.blocking_recv() {
+            outcomes[index] = Some(outcome);
+        }
+        assert!(outcomes[0].as_ref().unwrap().failed());
+        assert_eq!(outcomes[1], Some(StepOutcome::Skipped));
+        assert_eq!(outcomes[2], Some(StepOutcome::Skipped));
+        assert!(outcomes[3].as_ref().unwrap().failed());
+        assert_eq!(
+            run_summary(&outcomes),
+            "Failed - 2 of 4 steps failed, 2 skipped"
+        );
+    }
+
+    #[test]
+    fn test_scenario_sends_every_method() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut request_lines = Vec::new();
+            for _ in 0..3 {
+                let (mut socket, _) = listener.accept().unwrap();
+                let mut request = Vec::new();
+                let mut buffer = [0u8; 1024];
+                while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+                    let read = socket.read(&mut buffer).unwrap();
+                    if read == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buffer[..read]);
+                }
+                socket
+                    .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                    .unwrap();
+                let request = String::from_utf8_lossy(&request).into_owned();
+                request_lines.push(request.lines().next().unwrap_or_default().to_string());
+            }
+            request_lines
+        });
+
+        let mut create = step(Stage::Setup, &format!("{base}/users"));
+        create.method = HttpMethod::POST;
+        create.body = Some(b"{}".to_vec());
+        let mut update = step(Stage::Main, &format!("{base}/users/1"));
+        update.method = HttpMethod::PUT;
+        let mut delete = step(Stage::Teardown, &format!("{base}/users/1"));
+        delete.method = HttpMethod::DELETE;
+
+        let mut receiver = run_scenario(vec![create, update, delete]);
+        let mut outcomes = vec![None; 3];
+        while let Some((index, outcome)) = receiver.blocking_recv() {
+            outcomes[index] = Some(outcome);
+        }
+        assert_eq!(run_summary(&outcomes), "Passed - 3 steps");
+        assert_eq!(
+            server.join().unwrap(),
+            vec![
+                "POST /users HTTP/1.1",
+                "PUT /users/1 HTTP/1.1",
+                "DELETE /users/1 HTTP/1.1",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_summary() {
+        let passed = Some(StepOutcome::Passed {
+            status: 200,
+            elapsed: Duration::from_millis(12),
+        });
+        assert_eq!(
+            run_summary(&[passed.clone(), None]),
+            "Running - 1 of 2 steps done"
+        );
+        assert_eq!(run_summary(&[passed.clone()]), "Passed - 1 step");
+        assert_eq!(
+            run_summary(&[passed, Some(StepOutcome::Failed("500".to_string()))]),
+            "Failed - 1 of 2 steps failed"
+        );
+    }
+}
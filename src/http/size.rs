@@ -0,0 +1,132 @@
+//! Estimating how many bytes a request puts on the wire, so oversized
+//! requests can be flagged before the server rejects them.
+
+use crate::errors::AppError;
+
+/// Size above which requests are flagged unless the domain sets its own limit
+pub const DEFAULT_SIZE_LIMIT: u64 = 10 * 1024 * 1024;
+
+/// Bytes of an HTTP/1.1 request: the head (request line, `Host`, headers,
+/// `Content-Length` and the blank line) and the body
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestSize {
+    pub head: u64,
+    pub body: u64,
+}
+
+impl RequestSize {
+    /// Estimate the request as an HTTP/1.1 client would send it
+    pub fn estimate(method: &str, url: &str, headers: &[(String, String)], body: u64) -> Self {
+        let (host, target) = match reqwest::Url::parse(url.trim()) {
+            Ok(parsed) => {
+                let host = parsed.host_str().map(|host| match parsed.port() {
+                    Some(port) => format!("{host}:{port}"),
+                    None => host.to_string(),
+                });
+                let target = match parsed.query() {
+                    Some(query) => format!("{}?{query}", parsed.path()),
+                    None => parsed.path().to_string(),
+                };
+                (host, target)
+            }
+            Err(_) => (None, url.trim().to_string()),
+        };
+
+        let line = |key: &str, value: &str| (key.len() + ": ".len() + value.len() + 2) as u64;
+        let has = |name: &str| {
+            headers
+                .iter()
+                .any(|(key, _)| key.eq_ignore_ascii_case(name))
+        };
+
+        let mut head = (method.len() + 1 + target.len() + " HTTP/1.1\r\n".len()) as u64;
+        if let Some(host) = host.filter(|_| !has("Host")) {
+            head += line("Host", &host);
+        }
+        head += headers
+            .iter()
+            .map(|(key, value)| line(key, value))
+            .sum::<u64>();
+        if body > 0 && !has("Content-Length") {
+            head += line("Content-Length", &body.to_string());
+        }
+        head += 2;
+        Self { head, body }
+    }
+
+    pub fn total(&self) -> u64 {
+        self.head + self.body
+    }
+}
+
+/// Parse a size such as `1048576`, `512 KB`, `1.5mb` or `2G` (units are
+/// powers of 1024)
+pub fn parse_size(text: &str) -> Result<u64, AppError> {
+    let text = text.trim();
+    let invalid = || AppError::ValidationError(format!("Not a size: {text} (e.g. 512 KB, 10 MB)"));
+    let split = text
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .unwrap_or(text.len());
+    let (number, unit) = text.split_at(split);
+    let number: f64 = number.parse().map_err(|_| invalid())?;
+    let multiplier: u64 = match unit.trim().to_ascii_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" | "kib" => 1024,
+        "m" | "mb" | "mib" => 1024 * 1024,
+        "g" | "gb" | "gib" => 1024 * 1024 * 1024,
+        _ => return Err(invalid()),
+    };
+    Ok((number * multiplier as f64).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_estimate_counts_the_wire_format() {
+        let size = RequestSize::estimate(
+            "POST",
+            "https://api.example.com:8443/users?page=2",
+            &headers(&[("Accept", "*/*")]),
+            5,
+        );
+        let expected = "POST /users?page=2 HTTP/1.1\r\n\
+                        Host: api.example.com:8443\r\n\
+                        Accept: */*\r\n\
+                        Content-Length: 5\r\n\
+                        \r\n";
+        assert_eq!(size.head, expected.len() as u64);
+        assert_eq!(size.total(), expected.len() as u64 + 5);
+
+        // Headers the request sets itself are not counted twice
+        let size = RequestSize::estimate(
+            "GET",
+            "http://localhost/",
+            &headers(&[("host", "example.com")]),
+            0,
+        );
+        assert_eq!(
+            size.head,
+            "GET / HTTP/1.1\r\nhost: example.com\r\n\r\n".len() as u64
+        );
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("1048576").unwrap(), 1_048_576);
+        assert_eq!(parse_size("512 KB").unwrap(), 512 * 1024);
+        assert_eq!(parse_size(" 1.5mb ").unwrap(), 1_572_864);
+        assert_eq!(parse_size("2G").unwrap(), 2 * 1024 * 1024 * 1024);
+        assert!(parse_size("").is_err());
+        assert!(parse_size("ten MB").is_err());
+        assert!(parse_size("10 parsecs").is_err());
+    }
+}
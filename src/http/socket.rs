@@ -0,0 +1,313 @@
+//! HTTP over a Unix domain socket or a Windows named pipe (Docker daemon and
+//! other local services), using a custom hyper connector.
+
+use crate::errors::AppError;
+use crate::http::response::HttpResponse;
+use crate::models::HttpMethod;
+use hyper::client::connect::{Connected, Connection};
+use hyper::service::Service;
+use hyper::{Body, Client, Uri};
+use std::future::Future;
+use std::io;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+/// Host used when the request URL is only a path (e.g. `/v1.43/info`)
+const DEFAULT_HOST: &str = "http://localhost";
+
+/// Local socket a request is sent through instead of TCP
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SocketTarget {
+    Unix(PathBuf),
+    /// Full pipe name, e.g. `\\.\pipe\docker_engine`
+    NamedPipe(String),
+}
+
+impl SocketTarget {
+    /// Parse a socket path: `unix:///var/run/docker.sock`, a plain path,
+    /// `npipe:////./pipe/name` (Docker's format) or `\\.\pipe\name`
+    pub fn parse(value: &str) -> Result<Self, AppError> {
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(AppError::ValidationError(
+                "Socket path cannot be empty".to_string(),
+            ));
+        }
+        if let Some(path) = value.strip_prefix("unix://") {
+            return Ok(SocketTarget::Unix(PathBuf::from(path)));
+        }
+        let pipe = value.strip_prefix("npipe://").unwrap_or(value);
+        let normalized = pipe.replace('/', "\\");
+        if normalized.to_ascii_lowercase().starts_with(r"\\.\pipe\") {
+            return Ok(SocketTarget::NamedPipe(normalized));
+        }
+        if value.starts_with("npipe://") {
+            return Err(AppError::ValidationError(format!(
+                r"Named pipes must be under \\.\pipe\: {value}"
+            )));
+        }
+        Ok(SocketTarget::Unix(PathBuf::from(value)))
+    }
+}
+
+impl std::fmt::Display for SocketTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SocketTarget::Unix(path) => write!(f, "unix://{}", path.display()),
+            SocketTarget::NamedPipe(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// The request URL to send over the socket - a bare path gets a placeholder
+/// host, since the socket (not the URL) decides where the request goes
+pub fn socket_uri(url: &str) -> Result<Uri, AppError> {
+    let url = url.trim();
+    let url = if url.starts_with('/') {
+        format!("{DEFAULT_HOST}{url}")
+    } else {
+        url.to_string()
+    };
+    url.parse::<Uri>()
+        .map_err(|e| AppError::ValidationError(format!("Invalid URL {url}: {e}")))
+}
+
+/// Send a request through `target` and read the whole response
+pub async fn send(
+    target: &SocketTarget,
+    method: HttpMethod,
+    url: &str,
+    headers: Vec<(String, String)>,
+    body: Option<Vec<u8>>,
+) -> Result<HttpResponse, AppError> {
+    let uri = socket_uri(url)?;
+    tracing::info!("🧦 SocketClient - {method} {uri} via {target}");
+
+    let client: Client<SocketConnector, Body> = Client::builder().build(SocketConnector {
+        target: target.clone(),
+    });
+    let mut builder = hyper::Request::builder()
+        .method(method.to_string().as_str())
+        .uri(uri);
+    for (key, value) in headers {
+        builder = builder.header(key, value);
+    }
+    let request = builder
+        .body(body.map(Body::from).unwrap_or_else(Body::empty))
+        .map_err(|e| AppError::ValidationError(format!("Invalid request: {e}")))?;
+
+    let response = client
+        .request(request)
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Request via {target} failed: {e}")))?;
+    let status = response.status().as_u16();
+    let headers = response
+        .headers()
+        .iter()
+        .map(|(key, value)| {
+            (
+                key.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect();
+    let bytes = hyper::body::to_bytes(response.into_body())
+        .await
+        .map_err(|e| AppError::NetworkError(format!("Failed to read response body: {e}")))?;
+    Ok(HttpResponse::from_bytes(status, headers, bytes.to_vec()))
+}
+
+/// hyper connector that ignores the URI host and dials the socket instead
+#[derive(Clone)]
+struct SocketConnector {
+    target: SocketTarget,
+}
+
+impl Service<Uri> for SocketConnector {
+    type Response = SocketStream;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<SocketStream>> + Send>>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: Uri) -> Self::Future {
+        let target = self.target.clone();
+        Box::pin(async move { SocketStream::connect(&target).await })
+    }
+}
+
+/// A connected socket; which variants exist depends on the platform
+enum SocketStream {
+    #[cfg(unix)]
+    Unix(tokio::net::UnixStream),
+    #[cfg(windows)]
+    Pipe(tokio::net::windows::named_pipe::NamedPipeClient),
+}
+
+impl SocketStream {
+    async fn connect(target: &SocketTarget) -> io::Result<Self> {
+        match target {
+            #[cfg(unix)]
+            SocketTarget::Unix(path) => Ok(SocketStream::Unix(
+                tokio::net::UnixStream::connect(path).await?,
+            )),
+            #[cfg(windows)]
+            SocketTarget::NamedPipe(name) => Ok(SocketStream::Pipe(open_pipe(name).await?)),
+            other => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{other} is not supported on this platform"),
+            )),
+        }
+    }
+}
+
+/// Open a named pipe client, waiting while every pipe instance is busy
+#[cfg(windows)]
+async fn open_pipe(name: &str) -> io::Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+    use std::time::Duration;
+    use tokio::net::windows::named_pipe::ClientOptions;
+
+    // ERROR_PIPE_BUSY
+    const PIPE_BUSY: i32 = 231;
+    let mut attempts = 0;
+    loop {
+        match ClientOptions::new().open(name) {
+            Err(e) if e.raw_os_error() == Some(PIPE_BUSY) && attempts < 50 => {
+                attempts += 1;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+impl Connection for SocketStream {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for SocketStream {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            #[cfg(unix)]
+            SocketStream::Unix(stream) => Pin::new(stream).poll_read(cx, buf),
+            #[cfg(windows)]
+            SocketStream::Pipe(pipe) => Pin::new(pipe).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for SocketStream {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            #[cfg(unix)]
+            SocketStream::Unix(stream) => Pin::new(stream).poll_write(cx, buf),
+            #[cfg(windows)]
+            SocketStream::Pipe(pipe) => Pin::new(pipe).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            #[cfg(unix)]
+            SocketStream::Unix(stream) => Pin::new(stream).poll_flush(cx),
+            #[cfg(windows)]
+            SocketStream::Pipe(pipe) => Pin::new(pipe).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            #[cfg(unix)]
+            SocketStream::Unix(stream) => Pin::new(stream).poll_shutdown(cx),
+            #[cfg(windows)]
+            SocketStream::Pipe(pipe) => Pin::new(pipe).poll_shutdown(cx),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_socket_target() {
+        assert_eq!(
+            SocketTarget::parse("unix:///var/run/docker.sock").unwrap(),
+            SocketTarget::Unix(PathBuf::from("/var/run/docker.sock"))
+        );
+        assert_eq!(
+            SocketTarget::parse(" /tmp/app.sock ").unwrap(),
+            SocketTarget::Unix(PathBuf::from("/tmp/app.sock"))
+        );
+        assert_eq!(
+            SocketTarget::parse("npipe:////./pipe/docker_engine").unwrap(),
+            SocketTarget::NamedPipe(r"\\.\pipe\docker_engine".to_string())
+        );
+        assert_eq!(
+            SocketTarget::parse(r"\\.\pipe\my_app").unwrap(),
+            SocketTarget::NamedPipe(r"\\.\pipe\my_app".to_string())
+        );
+        assert!(SocketTarget::parse("").is_err());
+        assert!(SocketTarget::parse("npipe://server/share").is_err());
+    }
+
+    #[test]
+    fn test_socket_uri() {
+        assert_eq!(
+            socket_uri("/v1.43/info").unwrap().to_string(),
+            "http://localhost/v1.43/info"
+        );
+        assert_eq!(
+            socket_uri("http://docker/containers/json?all=1")
+                .unwrap()
+                .to_string(),
+            "http://docker/containers/json?all=1"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_send_over_unix_socket() {
+        use std::io::{Read, Write};
+        use std::os::unix::net::UnixListener;
+
+        let path = std::env::temp_dir().join(format!("postman-gpui-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+        let server = std::thread::spawn(move || {
+            let (mut socket, _) = listener.accept().unwrap();
+            let mut request = [0u8; 1024];
+            let read = socket.read(&mut request).unwrap();
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .unwrap();
+            String::from_utf8_lossy(&request[..read]).into_owned()
+        });
+
+        let rt = tokio::runtime::Runtime::new().unwrap();
+        let target = SocketTarget::Unix(path.clone());
+        let response = rt
+            .block_on(send(&target, HttpMethod::GET, "/_ping", Vec::new(), None))
+            .unwrap();
+        let request = server.join().unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        assert!(request.starts_with("GET /_ping HTTP/1.1\r\n"));
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.body, "ok");
+    }
+}
@@ -0,0 +1,336 @@
+//! WebSocket client connections over `ws://` and `wss://`. Each connection
+//! runs on its own thread: text messages go in through
+//! [`WebSocketConnection::send_text`] and everything the server sends comes
+//! back as [`WebSocketEvent`]s. Pings are answered automatically.
+
+use crate::errors::AppError;
+use crate::utils::websocket::{
+    client_frame, close_payload, handshake_key, handshake_request, verify_handshake, FrameDecoder,
+    Message, Opcode,
+};
+use hyper::Uri;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
+
+/// How long to wait for the TCP connection, the upgrade response and the
+/// server's answer to a close frame
+pub const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Largest handshake response accepted
+const MAX_HANDSHAKE_BYTES: usize = 16 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebSocketEvent {
+    /// Handshake done; the subprotocol the server picked
+    Opened {
+        protocol: Option<String>,
+    },
+    Text(String),
+    Binary(Vec<u8>),
+    /// The connection ended (close frame, or the server dropped it)
+    Closed {
+        code: Option<u16>,
+        reason: String,
+    },
+    /// Connection or protocol failure; no further events follow
+    Error(String),
+}
+
+/// Where a WebSocket URL points
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WebSocketTarget {
+    pub tls: bool,
+    pub host: String,
+    pub port: u16,
+    /// Path and query sent in the upgrade request
+    pub path: String,
+}
+
+impl WebSocketTarget {
+    /// Parse a `ws://` or `wss://` URL
+    pub fn parse(url: &str) -> Result<Self, AppError> {
+        let uri = url
+            .trim()
+            .parse::<Uri>()
+            .map_err(|e| AppError::ValidationError(format!("Invalid WebSocket URL {url}: {e}")))?;
+        let tls = match uri.scheme_str() {
+            Some("ws") => false,
+            Some("wss") => true,
+            _ => {
+                return Err(AppError::ValidationError(format!(
+                    "WebSocket URLs start with ws:// or wss:// ({url})"
+                )))
+            }
+        };
+        let host = uri
+            .host()
+            .filter(|host| !host.is_empty())
+            .ok_or_else(|| AppError::ValidationError(format!("Missing host in {url}")))?;
+        Ok(Self {
+            tls,
+            host: host.trim_matches(['[', ']']).to_string(),
+            port: uri.port_u16().unwrap_or(if tls { 443 } else { 80 }),
+            path: uri
+                .path_and_query()
+                .map(|path| path.as_str())
+                .filter(|path| !path.is_empty())
+                .unwrap_or("/")
+                .to_string(),
+        })
+    }
+
+    /// `Host` header value
+    fn authority(&self) -> String {
+        let host = if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        };
+        if self.port == if self.tls { 443 } else { 80 } {
+            host
+        } else {
+            format!("{host}:{}", self.port)
+        }
+    }
+}
+
+/// An open (or opening) connection
+pub struct WebSocketConnection {
+    outgoing: UnboundedSender<Outgoing>,
+}
+
+enum Outgoing {
+    Text(String),
+    Close,
+}
+
+impl WebSocketConnection {
+    /// Connect to `target`, offering `protocols`; `headers` are added to the
+    /// upgrade request
+    pub fn connect(
+        target: WebSocketTarget,
+        protocols: Vec<String>,
+        headers: Vec<(String, String)>,
+    ) -> (Self, UnboundedReceiver<WebSocketEvent>) {
+        let (events, receiver) = unbounded_channel();
+        let (outgoing, messages) = unbounded_channel();
+        tracing::info!(
+            "🔌 WebSocket - 连接 {}:{}{}",
+            target.host,
+            target.port,
+            target.path
+        );
+
+        std::thread::spawn(move || {
+            let rt = match tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+            {
+                Ok(rt) => rt,
+                Err(e) => {
+                    let _ = events.send(WebSocketEvent::Error(e.to_string()));
+                    return;
+                }
+            };
+            rt.block_on(async {
+                if let Err(e) = run_connection(target, protocols, headers, messages, &events).await
+                {
+                    tracing::info!("❌ WebSocket - 连接失败: {e}");
+                    let _ = events.send(WebSocketEvent::Error(e.to_string()));
+                }
+            });
+        });
+
+        (Self { outgoing }, receiver)
+    }
+
+    /// Queue a text message (sent once the handshake is done)
+    pub fn send_text(&self, text: impl Into<String>) -> Result<(), AppError> {
+        self.outgoing
+            .send(Outgoing::Text(text.into()))
+            .map_err(|_| AppError::NetworkError("The connection is closed".to_string()))
+    }
+
+    /// Start the closing handshake; a `Closed` event follows
+    pub fn close(&self) {
+        let _ = self.outgoing.send(Outgoing::Close);
+    }
+}
+
+async fn run_connection(
+    target: WebSocketTarget,
+    protocols: Vec<String>,
+    headers: Vec<(String, String)>,
+    messages: UnboundedReceiver<Outgoing>,
+    events: &UnboundedSender<WebSocketEvent>,
+) -> Result<(), AppError> {
+    let tcp = tokio::time::timeout(
+        CONNECT_TIMEOUT,
+        TcpStream::connect((target.host.as_str(), target.port)),
+    )
+    .await
+    .map_err(|_| AppError::NetworkError(format!("Connection to {} timed out", target.host)))?
+    .map_err(|e| AppError::NetworkError(format!("Connection to {} failed: {e}", target.host)))?;
+
+    if target.tls {
+        let connector = native_tls::TlsConnector::new()
+            .map_err(|e| AppError::NetworkError(format!("TLS setup failed: {e}")))?;
+        let stream = tokio_native_tls::TlsConnector::from(connector)
+            .connect(&target.host, tcp)
+            .await
+            .map_err(|e| AppError::NetworkError(format!("TLS handshake failed: {e}")))?;
+        run_session(stream, &target, &protocols, &headers, messages, events).await
+    } else {
+        run_session(tcp, &target, &protocols, &headers, messages, events).await
+    }
+}
+
+async fn run_session<S: AsyncRead + AsyncWrite + Unpin>(
+    mut stream: S,
+    target: &WebSocketTarget,
+    protocols: &[String],
+    headers: &[(String, String)],
+    mut messages: UnboundedReceiver<Outgoing>,
+    events: &UnboundedSender<WebSocketEvent>,
+) -> Result<(), AppError> {
+    let key = handshake_key();
+    let protocols: Vec<&str> = protocols.iter().map(String::as_str).collect();
+    let request = handshake_request(&target.authority(), &target.path, &key, &protocols, headers);
+    stream
+        .write_all(request.as_bytes())
+        .await
+        .map_err(network_error)?;
+
+    // Read the upgrade response; frames may follow it in the same read
+    let mut received = Vec::new();
+    let mut buffer = [0u8; 8192];
+    let header_end = loop {
+        if let Some(end) = received.windows(4).position(|window| window == b"\r\n\r\n") {
+            break end;
+        }
+        if received.len() > MAX_HANDSHAKE_BYTES {
+            return Err(AppError::NetworkError(
+                "WebSocket upgrade response is too large".to_string(),
+            ));
+        }
+        let read = tokio::time::timeout(CONNECT_TIMEOUT, stream.read(&mut buffer))
+            .await
+            .map_err(|_| AppError::NetworkError("No WebSocket upgrade response".to_string()))?
+            .map_err(network_error)?;
+        if read == 0 {
+            return Err(AppError::NetworkError(
+                "Server closed the connection during the upgrade".to_string(),
+            ));
+        }
+        received.extend_from_slice(&buffer[..read]);
+    };
+    let protocol = verify_handshake(&String::from_utf8_lossy(&received[..header_end]), &key)?;
+    tracing::info!("✅ WebSocket - 已连接 (protocol: {protocol:?})");
+    if events.send(WebSocketEvent::Opened { protocol }).is_err() {
+        return Ok(());
+    }
+
+    let (mut reader, mut writer) = tokio::io::split(stream);
+    let mut decoder = FrameDecoder::new();
+    let mut pending = received.split_off(header_end + 4);
+    let mut closing = false;
+    loop {
+        for message in decoder.push(&pending)? {
+            match message {
+                Message::Text(text) => {
+                    let _ = events.send(WebSocketEvent::Text(text));
+                }
+                Message::Binary(data) => {
+                    let _ = events.send(WebSocketEvent::Binary(data));
+                }
+                Message::Ping(payload) => {
+                    writer
+                        .write_all(&client_frame(Opcode::Pong, &payload))
+                        .await
+                        .map_err(network_error)?;
+                }
+                Message::Pong(_) => {}
+                Message::Close(status) => {
+                    if !closing {
+                        // Echo the close frame to finish the closing handshake
+                        let _ = writer
+                            .write_all(&client_frame(Opcode::Close, &close_payload(1000, "")))
+                            .await;
+                    }
+                    let (code, reason) =
+                        status.map_or((None, String::new()), |(code, reason)| (Some(code), reason));
+                    tracing::info!("🔌 WebSocket - 连接已关闭: {code:?} {reason}");
+                    let _ = events.send(WebSocketEvent::Closed { code, reason });
+                    return Ok(());
+                }
+            }
+        }
+        pending.clear();
+
+        tokio::select! {
+            read = reader.read(&mut buffer) => {
+                let read = read.map_err(network_error)?;
+                if read == 0 {
+                    let _ = events.send(WebSocketEvent::Closed {
+                        code: None,
+                        reason: "Connection dropped without a close frame".to_string(),
+                    });
+                    return Ok(());
+                }
+                pending.extend_from_slice(&buffer[..read]);
+            }
+            outgoing = messages.recv(), if !closing => {
+                let frame = match outgoing {
+                    Some(Outgoing::Text(text)) => client_frame(Opcode::Text, text.as_bytes()),
+                    // Close requested, or the connection handle was dropped
+                    Some(Outgoing::Close) | None => {
+                        closing = true;
+                        client_frame(Opcode::Close, &close_payload(1000, "Closed by client"))
+                    }
+                };
+                writer.write_all(&frame).await.map_err(network_error)?;
+            }
+            _ = tokio::time::sleep(CONNECT_TIMEOUT), if closing => {
+                let _ = events.send(WebSocketEvent::Closed {
+                    code: None,
+                    reason: "Server did not answer the close frame".to_string(),
+                });
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn network_error(err: std::io::Error) -> AppError {
+    AppError::NetworkError(err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let target = WebSocketTarget::parse("wss://api.example.com/graphql?x=1").unwrap();
+        assert_eq!(
+            target,
+            WebSocketTarget {
+                tls: true,
+                host: "api.example.com".to_string(),
+                port: 443,
+                path: "/graphql?x=1".to_string(),
+            }
+        );
+        assert_eq!(target.authority(), "api.example.com");
+
+        let target = WebSocketTarget::parse("ws://[::1]:4000").unwrap();
+        assert_eq!((target.host.as_str(), target.port), ("::1", 4000));
+        assert_eq!(target.path, "/");
+        assert_eq!(target.authority(), "[::1]:4000");
+
+        assert!(WebSocketTarget::parse("http://example.com").is_err());
+        assert!(WebSocketTarget::parse("not a url").is_err());
+    }
+}
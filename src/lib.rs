@@ -1,8 +1,15 @@
 // src/lib.rs
 pub mod app;
 pub mod assets;
+pub mod config;
 pub mod errors;
 pub mod http;
 pub mod models;
+pub mod plugins;
+pub mod state;
+pub mod storage;
 pub mod ui;
 pub mod utils;
+
+#[cfg(test)]
+mod test_support;
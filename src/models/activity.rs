@@ -0,0 +1,200 @@
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Maximum number of activity entries to keep
+const DEFAULT_MAX_ACTIVITY_ENTRIES: usize = 500;
+
+/// Kind of structural change recorded in the activity log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ActivityKind {
+    RequestShared,
+    RequestOpened,
+    SpecImported,
+    DomainsChanged,
+    CertificatesChanged,
+    SnippetsChanged,
+    HooksChanged,
+    BaselinesChanged,
+    ExamplesChanged,
+    FormatRulesChanged,
+    ScenariosChanged,
+    CollectionsChanged,
+    EnvironmentsChanged,
+    ProfileCreated,
+    ProfileSwitched,
+    EncryptionChanged,
+}
+
+impl ActivityKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RequestShared => "Request shared",
+            Self::RequestOpened => "Request opened",
+            Self::SpecImported => "Spec imported",
+            Self::DomainsChanged => "Domain defaults",
+            Self::CertificatesChanged => "Certificates",
+            Self::SnippetsChanged => "Assertions",
+            Self::HooksChanged => "Hooks",
+            Self::BaselinesChanged => "Baselines",
+            Self::ExamplesChanged => "Examples",
+            Self::FormatRulesChanged => "Formatting rules",
+            Self::ScenariosChanged => "Scenarios",
+            Self::CollectionsChanged => "Collections",
+            Self::EnvironmentsChanged => "Environments",
+            Self::ProfileCreated => "Profile created",
+            Self::ProfileSwitched => "Profile switched",
+            Self::EncryptionChanged => "Encryption",
+        }
+    }
+}
+
+/// One recorded action
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActivityEntry {
+    pub timestamp: DateTime<Utc>,
+    pub kind: ActivityKind,
+    /// What the action touched, e.g. `Added api.example.com`
+    pub detail: String,
+}
+
+impl ActivityEntry {
+    /// Get formatted local timestamp
+    pub fn formatted_time(&self) -> String {
+        self.timestamp
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+}
+
+/// Log of structural changes to a profile (requests shared or opened,
+/// specs imported, settings changed), newest first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityLog {
+    entries: Vec<ActivityEntry>,
+    max_entries: usize,
+}
+
+impl ActivityLog {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+            max_entries: DEFAULT_MAX_ACTIVITY_ENTRIES,
+        }
+    }
+
+    /// Record an action that happened now
+    pub fn record(&mut self, kind: ActivityKind, detail: impl Into<String>) {
+        self.record_at(Utc::now(), kind, detail);
+    }
+
+    /// Record an action with an explicit timestamp
+    pub fn record_at(
+        &mut self,
+        timestamp: DateTime<Utc>,
+        kind: ActivityKind,
+        detail: impl Into<String>,
+    ) {
+        self.entries.insert(
+            0,
+            ActivityEntry {
+                timestamp,
+                kind,
+                detail: detail.into(),
+            },
+        );
+        self.entries.truncate(self.max_entries);
+    }
+
+    pub fn entries(&self) -> &[ActivityEntry] {
+        &self.entries
+    }
+
+    /// Entries recorded at or after `cutoff`, newest first
+    pub fn since(&self, cutoff: DateTime<Utc>) -> impl Iterator<Item = &ActivityEntry> {
+        self.entries
+            .iter()
+            .filter(move |entry| entry.timestamp >= cutoff)
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    /// Plain text export, one `time  kind  detail` line per entry
+    pub fn to_text<'a>(entries: impl IntoIterator<Item = &'a ActivityEntry>) -> String {
+        entries
+            .into_iter()
+            .map(|entry| {
+                format!(
+                    "{}  {:<17} {}\n",
+                    entry.formatted_time(),
+                    entry.kind.label(),
+                    entry.detail
+                )
+            })
+            .collect()
+    }
+}
+
+impl Default for ActivityLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_record_and_filter_since() {
+        let now = Utc::now();
+        let mut log = ActivityLog::new();
+        log.record_at(
+            now - Duration::days(3),
+            ActivityKind::ProfileCreated,
+            "Work",
+        );
+        log.record_at(
+            now - Duration::hours(2),
+            ActivityKind::DomainsChanged,
+            "Added api.example.com",
+        );
+        log.record_at(now, ActivityKind::RequestShared, "users.pgreq");
+
+        assert_eq!(log.entries().len(), 3);
+        assert_eq!(log.entries()[0].kind, ActivityKind::RequestShared);
+
+        let recent: Vec<_> = log.since(now - Duration::days(1)).collect();
+        assert_eq!(recent.len(), 2);
+        assert_eq!(recent[1].detail, "Added api.example.com");
+
+        let text = ActivityLog::to_text(recent);
+        assert_eq!(text.lines().count(), 2);
+        assert!(text.contains("Domain defaults   Added api.example.com"));
+
+        log.clear();
+        assert!(log.entries().is_empty());
+    }
+
+    #[test]
+    fn test_log_is_capped_and_round_trips() {
+        let mut log = ActivityLog::new();
+        for i in 0..DEFAULT_MAX_ACTIVITY_ENTRIES + 5 {
+            log.record(ActivityKind::RequestOpened, format!("{i}.pgreq"));
+        }
+        assert_eq!(log.entries().len(), DEFAULT_MAX_ACTIVITY_ENTRIES);
+        assert_eq!(
+            log.entries()[0].detail,
+            format!("{}.pgreq", DEFAULT_MAX_ACTIVITY_ENTRIES + 4)
+        );
+
+        let json = serde_json::to_string(&log).unwrap();
+        assert!(json.contains("\"request_opened\""));
+        let loaded: ActivityLog = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded.entries(), log.entries());
+    }
+}
@@ -0,0 +1,187 @@
+use crate::errors::AppError;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+/// Highlight colors available for response annotations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HighlightColor {
+    Yellow,
+    Green,
+    Blue,
+    Pink,
+}
+
+impl HighlightColor {
+    /// All highlight colors, in picker order
+    pub fn all() -> [HighlightColor; 4] {
+        [
+            HighlightColor::Yellow,
+            HighlightColor::Green,
+            HighlightColor::Blue,
+            HighlightColor::Pink,
+        ]
+    }
+
+    /// Background color as `0xRRGGBB`
+    pub fn rgb(self) -> u32 {
+        match self {
+            HighlightColor::Yellow => 0x00ff_e066,
+            HighlightColor::Green => 0x00a3_e4a7,
+            HighlightColor::Blue => 0x00a5_d8ff,
+            HighlightColor::Pink => 0x00ff_b3cf,
+        }
+    }
+}
+
+/// A highlighted range of a response body with an optional comment.
+///
+/// `start` and `end` are character offsets into the body.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Annotation {
+    pub start: usize,
+    pub end: usize,
+    pub color: HighlightColor,
+    #[serde(default)]
+    pub comment: String,
+}
+
+impl Annotation {
+    pub fn range(&self) -> Range<usize> {
+        self.start..self.end
+    }
+}
+
+/// A response saved together with its annotations
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedResponse {
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub headers: Vec<(String, String)>,
+    pub body: String,
+    #[serde(default)]
+    pub annotations: Vec<Annotation>,
+}
+
+impl SavedResponse {
+    pub fn new(status: u16, body: impl Into<String>) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: body.into(),
+            annotations: Vec::new(),
+        }
+    }
+
+    /// Keep the response headers (e.g. `Content-Range`, `ETag`)
+    pub fn with_headers(mut self, headers: Vec<(String, String)>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Highlight `range` of the body, keeping annotations ordered by position
+    pub fn add_annotation(
+        &mut self,
+        range: Range<usize>,
+        color: HighlightColor,
+        comment: impl Into<String>,
+    ) -> Result<(), AppError> {
+        if range.is_empty() || range.end > self.body.chars().count() {
+            return Err(AppError::ValidationError(format!(
+                "Invalid annotation range {}..{}",
+                range.start, range.end
+            )));
+        }
+
+        let annotation = Annotation {
+            start: range.start,
+            end: range.end,
+            color,
+            comment: comment.into(),
+        };
+        let index = self
+            .annotations
+            .partition_point(|existing| existing.start <= annotation.start);
+        self.annotations.insert(index, annotation);
+        Ok(())
+    }
+
+    pub fn remove_annotation(&mut self, index: usize) -> Option<Annotation> {
+        (index < self.annotations.len()).then(|| self.annotations.remove(index))
+    }
+
+    /// The text an annotation refers to
+    pub fn annotated_text(&self, annotation: &Annotation) -> String {
+        self.body
+            .chars()
+            .skip(annotation.start)
+            .take(annotation.end.saturating_sub(annotation.start))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_annotation_keeps_order() {
+        let mut response = SavedResponse::new(200, r#"{"id": 1, "name": "John"}"#);
+        response
+            .add_annotation(10..16, HighlightColor::Yellow, "check this")
+            .unwrap();
+        response
+            .add_annotation(1..5, HighlightColor::Green, "")
+            .unwrap();
+
+        assert_eq!(response.annotations.len(), 2);
+        assert_eq!(response.annotations[0].start, 1);
+        assert_eq!(response.annotated_text(&response.annotations[0]), "\"id\"");
+        assert_eq!(
+            response.annotated_text(&response.annotations[1]),
+            "\"name\""
+        );
+    }
+
+    #[test]
+    fn test_add_annotation_rejects_invalid_range() {
+        let mut response = SavedResponse::new(200, "héllo");
+        assert!(response
+            .add_annotation(2..2, HighlightColor::Blue, "")
+            .is_err());
+        assert!(response
+            .add_annotation(0..6, HighlightColor::Blue, "")
+            .is_err());
+        assert!(response
+            .add_annotation(0..5, HighlightColor::Blue, "")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_remove_annotation() {
+        let mut response = SavedResponse::new(200, "hello world");
+        response
+            .add_annotation(0..5, HighlightColor::Pink, "greeting")
+            .unwrap();
+
+        assert!(response.remove_annotation(3).is_none());
+        let removed = response.remove_annotation(0).unwrap();
+        assert_eq!(removed.comment, "greeting");
+        assert!(response.annotations.is_empty());
+    }
+
+    #[test]
+    fn test_saved_response_serialization() {
+        let mut response = SavedResponse::new(404, "not found");
+        response
+            .add_annotation(0..3, HighlightColor::Yellow, "why?")
+            .unwrap();
+
+        let json = serde_json::to_string(&response).unwrap();
+        let parsed: SavedResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, response);
+
+        let parsed: SavedResponse =
+            serde_json::from_str(r#"{"status": 200, "body": "ok"}"#).unwrap();
+        assert!(parsed.annotations.is_empty());
+    }
+}
@@ -0,0 +1,106 @@
+use crate::models::HttpMethod;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A response pinned as the expected ("golden") answer to a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub method: HttpMethod,
+    pub url: String,
+    pub status: u16,
+    pub body: String,
+    pub pinned_at: DateTime<Utc>,
+    /// JSON paths left out of the comparison, e.g. `$.requestId`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ignored: Vec<String>,
+}
+
+/// Golden baselines of a profile, saved as `baselines.json`. A request is
+/// matched to its baseline by method and URL
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct BaselineStore {
+    baselines: Vec<Baseline>,
+}
+
+impl BaselineStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn baselines(&self) -> &[Baseline] {
+        &self.baselines
+    }
+
+    pub fn get(&self, method: HttpMethod, url: &str) -> Option<&Baseline> {
+        self.baselines
+            .iter()
+            .find(|baseline| baseline.method == method && baseline.url == url)
+    }
+
+    /// Pin a response for the request, replacing its previous baseline but
+    /// keeping the ignored paths
+    pub fn pin(&mut self, method: HttpMethod, url: &str, status: u16, body: &str) {
+        let ignored = self.unpin(method, url).map(|old| old.ignored);
+        self.baselines.push(Baseline {
+            method,
+            url: url.to_string(),
+            status,
+            body: body.to_string(),
+            pinned_at: Utc::now(),
+            ignored: ignored.unwrap_or_default(),
+        });
+    }
+
+    /// Remove the request's baseline, returning it
+    pub fn unpin(&mut self, method: HttpMethod, url: &str) -> Option<Baseline> {
+        let index = self
+            .baselines
+            .iter()
+            .position(|baseline| baseline.method == method && baseline.url == url)?;
+        Some(self.baselines.remove(index))
+    }
+
+    /// Replace the ignored paths of the request's baseline, `false` if it
+    /// has none
+    pub fn set_ignored(&mut self, method: HttpMethod, url: &str, ignored: Vec<String>) -> bool {
+        match self
+            .baselines
+            .iter_mut()
+            .find(|baseline| baseline.method == method && baseline.url == url)
+        {
+            Some(baseline) => {
+                baseline.ignored = ignored;
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pin_and_unpin_baselines() {
+        let url = "https://api.example.com/users";
+        let mut store = BaselineStore::new();
+        store.pin(HttpMethod::GET, url, 200, "[]");
+        assert!(store.get(HttpMethod::POST, url).is_none());
+        assert!(store.set_ignored(HttpMethod::GET, url, vec!["$.at".to_string()]));
+        assert!(!store.set_ignored(HttpMethod::POST, url, Vec::new()));
+
+        // Re-pinning replaces the response and keeps the ignored paths
+        store.pin(HttpMethod::GET, url, 201, "[1]");
+        assert_eq!(store.baselines().len(), 1);
+        let baseline = store.get(HttpMethod::GET, url).unwrap();
+        assert_eq!((baseline.status, baseline.body.as_str()), (201, "[1]"));
+        assert_eq!(baseline.ignored, ["$.at"]);
+
+        let json = serde_json::to_string(&store).unwrap();
+        assert_eq!(serde_json::from_str::<BaselineStore>(&json).unwrap(), store);
+
+        assert!(store.unpin(HttpMethod::GET, url).is_some());
+        assert!(store.baselines().is_empty());
+    }
+}
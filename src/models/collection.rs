@@ -1,6 +1,8 @@
 use super::request::Request;
+use crate::errors::AppError;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Collection {
     pub name: String,
     pub requests: Vec<Request>,
@@ -28,3 +30,76 @@ impl Collection {
         self.requests.get(index)
     }
 }
+
+/// Collections of a profile, saved as `collections.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CollectionLibrary {
+    collections: Vec<Collection>,
+}
+
+impl CollectionLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn collections(&self) -> &[Collection] {
+        &self.collections
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Collection> {
+        self.collections.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Collection> {
+        self.collections.get_mut(index)
+    }
+
+    /// Add an empty collection, returning its index; names must be unique
+    /// (ignoring case)
+    pub fn add(&mut self, name: &str) -> Result<usize, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Collection name cannot be empty".to_string(),
+            ));
+        }
+        if self
+            .collections
+            .iter()
+            .any(|collection| collection.name.eq_ignore_ascii_case(name))
+        {
+            return Err(AppError::ValidationError(format!(
+                "A collection named \"{name}\" already exists"
+            )));
+        }
+        self.collections.push(Collection::new(name.to_string()));
+        Ok(self.collections.len() - 1)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Collection> {
+        (index < self.collections.len()).then(|| self.collections.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_collection_names_are_unique() {
+        let mut library = CollectionLibrary::new();
+        let users = library.add(" Users API ").unwrap();
+        assert_eq!(library.collections()[users].name, "Users API");
+        assert!(library.add("users api").is_err());
+        assert!(library.add("  ").is_err());
+
+        let collection = library.get_mut(users).unwrap();
+        collection.add_request(Request::new("GET", "https://api.example.com/users"));
+        assert_eq!(
+            library.get(users).unwrap().get_request(0).unwrap().url,
+            "https://api.example.com/users"
+        );
+        assert_eq!(library.remove(users).unwrap().requests.len(), 1);
+        assert!(library.remove(users).is_none());
+    }
+}
@@ -0,0 +1,254 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A cookie a server set with `Set-Cookie`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    /// Host the cookie was set by, or the `Domain` it was set for
+    pub domain: String,
+    /// Sent to `domain` only, not to its subdomains (no `Domain` attribute)
+    #[serde(default)]
+    pub host_only: bool,
+    pub path: String,
+    #[serde(default)]
+    pub secure: bool,
+    /// Kept until the profile's cookies are cleared when `None`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<DateTime<Utc>>,
+}
+
+impl Cookie {
+    /// The cookie a `Set-Cookie` value sets for a response from `url`, `None`
+    /// if it is malformed or names a domain `url` isn't part of
+    pub fn parse(set_cookie: &str, url: &reqwest::Url, now: DateTime<Utc>) -> Option<Self> {
+        let host = url.host_str()?.to_ascii_lowercase();
+        let mut attributes = set_cookie.split(';');
+        let (name, value) = attributes.next()?.split_once('=')?;
+        let name = name.trim();
+        if name.is_empty() {
+            return None;
+        }
+
+        let mut cookie = Self {
+            name: name.to_string(),
+            value: value.trim().to_string(),
+            domain: host.clone(),
+            host_only: true,
+            path: default_path(url.path()),
+            secure: false,
+            expires: None,
+        };
+        let mut max_age = None;
+        for attribute in attributes {
+            let (key, value) = attribute.split_once('=').unwrap_or((attribute, ""));
+            let value = value.trim();
+            match key.trim().to_ascii_lowercase().as_str() {
+                "domain" if !value.is_empty() => {
+                    let domain = value.trim_start_matches('.').to_ascii_lowercase();
+                    if !domain_matches(&host, &domain) {
+                        return None;
+                    }
+                    cookie.domain = domain;
+                    cookie.host_only = false;
+                }
+                "path" if value.starts_with('/') => cookie.path = value.to_string(),
+                "secure" => cookie.secure = true,
+                "max-age" => max_age = value.parse::<i64>().ok(),
+                "expires" => {
+                    cookie.expires = cookie.expires.or_else(|| {
+                        DateTime::parse_from_rfc2822(&value.replace('-', " "))
+                            .ok()
+                            .map(|date| date.with_timezone(&Utc))
+                    })
+                }
+                _ => {}
+            }
+        }
+        // Max-Age wins over Expires; like browsers, keep a cookie 400 days at most
+        if let Some(seconds) = max_age {
+            let seconds = seconds.clamp(0, 400 * 24 * 60 * 60);
+            cookie.expires = Some(now + Duration::seconds(seconds));
+        }
+        Some(cookie)
+    }
+
+    fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires.is_some_and(|expires| expires <= now)
+    }
+
+    fn matches(&self, url: &reqwest::Url) -> bool {
+        let Some(host) = url.host_str().map(str::to_ascii_lowercase) else {
+            return false;
+        };
+        let domain = if self.host_only {
+            host == self.domain
+        } else {
+            domain_matches(&host, &self.domain)
+        };
+        let path = url.path();
+        let path = path == self.path
+            || (path.starts_with(&self.path)
+                && (self.path.ends_with('/') || path[self.path.len()..].starts_with('/')));
+        domain && path && (!self.secure || url.scheme() == "https")
+    }
+}
+
+/// Cookies of a profile, saved as `cookies.json`: stored from responses and
+/// sent back with later requests to the same site
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct CookieJar {
+    cookies: Vec<Cookie>,
+}
+
+impl CookieJar {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cookies(&self) -> &[Cookie] {
+        &self.cookies
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    /// Store the `Set-Cookie` headers of a response from `url`, returning
+    /// whether the jar changed. A cookie replaces the one with the same
+    /// name, domain and path; an expired one removes it
+    pub fn store(&mut self, url: &str, headers: &[(String, String)], now: DateTime<Utc>) -> bool {
+        let Ok(url) = reqwest::Url::parse(url.trim()) else {
+            return false;
+        };
+        let mut changed = false;
+        for (_, value) in headers
+            .iter()
+            .filter(|(key, _)| key.eq_ignore_ascii_case("Set-Cookie"))
+        {
+            let Some(cookie) = Cookie::parse(value, &url, now) else {
+                continue;
+            };
+            self.cookies.retain(|existing| {
+                let same = existing.name == cookie.name
+                    && existing.domain == cookie.domain
+                    && existing.path == cookie.path;
+                changed |= same;
+                !same
+            });
+            if !cookie.is_expired(now) {
+                self.cookies.push(cookie);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// `Cookie` header value for a request to `url`, `None` when no stored
+    /// cookie applies. Longer paths come first
+    pub fn header_for(&self, url: &str, now: DateTime<Utc>) -> Option<String> {
+        let url = reqwest::Url::parse(url.trim()).ok()?;
+        let mut cookies: Vec<&Cookie> = self
+            .cookies
+            .iter()
+            .filter(|cookie| !cookie.is_expired(now) && cookie.matches(&url))
+            .collect();
+        if cookies.is_empty() {
+            return None;
+        }
+        cookies.sort_by_key(|cookie| std::cmp::Reverse(cookie.path.len()));
+        Some(
+            cookies
+                .iter()
+                .map(|cookie| format!("{}={}", cookie.name, cookie.value))
+                .collect::<Vec<_>>()
+                .join("; "),
+        )
+    }
+
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+}
+
+// `host` is `domain` or one of its subdomains
+fn domain_matches(host: &str, domain: &str) -> bool {
+    host == domain || (host.ends_with(domain) && host[..host.len() - domain.len()].ends_with('.'))
+}
+
+// Directory of the request path, the path a cookie without `Path` applies to
+fn default_path(path: &str) -> String {
+    match path.rfind('/') {
+        Some(0) | None => "/".to_string(),
+        Some(end) => path[..end].to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set_cookies(values: &[&str]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|value| ("Set-Cookie".to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_cookies_are_sent_back_to_their_site() {
+        let now = Utc::now();
+        let mut jar = CookieJar::new();
+        assert!(jar.store(
+            "https://api.example.com/v1/login",
+            &set_cookies(&[
+                "session=abc; Path=/; Secure; HttpOnly",
+                "theme=dark; Domain=example.com; Path=/",
+                "scope=v1",
+                "stolen=1; Domain=other.com",
+            ]),
+            now,
+        ));
+        assert_eq!(jar.cookies().len(), 3);
+
+        assert_eq!(
+            jar.header_for("https://api.example.com/v1/users", now)
+                .as_deref(),
+            Some("scope=v1; session=abc; theme=dark")
+        );
+        // Secure cookies only go over https, host-only ones only to their host
+        assert_eq!(
+            jar.header_for("http://www.example.com/v1", now).as_deref(),
+            Some("theme=dark")
+        );
+        assert_eq!(
+            jar.header_for("https://api.example.com/v10", now)
+                .as_deref(),
+            Some("session=abc; theme=dark")
+        );
+        assert_eq!(jar.header_for("https://example.org/", now), None);
+    }
+
+    #[test]
+    fn test_cookies_expire_and_are_replaced() {
+        let now = Utc::now();
+        let mut jar = CookieJar::new();
+        let url = "https://api.example.com/";
+        jar.store(
+            url,
+            &set_cookies(&[
+                "a=1; Max-Age=60",
+                "b=2; Expires=Wed, 21 Oct 2015 07:28:00 GMT",
+            ]),
+            now,
+        );
+        assert_eq!(jar.header_for(url, now).as_deref(), Some("a=1"));
+        assert_eq!(jar.header_for(url, now + Duration::seconds(61)), None);
+
+        jar.store(url, &set_cookies(&["a=2"]), now);
+        assert_eq!(jar.header_for(url, now).as_deref(), Some("a=2"));
+        assert!(jar.store(url, &set_cookies(&["a=; Max-Age=0"]), now));
+        assert!(jar.is_empty());
+    }
+}
@@ -0,0 +1,287 @@
+use super::request::Request;
+use crate::errors::AppError;
+use serde::{Deserialize, Serialize};
+
+/// Credential handed to an auth provider plugin for every request to a domain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainAuth {
+    /// Name of the registered auth provider
+    pub provider: String,
+    pub credential: String,
+}
+
+/// Connection options that need a dedicated HTTP client
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransportOptions {
+    /// Proxy URL, e.g. `http://proxy.internal:3128`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Accept self-signed and otherwise invalid TLS certificates
+    #[serde(default)]
+    pub accept_invalid_certs: bool,
+    /// Name of the imported client certificate to authenticate with
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_certificate: Option<String>,
+}
+
+impl TransportOptions {
+    /// Whether the default client can be used as is
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Defaults applied to every request whose URL targets a matching host
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DomainDefaults {
+    /// `api.example.com`, `*.example.com` (any subdomain) or
+    /// `localhost:8080` (only that port)
+    pub host: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auth: Option<DomainAuth>,
+    #[serde(default)]
+    pub transport: TransportOptions,
+    /// Largest request (in bytes) the server accepts, to warn before sending
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_request_size: Option<u64>,
+}
+
+impl DomainDefaults {
+    pub fn new(host: impl Into<String>) -> Self {
+        Self {
+            host: host.into(),
+            headers: Vec::new(),
+            auth: None,
+            transport: TransportOptions::default(),
+            max_request_size: None,
+        }
+    }
+
+    /// How closely the pattern matches `host` (and `port`), `None` if it
+    /// doesn't. Exact hosts beat wildcards, longer patterns beat shorter ones
+    /// and a pattern naming the port beats one that doesn't.
+    fn specificity(&self, host: &str, port: Option<&str>) -> Option<(bool, usize, bool)> {
+        let (pattern, pattern_port) = split_port(&self.host);
+        if pattern_port.is_some() && pattern_port != port {
+            return None;
+        }
+        let exact = match pattern.strip_prefix("*.") {
+            Some(suffix) => {
+                let matches = host.len() > suffix.len()
+                    && host.ends_with(suffix)
+                    && host[..host.len() - suffix.len()].ends_with('.');
+                if !matches {
+                    return None;
+                }
+                false
+            }
+            None if pattern.eq_ignore_ascii_case(host) => true,
+            None => return None,
+        };
+        Some((exact, pattern.len(), pattern_port.is_some()))
+    }
+
+    /// Headers as `Key: Value` lines
+    pub fn headers_text(&self) -> String {
+        self.headers
+            .iter()
+            .map(|(key, value)| format!("{key}: {value}\n"))
+            .collect()
+    }
+
+    /// Replace the headers with `Key: Value` lines, skipping lines without a key
+    pub fn set_headers_text(&mut self, text: &str) {
+        self.headers = text
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .filter(|(key, _)| !key.is_empty())
+            .collect();
+    }
+
+    /// Add the default headers the request doesn't set itself
+    pub fn apply_headers(&self, request: &mut Request) {
+        for (key, value) in &self.headers {
+            let overridden = request
+                .headers
+                .iter()
+                .any(|(existing, _)| existing.eq_ignore_ascii_case(key));
+            if !overridden {
+                request.add_header(key, value);
+            }
+        }
+    }
+}
+
+/// Per-domain defaults of a profile, saved as `domains.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DomainSettings {
+    domains: Vec<DomainDefaults>,
+}
+
+impl DomainSettings {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn domains(&self) -> &[DomainDefaults] {
+        &self.domains
+    }
+
+    pub fn get(&self, index: usize) -> Option<&DomainDefaults> {
+        self.domains.get(index)
+    }
+
+    /// Add empty defaults for a host pattern, returning their index.
+    ///
+    /// Pasted URLs are reduced to their host; patterns must be unique.
+    pub fn add(&mut self, host: &str) -> Result<usize, AppError> {
+        let host = authority(host).unwrap_or_default();
+        let name = host.strip_prefix("*.").unwrap_or(&host);
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Domain cannot be empty".to_string(),
+            ));
+        }
+        if name.contains('*') {
+            return Err(AppError::ValidationError(format!(
+                "Wildcards are only allowed as a leading \"*.\": {host}"
+            )));
+        }
+        if self.domains.iter().any(|domain| domain.host == host) {
+            return Err(AppError::ValidationError(format!(
+                "Defaults for {host} already exist"
+            )));
+        }
+        self.domains.push(DomainDefaults::new(host));
+        Ok(self.domains.len() - 1)
+    }
+
+    /// Replace the defaults at `index` (the host pattern is kept)
+    pub fn update(&mut self, index: usize, defaults: DomainDefaults) -> bool {
+        let Some(domain) = self.domains.get_mut(index) else {
+            return false;
+        };
+        *domain = DomainDefaults {
+            host: domain.host.clone(),
+            ..defaults
+        };
+        true
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<DomainDefaults> {
+        (index < self.domains.len()).then(|| self.domains.remove(index))
+    }
+
+    /// The most specific defaults for the host `url` targets
+    pub fn for_url(&self, url: &str) -> Option<&DomainDefaults> {
+        let authority = authority(url)?;
+        let (host, port) = split_port(&authority);
+        self.domains
+            .iter()
+            .filter_map(|domain| Some((domain.specificity(host, port)?, domain)))
+            .max_by_key(|(specificity, _)| *specificity)
+            .map(|(_, domain)| domain)
+    }
+}
+
+/// Lowercase `host[:port]` of a URL, without credentials
+fn authority(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = rest.split(['/', '?', '#']).next()?;
+    let authority = authority.rsplit('@').next()?;
+    (!authority.is_empty()).then(|| authority.to_ascii_lowercase())
+}
+
+/// Split `host:port`, leaving IPv6 literals such as `[::1]` intact
+fn split_port(authority: &str) -> (&str, Option<&str>) {
+    match authority.rsplit_once(':') {
+        Some((host, port)) if !port.is_empty() && port.chars().all(|c| c.is_ascii_digit()) => {
+            (host, Some(port))
+        }
+        _ => (authority, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn settings(hosts: &[&str]) -> DomainSettings {
+        let mut settings = DomainSettings::new();
+        for host in hosts {
+            settings.add(host).unwrap();
+        }
+        settings
+    }
+
+    fn matched(settings: &DomainSettings, url: &str) -> Option<String> {
+        settings.for_url(url).map(|domain| domain.host.clone())
+    }
+
+    #[test]
+    fn test_add_normalizes_and_validates() {
+        let mut settings = DomainSettings::new();
+        assert_eq!(settings.add("https://API.example.com/v1?x=1").unwrap(), 0);
+        assert_eq!(settings.domains()[0].host, "api.example.com");
+        assert!(settings.add("api.example.com").is_err());
+        assert!(settings.add("  ").is_err());
+        assert!(settings.add("api.*.com").is_err());
+        assert!(settings.add("*.").is_err());
+        assert!(settings.add("*.example.com").is_ok());
+    }
+
+    #[test]
+    fn test_most_specific_domain_wins() {
+        let settings = settings(&["*.example.com", "api.example.com", "localhost:8080"]);
+        assert_eq!(
+            matched(&settings, "https://api.example.com/users").as_deref(),
+            Some("api.example.com")
+        );
+        assert_eq!(
+            matched(&settings, "https://user:pw@eu.api.example.com:8443/").as_deref(),
+            Some("*.example.com")
+        );
+        assert_eq!(matched(&settings, "https://example.com/"), None);
+        assert_eq!(matched(&settings, "https://notexample.com/"), None);
+        assert_eq!(
+            matched(&settings, "http://localhost:8080/health").as_deref(),
+            Some("localhost:8080")
+        );
+        assert_eq!(matched(&settings, "http://localhost:3000/"), None);
+        assert_eq!(matched(&settings, ""), None);
+    }
+
+    #[test]
+    fn test_apply_headers_keeps_request_headers() {
+        let mut settings = settings(&["api.example.com"]);
+        let mut defaults = DomainDefaults::new("ignored");
+        defaults.set_headers_text("Authorization: Bearer default\n\nX-Tenant : acme\n: orphan");
+        assert_eq!(
+            defaults.headers_text(),
+            "Authorization: Bearer default\nX-Tenant: acme\n"
+        );
+        assert!(settings.update(0, defaults));
+        assert_eq!(settings.domains()[0].host, "api.example.com");
+
+        let mut request = Request::new("GET", "https://api.example.com/me");
+        request.add_header("authorization", "Bearer mine");
+        settings
+            .for_url(&request.url.clone())
+            .unwrap()
+            .apply_headers(&mut request);
+        assert_eq!(
+            request.headers,
+            vec![
+                ("authorization".to_string(), "Bearer mine".to_string()),
+                ("X-Tenant".to_string(), "acme".to_string()),
+            ]
+        );
+
+        assert!(settings.remove(0).is_some());
+        assert!(settings.remove(0).is_none());
+    }
+}
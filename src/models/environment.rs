@@ -0,0 +1,190 @@
+use crate::errors::AppError;
+use crate::utils::hooks::is_variable_name;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Named variables (e.g. `base_url`, `token` for staging) substituted into
+/// requests as `{{name}}` while the environment is active
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Environment {
+    pub name: String,
+    #[serde(default)]
+    pub variables: Vec<(String, String)>,
+}
+
+impl Environment {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            variables: Vec::new(),
+        }
+    }
+
+    /// Replace the variables with `name=value` lines; blank lines and lines
+    /// starting with `#` are skipped
+    pub fn set_variables_from_text(&mut self, text: &str) -> Result<(), AppError> {
+        let mut variables = Vec::new();
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (name, value) = line.split_once('=').ok_or_else(|| {
+                AppError::ValidationError(format!("Expected name=value, got \"{line}\""))
+            })?;
+            let name = name.trim();
+            if !is_variable_name(name) {
+                return Err(AppError::ValidationError(format!(
+                    "\"{name}\" is not a variable name (letters, digits, _ and -)"
+                )));
+            }
+            variables.retain(|(existing, _): &(String, String)| existing != name);
+            variables.push((name.to_string(), value.trim().to_string()));
+        }
+        self.variables = variables;
+        Ok(())
+    }
+
+    /// The variables as `name=value` lines, as they are edited
+    pub fn variables_text(&self) -> String {
+        self.variables
+            .iter()
+            .map(|(name, value)| format!("{name}={value}\n"))
+            .collect()
+    }
+}
+
+/// Environments of a profile and which one is active, saved as
+/// `environments.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct EnvironmentLibrary {
+    environments: Vec<Environment>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    active: Option<usize>,
+}
+
+impl EnvironmentLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn environments(&self) -> &[Environment] {
+        &self.environments
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Environment> {
+        self.environments.get_mut(index)
+    }
+
+    /// Add an empty environment, returning its index; names must be unique
+    /// (ignoring case)
+    pub fn add(&mut self, name: &str) -> Result<usize, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Environment name cannot be empty".to_string(),
+            ));
+        }
+        if self
+            .environments
+            .iter()
+            .any(|environment| environment.name.eq_ignore_ascii_case(name))
+        {
+            return Err(AppError::ValidationError(format!(
+                "An environment named \"{name}\" already exists"
+            )));
+        }
+        self.environments.push(Environment::new(name));
+        Ok(self.environments.len() - 1)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Environment> {
+        if index >= self.environments.len() {
+            return None;
+        }
+        self.active = match self.active {
+            Some(active) if active == index => None,
+            Some(active) if active > index => Some(active - 1),
+            active => active,
+        };
+        Some(self.environments.remove(index))
+    }
+
+    pub fn active_index(&self) -> Option<usize> {
+        self.active
+    }
+
+    pub fn active(&self) -> Option<&Environment> {
+        self.environments.get(self.active?)
+    }
+
+    /// Make the environment at `index` active, or none with `None`
+    pub fn set_active(&mut self, index: Option<usize>) {
+        self.active = index.filter(|index| *index < self.environments.len());
+    }
+
+    /// Variables of the active environment, none when no environment is
+    /// active
+    pub fn variables(&self) -> HashMap<String, String> {
+        self.active()
+            .map(|environment| environment.variables.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_variables_from_text() {
+        let mut environment = Environment::new("Staging");
+        environment
+            .set_variables_from_text(
+                "# staging\nbase_url = https://staging.example.com\n\ntoken=abc=\ntoken=def\n",
+            )
+            .unwrap();
+        assert_eq!(
+            environment.variables,
+            [
+                (
+                    "base_url".to_string(),
+                    "https://staging.example.com".to_string()
+                ),
+                ("token".to_string(), "def".to_string()),
+            ]
+        );
+        assert_eq!(
+            environment.variables_text(),
+            "base_url=https://staging.example.com\ntoken=def\n"
+        );
+
+        assert!(environment.set_variables_from_text("no value").is_err());
+        assert!(environment.set_variables_from_text("two words=1").is_err());
+        assert_eq!(environment.variables.len(), 2);
+    }
+
+    #[test]
+    fn test_active_environment() {
+        let mut library = EnvironmentLibrary::new();
+        let staging = library.add("Staging").unwrap();
+        let production = library.add("Production").unwrap();
+        assert!(library.add("staging").is_err());
+        assert!(library.variables().is_empty());
+
+        library
+            .get_mut(production)
+            .unwrap()
+            .set_variables_from_text("host=api.example.com")
+            .unwrap();
+        library.set_active(Some(production));
+        assert_eq!(library.variables()["host"], "api.example.com");
+
+        // Removing an earlier environment keeps the same one active
+        library.remove(staging);
+        assert_eq!(library.active().unwrap().name, "Production");
+        library.remove(0);
+        assert_eq!(library.active(), None);
+        library.set_active(Some(5));
+        assert_eq!(library.active_index(), None);
+    }
+}
@@ -0,0 +1,205 @@
+use super::annotation::SavedResponse;
+use super::request::{HttpMethod, Request};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// A named response kept with a request for reference, either saved from a
+/// real send or written by hand (like Postman's examples)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseExample {
+    pub name: String,
+    pub response: SavedResponse,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl ResponseExample {
+    pub fn new(name: impl Into<String>, response: SavedResponse) -> Self {
+        Self {
+            name: name.into(),
+            response,
+            saved_at: Utc::now(),
+        }
+    }
+}
+
+/// The examples of one request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct RequestExamples {
+    method: HttpMethod,
+    url: String,
+    examples: Vec<ResponseExample>,
+}
+
+/// Response examples of a profile, saved as `examples.json`. A request is
+/// matched to its examples by method and URL; they travel with the request
+/// when it is shared or saved in a collection
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExampleLibrary {
+    requests: Vec<RequestExamples>,
+}
+
+impl ExampleLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Examples of the request, in the order they were saved
+    pub fn for_request(&self, method: HttpMethod, url: &str) -> &[ResponseExample] {
+        self.requests
+            .iter()
+            .find(|entry| entry.method == method && entry.url == url)
+            .map(|entry| entry.examples.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// Every request with examples, as method, URL and examples
+    pub fn requests(&self) -> impl Iterator<Item = (HttpMethod, &str, &[ResponseExample])> {
+        self.requests
+            .iter()
+            .map(|entry| (entry.method, entry.url.as_str(), entry.examples.as_slice()))
+    }
+
+    /// Add an example to the request; a name already taken gets a number
+    /// (`Not found 2`). Returns the name used
+    pub fn add(&mut self, method: HttpMethod, url: &str, mut example: ResponseExample) -> String {
+        let index = match self
+            .requests
+            .iter()
+            .position(|entry| entry.method == method && entry.url == url)
+        {
+            Some(index) => index,
+            None => {
+                self.requests.push(RequestExamples {
+                    method,
+                    url: url.to_string(),
+                    examples: Vec::new(),
+                });
+                self.requests.len() - 1
+            }
+        };
+        let examples = &mut self.requests[index].examples;
+        let base = match example.name.trim() {
+            "" => "Example".to_string(),
+            name => name.to_string(),
+        };
+        let mut name = base.clone();
+        let mut number = 2;
+        while examples.iter().any(|existing| existing.name == name) {
+            name = format!("{base} {number}");
+            number += 1;
+        }
+        example.name = name.clone();
+        examples.push(example);
+        name
+    }
+
+    /// Remove the request's example at `index`, returning it
+    pub fn remove(
+        &mut self,
+        method: HttpMethod,
+        url: &str,
+        index: usize,
+    ) -> Option<ResponseExample> {
+        let position = self
+            .requests
+            .iter()
+            .position(|entry| entry.method == method && entry.url == url)?;
+        let examples = &mut self.requests[position].examples;
+        if index >= examples.len() {
+            return None;
+        }
+        let example = examples.remove(index);
+        if examples.is_empty() {
+            self.requests.remove(position);
+        }
+        Some(example)
+    }
+
+    /// Copy the request's examples into it, e.g. before it is exported
+    pub fn attach(&self, request: &mut Request) {
+        request.examples = self.for_request(request.method, &request.url).to_vec();
+    }
+
+    /// Keep the examples that came with an opened request, skipping those
+    /// already saved under the same name. Returns how many were added
+    pub fn import(&mut self, request: &Request) -> usize {
+        let mut added = 0;
+        for example in &request.examples {
+            let known = self
+                .for_request(request.method, &request.url)
+                .iter()
+                .any(|existing| existing.name == example.name);
+            if !known {
+                self.add(request.method, &request.url, example.clone());
+                added += 1;
+            }
+        }
+        added
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_remove_and_export_examples() {
+        let url = "https://api.example.com/users/1";
+        let mut library = ExampleLibrary::new();
+        let not_found = SavedResponse::new(404, r#"{"error": "not found"}"#);
+        assert_eq!(
+            library.add(
+                HttpMethod::GET,
+                url,
+                ResponseExample::new("Not found", not_found.clone())
+            ),
+            "Not found"
+        );
+        assert_eq!(
+            library.add(
+                HttpMethod::GET,
+                url,
+                ResponseExample::new("Not found", not_found)
+            ),
+            "Not found 2"
+        );
+        assert_eq!(
+            library.add(
+                HttpMethod::GET,
+                url,
+                ResponseExample::new(" ", SavedResponse::new(200, "{}"))
+            ),
+            "Example"
+        );
+        assert!(library.for_request(HttpMethod::DELETE, url).is_empty());
+
+        let mut request = Request::new("GET", url);
+        library.attach(&mut request);
+        assert_eq!(request.examples.len(), 3);
+        let json = serde_json::to_string(&request).unwrap();
+        let restored: Request = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.examples, request.examples);
+
+        // Opening a shared request keeps only the examples not saved yet
+        let mut other = ExampleLibrary::new();
+        other.add(
+            HttpMethod::GET,
+            url,
+            ResponseExample::new("Example", SavedResponse::new(500, "")),
+        );
+        assert_eq!(other.import(&restored), 2);
+        assert_eq!(
+            other.for_request(HttpMethod::GET, url)[0].response.status,
+            500
+        );
+
+        assert_eq!(
+            library.remove(HttpMethod::GET, url, 0).unwrap().name,
+            "Not found"
+        );
+        assert!(library.remove(HttpMethod::GET, url, 5).is_none());
+        library.remove(HttpMethod::GET, url, 0);
+        library.remove(HttpMethod::GET, url, 0);
+        assert_eq!(library, ExampleLibrary::new());
+    }
+}
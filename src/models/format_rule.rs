@@ -0,0 +1,36 @@
+use crate::errors::AppError;
+use crate::utils::format_rules::{parse_rules, FormatRule};
+use serde::{Deserialize, Serialize};
+
+/// Conditional formatting rules of a profile, saved as `format_rules.json`.
+/// The rules are kept as written, one per line
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FormatRules {
+    pub script: String,
+}
+
+impl FormatRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rules(&self) -> Result<Vec<FormatRule>, AppError> {
+        parse_rules(&self.script)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_rules_round_trip() {
+        let rules = FormatRules {
+            script: "# Slow responses\nlatency > 1s => badge Slow\n".to_string(),
+        };
+        assert_eq!(rules.rules().unwrap().len(), 1);
+        let json = serde_json::to_string(&rules).unwrap();
+        assert_eq!(serde_json::from_str::<FormatRules>(&json).unwrap(), rules);
+        assert!(FormatRules::new().rules().unwrap().is_empty());
+    }
+}
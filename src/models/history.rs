@@ -1,5 +1,7 @@
+use super::annotation::SavedResponse;
 use super::request::Request;
 use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 #[cfg(test)]
 use super::request::HttpMethod;
@@ -8,19 +10,26 @@ use super::request::HttpMethod;
 const DEFAULT_MAX_HISTORY_ENTRIES: usize = 50;
 
 /// Request history entry
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HistoryEntry {
+    /// Unique within the history, stays the same while other entries are added
+    pub id: u64,
     pub request: Request,
     pub timestamp: DateTime<Utc>,
     pub name: String,
+    /// Response received for the request, with any annotations
+    #[serde(default)]
+    pub response: Option<SavedResponse>,
 }
 
 impl HistoryEntry {
     pub fn new(request: Request, name: String) -> Self {
         Self {
+            id: 0,
             request,
             timestamp: Utc::now(),
             name,
+            response: None,
         }
     }
 
@@ -36,10 +45,11 @@ impl HistoryEntry {
 }
 
 /// Request history manager
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestHistory {
     entries: Vec<HistoryEntry>,
     max_entries: usize,
+    next_id: u64,
 }
 
 impl RequestHistory {
@@ -47,18 +57,56 @@ impl RequestHistory {
         Self {
             entries: Vec::new(),
             max_entries: DEFAULT_MAX_HISTORY_ENTRIES,
+            next_id: 1,
         }
     }
 
-    /// Add a request to history
-    pub fn add(&mut self, request: Request, name: String) {
-        let entry = HistoryEntry::new(request, name);
+    /// Add a request to history, returning the new entry's id
+    pub fn add(&mut self, request: Request, name: String) -> u64 {
+        let mut entry = HistoryEntry::new(request, name);
+        entry.id = self.next_id;
+        self.next_id += 1;
+        let id = entry.id;
         self.entries.insert(0, entry); // Add to front (newest first)
 
         // Trim to max entries
         if self.entries.len() > self.max_entries {
             self.entries.truncate(self.max_entries);
         }
+        id
+    }
+
+    /// Find an entry by id
+    pub fn find(&self, id: u64) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| entry.id == id)
+    }
+
+    /// Find an entry by id, to change it
+    pub fn find_mut(&mut self, id: u64) -> Option<&mut HistoryEntry> {
+        self.entries.iter_mut().find(|entry| entry.id == id)
+    }
+
+    /// Most recent response received for `url`
+    pub fn last_response_for(&self, url: &str) -> Option<&SavedResponse> {
+        self.entries
+            .iter()
+            .filter(|entry| entry.request.url == url)
+            .find_map(|entry| entry.response.as_ref())
+    }
+
+    /// Entries sent between `from` and `to` (both included, `None` for no
+    /// bound), oldest first as they were sent
+    pub fn sent_between(
+        &self,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> Vec<&HistoryEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| from.is_none_or(|from| entry.timestamp >= from))
+            .filter(|entry| to.is_none_or(|to| entry.timestamp <= to))
+            .collect()
     }
 
     /// Get all history entries
@@ -190,6 +238,50 @@ mod tests {
         assert!(history.is_empty());
     }
 
+    #[test]
+    fn test_history_ids_are_stable() {
+        let mut history = RequestHistory::new();
+        let first = history.add(
+            Request::new("GET", "https://a.example.com"),
+            "A".to_string(),
+        );
+        let second = history.add(
+            Request::new("GET", "https://b.example.com"),
+            "B".to_string(),
+        );
+        assert_ne!(first, second);
+
+        let entry = history.find_mut(first).unwrap();
+        assert_eq!(entry.name, "A");
+        entry.response = Some(SavedResponse::new(200, "ok"));
+
+        assert!(history.get(1).unwrap().response.is_some());
+        assert_eq!(history.find(second).unwrap().name, "B");
+        assert!(history.find(999).is_none());
+        assert!(history.find_mut(999).is_none());
+    }
+
+    #[test]
+    fn test_last_response_for_url() {
+        let mut history = RequestHistory::new();
+        let url = "https://api.example.com/users/1";
+        let older = history.add(Request::new("GET", url), "old".to_string());
+        history.find_mut(older).unwrap().response = Some(SavedResponse::new(200, "old"));
+        let newer = history.add(Request::new("PUT", url), "new".to_string());
+        history.find_mut(newer).unwrap().response = Some(SavedResponse::new(200, "new"));
+        // Failed requests have no response and are skipped
+        history.add(Request::new("GET", url), "failed".to_string());
+        history.add(
+            Request::new("GET", "https://api.example.com/other"),
+            "other".to_string(),
+        );
+
+        assert_eq!(history.last_response_for(url).unwrap().body, "new");
+        assert!(history
+            .last_response_for("https://api.example.com/none")
+            .is_none());
+    }
+
     #[test]
     fn test_history_entry_display_name() {
         let request = Request::new("GET", "https://api.example.com/users");
@@ -197,4 +289,30 @@ mod tests {
 
         assert_eq!(entry.display_name(), "GET Users API");
     }
+
+    #[test]
+    fn test_sent_between_is_oldest_first() {
+        let mut history = RequestHistory::new();
+        for (name, minute) in [("first", 0), ("second", 10), ("third", 20)] {
+            let id = history.add(Request::new("GET", "/"), name.to_string());
+            history.find_mut(id).unwrap().timestamp =
+                DateTime::from_timestamp(minute * 60, 0).unwrap();
+        }
+        let names = |entries: Vec<&HistoryEntry>| -> Vec<String> {
+            entries.iter().map(|entry| entry.name.clone()).collect()
+        };
+
+        assert_eq!(
+            names(history.sent_between(None, None)),
+            ["first", "second", "third"]
+        );
+        assert_eq!(
+            names(history.sent_between(DateTime::from_timestamp(600, 0), None)),
+            ["second", "third"]
+        );
+        assert_eq!(
+            names(history.sent_between(None, DateTime::from_timestamp(599, 0))),
+            ["first"]
+        );
+    }
 }
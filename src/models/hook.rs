@@ -0,0 +1,144 @@
+use crate::errors::AppError;
+use crate::utils::hooks::is_variable_name;
+use serde::{Deserialize, Serialize};
+
+/// A local command run around a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hook {
+    pub command: String,
+    /// Variable the command's output is stored in, used as `{{name}}`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variable: Option<String>,
+}
+
+impl Hook {
+    /// Hook for a command and variable as typed, `None` if the command is
+    /// empty
+    pub fn parse(command: &str, variable: &str) -> Result<Option<Self>, AppError> {
+        let command = command.trim();
+        let variable = variable
+            .trim()
+            .trim_start_matches("{{")
+            .trim_end_matches("}}");
+        if command.is_empty() {
+            return Ok(None);
+        }
+        if !variable.is_empty() && !is_variable_name(variable) {
+            return Err(AppError::ValidationError(format!(
+                "\"{variable}\" is not a variable name (letters, digits, _ and -)"
+            )));
+        }
+        Ok(Some(Self {
+            command: command.to_string(),
+            variable: (!variable.is_empty()).then(|| variable.to_string()),
+        }))
+    }
+}
+
+/// Commands run before the request is sent and after its response arrives
+/// (the post hook reads the response body on stdin)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct RequestHooks {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pre: Option<Hook>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub post: Option<Hook>,
+}
+
+impl RequestHooks {
+    pub fn is_empty(&self) -> bool {
+        self.pre.is_none() && self.post.is_none()
+    }
+
+    pub fn commands(&self) -> impl Iterator<Item = &str> {
+        self.pre
+            .iter()
+            .chain(self.post.iter())
+            .map(|hook| hook.command.as_str())
+    }
+}
+
+/// Hook commands the user allowed to run without asking again, saved per
+/// profile as `trusted_hooks.json`. Commands are matched exactly, so a
+/// request that arrives with a changed command asks again
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TrustedHooks {
+    commands: Vec<String>,
+}
+
+impl TrustedHooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn commands(&self) -> &[String] {
+        &self.commands
+    }
+
+    pub fn is_trusted(&self, command: &str) -> bool {
+        self.commands.iter().any(|trusted| trusted == command)
+    }
+
+    /// Commands of `hooks` that still need confirmation
+    pub fn untrusted(&self, hooks: &RequestHooks) -> Vec<String> {
+        let mut untrusted: Vec<String> = Vec::new();
+        for command in hooks.commands() {
+            if !self.is_trusted(command) && !untrusted.iter().any(|seen| seen == command) {
+                untrusted.push(command.to_string());
+            }
+        }
+        untrusted
+    }
+
+    pub fn trust(&mut self, command: &str) {
+        if !self.is_trusted(command) {
+            self.commands.push(command.to_string());
+        }
+    }
+
+    pub fn revoke(&mut self, index: usize) -> Option<String> {
+        (index < self.commands.len()).then(|| self.commands.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hook() {
+        assert_eq!(Hook::parse("  ", "token").unwrap(), None);
+        let hook = Hook::parse(" gcloud auth print-access-token ", "{{token}}")
+            .unwrap()
+            .unwrap();
+        assert_eq!(hook.command, "gcloud auth print-access-token");
+        assert_eq!(hook.variable.as_deref(), Some("token"));
+        assert_eq!(Hook::parse("date", "").unwrap().unwrap().variable, None);
+        assert!(Hook::parse("date", "not a name").is_err());
+    }
+
+    #[test]
+    fn test_untrusted_commands_need_confirmation() {
+        let hooks = RequestHooks {
+            pre: Hook::parse("./token.sh", "token").unwrap(),
+            post: Hook::parse("./token.sh", "").unwrap(),
+        };
+        let mut trusted = TrustedHooks::new();
+        assert_eq!(trusted.untrusted(&hooks), ["./token.sh"]);
+
+        trusted.trust("./token.sh");
+        trusted.trust("./token.sh");
+        assert_eq!(trusted.commands().len(), 1);
+        assert!(trusted.untrusted(&hooks).is_empty());
+
+        // A changed command is not covered by the earlier approval
+        let changed = RequestHooks {
+            pre: Hook::parse("./token.sh; curl evil.example", "token").unwrap(),
+            post: None,
+        };
+        assert_eq!(trusted.untrusted(&changed).len(), 1);
+
+        assert_eq!(trusted.revoke(0).as_deref(), Some("./token.sh"));
+        assert_eq!(trusted.revoke(0), None);
+    }
+}
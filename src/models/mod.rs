@@ -1,11 +1,43 @@
 // This file serves as a module for data models used in the application.
 
+pub mod activity;
+pub mod annotation;
+pub mod baseline;
+pub mod certificate;
 pub mod collection;
+pub mod cookie;
+pub mod domain;
+pub mod environment;
+pub mod example;
+pub mod format_rule;
 pub mod history;
+pub mod hook;
+pub mod profile;
 pub mod request;
+pub mod request_file;
+pub mod scenario;
+pub mod snippet;
+pub mod usage;
+pub mod vault;
 pub mod workspace;
 
 // Re-export commonly used types
-pub use collection::Collection;
+pub use activity::{ActivityEntry, ActivityKind, ActivityLog};
+pub use annotation::{Annotation, HighlightColor, SavedResponse};
+pub use baseline::{Baseline, BaselineStore};
+pub use certificate::{CertificateData, CertificateStore, ClientCertificate};
+pub use collection::{Collection, CollectionLibrary};
+pub use cookie::{Cookie, CookieJar};
+pub use domain::{DomainAuth, DomainDefaults, DomainSettings, TransportOptions};
+pub use environment::{Environment, EnvironmentLibrary};
+pub use example::{ExampleLibrary, ResponseExample};
+pub use format_rule::FormatRules;
 pub use history::{HistoryEntry, RequestHistory};
+pub use hook::{Hook, RequestHooks, TrustedHooks};
+pub use profile::{Profile, ProfileRegistry, ProfileStorage};
 pub use request::{HttpMethod, Request};
+pub use request_file::RequestFile;
+pub use scenario::{Scenario, ScenarioLibrary, Stage};
+pub use snippet::{AssertionSnippet, SnippetLibrary};
+pub use usage::{Feature, UsageStats};
+pub use vault::{Vault, VaultHeader};
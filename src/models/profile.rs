@@ -0,0 +1,693 @@
+use super::activity::ActivityLog;
+use super::baseline::BaselineStore;
+use super::certificate::CertificateStore;
+use super::collection::CollectionLibrary;
+use super::cookie::CookieJar;
+use super::domain::DomainSettings;
+use super::environment::EnvironmentLibrary;
+use super::example::ExampleLibrary;
+use super::format_rule::FormatRules;
+use super::history::RequestHistory;
+use super::hook::TrustedHooks;
+use super::scenario::ScenarioLibrary;
+use super::snippet::SnippetLibrary;
+use super::usage::UsageStats;
+use super::vault::Vault;
+use crate::errors::AppError;
+use crate::storage::{read_json, write_json};
+use crate::utils::name_template::{self, DEFAULT_HISTORY_NAME_TEMPLATE};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// File in the data directory that lists the profiles
+const PROFILES_FILE: &str = "profiles.json";
+
+/// Directory (under the data directory) holding one directory per profile
+const PROFILES_DIR: &str = "profiles";
+
+/// Name of the profile created on first start
+pub const DEFAULT_PROFILE_NAME: &str = "Personal";
+
+/// A named profile (e.g. work / personal) with its own storage directory
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Profile {
+    pub name: String,
+    /// Directory name under `profiles/`, fixed when the profile is created
+    pub dir_name: String,
+    /// URL the health checker pings to show whether the profile's target is up
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    /// Template new history entries are named with, e.g.
+    /// `{method} {path} [{status}]`; the URL cut to 40 characters if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub history_name_template: Option<String>,
+}
+
+impl Profile {
+    /// Storage for this profile under the data directory `root`
+    pub fn storage(&self, root: &Path) -> ProfileStorage {
+        ProfileStorage::new(root.join(PROFILES_DIR).join(&self.dir_name))
+    }
+}
+
+/// The list of profiles and which one is active, saved as `profiles.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProfileRegistry {
+    profiles: Vec<Profile>,
+    active: usize,
+}
+
+impl ProfileRegistry {
+    pub fn new() -> Self {
+        Self {
+            profiles: vec![Profile {
+                name: DEFAULT_PROFILE_NAME.to_string(),
+                dir_name: slugify(DEFAULT_PROFILE_NAME),
+                base_url: None,
+                history_name_template: None,
+            }],
+            active: 0,
+        }
+    }
+
+    /// Default data directory, e.g. `~/.local/share/postman-gpui`
+    pub fn default_root() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("postman-gpui"))
+    }
+
+    /// Load the registry from `root`, starting with the default profile if
+    /// nothing has been saved yet
+    pub fn load(root: &Path) -> Result<Self, AppError> {
+        let registry: Self =
+            read_json(&root.join(PROFILES_FILE), None)?.unwrap_or_else(ProfileRegistry::new);
+        if registry.profiles.is_empty() {
+            return Ok(ProfileRegistry::new());
+        }
+        let active = registry.active.min(registry.profiles.len() - 1);
+        Ok(Self { active, ..registry })
+    }
+
+    pub fn save(&self, root: &Path) -> Result<(), AppError> {
+        write_json(&root.join(PROFILES_FILE), self, None)
+    }
+
+    pub fn profiles(&self) -> &[Profile] {
+        &self.profiles
+    }
+
+    pub fn active(&self) -> &Profile {
+        &self.profiles[self.active]
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active
+    }
+
+    /// Add a profile, returning its index.
+    ///
+    /// Names must be unique (ignoring case). The storage directory name is
+    /// derived from the name and never reused by another profile.
+    pub fn add(&mut self, name: &str) -> Result<usize, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Profile name cannot be empty".to_string(),
+            ));
+        }
+        if self
+            .profiles
+            .iter()
+            .any(|profile| profile.name.eq_ignore_ascii_case(name))
+        {
+            return Err(AppError::ValidationError(format!(
+                "A profile named \"{name}\" already exists"
+            )));
+        }
+
+        let base = slugify(name);
+        let mut dir_name = base.clone();
+        let mut suffix = 2;
+        while self
+            .profiles
+            .iter()
+            .any(|profile| profile.dir_name == dir_name)
+        {
+            dir_name = format!("{base}-{suffix}");
+            suffix += 1;
+        }
+
+        self.profiles.push(Profile {
+            name: name.to_string(),
+            dir_name,
+            base_url: None,
+            history_name_template: None,
+        });
+        Ok(self.profiles.len() - 1)
+    }
+
+    /// Set the base URL of the profile at `index`; an empty URL removes it
+    pub fn set_base_url(&mut self, index: usize, url: &str) -> Result<(), AppError> {
+        let url = url.trim().trim_end_matches('/');
+        if !url.is_empty() && !url.starts_with("http://") && !url.starts_with("https://") {
+            return Err(AppError::ValidationError(format!(
+                "Base URL \"{url}\" should start with http:// or https://"
+            )));
+        }
+        let profile = self
+            .profiles
+            .get_mut(index)
+            .ok_or_else(|| AppError::ValidationError("The profile no longer exists".to_string()))?;
+        profile.base_url = (!url.is_empty()).then(|| url.to_string());
+        Ok(())
+    }
+
+    /// Set the history naming template of the profile at `index`; an empty
+    /// template goes back to the default
+    pub fn set_history_name_template(
+        &mut self,
+        index: usize,
+        template: &str,
+    ) -> Result<(), AppError> {
+        let template = template.trim();
+        name_template::validate(template)?;
+        let profile = self
+            .profiles
+            .get_mut(index)
+            .ok_or_else(|| AppError::ValidationError("The profile no longer exists".to_string()))?;
+        profile.history_name_template = (!template.is_empty()
+            && template != DEFAULT_HISTORY_NAME_TEMPLATE)
+            .then(|| template.to_string());
+        Ok(())
+    }
+
+    /// Make the profile at `index` active; returns false if it doesn't exist
+    /// or is already active
+    pub fn switch_to(&mut self, index: usize) -> bool {
+        if index >= self.profiles.len() || index == self.active {
+            return false;
+        }
+        self.active = index;
+        true
+    }
+
+    /// Index of the profile after the active one (wrapping around)
+    pub fn next_index(&self) -> usize {
+        (self.active + 1) % self.profiles.len()
+    }
+}
+
+impl Default for ProfileRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Files belonging to one profile.
+///
+/// Every profile keeps its collections, environments, cookies, history,
+/// per-domain defaults, client certificates, assertion snippets, golden
+/// baselines, response examples, formatting rules, scenarios, activity log
+/// and usage statistics in its own directory so switching profiles never
+/// mixes their data. With a vault the files are encrypted; plaintext files
+/// are still read so data saved before encryption was turned on stays
+/// available. A read-only storage (viewer mode) reads the files but never
+/// writes them.
+#[derive(Debug, Clone)]
+pub struct ProfileStorage {
+    dir: PathBuf,
+    vault: Option<Vault>,
+    read_only: bool,
+}
+
+impl ProfileStorage {
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            vault: None,
+            read_only: false,
+        }
+    }
+
+    /// Encrypt files written through this storage with `vault`
+    pub fn with_vault(mut self, vault: Option<Vault>) -> Self {
+        self.vault = vault;
+        self
+    }
+
+    /// Skip every write when `read_only` is set: saving succeeds but the
+    /// files stay as they were
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    fn write<T: Serialize + ?Sized>(&self, path: &Path, value: &T) -> Result<(), AppError> {
+        if self.read_only {
+            return Ok(());
+        }
+        write_json(path, value, self.vault.as_ref())
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    pub fn collections_path(&self) -> PathBuf {
+        self.dir.join("collections.json")
+    }
+
+    pub fn environments_path(&self) -> PathBuf {
+        self.dir.join("environments.json")
+    }
+
+    pub fn cookies_path(&self) -> PathBuf {
+        self.dir.join("cookies.json")
+    }
+
+    pub fn history_path(&self) -> PathBuf {
+        self.dir.join("history.json")
+    }
+
+    pub fn domains_path(&self) -> PathBuf {
+        self.dir.join("domains.json")
+    }
+
+    pub fn certificates_path(&self) -> PathBuf {
+        self.dir.join("certificates.json")
+    }
+
+    pub fn activity_path(&self) -> PathBuf {
+        self.dir.join("activity.json")
+    }
+
+    pub fn snippets_path(&self) -> PathBuf {
+        self.dir.join("snippets.json")
+    }
+
+    pub fn trusted_hooks_path(&self) -> PathBuf {
+        self.dir.join("trusted_hooks.json")
+    }
+
+    pub fn baselines_path(&self) -> PathBuf {
+        self.dir.join("baselines.json")
+    }
+
+    pub fn examples_path(&self) -> PathBuf {
+        self.dir.join("examples.json")
+    }
+
+    pub fn format_rules_path(&self) -> PathBuf {
+        self.dir.join("format_rules.json")
+    }
+
+    pub fn usage_path(&self) -> PathBuf {
+        self.dir.join("usage.json")
+    }
+
+    pub fn scenarios_path(&self) -> PathBuf {
+        self.dir.join("scenarios.json")
+    }
+
+    /// Load the request history, empty if none has been saved yet
+    pub fn load_history(&self) -> Result<RequestHistory, AppError> {
+        Ok(read_json(&self.history_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_history(&self, history: &RequestHistory) -> Result<(), AppError> {
+        self.write(&self.history_path(), history)
+    }
+
+    /// Load the collections, none until one is created
+    pub fn load_collections(&self) -> Result<CollectionLibrary, AppError> {
+        Ok(read_json(&self.collections_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_collections(&self, collections: &CollectionLibrary) -> Result<(), AppError> {
+        self.write(&self.collections_path(), collections)
+    }
+
+    /// Load the environments, none until one is created
+    pub fn load_environments(&self) -> Result<EnvironmentLibrary, AppError> {
+        Ok(read_json(&self.environments_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_environments(&self, environments: &EnvironmentLibrary) -> Result<(), AppError> {
+        self.write(&self.environments_path(), environments)
+    }
+
+    /// Load the cookies, empty until a response sets one
+    pub fn load_cookies(&self) -> Result<CookieJar, AppError> {
+        Ok(read_json(&self.cookies_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_cookies(&self, cookies: &CookieJar) -> Result<(), AppError> {
+        self.write(&self.cookies_path(), cookies)
+    }
+
+    /// Load the per-domain defaults, empty if none have been saved yet
+    pub fn load_domains(&self) -> Result<DomainSettings, AppError> {
+        Ok(read_json(&self.domains_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_domains(&self, domains: &DomainSettings) -> Result<(), AppError> {
+        self.write(&self.domains_path(), domains)
+    }
+
+    /// Load the client certificates, empty if none have been imported yet
+    pub fn load_certificates(&self) -> Result<CertificateStore, AppError> {
+        Ok(read_json(&self.certificates_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_certificates(&self, certificates: &CertificateStore) -> Result<(), AppError> {
+        self.write(&self.certificates_path(), certificates)
+    }
+
+    /// Load the activity log, empty if nothing has been recorded yet
+    pub fn load_activity(&self) -> Result<ActivityLog, AppError> {
+        Ok(read_json(&self.activity_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_activity(&self, activity: &ActivityLog) -> Result<(), AppError> {
+        self.write(&self.activity_path(), activity)
+    }
+
+    /// Load the assertion snippets, empty if none have been saved yet
+    pub fn load_snippets(&self) -> Result<SnippetLibrary, AppError> {
+        Ok(read_json(&self.snippets_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_snippets(&self, snippets: &SnippetLibrary) -> Result<(), AppError> {
+        self.write(&self.snippets_path(), snippets)
+    }
+
+    /// Load the hook commands allowed to run, none until some are allowed
+    pub fn load_trusted_hooks(&self) -> Result<TrustedHooks, AppError> {
+        Ok(read_json(&self.trusted_hooks_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_trusted_hooks(&self, hooks: &TrustedHooks) -> Result<(), AppError> {
+        self.write(&self.trusted_hooks_path(), hooks)
+    }
+
+    /// Load the golden baselines, none until a response is pinned
+    pub fn load_baselines(&self) -> Result<BaselineStore, AppError> {
+        Ok(read_json(&self.baselines_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_baselines(&self, baselines: &BaselineStore) -> Result<(), AppError> {
+        self.write(&self.baselines_path(), baselines)
+    }
+
+    /// Load the response examples, none until one is saved
+    pub fn load_examples(&self) -> Result<ExampleLibrary, AppError> {
+        Ok(read_json(&self.examples_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_examples(&self, examples: &ExampleLibrary) -> Result<(), AppError> {
+        self.write(&self.examples_path(), examples)
+    }
+
+    /// Load the conditional formatting rules, none until some are saved
+    pub fn load_format_rules(&self) -> Result<FormatRules, AppError> {
+        Ok(read_json(&self.format_rules_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_format_rules(&self, rules: &FormatRules) -> Result<(), AppError> {
+        self.write(&self.format_rules_path(), rules)
+    }
+
+    /// Load the usage statistics, counting from now if nothing was counted yet
+    pub fn load_usage(&self) -> Result<UsageStats, AppError> {
+        Ok(read_json(&self.usage_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_usage(&self, usage: &UsageStats) -> Result<(), AppError> {
+        self.write(&self.usage_path(), usage)
+    }
+
+    /// Load the scenarios, none until one is created
+    pub fn load_scenarios(&self) -> Result<ScenarioLibrary, AppError> {
+        Ok(read_json(&self.scenarios_path(), self.vault.as_ref())?.unwrap_or_default())
+    }
+
+    pub fn save_scenarios(&self, scenarios: &ScenarioLibrary) -> Result<(), AppError> {
+        self.write(&self.scenarios_path(), scenarios)
+    }
+
+    /// Rewrite the saved files through `target`, e.g. to encrypt them after
+    /// a vault was created or to decrypt them when it is removed
+    pub fn migrate_to(&self, target: &ProfileStorage) -> Result<(), AppError> {
+        if self.history_path().exists() {
+            target.save_history(&self.load_history()?)?;
+        }
+        if self.collections_path().exists() {
+            target.save_collections(&self.load_collections()?)?;
+        }
+        if self.environments_path().exists() {
+            target.save_environments(&self.load_environments()?)?;
+        }
+        if self.cookies_path().exists() {
+            target.save_cookies(&self.load_cookies()?)?;
+        }
+        if self.domains_path().exists() {
+            target.save_domains(&self.load_domains()?)?;
+        }
+        if self.certificates_path().exists() {
+            target.save_certificates(&self.load_certificates()?)?;
+        }
+        if self.activity_path().exists() {
+            target.save_activity(&self.load_activity()?)?;
+        }
+        if self.snippets_path().exists() {
+            target.save_snippets(&self.load_snippets()?)?;
+        }
+        if self.trusted_hooks_path().exists() {
+            target.save_trusted_hooks(&self.load_trusted_hooks()?)?;
+        }
+        if self.baselines_path().exists() {
+            target.save_baselines(&self.load_baselines()?)?;
+        }
+        if self.examples_path().exists() {
+            target.save_examples(&self.load_examples()?)?;
+        }
+        if self.format_rules_path().exists() {
+            target.save_format_rules(&self.load_format_rules()?)?;
+        }
+        if self.usage_path().exists() {
+            target.save_usage(&self.load_usage()?)?;
+        }
+        if self.scenarios_path().exists() {
+            target.save_scenarios(&self.load_scenarios()?)?;
+        }
+        Ok(())
+    }
+}
+
+// Lowercase ASCII letters and digits joined by '-', e.g. "Work (ACME)" -> "work-acme"
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "profile".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{ActivityKind, Request};
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_add_and_switch_profiles() {
+        let mut registry = ProfileRegistry::new();
+        assert_eq!(registry.active().name, DEFAULT_PROFILE_NAME);
+
+        let work = registry.add("Work (ACME)").unwrap();
+        assert_eq!(registry.profiles()[work].dir_name, "work-acme");
+        assert!(registry.add("work (acme)").is_err());
+        assert!(registry.add("  ").is_err());
+
+        assert_eq!(registry.next_index(), work);
+        assert!(registry.switch_to(work));
+        assert!(!registry.switch_to(work));
+        assert!(!registry.switch_to(99));
+        assert_eq!(registry.active().name, "Work (ACME)");
+        assert_eq!(registry.next_index(), 0);
+
+        registry
+            .set_base_url(work, " https://api.acme.test/ ")
+            .unwrap();
+        assert_eq!(
+            registry.active().base_url.as_deref(),
+            Some("https://api.acme.test")
+        );
+        assert!(registry.set_base_url(work, "api.acme.test").is_err());
+        registry.set_base_url(work, "").unwrap();
+        assert_eq!(registry.active().base_url, None);
+
+        registry
+            .set_history_name_template(work, " {method} {path} [{status}] ")
+            .unwrap();
+        assert_eq!(
+            registry.active().history_name_template.as_deref(),
+            Some("{method} {path} [{status}]")
+        );
+        assert!(registry.set_history_name_template(work, "{verb}").is_err());
+        registry
+            .set_history_name_template(work, DEFAULT_HISTORY_NAME_TEMPLATE)
+            .unwrap();
+        assert_eq!(registry.active().history_name_template, None);
+    }
+
+    #[test]
+    fn test_profile_dir_names_are_unique() {
+        let mut registry = ProfileRegistry::new();
+        let first = registry.add("Work!").unwrap();
+        let second = registry.add("Work?").unwrap();
+        let third = registry.add("???").unwrap();
+        assert_eq!(registry.profiles()[first].dir_name, "work");
+        assert_eq!(registry.profiles()[second].dir_name, "work-2");
+        assert_eq!(registry.profiles()[third].dir_name, "profile");
+    }
+
+    #[test]
+    fn test_registry_round_trip() {
+        let root = TempDir::new("profile-registry");
+        assert_eq!(
+            ProfileRegistry::load(&root).unwrap(),
+            ProfileRegistry::new()
+        );
+
+        let mut registry = ProfileRegistry::new();
+        let work = registry.add("Work").unwrap();
+        registry.switch_to(work);
+        registry.save(&root).unwrap();
+
+        let loaded = ProfileRegistry::load(&root).unwrap();
+        assert_eq!(loaded, registry);
+        assert_eq!(loaded.active().name, "Work");
+    }
+
+    #[test]
+    fn test_profile_storage_is_isolated() {
+        let root = TempDir::new("profile-storage");
+        let mut registry = ProfileRegistry::new();
+        let work = registry.add("Work").unwrap();
+        let personal = registry.profiles()[0].storage(&root);
+        let work = registry.profiles()[work].storage(&root);
+        assert_ne!(personal.dir(), work.dir());
+
+        let mut history = RequestHistory::new();
+        history.add(
+            Request::new("GET", "https://work.example.com"),
+            "work".to_string(),
+        );
+        work.save_history(&history).unwrap();
+
+        let mut collections = CollectionLibrary::new();
+        collections.add("Work APIs").unwrap();
+        work.save_collections(&collections).unwrap();
+
+        let mut environments = EnvironmentLibrary::new();
+        let staging = environments.add("Staging").unwrap();
+        environments
+            .get_mut(staging)
+            .unwrap()
+            .set_variables_from_text("token=work")
+            .unwrap();
+        environments.set_active(Some(staging));
+        work.save_environments(&environments).unwrap();
+
+        let mut cookies = CookieJar::new();
+        cookies.store(
+            "https://work.example.com/",
+            &[("Set-Cookie".to_string(), "session=work".to_string())],
+            chrono::Utc::now(),
+        );
+        work.save_cookies(&cookies).unwrap();
+
+        let mut domains = DomainSettings::new();
+        domains.add("work.example.com").unwrap();
+        work.save_domains(&domains).unwrap();
+
+        let mut activity = ActivityLog::new();
+        activity.record(ActivityKind::DomainsChanged, "Added work.example.com");
+        work.save_activity(&activity).unwrap();
+
+        assert_eq!(work.load_history().unwrap().len(), 1);
+        assert!(personal.load_history().unwrap().is_empty());
+        assert_eq!(work.load_collections().unwrap(), collections);
+        assert!(personal
+            .load_collections()
+            .unwrap()
+            .collections()
+            .is_empty());
+        assert_eq!(
+            work.load_environments().unwrap().variables()["token"],
+            "work"
+        );
+        assert!(personal.load_environments().unwrap().variables().is_empty());
+        assert_eq!(work.load_cookies().unwrap(), cookies);
+        assert!(personal.load_cookies().unwrap().is_empty());
+        assert_eq!(work.load_domains().unwrap(), domains);
+        assert!(personal.load_domains().unwrap().domains().is_empty());
+        assert_eq!(work.load_activity().unwrap().entries(), activity.entries());
+        assert!(personal.load_activity().unwrap().entries().is_empty());
+    }
+
+    #[test]
+    fn test_migrate_to_encrypted_storage() {
+        let root = TempDir::new("profile-encrypted");
+        let plain = ProfileRegistry::new().active().storage(&root);
+        let mut history = RequestHistory::new();
+        history.add(
+            Request::new("GET", "https://api.example.com?token=secret"),
+            "secret".to_string(),
+        );
+        plain.save_history(&history).unwrap();
+
+        let (vault, _header) = Vault::create("correct horse").unwrap();
+        let encrypted = plain.clone().with_vault(Some(vault));
+        // Plaintext written before encryption was enabled is still readable
+        assert_eq!(encrypted.load_history().unwrap().len(), 1);
+        encrypted.migrate_to(&encrypted).unwrap();
+
+        let raw = std::fs::read(encrypted.history_path()).unwrap();
+        assert!(Vault::is_encrypted(&raw));
+        assert!(matches!(plain.load_history(), Err(AppError::FileError(_))));
+        assert_eq!(encrypted.load_history().unwrap().len(), 1);
+
+        encrypted.migrate_to(&plain).unwrap();
+        assert_eq!(plain.load_history().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_read_only_storage_keeps_files() {
+        let root = TempDir::new("profile-read-only");
+        let storage = ProfileRegistry::new().active().storage(&root);
+        let mut history = RequestHistory::new();
+        history.add(
+            Request::new("GET", "https://api.example.com"),
+            "api".to_string(),
+        );
+        storage.save_history(&history).unwrap();
+
+        let viewer = storage.clone().read_only(true);
+        assert_eq!(viewer.load_history().unwrap().len(), 1);
+        viewer.save_history(&RequestHistory::new()).unwrap();
+        viewer.save_domains(&DomainSettings::new()).unwrap();
+
+        assert_eq!(storage.load_history().unwrap().len(), 1);
+        assert!(!viewer.domains_path().exists());
+    }
+}
@@ -1,8 +1,11 @@
+use super::example::ResponseExample;
+use super::hook::RequestHooks;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fmt;
 
 /// HTTP 请求方法枚举
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum HttpMethod {
     GET,
     POST,
@@ -75,12 +78,30 @@ impl From<HttpMethod> for String {
 }
 
 /// 统一的 HTTP 请求模型
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Request {
     pub method: HttpMethod,
     pub url: String,
+    #[serde(default)]
     pub headers: Vec<(String, String)>,
+    #[serde(default)]
     pub body: Option<String>,
+    /// Unix socket path or Windows named pipe to send through instead of TCP
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub socket: Option<String>,
+    /// Name of the imported client certificate to send, overriding the
+    /// domain's default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_certificate: Option<String>,
+    /// Ids of the assertion snippets checked against the response
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assertions: Vec<u64>,
+    /// Local commands run before sending and after the response
+    #[serde(default, skip_serializing_if = "RequestHooks::is_empty")]
+    pub hooks: RequestHooks,
+    /// Saved response examples, kept when the request is exported
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub examples: Vec<ResponseExample>,
 }
 
 impl Request {
@@ -91,6 +112,11 @@ impl Request {
             url: url.into(),
             headers: Vec::new(),
             body: None,
+            socket: None,
+            client_certificate: None,
+            assertions: Vec::new(),
+            hooks: RequestHooks::default(),
+            examples: Vec::new(),
         }
     }
 
@@ -104,6 +130,12 @@ impl Request {
         self.body = Some(body.into());
     }
 
+    /// 设置 socket 路径，空字符串表示使用普通 TCP
+    pub fn set_socket(&mut self, socket: &str) {
+        let socket = socket.trim();
+        self.socket = (!socket.is_empty()).then(|| socket.to_string());
+    }
+
     /// 转换 headers 为 HashMap 格式（用于 HTTP 客户端）
     pub fn headers_as_map(&self) -> HashMap<String, String> {
         self.headers.iter().cloned().collect()
@@ -122,6 +154,11 @@ impl Default for Request {
             url: String::new(),
             headers: Vec::new(),
             body: None,
+            socket: None,
+            client_certificate: None,
+            assertions: Vec::new(),
+            hooks: RequestHooks::default(),
+            examples: Vec::new(),
         }
     }
 }
@@ -245,4 +282,38 @@ mod tests {
         let request = Request::new(HttpMethod::POST, "https://api.example.com");
         assert_eq!(request.method, HttpMethod::POST);
     }
+
+    #[test]
+    fn test_socket_is_optional() {
+        let mut request = Request::new("GET", "/v1.43/info");
+        request.set_socket("  ");
+        assert!(request.socket.is_none());
+        let json = serde_json::to_string(&request).unwrap();
+        assert!(!json.contains("socket"));
+
+        request.set_socket(" /var/run/docker.sock ");
+        let json = serde_json::to_string(&request).unwrap();
+        let restored: Request = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.socket.as_deref(), Some("/var/run/docker.sock"));
+
+        let old: Request = serde_json::from_str(r#"{"method":"GET","url":"/"}"#).unwrap();
+        assert!(old.socket.is_none());
+    }
+
+    #[test]
+    fn test_body_is_never_read_from_a_file() {
+        // A body that merely starts with `@` stays a body
+        let mut request = Request::new("POST", "https://example.com");
+        request.set_body("@channel hello");
+        let json = serde_json::to_string(&request).unwrap();
+        let restored: Request = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.body.as_deref(), Some("@channel hello"));
+
+        // A request file naming a local file as its body doesn't get to
+        // read it
+        let json = r#"{"method":"POST","url":"https://example.com","body_file":"/etc/passwd"}"#;
+        let restored: Request = serde_json::from_str(json).unwrap();
+        assert!(restored.body.is_none());
+        assert!(!serde_json::to_string(&restored).unwrap().contains("passwd"));
+    }
 }
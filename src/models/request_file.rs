@@ -0,0 +1,202 @@
+use super::annotation::SavedResponse;
+use super::request::Request;
+use crate::errors::AppError;
+use crate::storage::write_atomic;
+use crate::utils::export_encryption::ExportRecipients;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// File extension used for shared request files
+pub const REQUEST_FILE_EXTENSION: &str = "pgreq";
+
+/// Current version of the `.pgreq` format
+pub const REQUEST_FILE_VERSION: u32 = 1;
+
+/// A single request saved as a `.pgreq` file.
+///
+/// The file is pretty-printed JSON so it can be reviewed and shared easily:
+///
+/// ```json
+/// {
+///   "pgreq": 1,
+///   "name": "Users API",
+///   "request": {
+///     "method": "GET",
+///     "url": "https://api.example.com/users",
+///     "headers": [["Accept", "application/json"]],
+///     "body": null
+///   }
+/// }
+/// ```
+///
+/// A shared response can be included under `"response"` together with its
+/// annotations, so teammates see the same highlights and comments.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RequestFile {
+    #[serde(rename = "pgreq")]
+    pub version: u32,
+    pub name: String,
+    pub request: Request,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response: Option<SavedResponse>,
+}
+
+impl RequestFile {
+    pub fn new(name: impl Into<String>, request: Request) -> Self {
+        Self {
+            version: REQUEST_FILE_VERSION,
+            name: name.into(),
+            request,
+            response: None,
+        }
+    }
+
+    /// Include a response (and its annotations) in the file
+    pub fn with_response(mut self, response: Option<SavedResponse>) -> Self {
+        self.response = response;
+        self
+    }
+
+    /// Serialize to the on-disk JSON representation
+    pub fn to_json(&self) -> Result<String, AppError> {
+        serde_json::to_string_pretty(self).map_err(|e| AppError::ParseError(e.to_string()))
+    }
+
+    /// Parse the on-disk JSON representation
+    pub fn from_json(content: &str) -> Result<Self, AppError> {
+        let file: RequestFile =
+            serde_json::from_str(content).map_err(|e| AppError::ParseError(e.to_string()))?;
+        if file.version > REQUEST_FILE_VERSION {
+            return Err(AppError::ParseError(format!(
+                "Unsupported .{} version {} (newest supported: {})",
+                REQUEST_FILE_EXTENSION, file.version, REQUEST_FILE_VERSION
+            )));
+        }
+        Ok(file)
+    }
+
+    /// Write the request file to `path`, replacing it atomically
+    pub fn save(&self, path: &Path) -> Result<(), AppError> {
+        write_atomic(path, self.to_json()?.as_bytes())
+    }
+
+    /// Write the request file to `path`, encrypted for `recipients`
+    pub fn save_encrypted(
+        &self,
+        path: &Path,
+        recipients: &ExportRecipients,
+    ) -> Result<(), AppError> {
+        write_atomic(path, &recipients.encrypt(self.to_json()?.as_bytes())?)
+    }
+
+    /// Read a request file from `path`
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_json(&content)
+    }
+
+    /// Check whether a path looks like a `.pgreq` file
+    pub fn is_request_file(path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .is_some_and(|ext| ext.eq_ignore_ascii_case(REQUEST_FILE_EXTENSION))
+    }
+
+    /// Suggested file name, e.g. `GET-api-example-com-users.pgreq`
+    pub fn file_name(&self) -> String {
+        let source = self
+            .name
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        let mut slug = String::new();
+        for c in source.chars() {
+            if c.is_ascii_alphanumeric() {
+                slug.push(c);
+            } else if !slug.ends_with('-') && !slug.is_empty() {
+                slug.push('-');
+            }
+        }
+        let slug = slug.trim_end_matches('-');
+        if slug.is_empty() {
+            format!("{}.{}", self.request.method, REQUEST_FILE_EXTENSION)
+        } else {
+            format!(
+                "{}-{}.{}",
+                self.request.method, slug, REQUEST_FILE_EXTENSION
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HttpMethod;
+
+    #[test]
+    fn test_request_file_round_trip() {
+        let mut request = Request::new("POST", "https://api.example.com/users");
+        request.add_header("Content-Type", "application/json");
+        request.set_body(r#"{"name": "John"}"#);
+        let file = RequestFile::new("Create user", request);
+
+        let json = file.to_json().unwrap();
+        assert!(json.contains("\"pgreq\": 1"));
+
+        let parsed = RequestFile::from_json(&json).unwrap();
+        assert_eq!(parsed, file);
+        assert_eq!(parsed.request.method, HttpMethod::POST);
+    }
+
+    #[test]
+    fn test_request_file_with_annotated_response() {
+        let mut response = SavedResponse::new(200, r#"{"id": 1}"#);
+        response
+            .add_annotation(1..5, crate::models::HighlightColor::Yellow, "id field")
+            .unwrap();
+        let file = RequestFile::new("User", Request::new("GET", "https://example.com/users/1"))
+            .with_response(Some(response));
+
+        let parsed = RequestFile::from_json(&file.to_json().unwrap()).unwrap();
+        let parsed_response = parsed.response.unwrap();
+        assert_eq!(parsed_response.annotations.len(), 1);
+        assert_eq!(parsed_response.annotations[0].comment, "id field");
+    }
+
+    #[test]
+    fn test_request_file_optional_fields() {
+        let json = r#"{"pgreq": 1, "name": "Ping", "request": {"method": "GET", "url": "https://example.com"}}"#;
+        let file = RequestFile::from_json(json).unwrap();
+        assert!(file.request.headers.is_empty());
+        assert!(file.request.body.is_none());
+    }
+
+    #[test]
+    fn test_request_file_rejects_newer_version() {
+        let json = r#"{"pgreq": 99, "name": "Ping", "request": {"method": "GET", "url": "https://example.com"}}"#;
+        assert!(matches!(
+            RequestFile::from_json(json),
+            Err(AppError::ParseError(_))
+        ));
+    }
+
+    #[test]
+    fn test_is_request_file() {
+        assert!(RequestFile::is_request_file(Path::new("/tmp/users.pgreq")));
+        assert!(RequestFile::is_request_file(Path::new("USERS.PGREQ")));
+        assert!(!RequestFile::is_request_file(Path::new("users.json")));
+        assert!(!RequestFile::is_request_file(Path::new("pgreq")));
+    }
+
+    #[test]
+    fn test_file_name() {
+        let file = RequestFile::new(
+            "https://api.example.com/users?page=2",
+            Request::new("GET", "https://api.example.com/users?page=2"),
+        );
+        assert_eq!(file.file_name(), "GET-api-example-com-users-page-2.pgreq");
+
+        let file = RequestFile::new("", Request::new("DELETE", ""));
+        assert_eq!(file.file_name(), "DELETE.pgreq");
+    }
+}
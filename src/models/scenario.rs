@@ -0,0 +1,200 @@
+use super::request::Request;
+use crate::errors::AppError;
+use serde::{Deserialize, Serialize};
+
+/// Part of a scenario a request belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Stage {
+    /// Prepares what the main requests need, e.g. creates a test resource
+    Setup,
+    Main,
+    /// Cleans up after the run; always sent, even when a step failed
+    Teardown,
+}
+
+impl Stage {
+    pub fn all() -> [Stage; 3] {
+        [Stage::Setup, Stage::Main, Stage::Teardown]
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Stage::Setup => "Setup",
+            Stage::Main => "Main",
+            Stage::Teardown => "Teardown",
+        }
+    }
+}
+
+/// A group of requests run together and reported as one unit: the setup
+/// requests, then the main requests, then the teardown requests
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub setup: Vec<Request>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub main: Vec<Request>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub teardown: Vec<Request>,
+}
+
+impl Scenario {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            setup: Vec::new(),
+            main: Vec::new(),
+            teardown: Vec::new(),
+        }
+    }
+
+    pub fn requests(&self, stage: Stage) -> &[Request] {
+        match stage {
+            Stage::Setup => &self.setup,
+            Stage::Main => &self.main,
+            Stage::Teardown => &self.teardown,
+        }
+    }
+
+    fn requests_mut(&mut self, stage: Stage) -> &mut Vec<Request> {
+        match stage {
+            Stage::Setup => &mut self.setup,
+            Stage::Main => &mut self.main,
+            Stage::Teardown => &mut self.teardown,
+        }
+    }
+
+    /// Add a request at the end of `stage`
+    pub fn add(&mut self, stage: Stage, request: Request) {
+        self.requests_mut(stage).push(request);
+    }
+
+    pub fn remove(&mut self, stage: Stage, index: usize) -> Option<Request> {
+        let requests = self.requests_mut(stage);
+        (index < requests.len()).then(|| requests.remove(index))
+    }
+
+    /// Every request in the order a run sends them
+    pub fn steps(&self) -> Vec<(Stage, &Request)> {
+        Stage::all()
+            .into_iter()
+            .flat_map(|stage| {
+                self.requests(stage)
+                    .iter()
+                    .map(move |request| (stage, request))
+            })
+            .collect()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.setup.is_empty() && self.main.is_empty() && self.teardown.is_empty()
+    }
+}
+
+/// Scenarios of a profile, saved as `scenarios.json`
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScenarioLibrary {
+    scenarios: Vec<Scenario>,
+}
+
+impl ScenarioLibrary {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn scenarios(&self) -> &[Scenario] {
+        &self.scenarios
+    }
+
+    pub fn get(&self, index: usize) -> Option<&Scenario> {
+        self.scenarios.get(index)
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Scenario> {
+        self.scenarios.get_mut(index)
+    }
+
+    /// Add an empty scenario, returning its index; names must be unique
+    /// (ignoring case)
+    pub fn add(&mut self, name: &str) -> Result<usize, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Scenario name cannot be empty".to_string(),
+            ));
+        }
+        if self
+            .scenarios
+            .iter()
+            .any(|scenario| scenario.name.eq_ignore_ascii_case(name))
+        {
+            return Err(AppError::ValidationError(format!(
+                "A scenario named \"{name}\" already exists"
+            )));
+        }
+        self.scenarios.push(Scenario::new(name));
+        Ok(self.scenarios.len() - 1)
+    }
+
+    pub fn remove(&mut self, index: usize) -> Option<Scenario> {
+        (index < self.scenarios.len()).then(|| self.scenarios.remove(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HttpMethod;
+
+    #[test]
+    fn test_steps_run_setup_main_then_teardown() {
+        let mut scenario = Scenario::new("Create and delete a user");
+        scenario.add(
+            Stage::Teardown,
+            Request::new(HttpMethod::DELETE, "/users/1"),
+        );
+        scenario.add(Stage::Main, Request::new(HttpMethod::GET, "/users/1"));
+        scenario.add(Stage::Setup, Request::new(HttpMethod::POST, "/users"));
+        scenario.add(Stage::Main, Request::new(HttpMethod::PUT, "/users/1"));
+
+        let steps: Vec<(Stage, HttpMethod)> = scenario
+            .steps()
+            .into_iter()
+            .map(|(stage, request)| (stage, request.method))
+            .collect();
+        assert_eq!(
+            steps,
+            [
+                (Stage::Setup, HttpMethod::POST),
+                (Stage::Main, HttpMethod::GET),
+                (Stage::Main, HttpMethod::PUT),
+                (Stage::Teardown, HttpMethod::DELETE),
+            ]
+        );
+
+        assert_eq!(
+            scenario
+                .remove(Stage::Main, 0)
+                .map(|request| request.method),
+            Some(HttpMethod::GET)
+        );
+        assert!(scenario.remove(Stage::Main, 5).is_none());
+        assert_eq!(scenario.requests(Stage::Main).len(), 1);
+    }
+
+    #[test]
+    fn test_library_names_are_unique() {
+        let mut library = ScenarioLibrary::new();
+        assert_eq!(library.add(" Checkout ").unwrap(), 0);
+        assert!(library.add("checkout").is_err());
+        assert!(library.add("  ").is_err());
+        assert_eq!(library.get(0).unwrap().name, "Checkout");
+
+        let json = serde_json::to_string(&library).unwrap();
+        assert_eq!(json, r#"{"scenarios":[{"name":"Checkout"}]}"#);
+        let restored: ScenarioLibrary = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, library);
+    }
+}
@@ -0,0 +1,141 @@
+use crate::errors::AppError;
+use crate::utils::assertions::parse_script;
+use serde::{Deserialize, Serialize};
+
+/// A named assertion script that requests attach by id, so editing it
+/// changes the checks of every request using it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AssertionSnippet {
+    /// Unique within the library, stays the same when the snippet is renamed
+    pub id: u64,
+    pub name: String,
+    pub script: String,
+}
+
+/// Assertion snippets of a profile, saved as `snippets.json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnippetLibrary {
+    snippets: Vec<AssertionSnippet>,
+    next_id: u64,
+}
+
+impl SnippetLibrary {
+    pub fn new() -> Self {
+        Self {
+            snippets: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    pub fn snippets(&self) -> &[AssertionSnippet] {
+        &self.snippets
+    }
+
+    pub fn get(&self, id: u64) -> Option<&AssertionSnippet> {
+        self.snippets.iter().find(|snippet| snippet.id == id)
+    }
+
+    /// Add a snippet, returning its id; names must be unique (ignoring
+    /// case) and the script must parse
+    pub fn add(&mut self, name: &str, script: &str) -> Result<u64, AppError> {
+        let name = self.validate(None, name, script)?;
+        let id = self.next_id;
+        self.next_id += 1;
+        self.snippets.push(AssertionSnippet {
+            id,
+            name,
+            script: script.to_string(),
+        });
+        Ok(id)
+    }
+
+    /// Rename or rewrite the snippet `id`
+    pub fn update(&mut self, id: u64, name: &str, script: &str) -> Result<(), AppError> {
+        let name = self.validate(Some(id), name, script)?;
+        let snippet = self
+            .snippets
+            .iter_mut()
+            .find(|snippet| snippet.id == id)
+            .ok_or_else(|| AppError::ValidationError("The snippet no longer exists".to_string()))?;
+        snippet.name = name;
+        snippet.script = script.to_string();
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: u64) -> Option<AssertionSnippet> {
+        let index = self.snippets.iter().position(|snippet| snippet.id == id)?;
+        Some(self.snippets.remove(index))
+    }
+
+    // Trimmed name if it is free (other than for snippet `id`) and the script parses
+    fn validate(&self, id: Option<u64>, name: &str, script: &str) -> Result<String, AppError> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(AppError::ValidationError(
+                "Snippet name cannot be empty".to_string(),
+            ));
+        }
+        if self
+            .snippets
+            .iter()
+            .any(|snippet| Some(snippet.id) != id && snippet.name.eq_ignore_ascii_case(name))
+        {
+            return Err(AppError::ValidationError(format!(
+                "A snippet named \"{name}\" already exists"
+            )));
+        }
+        if parse_script(script)?.is_empty() {
+            return Err(AppError::ValidationError(
+                "The snippet has no assertions".to_string(),
+            ));
+        }
+        Ok(name.to_string())
+    }
+}
+
+impl Default for SnippetLibrary {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_update_and_remove() {
+        let mut library = SnippetLibrary::new();
+        let envelope = library
+            .add(
+                " JSON API envelope ",
+                "status in 200..299\njson data exists",
+            )
+            .unwrap();
+        let paging = library
+            .add("Pagination headers", "header Link exists")
+            .unwrap();
+        assert_ne!(envelope, paging);
+        assert_eq!(library.get(envelope).unwrap().name, "JSON API envelope");
+
+        assert!(library.add("json api ENVELOPE", "status == 200").is_err());
+        assert!(library.add("Empty", "# nothing yet").is_err());
+        assert!(library.add("Broken", "status is").is_err());
+
+        library
+            .update(envelope, "Envelope", "json data is array")
+            .unwrap();
+        assert_eq!(library.get(envelope).unwrap().script, "json data is array");
+        assert!(library
+            .update(envelope, "Pagination headers", "status == 200")
+            .is_err());
+
+        assert!(library.remove(paging).is_some());
+        assert!(library.get(paging).is_none());
+        // Ids are not reused, so requests never pick up another snippet
+        let next = library
+            .add("Pagination headers", "header Link exists")
+            .unwrap();
+        assert!(next > paging);
+    }
+}
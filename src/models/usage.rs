@@ -0,0 +1,143 @@
+use chrono::{DateTime, Local, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Feature whose use is counted in the usage statistics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    RequestSent,
+    SendAndDuplicate,
+    ApiExplorerTry,
+    GrpcCall,
+    GraphqlSubscription,
+    MockServer,
+    PagesFetched,
+    BaselinePinned,
+    ExampleSaved,
+    RequestShared,
+    SpecInferred,
+    BulkRun,
+    ScenarioRun,
+    SessionReplay,
+    TabCompared,
+    ResponsePoppedOut,
+    PdfExported,
+}
+
+impl Feature {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::RequestSent => "Requests sent",
+            Self::SendAndDuplicate => "Send & Duplicate",
+            Self::ApiExplorerTry => "API explorer Try it",
+            Self::GrpcCall => "gRPC calls",
+            Self::GraphqlSubscription => "GraphQL subscriptions",
+            Self::MockServer => "Mock server started",
+            Self::PagesFetched => "Fetch all pages",
+            Self::BaselinePinned => "Baselines pinned",
+            Self::ExampleSaved => "Examples saved",
+            Self::RequestShared => "Requests shared",
+            Self::SpecInferred => "Specs inferred",
+            Self::BulkRun => "Bulk runs",
+            Self::ScenarioRun => "Scenario runs",
+            Self::SessionReplay => "Session replays",
+            Self::TabCompared => "Tabs compared",
+            Self::ResponsePoppedOut => "Response popped out",
+            Self::PdfExported => "PDF exports",
+        }
+    }
+}
+
+/// How often each feature was used in a profile. The counters are only
+/// kept on this computer, saved as `usage.json`, and leave it only when
+/// the user exports them
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageStats {
+    /// When counting started (or was last reset)
+    since: DateTime<Utc>,
+    counts: BTreeMap<Feature, u64>,
+}
+
+impl Default for UsageStats {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl UsageStats {
+    pub fn new() -> Self {
+        Self {
+            since: Utc::now(),
+            counts: BTreeMap::new(),
+        }
+    }
+
+    /// Count one use of `feature`
+    pub fn record(&mut self, feature: Feature) {
+        *self.counts.entry(feature).or_default() += 1;
+    }
+
+    pub fn count(&self, feature: Feature) -> u64 {
+        self.counts.get(&feature).copied().unwrap_or_default()
+    }
+
+    /// Features used at least once, most used first
+    pub fn ranked(&self) -> Vec<(Feature, u64)> {
+        let mut ranked: Vec<(Feature, u64)> = self
+            .counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .map(|(feature, count)| (*feature, *count))
+            .collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        ranked
+    }
+
+    /// Local date counting started, e.g. `2024-05-01`
+    pub fn formatted_since(&self) -> String {
+        self.since
+            .with_timezone(&Local)
+            .format("%Y-%m-%d")
+            .to_string()
+    }
+
+    /// Forget every count and start again from now
+    pub fn reset(&mut self) {
+        *self = Self::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_and_rank() {
+        let mut stats = UsageStats::new();
+        stats.record(Feature::PdfExported);
+        for _ in 0..3 {
+            stats.record(Feature::RequestSent);
+        }
+        stats.record(Feature::BulkRun);
+
+        assert_eq!(stats.count(Feature::RequestSent), 3);
+        assert_eq!(stats.count(Feature::GrpcCall), 0);
+        assert_eq!(
+            stats.ranked(),
+            vec![
+                (Feature::RequestSent, 3),
+                (Feature::BulkRun, 1),
+                (Feature::PdfExported, 1)
+            ]
+        );
+
+        let json = serde_json::to_string(&stats).unwrap();
+        assert!(json.contains("\"request_sent\":3"));
+        let restored: UsageStats = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored, stats);
+
+        stats.reset();
+        assert!(stats.ranked().is_empty());
+    }
+}
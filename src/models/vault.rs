@@ -0,0 +1,240 @@
+use crate::errors::AppError;
+use crate::storage::write_atomic;
+use argon2::{Algorithm, Argon2, Params, Version};
+use chacha20poly1305::{
+    aead::{rand_core::RngCore, Aead, AeadCore, KeyInit, OsRng},
+    XChaCha20Poly1305, XNonce,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// File in the data directory describing how the passphrase key is derived
+const VAULT_FILE: &str = "vault.json";
+
+/// Current version of the vault format
+pub const VAULT_VERSION: u32 = 1;
+
+/// Prefix of every encrypted file, followed by the nonce and the ciphertext
+const MAGIC: &[u8] = b"PGVAULT1";
+
+const NONCE_LEN: usize = 24;
+const SALT_LEN: usize = 16;
+const KEY_LEN: usize = 32;
+
+/// Plaintext encrypted into the header to check the passphrase on unlock
+const VERIFIER: &[u8] = b"postman-gpui vault";
+
+/// Minimum passphrase length accepted when creating a vault
+pub const MIN_PASSPHRASE_LENGTH: usize = 8;
+
+/// Key derivation settings saved as `vault.json` (the key itself is never stored)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VaultHeader {
+    pub version: u32,
+    /// Argon2id memory cost in KiB
+    pub m_cost: u32,
+    /// Argon2id iterations
+    pub t_cost: u32,
+    /// Argon2id parallelism
+    pub p_cost: u32,
+    /// Hex encoded salt
+    pub salt: String,
+    /// Hex encoded `VERIFIER` encrypted with the derived key
+    pub verifier: String,
+}
+
+impl VaultHeader {
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(VAULT_FILE)
+    }
+
+    /// Load the header from the data directory, `None` if encryption is off
+    pub fn load(root: &Path) -> Result<Option<Self>, AppError> {
+        let path = Self::path(root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(&path)?;
+        let header: Self =
+            serde_json::from_str(&content).map_err(|e| AppError::ParseError(e.to_string()))?;
+        if header.version > VAULT_VERSION {
+            return Err(AppError::ParseError(format!(
+                "Unsupported vault version {} (newest supported: {})",
+                header.version, VAULT_VERSION
+            )));
+        }
+        Ok(Some(header))
+    }
+
+    pub fn save(&self, root: &Path) -> Result<(), AppError> {
+        let content =
+            serde_json::to_string_pretty(self).map_err(|e| AppError::ParseError(e.to_string()))?;
+        write_atomic(&Self::path(root), content.as_bytes())
+    }
+
+    /// Turn encryption off by removing the header
+    pub fn remove(root: &Path) -> Result<(), AppError> {
+        let path = Self::path(root);
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+/// Encrypts persisted data with a key derived from the user's passphrase.
+///
+/// Keys are derived with Argon2id and data is sealed with
+/// XChaCha20-Poly1305, using a fresh random nonce for every write.
+#[derive(Clone)]
+pub struct Vault {
+    cipher: XChaCha20Poly1305,
+}
+
+impl fmt::Debug for Vault {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Vault { .. }")
+    }
+}
+
+impl Vault {
+    /// Create a vault for a new passphrase, returning the header to save
+    pub fn create(passphrase: &str) -> Result<(Self, VaultHeader), AppError> {
+        if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+            return Err(AppError::ValidationError(format!(
+                "Passphrase must be at least {MIN_PASSPHRASE_LENGTH} characters"
+            )));
+        }
+
+        let mut salt = [0u8; SALT_LEN];
+        OsRng.fill_bytes(&mut salt);
+        let mut header = VaultHeader {
+            version: VAULT_VERSION,
+            m_cost: Params::DEFAULT_M_COST,
+            t_cost: Params::DEFAULT_T_COST,
+            p_cost: Params::DEFAULT_P_COST,
+            salt: to_hex(&salt),
+            verifier: String::new(),
+        };
+        let vault = Self::derive(passphrase, &header)?;
+        header.verifier = to_hex(&vault.encrypt(VERIFIER)?);
+        Ok((vault, header))
+    }
+
+    /// Unlock an existing vault; fails if the passphrase is wrong
+    pub fn unlock(passphrase: &str, header: &VaultHeader) -> Result<Self, AppError> {
+        let vault = Self::derive(passphrase, header)?;
+        let verifier = from_hex(&header.verifier)
+            .ok_or_else(|| AppError::ParseError("Invalid vault verifier".to_string()))?;
+        match vault.decrypt(&verifier) {
+            Ok(plaintext) if plaintext == VERIFIER => Ok(vault),
+            _ => Err(AppError::ValidationError("Wrong passphrase".to_string())),
+        }
+    }
+
+    fn derive(passphrase: &str, header: &VaultHeader) -> Result<Self, AppError> {
+        let salt = from_hex(&header.salt)
+            .ok_or_else(|| AppError::ParseError("Invalid vault salt".to_string()))?;
+        let params = Params::new(header.m_cost, header.t_cost, header.p_cost, Some(KEY_LEN))
+            .map_err(|e| AppError::ParseError(e.to_string()))?;
+        let mut key = [0u8; KEY_LEN];
+        Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+            .hash_password_into(passphrase.as_bytes(), &salt, &mut key)
+            .map_err(|e| AppError::ValidationError(e.to_string()))?;
+        let cipher = XChaCha20Poly1305::new(&key.into());
+        key.fill(0);
+        Ok(Self { cipher })
+    }
+
+    /// Whether `data` was written by [`Vault::encrypt`]
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .cipher
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| AppError::FileError("Encryption failed".to_string()))?;
+
+        let mut data = Vec::with_capacity(MAGIC.len() + NONCE_LEN + ciphertext.len());
+        data.extend_from_slice(MAGIC);
+        data.extend_from_slice(&nonce);
+        data.extend_from_slice(&ciphertext);
+        Ok(data)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>, AppError> {
+        if !Self::is_encrypted(data) || data.len() < MAGIC.len() + NONCE_LEN {
+            return Err(AppError::ParseError("Not an encrypted file".to_string()));
+        }
+        let (nonce, ciphertext) = data[MAGIC.len()..].split_at(NONCE_LEN);
+        self.cipher
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| {
+                AppError::FileError("Decryption failed (wrong key or corrupted data)".to_string())
+            })
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn from_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encrypt_round_trip() {
+        let (vault, header) = Vault::create("correct horse").unwrap();
+        let data = vault.encrypt(b"secret token").unwrap();
+        assert!(Vault::is_encrypted(&data));
+        assert!(!data.windows(6).any(|w| w == b"secret"));
+        assert_eq!(vault.decrypt(&data).unwrap(), b"secret token");
+
+        // Same passphrase unlocks and reads the data
+        let unlocked = Vault::unlock("correct horse", &header).unwrap();
+        assert_eq!(unlocked.decrypt(&data).unwrap(), b"secret token");
+    }
+
+    #[test]
+    fn test_wrong_passphrase_and_tampering() {
+        let (vault, header) = Vault::create("correct horse").unwrap();
+        assert!(matches!(
+            Vault::unlock("wrong horse", &header),
+            Err(AppError::ValidationError(_))
+        ));
+
+        let mut data = vault.encrypt(b"secret token").unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(vault.decrypt(&data).is_err());
+        assert!(vault.decrypt(b"{\"plain\": true}").is_err());
+    }
+
+    #[test]
+    fn test_short_passphrase_rejected() {
+        assert!(Vault::create("short").is_err());
+    }
+
+    #[test]
+    fn test_hex_round_trip() {
+        assert_eq!(to_hex(&[0x00, 0xab, 0xff]), "00abff");
+        assert_eq!(from_hex("00abff"), Some(vec![0x00, 0xab, 0xff]));
+        assert_eq!(from_hex("abc"), None);
+        assert_eq!(from_hex("zz"), None);
+    }
+}
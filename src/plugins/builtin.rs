@@ -0,0 +1,274 @@
+use super::{AuthProvider, BodySerializer, Plugin, PluginRegistry, ResponseRenderer};
+use crate::errors::AppError;
+use crate::models::Request;
+use crate::utils::{base64, cbor, msgpack, protobuf, protobuf::DescriptorPool};
+use std::sync::Arc;
+
+pub const JSON_SERIALIZER: &str = "Compact JSON";
+pub const BEARER_AUTH: &str = "Bearer";
+pub const BASIC_AUTH: &str = "Basic";
+pub const MESSAGEPACK_RENDERER: &str = "MessagePack";
+pub const CBOR_RENDERER: &str = "CBOR";
+pub const PROTOBUF_RENDERER: &str = "Protobuf";
+
+/// Extensions that ship with the app
+pub struct BuiltinPlugin;
+
+impl Plugin for BuiltinPlugin {
+    fn name(&self) -> &str {
+        "builtin"
+    }
+
+    fn register(&self, registry: &mut PluginRegistry) {
+        registry.register_body_serializer(CompactJsonSerializer);
+        registry.register_auth_provider(BearerAuth);
+        registry.register_auth_provider(BasicAuth);
+        registry.register_response_renderer(MessagePackRenderer);
+        registry.register_response_renderer(CborRenderer);
+        registry.register_response_renderer(ProtobufRenderer::raw());
+    }
+}
+
+/// Validates the JSON body and sends it without whitespace
+pub struct CompactJsonSerializer;
+
+impl BodySerializer for CompactJsonSerializer {
+    fn name(&self) -> &str {
+        JSON_SERIALIZER
+    }
+
+    fn content_type(&self) -> &str {
+        "application/json"
+    }
+
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, AppError> {
+        let value: serde_json::Value =
+            serde_json::from_str(text).map_err(|e| AppError::ParseError(e.to_string()))?;
+        serde_json::to_vec(&value).map_err(|e| AppError::ParseError(e.to_string()))
+    }
+}
+
+/// `Authorization: Bearer <token>`
+pub struct BearerAuth;
+
+impl AuthProvider for BearerAuth {
+    fn name(&self) -> &str {
+        BEARER_AUTH
+    }
+
+    fn credential_hint(&self) -> &str {
+        "Token"
+    }
+
+    fn apply(&self, request: &mut Request, credential: &str) -> Result<(), AppError> {
+        let token = credential.trim();
+        if token.is_empty() {
+            return Err(AppError::ValidationError(
+                "Bearer token cannot be empty".to_string(),
+            ));
+        }
+        set_authorization(request, format!("Bearer {token}"));
+        Ok(())
+    }
+}
+
+/// `Authorization: Basic <base64(username:password)>`
+pub struct BasicAuth;
+
+impl AuthProvider for BasicAuth {
+    fn name(&self) -> &str {
+        BASIC_AUTH
+    }
+
+    fn credential_hint(&self) -> &str {
+        "username:password"
+    }
+
+    fn apply(&self, request: &mut Request, credential: &str) -> Result<(), AppError> {
+        if !credential.contains(':') {
+            return Err(AppError::ValidationError(
+                "Basic auth credentials must look like username:password".to_string(),
+            ));
+        }
+        set_authorization(
+            request,
+            format!("Basic {}", base64::encode(credential.as_bytes())),
+        );
+        Ok(())
+    }
+}
+
+/// Decodes `application/msgpack` bodies to JSON
+pub struct MessagePackRenderer;
+
+impl ResponseRenderer for MessagePackRenderer {
+    fn name(&self) -> &str {
+        MESSAGEPACK_RENDERER
+    }
+
+    fn can_render(&self, content_type: &str) -> bool {
+        matches!(
+            content_type,
+            "application/msgpack" | "application/x-msgpack" | "application/vnd.msgpack"
+        )
+    }
+
+    fn render(&self, body: &[u8]) -> Result<String, AppError> {
+        Ok(msgpack::decode(body)?.to_json_pretty())
+    }
+}
+
+/// Decodes `application/cbor` (and `+cbor` suffixed) bodies to JSON
+pub struct CborRenderer;
+
+impl ResponseRenderer for CborRenderer {
+    fn name(&self) -> &str {
+        CBOR_RENDERER
+    }
+
+    fn can_render(&self, content_type: &str) -> bool {
+        content_type == "application/cbor" || content_type.ends_with("+cbor")
+    }
+
+    fn render(&self, body: &[u8]) -> Result<String, AppError> {
+        Ok(cbor::decode(body)?.to_json_pretty())
+    }
+}
+
+/// Decodes `application/x-protobuf` bodies to JSON, using field names from a
+/// descriptor set when one has been chosen
+pub struct ProtobufRenderer {
+    schema: Option<(Arc<DescriptorPool>, String)>,
+}
+
+impl ProtobufRenderer {
+    /// Without a schema: fields are keyed by number
+    pub fn raw() -> Self {
+        Self { schema: None }
+    }
+
+    /// Decode responses as `message`, which must be in `pool`
+    pub fn with_schema(pool: DescriptorPool, message: &str) -> Result<Self, AppError> {
+        let full = match pool.resolve(message) {
+            Some(full) => full.to_string(),
+            None => {
+                let names = pool.message_names();
+                let mut available = names
+                    .iter()
+                    .take(10)
+                    .copied()
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                if names.len() > 10 {
+                    available.push_str(", ...");
+                }
+                return Err(AppError::ValidationError(format!(
+                    "Unknown message type '{message}'. Available: {available}"
+                )));
+            }
+        };
+        Ok(Self {
+            schema: Some((Arc::new(pool), full)),
+        })
+    }
+
+    /// Fully qualified message type responses are decoded as
+    pub fn message(&self) -> Option<&str> {
+        self.schema.as_ref().map(|(_, message)| message.as_str())
+    }
+}
+
+impl ResponseRenderer for ProtobufRenderer {
+    fn name(&self) -> &str {
+        PROTOBUF_RENDERER
+    }
+
+    fn can_render(&self, content_type: &str) -> bool {
+        matches!(
+            content_type,
+            "application/x-protobuf"
+                | "application/protobuf"
+                | "application/x-google-protobuf"
+                | "application/vnd.google.protobuf"
+        )
+    }
+
+    fn render(&self, body: &[u8]) -> Result<String, AppError> {
+        let value = match &self.schema {
+            Some((pool, message)) => pool.decode(message, body)?,
+            None => protobuf::decode_raw(body)?,
+        };
+        Ok(value.to_json_pretty())
+    }
+}
+
+// Replace any Authorization header already on the request
+fn set_authorization(request: &mut Request, value: String) {
+    request
+        .headers
+        .retain(|(key, _)| !key.eq_ignore_ascii_case("Authorization"));
+    request.add_header("Authorization", value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compact_json_serializer() {
+        let bytes = CompactJsonSerializer
+            .serialize("{\n  \"name\": \"John\",\n  \"age\": 30\n}")
+            .unwrap();
+        assert_eq!(bytes, br#"{"age":30,"name":"John"}"#);
+        assert!(CompactJsonSerializer.serialize("{broken").is_err());
+    }
+
+    #[test]
+    fn test_bearer_auth_replaces_authorization() {
+        let mut request = Request::new("GET", "https://example.com");
+        request.add_header("authorization", "old");
+        BearerAuth.apply(&mut request, " abc ").unwrap();
+        assert_eq!(
+            request.headers,
+            vec![("Authorization".to_string(), "Bearer abc".to_string())]
+        );
+        assert!(BearerAuth.apply(&mut request, "").is_err());
+    }
+
+    #[test]
+    fn test_binary_renderers() {
+        let registry = PluginRegistry::with_plugins(&[]);
+        let msgpack_renderer = registry.response_renderer("application/msgpack").unwrap();
+        assert_eq!(
+            msgpack_renderer.render(&[0x81, 0xa1, b'a', 0x01]).unwrap(),
+            "{\n  \"a\": 1\n}"
+        );
+        assert!(msgpack_renderer.render(&[0xc1]).is_err());
+
+        let cbor_renderer = registry
+            .response_renderer("application/problem+cbor")
+            .unwrap();
+        assert_eq!(
+            cbor_renderer.render(&[0x82, 0x01, 0xf5]).unwrap(),
+            "[\n  1,\n  true\n]"
+        );
+
+        let protobuf_renderer = registry
+            .response_renderer("application/x-protobuf")
+            .unwrap();
+        assert_eq!(protobuf_renderer.name(), PROTOBUF_RENDERER);
+        assert_eq!(
+            protobuf_renderer.render(&[0x08, 0x96, 0x01]).unwrap(),
+            "{\n  \"1\": 150\n}"
+        );
+        assert!(ProtobufRenderer::with_schema(DescriptorPool::default(), "User").is_err());
+    }
+
+    #[test]
+    fn test_basic_auth() {
+        let mut request = Request::new("GET", "https://example.com");
+        BasicAuth.apply(&mut request, "user:pass").unwrap();
+        assert_eq!(request.headers[0].1, "Basic dXNlcjpwYXNz");
+        assert!(BasicAuth.apply(&mut request, "nocolon").is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Extension points for custom body types, auth schemes and response renderers.
+//!
+//! Plugins are registered at compile time: build a binary that depends on
+//! this crate and pass the plugins to [`crate::app::launch::run`]:
+//!
+//! ```ignore
+//! fn main() {
+//!     postman_gpui::app::launch::run(vec![Box::new(MyMessagePackPlugin)]);
+//! }
+//! ```
+
+pub mod builtin;
+
+use crate::errors::AppError;
+use crate::models::Request;
+use gpui::Global;
+use std::sync::Arc;
+
+/// Turns the text typed in the body editor into the bytes that are sent
+pub trait BodySerializer: Send + Sync {
+    /// Name shown in the body editor, unique among serializers
+    fn name(&self) -> &str;
+
+    /// `Content-Type` added when the request doesn't set one
+    fn content_type(&self) -> &str;
+
+    fn serialize(&self, text: &str) -> Result<Vec<u8>, AppError>;
+}
+
+/// Adds credentials to a request (headers, query parameters, signatures...)
+pub trait AuthProvider: Send + Sync {
+    /// Name shown in the auth selector, unique among auth providers
+    fn name(&self) -> &str;
+
+    /// Hint shown in the credential field, e.g. `username:password`
+    fn credential_hint(&self) -> &str;
+
+    fn apply(&self, request: &mut Request, credential: &str) -> Result<(), AppError>;
+}
+
+/// Renders a response body as text for the response viewer
+pub trait ResponseRenderer: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Whether this renderer handles the response `Content-Type` (parameters
+    /// such as `; charset=utf-8` are already stripped and lowercased)
+    fn can_render(&self, content_type: &str) -> bool;
+
+    fn render(&self, body: &[u8]) -> Result<String, AppError>;
+}
+
+/// A bundle of extensions registered together
+pub trait Plugin {
+    fn name(&self) -> &str;
+
+    fn register(&self, registry: &mut PluginRegistry);
+}
+
+/// All registered extensions, available to the app as a GPUI global
+#[derive(Clone, Default)]
+pub struct PluginRegistry {
+    body_serializers: Vec<Arc<dyn BodySerializer>>,
+    auth_providers: Vec<Arc<dyn AuthProvider>>,
+    response_renderers: Vec<Arc<dyn ResponseRenderer>>,
+}
+
+impl Global for PluginRegistry {}
+
+impl PluginRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registry with the built-in extensions followed by `plugins`
+    pub fn with_plugins(plugins: &[Box<dyn Plugin>]) -> Self {
+        let mut registry = Self::new();
+        builtin::BuiltinPlugin.register(&mut registry);
+        for plugin in plugins {
+            tracing::info!("🧩 PluginRegistry - 注册插件: {}", plugin.name());
+            plugin.register(&mut registry);
+        }
+        registry
+    }
+
+    /// Register a body serializer, replacing one with the same name
+    pub fn register_body_serializer(&mut self, serializer: impl BodySerializer + 'static) {
+        self.body_serializers
+            .retain(|existing| existing.name() != serializer.name());
+        self.body_serializers.push(Arc::new(serializer));
+    }
+
+    /// Register an auth provider, replacing one with the same name
+    pub fn register_auth_provider(&mut self, provider: impl AuthProvider + 'static) {
+        self.auth_providers
+            .retain(|existing| existing.name() != provider.name());
+        self.auth_providers.push(Arc::new(provider));
+    }
+
+    /// Register a response renderer; renderers registered later are tried first
+    pub fn register_response_renderer(&mut self, renderer: impl ResponseRenderer + 'static) {
+        self.response_renderers
+            .retain(|existing| existing.name() != renderer.name());
+        self.response_renderers.push(Arc::new(renderer));
+    }
+
+    pub fn body_serializers(&self) -> &[Arc<dyn BodySerializer>] {
+        &self.body_serializers
+    }
+
+    pub fn auth_providers(&self) -> &[Arc<dyn AuthProvider>] {
+        &self.auth_providers
+    }
+
+    pub fn body_serializer(&self, name: &str) -> Option<Arc<dyn BodySerializer>> {
+        self.body_serializers
+            .iter()
+            .find(|serializer| serializer.name() == name)
+            .cloned()
+    }
+
+    pub fn auth_provider(&self, name: &str) -> Option<Arc<dyn AuthProvider>> {
+        self.auth_providers
+            .iter()
+            .find(|provider| provider.name() == name)
+            .cloned()
+    }
+
+    /// Renderer for a `Content-Type` header value
+    pub fn response_renderer(&self, content_type: &str) -> Option<Arc<dyn ResponseRenderer>> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        self.response_renderers
+            .iter()
+            .rev()
+            .find(|renderer| renderer.can_render(&mime))
+            .cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ShoutRenderer;
+
+    impl ResponseRenderer for ShoutRenderer {
+        fn name(&self) -> &str {
+            "Shout"
+        }
+
+        fn can_render(&self, content_type: &str) -> bool {
+            content_type == "text/shout"
+        }
+
+        fn render(&self, body: &[u8]) -> Result<String, AppError> {
+            Ok(String::from_utf8_lossy(body).to_uppercase())
+        }
+    }
+
+    struct ApiKeyAuth;
+
+    impl AuthProvider for ApiKeyAuth {
+        fn name(&self) -> &str {
+            "API Key"
+        }
+
+        fn credential_hint(&self) -> &str {
+            "key"
+        }
+
+        fn apply(&self, request: &mut Request, credential: &str) -> Result<(), AppError> {
+            request.add_header("X-Api-Key", credential);
+            Ok(())
+        }
+    }
+
+    struct ShoutPlugin;
+
+    impl Plugin for ShoutPlugin {
+        fn name(&self) -> &str {
+            "shout"
+        }
+
+        fn register(&self, registry: &mut PluginRegistry) {
+            registry.register_response_renderer(ShoutRenderer);
+            registry.register_auth_provider(ApiKeyAuth);
+        }
+    }
+
+    #[test]
+    fn test_registry_with_plugins() {
+        let registry = PluginRegistry::with_plugins(&[Box::new(ShoutPlugin)]);
+        assert!(registry.body_serializer(builtin::JSON_SERIALIZER).is_some());
+        assert!(registry.auth_provider(builtin::BEARER_AUTH).is_some());
+
+        let renderer = registry
+            .response_renderer("Text/Shout; charset=utf-8")
+            .unwrap();
+        assert_eq!(renderer.render(b"hi").unwrap(), "HI");
+        assert!(registry.response_renderer("text/plain").is_none());
+
+        let mut request = Request::new("GET", "https://example.com");
+        registry
+            .auth_provider("API Key")
+            .unwrap()
+            .apply(&mut request, "abc")
+            .unwrap();
+        assert_eq!(
+            request.headers,
+            vec![("X-Api-Key".to_string(), "abc".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_register_replaces_same_name() {
+        let mut registry = PluginRegistry::new();
+        registry.register_auth_provider(ApiKeyAuth);
+        registry.register_auth_provider(ApiKeyAuth);
+        assert_eq!(registry.auth_providers().len(), 1);
+    }
+}
@@ -0,0 +1,311 @@
+//! Data shared by the views of the app.
+//!
+//! The history, scenarios, collections, environments and cookies of the
+//! active profile live in one [`AppState`]
+//! entity instead of being copied into every view that shows them. Changes
+//! go through its `update_*` methods, which emit a [`StateEvent`] so a view
+//! observing the state always renders the current data, and schedule an
+//! autosave that writes them once changes stop. Background tasks change it
+//! the same way, through the entity on the main thread, so their edits
+//! can't interleave.
+
+use crate::models::{
+    CollectionLibrary, CookieJar, EnvironmentLibrary, ProfileStorage, RequestHistory,
+    ScenarioLibrary,
+};
+use crate::storage::Autosave;
+use gpui::{Context, EventEmitter};
+
+/// Which part of the state changed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateEvent {
+    HistoryChanged,
+    ScenariosChanged,
+    CollectionsChanged,
+    EnvironmentsChanged,
+    CookiesChanged,
+}
+
+/// History, scenarios, collections, environments and cookies of the active
+/// profile
+pub struct AppState {
+    history: RequestHistory,
+    scenarios: ScenarioLibrary,
+    collections: CollectionLibrary,
+    environments: EnvironmentLibrary,
+    cookies: CookieJar,
+    // Where changes are saved - `None` while the data is locked or there is
+    // no data directory
+    storage: Option<ProfileStorage>,
+    // Parts changed since they were last written
+    unsaved_history: bool,
+    unsaved_scenarios: bool,
+    unsaved_collections: bool,
+    unsaved_environments: bool,
+    unsaved_cookies: bool,
+    autosave: Autosave,
+}
+
+impl EventEmitter<StateEvent> for AppState {}
+
+impl Default for AppState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl AppState {
+    /// Empty state that isn't saved, e.g. until encrypted data is unlocked
+    pub fn new() -> Self {
+        Self {
+            history: RequestHistory::new(),
+            scenarios: ScenarioLibrary::new(),
+            collections: CollectionLibrary::new(),
+            environments: EnvironmentLibrary::new(),
+            cookies: CookieJar::new(),
+            storage: None,
+            unsaved_history: false,
+            unsaved_scenarios: false,
+            unsaved_collections: false,
+            unsaved_environments: false,
+            unsaved_cookies: false,
+            autosave: Autosave::new(),
+        }
+    }
+
+    /// State of the profile saved in `storage`; data that can't be read
+    /// starts out empty
+    pub fn load(storage: Option<ProfileStorage>) -> Self {
+        let Some(storage) = storage else {
+            return Self::new();
+        };
+        let history = storage.load_history().unwrap_or_else(|e| {
+            tracing::info!("❌ AppState - 加载历史记录失败: {e}");
+            RequestHistory::new()
+        });
+        let scenarios = storage.load_scenarios().unwrap_or_else(|e| {
+            tracing::info!("❌ AppState - 加载场景失败: {e}");
+            ScenarioLibrary::new()
+        });
+        let collections = storage.load_collections().unwrap_or_else(|e| {
+            tracing::info!("❌ AppState - 加载集合失败: {e}");
+            CollectionLibrary::new()
+        });
+        let environments = storage.load_environments().unwrap_or_else(|e| {
+            tracing::info!("❌ AppState - 加载环境失败: {e}");
+            EnvironmentLibrary::new()
+        });
+        let cookies = storage.load_cookies().unwrap_or_else(|e| {
+            tracing::info!("❌ AppState - 加载 cookies 失败: {e}");
+            CookieJar::new()
+        });
+        Self {
+            history,
+            scenarios,
+            collections,
+            environments,
+            cookies,
+            storage: Some(storage),
+            ..Self::new()
+        }
+    }
+
+    /// Write the changes still waiting for the autosave when the app quits
+    pub fn flush_on_quit(&self, cx: &mut Context<Self>) {
+        cx.on_app_quit(|state, _cx| {
+            state.flush();
+            async {}
+        })
+        .detach();
+    }
+
+    pub fn history(&self) -> &RequestHistory {
+        &self.history
+    }
+
+    pub fn scenarios(&self) -> &ScenarioLibrary {
+        &self.scenarios
+    }
+
+    pub fn collections(&self) -> &CollectionLibrary {
+        &self.collections
+    }
+
+    pub fn environments(&self) -> &EnvironmentLibrary {
+        &self.environments
+    }
+
+    pub fn cookies(&self) -> &CookieJar {
+        &self.cookies
+    }
+
+    /// Change the history, then tell the views and schedule a save
+    pub fn update_history<R>(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut RequestHistory) -> R,
+    ) -> R {
+        let result = update(&mut self.history);
+        self.unsaved_history = true;
+        self.autosave.schedule(cx, |state, _cx| state.flush());
+        cx.emit(StateEvent::HistoryChanged);
+        cx.notify();
+        result
+    }
+
+    /// Change the scenarios, then tell the views and schedule a save
+    pub fn update_scenarios<R>(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut ScenarioLibrary) -> R,
+    ) -> R {
+        let result = update(&mut self.scenarios);
+        self.unsaved_scenarios = true;
+        self.autosave.schedule(cx, |state, _cx| state.flush());
+        cx.emit(StateEvent::ScenariosChanged);
+        cx.notify();
+        result
+    }
+
+    /// Change the collections, then tell the views and schedule a save
+    pub fn update_collections<R>(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut CollectionLibrary) -> R,
+    ) -> R {
+        let result = update(&mut self.collections);
+        self.unsaved_collections = true;
+        self.autosave.schedule(cx, |state, _cx| state.flush());
+        cx.emit(StateEvent::CollectionsChanged);
+        cx.notify();
+        result
+    }
+
+    /// Change the environments, then tell the views and schedule a save
+    pub fn update_environments<R>(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut EnvironmentLibrary) -> R,
+    ) -> R {
+        let result = update(&mut self.environments);
+        self.unsaved_environments = true;
+        self.autosave.schedule(cx, |state, _cx| state.flush());
+        cx.emit(StateEvent::EnvironmentsChanged);
+        cx.notify();
+        result
+    }
+
+    /// Change the cookies; only a change (`update` returning `true`) tells
+    /// the views and schedules a save, as most responses set none
+    pub fn update_cookies(
+        &mut self,
+        cx: &mut Context<Self>,
+        update: impl FnOnce(&mut CookieJar) -> bool,
+    ) -> bool {
+        let changed = update(&mut self.cookies);
+        if changed {
+            self.unsaved_cookies = true;
+            self.autosave.schedule(cx, |state, _cx| state.flush());
+            cx.emit(StateEvent::CookiesChanged);
+            cx.notify();
+        }
+        changed
+    }
+
+    /// Replace everything with the data saved in `storage`, e.g. after
+    /// unlocking or switching profiles. Unsaved changes are written to the
+    /// old storage first
+    pub fn reload(&mut self, storage: Option<ProfileStorage>, cx: &mut Context<Self>) {
+        self.flush();
+        let loaded = Self::load(storage);
+        self.history = loaded.history;
+        self.scenarios = loaded.scenarios;
+        self.collections = loaded.collections;
+        self.environments = loaded.environments;
+        self.cookies = loaded.cookies;
+        self.storage = loaded.storage;
+        cx.emit(StateEvent::HistoryChanged);
+        cx.emit(StateEvent::ScenariosChanged);
+        cx.emit(StateEvent::CollectionsChanged);
+        cx.emit(StateEvent::EnvironmentsChanged);
+        cx.emit(StateEvent::CookiesChanged);
+        cx.notify();
+    }
+
+    /// Save to `storage` from now on and write the current data there, e.g.
+    /// after encryption was turned on or off
+    pub fn set_storage(&mut self, storage: Option<ProfileStorage>) {
+        self.storage = storage;
+        self.unsaved_history = true;
+        self.unsaved_scenarios = true;
+        self.unsaved_collections = true;
+        self.unsaved_environments = true;
+        self.unsaved_cookies = true;
+        self.flush();
+    }
+
+    /// Write the changed parts now instead of waiting for the autosave
+    pub fn flush(&mut self) {
+        self.autosave.cancel();
+        if std::mem::take(&mut self.unsaved_history) {
+            self.save_history();
+        }
+        if std::mem::take(&mut self.unsaved_scenarios) {
+            self.save_scenarios();
+        }
+        if std::mem::take(&mut self.unsaved_collections) {
+            self.save_collections();
+        }
+        if std::mem::take(&mut self.unsaved_environments) {
+            self.save_environments();
+        }
+        if std::mem::take(&mut self.unsaved_cookies) {
+            self.save_cookies();
+        }
+    }
+
+    fn save_history(&self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        if let Err(e) = storage.save_history(&self.history) {
+            tracing::info!("❌ AppState - 保存历史记录失败: {e}");
+        }
+    }
+
+    fn save_scenarios(&self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        if let Err(e) = storage.save_scenarios(&self.scenarios) {
+            tracing::info!("❌ AppState - 保存场景失败: {e}");
+        }
+    }
+
+    fn save_collections(&self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        if let Err(e) = storage.save_collections(&self.collections) {
+            tracing::info!("❌ AppState - 保存集合失败: {e}");
+        }
+    }
+
+    fn save_environments(&self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        if let Err(e) = storage.save_environments(&self.environments) {
+            tracing::info!("❌ AppState - 保存环境失败: {e}");
+        }
+    }
+
+    fn save_cookies(&self) {
+        let Some(storage) = &self.storage else {
+            return;
+        };
+        if let Err(e) = storage.save_cookies(&self.cookies) {
+            tracing::info!("❌ AppState - 保存 cookies 失败: {e}");
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use gpui::{Context, Task};
+use std::time::Duration;
+
+/// How long changes have to stop before they are written
+pub const AUTOSAVE_DELAY: Duration = Duration::from_millis(500);
+
+/// Batches quick changes into one write: each change schedules the save
+/// again, so it runs once nothing changed for [`AUTOSAVE_DELAY`]
+#[derive(Default)]
+pub struct Autosave {
+    task: Option<Task<()>>,
+}
+
+impl Autosave {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Run `save` on the entity after the delay, replacing the save
+    /// scheduled before
+    pub fn schedule<T: 'static>(
+        &mut self,
+        cx: &mut Context<T>,
+        save: impl FnOnce(&mut T, &mut Context<T>) + 'static,
+    ) {
+        self.task = Some(cx.spawn(async move |this, cx| {
+            cx.background_executor().timer(AUTOSAVE_DELAY).await;
+            this.update(cx, save).ok();
+        }));
+    }
+
+    /// Drop the scheduled save, e.g. because the data was just written
+    pub fn cancel(&mut self) {
+        self.task = None;
+    }
+}
@@ -0,0 +1,194 @@
+//! Reading and writing the JSON files of the data directory.
+//!
+//! Every file is written as `{"schema_version": N, "data": ...}` (encrypted
+//! as a whole when a vault is given). When a file written with an older
+//! schema is read, its data goes through the migrations since that version
+//! first; files saved before versions were recorded hold the bare data and
+//! count as version 0.
+
+use crate::errors::AppError;
+use crate::models::Vault;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::io::Write;
+use std::path::Path;
+
+// Upgrades the data of a file by one version: `MIGRATIONS[n]` turns version
+// `n` into `n + 1`. It gets the file name, e.g. `history.json`, for changes
+// that only apply to some files
+type Migration = fn(&str, Value) -> Result<Value, AppError>;
+
+const MIGRATIONS: &[Migration] = &[wrap_in_version];
+
+/// Schema version of the files this build writes
+pub const SCHEMA_VERSION: u64 = MIGRATIONS.len() as u64;
+
+// 0 -> 1: the data is kept as it is and wrapped with its version
+fn wrap_in_version(_file: &str, data: Value) -> Result<Value, AppError> {
+    Ok(data)
+}
+
+// What a file holds
+#[derive(Serialize)]
+struct Versioned<'a, T: ?Sized> {
+    schema_version: u64,
+    data: &'a T,
+}
+
+/// Read a JSON file (decrypting it if needed) and migrate it to the current
+/// schema, `None` if it doesn't exist
+pub fn read_json<T: DeserializeOwned>(
+    path: &Path,
+    vault: Option<&Vault>,
+) -> Result<Option<T>, AppError> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut content = std::fs::read(path)?;
+    if Vault::is_encrypted(&content) {
+        let vault = vault.ok_or_else(|| {
+            AppError::FileError(format!(
+                "{} is encrypted and the vault is locked",
+                path.display()
+            ))
+        })?;
+        content = vault.decrypt(&content)?;
+    }
+    let parse_error =
+        |e: serde_json::Error| AppError::ParseError(format!("{}: {e}", path.display()));
+    let value: Value = serde_json::from_slice(&content).map_err(parse_error)?;
+    let (version, data) = split_version(value);
+    let file = path
+        .file_name()
+        .map(|name| name.to_string_lossy())
+        .unwrap_or_default();
+    let data = migrate(&file, version, data)?;
+    serde_json::from_value(data).map(Some).map_err(parse_error)
+}
+
+/// Write a JSON file with the current schema version (encrypted when a
+/// vault is given), creating its directory if needed
+pub fn write_json<T: Serialize + ?Sized>(
+    path: &Path,
+    value: &T,
+    vault: Option<&Vault>,
+) -> Result<(), AppError> {
+    let content = serde_json::to_vec_pretty(&Versioned {
+        schema_version: SCHEMA_VERSION,
+        data: value,
+    })
+    .map_err(|e| AppError::ParseError(e.to_string()))?;
+    let content = match vault {
+        Some(vault) => vault.encrypt(&content)?,
+        None => content,
+    };
+    write_atomic(path, &content)
+}
+
+/// Replace the file at `path` with `content` without ever leaving a partly
+/// written file: the content goes to a temporary file next to it, which is
+/// flushed to disk and then renamed over the old one
+pub fn write_atomic(path: &Path, content: &[u8]) -> Result<(), AppError> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut temp_name = path
+        .file_name()
+        .ok_or_else(|| AppError::FileError(format!("{} is not a file path", path.display())))?
+        .to_os_string();
+    temp_name.push(".tmp");
+    let temp = path.with_file_name(temp_name);
+
+    let result = std::fs::File::create(&temp)
+        .and_then(|mut file| {
+            file.write_all(content)?;
+            file.sync_all()
+        })
+        .and_then(|_| std::fs::rename(&temp, path));
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    result.map_err(AppError::from)
+}
+
+// (schema version, data) of a file's JSON
+fn split_version(value: Value) -> (u64, Value) {
+    match value {
+        Value::Object(mut map)
+            if map.len() == 2
+                && map.contains_key("data")
+                && map.get("schema_version").is_some_and(Value::is_u64) =>
+        {
+            let version = map["schema_version"].as_u64().unwrap_or_default();
+            (version, map.remove("data").unwrap_or_default())
+        }
+        value => (0, value),
+    }
+}
+
+fn migrate(file: &str, version: u64, mut data: Value) -> Result<Value, AppError> {
+    if version > SCHEMA_VERSION {
+        return Err(AppError::ParseError(format!(
+            "{file} was saved by a newer version of the app (schema {version}, this one reads up to {SCHEMA_VERSION})"
+        )));
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        data = migration(file, data)?;
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+    use std::collections::BTreeMap;
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = TempDir::new("storage-atomic");
+        let path = dir.join("settings.json");
+        write_atomic(&path, b"first").unwrap();
+        write_atomic(&path, b"second").unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), b"second");
+        // Only the file itself is left behind
+        let files: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(files.len(), 1);
+    }
+
+    #[test]
+    fn test_files_record_schema_version() {
+        let dir = TempDir::new("storage-versioned");
+        let path = dir.join("format_rules.json");
+        let value = BTreeMap::from([("script".to_string(), "indent 2".to_string())]);
+        write_json(&path, &value, None).unwrap();
+
+        let saved: Value = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(saved["schema_version"], SCHEMA_VERSION);
+        assert_eq!(saved["data"]["script"], "indent 2");
+        let restored: Option<BTreeMap<String, String>> = read_json(&path, None).unwrap();
+        assert_eq!(restored, Some(value));
+
+        let missing: Option<Value> = read_json(&dir.join("none.json"), None).unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn test_old_and_newer_files() {
+        let dir = TempDir::new("storage-migrate");
+        // Saved before versions were recorded
+        let path = dir.join("history.json");
+        write_atomic(&path, br#"{"entries": [], "next_id": 3}"#).unwrap();
+        let history: Option<Value> = read_json(&path, None).unwrap();
+        assert_eq!(history.unwrap()["next_id"], 3);
+
+        let future = format!(
+            r#"{{"schema_version": {}, "data": {{}}}}"#,
+            SCHEMA_VERSION + 1
+        );
+        write_atomic(&path, future.as_bytes()).unwrap();
+        let error = read_json::<Value>(&path, None).unwrap_err();
+        assert!(error.to_string().contains("newer version"));
+    }
+}
@@ -0,0 +1,12 @@
+//! Saving the files of the data directory.
+//!
+//! Writes go to a temporary file that is renamed over the old one, so a
+//! crash never leaves a half-written file behind; each file records the
+//! schema version it was written with and is migrated when read, and
+//! [`Autosave`] batches quick changes into a single write.
+
+pub mod autosave;
+pub mod files;
+
+pub use autosave::{Autosave, AUTOSAVE_DELAY};
+pub use files::{read_json, write_atomic, write_json, SCHEMA_VERSION};
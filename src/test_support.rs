@@ -0,0 +1,36 @@
+//! Helpers shared by the unit tests.
+
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+
+/// An empty directory under the system temp dir, removed again when it is
+/// dropped. `name` keeps tests running in parallel apart, so give each test
+/// its own (e.g. `profile-storage`).
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    pub fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("postman-gpui-{name}-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
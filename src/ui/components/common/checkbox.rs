@@ -0,0 +1,83 @@
+use gpui::{actions, div, px, rgb, Div, KeyBinding, ParentElement, Styled};
+
+// 切换当前行的启用状态（headers / form-data 表格）
+actions!(checkbox, [ToggleRow]);
+
+pub fn setup_checkbox_key_bindings() -> Vec<KeyBinding> {
+    vec![
+        KeyBinding::new("cmd-/", ToggleRow, None),
+        KeyBinding::new("ctrl-/", ToggleRow, None),
+    ]
+}
+
+/// State of a "select all" checkbox over a list of rows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MasterCheckState {
+    /// No rows at all
+    Empty,
+    AllEnabled,
+    SomeEnabled,
+    NoneEnabled,
+}
+
+impl MasterCheckState {
+    pub fn from_flags(flags: impl IntoIterator<Item = bool>) -> Self {
+        let (mut enabled, mut total) = (0, 0);
+        for flag in flags {
+            total += 1;
+            if flag {
+                enabled += 1;
+            }
+        }
+        match enabled {
+            _ if total == 0 => MasterCheckState::Empty,
+            0 => MasterCheckState::NoneEnabled,
+            _ if enabled == total => MasterCheckState::AllEnabled,
+            _ => MasterCheckState::SomeEnabled,
+        }
+    }
+
+    /// Value all rows are set to when the master checkbox is clicked
+    pub fn toggled_value(self) -> bool {
+        self != MasterCheckState::AllEnabled
+    }
+
+    /// Label for the batch action next to the checkbox
+    pub fn action_label(self) -> &'static str {
+        if self.toggled_value() {
+            "Enable all"
+        } else {
+            "Disable all"
+        }
+    }
+}
+
+/// Render a master checkbox; the caller adds the click handler
+pub fn master_checkbox(state: MasterCheckState) -> Div {
+    let checked = matches!(
+        state,
+        MasterCheckState::AllEnabled | MasterCheckState::SomeEnabled
+    );
+
+    div()
+        .w_4()
+        .h_4()
+        .flex()
+        .items_center()
+        .justify_center()
+        .border_1()
+        .border_color(rgb(0x00cc_cccc))
+        .rounded_sm()
+        .text_size(px(10.0))
+        .text_color(rgb(0x00ff_ffff))
+        .bg(if checked {
+            rgb(0x0000_7acc)
+        } else {
+            rgb(0x00ff_ffff)
+        })
+        .child(match state {
+            MasterCheckState::AllEnabled => "✓",
+            MasterCheckState::SomeEnabled => "–",
+            _ => "",
+        })
+}
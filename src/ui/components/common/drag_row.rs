@@ -0,0 +1,49 @@
+use gpui::{
+    div, px, rgb, Context, IntoElement, ParentElement, Render, SharedString, Styled, Window,
+};
+
+/// Which table a dragged row belongs to, so rows only drop into their own table
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RowTable {
+    Headers,
+    FormData,
+}
+
+/// Payload and drag preview for reordering table rows
+#[derive(Debug, Clone)]
+pub struct DraggedRow {
+    pub table: RowTable,
+    pub index: usize,
+    pub label: SharedString,
+}
+
+impl Render for DraggedRow {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .px_3()
+            .py_1()
+            .bg(rgb(0x00ff_ffff))
+            .border_1()
+            .border_color(rgb(0x0000_7acc))
+            .rounded_md()
+            .shadow_md()
+            .text_size(px(12.0))
+            .child(if self.label.is_empty() {
+                SharedString::from("(empty row)")
+            } else {
+                self.label.clone()
+            })
+    }
+}
+
+/// The "⠿" grip shown at the start of draggable rows
+pub fn drag_handle() -> gpui::Div {
+    div()
+        .w_4()
+        .flex()
+        .justify_center()
+        .text_size(px(12.0))
+        .text_color(rgb(0x00ad_b5bd))
+        .cursor_grab()
+        .child("⠿")
+}
@@ -50,6 +50,15 @@ impl Dropdown {
         self
     }
 
+    /// 替换选项列表；当前值不在新列表中时选中第一个选项（不发送事件）
+    pub fn set_options(&mut self, options: Vec<String>, cx: &mut Context<Self>) {
+        if !options.contains(&self.selected_value) {
+            self.selected_value = options.first().cloned().unwrap_or_default();
+        }
+        self.options = options;
+        cx.notify();
+    }
+
     pub fn selected_value(&self) -> &str {
         &self.selected_value
     }
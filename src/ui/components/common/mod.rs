@@ -1,2 +1,4 @@
 // src/ui/components/common/mod.rs
+pub mod checkbox;
+pub mod drag_row;
 pub mod dropdown;
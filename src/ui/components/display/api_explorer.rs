@@ -0,0 +1,497 @@
+use crate::models::{HttpMethod, Request};
+use crate::ui::components::body_input::{BodyInput, BodyType};
+use crate::ui::components::header_input::HeaderInput;
+use crate::ui::components::history_list::get_method_color;
+use crate::utils::openapi::{ApiSpec, Operation};
+use crate::utils::spec_drift::{check_response, DriftReport};
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, AppContext, Context, Entity, EventEmitter, FontWeight,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Window,
+};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Event emitted when an operation is tried out
+#[derive(Debug, Clone)]
+pub enum ApiExplorerEvent {
+    /// Send the request through the normal request flow
+    TryIt(Request),
+}
+
+/// Interactive docs for an imported OpenAPI spec - endpoints grouped by tag,
+/// a parameter form for the selected operation and a "Try it" button, plus
+/// a drift report comparing live responses with the documented schemas
+pub struct ApiExplorer {
+    spec: Option<ApiSpec>,
+    collapsed_tags: HashSet<String>,
+    selected: Option<usize>,
+    server_input: Entity<HeaderInput>,
+    // One input per parameter of the selected operation
+    parameter_inputs: Vec<Entity<HeaderInput>>,
+    body_input: Entity<BodyInput>,
+    error: Option<String>,
+    drift: DriftReport,
+}
+
+impl EventEmitter<ApiExplorerEvent> for ApiExplorer {}
+
+impl ApiExplorer {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            spec: None,
+            collapsed_tags: HashSet::new(),
+            selected: None,
+            server_input: cx.new(|cx| {
+                HeaderInput::new(cx).with_placeholder("Server URL, e.g. https://api.example.com")
+            }),
+            parameter_inputs: Vec::new(),
+            body_input: cx.new(|cx| {
+                let mut input = BodyInput::new(cx);
+                input.set_type(BodyType::Json, cx);
+                input
+            }),
+            error: None,
+            drift: DriftReport::new(),
+        }
+    }
+
+    pub fn set_spec(&mut self, spec: Option<ApiSpec>, cx: &mut Context<Self>) {
+        let server = spec
+            .as_ref()
+            .and_then(|spec| spec.servers.first().cloned())
+            .unwrap_or_default();
+        self.server_input
+            .update(cx, |input, cx| input.set_content(server, cx));
+        self.spec = spec;
+        self.collapsed_tags.clear();
+        self.selected = None;
+        self.parameter_inputs.clear();
+        self.error = None;
+        self.drift.clear();
+        cx.notify();
+    }
+
+    /// Compare a response with the spec when the request matches one of its
+    /// operations; returns the number of differences found in it
+    pub fn check_response(
+        &mut self,
+        method: HttpMethod,
+        url: &str,
+        status: u16,
+        body: &str,
+        cx: &mut Context<Self>,
+    ) -> usize {
+        let Some(spec) = &self.spec else {
+            return 0;
+        };
+        let Some(operation) = spec.find_operation(method, url) else {
+            return 0;
+        };
+        let drifts = check_response(spec, operation, status, body);
+        let found = drifts.len();
+        tracing::info!(
+            "🧭 ApiExplorer - 对比 spec: {} -> {found} 处差异",
+            operation.endpoint()
+        );
+        self.drift.record(&operation.endpoint(), drifts);
+        cx.notify();
+        found
+    }
+
+    pub fn spec(&self) -> Option<&ApiSpec> {
+        self.spec.as_ref()
+    }
+
+    /// Show the form for operation `index`, pre-filled with parameter
+    /// defaults and an example body
+    pub fn select(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(operation) = self
+            .spec
+            .as_ref()
+            .and_then(|spec| spec.operations.get(index))
+        else {
+            return;
+        };
+        self.parameter_inputs = operation
+            .parameters
+            .iter()
+            .map(|parameter| {
+                let placeholder = format!(
+                    "{} ({}{})",
+                    parameter.name,
+                    parameter.type_label(),
+                    if parameter.required { ", required" } else { "" }
+                );
+                let default = parameter
+                    .schema
+                    .get("default")
+                    .map(value_text)
+                    .unwrap_or_default();
+                cx.new(|cx| {
+                    let mut input = HeaderInput::new(cx).with_placeholder(placeholder);
+                    input.set_content(default, cx);
+                    input
+                })
+            })
+            .collect();
+
+        let body = match (&self.spec, &operation.request_body) {
+            (Some(spec), Some(body)) => {
+                serde_json::to_string_pretty(&spec.example(&body.schema)).unwrap_or_default()
+            }
+            _ => String::new(),
+        };
+        self.body_input
+            .update(cx, |input, cx| input.set_content(body, cx));
+        self.selected = Some(index);
+        self.error = None;
+        cx.notify();
+    }
+
+    fn toggle_tag(&mut self, tag: &str, cx: &mut Context<Self>) {
+        if !self.collapsed_tags.remove(tag) {
+            self.collapsed_tags.insert(tag.to_string());
+        }
+        cx.notify();
+    }
+
+    fn selected_operation(&self) -> Option<&Operation> {
+        self.spec.as_ref()?.operations.get(self.selected?)
+    }
+
+    fn try_it(&mut self, cx: &mut Context<Self>) {
+        let Some(operation) = self.selected_operation() else {
+            return;
+        };
+        let values: Vec<String> = self
+            .parameter_inputs
+            .iter()
+            .map(|input| input.read(cx).get_content().to_string())
+            .collect();
+        let body = operation
+            .request_body
+            .as_ref()
+            .map(|_| self.body_input.read(cx).get_content());
+        let server = self.server_input.read(cx).get_content().to_string();
+
+        match operation.build_request(&server, &values, body.as_deref()) {
+            Ok(request) => {
+                tracing::info!("🧭 ApiExplorer - 试用 {} {}", request.method, request.url);
+                self.error = None;
+                cx.emit(ApiExplorerEvent::TryIt(request));
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn render_operation_row(
+        &self,
+        index: usize,
+        operation: &Operation,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_selected = self.selected == Some(index);
+        div()
+            .id(("api-operation", index))
+            .flex()
+            .items_center()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_selected, |row| row.bg(rgb(0x00e7_f1ff)))
+            .hover(|style| style.bg(rgb(0x00f0_f0f0)))
+            .child(
+                div()
+                    .w_16()
+                    .font_weight(FontWeight::BOLD)
+                    .text_color(get_method_color(operation.method))
+                    .child(operation.method.to_string()),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .font_family("monospace")
+                    .when(operation.deprecated, |path| {
+                        path.line_through().text_color(rgb(0x006c_757d))
+                    })
+                    .child(operation.path.clone()),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| this.select(index, cx)),
+            )
+    }
+
+    fn render_operation_form(
+        &self,
+        operation: &Operation,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .pt_2()
+            .border_t_1()
+            .border_color(rgb(0x00e9_ecef))
+            .child(div().font_weight(FontWeight::MEDIUM).child(format!(
+                "{} {}",
+                operation.method,
+                operation.title()
+            )))
+            .children(operation.description.clone().map(|description| {
+                div()
+                    .text_size(px(11.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child(description)
+            }))
+            .children(operation.parameters.iter().zip(&self.parameter_inputs).map(
+                |(parameter, input)| {
+                    div()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .child(
+                            div()
+                                .flex()
+                                .gap_2()
+                                .child(format!(
+                                    "{}{}",
+                                    parameter.name,
+                                    if parameter.required { " *" } else { "" }
+                                ))
+                                .child(
+                                    div()
+                                        .text_color(rgb(0x006c_757d))
+                                        .child(parameter.location.as_str()),
+                                ),
+                        )
+                        .child(
+                            div()
+                                .border_1()
+                                .border_color(rgb(0x00cc_cccc))
+                                .rounded_md()
+                                .child(input.clone()),
+                        )
+                        .children(parameter.description.clone().map(|description| {
+                            div()
+                                .text_size(px(11.0))
+                                .text_color(rgb(0x006c_757d))
+                                .child(description)
+                        }))
+                },
+            ))
+            .children(operation.request_body.as_ref().map(|body| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .gap_1()
+                    .child(format!(
+                        "Body ({}){}",
+                        body.content_type,
+                        if body.required { " *" } else { "" }
+                    ))
+                    .child(self.body_input.clone())
+            }))
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| div().text_color(rgb(0x00dc_3545)).child(error)),
+            )
+            .child(
+                div()
+                    .id("api-try-it")
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x0000_7acc))
+                    .text_color(rgb(0x00ff_ffff))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x0000_56b3)))
+                    .child("Try it")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.try_it(cx)),
+                    ),
+            )
+    }
+
+    // Checked endpoints with their undocumented fields, type mismatches and statuses
+    fn render_drift_report(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .pt_2()
+            .border_t_1()
+            .border_color(rgb(0x00e9_ecef))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(format!("Drift report ({})", self.drift.drift_count())),
+                    )
+                    .child(div().flex_1())
+                    .child(
+                        div()
+                            .id("api-drift-clear")
+                            .text_color(rgb(0x006c_757d))
+                            .cursor_pointer()
+                            .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                            .child("Clear")
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.drift.clear();
+                                    cx.notify();
+                                }),
+                            ),
+                    ),
+            )
+            .children(self.drift.endpoints().map(|(endpoint, drift)| {
+                let checks = match drift.checks {
+                    1 => "1 response".to_string(),
+                    checks => format!("{checks} responses"),
+                };
+                div()
+                    .flex()
+                    .flex_col()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_2()
+                            .child(div().font_family("monospace").child(endpoint.clone()))
+                            .child(div().text_color(rgb(0x006c_757d)).child(checks)),
+                    )
+                    .when(drift.drifts.is_empty(), |entry| {
+                        entry.child(
+                            div()
+                                .pl_2()
+                                .text_color(rgb(0x0028_a745))
+                                .child("Matches the spec"),
+                        )
+                    })
+                    .children(drift.drifts.iter().map(|(difference, count)| {
+                        div()
+                            .pl_2()
+                            .text_color(rgb(0x00fd_7e14))
+                            .child(format!("⚠ {} ({count}×)", difference.describe()))
+                    }))
+            }))
+    }
+}
+
+/// Text for a parameter input: strings without quotes, anything else as JSON
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+impl Render for ApiExplorer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let Some(spec) = &self.spec else {
+            return div()
+                .text_size(px(12.0))
+                .text_color(rgb(0x006c_757d))
+                .child("Import an OpenAPI spec (JSON or YAML) to browse its endpoints");
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(spec.title.clone()),
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(0x006c_757d))
+                            .child(spec.version.clone()),
+                    ),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(div().text_color(rgb(0x006c_757d)).child("Server"))
+                    .child(
+                        div()
+                            .flex_1()
+                            .border_1()
+                            .border_color(rgb(0x00cc_cccc))
+                            .rounded_md()
+                            .child(self.server_input.clone()),
+                    ),
+            )
+            .child(
+                div()
+                    .id("api-operations")
+                    .flex()
+                    .flex_col()
+                    .max_h(px(360.0))
+                    .overflow_scroll()
+                    .children(spec.tags().into_iter().enumerate().map(
+                        |(tag_index, (tag, indices))| {
+                            let collapsed = self.collapsed_tags.contains(&tag);
+                            let label = format!(
+                                "{} {tag} ({})",
+                                if collapsed { "▸" } else { "▾" },
+                                indices.len()
+                            );
+                            div()
+                                .flex()
+                                .flex_col()
+                                .child(
+                                    div()
+                                        .id(("api-tag", tag_index))
+                                        .py_1()
+                                        .font_weight(FontWeight::MEDIUM)
+                                        .cursor_pointer()
+                                        .child(label)
+                                        .on_mouse_up(
+                                            MouseButton::Left,
+                                            cx.listener(move |this, _event, _window, cx| {
+                                                this.toggle_tag(&tag, cx)
+                                            }),
+                                        ),
+                                )
+                                .when(!collapsed, |group| {
+                                    group.children(indices.into_iter().map(|index| {
+                                        self.render_operation_row(
+                                            index,
+                                            &spec.operations[index],
+                                            cx,
+                                        )
+                                    }))
+                                })
+                        },
+                    )),
+            )
+            .children(
+                self.selected_operation()
+                    .map(|operation| self.render_operation_form(operation, cx)),
+            )
+            .when(!self.drift.is_empty(), |explorer| {
+                explorer.child(self.render_drift_report(cx))
+            })
+    }
+}
@@ -0,0 +1,231 @@
+use crate::http::client::client_identity;
+use crate::models::{CertificateStore, ClientCertificate};
+use crate::ui::components::header_input::HeaderInput;
+use gpui::{
+    div, px, rgb, AppContext, Context, Entity, EventEmitter, FontWeight, InteractiveElement,
+    IntoElement, MouseButton, ParentElement, Render, StatefulInteractiveElement, Styled, Window,
+};
+use std::path::Path;
+
+/// Event emitted when certificates are imported or removed
+#[derive(Debug, Clone)]
+pub enum CertificatesPageEvent {
+    /// The store changed - it should be saved and the selectors refreshed.
+    /// Carries a short description such as `Imported Staging`
+    Changed(String),
+}
+
+/// Imports client certificates once under a name, so requests and domains
+/// can pick them from a dropdown instead of pointing at files each time
+pub struct CertificatesPage {
+    store: CertificateStore,
+    name_input: Entity<HeaderInput>,
+    path_input: Entity<HeaderInput>,
+    key_path_input: Entity<HeaderInput>,
+    password_input: Entity<HeaderInput>,
+    error: Option<String>,
+}
+
+impl EventEmitter<CertificatesPageEvent> for CertificatesPage {}
+
+impl CertificatesPage {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            store: CertificateStore::new(),
+            name_input: cx.new(|cx| HeaderInput::new(cx).with_placeholder("Name, e.g. Staging")),
+            path_input: cx.new(|cx| {
+                HeaderInput::new(cx).with_placeholder("Certificate (.p12 / .pfx / .pem)")
+            }),
+            key_path_input: cx.new(|cx| {
+                HeaderInput::new(cx)
+                    .with_placeholder("Private key (.pem, if not in the certificate)")
+            }),
+            password_input: cx.new(|cx| {
+                HeaderInput::new(cx)
+                    .masked()
+                    .with_placeholder("PKCS#12 password")
+            }),
+            error: None,
+        }
+    }
+
+    pub fn store(&self) -> &CertificateStore {
+        &self.store
+    }
+
+    /// Show the certificates of another profile
+    pub fn set_store(&mut self, store: CertificateStore, cx: &mut Context<Self>) {
+        self.store = store;
+        self.error = None;
+        cx.notify();
+    }
+
+    fn import(&mut self, cx: &mut Context<Self>) {
+        let name = self.name_input.read(cx).get_content().to_string();
+        let path = self.path_input.read(cx).get_content().trim().to_string();
+        let key_path = self
+            .key_path_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        let password = self.password_input.read(cx).get_content().to_string();
+        if path.is_empty() {
+            self.error = Some("Enter the path of the certificate".to_string());
+            cx.notify();
+            return;
+        }
+
+        let key_path = (!key_path.is_empty()).then(|| Path::new(&key_path));
+        let result = ClientCertificate::import(&name, Path::new(&path), key_path, &password)
+            .and_then(|certificate| {
+                // Check the certificate can be loaded before keeping it
+                client_identity(&certificate)?;
+                self.store.add(certificate)
+            });
+        match result {
+            Ok(index) => {
+                tracing::info!(
+                    "🪪 CertificatesPage - 已导入客户端证书: {}",
+                    self.store.certificates()[index].name
+                );
+                let name = self.store.certificates()[index].name.clone();
+                for input in [
+                    &self.name_input,
+                    &self.path_input,
+                    &self.key_path_input,
+                    &self.password_input,
+                ] {
+                    input.update(cx, |input, cx| input.clear(cx));
+                }
+                self.error = None;
+                cx.emit(CertificatesPageEvent::Changed(format!("Imported {name}")));
+            }
+            Err(e) => {
+                tracing::info!("❌ CertificatesPage - 导入证书失败: {e}");
+                self.error = Some(e.to_string());
+            }
+        }
+        cx.notify();
+    }
+
+    fn remove(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(certificate) = self.store.remove(index) {
+            tracing::info!(
+                "🪪 CertificatesPage - 已删除客户端证书: {}",
+                certificate.name
+            );
+            cx.emit(CertificatesPageEvent::Changed(format!(
+                "Removed {}",
+                certificate.name
+            )));
+            cx.notify();
+        }
+    }
+
+    fn render_certificate_row(
+        &self,
+        index: usize,
+        certificate: &ClientCertificate,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .child(
+                div()
+                    .flex()
+                    .flex_col()
+                    .flex_1()
+                    .child(
+                        div()
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(certificate.name.clone()),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(11.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!(
+                                "{} · {}",
+                                certificate.format_label(),
+                                certificate.source
+                            )),
+                    ),
+            )
+            .child(
+                div()
+                    .id(("certificate-remove", index))
+                    .text_color(rgb(0x006c_757d))
+                    .cursor_pointer()
+                    .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                    .child("✕")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| this.remove(index, cx)),
+                    ),
+            )
+    }
+}
+
+impl Render for CertificatesPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let field = |input: &Entity<HeaderInput>| {
+            div()
+                .border_1()
+                .border_color(rgb(0x00cc_cccc))
+                .rounded_md()
+                .child(input.clone())
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(
+                div()
+                    .text_size(px(16.0))
+                    .font_weight(FontWeight::MEDIUM)
+                    .child("Client certificates"),
+            )
+            .child(div().text_color(rgb(0x006c_757d)).child(
+                "Certificates are copied into the profile (encrypted when the vault is on) and can then be chosen per request or per domain.",
+            ))
+            .child(field(&self.name_input))
+            .child(field(&self.path_input))
+            .child(field(&self.key_path_input))
+            .child(field(&self.password_input))
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| div().text_color(rgb(0x00dc_3545)).child(error)),
+            )
+            .child(
+                div()
+                    .id("certificate-import")
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x006c_757d))
+                    .text_color(rgb(0x00ff_ffff))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x005a_6268)))
+                    .child("Import")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.import(cx)),
+                    ),
+            )
+            .children(
+                self.store
+                    .certificates()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, certificate)| self.render_certificate_row(index, certificate, cx)),
+            )
+    }
+}
@@ -0,0 +1,252 @@
+use crate::utils::csv::{page_count, CsvTable};
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, Context, FontWeight, InteractiveElement, IntoElement,
+    MouseButton, ParentElement, Render, StatefulInteractiveElement, Styled, Window,
+};
+
+/// Rows shown per page
+const PAGE_SIZE: usize = 50;
+
+/// Width of a grid cell
+const CELL_WIDTH: f32 = 160.0;
+
+/// CSV response grid - paginated, sortable by clicking a column header
+pub struct CsvGrid {
+    table: CsvTable,
+    // Row indices in display order
+    order: Vec<usize>,
+    // (column, ascending)
+    sort: Option<(usize, bool)>,
+    page: usize,
+    // Result of the last export
+    status: Option<String>,
+}
+
+impl CsvGrid {
+    pub fn new(_cx: &mut Context<Self>) -> Self {
+        Self {
+            table: CsvTable::default(),
+            order: Vec::new(),
+            sort: None,
+            page: 0,
+            status: None,
+        }
+    }
+
+    pub fn set_body(&mut self, body: &str, cx: &mut Context<Self>) {
+        self.table = CsvTable::parse(body);
+        self.order = (0..self.table.rows.len()).collect();
+        self.sort = None;
+        self.page = 0;
+        self.status = None;
+        tracing::info!(
+            "📊 CsvGrid - 解析 CSV: {} 列, {} 行",
+            self.table.column_count(),
+            self.table.rows.len()
+        );
+        cx.notify();
+    }
+
+    // 点击列头: 升序 -> 降序 -> 原始顺序
+    fn toggle_sort(&mut self, column: usize, cx: &mut Context<Self>) {
+        self.sort = match self.sort {
+            Some((current, true)) if current == column => Some((column, false)),
+            Some((current, false)) if current == column => None,
+            _ => Some((column, true)),
+        };
+        self.order = match self.sort {
+            Some((column, ascending)) => self.table.sort_order(column, ascending),
+            None => (0..self.table.rows.len()).collect(),
+        };
+        self.page = 0;
+        cx.notify();
+    }
+
+    fn set_page(&mut self, page: usize, cx: &mut Context<Self>) {
+        self.page = page.min(page_count(self.order.len(), PAGE_SIZE) - 1);
+        cx.notify();
+    }
+
+    // 按当前排序导出到下载目录
+    fn export(&mut self, cx: &mut Context<Self>) {
+        let dir = dirs::download_dir().unwrap_or_else(std::env::temp_dir);
+        let name = format!(
+            "response-{}.csv",
+            chrono::Local::now().format("%Y%m%d-%H%M%S")
+        );
+        let path = dir.join(name);
+
+        match std::fs::write(&path, self.table.to_csv(&self.order)) {
+            Ok(()) => {
+                tracing::info!("📤 CsvGrid - CSV 已导出: {}", path.display());
+                self.status = Some(format!("Exported to {}", path.display()));
+            }
+            Err(e) => {
+                tracing::info!("❌ CsvGrid - 导出 CSV 失败: {e}");
+                self.status = Some(format!("Could not export CSV: {e}"));
+            }
+        }
+        cx.notify();
+    }
+
+    fn render_button(
+        &self,
+        label: &'static str,
+        enabled: bool,
+        cx: &mut Context<Self>,
+        on_click: impl Fn(&mut Self, &mut Context<Self>) + 'static,
+    ) -> impl IntoElement {
+        div()
+            .px_2()
+            .py_1()
+            .bg(rgb(if enabled { 0x006c_757d } else { 0x00ad_b5bd }))
+            .text_color(rgb(0x00ff_ffff))
+            .rounded_md()
+            .text_size(px(12.0))
+            .child(label)
+            .when(enabled, |button| {
+                button
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x005a_6268)))
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| on_click(this, cx)),
+                    )
+            })
+    }
+
+    fn render_header_cell(&self, column: usize, cx: &mut Context<Self>) -> impl IntoElement {
+        let arrow = match self.sort {
+            Some((current, true)) if current == column => " ▲",
+            Some((current, false)) if current == column => " ▼",
+            _ => "",
+        };
+        div()
+            .id(("csv-header", column))
+            .w(px(CELL_WIDTH))
+            .flex_none()
+            .px_2()
+            .py_1()
+            .overflow_hidden()
+            .font_weight(FontWeight::MEDIUM)
+            .bg(rgb(0x00f8_f9fa))
+            .border_r_1()
+            .border_color(rgb(0x00e9_ecef))
+            .cursor_pointer()
+            .hover(|style| style.bg(rgb(0x00e9_ecef)))
+            .child(format!("{}{arrow}", self.table.headers[column]))
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    this.toggle_sort(column, cx);
+                }),
+            )
+    }
+}
+
+impl Render for CsvGrid {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let pages = page_count(self.order.len(), PAGE_SIZE);
+        let page = self.page;
+        let start = page * PAGE_SIZE;
+        let end = (start + PAGE_SIZE).min(self.order.len());
+        let rows: Vec<Vec<String>> = self.order[start..end]
+            .iter()
+            .map(|index| self.table.rows[*index].clone())
+            .collect();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child("CSV"),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!(
+                                "{} rows | {} columns",
+                                self.table.rows.len(),
+                                self.table.column_count()
+                            )),
+                    )
+                    .child(div().flex_1())
+                    .child(self.render_button("Prev", page > 0, cx, move |this, cx| {
+                        this.set_page(page.saturating_sub(1), cx);
+                    }))
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!("Page {} of {pages}", page + 1)),
+                    )
+                    .child(
+                        self.render_button("Next", page + 1 < pages, cx, move |this, cx| {
+                            this.set_page(page + 1, cx);
+                        }),
+                    )
+                    .child(self.render_button("Export CSV", true, cx, |this, cx| {
+                        this.export(cx);
+                    })),
+            )
+            .when_some(self.status.clone(), |grid, status| {
+                grid.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child(status),
+                )
+            })
+            .child(
+                div()
+                    .id("csv-grid")
+                    .flex()
+                    .flex_col()
+                    .text_size(px(12.0))
+                    .border_1()
+                    .border_color(rgb(0x00cc_cccc))
+                    .rounded_md()
+                    .overflow_scroll()
+                    .child(
+                        div()
+                            .flex()
+                            .border_b_1()
+                            .border_color(rgb(0x00cc_cccc))
+                            .children(
+                                (0..self.table.column_count())
+                                    .map(|column| self.render_header_cell(column, cx)),
+                            ),
+                    )
+                    .when(rows.is_empty(), |grid| {
+                        grid.child(div().p_2().text_color(rgb(0x006c_757d)).child("No rows"))
+                    })
+                    .children(rows.into_iter().map(|row| {
+                        div()
+                            .flex()
+                            .border_b_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .children(row.into_iter().map(|cell| {
+                                div()
+                                    .w(px(CELL_WIDTH))
+                                    .flex_none()
+                                    .px_2()
+                                    .py_1()
+                                    .overflow_hidden()
+                                    .border_r_1()
+                                    .border_color(rgb(0x00e9_ecef))
+                                    .child(cell)
+                            }))
+                    })),
+            )
+    }
+}
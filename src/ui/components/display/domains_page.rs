@@ -0,0 +1,489 @@
+use crate::http::size::parse_size;
+use crate::models::{DomainAuth, DomainDefaults, DomainSettings, TransportOptions};
+use crate::plugins::PluginRegistry;
+use crate::ui::components::body_input::{BodyInput, BodyType};
+use crate::ui::components::dropdown::Dropdown;
+use crate::ui::components::header_input::HeaderInput;
+use crate::utils::formatter::format_size;
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, AppContext, Context, Entity, EventEmitter, FontWeight,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Window,
+};
+
+/// Event emitted when the per-domain defaults change
+#[derive(Debug, Clone)]
+pub enum DomainsPageEvent {
+    /// A domain was added, edited or removed - the settings should be saved.
+    /// Carries a short description such as `Added api.example.com`
+    Changed(String),
+}
+
+/// Certificate choice meaning "don't present a client certificate"
+const NO_CERTIFICATE: &str = "No client certificate";
+
+/// Settings page for defaults applied to every request targeting a host:
+/// headers, an auth provider credential, a proxy, TLS verification and a
+/// client certificate
+pub struct DomainsPage {
+    settings: DomainSettings,
+    selected: Option<usize>,
+    host_input: Entity<HeaderInput>,
+    // Form for the selected domain
+    headers_input: Entity<BodyInput>,
+    auth_provider: Option<String>,
+    credential_input: Entity<HeaderInput>,
+    proxy_input: Entity<HeaderInput>,
+    size_limit_input: Entity<HeaderInput>,
+    accept_invalid_certs: bool,
+    certificate_selector: Entity<Dropdown>,
+    error: Option<String>,
+}
+
+impl EventEmitter<DomainsPageEvent> for DomainsPage {}
+
+impl DomainsPage {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            settings: DomainSettings::new(),
+            selected: None,
+            host_input: cx.new(|cx| {
+                HeaderInput::new(cx).with_placeholder("api.example.com or *.example.com")
+            }),
+            headers_input: cx.new(|cx| {
+                let mut input = BodyInput::new(cx);
+                input.set_type(BodyType::Raw, cx);
+                input
+            }),
+            auth_provider: None,
+            credential_input: cx
+                .new(|cx| HeaderInput::new(cx).masked().with_placeholder("Credential")),
+            proxy_input: cx.new(|cx| {
+                HeaderInput::new(cx).with_placeholder("Proxy, e.g. http://proxy.internal:3128")
+            }),
+            size_limit_input: cx.new(|cx| {
+                HeaderInput::new(cx).with_placeholder("Max request size, e.g. 1 MB (default 10 MB)")
+            }),
+            accept_invalid_certs: false,
+            certificate_selector: cx.new(|cx| {
+                Dropdown::new("domain-certificate", cx)
+                    .with_options(vec![NO_CERTIFICATE.to_string()])
+            }),
+            error: None,
+        }
+    }
+
+    pub fn settings(&self) -> &DomainSettings {
+        &self.settings
+    }
+
+    /// Show the defaults of another profile
+    pub fn set_settings(&mut self, settings: DomainSettings, cx: &mut Context<Self>) {
+        self.settings = settings;
+        self.selected = None;
+        self.error = None;
+        cx.notify();
+    }
+
+    /// Names of the imported client certificates to choose from
+    pub fn set_certificate_names(&mut self, names: Vec<String>, cx: &mut Context<Self>) {
+        let options = std::iter::once(NO_CERTIFICATE.to_string())
+            .chain(names)
+            .collect();
+        self.certificate_selector
+            .update(cx, |selector, cx| selector.set_options(options, cx));
+    }
+
+    fn add_domain(&mut self, cx: &mut Context<Self>) {
+        let host = self.host_input.read(cx).get_content().to_string();
+        match self.settings.add(&host) {
+            Ok(index) => {
+                tracing::info!(
+                    "🌐 DomainsPage - 已添加域名: {}",
+                    self.settings.domains()[index].host
+                );
+                let host = self.settings.domains()[index].host.clone();
+                self.host_input.update(cx, |input, cx| input.clear(cx));
+                self.select(index, cx);
+                cx.emit(DomainsPageEvent::Changed(format!("Added {host}")));
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn remove_domain(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(domain) = self.settings.remove(index) {
+            tracing::info!("🌐 DomainsPage - 已删除域名: {}", domain.host);
+            self.selected = match self.selected {
+                Some(selected) if selected == index => None,
+                Some(selected) if selected > index => Some(selected - 1),
+                selected => selected,
+            };
+            cx.emit(DomainsPageEvent::Changed(format!(
+                "Removed {}",
+                domain.host
+            )));
+            cx.notify();
+        }
+    }
+
+    /// Fill the form with the defaults of domain `index`
+    fn select(&mut self, index: usize, cx: &mut Context<Self>) {
+        let Some(domain) = self.settings.get(index).cloned() else {
+            return;
+        };
+        self.headers_input
+            .update(cx, |input, cx| input.set_content(domain.headers_text(), cx));
+        let (provider, credential) = match domain.auth {
+            Some(auth) => (Some(auth.provider), auth.credential),
+            None => (None, String::new()),
+        };
+        self.auth_provider = provider;
+        self.credential_input
+            .update(cx, |input, cx| input.set_content(credential, cx));
+        self.proxy_input.update(cx, |input, cx| {
+            input.set_content(domain.transport.proxy.unwrap_or_default(), cx)
+        });
+        self.size_limit_input.update(cx, |input, cx| {
+            // Rounded sizes would change the limit when saved again
+            let limit = domain.max_request_size.map(|limit| {
+                let text = format_size(limit);
+                if parse_size(&text).ok() == Some(limit) {
+                    text
+                } else {
+                    limit.to_string()
+                }
+            });
+            input.set_content(limit.unwrap_or_default(), cx)
+        });
+        self.accept_invalid_certs = domain.transport.accept_invalid_certs;
+        let certificate = domain
+            .transport
+            .client_certificate
+            .unwrap_or_else(|| NO_CERTIFICATE.to_string());
+        self.certificate_selector.update(cx, |selector, cx| {
+            selector.set_selected(certificate.clone(), cx);
+            if selector.selected_value() != certificate {
+                selector.set_selected(NO_CERTIFICATE, cx);
+            }
+        });
+        self.selected = Some(index);
+        self.error = None;
+        cx.notify();
+    }
+
+    fn save_selected(&mut self, cx: &mut Context<Self>) {
+        let Some(index) = self.selected else {
+            return;
+        };
+        let mut defaults = DomainDefaults::new(String::new());
+        defaults.set_headers_text(&self.headers_input.read(cx).get_content());
+        defaults.auth = self.auth_provider.clone().map(|provider| DomainAuth {
+            provider,
+            credential: self.credential_input.read(cx).get_content().to_string(),
+        });
+        let size_limit = self
+            .size_limit_input
+            .read(cx)
+            .get_content()
+            .trim()
+            .to_string();
+        defaults.max_request_size = if size_limit.is_empty() {
+            None
+        } else {
+            match parse_size(&size_limit) {
+                Ok(limit) => Some(limit),
+                Err(e) => {
+                    self.error = Some(e.to_string());
+                    cx.notify();
+                    return;
+                }
+            }
+        };
+        let proxy = self.proxy_input.read(cx).get_content().trim().to_string();
+        let certificate = self
+            .certificate_selector
+            .read(cx)
+            .selected_value()
+            .to_string();
+        defaults.transport = TransportOptions {
+            proxy: (!proxy.is_empty()).then_some(proxy),
+            accept_invalid_certs: self.accept_invalid_certs,
+            client_certificate: (certificate != NO_CERTIFICATE).then_some(certificate),
+        };
+        if self.settings.update(index, defaults) {
+            tracing::info!("🌐 DomainsPage - 已保存域名默认值");
+            self.error = None;
+            let host = self.settings.domains()[index].host.clone();
+            cx.emit(DomainsPageEvent::Changed(format!("Updated {host}")));
+        }
+        cx.notify();
+    }
+
+    fn render_domain_row(
+        &self,
+        index: usize,
+        domain: &DomainDefaults,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let is_selected = self.selected == Some(index);
+        let mut summary = Vec::new();
+        if !domain.headers.is_empty() {
+            summary.push(format!("{} headers", domain.headers.len()));
+        }
+        if let Some(auth) = &domain.auth {
+            summary.push(auth.provider.clone());
+        }
+        if domain.transport.proxy.is_some() {
+            summary.push("proxy".to_string());
+        }
+        if domain.transport.accept_invalid_certs {
+            summary.push("insecure TLS".to_string());
+        }
+        if let Some(certificate) = &domain.transport.client_certificate {
+            summary.push(certificate.clone());
+        }
+        if let Some(limit) = domain.max_request_size {
+            summary.push(format!("≤ {}", format_size(limit)));
+        }
+
+        div()
+            .id(("domain-row", index))
+            .flex()
+            .items_center()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .when(is_selected, |row| row.bg(rgb(0x00e7_f1ff)))
+            .hover(|style| style.bg(rgb(0x00f0_f0f0)))
+            .child(
+                div()
+                    .flex_1()
+                    .font_family("monospace")
+                    .child(domain.host.clone()),
+            )
+            .child(div().text_color(rgb(0x006c_757d)).child(summary.join(", ")))
+            .child(
+                div()
+                    .id(("domain-remove", index))
+                    .text_color(rgb(0x006c_757d))
+                    .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                    .child("✕")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            cx.stop_propagation();
+                            this.remove_domain(index, cx);
+                        }),
+                    ),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| this.select(index, cx)),
+            )
+    }
+
+    fn render_auth_choice(
+        &self,
+        label: String,
+        provider: Option<String>,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let selected = self.auth_provider == provider;
+        let (bg, hover) = if selected {
+            (0x0000_7acc, 0x0000_56b3)
+        } else {
+            (0x006c_757d, 0x005a_6268)
+        };
+        div()
+            .px_2()
+            .py_1()
+            .bg(rgb(bg))
+            .text_color(rgb(0x00ff_ffff))
+            .rounded_md()
+            .cursor_pointer()
+            .hover(move |style| style.bg(rgb(hover)))
+            .child(label)
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    this.auth_provider = provider.clone();
+                    cx.notify();
+                }),
+            )
+    }
+
+    fn render_domain_form(
+        &self,
+        domain: &DomainDefaults,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let providers: Vec<String> = cx
+            .global::<PluginRegistry>()
+            .auth_providers()
+            .iter()
+            .map(|provider| provider.name().to_string())
+            .collect();
+        let field = |input: Entity<HeaderInput>| {
+            div()
+                .border_1()
+                .border_color(rgb(0x00cc_cccc))
+                .rounded_md()
+                .child(input)
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .pt_2()
+            .border_t_1()
+            .border_color(rgb(0x00e9_ecef))
+            .child(
+                div()
+                    .font_weight(FontWeight::MEDIUM)
+                    .child(format!("Defaults for {}", domain.host)),
+            )
+            .child("Headers (Key: Value per line)")
+            .child(self.headers_input.clone())
+            .child(
+                div()
+                    .flex()
+                    .flex_wrap()
+                    .items_center()
+                    .gap_2()
+                    .child("Auth")
+                    .child(self.render_auth_choice("None".to_string(), None, cx))
+                    .children(
+                        providers
+                            .into_iter()
+                            .map(|name| self.render_auth_choice(name.clone(), Some(name), cx)),
+                    ),
+            )
+            .when(self.auth_provider.is_some(), |form| {
+                form.child(field(self.credential_input.clone()))
+            })
+            .child(field(self.proxy_input.clone()))
+            .child(field(self.size_limit_input.clone()))
+            .child(self.certificate_selector.clone())
+            .child(
+                div()
+                    .id("domain-insecure")
+                    .flex()
+                    .items_center()
+                    .gap_1()
+                    .cursor_pointer()
+                    .child(
+                        div()
+                            .w_4()
+                            .h_4()
+                            .flex()
+                            .items_center()
+                            .justify_center()
+                            .border_1()
+                            .border_color(rgb(0x00cc_cccc))
+                            .rounded_sm()
+                            .text_color(rgb(0x00ff_ffff))
+                            .when(self.accept_invalid_certs, |checkbox| {
+                                checkbox.bg(rgb(0x0000_7acc)).child("✓")
+                            }),
+                    )
+                    .child("Accept invalid TLS certificates")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.accept_invalid_certs = !this.accept_invalid_certs;
+                            cx.notify();
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .id("domain-save")
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x0000_7acc))
+                    .text_color(rgb(0x00ff_ffff))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x0000_56b3)))
+                    .child("Save")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.save_selected(cx)),
+                    ),
+            )
+    }
+}
+
+impl Render for DomainsPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let selected = self
+            .selected
+            .and_then(|index| self.settings.get(index))
+            .cloned();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(
+                div()
+                    .text_size(px(16.0))
+                    .font_weight(FontWeight::MEDIUM)
+                    .child("Domains"),
+            )
+            .child(
+                div()
+                    .text_color(rgb(0x006c_757d))
+                    .child("Defaults applied to every request for a host. Headers and auth set on the request itself take precedence."),
+            )
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .flex_1()
+                            .border_1()
+                            .border_color(rgb(0x00cc_cccc))
+                            .rounded_md()
+                            .child(self.host_input.clone()),
+                    )
+                    .child(
+                        div()
+                            .id("domain-add")
+                            .px_3()
+                            .py_1()
+                            .rounded_md()
+                            .bg(rgb(0x006c_757d))
+                            .text_color(rgb(0x00ff_ffff))
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x005a_6268)))
+                            .child("Add")
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| this.add_domain(cx)),
+                            ),
+                    ),
+            )
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| div().text_color(rgb(0x00dc_3545)).child(error)),
+            )
+            .children(
+                self.settings
+                    .domains()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, domain)| self.render_domain_row(index, domain, cx)),
+            )
+            .children(selected.map(|domain| self.render_domain_form(&domain, cx)))
+    }
+}
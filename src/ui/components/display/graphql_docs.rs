@@ -0,0 +1,270 @@
+use crate::utils::graphql::{Schema, TypeRef};
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, AnyElement, Context, FontWeight, InteractiveElement,
+    IntoElement, MouseButton, ParentElement, Render, StatefulInteractiveElement, Styled, Window,
+};
+
+/// Browsable schema documentation - starts at the root operation types and
+/// follows field / argument types by clicking them, with a back history
+pub struct GraphqlDocs {
+    schema: Option<Schema>,
+    // Type being shown, `None` for the overview
+    current: Option<String>,
+    history: Vec<Option<String>>,
+}
+
+impl GraphqlDocs {
+    pub fn new(_cx: &mut Context<Self>) -> Self {
+        Self {
+            schema: None,
+            current: None,
+            history: Vec::new(),
+        }
+    }
+
+    pub fn set_schema(&mut self, schema: Option<Schema>, cx: &mut Context<Self>) {
+        self.schema = schema;
+        self.current = None;
+        self.history.clear();
+        cx.notify();
+    }
+
+    pub fn open(&mut self, type_name: &str, cx: &mut Context<Self>) {
+        if self.current.as_deref() == Some(type_name) {
+            return;
+        }
+        self.history.push(self.current.take());
+        self.current = Some(type_name.to_string());
+        cx.notify();
+    }
+
+    pub fn back(&mut self, cx: &mut Context<Self>) {
+        if let Some(previous) = self.history.pop() {
+            self.current = previous;
+            cx.notify();
+        }
+    }
+
+    fn type_link(
+        &self,
+        id: (&'static str, usize),
+        type_ref: &TypeRef,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let name = type_ref.named().to_string();
+        div()
+            .id(id)
+            .text_color(rgb(0x0000_7acc))
+            .cursor_pointer()
+            .hover(|style| style.underline())
+            .child(type_ref.to_string())
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| this.open(&name, cx)),
+            )
+    }
+
+    fn description(text: Option<&str>) -> impl IntoElement {
+        div()
+            .text_size(px(11.0))
+            .text_color(rgb(0x006c_757d))
+            .children(text.map(str::to_string))
+    }
+
+    fn render_overview(&self, schema: &Schema, cx: &mut Context<Self>) -> AnyElement {
+        let roots = [
+            ("query", Some(schema.query_type.as_str())),
+            ("mutation", schema.mutation_type.as_deref()),
+            ("subscription", schema.subscription_type.as_deref()),
+        ];
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .children(
+                roots
+                    .into_iter()
+                    .enumerate()
+                    .filter_map(|(index, (operation, root))| {
+                        let root = root?;
+                        Some(div().flex().gap_2().child(operation).child(self.type_link(
+                            ("gql-root", index),
+                            &TypeRef::Named(root.to_string()),
+                            cx,
+                        )))
+                    }),
+            )
+            .child(
+                div()
+                    .pt_2()
+                    .font_weight(FontWeight::MEDIUM)
+                    .child(format!("All types ({})", schema.type_count())),
+            )
+            .children(
+                schema
+                    .type_names()
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, name)| {
+                        self.type_link(("gql-type", index), &TypeRef::Named(name.to_string()), cx)
+                    }),
+            )
+            .into_any_element()
+    }
+
+    fn render_type(&self, schema: &Schema, name: &str, cx: &mut Context<Self>) -> AnyElement {
+        let Some(schema_type) = schema.get_type(name) else {
+            return div()
+                .text_color(rgb(0x00dc_3545))
+                .child(format!("Type '{name}' is not in the schema"))
+                .into_any_element();
+        };
+        let mut link = 0;
+        let mut next_id = || {
+            link += 1;
+            ("gql-link", link)
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        div()
+                            .font_weight(FontWeight::MEDIUM)
+                            .child(schema_type.name.clone()),
+                    )
+                    .child(
+                        div()
+                            .text_color(rgb(0x006c_757d))
+                            .child(schema_type.kind.to_lowercase()),
+                    ),
+            )
+            .child(Self::description(schema_type.description.as_deref()))
+            .children(schema_type.fields.iter().map(|field| {
+                div()
+                    .flex()
+                    .flex_col()
+                    .py_1()
+                    .border_b_1()
+                    .border_color(rgb(0x00e9_ecef))
+                    .child(
+                        div()
+                            .flex()
+                            .flex_wrap()
+                            .gap_1()
+                            .child(field.name.clone())
+                            .when(!field.args.is_empty(), |row| {
+                                row.child("(")
+                                    .children(field.args.iter().map(|arg| {
+                                        div()
+                                            .flex()
+                                            .gap_1()
+                                            .child(format!("{}:", arg.name))
+                                            .child(self.type_link(next_id(), &arg.type_ref, cx))
+                                            .children(
+                                                arg.default_value
+                                                    .as_ref()
+                                                    .map(|value| format!("= {value}")),
+                                            )
+                                    }))
+                                    .child(")")
+                            })
+                            .child(":")
+                            .child(self.type_link(next_id(), &field.type_ref, cx)),
+                    )
+                    .child(Self::description(field.description.as_deref()))
+            }))
+            .children(schema_type.input_fields.iter().map(|input| {
+                div()
+                    .flex()
+                    .gap_1()
+                    .child(format!("{}:", input.name))
+                    .child(self.type_link(next_id(), &input.type_ref, cx))
+            }))
+            .children(
+                schema_type
+                    .enum_values
+                    .iter()
+                    .map(|value| div().font_family("monospace").child(value.clone())),
+            )
+            .when(!schema_type.related.is_empty(), |pane| {
+                pane.child(div().pt_2().text_color(rgb(0x006c_757d)).child(
+                    if schema_type.kind == "OBJECT" {
+                        "Implements"
+                    } else {
+                        "Possible types"
+                    },
+                ))
+                .children(
+                    schema_type.related.iter().map(|related| {
+                        self.type_link(next_id(), &TypeRef::Named(related.clone()), cx)
+                    }),
+                )
+            })
+            .into_any_element()
+    }
+}
+
+impl Render for GraphqlDocs {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let content = match (&self.schema, &self.current) {
+            (None, _) => div()
+                .text_color(rgb(0x006c_757d))
+                .child("Fetch the schema to browse its types")
+                .into_any_element(),
+            (Some(schema), None) => self.render_overview(schema, cx),
+            (Some(schema), Some(name)) => self.render_type(schema, name, cx),
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child("Docs"),
+                    )
+                    .child(div().flex_1())
+                    .when(!self.history.is_empty(), |header| {
+                        header.child(
+                            div()
+                                .id("gql-docs-back")
+                                .px_2()
+                                .py_1()
+                                .bg(rgb(0x006c_757d))
+                                .text_color(rgb(0x00ff_ffff))
+                                .rounded_md()
+                                .cursor_pointer()
+                                .hover(|style| style.bg(rgb(0x005a_6268)))
+                                .child("← Back")
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _event, _window, cx| this.back(cx)),
+                                ),
+                        )
+                    }),
+            )
+            .child(
+                div()
+                    .id("gql-docs")
+                    .flex()
+                    .flex_col()
+                    .max_h(px(480.0))
+                    .overflow_scroll()
+                    .child(content),
+            )
+    }
+}
@@ -1,11 +1,12 @@
 use crate::models::{HistoryEntry, HttpMethod, Request};
+use crate::state::{AppState, StateEvent};
 use gpui::{
-    div, px, rgb, Context, EventEmitter, InteractiveElement, IntoElement, ParentElement, Render,
-    Rgba, StatefulInteractiveElement, Styled, Window,
+    div, px, rgb, App, Context, Entity, EventEmitter, InteractiveElement, IntoElement,
+    ParentElement, Render, Rgba, StatefulInteractiveElement, Styled, Subscription, Window,
 };
 
 /// Get color for HTTP method
-fn get_method_color(method: HttpMethod) -> Rgba {
+pub fn get_method_color(method: HttpMethod) -> Rgba {
     match method {
         HttpMethod::GET => rgb(0x0028_a745),
         HttpMethod::POST => rgb(0x0000_7acc),
@@ -24,52 +25,83 @@ const COLOR_INFO_TEXT: u32 = 0x0099_9999;
 #[derive(Debug, Clone)]
 pub enum HistoryListEvent {
     RequestSelected(Request),
+    /// Export the entry as a shareable `.pgreq` file
+    ShareRequested(HistoryEntry),
+    /// Infer an OpenAPI skeleton from the entries sent to this entry's host
+    SpecRequested(HistoryEntry),
 }
 
-/// History list component for displaying request history
+/// History list component for displaying request history. The entries are
+/// read from the shared [`AppState`], so the list follows every change to
+/// the history
 pub struct HistoryList {
-    entries: Vec<HistoryEntry>,
-    selected_index: Option<usize>,
+    state: Entity<AppState>,
+    // Selected by id, so the selection stays on its entry as new ones arrive
+    selected_id: Option<u64>,
+    _subscription: Subscription,
 }
 
 impl EventEmitter<HistoryListEvent> for HistoryList {}
 
 impl HistoryList {
-    pub fn new() -> Self {
+    pub fn new(state: Entity<AppState>, cx: &mut Context<Self>) -> Self {
+        let subscription = cx.subscribe(&state, Self::on_state_event);
         Self {
-            entries: Vec::new(),
-            selected_index: None,
+            state,
+            selected_id: None,
+            _subscription: subscription,
         }
     }
 
-    /// Update the history entries
-    pub fn set_entries(&mut self, entries: Vec<HistoryEntry>, cx: &mut Context<Self>) {
-        self.entries = entries;
+    fn on_state_event(
+        &mut self,
+        state: Entity<AppState>,
+        event: &StateEvent,
+        cx: &mut Context<Self>,
+    ) {
+        if *event != StateEvent::HistoryChanged {
+            return;
+        }
+        // Drop the selection once its entry is gone, e.g. trimmed or after a profile switch
+        if self
+            .selected_id
+            .is_some_and(|id| state.read(cx).history().find(id).is_none())
+        {
+            self.selected_id = None;
+        }
         cx.notify();
     }
 
+    /// Id of the selected entry
+    pub fn selected_id(&self) -> Option<u64> {
+        self.selected_id
+    }
+
+    /// Get the currently selected entry
+    pub fn selected_entry<'a>(&self, cx: &'a App) -> Option<&'a HistoryEntry> {
+        self.selected_id
+            .and_then(|id| self.state.read(cx).history().find(id))
+    }
+
     /// Get the currently selected request
-    pub fn selected_request(&self) -> Option<&Request> {
-        self.selected_index
-            .and_then(|idx| self.entries.get(idx))
-            .map(|entry| &entry.request)
+    pub fn selected_request<'a>(&self, cx: &'a App) -> Option<&'a Request> {
+        self.selected_entry(cx).map(|entry| &entry.request)
     }
 
-    /// Clear all entries
-    pub fn clear(&mut self, cx: &mut Context<Self>) {
-        self.entries.clear();
-        self.selected_index = None;
+    /// Deselect the selected entry
+    pub fn clear_selection(&mut self, cx: &mut Context<Self>) {
+        self.selected_id = None;
         cx.notify();
     }
 
-    fn on_item_clicked(&mut self, index: usize, cx: &mut Context<Self>) -> HistoryListEvent {
-        self.selected_index = Some(index);
+    fn on_item_clicked(&mut self, id: u64, cx: &mut Context<Self>) -> HistoryListEvent {
+        self.selected_id = Some(id);
         cx.notify();
 
-        if let Some(entry) = self.entries.get(index) {
+        if let Some(entry) = self.state.read(cx).history().find(id) {
             tracing::info!(
-                "🔘 History item clicked: Index: {}, Method: {}, URL: {}",
-                index,
+                "🔘 History item clicked: Id: {}, Method: {}, URL: {}",
+                id,
                 entry.request.method,
                 entry.request.url
             );
@@ -80,12 +112,8 @@ impl HistoryList {
             tracing::info!("   ➡️ Loading request into form...");
             HistoryListEvent::RequestSelected(entry.request.clone())
         } else {
-            // Log the error if index is out of bounds (shouldn't happen, but handle gracefully)
-            tracing::info!(
-                "Warning: Attempted to select history item at invalid index {} (entries length: {})",
-                index,
-                self.entries.len()
-            );
+            // Log the error if the entry is gone (shouldn't happen, but handle gracefully)
+            tracing::info!("Warning: Attempted to select missing history item {}", id);
             HistoryListEvent::RequestSelected(Request::default())
         }
     }
@@ -93,6 +121,8 @@ impl HistoryList {
 
 impl Render for HistoryList {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let entries = self.state.read(cx).history().entries();
+
         div()
             .id("history-list")
             .flex()
@@ -123,7 +153,7 @@ impl Render for HistoryList {
                 div()
                     .flex()
                     .flex_col()
-                    .children(if self.entries.is_empty() {
+                    .children(if entries.is_empty() {
                         vec![div()
                             .px_3()
                             .py_4()
@@ -131,11 +161,11 @@ impl Render for HistoryList {
                             .text_color(rgb(0x006c_757d))
                             .child("No requests yet")]
                     } else {
-                        self.entries
+                        entries
                             .iter()
-                            .enumerate()
-                            .map(|(index, entry)| {
-                                let is_selected = self.selected_index == Some(index);
+                            .map(|entry| {
+                                let id = entry.id;
+                                let is_selected = self.selected_id == Some(id);
                                 let method_color = get_method_color(entry.request.method);
 
                                 let bg_color = if is_selected {
@@ -161,7 +191,7 @@ impl Render for HistoryList {
                                     .on_mouse_up(
                                         gpui::MouseButton::Left,
                                         cx.listener(move |this, _event, _window, cx| {
-                                            let event = this.on_item_clicked(index, cx);
+                                            let event = this.on_item_clicked(id, cx);
                                             cx.emit(event);
                                         }),
                                     )
@@ -190,6 +220,67 @@ impl Render for HistoryList {
                                                             .text_size(px(10.0))
                                                             .text_color(rgb(0x006c_757d))
                                                             .child(entry.formatted_time()),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .ml_auto()
+                                                            .px_1()
+                                                            .text_size(px(10.0))
+                                                            .text_color(rgb(0x0000_7acc))
+                                                            .rounded_sm()
+                                                            .hover(|style| {
+                                                                style.bg(rgb(0x00e9_ecef))
+                                                            })
+                                                            .child("Share")
+                                                            .on_mouse_up(
+                                                                gpui::MouseButton::Left,
+                                                                cx.listener(
+                                                                    move |this, _event, _window, cx| {
+                                                                        cx.stop_propagation();
+                                                                        if let Some(entry) = this
+                                                                            .state
+                                                                            .read(cx)
+                                                                            .history()
+                                                                            .find(id)
+                                                                            .cloned()
+                                                                        {
+                                                                            cx.emit(HistoryListEvent::ShareRequested(
+                                                                                entry,
+                                                                            ));
+                                                                        }
+                                                                    },
+                                                                ),
+                                                            ),
+                                                    )
+                                                    .child(
+                                                        div()
+                                                            .px_1()
+                                                            .text_size(px(10.0))
+                                                            .text_color(rgb(0x0000_7acc))
+                                                            .rounded_sm()
+                                                            .hover(|style| {
+                                                                style.bg(rgb(0x00e9_ecef))
+                                                            })
+                                                            .child("Spec")
+                                                            .on_mouse_up(
+                                                                gpui::MouseButton::Left,
+                                                                cx.listener(
+                                                                    move |this, _event, _window, cx| {
+                                                                        cx.stop_propagation();
+                                                                        if let Some(entry) = this
+                                                                            .state
+                                                                            .read(cx)
+                                                                            .history()
+                                                                            .find(id)
+                                                                            .cloned()
+                                                                        {
+                                                                            cx.emit(HistoryListEvent::SpecRequested(
+                                                                                entry,
+                                                                            ));
+                                                                        }
+                                                                    },
+                                                                ),
+                                                            ),
                                                     ),
                                             )
                                             .child(
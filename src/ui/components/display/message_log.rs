@@ -0,0 +1,144 @@
+use chrono::{DateTime, Local};
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, Context, FontWeight, InteractiveElement, IntoElement,
+    ParentElement, Render, StatefulInteractiveElement, Styled, Window,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLogKind {
+    Sent,
+    Received,
+    /// Connection or call state changes
+    Info,
+    Status {
+        ok: bool,
+    },
+}
+
+#[derive(Debug, Clone)]
+pub struct MessageLogEntry {
+    pub time: DateTime<Local>,
+    pub kind: MessageLogKind,
+    pub text: String,
+}
+
+/// Message log of a streaming call (gRPC, GraphQL subscriptions) - client
+/// and server messages with the time they were sent or received, appended as
+/// the streams progress
+pub struct MessageLog {
+    entries: Vec<MessageLogEntry>,
+    placeholder: &'static str,
+}
+
+impl MessageLog {
+    pub fn new(_cx: &mut Context<Self>) -> Self {
+        Self {
+            entries: Vec::new(),
+            placeholder: "Start a call to see the messages exchanged",
+        }
+    }
+
+    /// Text shown while the log is empty
+    pub fn with_placeholder(mut self, placeholder: &'static str) -> Self {
+        self.placeholder = placeholder;
+        self
+    }
+
+    pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.entries.clear();
+        cx.notify();
+    }
+
+    pub fn push(&mut self, kind: MessageLogKind, text: impl Into<String>, cx: &mut Context<Self>) {
+        self.entries.push(MessageLogEntry {
+            time: Local::now(),
+            kind,
+            text: text.into(),
+        });
+        cx.notify();
+    }
+
+    pub fn entries(&self) -> &[MessageLogEntry] {
+        &self.entries
+    }
+
+    fn count(&self, kind: MessageLogKind) -> usize {
+        self.entries
+            .iter()
+            .filter(|entry| entry.kind == kind)
+            .count()
+    }
+}
+
+impl Render for MessageLog {
+    fn render(&mut self, _window: &mut Window, _cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child("Messages"),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!(
+                                "{} sent | {} received",
+                                self.count(MessageLogKind::Sent),
+                                self.count(MessageLogKind::Received)
+                            )),
+                    ),
+            )
+            .when(self.entries.is_empty(), |log| {
+                log.child(
+                    div()
+                        .text_size(px(12.0))
+                        .text_color(rgb(0x006c_757d))
+                        .child(self.placeholder),
+                )
+            })
+            .child(
+                div()
+                    .id("message-log")
+                    .flex()
+                    .flex_col()
+                    .overflow_scroll()
+                    .children(self.entries.iter().enumerate().map(|(index, entry)| {
+                        let (marker, color) = match entry.kind {
+                            MessageLogKind::Sent => ("→", 0x0000_7acc),
+                            MessageLogKind::Received => ("←", 0x0028_a745),
+                            MessageLogKind::Info => ("•", 0x006c_757d),
+                            MessageLogKind::Status { ok: true } => ("■", 0x0028_a745),
+                            MessageLogKind::Status { ok: false } => ("■", 0x00dc_3545),
+                        };
+                        div()
+                            .id(("message-entry", index))
+                            .flex()
+                            .gap_2()
+                            .py_1()
+                            .border_b_1()
+                            .border_color(rgb(0x00e9_ecef))
+                            .text_size(px(12.0))
+                            .child(
+                                div()
+                                    .flex_none()
+                                    .text_color(rgb(0x006c_757d))
+                                    .child(entry.time.format("%H:%M:%S%.3f").to_string()),
+                            )
+                            .child(div().flex_none().text_color(rgb(color)).child(marker))
+                            .child(div().flex_1().font_family("monospace").children(
+                                entry.text.lines().map(|line| div().child(line.to_string())),
+                            ))
+                    })),
+            )
+    }
+}
@@ -1,6 +1,6 @@
 use gpui::{
-    div, AppContext, Context, Entity, EventEmitter, IntoElement, ParentElement, Render, Styled,
-    Subscription, Window,
+    div, App, AppContext, Context, Entity, EventEmitter, IntoElement, ParentElement, Render,
+    Styled, Subscription, Window,
 };
 
 use crate::models::HttpMethod;
@@ -51,6 +51,11 @@ impl MethodSelector {
         method
     }
 
+    /// 读取当前方法（不输出日志，供渲染时使用）
+    pub fn current_method(&self, cx: &App) -> HttpMethod {
+        self.dropdown.read(cx).selected_value().into()
+    }
+
     pub fn set_selected_method(&mut self, method: HttpMethod, cx: &mut Context<Self>) {
         tracing::info!("📝 MethodSelector::set_selected_method - 设置方法: {method}");
         //println!("📝 调用栈: {:?}", std::backtrace::Backtrace::capture());
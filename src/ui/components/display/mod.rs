@@ -1,4 +1,12 @@
 // src/ui/components/display/mod.rs
+pub mod api_explorer;
+pub mod certificates_page;
+pub mod csv_grid;
+pub mod domains_page;
+pub mod graphql_docs;
 pub mod history_list;
+pub mod message_log;
 pub mod method_selector;
+pub mod ndjson_viewer;
 pub mod response_viewer;
+pub mod snippets_page;
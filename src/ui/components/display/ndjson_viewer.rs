@@ -0,0 +1,274 @@
+use crate::ui::components::header_input::{HeaderInput, HeaderInputEvent};
+use crate::utils::ndjson::{parse_lines, NdjsonParser, NdjsonRecord};
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, AppContext, Context, Entity, FontWeight,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Subscription, Window,
+};
+use std::collections::BTreeSet;
+
+/// NDJSON / JSON Lines response viewer - one collapsible row per record,
+/// filled in as the response streams in
+pub struct NdjsonViewer {
+    records: Vec<NdjsonRecord>,
+    parser: NdjsonParser,
+    // Indices of expanded records
+    expanded: BTreeSet<usize>,
+    filter_input: Entity<HeaderInput>,
+    streaming: bool,
+    _filter_subscription: Subscription,
+}
+
+impl NdjsonViewer {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        let filter_input = cx.new(|cx| HeaderInput::new(cx).with_placeholder("Filter records..."));
+        // 过滤条件变化时重新渲染
+        let filter_subscription = cx.subscribe(
+            &filter_input,
+            |_this, _input, event: &HeaderInputEvent, cx| {
+                if let HeaderInputEvent::ValueChanged(_) = event {
+                    cx.notify();
+                }
+            },
+        );
+
+        Self {
+            records: Vec::new(),
+            parser: NdjsonParser::new(),
+            expanded: BTreeSet::new(),
+            filter_input,
+            streaming: false,
+            _filter_subscription: filter_subscription,
+        }
+    }
+
+    /// Clear the records before a new stream starts
+    pub fn start_stream(&mut self, cx: &mut Context<Self>) {
+        self.records.clear();
+        self.parser = NdjsonParser::new();
+        self.expanded.clear();
+        self.streaming = true;
+        cx.notify();
+    }
+
+    /// Add the records completed by a chunk of the stream
+    pub fn push_chunk(&mut self, chunk: &[u8], cx: &mut Context<Self>) {
+        let records = self.parser.push(chunk);
+        if !records.is_empty() {
+            self.records.extend(records);
+            cx.notify();
+        }
+    }
+
+    /// End of the stream - an unterminated last line becomes a record too
+    pub fn finish_stream(&mut self, cx: &mut Context<Self>) {
+        self.records.extend(self.parser.finish());
+        self.streaming = false;
+        tracing::info!("📜 NdjsonViewer - 流结束，共 {} 条记录", self.records.len());
+        cx.notify();
+    }
+
+    /// Show a complete (non-streamed) body
+    pub fn set_body(&mut self, body: &str, cx: &mut Context<Self>) {
+        self.records = parse_lines(body);
+        self.parser = NdjsonParser::new();
+        self.expanded.clear();
+        self.streaming = false;
+        cx.notify();
+    }
+
+    pub fn records(&self) -> &[NdjsonRecord] {
+        &self.records
+    }
+
+    pub fn is_streaming(&self) -> bool {
+        self.streaming
+    }
+
+    fn toggle_record(&mut self, index: usize, cx: &mut Context<Self>) {
+        if !self.expanded.remove(&index) {
+            self.expanded.insert(index);
+        }
+        cx.notify();
+    }
+
+    fn set_all_expanded(&mut self, expanded: bool, cx: &mut Context<Self>) {
+        self.expanded = if expanded {
+            (0..self.records.len()).collect()
+        } else {
+            BTreeSet::new()
+        };
+        cx.notify();
+    }
+
+    fn render_record(
+        &self,
+        index: usize,
+        record: &NdjsonRecord,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let expanded = self.expanded.contains(&index);
+        div()
+            .id(("ndjson-record", index))
+            .flex()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .border_b_1()
+            .border_color(rgb(0x00e9_ecef))
+            .cursor_pointer()
+            .hover(|style| style.bg(rgb(0x00f8_f9fa)))
+            .child(
+                div()
+                    .w_10()
+                    .flex_none()
+                    .text_color(rgb(0x006c_757d))
+                    .child(format!("{}", index + 1)),
+            )
+            .child(
+                div()
+                    .w_3()
+                    .flex_none()
+                    .text_color(rgb(0x006c_757d))
+                    .child(if expanded { "▾" } else { "▸" }),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .font_family("monospace")
+                    .when(!record.is_valid(), |text| text.text_color(rgb(0x00dc_3545)))
+                    .child(if expanded {
+                        record.pretty()
+                    } else {
+                        record.summary()
+                    }),
+            )
+            .when_some(record.value.as_ref().err(), |row, error| {
+                row.child(
+                    div()
+                        .flex_none()
+                        .text_color(rgb(0x00dc_3545))
+                        .child(format!("Invalid JSON: {error}")),
+                )
+            })
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| {
+                    this.toggle_record(index, cx);
+                }),
+            )
+    }
+}
+
+impl Render for NdjsonViewer {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let filter = self.filter_input.read(cx).get_content().to_string();
+        let visible: Vec<(usize, NdjsonRecord)> = self
+            .records
+            .iter()
+            .enumerate()
+            .filter(|(_, record)| record.matches(&filter))
+            .map(|(index, record)| (index, record.clone()))
+            .collect();
+        let invalid = self
+            .records
+            .iter()
+            .filter(|record| !record.is_valid())
+            .count();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .child(
+                div()
+                    .flex()
+                    .items_center()
+                    .gap_2()
+                    .child(
+                        div()
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM)
+                            .child("Records"),
+                    )
+                    .child(
+                        div()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(format!(
+                                "{} records | {} shown | {} invalid{}",
+                                self.records.len(),
+                                visible.len(),
+                                invalid,
+                                if self.streaming {
+                                    " | streaming..."
+                                } else {
+                                    ""
+                                }
+                            )),
+                    )
+                    .child(div().flex_1().child(self.filter_input.clone()))
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x006c_757d))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x005a_6268)))
+                            .child("Expand all")
+                            .text_size(px(12.0))
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.set_all_expanded(true, cx);
+                                }),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .px_2()
+                            .py_1()
+                            .bg(rgb(0x006c_757d))
+                            .text_color(rgb(0x00ff_ffff))
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x005a_6268)))
+                            .child("Collapse all")
+                            .text_size(px(12.0))
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.set_all_expanded(false, cx);
+                                }),
+                            ),
+                    ),
+            )
+            .child(
+                div()
+                    .id("ndjson-records")
+                    .flex()
+                    .flex_col()
+                    .text_size(px(12.0))
+                    .border_1()
+                    .border_color(rgb(0x00cc_cccc))
+                    .rounded_md()
+                    .overflow_scroll()
+                    .when(visible.is_empty(), |list| {
+                        list.child(div().p_2().text_color(rgb(0x006c_757d)).child(
+                            if self.streaming {
+                                "Waiting for records..."
+                            } else {
+                                "No records"
+                            },
+                        ))
+                    })
+                    .children(
+                        visible
+                            .iter()
+                            .map(|(index, record)| self.render_record(*index, record, cx)),
+                    ),
+            )
+    }
+}
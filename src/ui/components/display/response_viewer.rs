@@ -1,11 +1,27 @@
+use crate::http::response::header_value;
+use crate::models::{Annotation, HighlightColor, SavedResponse};
+use crate::ui::components::common::dropdown::{Dropdown, DropdownEvent};
+use crate::ui::components::header_input::HeaderInput;
+use crate::utils::formatter::format_body;
+use crate::utils::json_fold::{fold_regions, FoldRegion, FoldedText};
+use crate::utils::links::{link_at, Link};
+use crate::utils::range::ContentRange;
+use crate::utils::sniff::{detect, BodyKind};
+use crate::utils::status_codes::{explain_status, reason_phrase, StatusExplanation};
+use crate::utils::whitespace::{
+    line_ending_summary, lines_with_endings, visible_char, visible_whitespace,
+};
 use gpui::{
-    actions, div, fill, point, px, rgb, rgba, App, Bounds, ClipboardItem, Context, CursorStyle,
-    Element, ElementId, Entity, FocusHandle, Focusable, FontWeight, GlobalElementId,
-    InteractiveElement, IntoElement, KeyBinding, LayoutId, MouseButton, MouseDownEvent,
-    MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels, Point, Render, ShapedLine,
-    StatefulInteractiveElement, Style, Styled, TextAlign, TextRun, Window,
+    actions, div, fill, img, point, prelude::FluentBuilder, px, rgb, rgba, App, AppContext, Bounds,
+    ClipboardItem, Context, CursorStyle, Element, ElementId, Entity, EventEmitter, FocusHandle,
+    Focusable, FontWeight, GlobalElementId, InteractiveElement, IntoElement, KeyBinding, LayoutId,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels,
+    Point, Render, Rgba, ShapedLine, StatefulInteractiveElement, Style, Styled, Subscription,
+    TextAlign, TextRun, TextStyle, Window,
 };
+use std::collections::BTreeSet;
 use std::ops::Range;
+use std::path::PathBuf;
 
 actions!(response_viewer, [Copy, SelectAll]);
 
@@ -26,11 +42,22 @@ pub enum ResponseState {
     /// 加载中
     Loading,
     /// 已收到响应
-    Success { status: u16, body: String },
+    Success {
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+    },
     /// 请求失败
     Error { message: String },
 }
 
+/// Response 查看器事件
+#[derive(Debug, Clone)]
+pub enum ResponseViewerEvent {
+    /// 在新标签页中请求响应里的链接
+    OpenLink(Link),
+}
+
 /// Response 查看器组件
 pub struct ResponseViewer {
     state: ResponseState,
@@ -40,8 +67,37 @@ pub struct ResponseViewer {
     is_selecting: bool,
     last_bounds: Option<Bounds<Pixels>>,
     last_lines_layout: Vec<(ShapedLine, usize)>, // (shaped_line, char_offset)
+    show_status_explanation: bool,
+    // 响应批注
+    annotations: Vec<Annotation>,
+    annotation_color: HighlightColor,
+    comment_input: Entity<HeaderInput>,
+    // JSON 折叠 - 折叠区域按原始行号记录
+    fold_regions: Vec<FoldRegion>,
+    folded_lines: BTreeSet<usize>,
+    folded_text: Option<FoldedText>,
+    // 右键点击找到的链接（URL / 路径 / id）
+    link_offer: Option<Link>,
+    // 显示空格、制表符和换行符
+    show_whitespace: bool,
+    // 按内容检测到的响应体类型，以及手动选择的查看方式（None 为自动）
+    detected_kind: Option<BodyKind>,
+    view_as: Option<BodyKind>,
+    view_as_selector: Entity<Dropdown>,
+    // 按手动选择的类型重新格式化的响应体
+    view_text: Option<String>,
+    // 图片响应写入的文件
+    image: Option<PathBuf>,
+    // 只读（查看模式）- 不能添加或删除批注
+    read_only: bool,
+    _view_as_subscription: Subscription,
 }
 
+// View as option that shows the body as detected
+const AUTO_VIEW: &str = "Auto";
+
+impl EventEmitter<ResponseViewerEvent> for ResponseViewer {}
+
 impl Focusable for ResponseViewer {
     fn focus_handle(&self, _cx: &gpui::App) -> FocusHandle {
         self.focus_handle.clone()
@@ -50,6 +106,16 @@ impl Focusable for ResponseViewer {
 
 impl ResponseViewer {
     pub fn new(cx: &mut Context<Self>) -> Self {
+        let view_as_selector = cx.new(|cx| {
+            let options = std::iter::once(AUTO_VIEW)
+                .chain(BodyKind::choices().map(BodyKind::label))
+                .map(String::from)
+                .collect();
+            Dropdown::new("response-view-as", cx)
+                .with_options(options)
+                .with_selected(AUTO_VIEW)
+        });
+        let view_as_subscription = cx.subscribe(&view_as_selector, Self::on_view_as_event);
         Self {
             state: ResponseState::NotSent,
             focus_handle: cx.focus_handle(),
@@ -58,9 +124,31 @@ impl ResponseViewer {
             is_selecting: false,
             last_bounds: None,
             last_lines_layout: Vec::new(),
+            show_status_explanation: false,
+            annotations: Vec::new(),
+            annotation_color: HighlightColor::Yellow,
+            comment_input: cx.new(|cx| HeaderInput::new(cx).with_placeholder("Comment (optional)")),
+            fold_regions: Vec::new(),
+            folded_lines: BTreeSet::new(),
+            folded_text: None,
+            link_offer: None,
+            show_whitespace: false,
+            detected_kind: None,
+            view_as: None,
+            view_as_selector,
+            view_text: None,
+            image: None,
+            read_only: false,
+            _view_as_subscription: view_as_subscription,
         }
     }
 
+    /// 只读模式 - 隐藏添加和删除批注的按钮，已有的批注照常显示
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
     /// 设置为加载状态
     pub fn set_loading(&mut self, cx: &mut Context<Self>) {
         self.state = ResponseState::Loading;
@@ -68,9 +156,23 @@ impl ResponseViewer {
     }
 
     /// 设置成功响应
-    pub fn set_success(&mut self, status: u16, body: String, cx: &mut Context<Self>) {
-        self.state = ResponseState::Success { status, body };
+    pub fn set_success(
+        &mut self,
+        status: u16,
+        headers: Vec<(String, String)>,
+        body: String,
+        cx: &mut Context<Self>,
+    ) {
+        self.state = ResponseState::Success {
+            status,
+            headers,
+            body,
+        };
         self.selected_range = 0..0;
+        self.show_status_explanation = false;
+        self.annotations.clear();
+        self.reset_view(cx);
+        self.reset_folds();
         cx.notify();
     }
 
@@ -78,6 +180,9 @@ impl ResponseViewer {
     pub fn set_error(&mut self, message: String, cx: &mut Context<Self>) {
         self.state = ResponseState::Error { message };
         self.selected_range = 0..0;
+        self.annotations.clear();
+        self.reset_view(cx);
+        self.reset_folds();
         cx.notify();
     }
 
@@ -85,22 +190,250 @@ impl ResponseViewer {
     pub fn clear(&mut self, cx: &mut Context<Self>) {
         self.state = ResponseState::NotSent;
         self.selected_range = 0..0;
+        self.annotations.clear();
+        self.reset_view(cx);
+        self.reset_folds();
         cx.notify();
     }
 
+    /// 恢复状态（切换标签页时使用）
+    pub fn set_state(&mut self, state: ResponseState, cx: &mut Context<Self>) {
+        self.state = state;
+        self.selected_range = 0..0;
+        self.show_status_explanation = false;
+        self.annotations.clear();
+        self.reset_view(cx);
+        self.reset_folds();
+        cx.notify();
+    }
+
+    /// 显示图片响应（响应体已写入 `path`）
+    pub fn set_image(&mut self, path: PathBuf, cx: &mut Context<Self>) {
+        self.detected_kind = Some(BodyKind::Image);
+        self.image = Some(path);
+        cx.notify();
+    }
+
+    /// 按指定类型查看响应体，None 恢复为检测到的类型
+    pub fn set_view_as(&mut self, kind: Option<BodyKind>, cx: &mut Context<Self>) {
+        if kind == self.view_as {
+            return;
+        }
+        tracing::info!("👁️ ResponseViewer - 查看方式: {kind:?}");
+        self.view_as = kind;
+        self.view_text = match (&self.state, kind) {
+            (ResponseState::Success { body, .. }, Some(kind)) => {
+                Some(format_body(kind, body)).filter(|text| text != body)
+            }
+            _ => None,
+        };
+        self.selected_range = 0..0;
+        self.reset_folds();
+        cx.notify();
+    }
+
+    fn on_view_as_event(
+        &mut self,
+        _selector: Entity<Dropdown>,
+        event: &DropdownEvent,
+        cx: &mut Context<Self>,
+    ) {
+        let DropdownEvent::SelectionChanged(label) = event;
+        self.set_view_as(BodyKind::from_label(label), cx);
+    }
+
+    // 新响应按检测到的类型显示
+    fn reset_view(&mut self, cx: &mut Context<Self>) {
+        self.detected_kind = match &self.state {
+            ResponseState::Success { headers, body, .. } => Some(detect(
+                header_value(headers, "Content-Type"),
+                body.as_bytes(),
+            )),
+            _ => None,
+        };
+        self.view_as = None;
+        self.view_text = None;
+        self.image = None;
+        self.view_as_selector
+            .update(cx, |selector, cx| selector.set_selected(AUTO_VIEW, cx));
+    }
+
+    /// 显示的响应体：手动选择查看方式后为重新格式化的文本
+    pub fn shown_body(&self) -> Option<&str> {
+        match &self.state {
+            ResponseState::Success { body, .. } => Some(self.view_text.as_deref().unwrap_or(body)),
+            _ => None,
+        }
+    }
+
     /// 获取当前状态
     pub fn get_state(&self) -> &ResponseState {
         &self.state
     }
 
+    /// 设置批注（从历史记录或文件恢复时使用）
+    pub fn set_annotations(&mut self, annotations: Vec<Annotation>, cx: &mut Context<Self>) {
+        self.annotations = annotations;
+        cx.notify();
+    }
+
+    /// 获取当前批注
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
+    }
+
+    /// 当前响应及其批注（用于保存到历史记录或导出）
+    pub fn saved_response(&self) -> Option<SavedResponse> {
+        match &self.state {
+            ResponseState::Success {
+                status,
+                headers,
+                body,
+            } => Some(SavedResponse {
+                status: *status,
+                headers: headers.clone(),
+                body: body.clone(),
+                annotations: self.annotations.clone(),
+            }),
+            _ => None,
+        }
+    }
+
+    // 为选中的文本添加批注
+    fn add_annotation(&mut self, cx: &mut Context<Self>) {
+        let Some(mut response) = self.saved_response() else {
+            return;
+        };
+        let comment = self.comment_input.read(cx).get_content().trim().to_string();
+        let range = self.source_range(&self.selected_range);
+
+        match response.add_annotation(range, self.annotation_color, comment) {
+            Ok(()) => {
+                tracing::info!(
+                    "🖍️ ResponseViewer - 添加批注: {}..{}",
+                    self.selected_range.start,
+                    self.selected_range.end
+                );
+                self.annotations = response.annotations;
+                self.selected_range = self.selected_range.end..self.selected_range.end;
+                self.comment_input.update(cx, |input, cx| input.clear(cx));
+            }
+            Err(e) => tracing::info!("❌ ResponseViewer - 添加批注失败: {e}"),
+        }
+        cx.notify();
+    }
+
+    fn remove_annotation(&mut self, index: usize, cx: &mut Context<Self>) {
+        if index < self.annotations.len() {
+            self.annotations.remove(index);
+            cx.notify();
+        }
+    }
+
+    // 重新计算可折叠区域（仅 JSON 响应）
+    fn reset_folds(&mut self) {
+        self.fold_regions = match self.shown_body() {
+            Some(body) if serde_json::from_str::<serde_json::Value>(body).is_ok() => {
+                fold_regions(body)
+            }
+            _ => Vec::new(),
+        };
+        self.folded_lines.clear();
+        self.folded_text = None;
+        self.link_offer = None;
+    }
+
+    /// 折叠或展开从 `line` 开始的 JSON 对象/数组
+    pub fn toggle_fold(&mut self, line: usize, cx: &mut Context<Self>) {
+        if !self.folded_lines.remove(&line) {
+            self.folded_lines.insert(line);
+        }
+        self.folded_text = match self.shown_body() {
+            Some(body) if !self.folded_lines.is_empty() => Some(FoldedText::new(
+                body,
+                &self.fold_regions,
+                &self.folded_lines,
+            )),
+            _ => None,
+        };
+        self.selected_range = 0..0;
+        self.selection_reversed = false;
+        cx.notify();
+    }
+
+    // 显示行对应的原始行号
+    fn source_line_for_display_line(&self, display_line: usize) -> Option<usize> {
+        match &self.folded_text {
+            Some(folded) => folded.lines.get(display_line).map(|line| line.source_line),
+            None => Some(display_line),
+        }
+    }
+
+    // 可折叠区域的起始行是否已折叠（None 表示该行不可折叠）
+    fn fold_state_for_display_line(&self, display_line: usize) -> Option<bool> {
+        let source_line = self.source_line_for_display_line(display_line)?;
+        self.fold_regions
+            .iter()
+            .any(|region| region.start_line == source_line)
+            .then(|| self.folded_lines.contains(&source_line))
+    }
+
+    // 点击位置是否在折叠按钮上，返回对应区域的起始行
+    fn fold_handle_at(&self, position: Point<Pixels>) -> Option<usize> {
+        let bounds = self.last_bounds.as_ref()?;
+        if self.fold_regions.is_empty()
+            || self.last_lines_layout.is_empty()
+            || position.x >= bounds.left()
+            || position.x < bounds.left() - px(FOLD_GUTTER_WIDTH)
+            || position.y < bounds.top()
+        {
+            return None;
+        }
+        let line_height = bounds.size.height / self.last_lines_layout.len() as f32;
+        let display_line = ((position.y - bounds.top()) / line_height).floor() as usize;
+        self.fold_state_for_display_line(display_line)?;
+        self.source_line_for_display_line(display_line)
+    }
+
+    // 将显示文本中的范围转换为原始响应中的范围
+    fn source_range(&self, range: &Range<usize>) -> Range<usize> {
+        match &self.folded_text {
+            Some(folded) => folded.source_offset(range.start)..folded.source_offset(range.end),
+            None => range.clone(),
+        }
+    }
+
+    // 将原始响应中的范围转换为显示文本中的范围（被折叠隐藏时返回 None）
+    fn display_range(&self, range: &Range<usize>) -> Option<Range<usize>> {
+        match &self.folded_text {
+            Some(folded) => folded.display_range(range),
+            None => Some(range.clone()),
+        }
+    }
+
     fn get_content(&self) -> String {
         match &self.state {
-            ResponseState::Success { body, .. } => body.clone(),
+            ResponseState::Success { .. } => match &self.folded_text {
+                Some(folded) => folded.text.clone(),
+                None => self.shown_body().unwrap_or_default().to_string(),
+            },
             ResponseState::Error { message } => message.clone(),
             _ => String::new(),
         }
     }
 
+    /// 切换状态码说明面板
+    pub fn toggle_status_explanation(&mut self, cx: &mut Context<Self>) {
+        self.show_status_explanation = !self.show_status_explanation;
+        cx.notify();
+    }
+
+    /// 切换空白字符和换行符的显示
+    pub fn toggle_whitespace(&mut self, cx: &mut Context<Self>) {
+        self.show_whitespace = !self.show_whitespace;
+        cx.notify();
+    }
+
     fn copy(&mut self, _: &Copy, _window: &mut Window, cx: &mut Context<Self>) {
         if !self.selected_range.is_empty() {
             let content = self.get_content();
@@ -134,6 +467,10 @@ impl ResponseViewer {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if let Some(line) = self.fold_handle_at(event.position) {
+            self.toggle_fold(line, cx);
+            return;
+        }
         self.is_selecting = true;
         if event.modifiers.shift {
             self.response_select_to(self.index_for_mouse_position(event.position), cx);
@@ -142,6 +479,33 @@ impl ResponseViewer {
         }
     }
 
+    // 右键点击响应中的 URL、路径或 id 时提供在新标签页中打开
+    fn on_right_mouse_down(
+        &mut self,
+        event: &MouseDownEvent,
+        _window: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(body) = self.shown_body() else {
+            return;
+        };
+        let offset = self.index_for_mouse_position(event.position);
+        let offset = self.source_range(&(offset..offset)).start;
+        let link = link_at(body, offset);
+        if let Some(link) = &link {
+            tracing::info!("🔗 ResponseViewer - 找到链接: {link:?}");
+        }
+        self.link_offer = link;
+        cx.notify();
+    }
+
+    fn open_link(&mut self, cx: &mut Context<Self>) {
+        if let Some(link) = self.link_offer.take() {
+            cx.emit(ResponseViewerEvent::OpenLink(link));
+            cx.notify();
+        }
+    }
+
     fn response_move_to(&mut self, offset: usize, cx: &mut Context<Self>) {
         self.selected_range = offset..offset;
         cx.notify();
@@ -209,12 +573,305 @@ impl ResponseViewer {
 
         let (shaped_line, line_char_offset) = &self.last_lines_layout[line_index];
         let x_in_line = position.x - bounds.left();
-        let offset_in_line = shaped_line.closest_index_for_x(x_in_line);
+        // 排版结果按字节计，选区按字符计
+        let byte_in_line = shaped_line.closest_index_for_x(x_in_line);
+        let offset_in_line = shaped_line.text[..byte_in_line].chars().count();
 
         let absolute_offset = line_char_offset.saturating_add(offset_in_line);
         absolute_offset.min(content.chars().count())
     }
 
+    // 206/416 响应显示解析后的 Content-Range
+    fn render_partial_content(
+        &self,
+        status: u16,
+        headers: &[(String, String)],
+    ) -> Option<impl IntoElement> {
+        let content_range = header_value(headers, "Content-Range").and_then(ContentRange::parse);
+        let summary = match (status, content_range) {
+            (206, Some(range)) => format!("Partial Content - {}", range.describe()),
+            (206, None) => {
+                "Partial Content - the server did not send a valid Content-Range header".to_string()
+            }
+            (416, Some(range)) => format!("Range Not Satisfiable - {}", range.describe()),
+            _ => return None,
+        };
+
+        Some(
+            div()
+                .px_3()
+                .py_2()
+                .bg(rgb(0x00f0_f8ff))
+                .border_1()
+                .border_color(rgb(0x00b8_daff))
+                .rounded_md()
+                .text_size(px(12.0))
+                .font_weight(FontWeight::MEDIUM)
+                .child(summary),
+        )
+    }
+
+    fn render_status_explanation(&self, explanation: StatusExplanation) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .px_3()
+            .py_2()
+            .bg(rgb(0x00f0_f8ff))
+            .border_1()
+            .border_color(rgb(0x00b8_daff))
+            .rounded_md()
+            .text_size(px(12.0))
+            .child(div().font_weight(FontWeight::SEMIBOLD).child(format!(
+                "{} {} ({})",
+                explanation.code, explanation.reason, explanation.reference
+            )))
+            .child(div().child(explanation.meaning))
+            .child(
+                div()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(0x006c_757d))
+                    .child("Likely causes:"),
+            )
+            .children(
+                explanation
+                    .likely_causes
+                    .iter()
+                    .map(|cause| div().pl_2().child(format!("• {cause}"))),
+            )
+            .child(
+                div()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(0x006c_757d))
+                    .child("Next steps:"),
+            )
+            .children(
+                explanation
+                    .next_steps
+                    .iter()
+                    .map(|step| div().pl_2().child(format!("• {step}"))),
+            )
+    }
+
+    // 选中文本后显示的批注工具栏
+    // 查看方式下拉框；检测到的类型与 Content-Type 不符时说明原因
+    fn render_view_as(&self, headers: &[(String, String)]) -> impl IntoElement {
+        let content_type = header_value(headers, "Content-Type");
+        let declared = content_type.and_then(BodyKind::from_content_type);
+        let note = self
+            .detected_kind
+            .filter(|kind| {
+                declared.is_some_and(|declared| declared != *kind)
+                    || (declared.is_none() && !matches!(kind, BodyKind::Text | BodyKind::Binary))
+            })
+            .map(|kind| match content_type {
+                Some(content_type) => {
+                    format!(
+                        "Detected as {} (Content-Type: {content_type})",
+                        kind.label()
+                    )
+                }
+                None => format!("Detected as {} (no Content-Type)", kind.label()),
+            });
+        let unavailable =
+            self.view_as.or(self.detected_kind) == Some(BodyKind::Image) && self.image.is_none();
+
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_size(px(12.0))
+            .text_color(rgb(0x006c_757d))
+            .child("View as")
+            .child(div().w_32().child(self.view_as_selector.clone()))
+            .children(note)
+            .when(unavailable, |row| {
+                row.child("The body isn't an image the viewer can show")
+            })
+    }
+
+    fn render_annotation_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(div().text_color(rgb(0x006c_757d)).child("Highlight:"))
+            .children(HighlightColor::all().into_iter().map(|color| {
+                let is_selected = color == self.annotation_color;
+                div()
+                    .w_4()
+                    .h_4()
+                    .rounded_sm()
+                    .cursor_pointer()
+                    .bg(rgb(color.rgb()))
+                    .border_2()
+                    .border_color(if is_selected {
+                        rgb(0x0021_2529)
+                    } else {
+                        rgb(color.rgb())
+                    })
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            this.annotation_color = color;
+                            cx.notify();
+                        }),
+                    )
+            }))
+            .child(
+                div()
+                    .flex_1()
+                    .border_1()
+                    .border_color(rgb(0x00cc_cccc))
+                    .rounded_md()
+                    .child(self.comment_input.clone()),
+            )
+            .child(
+                div()
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x0000_7acc))
+                    .text_color(rgb(0x00ff_ffff))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x0000_56b3)))
+                    .child("Add")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.add_annotation(cx);
+                        }),
+                    ),
+            )
+    }
+
+    // 右键找到链接后显示的操作条
+    fn render_link_offer(&self, link: &Link, cx: &mut Context<Self>) -> impl IntoElement {
+        let label = match link {
+            Link::Url(url) | Link::Path(url) => url.clone(),
+            Link::Id(id) => format!("id {id}"),
+        };
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(div().text_color(rgb(0x006c_757d)).child("Follow:"))
+            .child(
+                div()
+                    .flex_1()
+                    .font_family("monospace")
+                    .overflow_hidden()
+                    .child(label),
+            )
+            .child(
+                div()
+                    .px_3()
+                    .py_1()
+                    .rounded_md()
+                    .bg(rgb(0x0000_7acc))
+                    .text_color(rgb(0x00ff_ffff))
+                    .cursor_pointer()
+                    .hover(|style| style.bg(rgb(0x0000_56b3)))
+                    .child("Open in new tab")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| this.open_link(cx)),
+                    ),
+            )
+            .child(
+                div()
+                    .text_color(rgb(0x006c_757d))
+                    .cursor_pointer()
+                    .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                    .child("✕")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(|this, _event, _window, cx| {
+                            this.link_offer = None;
+                            cx.notify();
+                        }),
+                    ),
+            )
+    }
+
+    // 批注列表
+    fn render_annotation_list(&self, body: &str, cx: &mut Context<Self>) -> impl IntoElement {
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .text_size(px(12.0))
+            .children(
+                self.annotations
+                    .iter()
+                    .enumerate()
+                    .map(|(index, annotation)| {
+                        let range = annotation.range();
+                        let mut snippet: String = body
+                            .chars()
+                            .skip(range.start)
+                            .take(range.len().min(MAX_ANNOTATION_SNIPPET_LENGTH))
+                            .collect();
+                        if range.len() > MAX_ANNOTATION_SNIPPET_LENGTH {
+                            snippet.push_str("...");
+                        }
+
+                        div()
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .px_2()
+                            .py_1()
+                            .rounded_md()
+                            .cursor_pointer()
+                            .hover(|style| style.bg(rgb(0x00f0_f0f0)))
+                            .child(
+                                div()
+                                    .w_3()
+                                    .h_3()
+                                    .rounded_sm()
+                                    .bg(rgb(annotation.color.rgb())),
+                            )
+                            .child(
+                                div()
+                                    .font_family("monospace")
+                                    .text_color(rgb(0x006c_757d))
+                                    .child(snippet.replace('\n', " ")),
+                            )
+                            .child(div().flex_1().child(annotation.comment.clone()))
+                            .when(!self.read_only, |row| {
+                                row.child(
+                                    div()
+                                        .text_color(rgb(0x006c_757d))
+                                        .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                                        .child("✕")
+                                        .on_mouse_up(
+                                            MouseButton::Left,
+                                            cx.listener(move |this, _event, _window, cx| {
+                                                cx.stop_propagation();
+                                                this.remove_annotation(index, cx);
+                                            }),
+                                        ),
+                                )
+                            })
+                            // 点击批注选中对应文本
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    if let Some(range) = this.display_range(&range) {
+                                        this.selected_range = range;
+                                        this.selection_reversed = false;
+                                        cx.notify();
+                                    }
+                                }),
+                            )
+                    }),
+            )
+    }
+
     fn render_selectable_content(
         &self,
         _content: &str,
@@ -228,6 +885,7 @@ impl ResponseViewer {
             .cursor(CursorStyle::IBeam)
             .track_focus(&self.focus_handle(cx))
             .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
+            .on_mouse_down(MouseButton::Right, cx.listener(Self::on_right_mouse_down))
             .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_move(cx.listener(Self::on_mouse_move))
@@ -246,6 +904,15 @@ impl ResponseViewer {
     }
 }
 
+// Maximum length of the highlighted text shown in the annotation list
+const MAX_ANNOTATION_SNIPPET_LENGTH: usize = 40;
+
+// Width of the fold handle column shown for JSON responses
+const FOLD_GUTTER_WIDTH: f32 = 14.0;
+
+// Color of the space, tab and line ending markers
+const WHITESPACE_MARKER_COLOR: u32 = 0x00ad_b5bd;
+
 // Custom text element for rendering multi-line response content with selection
 struct MultiLineTextElement {
     viewer: Entity<ResponseViewer>,
@@ -253,6 +920,12 @@ struct MultiLineTextElement {
 
 struct PrepaintState {
     lines: Vec<(ShapedLine, usize)>,
+    // 文本区域（去掉折叠按钮列后的范围）
+    text_bounds: Bounds<Pixels>,
+    fold_markers: Vec<(usize, ShapedLine)>,
+    // 显示空白字符时画在行尾的换行符标记
+    ending_markers: Vec<(usize, ShapedLine)>,
+    highlights: Vec<PaintQuad>,
     selections: Vec<PaintQuad>,
     cursor: Option<PaintQuad>,
 }
@@ -308,29 +981,114 @@ impl Element for MultiLineTextElement {
         let viewer = self.viewer.read(cx);
         let content = viewer.get_content();
         let selected_range = viewer.selected_range.clone();
+        // 批注按显示文本定位，被折叠隐藏的批注不绘制；批注是对原始响应体
+        // 做的，按其他类型查看时也不绘制
+        let annotations: Vec<(Range<usize>, HighlightColor)> = viewer
+            .annotations
+            .iter()
+            .filter(|_| viewer.view_text.is_none())
+            .filter_map(|annotation| {
+                viewer
+                    .display_range(&annotation.range())
+                    .map(|range| (range, annotation.color))
+            })
+            .collect();
 
         let style = window.text_style();
         let font_size = px(12.0);
         let line_height = window.line_height();
 
-        let lines: Vec<&str> = content.lines().collect();
-        let mut shaped_lines = Vec::new();
-        let mut char_offset = 0;
+        // 显示空白字符时逐字符替换为标记，字符偏移保持不变
+        let show_whitespace = viewer.show_whitespace;
+        let visible_lines: Vec<String> = if show_whitespace {
+            content.lines().map(visible_whitespace).collect()
+        } else {
+            Vec::new()
+        };
+        let lines: Vec<&str> = if show_whitespace {
+            visible_lines.iter().map(String::as_str).collect()
+        } else {
+            content.lines().collect()
+        };
+        let fold_states: Vec<Option<bool>> = (0..lines.len())
+            .map(|line_idx| viewer.fold_state_for_display_line(line_idx))
+            .collect();
 
-        for line in &lines {
+        // 可折叠的 JSON 响应在左侧留出折叠按钮列
+        let gutter = if viewer.fold_regions.is_empty() {
+            px(0.0)
+        } else {
+            px(FOLD_GUTTER_WIDTH)
+        };
+        let bounds = Bounds::new(
+            point(bounds.left() + gutter, bounds.top()),
+            gpui::size(bounds.size.width - gutter, bounds.size.height),
+        );
+
+        let mut fold_markers = Vec::new();
+        for (line_idx, folded) in fold_states.iter().enumerate() {
+            let Some(folded) = folded else {
+                continue;
+            };
+            let marker = if *folded { "▸" } else { "▾" };
             let run = TextRun {
-                len: line.len(),
+                len: marker.len(),
                 font: style.font(),
-                color: style.color,
+                color: rgb(0x0088_8888).into(),
                 background_color: None,
                 underline: None,
                 strikethrough: None,
             };
+            let shaped_marker =
+                window
+                    .text_system()
+                    .shape_line(marker.into(), font_size, &[run], None);
+            fold_markers.push((line_idx, shaped_marker));
+        }
+        let mut ending_markers = Vec::new();
+        if show_whitespace {
+            for (line_idx, (_, ending)) in lines_with_endings(&content).into_iter().enumerate() {
+                let marker = ending.marker();
+                if marker.is_empty() {
+                    continue;
+                }
+                let run = TextRun {
+                    len: marker.len(),
+                    font: style.font(),
+                    color: rgb(WHITESPACE_MARKER_COLOR).into(),
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                };
+                let shaped_marker =
+                    window
+                        .text_system()
+                        .shape_line(marker.into(), font_size, &[run], None);
+                ending_markers.push((line_idx, shaped_marker));
+            }
+        }
+
+        let mut shaped_lines = Vec::new();
+        let mut char_offset = 0;
+
+        for (line, source) in lines.iter().zip(content.lines()) {
+            let runs = if show_whitespace {
+                whitespace_runs(source, &style)
+            } else {
+                vec![TextRun {
+                    len: line.len(),
+                    font: style.font(),
+                    color: style.color,
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                }]
+            };
 
             let shaped_line = window.text_system().shape_line(
                 (*line).to_string().into(),
                 font_size.into(),
-                &[run],
+                &runs,
                 None,
             );
 
@@ -391,86 +1149,34 @@ impl Element for MultiLineTextElement {
                 current_offset += line_len + 1;
             }
         } else if !selected_range.is_empty() && !content.is_empty() {
-            let mut current_offset = 0;
-
-            for (line_idx, (shaped_line, _)) in shaped_lines.iter().enumerate() {
-                let line_len = if line_idx < lines.len() {
-                    lines[line_idx].chars().count()
-                } else {
-                    0
-                };
-
-                let line_start = current_offset;
-                let line_end = current_offset + line_len;
-
-                if selected_range.end > line_start && selected_range.start < line_end {
-                    let sel_start = selected_range.start.max(line_start).min(line_end);
-                    let sel_end = selected_range.end.max(line_start).min(line_end);
-
-                    let local_start = sel_start - line_start;
-                    let local_end = sel_end - line_start;
-
-                    let start_x = if local_start == 0 {
-                        px(0.0)
-                    } else {
-                        let text_before: String =
-                            lines[line_idx].chars().take(local_start).collect();
-                        let temp_run = TextRun {
-                            len: text_before.len(),
-                            font: style.font(),
-                            color: style.color,
-                            background_color: None,
-                            underline: None,
-                            strikethrough: None,
-                        };
-                        let temp_line = window.text_system().shape_line(
-                            text_before.into(),
-                            font_size.into(),
-                            &[temp_run],
-                            None,
-                        );
-                        temp_line.x_for_index(temp_line.len())
-                    };
-
-                    let end_x = if local_end == 0 {
-                        px(0.0)
-                    } else if local_end >= line_len {
-                        shaped_line.width
-                    } else {
-                        let text_before: String = lines[line_idx].chars().take(local_end).collect();
-                        let temp_run = TextRun {
-                            len: text_before.len(),
-                            font: style.font(),
-                            color: style.color,
-                            background_color: None,
-                            underline: None,
-                            strikethrough: None,
-                        };
-                        let temp_line = window.text_system().shape_line(
-                            text_before.into(),
-                            font_size.into(),
-                            &[temp_run],
-                            None,
-                        );
-                        temp_line.x_for_index(temp_line.len())
-                    };
-
-                    selections.push(fill(
-                        Bounds::from_corners(
-                            point(
-                                bounds.left() + start_x,
-                                bounds.top() + line_height * line_idx as f32,
-                            ),
-                            point(
-                                bounds.left() + end_x,
-                                bounds.top() + line_height * (line_idx + 1) as f32,
-                            ),
-                        ),
-                        rgba(0x3366_ff55),
-                    ));
-                }
+            selections = range_quads(
+                &selected_range,
+                &lines,
+                &shaped_lines,
+                bounds,
+                line_height,
+                &style,
+                font_size,
+                window,
+                rgba(0x3366_ff55),
+            );
+        }
 
-                current_offset += line_len + 1;
+        // 批注高亮
+        let mut highlights = Vec::new();
+        if !content.is_empty() {
+            for (range, color) in &annotations {
+                highlights.extend(range_quads(
+                    range,
+                    &lines,
+                    &shaped_lines,
+                    bounds,
+                    line_height,
+                    &style,
+                    font_size,
+                    window,
+                    rgb(color.rgb()),
+                ));
             }
         }
 
@@ -481,6 +1187,10 @@ impl Element for MultiLineTextElement {
 
         PrepaintState {
             lines: shaped_lines,
+            text_bounds: bounds,
+            fold_markers,
+            ending_markers,
+            highlights,
             selections,
             cursor,
         }
@@ -498,6 +1208,10 @@ impl Element for MultiLineTextElement {
     ) {
         let line_height = window.line_height();
 
+        for highlight in &prepaint.highlights {
+            window.paint_quad(highlight.clone());
+        }
+
         for selection in &prepaint.selections {
             window.paint_quad(selection.clone());
         }
@@ -508,14 +1222,148 @@ impl Element for MultiLineTextElement {
 
         for (line_idx, (shaped_line, _)) in prepaint.lines.iter().enumerate() {
             let origin = point(
-                bounds.origin.x,
-                bounds.origin.y + line_height * line_idx as f32,
+                prepaint.text_bounds.origin.x,
+                prepaint.text_bounds.origin.y + line_height * line_idx as f32,
             );
             shaped_line
                 .paint(origin, line_height, TextAlign::Left, None, window, cx)
                 .ok();
         }
+
+        for (line_idx, marker) in &prepaint.ending_markers {
+            let Some((shaped_line, _)) = prepaint.lines.get(*line_idx) else {
+                continue;
+            };
+            let origin = point(
+                prepaint.text_bounds.origin.x + shaped_line.width,
+                prepaint.text_bounds.origin.y + line_height * *line_idx as f32,
+            );
+            marker
+                .paint(origin, line_height, TextAlign::Left, None, window, cx)
+                .ok();
+        }
+
+        for (line_idx, marker) in &prepaint.fold_markers {
+            let origin = point(
+                bounds.origin.x,
+                bounds.origin.y + line_height * *line_idx as f32,
+            );
+            marker
+                .paint(origin, line_height, TextAlign::Left, None, window, cx)
+                .ok();
+        }
+    }
+}
+
+// Runs for a line shown with visible whitespace, dimming the markers that
+// replace the whitespace in `source`
+fn whitespace_runs(source: &str, style: &TextStyle) -> Vec<TextRun> {
+    let mut runs: Vec<TextRun> = Vec::new();
+    let mut dimmed_run = None;
+    for c in source.chars() {
+        let (len, dimmed) = match visible_char(c) {
+            Some(marker) => (marker.len_utf8(), true),
+            None => (c.len_utf8(), false),
+        };
+        match runs.last_mut() {
+            Some(run) if dimmed_run == Some(dimmed) => run.len += len,
+            _ => {
+                runs.push(TextRun {
+                    len,
+                    font: style.font(),
+                    color: if dimmed {
+                        rgb(WHITESPACE_MARKER_COLOR).into()
+                    } else {
+                        style.color
+                    },
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                });
+                dimmed_run = Some(dimmed);
+            }
+        }
     }
+    runs
+}
+
+// Width of the first `char_count` characters of `line`
+fn x_for_char(
+    window: &mut Window,
+    style: &TextStyle,
+    font_size: Pixels,
+    line: &str,
+    char_count: usize,
+) -> Pixels {
+    if char_count == 0 {
+        return px(0.0);
+    }
+    let text_before: String = line.chars().take(char_count).collect();
+    let run = TextRun {
+        len: text_before.len(),
+        font: style.font(),
+        color: style.color,
+        background_color: None,
+        underline: None,
+        strikethrough: None,
+    };
+    let shaped = window
+        .text_system()
+        .shape_line(text_before.into(), font_size, &[run], None);
+    shaped.x_for_index(shaped.len())
+}
+
+// Quads covering a range of character offsets, one per line it spans
+#[allow(clippy::too_many_arguments)]
+fn range_quads(
+    range: &Range<usize>,
+    lines: &[&str],
+    shaped_lines: &[(ShapedLine, usize)],
+    bounds: Bounds<Pixels>,
+    line_height: Pixels,
+    style: &TextStyle,
+    font_size: Pixels,
+    window: &mut Window,
+    color: Rgba,
+) -> Vec<PaintQuad> {
+    let mut quads = Vec::new();
+    let mut current_offset = 0;
+
+    for (line_idx, (shaped_line, _)) in shaped_lines.iter().enumerate() {
+        let line_len = lines.get(line_idx).map_or(0, |line| line.chars().count());
+        let line_start = current_offset;
+        let line_end = current_offset + line_len;
+
+        if range.end > line_start && range.start < line_end {
+            let local_start = range.start.max(line_start).min(line_end) - line_start;
+            let local_end = range.end.max(line_start).min(line_end) - line_start;
+
+            let start_x = x_for_char(window, style, font_size, lines[line_idx], local_start);
+            let end_x = if local_end >= line_len {
+                shaped_line.width
+            } else {
+                x_for_char(window, style, font_size, lines[line_idx], local_end)
+            };
+
+            quads.push(fill(
+                Bounds::from_corners(
+                    point(
+                        bounds.left() + start_x,
+                        bounds.top() + line_height * line_idx as f32,
+                    ),
+                    point(
+                        bounds.left() + end_x,
+                        bounds.top() + line_height * (line_idx + 1) as f32,
+                    ),
+                ),
+                color,
+            ));
+        }
+
+        current_offset += line_len + 1;
+    }
+
+    quads
 }
 
 impl Render for ResponseViewer {
@@ -526,9 +1374,61 @@ impl Render for ResponseViewer {
             .gap_2()
             .child(
                 div()
-                    .child("Response")
-                    .text_size(px(16.0))
-                    .font_weight(FontWeight::MEDIUM),
+                    .flex()
+                    .items_center()
+                    .gap_4()
+                    .child(
+                        div()
+                            .child("Response")
+                            .text_size(px(16.0))
+                            .font_weight(FontWeight::MEDIUM),
+                    )
+                    .child(
+                        div()
+                            .id("show-whitespace")
+                            .flex()
+                            .items_center()
+                            .gap_1()
+                            .cursor_pointer()
+                            .text_size(px(12.0))
+                            .text_color(rgb(0x006c_757d))
+                            .child(
+                                div()
+                                    .w_4()
+                                    .h_4()
+                                    .flex()
+                                    .items_center()
+                                    .justify_center()
+                                    .border_1()
+                                    .border_color(rgb(0x00cc_cccc))
+                                    .rounded_sm()
+                                    .text_color(rgb(0x00ff_ffff))
+                                    .when(self.show_whitespace, |checkbox| {
+                                        checkbox.bg(rgb(0x0000_7acc)).child("✓")
+                                    }),
+                            )
+                            .child("Show whitespace")
+                            .on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| {
+                                    this.toggle_whitespace(cx);
+                                }),
+                            ),
+                    )
+                    .when_some(
+                        match self.shown_body() {
+                            Some(body) if self.show_whitespace => line_ending_summary(body),
+                            _ => None,
+                        },
+                        |row, summary| {
+                            row.child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(rgb(0x006c_757d))
+                                    .child(format!("Line endings: {summary}")),
+                            )
+                        },
+                    ),
             )
             .child(match &self.state {
                 ResponseState::NotSent => {
@@ -567,23 +1467,69 @@ impl Render for ResponseViewer {
                                 .child("请稍等，正在处理请求..."),
                         )
                 }
-                ResponseState::Success { status, body } => {
-                    // 成功响应状态
+                ResponseState::Success {
+                    status,
+                    headers,
+                    body,
+                } => {
+                    // 成功响应状态；按其他类型查看时隐藏批注
+                    let annotated = self.view_text.is_none();
+                    let shown_image = self
+                        .image
+                        .clone()
+                        .filter(|_| self.view_as.or(self.detected_kind) == Some(BodyKind::Image));
                     div()
                         .flex()
                         .flex_col()
                         .gap_2()
                         .child(
                             div()
-                                .child(format!("Status: {status}"))
+                                .child(format!("Status: {status} {}", reason_phrase(*status)))
                                 .text_color(if *status < 400 {
                                     rgb(0x0028_a745) // 成功
                                 } else {
                                     rgb(0x00dc_3545) // 客户端/服务器错误
                                 })
-                                .font_weight(FontWeight::MEDIUM),
+                                .font_weight(FontWeight::MEDIUM)
+                                .cursor_pointer()
+                                .hover(|style| style.underline())
+                                .on_mouse_up(
+                                    MouseButton::Left,
+                                    cx.listener(|this, _event, _window, cx| {
+                                        this.toggle_status_explanation(cx);
+                                    }),
+                                ),
+                        )
+                        .children(
+                            explain_status(*status)
+                                .filter(|_| self.show_status_explanation)
+                                .map(|explanation| self.render_status_explanation(explanation)),
                         )
-                        .child(self.render_selectable_content(body, cx))
+                        .children(self.render_partial_content(*status, headers))
+                        .child(self.render_view_as(headers))
+                        .when(
+                            annotated && !self.read_only && !self.selected_range.is_empty(),
+                            |div| div.child(self.render_annotation_toolbar(cx)),
+                        )
+                        .when_some(self.link_offer.as_ref(), |div, link| {
+                            div.child(self.render_link_offer(link, cx))
+                        })
+                        .map(|content| match shown_image {
+                            Some(path) => content.child(
+                                div()
+                                    .w_full()
+                                    .p_2()
+                                    .bg(rgb(0x00f8_f9fa))
+                                    .border_1()
+                                    .border_color(rgb(0x00cc_cccc))
+                                    .rounded_md()
+                                    .child(img(path).max_h(px(256.0))),
+                            ),
+                            None => content.child(self.render_selectable_content(body, cx)),
+                        })
+                        .when(annotated && !self.annotations.is_empty(), |div| {
+                            div.child(self.render_annotation_list(body, cx))
+                        })
                 }
                 ResponseState::Error { message } => {
                     // 错误状态
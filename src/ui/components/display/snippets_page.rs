@@ -0,0 +1,414 @@
+use crate::models::{AssertionSnippet, SnippetLibrary};
+use crate::ui::components::body_input::{BodyInput, BodyType};
+use crate::ui::components::header_input::HeaderInput;
+use crate::utils::assertions::{run_script, AssertionContext, AssertionOutcome};
+use gpui::{
+    div, prelude::FluentBuilder, px, rgb, AppContext, Context, Entity, EventEmitter, FontWeight,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, Render,
+    StatefulInteractiveElement, Styled, Window,
+};
+use std::time::Duration;
+
+/// Event emitted when snippets are saved or removed
+#[derive(Debug, Clone)]
+pub enum SnippetsPageEvent {
+    /// The library changed - it should be saved. Carries a short
+    /// description such as `Saved Pagination headers`
+    Changed(String),
+}
+
+/// Result of one attached snippet for the last response
+#[derive(Debug, Clone)]
+struct SnippetResult {
+    name: String,
+    outcomes: Result<Vec<AssertionOutcome>, String>,
+}
+
+/// Library of reusable assertion snippets; the current request attaches
+/// snippets by reference and they are checked against every response
+pub struct SnippetsPage {
+    library: SnippetLibrary,
+    // Snippets attached to the request in the editor
+    attached: Vec<u64>,
+    // Snippet being edited, `None` while writing a new one
+    selected: Option<u64>,
+    name_input: Entity<HeaderInput>,
+    script_input: Entity<BodyInput>,
+    results: Vec<SnippetResult>,
+    error: Option<String>,
+}
+
+impl EventEmitter<SnippetsPageEvent> for SnippetsPage {}
+
+impl SnippetsPage {
+    pub fn new(cx: &mut Context<Self>) -> Self {
+        Self {
+            library: SnippetLibrary::new(),
+            attached: Vec::new(),
+            selected: None,
+            name_input: cx
+                .new(|cx| HeaderInput::new(cx).with_placeholder("Name, e.g. JSON API envelope")),
+            script_input: cx.new(|cx| {
+                let mut input = BodyInput::new(cx);
+                input.set_type(BodyType::Raw, cx);
+                input
+            }),
+            results: Vec::new(),
+            error: None,
+        }
+    }
+
+    pub fn library(&self) -> &SnippetLibrary {
+        &self.library
+    }
+
+    /// Show the snippets of another profile
+    pub fn set_library(&mut self, library: SnippetLibrary, cx: &mut Context<Self>) {
+        self.library = library;
+        self.results.clear();
+        self.error = None;
+        self.new_snippet(cx);
+    }
+
+    /// Snippets attached to the current request that still exist
+    pub fn attached(&self) -> Vec<u64> {
+        self.attached
+            .iter()
+            .copied()
+            .filter(|id| self.library.get(*id).is_some())
+            .collect()
+    }
+
+    /// Show the attachments of another request (and forget the results of
+    /// the previous one)
+    pub fn set_attached(&mut self, attached: Vec<u64>, cx: &mut Context<Self>) {
+        self.attached = attached;
+        self.results.clear();
+        cx.notify();
+    }
+
+    /// Check the attached snippets against a response, returning how many
+    /// assertions failed
+    pub fn check_response(
+        &mut self,
+        status: u16,
+        headers: &[(String, String)],
+        body: &str,
+        latency: Duration,
+        cx: &mut Context<Self>,
+    ) -> usize {
+        let context = AssertionContext::new(status, headers, body).with_latency(latency);
+        self.results = self
+            .attached()
+            .into_iter()
+            .filter_map(|id| self.library.get(id))
+            .map(|snippet| SnippetResult {
+                name: snippet.name.clone(),
+                outcomes: run_script(&snippet.script, &context).map_err(|e| e.to_string()),
+            })
+            .collect();
+        cx.notify();
+        self.results
+            .iter()
+            .map(|result| match &result.outcomes {
+                Ok(outcomes) => outcomes.iter().filter(|outcome| !outcome.passed()).count(),
+                Err(_) => 1,
+            })
+            .sum()
+    }
+
+    fn toggle_attached(&mut self, id: u64, cx: &mut Context<Self>) {
+        if let Some(index) = self.attached.iter().position(|attached| *attached == id) {
+            self.attached.remove(index);
+        } else {
+            self.attached.push(id);
+        }
+        cx.notify();
+    }
+
+    fn select(&mut self, snippet: &AssertionSnippet, cx: &mut Context<Self>) {
+        self.selected = Some(snippet.id);
+        self.name_input
+            .update(cx, |input, cx| input.set_content(snippet.name.clone(), cx));
+        self.script_input.update(cx, |input, cx| {
+            input.set_content(snippet.script.clone(), cx)
+        });
+        self.error = None;
+        cx.notify();
+    }
+
+    fn new_snippet(&mut self, cx: &mut Context<Self>) {
+        self.selected = None;
+        self.name_input.update(cx, |input, cx| input.clear(cx));
+        self.script_input.update(cx, |input, cx| input.clear(cx));
+        cx.notify();
+    }
+
+    fn save(&mut self, cx: &mut Context<Self>) {
+        let name = self.name_input.read(cx).get_content().to_string();
+        let script = self.script_input.read(cx).get_content();
+        let result = match self.selected {
+            Some(id) => self.library.update(id, &name, &script).map(|_| id),
+            None => self.library.add(&name, &script),
+        };
+        match result {
+            Ok(id) => {
+                let name = self
+                    .library
+                    .get(id)
+                    .map(|snippet| snippet.name.clone())
+                    .unwrap_or_default();
+                tracing::info!("🧪 SnippetsPage - 已保存断言片段: {name}");
+                self.selected = Some(id);
+                self.error = None;
+                cx.emit(SnippetsPageEvent::Changed(format!("Saved {name}")));
+            }
+            Err(e) => self.error = Some(e.to_string()),
+        }
+        cx.notify();
+    }
+
+    fn remove(&mut self, id: u64, cx: &mut Context<Self>) {
+        if let Some(snippet) = self.library.remove(id) {
+            tracing::info!("🧪 SnippetsPage - 已删除断言片段: {}", snippet.name);
+            self.attached.retain(|attached| *attached != id);
+            if self.selected == Some(id) {
+                self.new_snippet(cx);
+            }
+            cx.emit(SnippetsPageEvent::Changed(format!(
+                "Removed {}",
+                snippet.name
+            )));
+            cx.notify();
+        }
+    }
+
+    fn render_snippet_row(
+        &self,
+        index: usize,
+        snippet: &AssertionSnippet,
+        cx: &mut Context<Self>,
+    ) -> impl IntoElement {
+        let id = snippet.id;
+        let is_attached = self.attached.contains(&id);
+        let checks = snippet
+            .script
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.trim().starts_with('#'))
+            .count();
+        let edit = snippet.clone();
+
+        div()
+            .id(("snippet-row", index))
+            .flex()
+            .items_center()
+            .gap_2()
+            .px_2()
+            .py_1()
+            .rounded_md()
+            .cursor_pointer()
+            .when(self.selected == Some(id), |row| row.bg(rgb(0x00e7_f1ff)))
+            .hover(|style| style.bg(rgb(0x00f0_f0f0)))
+            .child(
+                div()
+                    .id(("snippet-attach", index))
+                    .w_4()
+                    .h_4()
+                    .flex()
+                    .items_center()
+                    .justify_center()
+                    .border_1()
+                    .border_color(rgb(0x00cc_cccc))
+                    .rounded_sm()
+                    .text_color(rgb(0x00ff_ffff))
+                    .when(is_attached, |checkbox| {
+                        checkbox.bg(rgb(0x0000_7acc)).child("✓")
+                    })
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            cx.stop_propagation();
+                            this.toggle_attached(id, cx);
+                        }),
+                    ),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .font_weight(FontWeight::MEDIUM)
+                    .child(snippet.name.clone()),
+            )
+            .child(div().text_color(rgb(0x006c_757d)).child(match checks {
+                1 => "1 check".to_string(),
+                checks => format!("{checks} checks"),
+            }))
+            .child(
+                div()
+                    .id(("snippet-remove", index))
+                    .text_color(rgb(0x006c_757d))
+                    .hover(|style| style.text_color(rgb(0x00dc_3545)))
+                    .child("✕")
+                    .on_mouse_up(
+                        MouseButton::Left,
+                        cx.listener(move |this, _event, _window, cx| {
+                            cx.stop_propagation();
+                            this.remove(id, cx);
+                        }),
+                    ),
+            )
+            .on_mouse_up(
+                MouseButton::Left,
+                cx.listener(move |this, _event, _window, cx| this.select(&edit, cx)),
+            )
+    }
+
+    fn render_result(&self, result: &SnippetResult) -> impl IntoElement {
+        let outcomes = match &result.outcomes {
+            Ok(outcomes) => outcomes.clone(),
+            Err(error) => vec![AssertionOutcome {
+                source: "script".to_string(),
+                failure: Some(error.clone()),
+            }],
+        };
+        let passed = outcomes.iter().filter(|outcome| outcome.passed()).count();
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_1()
+            .child(
+                div()
+                    .font_weight(FontWeight::MEDIUM)
+                    .text_color(rgb(if passed == outcomes.len() {
+                        0x0028_a745
+                    } else {
+                        0x00dc_3545
+                    }))
+                    .child(format!(
+                        "{} - {passed}/{} passed",
+                        result.name,
+                        outcomes.len()
+                    )),
+            )
+            .children(outcomes.into_iter().map(|outcome| {
+                let (mark, color) = if outcome.passed() {
+                    ("✓", 0x0028_a745)
+                } else {
+                    ("✗", 0x00dc_3545)
+                };
+                div()
+                    .flex()
+                    .flex_col()
+                    .pl_2()
+                    .child(
+                        div()
+                            .flex()
+                            .gap_1()
+                            .child(div().text_color(rgb(color)).child(mark))
+                            .child(div().font_family("monospace").child(outcome.source)),
+                    )
+                    .children(
+                        outcome.failure.map(|failure| {
+                            div().pl_4().text_color(rgb(0x006c_757d)).child(failure)
+                        }),
+                    )
+            }))
+    }
+}
+
+impl Render for SnippetsPage {
+    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let button = |id: &'static str, label: &'static str, color: u32, hover: u32| {
+            div()
+                .id(id)
+                .px_3()
+                .py_1()
+                .rounded_md()
+                .bg(rgb(color))
+                .text_color(rgb(0x00ff_ffff))
+                .cursor_pointer()
+                .hover(move |style| style.bg(rgb(hover)))
+                .child(label)
+        };
+
+        div()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .text_size(px(12.0))
+            .child(
+                div()
+                    .text_size(px(16.0))
+                    .font_weight(FontWeight::MEDIUM)
+                    .child("Assertions"),
+            )
+            .child(div().text_color(rgb(0x006c_757d)).child(
+                "Tick a snippet to check it against every response of this request. Requests refer to snippets, so editing one updates all of them.",
+            ))
+            .children(
+                self.library
+                    .snippets()
+                    .iter()
+                    .enumerate()
+                    .map(|(index, snippet)| self.render_snippet_row(index, snippet, cx)),
+            )
+            .child(
+                div()
+                    .pt_2()
+                    .border_t_1()
+                    .border_color(rgb(0x00e9_ecef))
+                    .font_weight(FontWeight::MEDIUM)
+                    .child(if self.selected.is_some() {
+                        "Edit snippet"
+                    } else {
+                        "New snippet"
+                    }),
+            )
+            .child(
+                div()
+                    .border_1()
+                    .border_color(rgb(0x00cc_cccc))
+                    .rounded_md()
+                    .child(self.name_input.clone()),
+            )
+            .child(div().text_color(rgb(0x006c_757d)).child(
+                "One check per line: status in 200..299, header Link exists, json data is array, json meta.page >= 1, body contains \"ok\"",
+            ))
+            .child(self.script_input.clone())
+            .children(
+                self.error
+                    .clone()
+                    .map(|error| div().text_color(rgb(0x00dc_3545)).child(error)),
+            )
+            .child(
+                div()
+                    .flex()
+                    .gap_2()
+                    .child(
+                        button("snippet-save", "Save", 0x0000_7acc, 0x0000_56b3).on_mouse_up(
+                            MouseButton::Left,
+                            cx.listener(|this, _event, _window, cx| this.save(cx)),
+                        ),
+                    )
+                    .when(self.selected.is_some(), |buttons| {
+                        buttons.child(
+                            button("snippet-new", "New", 0x006c_757d, 0x005a_6268).on_mouse_up(
+                                MouseButton::Left,
+                                cx.listener(|this, _event, _window, cx| this.new_snippet(cx)),
+                            ),
+                        )
+                    }),
+            )
+            .when(!self.results.is_empty(), |page| {
+                page.child(
+                    div()
+                        .pt_2()
+                        .border_t_1()
+                        .border_color(rgb(0x00e9_ecef))
+                        .font_weight(FontWeight::MEDIUM)
+                        .child("Last response"),
+                )
+                .children(self.results.iter().map(|result| self.render_result(result)))
+            })
+    }
+}
@@ -1,13 +1,24 @@
+use crate::ui::components::checkbox::{master_checkbox, MasterCheckState, ToggleRow};
+use crate::ui::components::drag_row::{drag_handle, DraggedRow, RowTable};
+use crate::utils::clipboard::parse_pasted_rows;
+use crate::utils::faker::{FakeKind, Faker};
+use crate::utils::formatter::{format_count, format_size};
+use crate::utils::multipart::FormPart;
+use crate::utils::paste::{chunk_ranges, PasteSize};
+use crate::utils::reorder::move_item;
 use form_urlencoded;
 use gpui::{
     actions, div, fill, hsla, point, prelude::FluentBuilder, px, relative, rgb, rgba, size, App,
-    Bounds, ClipboardItem, Context, CursorStyle, Element, ElementId, ElementInputHandler, Entity,
-    EntityInputHandler, EventEmitter, FocusHandle, Focusable, GlobalElementId, InteractiveElement,
-    IntoElement, KeyBinding, KeyDownEvent, LayoutId, MouseButton, MouseDownEvent, MouseMoveEvent,
-    MouseUpEvent, PaintQuad, ParentElement, Pixels, Point, Render, ShapedLine, SharedString, Style,
-    Styled, TextAlign, TextRun, UTF16Selection, Window,
+    AppContext, Bounds, ClipboardItem, Context, CursorStyle, Element, ElementId,
+    ElementInputHandler, Entity, EntityInputHandler, EventEmitter, FocusHandle, Focusable,
+    GlobalElementId, InteractiveElement, IntoElement, KeyBinding, KeyDownEvent, LayoutId,
+    MouseButton, MouseDownEvent, MouseMoveEvent, MouseUpEvent, PaintQuad, ParentElement, Pixels,
+    Point, Render, ShapedLine, SharedString, StatefulInteractiveElement, Style, Styled, Task,
+    TextAlign, TextRun, UTF16Selection, Window,
 };
+use serde_json::{Map, Number, Value};
 use std::ops::Range;
+use std::time::Duration;
 use unicode_segmentation::*;
 
 actions!(
@@ -16,6 +27,7 @@ actions!(
         Backspace,
         Delete,
         Enter,
+        NewLine,
         Escape,
         Tab,
         ShiftTab,
@@ -48,11 +60,204 @@ pub enum BodyInputEvent {
     ValueChanged(String),
 }
 
+// Pause between the chunks of a large paste, long enough for a frame to be
+// drawn in between
+const PASTE_CHUNK_DELAY: Duration = Duration::from_millis(16);
+
+/// A large paste being inserted into the JSON editor in chunks
+#[derive(Debug, Clone, Copy)]
+struct PasteProgress {
+    start: usize,
+    inserted: usize,
+    total: usize,
+}
+
+// Content-Types a multipart part can be given, after "detect"
+const PART_CONTENT_TYPES: [&str; 6] = [
+    "application/json",
+    "text/plain",
+    "application/xml",
+    "text/csv",
+    "application/octet-stream",
+    "image/png",
+];
+
+/// How a form-data value is typed when the form is converted to JSON
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FormValueType {
+    #[default]
+    Text,
+    Number,
+    Boolean,
+}
+
+impl FormValueType {
+    pub fn label(self) -> &'static str {
+        match self {
+            FormValueType::Text => "Text",
+            FormValueType::Number => "Number",
+            FormValueType::Boolean => "Boolean",
+        }
+    }
+
+    /// The type after this one, as the type hint is clicked through
+    pub fn next(self) -> Self {
+        match self {
+            FormValueType::Text => FormValueType::Number,
+            FormValueType::Number => FormValueType::Boolean,
+            FormValueType::Boolean => FormValueType::Text,
+        }
+    }
+
+    /// JSON value of `value`; one that is not a valid number or boolean
+    /// stays a string
+    pub fn to_json(self, value: &str) -> Value {
+        let text = || Value::String(value.to_string());
+        match self {
+            FormValueType::Text => text(),
+            FormValueType::Number => {
+                let trimmed = value.trim();
+                trimmed
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .ok()
+                    .or_else(|| {
+                        trimmed
+                            .parse::<f64>()
+                            .ok()
+                            .and_then(Number::from_f64)
+                            .map(Value::Number)
+                    })
+                    .unwrap_or_else(text)
+            }
+            FormValueType::Boolean => match value.trim().to_ascii_lowercase().as_str() {
+                "true" | "1" | "yes" | "on" => Value::Bool(true),
+                "false" | "0" | "no" | "off" => Value::Bool(false),
+                _ => text(),
+            },
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FormDataEntry {
     pub key: String,
+    /// May span several lines
     pub value: String,
     pub enabled: bool,
+    pub value_type: FormValueType,
+    /// Content-Type of the part when sent as multipart (detected when unset)
+    pub content_type: Option<String>,
+}
+
+impl FormDataEntry {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+            enabled: true,
+            value_type: FormValueType::Text,
+            content_type: None,
+        }
+    }
+}
+
+/// The enabled entries as a JSON object, typed by their hints; a key given
+/// more than once becomes an array
+pub fn form_to_json(entries: &[FormDataEntry]) -> Value {
+    let mut object = Map::new();
+    for entry in entries
+        .iter()
+        .filter(|entry| entry.enabled && !entry.key.is_empty())
+    {
+        let value = entry.value_type.to_json(&entry.value);
+        match object.get_mut(&entry.key) {
+            Some(Value::Array(values)) => values.push(value),
+            Some(existing) => {
+                let first = existing.take();
+                *existing = Value::Array(vec![first, value]);
+            }
+            None => {
+                object.insert(entry.key.clone(), value);
+            }
+        }
+    }
+    Value::Object(object)
+}
+
+/// Put pasted rows into the table at `index`: the first replaces that row
+/// if it is still empty, the rest go right after it
+fn paste_rows(entries: &mut Vec<FormDataEntry>, index: usize, rows: Vec<(String, String)>) {
+    let mut at = index.min(entries.len());
+    let replace_first = entries
+        .get(at)
+        .is_some_and(|entry| entry.key.is_empty() && entry.value.is_empty());
+    if !replace_first {
+        at = (at + 1).min(entries.len());
+    }
+    let pasted = rows
+        .into_iter()
+        .map(|(key, value)| FormDataEntry::new(key, value));
+    entries.splice(at..at + usize::from(replace_first), pasted);
+}
+
+/// The part Content-Type after `current` in the list, back to detecting it
+/// after the last one
+fn next_part_content_type(current: Option<&str>) -> Option<&'static str> {
+    let next = match current {
+        None => 0,
+        Some(current) => match PART_CONTENT_TYPES
+            .iter()
+            .position(|known| *known == current)
+        {
+            Some(index) => index + 1,
+            None => return None,
+        },
+    };
+    PART_CONTENT_TYPES.get(next).copied()
+}
+
+/// Line and byte column of `offset` in a (multi-line) form-data value
+fn line_and_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (before.matches('\n').count(), before.len() - line_start)
+}
+
+/// Status line for text being edited: the cursor position (1-based, column
+/// in characters) when there is one, then character, word and line counts
+fn editor_status(text: &str, cursor: Option<usize>) -> String {
+    let count = |count: usize, noun: &str| {
+        let plural = if count == 1 { "" } else { "s" };
+        format!("{} {noun}{plural}", format_count(count))
+    };
+    let mut parts = Vec::new();
+    if let Some(cursor) = cursor {
+        let cursor = cursor.min(text.len());
+        let (line, column) = line_and_column(text, cursor);
+        let column = text[cursor - column..cursor].chars().count();
+        parts.push(format!("Ln {}, Col {}", line + 1, column + 1));
+    }
+    parts.push(count(text.chars().count(), "char"));
+    parts.push(count(text.split_whitespace().count(), "word"));
+    parts.push(count(text.split('\n').count(), "line"));
+    parts.join(" • ")
+}
+
+/// Offset in the text laid out as `lines` that is closest to `position`
+fn index_for_position(
+    lines: &[ShapedLine],
+    bounds: &Bounds<Pixels>,
+    position: Point<Pixels>,
+) -> usize {
+    let line_height = bounds.size.height / lines.len() as f32;
+    let line_index = ((position.y - bounds.top()) / line_height).floor() as usize;
+    let line_index = line_index.min(lines.len() - 1);
+    let line_start: usize = lines[..line_index]
+        .iter()
+        .map(|line| line.text.len() + 1) // +1 for newline
+        .sum();
+    line_start + lines[line_index].closest_index_for_x(position.x - bounds.left())
 }
 
 pub struct BodyInput {
@@ -80,10 +285,20 @@ pub struct BodyInput {
     form_value_selection_reversed: bool,
     form_value_is_selecting: bool,
     // FormData text layout for precise mouse positioning
-    form_key_last_layout: Option<ShapedLine>,
+    form_key_last_layout: Vec<ShapedLine>,
     form_key_last_bounds: Option<Bounds<Pixels>>,
-    form_value_last_layout: Option<ShapedLine>,
+    form_value_last_layout: Vec<ShapedLine>,
     form_value_last_bounds: Option<Bounds<Pixels>>,
+    // Test data generator for "insert random"
+    faker: Faker,
+    // Large paste being inserted, and the task inserting it
+    paste_progress: Option<PasteProgress>,
+    paste_task: Option<Task<()>>,
+    // Very large paste waiting for "attach as body" or "paste anyway"
+    offered_paste: Option<String>,
+    // Very large paste sent as the JSON body instead of the editor content,
+    // kept in memory so it is never laid out
+    attached_paste: Option<String>,
 }
 
 impl EventEmitter<BodyInputEvent> for BodyInput {}
@@ -165,7 +380,7 @@ impl EntityInputHandler for BodyInput {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
-        if self.current_type != BodyType::Json {
+        if self.current_type != BodyType::Json || self.json_locked() {
             return;
         }
 
@@ -233,11 +448,7 @@ impl BodyInput {
             focus_handle: cx.focus_handle(),
             current_type: BodyType::Json,
             json_content: String::new(),
-            form_data_entries: vec![FormDataEntry {
-                key: String::new(),
-                value: String::new(),
-                enabled: true,
-            }],
+            form_data_entries: vec![FormDataEntry::new("", "")],
             raw_content: String::new(),
             editing_key_index: None,
             editing_value_index: None,
@@ -255,10 +466,15 @@ impl BodyInput {
             form_value_selected_range: 0..0,
             form_value_selection_reversed: false,
             form_value_is_selecting: false,
-            form_key_last_layout: None,
+            form_key_last_layout: Vec::new(),
             form_key_last_bounds: None,
-            form_value_last_layout: None,
+            form_value_last_layout: Vec::new(),
             form_value_last_bounds: None,
+            faker: Faker::new(),
+            paste_progress: None,
+            paste_task: None,
+            offered_paste: None,
+            attached_paste: None,
         }
     }
 
@@ -280,7 +496,7 @@ impl BodyInput {
 
     pub fn is_empty(&self) -> bool {
         match &self.current_type {
-            BodyType::Json => self.json_content.is_empty(),
+            BodyType::Json => self.json_content.is_empty() && self.attached_paste.is_none(),
             BodyType::Raw => self.raw_content.is_empty(),
             BodyType::FormData => self
                 .form_data_entries
@@ -304,14 +520,37 @@ impl BodyInput {
 
     pub fn get_content(&self) -> String {
         match &self.current_type {
-            BodyType::Json => self.json_content.clone(),
+            BodyType::Json => self
+                .attached_paste
+                .clone()
+                .unwrap_or_else(|| self.json_content.clone()),
             BodyType::Raw => self.raw_content.clone(),
             BodyType::FormData => self.get_form_data_as_string(),
         }
     }
 
+    /// `get_content` 的字节数，JSON 和 Raw 请求体不复制内容
+    pub fn content_len(&self) -> usize {
+        match &self.current_type {
+            BodyType::Json => self
+                .attached_paste
+                .as_ref()
+                .map_or(self.json_content.len(), String::len),
+            BodyType::Raw => self.raw_content.len(),
+            BodyType::FormData => self.get_form_data_as_string().len(),
+        }
+    }
+
     pub fn set_content(&mut self, content: impl Into<String>, cx: &mut Context<Self>) {
         let new_content = content.into();
+        self.cancel_paste(cx);
+        self.attached_paste = None;
+
+        // 太大而无法编辑的内容（例如历史记录中附加的粘贴）仍作为附加的请求体
+        if self.current_type == BodyType::Json && PasteSize::of(&new_content) == PasteSize::Huge {
+            self.attach_paste(new_content, cx);
+            return;
+        }
 
         match &self.current_type {
             BodyType::Json => {
@@ -335,11 +574,7 @@ impl BodyInput {
     }
 
     pub fn add_form_data_entry(&mut self, cx: &mut Context<Self>) {
-        self.form_data_entries.push(FormDataEntry {
-            key: String::new(),
-            value: String::new(),
-            enabled: true,
-        });
+        self.form_data_entries.push(FormDataEntry::new("", ""));
         cx.notify();
     }
 
@@ -347,11 +582,7 @@ impl BodyInput {
         if index < self.form_data_entries.len() {
             self.form_data_entries.remove(index);
             if self.form_data_entries.is_empty() {
-                self.form_data_entries.push(FormDataEntry {
-                    key: String::new(),
-                    value: String::new(),
-                    enabled: true,
-                });
+                self.form_data_entries.push(FormDataEntry::new("", ""));
             }
             cx.notify();
         }
@@ -365,6 +596,79 @@ impl BodyInput {
         }
     }
 
+    /// 拖动调整 form-data 行顺序
+    pub fn move_form_data_entry(&mut self, from: usize, to: usize, cx: &mut Context<Self>) {
+        // 先保存正在编辑的内容，避免编辑索引指向错误的行
+        self.finish_editing(cx);
+        if move_item(&mut self.form_data_entries, from, to) {
+            tracing::info!("↕️ BodyInput - 移动 form-data 行: {from} -> {to}");
+            cx.emit(BodyInputEvent::ValueChanged(self.get_form_data_as_string()));
+            cx.notify();
+        }
+    }
+
+    /// 启用或禁用所有 form-data 行
+    pub fn set_all_form_data_enabled(&mut self, enabled: bool, cx: &mut Context<Self>) {
+        for entry in &mut self.form_data_entries {
+            entry.enabled = enabled;
+        }
+        cx.emit(BodyInputEvent::ValueChanged(self.get_form_data_as_string()));
+        cx.notify();
+    }
+
+    /// 切换 form-data 值的类型提示（转换为 JSON 时使用）
+    pub fn cycle_value_type(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(entry) = self.form_data_entries.get_mut(index) {
+            entry.value_type = entry.value_type.next();
+            cx.notify();
+        }
+    }
+
+    /// 在常用类型之间切换 multipart 部分的 Content-Type，最后回到自动检测
+    pub fn cycle_part_content_type(&mut self, index: usize, cx: &mut Context<Self>) {
+        if let Some(entry) = self.form_data_entries.get_mut(index) {
+            entry.content_type =
+                next_part_content_type(entry.content_type.as_deref()).map(str::to_string);
+            cx.emit(BodyInputEvent::ValueChanged(self.get_form_data_as_string()));
+            cx.notify();
+        }
+    }
+
+    /// Enabled entries with a key, as the parts of a multipart body
+    pub fn form_parts(&self) -> Vec<FormPart> {
+        self.form_data_entries
+            .iter()
+            .filter(|entry| entry.enabled && !entry.key.is_empty())
+            .map(|entry| {
+                FormPart::new(&entry.key, &entry.value)
+                    .with_content_type(entry.content_type.clone())
+            })
+            .collect()
+    }
+
+    /// 将 form-data 按类型提示转换为 JSON 请求体，并切换到 JSON 编辑器
+    pub fn convert_form_to_json(&mut self, cx: &mut Context<Self>) {
+        self.finish_editing(cx);
+        self.cancel_paste(cx);
+        let json = form_to_json(&self.form_data_entries);
+        self.json_content = serde_json::to_string_pretty(&json).unwrap_or_default();
+        let end = self.json_content.len();
+        self.json_selected_range = end..end;
+        self.json_selection_reversed = false;
+        self.json_marked_range = None;
+        self.current_type = BodyType::Json;
+        tracing::info!("🔄 BodyInput - form-data 已转换为 JSON");
+        cx.emit(BodyInputEvent::ValueChanged(self.json_content.clone()));
+        cx.notify();
+    }
+
+    // 快捷键切换正在编辑的行
+    fn toggle_row(&mut self, _: &ToggleRow, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(index) = self.editing_key_index.or(self.editing_value_index) {
+            self.toggle_form_data_entry(index, cx);
+        }
+    }
+
     pub fn get_form_data_as_string(&self) -> String {
         let encoder = form_urlencoded::Serializer::new(String::new());
         self.form_data_entries
@@ -380,17 +684,15 @@ impl BodyInput {
     pub fn set_form_data_entries(&mut self, entries: Vec<FormDataEntry>, cx: &mut Context<Self>) {
         self.form_data_entries = entries;
         if self.form_data_entries.is_empty() {
-            self.form_data_entries.push(FormDataEntry {
-                key: String::new(),
-                value: String::new(),
-                enabled: true,
-            });
+            self.form_data_entries.push(FormDataEntry::new("", ""));
         }
         cx.emit(BodyInputEvent::ValueChanged(self.get_form_data_as_string()));
         cx.notify();
     }
 
     pub fn clear(&mut self, cx: &mut Context<Self>) {
+        self.cancel_paste(cx);
+        self.attached_paste = None;
         match &self.current_type {
             BodyType::Json => {
                 self.json_content.clear();
@@ -399,11 +701,7 @@ impl BodyInput {
                 self.raw_content.clear();
             }
             BodyType::FormData => {
-                self.form_data_entries = vec![FormDataEntry {
-                    key: String::new(),
-                    value: String::new(),
-                    enabled: true,
-                }];
+                self.form_data_entries = vec![FormDataEntry::new("", "")];
             }
         }
         cx.emit(BodyInputEvent::ValueChanged(String::new()));
@@ -492,6 +790,59 @@ impl BodyInput {
         cx.notify();
     }
 
+    /// 在光标处插入随机测试数据（JSON 编辑器或正在编辑的 form-data 值）
+    pub fn insert_generated(&mut self, kind: &FakeKind, cx: &mut Context<Self>) {
+        let value = self.faker.generate(kind);
+
+        match self.current_type {
+            BodyType::Json if self.json_locked() => return,
+            BodyType::Json => {
+                let range = self.json_selected_range.clone();
+                self.json_content.replace_range(range.clone(), &value);
+                let cursor = range.start + value.len();
+                self.json_selected_range = cursor..cursor;
+                self.json_marked_range = None;
+                cx.emit(BodyInputEvent::ValueChanged(self.json_content.clone()));
+            }
+            BodyType::FormData => {
+                if self.editing_value_index.is_none() {
+                    return;
+                }
+                let range = self.form_value_selected_range.clone();
+                self.temp_value_value.replace_range(range.clone(), &value);
+                let cursor = range.start + value.len();
+                self.form_value_selected_range = cursor..cursor;
+            }
+            BodyType::Raw => return,
+        }
+        tracing::info!("🎲 BodyInput - 插入随机数据 ({})", kind.label());
+        cx.notify();
+    }
+
+    /// JSON 编辑器中光标的字节偏移
+    pub fn cursor_offset(&self) -> usize {
+        self.json_cursor_offset()
+    }
+
+    /// 用 `text` 替换光标前的 `len` 个字节（用于补全已输入一半的单词）
+    pub fn replace_before_cursor(&mut self, len: usize, text: &str, cx: &mut Context<Self>) {
+        if self.current_type != BodyType::Json || self.json_locked() {
+            return;
+        }
+        let cursor = self.json_cursor_offset();
+        let start = cursor.saturating_sub(len);
+        if !self.json_content.is_char_boundary(start) {
+            return;
+        }
+        self.json_content.replace_range(start..cursor, text);
+        let cursor = start + text.len();
+        self.json_selected_range = cursor..cursor;
+        self.json_selection_reversed = false;
+        self.json_marked_range = None;
+        cx.emit(BodyInputEvent::ValueChanged(self.json_content.clone()));
+        cx.notify();
+    }
+
     pub fn cancel_editing(&mut self, cx: &mut Context<Self>) {
         self.editing_key_index = None;
         self.editing_value_index = None;
@@ -548,6 +899,56 @@ impl BodyInput {
         }
     }
 
+    // Shift-Enter 在 form-data 值中换行
+    fn new_line(&mut self, _: &NewLine, _: &mut Window, cx: &mut Context<Self>) {
+        if self.editing_value_index.is_none() {
+            return;
+        }
+        let range = self.form_value_selected_range.clone();
+        self.temp_value_value.replace_range(range.clone(), "\n");
+        let cursor = range.start + 1;
+        self.form_value_selected_range = cursor..cursor;
+        self.form_value_selection_reversed = false;
+        cx.notify();
+    }
+
+    // 粘贴：表格数据（每行一个键值对）展开为多行，其他文本插入到光标处
+    fn paste(&mut self, _: &Paste, _: &mut Window, cx: &mut Context<Self>) {
+        let Some(index) = self.editing_key_index.or(self.editing_value_index) else {
+            return;
+        };
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
+            return;
+        };
+
+        if let Some(rows) = parse_pasted_rows(&text) {
+            self.finish_editing(cx);
+            let count = rows.len();
+            paste_rows(&mut self.form_data_entries, index, rows);
+            tracing::info!("📋 BodyInput - 粘贴了 {count} 行 form-data");
+            cx.emit(BodyInputEvent::ValueChanged(self.get_form_data_as_string()));
+            cx.notify();
+            return;
+        }
+
+        if self.editing_key_index.is_some() {
+            let text = text.replace(['\r', '\n'], "");
+            let range = self.form_key_selected_range.clone();
+            self.temp_key_value.replace_range(range.clone(), &text);
+            let cursor = range.start + text.len();
+            self.form_key_selected_range = cursor..cursor;
+            self.form_key_selection_reversed = false;
+        } else {
+            let text = text.replace("\r\n", "\n");
+            let range = self.form_value_selected_range.clone();
+            self.temp_value_value.replace_range(range.clone(), &text);
+            let cursor = range.start + text.len();
+            self.form_value_selected_range = cursor..cursor;
+            self.form_value_selection_reversed = false;
+        }
+        cx.notify();
+    }
+
     fn escape(&mut self, _: &Escape, _: &mut Window, cx: &mut Context<Self>) {
         if self.editing_key_index.is_some() || self.editing_value_index.is_some() {
             self.cancel_editing(cx);
@@ -829,15 +1230,129 @@ impl BodyInput {
         self.json_replace_text_in_range(None, "\n", window, cx);
     }
 
+    // 粘贴：大段文本分块插入，超大文本先询问是否改为文件请求体
     fn json_paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
-        if self.current_type != BodyType::Json {
+        if self.current_type != BodyType::Json || self.json_locked() {
+            return;
+        }
+        let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) else {
             return;
+        };
+        match PasteSize::of(&text) {
+            PasteSize::Small => self.json_replace_text_in_range(None, &text, window, cx),
+            PasteSize::Large => self.paste_in_chunks(text, cx),
+            PasteSize::Huge => {
+                tracing::info!(
+                    "📋 BodyInput - 粘贴内容过大 ({} bytes)，询问是否作为文件请求体",
+                    text.len()
+                );
+                self.offered_paste = Some(text);
+                cx.notify();
+            }
         }
-        if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
-            self.json_replace_text_in_range(None, &text, window, cx);
+    }
+
+    // 分块插入粘贴内容，每块之间让出主线程，界面保持响应并显示进度
+    fn paste_in_chunks(&mut self, text: String, cx: &mut Context<Self>) {
+        let range = self
+            .json_marked_range
+            .take()
+            .unwrap_or_else(|| self.json_selected_range.clone());
+        self.json_content.replace_range(range.clone(), "");
+        self.json_selected_range = range.start..range.start;
+        self.paste_progress = Some(PasteProgress {
+            start: range.start,
+            inserted: 0,
+            total: text.len(),
+        });
+        tracing::info!("📋 BodyInput - 分块粘贴 {} bytes", text.len());
+        self.paste_task = Some(cx.spawn(async move |this, cx| {
+            for chunk in chunk_ranges(&text) {
+                cx.background_executor().timer(PASTE_CHUNK_DELAY).await;
+                let inserted =
+                    this.update(cx, |input, cx| input.insert_paste_chunk(&text[chunk], cx));
+                if inserted.is_err() {
+                    return;
+                }
+            }
+            let _ = this.update(cx, |input, cx| input.finish_paste(cx));
+        }));
+        cx.notify();
+    }
+
+    fn insert_paste_chunk(&mut self, chunk: &str, cx: &mut Context<Self>) {
+        let Some(progress) = self.paste_progress.as_mut() else {
+            return;
+        };
+        let offset = progress.start + progress.inserted;
+        self.json_content.insert_str(offset, chunk);
+        progress.inserted += chunk.len();
+        let cursor = offset + chunk.len();
+        self.json_selected_range = cursor..cursor;
+        cx.notify();
+    }
+
+    // 全部插入后才通知内容变化，避免每块都触发一次
+    fn finish_paste(&mut self, cx: &mut Context<Self>) {
+        if self.paste_progress.take().is_some() {
+            tracing::info!("📋 BodyInput - 分块粘贴完成");
+            cx.emit(BodyInputEvent::ValueChanged(self.json_content.clone()));
+            cx.notify();
         }
     }
 
+    /// 取消正在进行的分块粘贴，已插入的部分会被移除
+    pub fn cancel_paste(&mut self, cx: &mut Context<Self>) {
+        self.paste_task = None;
+        self.offered_paste = None;
+        if let Some(progress) = self.paste_progress.take() {
+            let end = progress.start + progress.inserted;
+            self.json_content.replace_range(progress.start..end, "");
+            self.json_selected_range = progress.start..progress.start;
+            cx.notify();
+        }
+    }
+
+    fn attach_offered_paste(&mut self, cx: &mut Context<Self>) {
+        if let Some(text) = self.offered_paste.take() {
+            self.attach_paste(text, cx);
+        }
+    }
+
+    fn paste_offered_anyway(&mut self, cx: &mut Context<Self>) {
+        if let Some(text) = self.offered_paste.take() {
+            self.paste_in_chunks(text, cx);
+        }
+    }
+
+    // 超大粘贴只保存在内存中并作为请求体发送，编辑器清空并在 Detach 前不接受编辑
+    fn attach_paste(&mut self, text: String, cx: &mut Context<Self>) {
+        self.set_type(BodyType::Json, cx);
+        self.cancel_paste(cx);
+        self.json_content.clear();
+        self.json_selected_range = 0..0;
+        self.json_selection_reversed = false;
+        tracing::info!(
+            "📎 BodyInput - 附加粘贴内容作为请求体: {} bytes",
+            text.len()
+        );
+        cx.emit(BodyInputEvent::ValueChanged(text.clone()));
+        self.attached_paste = Some(text);
+        cx.notify();
+    }
+
+    /// 作为请求体发送的超大粘贴（JSON 模式下）
+    pub fn attached_paste(&self) -> Option<&str> {
+        self.attached_paste
+            .as_deref()
+            .filter(|_| self.current_type == BodyType::Json)
+    }
+
+    // 分块粘贴进行中或附加了超大粘贴时 JSON 编辑器不接受编辑
+    fn json_locked(&self) -> bool {
+        self.paste_progress.is_some() || self.attached_paste.is_some()
+    }
+
     fn json_copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
         if self.current_type != BodyType::Json {
             return;
@@ -867,6 +1382,42 @@ impl BodyInput {
         cx.notify();
     }
 
+    // 编辑器下方的状态行：光标位置和字符/单词/行数
+    fn status_line(&self) -> String {
+        match self.current_type {
+            BodyType::Json => editor_status(&self.json_content, Some(self.json_cursor_offset())),
+            BodyType::Raw => editor_status(&self.raw_content, None),
+            BodyType::FormData => {
+                let entries = self.form_data_entries.len();
+                let plural = if entries == 1 { "entry" } else { "entries" };
+                let summary = format!("{} {plural}", format_count(entries));
+                let editing = if self.editing_key_index.is_some() {
+                    let range = &self.form_key_selected_range;
+                    let cursor = if self.form_key_selection_reversed {
+                        range.start
+                    } else {
+                        range.end
+                    };
+                    Some(editor_status(&self.temp_key_value, Some(cursor)))
+                } else if self.editing_value_index.is_some() {
+                    let range = &self.form_value_selected_range;
+                    let cursor = if self.form_value_selection_reversed {
+                        range.start
+                    } else {
+                        range.end
+                    };
+                    Some(editor_status(&self.temp_value_value, Some(cursor)))
+                } else {
+                    None
+                };
+                match editing {
+                    Some(editing) => format!("{summary} • {editing}"),
+                    None => summary,
+                }
+            }
+        }
+    }
+
     fn json_cursor_offset(&self) -> usize {
         if self.json_selection_reversed {
             self.json_selected_range.start
@@ -988,6 +1539,10 @@ impl BodyInput {
         _: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        // 分块粘贴进行中或请求体来自文件时不接受编辑
+        if self.json_locked() {
+            return;
+        }
         let range = range_utf16
             .as_ref()
             .map(|range_utf16| self.json_range_from_utf16(range_utf16))
@@ -1236,15 +1791,12 @@ impl BodyInput {
             return self.form_key_cursor_offset();
         };
 
-        let Some(layout) = self.form_key_last_layout.as_ref() else {
+        if self.form_key_last_layout.is_empty() {
             return self.form_key_cursor_offset();
-        };
-
-        // 计算相对于文本框的 x 位置
-        let x_in_text = position.x - bounds.left();
+        }
 
         // 使用 ShapedLine 的 closest_index_for_x 方法获取精确索引
-        layout.closest_index_for_x(x_in_text)
+        index_for_position(&self.form_key_last_layout, bounds, position)
     }
 
     // FormData value mouse event handlers
@@ -1305,15 +1857,12 @@ impl BodyInput {
             return self.form_value_cursor_offset();
         };
 
-        let Some(layout) = self.form_value_last_layout.as_ref() else {
+        if self.form_value_last_layout.is_empty() {
             return self.form_value_cursor_offset();
-        };
-
-        // 计算相对于文本框的 x 位置
-        let x_in_text = position.x - bounds.left();
+        }
 
-        // 使用 ShapedLine 的 closest_index_for_x 方法获取精确索引
-        layout.closest_index_for_x(x_in_text)
+        // 多行值先按 y 找到所在行，再用 closest_index_for_x 获取精确索引
+        index_for_position(&self.form_value_last_layout, bounds, position)
     }
 }
 
@@ -1615,9 +2164,10 @@ struct FormTextElement {
 }
 
 struct FormPrepaintState {
-    shaped_line: Option<ShapedLine>,
+    // One per line - values may span several
+    lines: Vec<ShapedLine>,
     cursor: Option<PaintQuad>,
-    selection: Option<PaintQuad>,
+    selection: Vec<PaintQuad>,
 }
 
 impl IntoElement for FormTextElement {
@@ -1645,14 +2195,22 @@ impl Element for FormTextElement {
         _id: Option<&GlobalElementId>,
         _inspector_id: Option<&gpui::InspectorElementId>,
         window: &mut Window,
-        _cx: &mut App,
+        cx: &mut App,
     ) -> (LayoutId, Self::RequestLayoutState) {
+        let input = self.input.read(cx);
+        let content = if self.is_key {
+            &input.temp_key_value
+        } else {
+            &input.temp_value_value
+        };
+        let line_count = content.split('\n').count();
+
         let mut style = Style::default();
         style.size.width = relative(1.).into();
         let line_height = window.line_height();
-        style.size.height = line_height.into();
+        style.size.height = (line_height * line_count as f32).into();
 
-        (window.request_layout(style, [], _cx), ())
+        (window.request_layout(style, [], cx), ())
     }
 
     fn prepaint(
@@ -1673,72 +2231,91 @@ impl Element for FormTextElement {
 
         if content.is_empty() {
             return FormPrepaintState {
-                shaped_line: None,
+                lines: Vec::new(),
                 cursor: None,
-                selection: None,
+                selection: Vec::new(),
             };
         }
 
         let style = window.text_style();
         let font_size = style.font_size.to_pixels(window.rem_size());
-        let line_str: SharedString = content.clone().into();
-
-        let run = TextRun {
-            len: line_str.len(),
-            font: style.font(),
-            color: style.color,
-            background_color: None,
-            underline: None,
-            strikethrough: None,
-        };
-
-        let shaped_line = window
-            .text_system()
-            .shape_line(line_str, font_size, &[run], None);
+        let lines: Vec<ShapedLine> = content
+            .split('\n')
+            .map(|line| {
+                let line_str: SharedString = line.to_string().into();
+                let run = TextRun {
+                    len: line_str.len(),
+                    font: style.font(),
+                    color: style.color,
+                    background_color: None,
+                    underline: None,
+                    strikethrough: None,
+                };
+                window
+                    .text_system()
+                    .shape_line(line_str, font_size, &[run], None)
+            })
+            .collect();
 
         let line_height = window.line_height();
 
         // Calculate cursor or selection
-        let (selection_quad, cursor_quad) = if selected_range.is_empty() {
+        let (selection_quads, cursor_quad) = if selected_range.is_empty() {
             // Show cursor
             let cursor_offset = if self.is_key {
                 input.form_key_cursor_offset()
             } else {
                 input.form_value_cursor_offset()
             };
-            let cursor_x = shaped_line.x_for_index(cursor_offset);
+            let (line, column) = line_and_column(content, cursor_offset);
+            let cursor_x = lines[line].x_for_index(column);
+            let cursor_y = line_height * line as f32;
 
             (
-                None,
+                Vec::new(),
                 Some(fill(
                     Bounds::new(
-                        point(bounds.left() + cursor_x, bounds.top()),
+                        point(bounds.left() + cursor_x, bounds.top() + cursor_y),
                         size(px(2.), line_height),
                     ),
                     rgb(0x0000_7acc),
                 )),
             )
         } else {
-            // Show selection
-            let start_x = shaped_line.x_for_index(selected_range.start);
-            let end_x = shaped_line.x_for_index(selected_range.end);
+            // Show selection, one rectangle per line it covers
+            let (start_line, start_column) = line_and_column(content, selected_range.start);
+            let (end_line, end_column) = line_and_column(content, selected_range.end);
+            let quads = (start_line..=end_line)
+                .map(|index| {
+                    let line = &lines[index];
+                    let start_x = if index == start_line {
+                        line.x_for_index(start_column)
+                    } else {
+                        px(0.0)
+                    };
+                    let end_x = if index == end_line {
+                        line.x_for_index(end_column)
+                    } else {
+                        line.x_for_index(line.text.len())
+                    };
+                    let y = line_height * index as f32;
+                    fill(
+                        Bounds::from_corners(
+                            point(bounds.left() + start_x, bounds.top() + y),
+                            point(bounds.left() + end_x, bounds.top() + y + line_height),
+                        ),
+                        rgba(0x3366_ff33),
+                    )
+                })
+                .collect();
 
-            (
-                Some(fill(
-                    Bounds::from_corners(
-                        point(bounds.left() + start_x, bounds.top()),
-                        point(bounds.left() + end_x, bounds.top() + line_height),
-                    ),
-                    rgba(0x3366_ff33),
-                )),
-                None,
-            )
+            (quads, None)
         };
 
         FormPrepaintState {
-            shaped_line: Some(shaped_line),
+            lines,
             cursor: cursor_quad,
-            selection: selection_quad,
+            selection: selection_quads,
         }
     }
 
@@ -1753,15 +2330,15 @@ impl Element for FormTextElement {
         cx: &mut App,
     ) {
         // Paint selection if any
-        if let Some(selection) = &prepaint.selection {
+        for selection in &prepaint.selection {
             window.paint_quad(selection.clone());
         }
 
-        // Paint text
-        if let Some(shaped_line) = &prepaint.shaped_line {
-            let line_height = window.line_height();
-            let _ = shaped_line.paint(
-                point(bounds.left(), bounds.top()),
+        // Paint text lines
+        let line_height = window.line_height();
+        for (i, line) in prepaint.lines.iter().enumerate() {
+            let _ = line.paint(
+                point(bounds.left(), bounds.top() + line_height * i as f32),
                 line_height,
                 TextAlign::Left,
                 None,
@@ -1781,18 +2358,134 @@ impl Element for FormTextElement {
         // Save layout and bounds for mouse interaction
         self.input.update(cx, |input, _cx| {
             if self.is_key {
-                input.form_key_last_layout = prepaint.shaped_line.clone();
+                input.form_key_last_layout = prepaint.lines.clone();
                 input.form_key_last_bounds = Some(bounds);
             } else {
-                input.form_value_last_layout = prepaint.shaped_line.clone();
+                input.form_value_last_layout = prepaint.lines.clone();
                 input.form_value_last_bounds = Some(bounds);
             }
         });
     }
 }
 
+impl BodyInput {
+    // 粘贴进度、超大粘贴的处理选项，或请求体来自文件的说明
+    fn render_paste_banner(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        type Action = fn(&mut BodyInput, &mut Context<BodyInput>);
+        let (message, actions): (String, Vec<(&str, Action)>) =
+            if let Some(progress) = &self.paste_progress {
+                (
+                    format!(
+                        "Pasting {}… {}%",
+                        format_size(progress.total as u64),
+                        progress.inserted * 100 / progress.total.max(1)
+                    ),
+                    vec![("Cancel", Self::cancel_paste as Action)],
+                )
+            } else if let Some(text) = &self.offered_paste {
+                (
+                    format!(
+                        "The clipboard holds {} of text, which is slow to edit here",
+                        format_size(text.len() as u64)
+                    ),
+                    vec![
+                        ("Attach as body", Self::attach_offered_paste as Action),
+                        ("Paste anyway", Self::paste_offered_anyway),
+                        ("Cancel", Self::cancel_paste),
+                    ],
+                )
+            } else {
+                let text = self.attached_paste()?;
+                (
+                    format!(
+                        "The body is a pasted {} of text, kept out of the editor",
+                        format_size(text.len() as u64)
+                    ),
+                    vec![("Detach", Self::clear as Action)],
+                )
+            };
+
+        Some(
+            div()
+                .flex()
+                .items_center()
+                .gap_2()
+                .px_2()
+                .py_1()
+                .rounded_md()
+                .bg(rgb(0x00ff_f3cd))
+                .text_size(px(12.0))
+                .text_color(rgb(0x0021_2529))
+                .child(message)
+                .children(
+                    actions
+                        .into_iter()
+                        .enumerate()
+                        .map(|(index, (label, action))| {
+                            div()
+                                .id(("paste-action", index))
+                                .px_2()
+                                .py_1()
+                                .rounded_md()
+                                .border_1()
+                                .border_color(rgb(0x00de_e2e6))
+                                .bg(rgb(0x00f8_f9fa))
+                                .cursor_pointer()
+                                .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                                .child(label)
+                                .on_mouse_up(
+                                    gpui::MouseButton::Left,
+                                    cx.listener(move |this, _event, _window, cx| action(this, cx)),
+                                )
+                        }),
+                ),
+        )
+    }
+
+    fn render_generator_toolbar(&self, cx: &mut Context<Self>) -> impl IntoElement {
+        let can_insert = match self.current_type {
+            BodyType::Json => true,
+            BodyType::FormData => self.editing_value_index.is_some(),
+            BodyType::Raw => false,
+        };
+
+        div()
+            .flex()
+            .items_center()
+            .gap_2()
+            .text_size(px(12.0))
+            .text_color(rgb(0x006c_757d))
+            .child("Insert random:")
+            .children(FakeKind::presets().into_iter().map(|kind| {
+                div()
+                    .px_2()
+                    .py_1()
+                    .rounded_md()
+                    .border_1()
+                    .border_color(rgb(0x00de_e2e6))
+                    .bg(rgb(0x00f8_f9fa))
+                    .child(kind.label())
+                    .when(can_insert, |div| {
+                        div.cursor_pointer()
+                            .text_color(rgb(0x0021_2529))
+                            .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                            .on_mouse_up(
+                                gpui::MouseButton::Left,
+                                cx.listener(move |this, _event, _window, cx| {
+                                    this.insert_generated(&kind, cx);
+                                }),
+                            )
+                    })
+            }))
+            .when(!can_insert, |div| {
+                div.child("(click a value to edit it first)")
+            })
+    }
+}
+
 impl Render for BodyInput {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let status = self.status_line();
         let current_type = self.current_type.clone();
         let raw_content = self.raw_content.clone();
         let form_data_entries = self.form_data_entries.clone();
@@ -1879,6 +2572,8 @@ impl Render for BodyInput {
                     .flex()
                     .flex_col()
                     .gap_2()
+                    .child(self.render_generator_toolbar(cx))
+                    .children(self.render_paste_banner(cx))
                     .child(
                         div()
                             .w_full()
@@ -1933,6 +2628,8 @@ impl Render for BodyInput {
                     .on_action(cx.listener(Self::backspace))
                     .on_action(cx.listener(Self::delete))
                     .on_action(cx.listener(Self::enter))
+                    .on_action(cx.listener(Self::new_line))
+                    .on_action(cx.listener(Self::paste))
                     .on_action(cx.listener(Self::escape))
                     .on_action(cx.listener(Self::tab))
                     .on_action(cx.listener(Self::shift_tab))
@@ -1943,7 +2640,9 @@ impl Render for BodyInput {
                     .on_action(cx.listener(Self::select_all))
                     .on_action(cx.listener(Self::home))
                     .on_action(cx.listener(Self::end))
+                    .on_action(cx.listener(Self::toggle_row))
                     .on_key_down(cx.listener(Self::on_key_down))
+                    .child(self.render_generator_toolbar(cx))
                     .child(
                         div()
                             .flex()
@@ -1953,26 +2652,45 @@ impl Render for BodyInput {
                             .bg(rgb(0x00f8_f9fa))
                             .border_1()
                             .border_color(rgb(0x00de_e2e6))
+                            .child(div().w_4())
+                            .child({
+                                let state = MasterCheckState::from_flags(
+                                    form_data_entries.iter().map(|entry| entry.enabled),
+                                );
+                                master_checkbox(state).cursor_pointer().on_mouse_up(
+                                    gpui::MouseButton::Left,
+                                    cx.listener(move |this, _event, _window, cx| {
+                                        this.set_all_form_data_enabled(state.toggled_value(), cx);
+                                    }),
+                                )
+                            })
                             .child(
                                 div()
-                                    .w_4()
+                                    .flex_1()
                                     .text_size(px(12.0))
                                     .text_color(rgb(0x006c_757d))
-                                    .child("✓"),
+                                    .child("Key"),
                             )
                             .child(
                                 div()
                                     .flex_1()
                                     .text_size(px(12.0))
                                     .text_color(rgb(0x006c_757d))
-                                    .child("Key"),
+                                    .child("Value"),
                             )
                             .child(
                                 div()
-                                    .flex_1()
+                                    .w_16()
                                     .text_size(px(12.0))
                                     .text_color(rgb(0x006c_757d))
-                                    .child("Value"),
+                                    .child("Type"),
+                            )
+                            .child(
+                                div()
+                                    .w_40()
+                                    .text_size(px(12.0))
+                                    .text_color(rgb(0x006c_757d))
+                                    .child("Content-Type"),
                             )
                             .child(
                                 div()
@@ -1987,11 +2705,36 @@ impl Render for BodyInput {
                             let entry_key = entry.key.clone();
                             let entry_value = entry.value.clone();
                             let entry_enabled = entry.enabled;
+                            let entry_type = entry.value_type;
+                            let entry_content_type = entry.content_type.clone();
 
                             div()
                                 .flex()
                                 .gap_2()
                                 .items_center()
+                                // 拖动排序
+                                .drag_over::<DraggedRow>(|style, dragged, _, _| {
+                                    if dragged.table == RowTable::FormData {
+                                        style.bg(rgb(0x00e7_f1ff))
+                                    } else {
+                                        style
+                                    }
+                                })
+                                .on_drop(cx.listener(
+                                    move |this, dragged: &DraggedRow, _window, cx| {
+                                        if dragged.table == RowTable::FormData {
+                                            this.move_form_data_entry(dragged.index, index, cx);
+                                        }
+                                    },
+                                ))
+                                .child(drag_handle().id(("form-data-drag", index)).on_drag(
+                                    DraggedRow {
+                                        table: RowTable::FormData,
+                                        index,
+                                        label: format!("{entry_key}={entry_value}").into(),
+                                    },
+                                    |dragged, _offset, _window, cx| cx.new(|_| dragged.clone()),
+                                ))
                                 .child(
                                     // Checkbox
                                     div()
@@ -2121,6 +2864,58 @@ impl Render for BodyInput {
                                             )
                                         }),
                                 )
+                                .child(
+                                    // 类型提示 - 点击切换，转换为 JSON 时使用
+                                    div()
+                                        .w_16()
+                                        .px_2()
+                                        .py_1()
+                                        .rounded_md()
+                                        .border_1()
+                                        .border_color(rgb(0x00de_e2e6))
+                                        .bg(rgb(0x00f8_f9fa))
+                                        .text_size(px(12.0))
+                                        .text_color(rgb(0x0021_2529))
+                                        .cursor_pointer()
+                                        .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                                        .child(entry_type.label())
+                                        .on_mouse_up(
+                                            gpui::MouseButton::Left,
+                                            cx.listener(move |this, _event, _window, cx| {
+                                                this.cycle_value_type(index, cx);
+                                            }),
+                                        ),
+                                )
+                                .child(
+                                    // multipart 部分的 Content-Type - 点击切换
+                                    div()
+                                        .w_40()
+                                        .overflow_hidden()
+                                        .px_2()
+                                        .py_1()
+                                        .rounded_md()
+                                        .border_1()
+                                        .border_color(rgb(0x00de_e2e6))
+                                        .bg(rgb(0x00f8_f9fa))
+                                        .text_size(px(12.0))
+                                        .cursor_pointer()
+                                        .hover(|style| style.bg(rgb(0x00e9_ecef)))
+                                        .when_some(
+                                            entry_content_type.clone(),
+                                            |div, content_type| {
+                                                div.text_color(rgb(0x0021_2529)).child(content_type)
+                                            },
+                                        )
+                                        .when(entry_content_type.is_none(), |div| {
+                                            div.text_color(rgb(0x006c_757d)).child("Detect")
+                                        })
+                                        .on_mouse_up(
+                                            gpui::MouseButton::Left,
+                                            cx.listener(move |this, _event, _window, cx| {
+                                                this.cycle_part_content_type(index, cx);
+                                            }),
+                                        ),
+                                )
                                 .child(
                                     // Delete button
                                     div()
@@ -2144,20 +2939,53 @@ impl Render for BodyInput {
                     ))
                     .child(
                         div()
-                            .px_3()
-                            .py_2()
-                            .bg(rgb(0x0028_a745))
-                            .text_color(rgb(0x00ff_ffff))
-                            .rounded_md()
-                            .cursor_pointer()
-                            .hover(|style| style.bg(rgb(0x0021_8838)))
-                            .child("Add Row")
-                            .text_size(px(14.0))
-                            .on_mouse_up(
-                                gpui::MouseButton::Left,
-                                cx.listener(|this, _event, _window, cx| {
-                                    this.add_form_data_entry(cx);
-                                }),
+                            .flex()
+                            .items_center()
+                            .gap_2()
+                            .child(
+                                div()
+                                    .px_3()
+                                    .py_2()
+                                    .bg(rgb(0x0028_a745))
+                                    .text_color(rgb(0x00ff_ffff))
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(rgb(0x0021_8838)))
+                                    .child("Add Row")
+                                    .text_size(px(14.0))
+                                    .on_mouse_up(
+                                        gpui::MouseButton::Left,
+                                        cx.listener(|this, _event, _window, cx| {
+                                            this.add_form_data_entry(cx);
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                div()
+                                    .px_3()
+                                    .py_2()
+                                    .bg(rgb(0x006c_757d))
+                                    .text_color(rgb(0x00ff_ffff))
+                                    .rounded_md()
+                                    .cursor_pointer()
+                                    .hover(|style| style.bg(rgb(0x005a_6268)))
+                                    .child("Convert to JSON")
+                                    .text_size(px(14.0))
+                                    .on_mouse_up(
+                                        gpui::MouseButton::Left,
+                                        cx.listener(|this, _event, _window, cx| {
+                                            this.convert_form_to_json(cx);
+                                        }),
+                                    ),
+                            )
+                            .child(
+                                div()
+                                    .text_size(px(12.0))
+                                    .text_color(rgb(0x006c_757d))
+                                    .child(
+                                        "Shift-Enter starts a new line in a value; \
+                                         pasted spreadsheet rows become new rows",
+                                    ),
                             ),
                     )
                     .into_any_element(),
@@ -2184,6 +3012,12 @@ impl Render for BodyInput {
                     )
                     .into_any_element(),
             })
+            .child(
+                div()
+                    .text_size(px(12.0))
+                    .text_color(rgb(0x006c_757d))
+                    .child(status),
+            )
     }
 }
 
@@ -2192,6 +3026,7 @@ pub fn setup_body_input_key_bindings() -> Vec<KeyBinding> {
         KeyBinding::new("backspace", Backspace, None),
         KeyBinding::new("delete", Delete, None),
         KeyBinding::new("enter", Enter, None),
+        KeyBinding::new("shift-enter", NewLine, None),
         KeyBinding::new("escape", Escape, None),
         KeyBinding::new("tab", Tab, None),
         KeyBinding::new("shift-tab", ShiftTab, None),
@@ -2232,25 +3067,104 @@ mod tests {
 
     #[test]
     fn test_form_data_entry_creation() {
-        let entry = FormDataEntry {
-            key: "username".to_string(),
-            value: "john_doe".to_string(),
-            enabled: true,
-        };
+        let entry = FormDataEntry::new("username", "john_doe");
 
         assert_eq!(entry.key, "username");
         assert_eq!(entry.value, "john_doe");
         assert!(entry.enabled);
+        assert_eq!(entry.value_type, FormValueType::Text);
+        assert_eq!(entry.content_type, None);
     }
 
     #[test]
     fn test_form_data_entry_disabled() {
         let entry = FormDataEntry {
-            key: "api_key".to_string(),
-            value: "secret123".to_string(),
             enabled: false,
+            ..FormDataEntry::new("api_key", "secret123")
         };
 
         assert!(!entry.enabled);
     }
+
+    #[test]
+    fn test_form_to_json_uses_type_hints() {
+        let typed = |key: &str, value: &str, value_type: FormValueType| FormDataEntry {
+            value_type,
+            ..FormDataEntry::new(key, value)
+        };
+        let entries = vec![
+            typed("age", " 42 ", FormValueType::Number),
+            typed("ratio", "0.5", FormValueType::Number),
+            typed("count", "many", FormValueType::Number),
+            typed("admin", "Yes", FormValueType::Boolean),
+            typed("tag", "a", FormValueType::Text),
+            typed("tag", "b", FormValueType::Text),
+            FormDataEntry::new("bio", "line one\nline two"),
+            FormDataEntry {
+                enabled: false,
+                ..FormDataEntry::new("hidden", "x")
+            },
+        ];
+
+        assert_eq!(
+            form_to_json(&entries),
+            serde_json::json!({
+                "age": 42,
+                "ratio": 0.5,
+                "count": "many",
+                "admin": true,
+                "tag": ["a", "b"],
+                "bio": "line one\nline two",
+            })
+        );
+    }
+
+    #[test]
+    fn test_part_content_types_cycle_back_to_detect() {
+        assert_eq!(next_part_content_type(None), Some("application/json"));
+        assert_eq!(
+            next_part_content_type(Some("application/json")),
+            Some("text/plain")
+        );
+        assert_eq!(next_part_content_type(Some("image/png")), None);
+        assert_eq!(next_part_content_type(Some("text/x-custom")), None);
+    }
+
+    #[test]
+    fn test_editor_status() {
+        assert_eq!(
+            editor_status("{\n  \"name\": \"Zoë\"\n}", Some(18)),
+            "Ln 2, Col 16 • 19 chars • 4 words • 3 lines"
+        );
+        assert_eq!(editor_status("", None), "0 chars • 0 words • 1 line");
+    }
+
+    #[test]
+    fn test_line_and_column() {
+        let value = "ab\ncd\n";
+        assert_eq!(line_and_column(value, 1), (0, 1));
+        assert_eq!(line_and_column(value, 3), (1, 0));
+        assert_eq!(line_and_column(value, 5), (1, 2));
+        assert_eq!(line_and_column(value, 6), (2, 0));
+    }
+
+    #[test]
+    fn test_paste_rows_fill_empty_row_then_insert() {
+        let rows = |keys: &[&str]| -> Vec<(String, String)> {
+            keys.iter()
+                .map(|key| (key.to_string(), "1".to_string()))
+                .collect()
+        };
+        let keys = |entries: &[FormDataEntry]| -> Vec<String> {
+            entries.iter().map(|entry| entry.key.clone()).collect()
+        };
+
+        let mut entries = vec![FormDataEntry::new("", "")];
+        paste_rows(&mut entries, 0, rows(&["a", "b"]));
+        assert_eq!(keys(&entries), ["a", "b"]);
+
+        paste_rows(&mut entries, 0, rows(&["c", "d"]));
+        assert_eq!(keys(&entries), ["a", "c", "d", "b"]);
+        assert_eq!(entries[1].value, "1");
+    }
 }
@@ -1,3 +1,4 @@
+use crate::utils::clipboard::{clean_pasted_line, CleanedPaste};
 use gpui::{
     actions, div, fill, hsla, point, prelude::*, px, relative, rgb, rgba, size, App, Bounds,
     ClipboardItem, Context, CursorStyle, Element, ElementId, ElementInputHandler, Entity,
@@ -45,6 +46,13 @@ pub struct HeaderInput {
     last_layout: Option<ShapedLine>,
     last_bounds: Option<Bounds<Pixels>>,
     is_selecting: bool,
+    // 密码模式 - 显示为 * 且禁止复制
+    masked: bool,
+    // 粘贴时清理 BOM、零宽字符和首尾空白；`keep_raw` 为 true 时保留原始值
+    cleans_pastes: bool,
+    keep_raw: bool,
+    // 最近一次需要清理的粘贴及其插入位置，用于在清理后和原始值之间切换
+    last_cleaned: Option<(usize, CleanedPaste)>,
 }
 
 impl HeaderInput {
@@ -59,6 +67,49 @@ impl HeaderInput {
             last_layout: None,
             last_bounds: None,
             is_selecting: false,
+            masked: false,
+            cleans_pastes: false,
+            keep_raw: false,
+            last_cleaned: None,
+        }
+    }
+
+    /// 粘贴时去掉不可见字符和首尾空白（URL、请求头等值），并显示可切换回原始值的标记
+    pub fn cleans_pastes(mut self) -> Self {
+        self.cleans_pastes = true;
+        self
+    }
+
+    /// 密码输入框 - 内容显示为 `*`，不能复制或剪切
+    pub fn masked(mut self) -> Self {
+        self.masked = true;
+        self
+    }
+
+    // 显示的文本 - 密码模式下每个字符显示一个 `*`
+    fn display_content(&self) -> SharedString {
+        if self.masked {
+            "*".repeat(self.content.chars().count()).into()
+        } else {
+            self.content.clone()
+        }
+    }
+
+    // 内容偏移量 -> 显示文本偏移量
+    fn display_offset(&self, offset: usize) -> usize {
+        if self.masked {
+            masked_offset(&self.content, offset)
+        } else {
+            offset
+        }
+    }
+
+    // 显示文本偏移量 -> 内容偏移量
+    fn content_offset(&self, display_offset: usize) -> usize {
+        if self.masked {
+            unmasked_offset(&self.content, display_offset)
+        } else {
+            display_offset
         }
     }
 
@@ -67,6 +118,11 @@ impl HeaderInput {
         self
     }
 
+    pub fn set_placeholder(&mut self, placeholder: impl Into<String>, cx: &mut Context<Self>) {
+        self.placeholder = placeholder.into().into();
+        cx.notify();
+    }
+
     pub fn get_content(&self) -> &str {
         &self.content
     }
@@ -78,6 +134,7 @@ impl HeaderInput {
             let cursor_position = self.selected_range.start.min(self.content.len());
             self.selected_range = cursor_position..cursor_position;
             self.selection_reversed = false;
+            self.last_cleaned = None;
             cx.emit(HeaderInputEvent::ValueChanged(new_content.to_string()));
             cx.notify();
         }
@@ -87,6 +144,7 @@ impl HeaderInput {
         self.content = "".into();
         self.selected_range = 0..0;
         self.selection_reversed = false;
+        self.last_cleaned = None;
         cx.emit(HeaderInputEvent::ValueChanged(String::new()));
         cx.notify();
     }
@@ -146,19 +204,87 @@ impl HeaderInput {
     fn submit(&mut self, _: &Submit, _: &mut Window, cx: &mut Context<Self>) {
         tracing::info!(
             "📝 HeaderInput: Submit triggered with value: {}",
-            self.content
+            if self.masked { "***" } else { &self.content }
         );
         cx.emit(HeaderInputEvent::SubmitRequested);
     }
 
     fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
-            self.replace_text_in_range(None, &text.replace("\n", ""), window, cx);
+            let cleaned = self
+                .cleans_pastes
+                .then(|| clean_pasted_line(&text))
+                .flatten();
+            let Some(cleaned) = cleaned else {
+                self.last_cleaned = None;
+                self.replace_text_in_range(None, &text.replace("\n", ""), window, cx);
+                return;
+            };
+            tracing::info!("🧹 HeaderInput: 粘贴内容 - {}", cleaned.summary());
+            let start = self
+                .marked_range
+                .as_ref()
+                .unwrap_or(&self.selected_range)
+                .start;
+            let inserted = if self.keep_raw {
+                cleaned.raw.clone()
+            } else {
+                cleaned.text.clone()
+            };
+            self.replace_text_in_range(None, &inserted, window, cx);
+            self.last_cleaned = Some((start, cleaned));
+        }
+    }
+
+    // 切换是否保留原始粘贴值 - 最近一次粘贴的内容未被修改时一并替换
+    fn toggle_keep_raw(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.keep_raw = !self.keep_raw;
+        if let Some((start, cleaned)) = self.last_cleaned.clone() {
+            let (shown, replacement) = if self.keep_raw {
+                (&cleaned.text, &cleaned.raw)
+            } else {
+                (&cleaned.raw, &cleaned.text)
+            };
+            if self.content.get(start..start + shown.len()) == Some(shown.as_str()) {
+                self.selected_range = start..start + shown.len();
+                self.marked_range = None;
+                self.replace_text_in_range(None, replacement, window, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    // 粘贴清理标记 - "cleaned" 表示粘贴的值被清理过，"raw" 表示保留原始值；点击切换
+    fn render_paste_marker(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if self.last_cleaned.is_none() && !self.keep_raw {
+            return None;
         }
+        Some(
+            div()
+                .flex_none()
+                .px_1()
+                .rounded_sm()
+                .bg(rgb(0x00e9_ecef))
+                .text_size(px(11.0))
+                .text_color(rgb(0x006c_757d))
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(0x00de_e2e6)))
+                .child(if self.keep_raw { "raw" } else { "cleaned" })
+                .on_mouse_down(MouseButton::Left, |_event, _window, cx| {
+                    cx.stop_propagation();
+                })
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, window, cx| {
+                        cx.stop_propagation();
+                        this.toggle_keep_raw(window, cx);
+                    }),
+                ),
+        )
     }
 
     fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
-        if !self.selected_range.is_empty() {
+        if !self.selected_range.is_empty() && !self.masked {
             cx.write_to_clipboard(ClipboardItem::new_string(
                 self.content[self.selected_range.clone()].to_string(),
             ));
@@ -166,7 +292,7 @@ impl HeaderInput {
     }
 
     fn cut(&mut self, _: &Cut, window: &mut Window, cx: &mut Context<Self>) {
-        if !self.selected_range.is_empty() {
+        if !self.selected_range.is_empty() && !self.masked {
             cx.write_to_clipboard(ClipboardItem::new_string(
                 self.content[self.selected_range.clone()].to_string(),
             ));
@@ -230,7 +356,7 @@ impl HeaderInput {
         if position.y > bounds.bottom() {
             return self.content.len();
         }
-        line.closest_index_for_x(position.x - bounds.left())
+        self.content_offset(line.closest_index_for_x(position.x - bounds.left()))
     }
 
     fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
@@ -404,11 +530,11 @@ impl EntityInputHandler for HeaderInput {
         let range = self.range_from_utf16(&range_utf16);
         Some(Bounds::from_corners(
             point(
-                bounds.left() + last_layout.x_for_index(range.start),
+                bounds.left() + last_layout.x_for_index(self.display_offset(range.start)),
                 bounds.top(),
             ),
             point(
-                bounds.left() + last_layout.x_for_index(range.end),
+                bounds.left() + last_layout.x_for_index(self.display_offset(range.end)),
                 bounds.bottom(),
             ),
         ))
@@ -428,8 +554,8 @@ impl EntityInputHandler for HeaderInput {
             return Some(0);
         }
 
-        assert_eq!(last_layout.text, self.content);
-        let utf8_index = last_layout.index_for_x(point.x - line_point.x)?;
+        assert_eq!(last_layout.text, self.display_content());
+        let utf8_index = self.content_offset(last_layout.index_for_x(point.x - line_point.x)?);
         Some(self.offset_to_utf16(utf8_index))
     }
 }
@@ -488,9 +614,10 @@ impl Element for HeaderTextElement {
         cx: &mut App,
     ) -> Self::PrepaintState {
         let input = self.input.read(cx);
-        let content = input.content.clone();
-        let selected_range = input.selected_range.clone();
-        let cursor = input.cursor_offset();
+        let content = input.display_content();
+        let selected_range = input.display_offset(input.selected_range.start)
+            ..input.display_offset(input.selected_range.end);
+        let cursor = input.display_offset(input.cursor_offset());
         let style = window.text_style();
 
         let (display_text, text_color) = if content.is_empty() {
@@ -616,8 +743,9 @@ impl Element for HeaderTextElement {
             let font_size = style.font_size.to_pixels(window.rem_size());
 
             // 创建实际内容的布局（不是显示文本）
+            let content = input.display_content();
             let content_run = TextRun {
-                len: input.content.len(),
+                len: content.len(),
                 font: style.font(),
                 color: style.color,
                 background_color: None,
@@ -625,12 +753,10 @@ impl Element for HeaderTextElement {
                 strikethrough: None,
             };
 
-            let content_line = window.text_system().shape_line(
-                input.content.clone(),
-                font_size,
-                &[content_run],
-                None,
-            );
+            let content_line =
+                window
+                    .text_system()
+                    .shape_line(content, font_size, &[content_run], None);
 
             input.last_layout = Some(content_line);
             input.last_bounds = Some(bounds);
@@ -679,9 +805,13 @@ impl Render for HeaderInput {
             .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_move(cx.listener(Self::on_mouse_move))
-            .child(HeaderTextElement {
+            .flex()
+            .items_center()
+            .gap_1()
+            .child(div().flex_1().child(HeaderTextElement {
                 input: cx.entity().clone(),
-            })
+            }))
+            .children(self.render_paste_marker(cx))
     }
 }
 
@@ -703,3 +833,42 @@ pub fn setup_header_input_key_bindings() -> Vec<KeyBinding> {
         KeyBinding::new("enter", Submit, None),
     ]
 }
+
+/// Offset in the masked text (one `*` per char) of a content offset
+fn masked_offset(content: &str, offset: usize) -> usize {
+    content
+        .char_indices()
+        .take_while(|(index, _)| *index < offset)
+        .count()
+}
+
+/// Content offset of an offset in the masked text, always on a char boundary
+fn unmasked_offset(content: &str, masked_offset: usize) -> usize {
+    content
+        .char_indices()
+        .nth(masked_offset)
+        .map_or(content.len(), |(index, _)| index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_masked_offsets_with_multibyte_secret() {
+        let secret = "pä𝄞sé";
+        let masked = "*".repeat(secret.chars().count());
+        assert_eq!(masked.len(), 5);
+
+        for display in 0..=masked.len() {
+            let offset = unmasked_offset(secret, display);
+            assert!(secret.is_char_boundary(offset));
+            assert_eq!(masked_offset(secret, offset), display);
+        }
+        assert_eq!(unmasked_offset(secret, 2), "pä".len());
+        assert_eq!(unmasked_offset(secret, 99), secret.len());
+        assert_eq!(masked_offset(secret, secret.len()), 5);
+        // An offset inside a char counts that char
+        assert_eq!(masked_offset(secret, 2), 2);
+    }
+}
@@ -1,3 +1,6 @@
+use crate::utils::clipboard::{
+    append_query_rows, clean_pasted_line, parse_pasted_rows, CleanedPaste,
+};
 use gpui::{
     actions, div, fill, hsla, point, prelude::*, px, relative, rgb, rgba, size, App, Bounds,
     ClipboardItem, Context, CursorStyle, Element, ElementId, ElementInputHandler, Entity,
@@ -45,6 +48,10 @@ pub struct UrlInput {
     last_layout: Option<ShapedLine>,
     last_bounds: Option<Bounds<Pixels>>,
     is_selecting: bool,
+    // 粘贴时清理 BOM、零宽字符和首尾空白；`keep_raw` 为 true 时保留原始值
+    keep_raw: bool,
+    // 最近一次需要清理的粘贴及其插入位置，用于在清理后和原始值之间切换
+    last_cleaned: Option<(usize, CleanedPaste)>,
 }
 
 impl UrlInput {
@@ -59,6 +66,8 @@ impl UrlInput {
             last_layout: None,
             last_bounds: None,
             is_selecting: false,
+            keep_raw: false,
+            last_cleaned: None,
         }
     }
 
@@ -78,6 +87,7 @@ impl UrlInput {
             let cursor_position = self.selected_range.start.min(self.content.len());
             self.selected_range = cursor_position..cursor_position;
             self.selection_reversed = false;
+            self.last_cleaned = None;
             cx.emit(UrlInputEvent::UrlChanged(new_url.to_string()));
             cx.notify();
         }
@@ -142,8 +152,80 @@ impl UrlInput {
 
     fn paste(&mut self, _: &Paste, window: &mut Window, cx: &mut Context<Self>) {
         if let Some(text) = cx.read_from_clipboard().and_then(|item| item.text()) {
-            self.replace_text_in_range(None, &text.replace("\n", ""), window, cx);
+            // 粘贴表格数据（每行一个键值对）时追加为查询参数
+            if let Some(rows) = parse_pasted_rows(&text) {
+                tracing::info!("📋 粘贴了 {} 个查询参数", rows.len());
+                let url = append_query_rows(&self.content, &rows);
+                self.selected_range = url.len()..url.len();
+                self.set_url(url, cx);
+                return;
+            }
+            let Some(cleaned) = clean_pasted_line(&text) else {
+                self.last_cleaned = None;
+                self.replace_text_in_range(None, &text.replace("\n", ""), window, cx);
+                return;
+            };
+            tracing::info!("🧹 粘贴的 URL - {}", cleaned.summary());
+            let start = self
+                .marked_range
+                .as_ref()
+                .unwrap_or(&self.selected_range)
+                .start;
+            let inserted = if self.keep_raw {
+                cleaned.raw.clone()
+            } else {
+                cleaned.text.clone()
+            };
+            self.replace_text_in_range(None, &inserted, window, cx);
+            self.last_cleaned = Some((start, cleaned));
+        }
+    }
+
+    // 切换是否保留原始粘贴值 - 最近一次粘贴的内容未被修改时一并替换
+    fn toggle_keep_raw(&mut self, window: &mut Window, cx: &mut Context<Self>) {
+        self.keep_raw = !self.keep_raw;
+        if let Some((start, cleaned)) = self.last_cleaned.clone() {
+            let (shown, replacement) = if self.keep_raw {
+                (&cleaned.text, &cleaned.raw)
+            } else {
+                (&cleaned.raw, &cleaned.text)
+            };
+            if self.content.get(start..start + shown.len()) == Some(shown.as_str()) {
+                self.selected_range = start..start + shown.len();
+                self.marked_range = None;
+                self.replace_text_in_range(None, replacement, window, cx);
+            }
+        }
+        cx.notify();
+    }
+
+    // 粘贴清理标记 - "cleaned" 表示粘贴的值被清理过，"raw" 表示保留原始值；点击切换
+    fn render_paste_marker(&self, cx: &mut Context<Self>) -> Option<impl IntoElement> {
+        if self.last_cleaned.is_none() && !self.keep_raw {
+            return None;
         }
+        Some(
+            div()
+                .flex_none()
+                .px_1()
+                .rounded_sm()
+                .bg(rgb(0x00e9_ecef))
+                .text_size(px(11.0))
+                .text_color(rgb(0x006c_757d))
+                .cursor_pointer()
+                .hover(|style| style.bg(rgb(0x00de_e2e6)))
+                .child(if self.keep_raw { "raw" } else { "cleaned" })
+                .on_mouse_down(MouseButton::Left, |_event, _window, cx| {
+                    cx.stop_propagation();
+                })
+                .on_mouse_up(
+                    MouseButton::Left,
+                    cx.listener(|this, _event, window, cx| {
+                        cx.stop_propagation();
+                        this.toggle_keep_raw(window, cx);
+                    }),
+                ),
+        )
     }
 
     fn copy(&mut self, _: &Copy, _: &mut Window, cx: &mut Context<Self>) {
@@ -668,9 +750,13 @@ impl Render for UrlInput {
             .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_move(cx.listener(Self::on_mouse_move))
-            .child(UrlTextElement {
+            .flex()
+            .items_center()
+            .gap_2()
+            .child(div().flex_1().child(UrlTextElement {
                 input: cx.entity().clone(),
-            })
+            }))
+            .children(self.render_paste_marker(cx))
     }
 }
 
@@ -4,6 +4,9 @@ pub mod display;
 pub mod input;
 
 // Re-export commonly used types for backward compatibility
-pub use common::dropdown;
-pub use display::{history_list, method_selector, response_viewer};
+pub use common::{checkbox, drag_row, dropdown};
+pub use display::{
+    api_explorer, certificates_page, csv_grid, domains_page, graphql_docs, history_list,
+    message_log, method_selector, ndjson_viewer, response_viewer, snippets_page,
+};
 pub use input::{body_input, header_input, url_input};
@@ -0,0 +1,621 @@
+//! A small line-based assertion language for checking responses, used by
+//! the snippets saved in the assertion library.
+//!
+//! Each non-empty line that doesn't start with `#` is one check:
+//!
+//! ```text
+//! status in 200..299
+//! header Content-Type contains json
+//! header X-Total-Count exists
+//! json data is array
+//! json meta.page >= 1
+//! json data[0].id exists
+//! json error missing
+//! body contains "ok"
+//! latency < 1s
+//! ```
+//!
+//! `latency` is the response time in milliseconds; its limits may also be
+//! written as `250ms` or `1.5s`.
+
+use crate::errors::AppError;
+use serde_json::Value;
+use std::time::Duration;
+
+/// The response an assertion is checked against
+#[derive(Debug, Clone)]
+pub struct AssertionContext<'a> {
+    status: u16,
+    headers: &'a [(String, String)],
+    body: &'a str,
+    json: Option<Value>,
+    latency: Option<Duration>,
+}
+
+impl<'a> AssertionContext<'a> {
+    pub fn new(status: u16, headers: &'a [(String, String)], body: &'a str) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+            json: serde_json::from_str(body).ok(),
+            latency: None,
+        }
+    }
+
+    /// Time the response took to arrive, checked by `latency`
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Subject {
+    Status,
+    Header(String),
+    Json(Vec<PathSegment>),
+    Body,
+    Latency,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Check {
+    Exists,
+    Missing,
+    Equals(String),
+    NotEquals(String),
+    Compare(Ordering, f64),
+    Contains(String),
+    Is(JsonType),
+    Between(f64, f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Ordering {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum JsonType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonType {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "null" => Some(Self::Null),
+            "bool" | "boolean" => Some(Self::Bool),
+            "number" => Some(Self::Number),
+            "string" => Some(Self::String),
+            "array" => Some(Self::Array),
+            "object" => Some(Self::Object),
+            _ => None,
+        }
+    }
+
+    fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => Self::Null,
+            Value::Bool(_) => Self::Bool,
+            Value::Number(_) => Self::Number,
+            Value::String(_) => Self::String,
+            Value::Array(_) => Self::Array,
+            Value::Object(_) => Self::Object,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Null => "null",
+            Self::Bool => "bool",
+            Self::Number => "number",
+            Self::String => "string",
+            Self::Array => "array",
+            Self::Object => "object",
+        }
+    }
+}
+
+/// One parsed line of a script
+#[derive(Debug, Clone, PartialEq)]
+pub struct Assertion {
+    /// The line as written, shown next to its result
+    pub source: String,
+    subject: Subject,
+    check: Check,
+}
+
+/// Result of checking one assertion
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssertionOutcome {
+    pub source: String,
+    /// Why the assertion failed, `None` if it passed
+    pub failure: Option<String>,
+}
+
+impl AssertionOutcome {
+    pub fn passed(&self) -> bool {
+        self.failure.is_none()
+    }
+}
+
+/// Parse every check of `script`; errors name the offending line
+pub fn parse_script(script: &str) -> Result<Vec<Assertion>, AppError> {
+    script
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            Assertion::parse(line)
+                .map_err(|e| AppError::ValidationError(format!("Line {number}: {e}")))
+        })
+        .collect()
+}
+
+/// Check every assertion of `script` against the response
+pub fn run_script(
+    script: &str,
+    context: &AssertionContext,
+) -> Result<Vec<AssertionOutcome>, AppError> {
+    Ok(parse_script(script)?
+        .iter()
+        .map(|assertion| assertion.check(context))
+        .collect())
+}
+
+impl Assertion {
+    fn parse(line: &str) -> Result<Self, String> {
+        let (subject, rest) = split_word(line);
+        let (subject, rest) = match subject {
+            "status" => (Subject::Status, rest),
+            "body" => (Subject::Body, rest),
+            "latency" => (Subject::Latency, rest),
+            "header" => {
+                let (name, rest) = split_word(rest);
+                if name.is_empty() {
+                    return Err("header needs a name".to_string());
+                }
+                (Subject::Header(name.to_string()), rest)
+            }
+            "json" => {
+                let (path, rest) = split_word(rest);
+                if path.is_empty() {
+                    return Err("json needs a path such as data[0].id".to_string());
+                }
+                (Subject::Json(parse_path(path)?), rest)
+            }
+            _ => {
+                return Err(format!(
+                    "expected status, header, json, body or latency, found \"{subject}\""
+                ))
+            }
+        };
+
+        let (operator, operand) = split_word(rest);
+        let parse_number = |text: &str| match subject {
+            Subject::Latency => parse_millis(text),
+            _ => text.parse::<f64>().ok(),
+        };
+        let number = || {
+            parse_number(operand)
+                .ok_or_else(|| format!("{operator} needs a number, found \"{operand}\""))
+        };
+        let check = match operator {
+            "exists" => Check::Exists,
+            "missing" => Check::Missing,
+            "==" => Check::Equals(operand.to_string()),
+            "!=" => Check::NotEquals(operand.to_string()),
+            "<" => Check::Compare(Ordering::Less, number()?),
+            "<=" => Check::Compare(Ordering::LessOrEqual, number()?),
+            ">" => Check::Compare(Ordering::Greater, number()?),
+            ">=" => Check::Compare(Ordering::GreaterOrEqual, number()?),
+            "contains" => Check::Contains(unquote(operand).to_string()),
+            "is" => Check::Is(
+                JsonType::parse(operand)
+                    .ok_or_else(|| format!("unknown type \"{operand}\" (e.g. array, object)"))?,
+            ),
+            "in" => {
+                let range = operand
+                    .split_once("..")
+                    .and_then(|(low, high)| Some((parse_number(low)?, parse_number(high)?)));
+                let (low, high) = range.ok_or_else(|| {
+                    format!("in needs a range such as 200..299, found \"{operand}\"")
+                })?;
+                Check::Between(low, high)
+            }
+            "" => return Err("missing a check such as ==, exists or contains".to_string()),
+            _ => return Err(format!("unknown check \"{operator}\"")),
+        };
+        let needs_operand = !matches!(check, Check::Exists | Check::Missing);
+        if needs_operand == operand.is_empty() {
+            return Err(if needs_operand {
+                format!("{operator} needs a value")
+            } else {
+                format!("{operator} takes no value")
+            });
+        }
+
+        Ok(Self {
+            source: line.to_string(),
+            subject,
+            check,
+        })
+    }
+
+    /// Check the assertion against a response
+    pub fn check(&self, context: &AssertionContext) -> AssertionOutcome {
+        AssertionOutcome {
+            source: self.source.clone(),
+            failure: self.evaluate(context).err(),
+        }
+    }
+
+    fn evaluate(&self, context: &AssertionContext) -> Result<(), String> {
+        let actual = self.resolve(context)?;
+        let what = self.subject_name();
+        let Some(actual) = actual else {
+            return match self.check {
+                Check::Missing => Ok(()),
+                _ => Err(format!("{what} is missing")),
+            };
+        };
+        let shown = show(&actual);
+
+        match &self.check {
+            Check::Exists => Ok(()),
+            Check::Missing => Err(format!("{what} is present ({shown})")),
+            Check::Equals(expected) => {
+                if matches(&actual, expected) {
+                    Ok(())
+                } else {
+                    Err(format!("expected {what} == {expected}, got {shown}"))
+                }
+            }
+            Check::NotEquals(expected) => {
+                if matches(&actual, expected) {
+                    Err(format!("expected {what} != {expected}"))
+                } else {
+                    Ok(())
+                }
+            }
+            Check::Compare(ordering, limit) => {
+                let value = as_number(&actual)
+                    .ok_or_else(|| format!("{what} is not a number ({shown})"))?;
+                let passed = match ordering {
+                    Ordering::Less => value < *limit,
+                    Ordering::LessOrEqual => value <= *limit,
+                    Ordering::Greater => value > *limit,
+                    Ordering::GreaterOrEqual => value >= *limit,
+                };
+                if passed {
+                    Ok(())
+                } else {
+                    Err(format!("{what} is {shown}"))
+                }
+            }
+            Check::Contains(needle) => {
+                let found = match &actual {
+                    Value::String(text) => text.contains(needle.as_str()),
+                    Value::Array(items) => items.iter().any(|item| matches(item, needle)),
+                    Value::Object(fields) => fields.contains_key(needle),
+                    other => other.to_string().contains(needle.as_str()),
+                };
+                if found {
+                    Ok(())
+                } else {
+                    Err(format!("{what} does not contain {needle}"))
+                }
+            }
+            Check::Is(expected) => {
+                let found = JsonType::of(&actual);
+                if found == *expected {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "expected {what} to be {}, got {}",
+                        expected.name(),
+                        found.name()
+                    ))
+                }
+            }
+            Check::Between(low, high) => {
+                let value = as_number(&actual)
+                    .ok_or_else(|| format!("{what} is not a number ({shown})"))?;
+                if (*low..=*high).contains(&value) {
+                    Ok(())
+                } else {
+                    Err(format!("{what} is {shown}, outside {low}..{high}"))
+                }
+            }
+        }
+    }
+
+    /// Text of the value the assertion looks at (strings without quotes),
+    /// `None` if it is absent
+    pub fn value(&self, context: &AssertionContext) -> Option<String> {
+        match self.resolve(context).ok()?? {
+            Value::String(text) => Some(text),
+            other => Some(other.to_string()),
+        }
+    }
+
+    // Value the assertion looks at, `None` if the header / field is absent
+    fn resolve(&self, context: &AssertionContext) -> Result<Option<Value>, String> {
+        Ok(match &self.subject {
+            Subject::Status => Some(Value::from(context.status)),
+            Subject::Body => Some(Value::String(context.body.to_string())),
+            Subject::Latency => {
+                let latency = context
+                    .latency
+                    .ok_or_else(|| "the response time was not measured".to_string())?;
+                Some(Value::from(latency.as_millis() as u64))
+            }
+            Subject::Header(name) => context
+                .headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| Value::String(value.clone())),
+            Subject::Json(path) => {
+                let json = context
+                    .json
+                    .as_ref()
+                    .ok_or_else(|| "the body is not JSON".to_string())?;
+                lookup(json, path).cloned()
+            }
+        })
+    }
+
+    fn subject_name(&self) -> String {
+        match &self.subject {
+            Subject::Status => "status".to_string(),
+            Subject::Body => "body".to_string(),
+            Subject::Latency => "latency".to_string(),
+            Subject::Header(name) => format!("header {name}"),
+            Subject::Json(_) => {
+                let (_, rest) = split_word(&self.source);
+                format!("json {}", split_word(rest).0)
+            }
+        }
+    }
+}
+
+// First whitespace separated word and the trimmed rest
+fn split_word(text: &str) -> (&str, &str) {
+    let text = text.trim();
+    match text.split_once(char::is_whitespace) {
+        Some((word, rest)) => (word, rest.trim()),
+        None => (text, ""),
+    }
+}
+
+// Milliseconds from `250`, `250ms` or `1.5s`
+fn parse_millis(text: &str) -> Option<f64> {
+    if let Some(millis) = text.strip_suffix("ms") {
+        millis.parse().ok()
+    } else if let Some(seconds) = text.strip_suffix('s') {
+        seconds.parse::<f64>().ok().map(|seconds| seconds * 1000.0)
+    } else {
+        text.parse().ok()
+    }
+}
+
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"')
+        .and_then(|text| text.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
+// `$.data[0].id`, `data[0].id` and `$` (the whole body)
+fn parse_path(path: &str) -> Result<Vec<PathSegment>, String> {
+    let path = path.strip_prefix('$').unwrap_or(path);
+    let path = path.strip_prefix('.').unwrap_or(path);
+    let mut segments = Vec::new();
+    for part in path.split('.').filter(|part| !part.is_empty()) {
+        let (key, mut indexes) = match part.find('[') {
+            Some(start) => part.split_at(start),
+            None => (part, ""),
+        };
+        if !key.is_empty() {
+            segments.push(PathSegment::Key(key.to_string()));
+        }
+        while !indexes.is_empty() {
+            let (index, rest) = indexes
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once(']'))
+                .ok_or_else(|| format!("bad index in \"{part}\""))?;
+            let index = index
+                .parse()
+                .map_err(|_| format!("bad index \"{index}\" in \"{part}\""))?;
+            segments.push(PathSegment::Index(index));
+            indexes = rest;
+        }
+    }
+    Ok(segments)
+}
+
+fn lookup<'v>(value: &'v Value, path: &[PathSegment]) -> Option<&'v Value> {
+    path.iter().try_fold(value, |value, segment| match segment {
+        PathSegment::Key(key) => value.get(key),
+        PathSegment::Index(index) => value.get(index),
+    })
+}
+
+// Strings (headers, the body, JSON strings) are compared with the text as
+// written; other values with the operand parsed as JSON
+fn matches(actual: &Value, expected: &str) -> bool {
+    match actual {
+        Value::String(text) => text == unquote(expected),
+        other => serde_json::from_str::<Value>(expected).is_ok_and(|expected| {
+            match (as_number(other), as_number(&expected)) {
+                (Some(a), Some(b)) => a == b,
+                _ => *other == expected,
+            }
+        }),
+    }
+}
+
+fn as_number(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+// Short form of a value for failure messages
+fn show(value: &Value) -> String {
+    const MAX_SHOWN: usize = 60;
+    let text = match value {
+        Value::String(text) => format!("\"{text}\""),
+        other => other.to_string(),
+    };
+    if text.chars().count() > MAX_SHOWN {
+        let shortened: String = text.chars().take(MAX_SHOWN).collect();
+        format!("{shortened}…")
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str =
+        r#"{"data":[{"id":1,"name":"Ada"}],"meta":{"page":1,"total":"3"},"next":null}"#;
+
+    fn headers() -> Vec<(String, String)> {
+        vec![
+            ("Content-Type".to_string(), "application/json".to_string()),
+            ("X-Total-Count".to_string(), "3".to_string()),
+        ]
+    }
+
+    fn run(script: &str) -> Vec<AssertionOutcome> {
+        let headers = headers();
+        run_script(script, &AssertionContext::new(200, &headers, BODY)).unwrap()
+    }
+
+    #[test]
+    fn test_passing_checks() {
+        let outcomes = run("# JSON API envelope\n\
+                            status in 200..299\n\
+                            status == 200\n\
+                            header content-type contains json\n\
+                            header X-Total-Count >= 1\n\
+                            json data is array\n\
+                            json $.data[0].id == 1\n\
+                            json data[0].name == \"Ada\"\n\
+                            json data[0] contains name\n\
+                            json meta.total == 3\n\
+                            json next is null\n\
+                            json error missing\n\
+                            body contains \"Ada\"\n");
+        assert_eq!(outcomes.len(), 12);
+        for outcome in &outcomes {
+            assert!(
+                outcome.passed(),
+                "{}: {:?}",
+                outcome.source,
+                outcome.failure
+            );
+        }
+    }
+
+    #[test]
+    fn test_failures_explain_the_value() {
+        let outcomes = run("status < 200\n\
+                            header Link exists\n\
+                            json data is object\n\
+                            json meta.page != 1\n\
+                            json data[5].id exists");
+        let failures: Vec<String> = outcomes
+            .into_iter()
+            .map(|outcome| outcome.failure.unwrap())
+            .collect();
+        assert_eq!(
+            failures,
+            [
+                "status is 200",
+                "header Link is missing",
+                "expected json data to be object, got array",
+                "expected json meta.page != 1",
+                "json data[5].id is missing",
+            ]
+        );
+
+        let headers = headers();
+        let outcomes = run_script(
+            "json id exists",
+            &AssertionContext::new(200, &headers, "not json"),
+        )
+        .unwrap();
+        assert_eq!(outcomes[0].failure.as_deref(), Some("the body is not JSON"));
+    }
+
+    #[test]
+    fn test_latency_and_values() {
+        let headers = headers();
+        let context =
+            AssertionContext::new(200, &headers, BODY).with_latency(Duration::from_millis(1200));
+        let outcomes = run_script(
+            "latency > 1s\nlatency in 0..1.5s\nlatency < 500ms",
+            &context,
+        )
+        .unwrap();
+        let failures: Vec<_> = outcomes.iter().map(|o| o.failure.as_deref()).collect();
+        assert_eq!(failures, [None, None, Some("latency is 1200")]);
+
+        let outcomes = run("latency < 1s");
+        assert_eq!(
+            outcomes[0].failure.as_deref(),
+            Some("the response time was not measured")
+        );
+
+        let assertions =
+            parse_script("json data[0].name exists\njson meta exists\njson error exists").unwrap();
+        let values: Vec<_> = assertions.iter().map(|a| a.value(&context)).collect();
+        assert_eq!(
+            values,
+            [
+                Some("Ada".to_string()),
+                Some(r#"{"page":1,"total":"3"}"#.to_string()),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        assert!(parse_script("").unwrap().is_empty());
+        for (script, message) in [
+            ("status == 200\nstatsu == 200", "Line 2"),
+            ("status >= ok", "needs a number"),
+            ("json data is list", "unknown type"),
+            ("header", "needs a name"),
+            ("status exists 200", "takes no value"),
+            ("json data[x] exists", "bad index"),
+            ("status in 200", "range"),
+            ("latency > soon", "needs a number"),
+        ] {
+            let error = parse_script(script).unwrap_err().to_string();
+            assert!(error.contains(message), "{script}: {error}");
+        }
+    }
+}
@@ -0,0 +1,103 @@
+//! Standard base64 (RFC 4648 §4) with padding, and the unpadded URL-safe
+//! variant (§5) used by JOSE.
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encode bytes as padded base64
+pub fn encode(input: &[u8]) -> String {
+    let mut output = String::with_capacity(input.len().div_ceil(3) * 4);
+    for chunk in input.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                output.push('=');
+            }
+        }
+    }
+    output
+}
+
+/// Decode padded or unpadded base64; `None` if the input is not valid base64
+pub fn decode(input: &str) -> Option<Vec<u8>> {
+    let input = input.trim().trim_end_matches('=');
+    let mut output = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in input.bytes() {
+        let value = ALPHABET.iter().position(|&a| a == c)? as u32;
+        buffer = (buffer << 6) | value;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            output.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // A single leftover character can't encode a whole byte
+    if bits >= 6 {
+        return None;
+    }
+    Some(output)
+}
+
+/// Encode bytes as unpadded base64url
+pub fn encode_url(input: &[u8]) -> String {
+    encode(input)
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            c => c,
+        })
+        .collect()
+}
+
+/// Decode base64url (with or without padding)
+pub fn decode_url(input: &str) -> Option<Vec<u8>> {
+    if input.contains(['+', '/']) {
+        return None;
+    }
+    decode(&input.replace('-', "+").replace('_', "/"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        assert_eq!(encode(b""), "");
+        assert_eq!(encode(b"f"), "Zg==");
+        assert_eq!(encode(b"fo"), "Zm8=");
+        assert_eq!(encode(b"foo"), "Zm9v");
+        assert_eq!(encode(b"user:pass"), "dXNlcjpwYXNz");
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(decode("Zg==").unwrap(), b"f");
+        assert_eq!(decode("Zm8").unwrap(), b"fo");
+        assert_eq!(decode("dXNlcjpwYXNz").unwrap(), b"user:pass");
+        assert_eq!(decode("Z"), None);
+        assert_eq!(decode("Zm9v!"), None);
+    }
+
+    #[test]
+    fn test_url_safe_round_trip() {
+        let bytes = [0xfb, 0xff, 0xbf];
+        assert_eq!(encode(&bytes), "+/+/");
+        assert_eq!(encode_url(&bytes), "-_-_");
+        assert_eq!(encode_url(b"f"), "Zg");
+        assert_eq!(decode_url("-_-_").unwrap(), bytes);
+        assert_eq!(decode_url("Zg").unwrap(), b"f");
+        assert_eq!(decode_url("+/+/"), None);
+    }
+}
@@ -0,0 +1,300 @@
+//! Shared pieces of the binary format decoders (MessagePack, CBOR, Protobuf):
+//! a format-neutral value tree rendered as pretty JSON, and a byte cursor.
+
+use crate::errors::AppError;
+use crate::utils::base64;
+
+/// Nesting limit for decoded values, so hostile input can't overflow the stack
+pub const MAX_DEPTH: usize = 128;
+
+/// A decoded value; richer than JSON so nothing is lost before rendering
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataValue {
+    Null,
+    Bool(bool),
+    Integer(i128),
+    Float(f64),
+    String(String),
+    Bytes(Vec<u8>),
+    Array(Vec<DataValue>),
+    /// Key/value pairs in wire order (keys are not necessarily strings)
+    Map(Vec<(DataValue, DataValue)>),
+    /// CBOR tagged item
+    Tagged(u64, Box<DataValue>),
+    /// MessagePack extension type
+    Ext(i8, Vec<u8>),
+    /// CBOR simple value without a JSON equivalent
+    Simple(u8),
+}
+
+impl DataValue {
+    /// Pretty JSON with two-space indentation.
+    ///
+    /// Bytes become base64 strings, non-string map keys are written as their
+    /// compact JSON, and tags / extensions become `{"$tag": .., "value": ..}`
+    /// / `{"$ext": .., "data": ..}` objects.
+    pub fn to_json_pretty(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, Some(0));
+        out
+    }
+
+    /// JSON on a single line
+    pub fn to_json_compact(&self) -> String {
+        let mut out = String::new();
+        self.write_json(&mut out, None);
+        out
+    }
+
+    fn write_json(&self, out: &mut String, indent: Option<usize>) {
+        match self {
+            DataValue::Null => out.push_str("null"),
+            DataValue::Bool(value) => out.push_str(if *value { "true" } else { "false" }),
+            DataValue::Integer(value) => out.push_str(&value.to_string()),
+            DataValue::Float(value) if value.is_finite() => out.push_str(&format!("{value:?}")),
+            DataValue::Float(value) => write_json_string(out, &value.to_string()),
+            DataValue::String(value) => write_json_string(out, value),
+            DataValue::Bytes(bytes) => write_json_string(out, &base64::encode(bytes)),
+            DataValue::Array(items) => {
+                write_container(out, indent, '[', ']', items, |item, out, indent| {
+                    item.write_json(out, indent)
+                });
+            }
+            DataValue::Map(entries) => {
+                write_container(
+                    out,
+                    indent,
+                    '{',
+                    '}',
+                    entries,
+                    |(key, value), out, indent| {
+                        match key {
+                            DataValue::String(key) => write_json_string(out, key),
+                            key => write_json_string(out, &key.to_json_compact()),
+                        }
+                        out.push_str(if indent.is_some() { ": " } else { ":" });
+                        value.write_json(out, indent);
+                    },
+                );
+            }
+            DataValue::Tagged(tag, value) => DataValue::Map(vec![
+                (
+                    DataValue::String("$tag".to_string()),
+                    DataValue::Integer(i128::from(*tag)),
+                ),
+                (DataValue::String("value".to_string()), (**value).clone()),
+            ])
+            .write_json(out, indent),
+            DataValue::Ext(kind, data) => DataValue::Map(vec![
+                (
+                    DataValue::String("$ext".to_string()),
+                    DataValue::Integer(i128::from(*kind)),
+                ),
+                (
+                    DataValue::String("data".to_string()),
+                    DataValue::Bytes(data.clone()),
+                ),
+            ])
+            .write_json(out, indent),
+            DataValue::Simple(value) => DataValue::Map(vec![(
+                DataValue::String("$simple".to_string()),
+                DataValue::Integer(i128::from(*value)),
+            )])
+            .write_json(out, indent),
+        }
+    }
+}
+
+fn write_container<T>(
+    out: &mut String,
+    indent: Option<usize>,
+    open: char,
+    close: char,
+    items: &[T],
+    mut write_item: impl FnMut(&T, &mut String, Option<usize>),
+) {
+    out.push(open);
+    if items.is_empty() {
+        out.push(close);
+        return;
+    }
+    let inner = indent.map(|level| level + 1);
+    for (i, item) in items.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if let Some(level) = inner {
+            out.push('\n');
+            out.push_str(&"  ".repeat(level));
+        }
+        write_item(item, out, inner);
+    }
+    if let Some(level) = indent {
+        out.push('\n');
+        out.push_str(&"  ".repeat(level));
+    }
+    out.push(close);
+}
+
+fn write_json_string(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if u32::from(c) < 0x20 => out.push_str(&format!("\\u{:04x}", u32::from(c))),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+/// Cursor over a byte slice; every read fails cleanly at the end of input
+pub struct ByteReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    /// Next byte without consuming it
+    pub fn peek(&self) -> Result<u8, AppError> {
+        self.data.get(self.pos).copied().ok_or_else(|| {
+            AppError::ParseError(format!("Unexpected end of data at byte {}", self.pos))
+        })
+    }
+
+    pub fn u8(&mut self) -> Result<u8, AppError> {
+        Ok(self.take(1)?[0])
+    }
+
+    pub fn take(&mut self, len: usize) -> Result<&'a [u8], AppError> {
+        if len > self.remaining() {
+            return Err(AppError::ParseError(format!(
+                "Unexpected end of data at byte {} (needed {len} more)",
+                self.pos
+            )));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    /// Bytes read since `start`, a previous [`ByteReader::position`]
+    pub fn since(&self, start: usize) -> &'a [u8] {
+        &self.data[start..self.pos]
+    }
+
+    /// Read `N` bytes as an array (big-endian decoding is up to the caller)
+    pub fn array<const N: usize>(&mut self) -> Result<[u8; N], AppError> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.take(N)?);
+        Ok(array)
+    }
+
+    /// Big-endian unsigned integer of `len` bytes (1, 2, 4 or 8)
+    pub fn uint_be(&mut self, len: usize) -> Result<u64, AppError> {
+        Ok(self
+            .take(len)?
+            .iter()
+            .fold(0u64, |acc, byte| (acc << 8) | u64::from(*byte)))
+    }
+
+    /// Length read from the input as a `usize`, checked against the bytes left
+    /// so a bogus length can't trigger a huge allocation
+    pub fn checked_len(&self, len: u64) -> Result<usize, AppError> {
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.remaining())
+            .ok_or_else(|| {
+                AppError::ParseError(format!(
+                    "Length {len} at byte {} exceeds the remaining data",
+                    self.pos
+                ))
+            })
+    }
+
+    pub fn utf8(&mut self, len: usize) -> Result<String, AppError> {
+        let start = self.pos;
+        String::from_utf8(self.take(len)?.to_vec())
+            .map_err(|_| AppError::ParseError(format!("Invalid UTF-8 string at byte {start}")))
+    }
+}
+
+/// Error for input nested deeper than [`MAX_DEPTH`]
+pub fn too_deep() -> AppError {
+    AppError::ParseError(format!("Data is nested deeper than {MAX_DEPTH} levels"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_json_pretty() {
+        let value = DataValue::Map(vec![
+            (
+                DataValue::String("name".to_string()),
+                DataValue::String("a\"b".to_string()),
+            ),
+            (
+                DataValue::Integer(1),
+                DataValue::Array(vec![DataValue::Float(1.5), DataValue::Null]),
+            ),
+            (
+                DataValue::String("empty".to_string()),
+                DataValue::Array(vec![]),
+            ),
+            (
+                DataValue::String("raw".to_string()),
+                DataValue::Bytes(b"foo".to_vec()),
+            ),
+        ]);
+        assert_eq!(
+            value.to_json_pretty(),
+            "{\n  \"name\": \"a\\\"b\",\n  \"1\": [\n    1.5,\n    null\n  ],\n  \"empty\": [],\n  \"raw\": \"Zm9v\"\n}"
+        );
+    }
+
+    #[test]
+    fn test_to_json_special_values() {
+        let value = DataValue::Array(vec![
+            DataValue::Tagged(1, Box::new(DataValue::Integer(-5))),
+            DataValue::Ext(-1, vec![0]),
+            DataValue::Float(f64::NAN),
+            DataValue::Float(2.0),
+        ]);
+        assert_eq!(
+            value.to_json_compact(),
+            r#"[{"$tag":1,"value":-5},{"$ext":-1,"data":"AA=="},"NaN",2.0]"#
+        );
+    }
+
+    #[test]
+    fn test_byte_reader_bounds() {
+        let mut reader = ByteReader::new(&[0x01, 0x02, 0x03]);
+        assert_eq!(reader.uint_be(2).unwrap(), 0x0102);
+        assert!(reader.checked_len(2).is_err());
+        assert!(reader.take(2).is_err());
+        assert_eq!(reader.u8().unwrap(), 0x03);
+        assert!(reader.is_empty());
+    }
+}
@@ -0,0 +1,233 @@
+//! CBOR decoding (RFC 8949).
+
+use crate::errors::AppError;
+use crate::utils::binary::{too_deep, ByteReader, DataValue, MAX_DEPTH};
+
+/// Additional-information value marking an indefinite-length item
+const INDEFINITE: u8 = 31;
+
+/// The "break" byte ending an indefinite-length item
+const BREAK: u8 = 0xff;
+
+/// Decode a single CBOR data item that spans the whole input
+pub fn decode(data: &[u8]) -> Result<DataValue, AppError> {
+    let mut reader = ByteReader::new(data);
+    let value = read_item(&mut reader, 0)?;
+    if !reader.is_empty() {
+        return Err(AppError::ParseError(format!(
+            "Unexpected data after the CBOR item at byte {}",
+            reader.position()
+        )));
+    }
+    Ok(value)
+}
+
+fn read_item(reader: &mut ByteReader, depth: usize) -> Result<DataValue, AppError> {
+    if depth > MAX_DEPTH {
+        return Err(too_deep());
+    }
+    let start = reader.position();
+    let initial = reader.u8()?;
+    let major = initial >> 5;
+    let info = initial & 0x1f;
+
+    if info == INDEFINITE {
+        return read_indefinite(reader, major, start, depth);
+    }
+    let argument = read_argument(reader, info, start)?;
+
+    let value = match major {
+        0 => DataValue::Integer(i128::from(argument)),
+        1 => DataValue::Integer(-1 - i128::from(argument)),
+        2 => {
+            let len = reader.checked_len(argument)?;
+            DataValue::Bytes(reader.take(len)?.to_vec())
+        }
+        3 => {
+            let len = reader.checked_len(argument)?;
+            DataValue::String(reader.utf8(len)?)
+        }
+        4 => {
+            // Every item takes at least one byte
+            let len = reader.checked_len(argument)?;
+            let mut items = Vec::with_capacity(len);
+            for _ in 0..len {
+                items.push(read_item(reader, depth + 1)?);
+            }
+            DataValue::Array(items)
+        }
+        5 => {
+            let len = reader.checked_len(argument)?;
+            let mut entries = Vec::with_capacity(len);
+            for _ in 0..len {
+                let key = read_item(reader, depth + 1)?;
+                let value = read_item(reader, depth + 1)?;
+                entries.push((key, value));
+            }
+            DataValue::Map(entries)
+        }
+        6 => DataValue::Tagged(argument, Box::new(read_item(reader, depth + 1)?)),
+        _ => read_simple(info, argument, start)?,
+    };
+    Ok(value)
+}
+
+/// Argument following the initial byte: inline below 24, else 1/2/4/8 bytes
+fn read_argument(reader: &mut ByteReader, info: u8, start: usize) -> Result<u64, AppError> {
+    match info {
+        0..=23 => Ok(u64::from(info)),
+        24..=27 => reader.uint_be(1 << (info - 24)),
+        _ => Err(AppError::ParseError(format!(
+            "Reserved CBOR additional information {info} at byte {start}"
+        ))),
+    }
+}
+
+/// Major type 7: simple values and floats (`argument` holds the raw float bits)
+fn read_simple(info: u8, argument: u64, start: usize) -> Result<DataValue, AppError> {
+    let value = match info {
+        20 => DataValue::Bool(false),
+        21 => DataValue::Bool(true),
+        // null and undefined
+        22 | 23 => DataValue::Null,
+        25 => DataValue::Float(half_to_f64(argument as u16)),
+        26 => DataValue::Float(f64::from(f32::from_bits(argument as u32))),
+        27 => DataValue::Float(f64::from_bits(argument)),
+        24 if argument < 32 => {
+            return Err(AppError::ParseError(format!(
+                "Invalid two-byte CBOR simple value at byte {start}"
+            )))
+        }
+        _ => DataValue::Simple(argument as u8),
+    };
+    Ok(value)
+}
+
+fn read_indefinite(
+    reader: &mut ByteReader,
+    major: u8,
+    start: usize,
+    depth: usize,
+) -> Result<DataValue, AppError> {
+    match major {
+        // Byte / text strings: definite-length chunks of the same type
+        2 | 3 => {
+            let mut bytes = Vec::new();
+            while !at_break(reader)? {
+                match read_item(reader, depth + 1)? {
+                    DataValue::Bytes(chunk) if major == 2 => bytes.extend(chunk),
+                    DataValue::String(chunk) if major == 3 => bytes.extend(chunk.into_bytes()),
+                    _ => {
+                        return Err(AppError::ParseError(format!(
+                            "Invalid chunk in indefinite-length string at byte {start}"
+                        )))
+                    }
+                }
+            }
+            if major == 2 {
+                Ok(DataValue::Bytes(bytes))
+            } else {
+                // Chunks are valid UTF-8 on their own, so the whole is too
+                Ok(DataValue::String(
+                    String::from_utf8(bytes).unwrap_or_default(),
+                ))
+            }
+        }
+        4 => {
+            let mut items = Vec::new();
+            while !at_break(reader)? {
+                items.push(read_item(reader, depth + 1)?);
+            }
+            Ok(DataValue::Array(items))
+        }
+        5 => {
+            let mut entries = Vec::new();
+            while !at_break(reader)? {
+                let key = read_item(reader, depth + 1)?;
+                let value = read_item(reader, depth + 1)?;
+                entries.push((key, value));
+            }
+            Ok(DataValue::Map(entries))
+        }
+        _ => Err(AppError::ParseError(format!(
+            "Unexpected CBOR break or indefinite length at byte {start}"
+        ))),
+    }
+}
+
+/// Consume the break byte if it comes next
+fn at_break(reader: &mut ByteReader) -> Result<bool, AppError> {
+    if reader.peek()? == BREAK {
+        reader.u8()?;
+        return Ok(true);
+    }
+    Ok(false)
+}
+
+/// IEEE 754 half precision to double
+fn half_to_f64(bits: u16) -> f64 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = i32::from((bits >> 10) & 0x1f);
+    let mantissa = f64::from(bits & 0x03ff);
+    let magnitude = match exponent {
+        0 => mantissa * 2f64.powi(-24),
+        31 if mantissa == 0.0 => f64::INFINITY,
+        31 => f64::NAN,
+        _ => (1.0 + mantissa / 1024.0) * 2f64.powi(exponent - 15),
+    };
+    sign * magnitude
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_rfc_examples() {
+        // {"a": 1, "b": [2, 3]}
+        let data = [0xa2, 0x61, b'a', 0x01, 0x61, b'b', 0x82, 0x02, 0x03];
+        assert_eq!(
+            decode(&data).unwrap().to_json_compact(),
+            r#"{"a":1,"b":[2,3]}"#
+        );
+        assert_eq!(decode(&[0x38, 0x63]).unwrap(), DataValue::Integer(-100));
+        assert_eq!(
+            decode(&[0x3b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).unwrap(),
+            DataValue::Integer(-18_446_744_073_709_551_616)
+        );
+        assert_eq!(decode(&[0xf9, 0x3e, 0x00]).unwrap(), DataValue::Float(1.5));
+        assert_eq!(decode(&[0xf9, 0xc4, 0x00]).unwrap(), DataValue::Float(-4.0));
+        assert_eq!(decode(&[0xf6]).unwrap(), DataValue::Null);
+    }
+
+    #[test]
+    fn test_decode_indefinite_and_tags() {
+        // [_ 1, {_ "a": (_ "x" "y")}]
+        let data = [
+            0x9f, 0x01, 0xbf, 0x61, b'a', 0x7f, 0x61, b'x', 0x61, b'y', 0xff, 0xff, 0xff,
+        ];
+        assert_eq!(
+            decode(&data).unwrap().to_json_compact(),
+            r#"[1,{"a":"xy"}]"#
+        );
+        // 1(1363896240) - epoch-based date/time
+        let data = [0xc1, 0x1a, 0x51, 0x4b, 0x67, 0xb0];
+        assert_eq!(
+            decode(&data).unwrap().to_json_compact(),
+            r#"{"$tag":1,"value":1363896240}"#
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        // Truncated array
+        assert!(decode(&[0x82, 0x01]).is_err());
+        // Lone break
+        assert!(decode(&[0xff]).is_err());
+        // Text chunk inside an indefinite byte string
+        assert!(decode(&[0x5f, 0x61, b'a', 0xff]).is_err());
+        // Huge length
+        assert!(decode(&[0x9b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).is_err());
+        assert!(decode(&[0x81; 1000]).is_err());
+    }
+}
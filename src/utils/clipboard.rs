@@ -0,0 +1,277 @@
+use crate::models::Request;
+use crate::utils::csv::parse_records;
+use crate::utils::curl::parse_curl;
+
+/// Something on the clipboard that can be imported as a request
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipboardImport {
+    /// A `curl ...` command line
+    Curl(Request),
+    /// A bare `http(s)://` URL
+    Url(Request),
+}
+
+impl ClipboardImport {
+    pub fn request(&self) -> &Request {
+        match self {
+            ClipboardImport::Curl(request) | ClipboardImport::Url(request) => request,
+        }
+    }
+
+    /// Short description for the import prompt
+    pub fn label(&self) -> String {
+        match self {
+            ClipboardImport::Curl(request) => {
+                format!(
+                    "Import copied curl command ({} {})?",
+                    request.method, request.url
+                )
+            }
+            ClipboardImport::Url(request) => format!("Open copied URL {}?", request.url),
+        }
+    }
+}
+
+/// Check whether clipboard text is a curl command or a bare URL
+pub fn detect_import(text: &str) -> Option<ClipboardImport> {
+    let text = text.trim();
+
+    if text.starts_with("curl ") || text.starts_with("curl\t") {
+        return parse_curl(text).ok().map(ClipboardImport::Curl);
+    }
+
+    let is_url = (text.starts_with("http://") || text.starts_with("https://"))
+        && !text.chars().any(char::is_whitespace)
+        && text.len() > "https://".len();
+    if is_url {
+        return Some(ClipboardImport::Url(Request::new("GET", text)));
+    }
+
+    None
+}
+
+/// Key/value rows in pasted text copied from a spreadsheet (tab-separated)
+/// or a CSV file, one row per line. Columns past the second are ignored and
+/// a `key`/`value` header row is skipped. `None` when the text doesn't look
+/// like a table: no tabs, and not several lines that all have a comma
+pub fn parse_pasted_rows(text: &str) -> Option<Vec<(String, String)>> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let lines: Vec<&str> = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .collect();
+    let records: Vec<Vec<String>> = if text.contains('\t') {
+        lines
+            .iter()
+            .map(|line| line.split('\t').map(str::to_string).collect())
+            .collect()
+    } else if lines.len() > 1 && lines.iter().all(|line| line.contains(',')) {
+        parse_records(text)
+    } else {
+        return None;
+    };
+
+    let mut rows: Vec<(String, String)> = records
+        .into_iter()
+        .map(|cells| {
+            let mut cells = cells.into_iter().map(|cell| cell.trim().to_string());
+            (
+                cells.next().unwrap_or_default(),
+                cells.next().unwrap_or_default(),
+            )
+        })
+        .filter(|(key, _)| !key.is_empty())
+        .collect();
+    let is_header = rows.first().is_some_and(|(key, value)| {
+        (key.eq_ignore_ascii_case("key") || key.eq_ignore_ascii_case("name"))
+            && value.eq_ignore_ascii_case("value")
+    });
+    if is_header {
+        rows.remove(0);
+    }
+    (!rows.is_empty()).then_some(rows)
+}
+
+/// The URL with the rows appended to its query string (before any fragment)
+pub fn append_query_rows(url: &str, rows: &[(String, String)]) -> String {
+    let (base, fragment) = match url.split_once('#') {
+        Some((base, fragment)) => (base, Some(fragment)),
+        None => (url, None),
+    };
+    let query = form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(rows)
+        .finish();
+    let separator = if !base.contains('?') {
+        "?"
+    } else if base.ends_with('?') || base.ends_with('&') {
+        ""
+    } else {
+        "&"
+    };
+    let mut result = format!("{base}{separator}{query}");
+    if let Some(fragment) = fragment {
+        result.push('#');
+        result.push_str(fragment);
+    }
+    result
+}
+
+/// A paste into a one-line field that had invisible characters cleaned out
+#[derive(Debug, Clone, PartialEq)]
+pub struct CleanedPaste {
+    /// The pasted text as it would go in without cleaning (line feeds are
+    /// still dropped, since the field is one line)
+    pub raw: String,
+    pub text: String,
+    /// What was removed, e.g. `byte order mark`
+    pub removed: Vec<&'static str>,
+}
+
+impl CleanedPaste {
+    /// e.g. `Removed byte order mark and trailing whitespace`
+    pub fn summary(&self) -> String {
+        let removed = match self.removed.split_last() {
+            Some((last, [])) => last.to_string(),
+            Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+            None => "nothing".to_string(),
+        };
+        format!("Removed {removed}")
+    }
+}
+
+// Characters that take no space, which servers reject but nobody can see
+fn is_zero_width(c: char) -> bool {
+    matches!(c, '\u{200b}' | '\u{200c}' | '\u{200d}' | '\u{2060}')
+}
+
+/// Clean text pasted into a URL or header field: byte order marks,
+/// zero-width characters, surrounding whitespace and line breaks copied
+/// along with a value are what usually turn a request into a baffling 400.
+/// `None` if there was nothing to clean
+pub fn clean_pasted_line(text: &str) -> Option<CleanedPaste> {
+    let mut removed = Vec::new();
+    let mut cleaned = text.to_string();
+    if cleaned.contains('\u{feff}') {
+        cleaned.retain(|c| c != '\u{feff}');
+        removed.push("byte order mark");
+    }
+    if cleaned.contains(is_zero_width) {
+        cleaned.retain(|c| !is_zero_width(c));
+        removed.push("zero-width characters");
+    }
+    let start = cleaned.len() - cleaned.trim_start().len();
+    let end = cleaned.trim_end().len();
+    match (start > 0, end < cleaned.len()) {
+        (true, true) => removed.push("leading and trailing whitespace"),
+        (true, false) => removed.push("leading whitespace"),
+        (false, true) => removed.push("trailing whitespace"),
+        (false, false) => {}
+    }
+    let mut cleaned = cleaned.trim().to_string();
+    if cleaned.contains(['\r', '\n']) {
+        cleaned.retain(|c| c != '\r' && c != '\n');
+        removed.push("line breaks");
+    }
+
+    (!removed.is_empty()).then(|| CleanedPaste {
+        raw: text.replace('\n', ""),
+        text: cleaned,
+        removed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::HttpMethod;
+
+    #[test]
+    fn test_detect_curl() {
+        let import = detect_import("  curl -X DELETE https://example.com/users/1\n").unwrap();
+        assert!(matches!(import, ClipboardImport::Curl(_)));
+        assert_eq!(import.request().method, HttpMethod::DELETE);
+    }
+
+    #[test]
+    fn test_detect_url() {
+        let import = detect_import("https://example.com/users?page=2").unwrap();
+        assert!(matches!(import, ClipboardImport::Url(_)));
+        assert_eq!(import.request().url, "https://example.com/users?page=2");
+    }
+
+    #[test]
+    fn test_ignore_other_text() {
+        assert!(detect_import("hello world").is_none());
+        assert!(detect_import("see https://example.com for details").is_none());
+        assert!(detect_import("https://").is_none());
+        assert!(detect_import("curl 'broken").is_none());
+    }
+
+    #[test]
+    fn test_parse_pasted_rows() {
+        let rows = |pairs: &[(&str, &str)]| -> Vec<(String, String)> {
+            pairs
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect()
+        };
+
+        // Spreadsheet cells, with a header row and an extra column
+        let pasted = "Key\tValue\tNotes\r\nname\tAda Lovelace\tx\r\nflag\r\n\r\n";
+        assert_eq!(
+            parse_pasted_rows(pasted).unwrap(),
+            rows(&[("name", "Ada Lovelace"), ("flag", "")])
+        );
+
+        // CSV with a quoted comma
+        assert_eq!(
+            parse_pasted_rows("city, \"Paris, France\"\npage,2\n").unwrap(),
+            rows(&[("city", "Paris, France"), ("page", "2")])
+        );
+
+        assert!(parse_pasted_rows("hello, world").is_none());
+        assert!(parse_pasted_rows("first line\nsecond line").is_none());
+        assert!(parse_pasted_rows("key\tvalue").is_none());
+    }
+
+    #[test]
+    fn test_clean_pasted_line() {
+        let cleaned = clean_pasted_line("\u{feff}https://example.com/users \r\n").unwrap();
+        assert_eq!(cleaned.text, "https://example.com/users");
+        assert_eq!(cleaned.raw, "\u{feff}https://example.com/users \r");
+        assert_eq!(
+            cleaned.summary(),
+            "Removed byte order mark and trailing whitespace"
+        );
+
+        let cleaned = clean_pasted_line("\u{a0}Bearer abc\u{200b}\r\ndef\t").unwrap();
+        assert_eq!(cleaned.text, "Bearer abcdef");
+        assert_eq!(
+            cleaned.summary(),
+            "Removed zero-width characters, leading and trailing whitespace and line breaks"
+        );
+
+        assert_eq!(clean_pasted_line("application/json"), None);
+        assert_eq!(clean_pasted_line("a b"), None);
+    }
+
+    #[test]
+    fn test_append_query_rows() {
+        let rows = vec![
+            ("q".to_string(), "a b".to_string()),
+            ("page".to_string(), "2".to_string()),
+        ];
+        assert_eq!(
+            append_query_rows("https://example.com/search", &rows),
+            "https://example.com/search?q=a+b&page=2"
+        );
+        assert_eq!(
+            append_query_rows("https://example.com/search?lang=en#top", &rows),
+            "https://example.com/search?lang=en&q=a+b&page=2#top"
+        );
+        assert_eq!(
+            append_query_rows("https://example.com/search?", &rows),
+            "https://example.com/search?q=a+b&page=2"
+        );
+    }
+}
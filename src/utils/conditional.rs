@@ -0,0 +1,121 @@
+use crate::http::response::header_value;
+
+/// Precondition headers that can be built from a previous response
+/// (RFC 9110 §13.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionalHeader {
+    /// Only act if the resource still has the given ETag
+    IfMatch,
+    /// Only act if the resource has not changed since the given date
+    IfUnmodifiedSince,
+}
+
+impl ConditionalHeader {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConditionalHeader::IfMatch => "If-Match",
+            ConditionalHeader::IfUnmodifiedSince => "If-Unmodified-Since",
+        }
+    }
+}
+
+/// Validators captured from a response's `ETag` and `Last-Modified` headers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    pub fn from_headers(headers: &[(String, String)]) -> Self {
+        let value = |name| {
+            header_value(headers, name)
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .map(str::to_string)
+        };
+        Self {
+            etag: value("ETag"),
+            last_modified: value("Last-Modified"),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+
+    /// Whether the ETag is weak (`W/"..."`).
+    ///
+    /// `If-Match` uses strong comparison, so a weak ETag never matches and
+    /// the server answers 412 Precondition Failed.
+    pub fn has_weak_etag(&self) -> bool {
+        self.etag
+            .as_deref()
+            .is_some_and(|etag| etag.starts_with("W/"))
+    }
+
+    /// Headers (name and value) that can be filled from these validators
+    pub fn conditional_headers(&self) -> Vec<(ConditionalHeader, String)> {
+        let mut headers = Vec::new();
+        if let Some(etag) = &self.etag {
+            headers.push((ConditionalHeader::IfMatch, etag.clone()));
+        }
+        if let Some(last_modified) = &self.last_modified {
+            headers.push((ConditionalHeader::IfUnmodifiedSince, last_modified.clone()));
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_validators_from_headers() {
+        let validators = Validators::from_headers(&headers(&[
+            ("content-type", "application/json"),
+            ("etag", "\"33a64df5\""),
+            ("Last-Modified", "Wed, 21 Oct 2015 07:28:00 GMT"),
+        ]));
+        assert_eq!(validators.etag.as_deref(), Some("\"33a64df5\""));
+        assert!(!validators.has_weak_etag());
+        assert_eq!(
+            validators.conditional_headers(),
+            vec![
+                (ConditionalHeader::IfMatch, "\"33a64df5\"".to_string()),
+                (
+                    ConditionalHeader::IfUnmodifiedSince,
+                    "Wed, 21 Oct 2015 07:28:00 GMT".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_validators_missing_or_weak() {
+        let validators = Validators::from_headers(&headers(&[("ETag", " ")]));
+        assert!(validators.is_empty());
+        assert!(validators.conditional_headers().is_empty());
+
+        let validators = Validators::from_headers(&headers(&[("ETag", "W/\"abc\"")]));
+        assert!(validators.has_weak_etag());
+        assert_eq!(validators.conditional_headers().len(), 1);
+    }
+
+    #[test]
+    fn test_conditional_header_names() {
+        assert_eq!(ConditionalHeader::IfMatch.name(), "If-Match");
+        assert_eq!(
+            ConditionalHeader::IfUnmodifiedSince.name(),
+            "If-Unmodified-Since"
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! CSV (RFC 4180) parsing and writing for the response grid.
+
+use std::cmp::Ordering;
+
+/// Whether a `Content-Type` header value is CSV
+pub fn is_csv_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    matches!(mime.as_str(), "text/csv" | "application/csv")
+}
+
+/// A parsed CSV body: the first record is the header row
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CsvTable {
+    pub headers: Vec<String>,
+    pub rows: Vec<Vec<String>>,
+}
+
+impl CsvTable {
+    /// Parse comma-separated text. Quoted fields may contain commas, quotes
+    /// (doubled) and line breaks; short rows are padded so every row has
+    /// [`CsvTable::column_count`] cells.
+    pub fn parse(text: &str) -> Self {
+        let mut records = parse_records(text.strip_prefix('\u{feff}').unwrap_or(text));
+        if records.is_empty() {
+            return Self::default();
+        }
+        let mut headers = records.remove(0);
+        let columns = records
+            .iter()
+            .map(Vec::len)
+            .chain(std::iter::once(headers.len()))
+            .max()
+            .unwrap_or_default();
+        // Unnamed columns (rows longer than the header) get their number
+        for index in headers.len()..columns {
+            headers.push(format!("Column {}", index + 1));
+        }
+        for row in &mut records {
+            row.resize(columns, String::new());
+        }
+        Self {
+            headers,
+            rows: records,
+        }
+    }
+
+    pub fn column_count(&self) -> usize {
+        self.headers.len()
+    }
+
+    /// Row indices ordered by `column`. Numbers compare numerically, other
+    /// text case-insensitively; the sort is stable.
+    pub fn sort_order(&self, column: usize, ascending: bool) -> Vec<usize> {
+        let mut order: Vec<usize> = (0..self.rows.len()).collect();
+        order.sort_by(|a, b| {
+            let ordering = compare_cells(&self.rows[*a][column], &self.rows[*b][column]);
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+        order
+    }
+
+    /// Write the header and the rows in `order` back out as CSV
+    pub fn to_csv(&self, order: &[usize]) -> String {
+        let mut out = String::new();
+        write_record(&mut out, &self.headers);
+        for index in order {
+            write_record(&mut out, &self.rows[*index]);
+        }
+        out
+    }
+}
+
+/// Number of pages needed for `rows` rows (at least one, so an empty table
+/// still has a page to show)
+pub fn page_count(rows: usize, page_size: usize) -> usize {
+    rows.div_ceil(page_size.max(1)).max(1)
+}
+
+/// Records of comma-separated text, with quoted fields unescaped; blank
+/// lines are skipped
+pub fn parse_records(text: &str) -> Vec<Vec<String>> {
+    let mut records = Vec::new();
+    let mut record = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    // Whether the current record has any content (blank lines are skipped)
+    let mut started = false;
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            match c {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                c => field.push(c),
+            }
+            continue;
+        }
+        match c {
+            '"' => {
+                in_quotes = true;
+                started = true;
+            }
+            ',' => {
+                record.push(std::mem::take(&mut field));
+                started = true;
+            }
+            '\r' | '\n' => {
+                if c == '\r' && chars.peek() == Some(&'\n') {
+                    chars.next();
+                }
+                if started || !field.is_empty() {
+                    record.push(std::mem::take(&mut field));
+                    records.push(std::mem::take(&mut record));
+                }
+                started = false;
+            }
+            c => {
+                field.push(c);
+                started = true;
+            }
+        }
+    }
+    if started || !field.is_empty() {
+        record.push(field);
+        records.push(record);
+    }
+    records
+}
+
+fn compare_cells(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.partial_cmp(&b).unwrap_or(Ordering::Equal),
+        // Numbers before text
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+fn write_record(out: &mut String, fields: &[String]) {
+    for (i, field) in fields.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        if field.contains([',', '"', '\r', '\n']) {
+            out.push('"');
+            out.push_str(&field.replace('"', "\"\""));
+            out.push('"');
+        } else {
+            out.push_str(field);
+        }
+    }
+    out.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type() {
+        assert!(is_csv_content_type("text/csv; charset=utf-8"));
+        assert!(is_csv_content_type("Application/CSV"));
+        assert!(!is_csv_content_type("text/plain"));
+    }
+
+    #[test]
+    fn test_parse_quoted_fields() {
+        let table = CsvTable::parse(
+            "\u{feff}name,note\r\n\"Smith, J\",\"said \"\"hi\"\"\nthen left\"\r\n\nsolo\r\n",
+        );
+        assert_eq!(table.headers, vec!["name", "note"]);
+        assert_eq!(
+            table.rows,
+            vec![
+                vec!["Smith, J".to_string(), "said \"hi\"\nthen left".to_string()],
+                vec!["solo".to_string(), String::new()],
+            ]
+        );
+        assert_eq!(CsvTable::parse(""), CsvTable::default());
+    }
+
+    #[test]
+    fn test_ragged_rows_are_padded() {
+        let table = CsvTable::parse("a\n1,2,3\n4");
+        assert_eq!(table.headers, vec!["a", "Column 2", "Column 3"]);
+        assert_eq!(table.rows[1], vec!["4", "", ""]);
+    }
+
+    #[test]
+    fn test_sort_order() {
+        let table = CsvTable::parse("id,name\n10,bob\n9,Alice\nn/a,carol\n10,dave");
+        assert_eq!(table.sort_order(0, true), vec![1, 0, 3, 2]);
+        assert_eq!(table.sort_order(0, false), vec![2, 0, 3, 1]);
+        assert_eq!(table.sort_order(1, true), vec![1, 0, 2, 3]);
+    }
+
+    #[test]
+    fn test_to_csv_round_trip() {
+        let text = "name,note\r\n\"Smith, J\",\"a \"\"b\"\"\"\r\nx,y\r\n";
+        let table = CsvTable::parse(text);
+        assert_eq!(table.to_csv(&[0, 1]), text);
+        assert_eq!(table.to_csv(&[1]), "name,note\r\nx,y\r\n");
+    }
+
+    #[test]
+    fn test_page_count() {
+        assert_eq!(page_count(0, 50), 1);
+        assert_eq!(page_count(50, 50), 1);
+        assert_eq!(page_count(51, 50), 2);
+    }
+}
@@ -0,0 +1,382 @@
+use crate::errors::AppError;
+use crate::models::{HttpMethod, Request};
+use crate::utils::{base64, multipart};
+
+/// Short options that take a value, which curl also accepts attached (`-XPOST`)
+const SHORT_OPTIONS_WITH_VALUE: &[&str] = &[
+    "-X", "-H", "-d", "-A", "-b", "-e", "-u", "-F", "-o", "-x", "-m", "-w",
+];
+
+/// Parse a curl command line into a [`Request`].
+///
+/// Handles the options commonly produced by "Copy as cURL" in browsers and
+/// API docs: `-X`, `-H`, `-d`/`--data*`, `--url`, `-A`, `-b`, `-e`, `-u`,
+/// `-F`, `-I` and `-G`. `-u` becomes a Basic `Authorization` header and `-F`
+/// fields become a `multipart/form-data` body. Other flags are ignored.
+pub fn parse_curl(command: &str) -> Result<Request, AppError> {
+    let tokens = tokenize(command)?;
+    let mut tokens = tokens.into_iter();
+
+    match tokens.next() {
+        Some(first) if first == "curl" => {}
+        _ => return Err(AppError::ParseError("Not a curl command".to_string())),
+    }
+
+    let mut method: Option<HttpMethod> = None;
+    let mut url: Option<String> = None;
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut data: Vec<String> = Vec::new();
+    let mut form: Vec<(String, String)> = Vec::new();
+    let mut get_with_data = false;
+
+    while let Some(token) = tokens.next() {
+        let (flag, inline_value) = split_flag(&token);
+        match flag {
+            "-X" | "--request" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                method = Some(HttpMethod::from_str(&value).map_err(AppError::ParseError)?);
+            }
+            "-H" | "--header" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                if let Some((key, value)) = value.split_once(':') {
+                    headers.push((key.trim().to_string(), value.trim().to_string()));
+                }
+            }
+            "-d" | "--data" | "--data-raw" | "--data-binary" | "--data-ascii"
+            | "--data-urlencode" | "--json" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                if flag == "--json" {
+                    headers.push(("Content-Type".to_string(), "application/json".to_string()));
+                    headers.push(("Accept".to_string(), "application/json".to_string()));
+                }
+                data.push(value);
+            }
+            "-A" | "--user-agent" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                headers.push(("User-Agent".to_string(), value));
+            }
+            "-b" | "--cookie" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                headers.push(("Cookie".to_string(), value));
+            }
+            "-e" | "--referer" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                headers.push(("Referer".to_string(), value));
+            }
+            "-u" | "--user" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                headers.push((
+                    "Authorization".to_string(),
+                    format!("Basic {}", base64::encode(value.as_bytes())),
+                ));
+            }
+            "-F" | "--form" => {
+                let value = take_value(inline_value, &mut tokens, flag)?;
+                let (name, value) = value
+                    .split_once('=')
+                    .ok_or_else(|| AppError::ParseError(format!("Invalid form field: {value}")))?;
+                // `;type=...` and other part attributes aren't kept
+                let value = match value.split_once(';') {
+                    Some((file, _)) if multipart::file_path(file).is_some() => file,
+                    _ => value,
+                };
+                form.push((name.to_string(), value.to_string()));
+            }
+            "--url" => {
+                url = Some(take_value(inline_value, &mut tokens, flag)?);
+            }
+            "-I" | "--head" => method = Some(HttpMethod::HEAD),
+            "-G" | "--get" => get_with_data = true,
+            // Options that take a value we don't use
+            "-o" | "--output" | "-x" | "--proxy" | "-m" | "--max-time" | "--connect-timeout"
+            | "-w" | "--write-out" | "--cacert" | "--cert" | "--key" => {
+                take_value(inline_value, &mut tokens, flag)?;
+            }
+            _ if token.starts_with('-') => {}
+            _ => {
+                if url.is_none() {
+                    url = Some(token);
+                }
+            }
+        }
+    }
+
+    let mut url = url.ok_or_else(|| AppError::ParseError("curl command has no URL".to_string()))?;
+    if !form.is_empty() && (!data.is_empty() || get_with_data) {
+        return Err(AppError::ParseError(
+            "curl command mixes -F with -d or -G".to_string(),
+        ));
+    }
+    let body = if !form.is_empty() {
+        // Form bodies are stored url-encoded and sent as multipart because
+        // of the boundary in the Content-Type
+        let has_content_type = headers
+            .iter()
+            .any(|(key, _)| key.eq_ignore_ascii_case("content-type"));
+        if !has_content_type {
+            headers.push((
+                "Content-Type".to_string(),
+                format!(
+                    "multipart/form-data; boundary={}",
+                    multipart::new_boundary()
+                ),
+            ));
+        }
+        let mut encoder = form_urlencoded::Serializer::new(String::new());
+        encoder.extend_pairs(form.iter());
+        Some(encoder.finish())
+    } else if data.is_empty() {
+        None
+    } else {
+        Some(data.join("&"))
+    };
+
+    let mut request = if get_with_data {
+        if let Some(query) = body {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query);
+        }
+        Request::new(method.unwrap_or(HttpMethod::GET), url)
+    } else {
+        // curl switches to POST when data is sent
+        let default_method = if body.is_some() {
+            HttpMethod::POST
+        } else {
+            HttpMethod::GET
+        };
+        let mut request = Request::new(method.unwrap_or(default_method), url);
+        request.body = body;
+        request
+    };
+    request.headers = headers;
+
+    Ok(request)
+}
+
+/// Split `--flag=value` and `-Xvalue` into the flag and its value
+fn split_flag(token: &str) -> (&str, Option<String>) {
+    if token.starts_with("--") {
+        if let Some((flag, value)) = token.split_once('=') {
+            return (flag, Some(value.to_string()));
+        }
+    } else if token.len() > 2 && token.is_char_boundary(2) {
+        let (flag, value) = token.split_at(2);
+        if SHORT_OPTIONS_WITH_VALUE.contains(&flag) {
+            return (flag, Some(value.to_string()));
+        }
+    }
+    (token, None)
+}
+
+fn take_value(
+    inline_value: Option<String>,
+    tokens: &mut impl Iterator<Item = String>,
+    flag: &str,
+) -> Result<String, AppError> {
+    inline_value
+        .or_else(|| tokens.next())
+        .ok_or_else(|| AppError::ParseError(format!("Missing value for {flag}")))
+}
+
+/// Split a command line into words using POSIX shell quoting rules.
+///
+/// Supports single quotes, double quotes, `$'...'`, backslash escapes and
+/// line continuations (`\` or `^` at the end of a line).
+fn tokenize(command: &str) -> Result<Vec<String>, AppError> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\'' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated()),
+                    }
+                }
+            }
+            '"' => {
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => current.push(c),
+                            Some('\n') => {}
+                            Some(c) => {
+                                current.push('\\');
+                                current.push(c);
+                            }
+                            None => return Err(unterminated()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated()),
+                    }
+                }
+            }
+            '$' if chars.peek() == Some(&'\'') => {
+                chars.next();
+                in_token = true;
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some('\\') => match chars.next() {
+                            Some('n') => current.push('\n'),
+                            Some('t') => current.push('\t'),
+                            Some('r') => current.push('\r'),
+                            Some(c) => current.push(c),
+                            None => return Err(unterminated()),
+                        },
+                        Some(c) => current.push(c),
+                        None => return Err(unterminated()),
+                    }
+                }
+            }
+            '\\' => match chars.next() {
+                // Line continuation
+                Some('\n') => {}
+                Some('\r') if chars.peek() == Some(&'\n') => {
+                    chars.next();
+                }
+                Some(c) => {
+                    in_token = true;
+                    current.push(c);
+                }
+                None => {}
+            },
+            // Windows cmd line continuation
+            '^' if matches!(chars.peek(), Some('\n') | Some('\r')) => {}
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                in_token = true;
+                current.push(c);
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+
+    Ok(tokens)
+}
+
+fn unterminated() -> AppError {
+    AppError::ParseError("Unterminated quote in curl command".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_simple_get() {
+        let request = parse_curl("curl https://api.example.com/users").unwrap();
+        assert_eq!(request.method, HttpMethod::GET);
+        assert_eq!(request.url, "https://api.example.com/users");
+        assert!(request.headers.is_empty());
+        assert!(request.body.is_none());
+    }
+
+    #[test]
+    fn test_parse_post_with_headers_and_body() {
+        let command = r#"curl -X POST 'https://api.example.com/users' \
+  -H 'Content-Type: application/json' \
+  -H "Authorization: Bearer abc" \
+  --data-raw '{"name": "John"}'"#;
+        let request = parse_curl(command).unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(request.url, "https://api.example.com/users");
+        assert_eq!(
+            request.headers,
+            vec![
+                ("Content-Type".to_string(), "application/json".to_string()),
+                ("Authorization".to_string(), "Bearer abc".to_string()),
+            ]
+        );
+        assert_eq!(request.body.as_deref(), Some(r#"{"name": "John"}"#));
+    }
+
+    #[test]
+    fn test_data_implies_post() {
+        let request = parse_curl("curl -d a=1 -d b=2 https://example.com").unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(request.body.as_deref(), Some("a=1&b=2"));
+    }
+
+    #[test]
+    fn test_get_with_data_moves_to_query() {
+        let request = parse_curl("curl -G -d q=rust https://example.com/search?page=1").unwrap();
+        assert_eq!(request.method, HttpMethod::GET);
+        assert_eq!(request.url, "https://example.com/search?page=1&q=rust");
+        assert!(request.body.is_none());
+    }
+
+    #[test]
+    fn test_ignores_unknown_flags() {
+        let request =
+            parse_curl("curl -sSL --compressed -o out.json --url=https://example.com -I").unwrap();
+        assert_eq!(request.method, HttpMethod::HEAD);
+        assert_eq!(request.url, "https://example.com");
+    }
+
+    #[test]
+    fn test_attached_short_values() {
+        let request =
+            parse_curl("curl -XPOST -H'Accept: text/plain' -d'a=1' https://example.com").unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        assert_eq!(
+            request.headers,
+            vec![("Accept".to_string(), "text/plain".to_string())]
+        );
+        assert_eq!(request.body.as_deref(), Some("a=1"));
+    }
+
+    #[test]
+    fn test_user_becomes_basic_auth() {
+        let request = parse_curl("curl -u alice:secret https://example.com").unwrap();
+        assert_eq!(
+            request.headers,
+            vec![(
+                "Authorization".to_string(),
+                "Basic YWxpY2U6c2VjcmV0".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_form_becomes_multipart_body() {
+        let request = parse_curl(
+            "curl -F 'name=John Doe' -F 'avatar=@/tmp/a.png;type=image/png' https://example.com",
+        )
+        .unwrap();
+        assert_eq!(request.method, HttpMethod::POST);
+        let content_type = &request.headers[0];
+        assert_eq!(content_type.0, "Content-Type");
+        assert!(multipart::boundary_of(&content_type.1).is_some());
+        assert_eq!(
+            request.body.as_deref(),
+            Some("name=John+Doe&avatar=%40%2Ftmp%2Fa.png")
+        );
+        assert!(parse_curl("curl -F novalue https://example.com").is_err());
+        assert!(parse_curl("curl -F a=1 -d b=2 https://example.com").is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(parse_curl("wget https://example.com").is_err());
+        assert!(parse_curl("curl -X").is_err());
+        assert!(parse_curl("curl -H 'Accept: */*'").is_err());
+        assert!(parse_curl("curl 'https://example.com").is_err());
+    }
+}
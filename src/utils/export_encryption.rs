@@ -0,0 +1,178 @@
+//! Encrypting exported files for age or GPG recipients with the installed
+//! `age` / `gpg` command line tools, so shared bundles stay protected while
+//! they are passed around.
+
+use crate::errors::AppError;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Tool that encrypts the export
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportCipher {
+    Age,
+    Gpg,
+}
+
+impl ExportCipher {
+    pub fn program(self) -> &'static str {
+        match self {
+            Self::Age => "age",
+            Self::Gpg => "gpg",
+        }
+    }
+
+    /// Extension appended to the exported file name
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Age => "age",
+            Self::Gpg => "gpg",
+        }
+    }
+}
+
+/// Recipients an export is encrypted for
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportRecipients {
+    pub cipher: ExportCipher,
+    pub recipients: Vec<String>,
+}
+
+impl ExportRecipients {
+    /// Parse a comma or newline separated recipient list, `None` if empty.
+    ///
+    /// age public keys (`age1…`) and SSH public keys (`ssh-ed25519 …`,
+    /// `ssh-rsa …`) select age; anything else is a GPG key id, fingerprint
+    /// or email. Both kinds can't be mixed in one export.
+    pub fn parse(text: &str) -> Result<Option<Self>, AppError> {
+        let recipients: Vec<String> = text
+            .split([',', '\n'])
+            .map(str::trim)
+            .filter(|recipient| !recipient.is_empty())
+            .map(str::to_string)
+            .collect();
+        let Some(first) = recipients.first() else {
+            return Ok(None);
+        };
+
+        let cipher = cipher_for(first);
+        if let Some(other) = recipients
+            .iter()
+            .find(|recipient| cipher_for(recipient) != cipher)
+        {
+            return Err(AppError::ValidationError(format!(
+                "Can't mix age and GPG recipients ({first} and {other})"
+            )));
+        }
+        Ok(Some(Self { cipher, recipients }))
+    }
+
+    /// Arguments to encrypt stdin to stdout
+    pub fn args(&self) -> Vec<String> {
+        let mut args: Vec<String> = match self.cipher {
+            ExportCipher::Age => Vec::new(),
+            // Recipients are chosen explicitly, so don't stop to ask about
+            // keys without a trust path
+            ExportCipher::Gpg => ["--batch", "--yes", "--trust-model", "always", "--encrypt"]
+                .map(str::to_string)
+                .to_vec(),
+        };
+        let flag = match self.cipher {
+            ExportCipher::Age => "-r",
+            ExportCipher::Gpg => "--recipient",
+        };
+        for recipient in &self.recipients {
+            args.push(flag.to_string());
+            args.push(recipient.clone());
+        }
+        if self.cipher == ExportCipher::Gpg {
+            args.extend(["--output", "-"].map(str::to_string));
+        }
+        args
+    }
+
+    /// Run the tool on `plaintext` and return the encrypted bytes
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, AppError> {
+        let program = self.cipher.program();
+        let mut child = Command::new(program)
+            .args(self.args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => AppError::ValidationError(format!(
+                    "`{program}` was not found - install it to encrypt exports"
+                )),
+                _ => AppError::FileError(format!("Could not run {program}: {e}")),
+            })?;
+
+        // Write from another thread so a full stdout pipe can't block us
+        let mut stdin = child.stdin.take().expect("stdin is piped");
+        let input = plaintext.to_vec();
+        let writer = std::thread::spawn(move || stdin.write_all(&input));
+        let output = child
+            .wait_with_output()
+            .map_err(|e| AppError::FileError(format!("{program} failed: {e}")))?;
+        let written = writer.join().unwrap_or(Ok(()));
+
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr);
+            return Err(AppError::ValidationError(format!(
+                "{program} could not encrypt the export: {}",
+                message.trim()
+            )));
+        }
+        written.map_err(|e| AppError::FileError(format!("Writing to {program} failed: {e}")))?;
+        Ok(output.stdout)
+    }
+}
+
+fn cipher_for(recipient: &str) -> ExportCipher {
+    if recipient.starts_with("age1") || recipient.starts_with("ssh-") {
+        ExportCipher::Age
+    } else {
+        ExportCipher::Gpg
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AGE_KEY: &str = "age1ql3z7hjy54pw3hyww5ayyfg7zqgvc7w3j2elw8zmrj2kg5sfn9aqmcac8p";
+
+    #[test]
+    fn test_parse_recipients() {
+        assert_eq!(ExportRecipients::parse(" ,\n ").unwrap(), None);
+
+        let age = ExportRecipients::parse(&format!("{AGE_KEY}, ssh-ed25519 AAAAC3 bob"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(age.cipher, ExportCipher::Age);
+        assert_eq!(age.args(), ["-r", AGE_KEY, "-r", "ssh-ed25519 AAAAC3 bob"]);
+
+        let gpg = ExportRecipients::parse("Alice <alice@example.com>\n0x1234ABCD")
+            .unwrap()
+            .unwrap();
+        assert_eq!(gpg.cipher, ExportCipher::Gpg);
+        assert_eq!(gpg.cipher.extension(), "gpg");
+        assert_eq!(
+            gpg.args(),
+            [
+                "--batch",
+                "--yes",
+                "--trust-model",
+                "always",
+                "--encrypt",
+                "--recipient",
+                "Alice <alice@example.com>",
+                "--recipient",
+                "0x1234ABCD",
+                "--output",
+                "-",
+            ]
+        );
+
+        assert!(ExportRecipients::parse(&format!("{AGE_KEY}, alice@example.com")).is_err());
+    }
+}
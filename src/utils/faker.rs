@@ -0,0 +1,328 @@
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const FIRST_NAMES: &[&str] = &[
+    "Alice", "Bob", "Carol", "David", "Emma", "Frank", "Grace", "Henry", "Ivy", "Jack", "Karen",
+    "Leo", "Mia", "Noah", "Olivia", "Paul", "Quinn", "Ruby", "Sam", "Tina", "Uma", "Victor",
+    "Wendy", "Yuki", "Zoe",
+];
+
+const LAST_NAMES: &[&str] = &[
+    "Anderson", "Brown", "Chen", "Davis", "Evans", "Garcia", "Harris", "Ito", "Johnson", "Kim",
+    "Lopez", "Miller", "Nguyen", "Patel", "Rossi", "Smith", "Taylor", "Wang", "Wilson", "Young",
+];
+
+// Reserved for documentation (RFC 2606), so generated addresses never reach anyone
+const EMAIL_DOMAINS: &[&str] = &["example.com", "example.org", "example.net"];
+
+const LOREM_WORDS: &[&str] = &[
+    "lorem",
+    "ipsum",
+    "dolor",
+    "sit",
+    "amet",
+    "consectetur",
+    "adipiscing",
+    "elit",
+    "sed",
+    "do",
+    "eiusmod",
+    "tempor",
+    "incididunt",
+    "ut",
+    "labore",
+    "et",
+    "dolore",
+    "magna",
+    "aliqua",
+    "enim",
+    "ad",
+    "minim",
+    "veniam",
+    "quis",
+    "nostrud",
+    "exercitation",
+    "ullamco",
+    "laboris",
+    "nisi",
+    "aliquip",
+    "ex",
+    "ea",
+    "commodo",
+    "consequat",
+];
+
+/// Kinds of test data the editors can insert
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FakeKind {
+    Uuid,
+    Email,
+    Name,
+    Lorem,
+    Number(RangeInclusive<i64>),
+}
+
+impl FakeKind {
+    /// Generators offered in the editors, in menu order
+    pub fn presets() -> Vec<FakeKind> {
+        vec![
+            FakeKind::Uuid,
+            FakeKind::Email,
+            FakeKind::Name,
+            FakeKind::Lorem,
+            FakeKind::Number(1..=100),
+            FakeKind::Number(1000..=9999),
+        ]
+    }
+
+    pub fn label(&self) -> String {
+        match self {
+            FakeKind::Uuid => "UUID".to_string(),
+            FakeKind::Email => "Email".to_string(),
+            FakeKind::Name => "Name".to_string(),
+            FakeKind::Lorem => "Lorem".to_string(),
+            FakeKind::Number(range) => format!("{}-{}", range.start(), range.end()),
+        }
+    }
+}
+
+/// Small random test data generator.
+///
+/// Backs both the "insert random" helpers in the body editors and the
+/// `{{$...}}` dynamic variables, so both produce the same kind of data.
+pub struct Faker {
+    state: u64,
+}
+
+impl Faker {
+    /// Create a generator seeded from the clock
+    pub fn new() -> Self {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+        Self::with_seed(nanos ^ count.wrapping_mul(0x9e37_79b9_7f4a_7c15))
+    }
+
+    /// Create a generator with a fixed seed (same seed, same values)
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            // xorshift must not start at zero
+            state: seed.max(1),
+        }
+    }
+
+    // xorshift64*
+    fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    fn pick<'a>(&mut self, items: &[&'a str]) -> &'a str {
+        items[(self.next_u64() % items.len() as u64) as usize]
+    }
+
+    /// Random version 4 UUID
+    pub fn uuid(&mut self) -> String {
+        let high = self.next_u64();
+        let low = self.next_u64();
+        let mut bytes = [0u8; 16];
+        bytes[..8].copy_from_slice(&high.to_be_bytes());
+        bytes[8..].copy_from_slice(&low.to_be_bytes());
+        bytes[6] = (bytes[6] & 0x0f) | 0x40; // version 4
+        bytes[8] = (bytes[8] & 0x3f) | 0x80; // RFC 4122 variant
+
+        let hex: String = bytes.iter().map(|b| format!("{b:02x}")).collect();
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[0..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..32]
+        )
+    }
+
+    pub fn first_name(&mut self) -> String {
+        self.pick(FIRST_NAMES).to_string()
+    }
+
+    pub fn last_name(&mut self) -> String {
+        self.pick(LAST_NAMES).to_string()
+    }
+
+    pub fn full_name(&mut self) -> String {
+        format!("{} {}", self.first_name(), self.last_name())
+    }
+
+    pub fn email(&mut self) -> String {
+        let first = self.pick(FIRST_NAMES).to_lowercase();
+        let last = self.pick(LAST_NAMES).to_lowercase();
+        let number = self.number(1..=99);
+        let domain = self.pick(EMAIL_DOMAINS);
+        format!("{first}.{last}{number}@{domain}")
+    }
+
+    /// `count` lorem ipsum words separated by spaces
+    pub fn lorem_words(&mut self, count: usize) -> String {
+        (0..count)
+            .map(|_| self.pick(LOREM_WORDS))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// A capitalized lorem ipsum sentence ending with a period
+    pub fn lorem_sentence(&mut self) -> String {
+        let count = self.number(6..=12) as usize;
+        let words = self.lorem_words(count);
+        let mut chars = words.chars();
+        match chars.next() {
+            Some(first) => format!("{}{}.", first.to_uppercase(), chars.as_str()),
+            None => String::new(),
+        }
+    }
+
+    /// Random integer in `range` (inclusive)
+    pub fn number(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (start, end) = (*range.start(), *range.end());
+        if start >= end {
+            return start;
+        }
+        let span = end.abs_diff(start).saturating_add(1);
+        start.wrapping_add((self.next_u64() % span) as i64)
+    }
+
+    /// Generate a value for one of the editor presets
+    pub fn generate(&mut self, kind: &FakeKind) -> String {
+        match kind {
+            FakeKind::Uuid => self.uuid(),
+            FakeKind::Email => self.email(),
+            FakeKind::Name => self.full_name(),
+            FakeKind::Lorem => self.lorem_sentence(),
+            FakeKind::Number(range) => self.number(range.clone()).to_string(),
+        }
+    }
+
+    /// Value of a dynamic variable such as `$guid` or `$randomEmail`.
+    ///
+    /// Returns `None` for names that aren't dynamic variables.
+    pub fn dynamic_variable(&mut self, name: &str) -> Option<String> {
+        let value = match name {
+            "$guid" | "$randomUUID" => self.uuid(),
+            "$timestamp" => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0)
+                .to_string(),
+            "$randomInt" => self.number(0..=1000).to_string(),
+            "$randomEmail" => self.email(),
+            "$randomFirstName" => self.first_name(),
+            "$randomLastName" => self.last_name(),
+            "$randomFullName" => self.full_name(),
+            "$randomLoremWord" => self.lorem_words(1),
+            "$randomLoremWords" => self.lorem_words(3),
+            "$randomLoremSentence" => self.lorem_sentence(),
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+impl Default for Faker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_uuid_format() {
+        let mut faker = Faker::with_seed(42);
+        let uuid = faker.uuid();
+        assert_eq!(uuid.len(), 36);
+        let parts: Vec<&str> = uuid.split('-').collect();
+        assert_eq!(
+            parts.iter().map(|p| p.len()).collect::<Vec<_>>(),
+            [8, 4, 4, 4, 12]
+        );
+        assert!(parts[2].starts_with('4'));
+        assert!(matches!(
+            parts[3].chars().next(),
+            Some('8' | '9' | 'a' | 'b')
+        ));
+        assert_ne!(faker.uuid(), uuid);
+    }
+
+    #[test]
+    fn test_same_seed_same_values() {
+        let mut a = Faker::with_seed(7);
+        let mut b = Faker::with_seed(7);
+        assert_eq!(a.full_name(), b.full_name());
+        assert_eq!(a.email(), b.email());
+    }
+
+    #[test]
+    fn test_email_uses_reserved_domains() {
+        let mut faker = Faker::with_seed(1);
+        for _ in 0..20 {
+            let email = faker.email();
+            let (_, domain) = email.split_once('@').unwrap();
+            assert!(EMAIL_DOMAINS.contains(&domain));
+        }
+    }
+
+    #[test]
+    fn test_number_stays_in_range() {
+        let mut faker = Faker::with_seed(3);
+        for _ in 0..200 {
+            assert!((1..=100).contains(&faker.number(1..=100)));
+        }
+        assert_eq!(faker.number(5..=5), 5);
+        assert_eq!(faker.number(9..=1), 9);
+        // Full range must not overflow
+        faker.number(i64::MIN..=i64::MAX);
+    }
+
+    #[test]
+    fn test_lorem_sentence() {
+        let mut faker = Faker::with_seed(9);
+        let sentence = faker.lorem_sentence();
+        assert!(sentence.ends_with('.'));
+        assert!(sentence.chars().next().unwrap().is_uppercase());
+        assert_eq!(faker.lorem_words(3).split(' ').count(), 3);
+    }
+
+    #[test]
+    fn test_generate_presets() {
+        let mut faker = Faker::with_seed(11);
+        for kind in FakeKind::presets() {
+            assert!(!faker.generate(&kind).is_empty());
+        }
+        assert_eq!(FakeKind::Number(1..=100).label(), "1-100");
+    }
+
+    #[test]
+    fn test_dynamic_variables() {
+        let mut faker = Faker::with_seed(5);
+        assert_eq!(faker.dynamic_variable("$guid").unwrap().len(), 36);
+        assert!(faker
+            .dynamic_variable("$randomEmail")
+            .unwrap()
+            .contains('@'));
+        assert!(faker
+            .dynamic_variable("$timestamp")
+            .unwrap()
+            .parse::<u64>()
+            .is_ok());
+        assert!(faker.dynamic_variable("$unknown").is_none());
+        assert!(faker.dynamic_variable("guid").is_none());
+    }
+}
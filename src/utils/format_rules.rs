@@ -0,0 +1,227 @@
+//! Conditional formatting of responses: rules that show a banner above the
+//! response or a badge on its tab when a check from the assertion language
+//! holds.
+//!
+//! Each non-empty line that doesn't start with `#` is one rule, the check
+//! and the action separated by `=>`:
+//!
+//! ```text
+//! json error.message exists => banner red {value}
+//! status >= 500 => banner red Server error {value}
+//! latency > 1s => badge yellow Slow
+//! header Deprecation exists => badge Deprecated
+//! ```
+//!
+//! The color (red, yellow, green or blue) is optional, and `{value}` in the
+//! text is replaced by the value the check looked at.
+
+use crate::errors::AppError;
+use crate::utils::assertions::{parse_script, Assertion, AssertionContext};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleColor {
+    Red,
+    Yellow,
+    Green,
+    Blue,
+}
+
+impl RuleColor {
+    fn parse(text: &str) -> Option<Self> {
+        match text {
+            "red" => Some(Self::Red),
+            "yellow" => Some(Self::Yellow),
+            "green" => Some(Self::Green),
+            "blue" => Some(Self::Blue),
+            _ => None,
+        }
+    }
+
+    /// Background color of banners and badges
+    pub fn background(self) -> u32 {
+        match self {
+            Self::Red => 0x00f8_d7da,
+            Self::Yellow => 0x00ff_f3cd,
+            Self::Green => 0x00d4_edda,
+            Self::Blue => 0x00cc_e5ff,
+        }
+    }
+
+    /// Text color readable on `background`
+    pub fn foreground(self) -> u32 {
+        match self {
+            Self::Red => 0x0072_1c24,
+            Self::Yellow => 0x0085_6404,
+            Self::Green => 0x0015_5724,
+            Self::Blue => 0x0000_4085,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Placement {
+    /// A line above the response
+    Banner,
+    /// A label on the request's tab
+    Badge,
+}
+
+/// One parsed line of the rules
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatRule {
+    condition: Assertion,
+    placement: Placement,
+    color: RuleColor,
+    /// Text shown, `{value}` standing for the checked value
+    template: String,
+}
+
+/// A rule whose check held for a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Highlight {
+    pub placement: Placement,
+    pub color: RuleColor,
+    pub text: String,
+}
+
+/// Parse every rule of `script`; errors name the offending line
+pub fn parse_rules(script: &str) -> Result<Vec<FormatRule>, AppError> {
+    script
+        .lines()
+        .enumerate()
+        .map(|(index, line)| (index + 1, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| {
+            FormatRule::parse(line)
+                .map_err(|e| AppError::ValidationError(format!("Line {number}: {e}")))
+        })
+        .collect()
+}
+
+/// What the rules whose checks hold show for the response, in rule order
+pub fn apply_rules(rules: &[FormatRule], context: &AssertionContext) -> Vec<Highlight> {
+    rules
+        .iter()
+        .filter(|rule| rule.condition.check(context).passed())
+        .map(|rule| {
+            let value = rule.condition.value(context).unwrap_or_default();
+            Highlight {
+                placement: rule.placement,
+                color: rule.color,
+                text: rule.template.replace("{value}", &value),
+            }
+        })
+        .collect()
+}
+
+impl FormatRule {
+    fn parse(line: &str) -> Result<Self, String> {
+        let (condition, action) = line
+            .split_once("=>")
+            .ok_or_else(|| "expected a check and an action separated by =>".to_string())?;
+        let condition = match parse_script(condition.trim()) {
+            Ok(mut assertions) if assertions.len() == 1 => assertions.remove(0),
+            Ok(_) => return Err("missing a check before =>".to_string()),
+            Err(e) => return Err(e.to_string().replacen("Line 1: ", "", 1)),
+        };
+
+        let action = action.trim();
+        let (placement, rest) = match action.split_once(char::is_whitespace) {
+            Some((placement, rest)) => (placement, rest.trim()),
+            None => (action, ""),
+        };
+        let placement = match placement {
+            "banner" => Placement::Banner,
+            "badge" => Placement::Badge,
+            _ => return Err(format!("expected banner or badge, found \"{placement}\"")),
+        };
+        let (color, text) = match rest.split_once(char::is_whitespace) {
+            Some((word, text)) if RuleColor::parse(word).is_some() => {
+                (RuleColor::parse(word), text.trim())
+            }
+            _ => match RuleColor::parse(rest) {
+                Some(color) => (Some(color), ""),
+                None => (None, rest),
+            },
+        };
+        // Without a text the banner or badge shows the checked value
+        let template = if text.is_empty() { "{value}" } else { text };
+        let default_color = match placement {
+            Placement::Banner => RuleColor::Red,
+            Placement::Badge => RuleColor::Yellow,
+        };
+
+        Ok(Self {
+            condition,
+            placement,
+            color: color.unwrap_or(default_color),
+            template: template.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_apply_rules() {
+        let rules = parse_rules(
+            "# API errors\n\
+             json error.message exists => banner red {value}\n\
+             status >= 500 => banner Server error {value}\n\
+             latency > 1s => badge yellow Slow ({value} ms)\n\
+             header Deprecation exists => badge blue\n",
+        )
+        .unwrap();
+        assert_eq!(rules.len(), 4);
+
+        let headers = vec![("Deprecation".to_string(), "true".to_string())];
+        let body = r#"{"error": {"message": "Quota exceeded"}}"#;
+        let context =
+            AssertionContext::new(429, &headers, body).with_latency(Duration::from_millis(1500));
+        assert_eq!(
+            apply_rules(&rules, &context),
+            [
+                Highlight {
+                    placement: Placement::Banner,
+                    color: RuleColor::Red,
+                    text: "Quota exceeded".to_string(),
+                },
+                Highlight {
+                    placement: Placement::Badge,
+                    color: RuleColor::Yellow,
+                    text: "Slow (1500 ms)".to_string(),
+                },
+                Highlight {
+                    placement: Placement::Badge,
+                    color: RuleColor::Blue,
+                    text: "true".to_string(),
+                },
+            ]
+        );
+
+        let context = AssertionContext::new(503, &[], "unavailable");
+        let highlights = apply_rules(&rules, &context);
+        assert_eq!(highlights.len(), 1);
+        assert_eq!(highlights[0].text, "Server error 503");
+    }
+
+    #[test]
+    fn test_parse_errors_name_the_line() {
+        assert!(parse_rules("").unwrap().is_empty());
+        for (script, message) in [
+            ("status == 200", "separated by =>"),
+            ("=> banner hi", "missing a check"),
+            ("status >= ok => banner hi", "needs a number"),
+            (
+                "# rules\nstatus == 500 => toast hi",
+                "Line 2: expected banner or badge",
+            ),
+        ] {
+            let error = parse_rules(script).unwrap_err().to_string();
+            assert!(error.contains(message), "{script}: {error}");
+        }
+    }
+}
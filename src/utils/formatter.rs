@@ -1,3 +1,4 @@
+use crate::utils::sniff::BodyKind;
 use serde_json::{from_str, to_string_pretty, Value};
 
 /// Attempts to pretty-print JSON content.
@@ -20,6 +21,180 @@ pub fn format_response_body(body: &str) -> String {
     }
 }
 
+/// Format a response body the way its kind reads: JSON is pretty-printed,
+/// XML and HTML are indented and anything else is left as it is
+pub fn format_body(kind: BodyKind, body: &str) -> String {
+    match kind {
+        BodyKind::Json => format_response_body(body),
+        BodyKind::Xml | BodyKind::Html => format_markup(body),
+        BodyKind::Image | BodyKind::Text | BodyKind::Binary => body.to_string(),
+    }
+}
+
+/// Elements HTML writes without a closing tag
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "source", "track",
+    "wbr",
+];
+
+/// Elements whose content is kept exactly as received
+const RAW_ELEMENTS: &[&str] = &["script", "style", "pre", "textarea"];
+
+const MARKUP_INDENT: &str = "  ";
+
+// A piece of XML / HTML: the tag's source and its lowercase element name
+enum MarkupToken<'a> {
+    Open(&'a str, String),
+    Close(&'a str, String),
+    // Self-closing and void tags, comments, declarations and CDATA
+    Standalone(&'a str),
+    Text(&'a str),
+    // Content of a raw element
+    Raw(&'a str),
+}
+
+/// Indent XML or HTML with one element per line; an element holding only
+/// text stays on one line (`<id>42</id>`). Markup that can't be split into
+/// tags is returned unchanged
+pub fn format_markup(text: &str) -> String {
+    let Some(tokens) = tokenize_markup(text.trim()) else {
+        return text.to_string();
+    };
+    let mut lines = Vec::new();
+    let mut depth = 0usize;
+    let mut index = 0;
+    while index < tokens.len() {
+        let indent = MARKUP_INDENT.repeat(depth);
+        match &tokens[index] {
+            MarkupToken::Open(tag, name) => {
+                // Nothing but text (or nothing at all) before the closing tag
+                let (inner, offset) = match tokens.get(index + 1) {
+                    Some(MarkupToken::Text(text)) => (text.trim(), 2),
+                    Some(MarkupToken::Raw(raw)) => (*raw, 2),
+                    _ => ("", 1),
+                };
+                if let Some(MarkupToken::Close(close, close_name)) = tokens.get(index + offset) {
+                    if close_name == name {
+                        lines.push(format!("{indent}{tag}{inner}{close}"));
+                        index += offset + 1;
+                        continue;
+                    }
+                }
+                lines.push(format!("{indent}{tag}"));
+                depth += 1;
+            }
+            MarkupToken::Close(tag, _) => {
+                depth = depth.saturating_sub(1);
+                lines.push(format!("{}{tag}", MARKUP_INDENT.repeat(depth)));
+            }
+            MarkupToken::Standalone(tag) => lines.push(format!("{indent}{tag}")),
+            MarkupToken::Text(text) => {
+                let text = text.trim();
+                if !text.is_empty() {
+                    lines.push(format!("{indent}{text}"));
+                }
+            }
+            MarkupToken::Raw(raw) => lines.push(format!("{indent}{raw}")),
+        }
+        index += 1;
+    }
+    lines.join("\n")
+}
+
+fn tokenize_markup(text: &str) -> Option<Vec<MarkupToken<'_>>> {
+    let mut tokens = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        if !rest.starts_with('<') {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(MarkupToken::Text(&rest[..end]));
+            rest = &rest[end..];
+            continue;
+        }
+        let end = if rest.starts_with("<!--") {
+            rest.find("-->")? + 3
+        } else if rest.starts_with("<![CDATA[") {
+            rest.find("]]>")? + 3
+        } else {
+            tag_end(rest)?
+        };
+        let tag = &rest[..end];
+        rest = &rest[end..];
+
+        if let Some(name) = tag.strip_prefix("</") {
+            tokens.push(MarkupToken::Close(tag, element_name(name)));
+            continue;
+        }
+        let name = element_name(&tag[1..]);
+        if tag.starts_with("<!") || tag.starts_with("<?") || tag.ends_with("/>") {
+            tokens.push(MarkupToken::Standalone(tag));
+        } else if VOID_ELEMENTS.contains(&name.as_str()) {
+            tokens.push(MarkupToken::Standalone(tag));
+        } else if RAW_ELEMENTS.contains(&name.as_str()) {
+            // Lowercasing ASCII keeps byte offsets, so the index fits `rest`
+            let close = rest.to_ascii_lowercase().find(&format!("</{name}"))?;
+            tokens.push(MarkupToken::Open(tag, name));
+            if close > 0 {
+                tokens.push(MarkupToken::Raw(&rest[..close]));
+            }
+            rest = &rest[close..];
+        } else {
+            tokens.push(MarkupToken::Open(tag, name));
+        }
+    }
+    Some(tokens)
+}
+
+// Index just past the `>` ending the tag at the start of `text`, skipping
+// `>` inside quoted attribute values
+fn tag_end(text: &str) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices().skip(1) {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(open), _) if c == open => quote = None,
+            (None, '>') => return Some(index + 1),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn element_name(tag: &str) -> String {
+    tag.chars()
+        .take_while(|c| !c.is_whitespace() && *c != '/' && *c != '>')
+        .collect::<String>()
+        .to_ascii_lowercase()
+}
+
+/// Human readable byte count, e.g. `512 B`, `1.5 KB`, `12.0 MB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Count with thousands separators, e.g. `1,204`
+pub fn format_count(count: usize) -> String {
+    let digits = count.to_string();
+    let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -77,4 +252,55 @@ mod tests {
         // Should be valid JSON
         assert!(from_str::<Value>(&output).is_ok());
     }
+
+    #[test]
+    fn test_format_size() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(12 * 1024 * 1024), "12.0 MB");
+    }
+
+    #[test]
+    fn test_format_count() {
+        assert_eq!(format_count(0), "0");
+        assert_eq!(format_count(999), "999");
+        assert_eq!(format_count(1204), "1,204");
+        assert_eq!(format_count(1_234_567), "1,234,567");
+    }
+
+    #[test]
+    fn test_format_markup() {
+        let xml =
+            r#"<?xml version="1.0"?><order id="a>b"><item>Pen</item><note/><!-- x --></order>"#;
+        let expected = r#"<?xml version="1.0"?>
+<order id="a>b">
+  <item>Pen</item>
+  <note/>
+  <!-- x -->
+</order>"#;
+        assert_eq!(format_markup(xml), expected);
+        // Formatting again changes nothing
+        assert_eq!(format_markup(expected), expected);
+
+        let html = "<html><body><br><pre> keep\n  this</pre></body></html>";
+        assert_eq!(
+            format_markup(html),
+            "<html>\n  <body>\n    <br>\n    <pre> keep\n  this</pre>\n  </body>\n</html>"
+        );
+        assert_eq!(format_markup("<a><b"), "<a><b");
+    }
+
+    #[test]
+    fn test_format_body_by_kind() {
+        assert!(format_body(BodyKind::Json, r#"{"a":1}"#).contains('\n'));
+        assert_eq!(
+            format_body(BodyKind::Xml, "<a><b>1</b></a>"),
+            "<a>\n  <b>1</b>\n</a>"
+        );
+        assert_eq!(
+            format_body(BodyKind::Text, "<a><b>1</b></a>"),
+            "<a><b>1</b></a>"
+        );
+    }
 }
@@ -0,0 +1,1157 @@
+//! GraphQL schema introspection, plus field/argument completion and
+//! validation of a query against the introspected schema.
+
+use crate::errors::AppError;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Standard introspection query (types, fields, arguments, enum values)
+pub const INTROSPECTION_QUERY: &str = "query IntrospectionQuery {
+  __schema {
+    queryType { name }
+    mutationType { name }
+    subscriptionType { name }
+    types {
+      kind name description
+      fields(includeDeprecated: true) {
+        name description
+        args { name description type { ...TypeRef } defaultValue }
+        type { ...TypeRef }
+      }
+      inputFields { name description type { ...TypeRef } defaultValue }
+      enumValues(includeDeprecated: true) { name description }
+      interfaces { name }
+      possibleTypes { name }
+    }
+  }
+}
+fragment TypeRef on __Type {
+  kind name
+  ofType { kind name ofType { kind name ofType { kind name ofType { kind name } } } }
+}";
+
+/// Request body for the introspection query
+pub fn introspection_body() -> String {
+    serde_json::json!({ "query": INTROSPECTION_QUERY }).to_string()
+}
+
+/// A field or argument type, e.g. `[User!]!`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypeRef {
+    Named(String),
+    List(Box<TypeRef>),
+    NonNull(Box<TypeRef>),
+}
+
+impl TypeRef {
+    /// The named type under any list / non-null wrappers
+    pub fn named(&self) -> &str {
+        match self {
+            TypeRef::Named(name) => name,
+            TypeRef::List(inner) | TypeRef::NonNull(inner) => inner.named(),
+        }
+    }
+
+    pub fn is_non_null(&self) -> bool {
+        matches!(self, TypeRef::NonNull(_))
+    }
+
+    fn parse(value: &Value) -> Result<Self, AppError> {
+        let inner = || {
+            value
+                .get("ofType")
+                .filter(|inner| !inner.is_null())
+                .ok_or_else(|| invalid("a wrapper type without ofType"))
+                .and_then(TypeRef::parse)
+        };
+        match value.get("kind").and_then(Value::as_str) {
+            Some("NON_NULL") => Ok(TypeRef::NonNull(Box::new(inner()?))),
+            Some("LIST") => Ok(TypeRef::List(Box::new(inner()?))),
+            _ => Ok(TypeRef::Named(
+                str_field(value, "name").ok_or_else(|| invalid("a type without a name"))?,
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for TypeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TypeRef::Named(name) => write!(f, "{name}"),
+            TypeRef::List(inner) => write!(f, "[{inner}]"),
+            TypeRef::NonNull(inner) => write!(f, "{inner}!"),
+        }
+    }
+}
+
+/// An argument or input object field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InputValue {
+    pub name: String,
+    pub description: Option<String>,
+    pub type_ref: TypeRef,
+    pub default_value: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Field {
+    pub name: String,
+    pub description: Option<String>,
+    pub args: Vec<InputValue>,
+    pub type_ref: TypeRef,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaType {
+    pub name: String,
+    /// `OBJECT`, `SCALAR`, `ENUM`, `INPUT_OBJECT`, `INTERFACE` or `UNION`
+    pub kind: String,
+    pub description: Option<String>,
+    pub fields: Vec<Field>,
+    pub input_fields: Vec<InputValue>,
+    pub enum_values: Vec<String>,
+    /// Interfaces an object implements, or the members of a union / interface
+    pub related: Vec<String>,
+}
+
+impl SchemaType {
+    /// Objects, interfaces and unions need a selection of subfields
+    pub fn is_composite(&self) -> bool {
+        matches!(self.kind.as_str(), "OBJECT" | "INTERFACE" | "UNION")
+    }
+
+    pub fn field(&self, name: &str) -> Option<&Field> {
+        self.fields.iter().find(|field| field.name == name)
+    }
+}
+
+/// A schema loaded from an introspection result
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub query_type: String,
+    pub mutation_type: Option<String>,
+    pub subscription_type: Option<String>,
+    types: BTreeMap<String, SchemaType>,
+}
+
+impl Schema {
+    /// Parse an introspection response (`{"data": {"__schema": ...}}`); a
+    /// response with only `errors` is reported as an error
+    pub fn from_introspection(body: &str) -> Result<Self, AppError> {
+        let response: Value = serde_json::from_str(body)
+            .map_err(|e| AppError::ParseError(format!("Introspection result is not JSON: {e}")))?;
+        let data = response.get("data").unwrap_or(&response);
+        let Some(schema) = data.get("__schema").filter(|schema| !schema.is_null()) else {
+            let errors: Vec<&str> = response
+                .get("errors")
+                .and_then(Value::as_array)
+                .map(|errors| {
+                    errors
+                        .iter()
+                        .filter_map(|error| error.get("message").and_then(Value::as_str))
+                        .collect()
+                })
+                .unwrap_or_default();
+            return Err(AppError::ParseError(if errors.is_empty() {
+                "Response does not contain an introspection result".to_string()
+            } else {
+                format!("Introspection failed: {}", errors.join("; "))
+            }));
+        };
+
+        let root = |key: &str| schema.get(key).and_then(|root| str_field(root, "name"));
+        let mut types = BTreeMap::new();
+        for value in schema
+            .get("types")
+            .and_then(Value::as_array)
+            .ok_or_else(|| invalid("a schema without types"))?
+        {
+            let schema_type = parse_type(value)?;
+            types.insert(schema_type.name.clone(), schema_type);
+        }
+        Ok(Self {
+            query_type: root("queryType").unwrap_or_else(|| "Query".to_string()),
+            mutation_type: root("mutationType"),
+            subscription_type: root("subscriptionType"),
+            types,
+        })
+    }
+
+    pub fn get_type(&self, name: &str) -> Option<&SchemaType> {
+        self.types.get(name)
+    }
+
+    /// Type names without the introspection types (`__Schema`, ...), sorted
+    pub fn type_names(&self) -> Vec<&str> {
+        self.types
+            .keys()
+            .map(String::as_str)
+            .filter(|name| !name.starts_with("__"))
+            .collect()
+    }
+
+    pub fn type_count(&self) -> usize {
+        self.type_names().len()
+    }
+
+    /// Root type of `query`, `mutation` or `subscription`
+    pub fn root_type(&self, operation: &str) -> Option<&str> {
+        match operation {
+            "query" => Some(self.query_type.as_str()),
+            "mutation" => self.mutation_type.as_deref(),
+            "subscription" => self.subscription_type.as_deref(),
+            _ => None,
+        }
+    }
+
+    fn field(&self, type_name: &str, field: &str) -> Option<&Field> {
+        self.types.get(type_name)?.field(field)
+    }
+
+    fn is_composite(&self, type_name: &str) -> Option<bool> {
+        self.types.get(type_name).map(SchemaType::is_composite)
+    }
+}
+
+fn parse_type(value: &Value) -> Result<SchemaType, AppError> {
+    let list = |key: &str| {
+        value
+            .get(key)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    };
+    let fields = list("fields")
+        .iter()
+        .map(|field| {
+            Ok(Field {
+                name: str_field(field, "name").ok_or_else(|| invalid("a field without a name"))?,
+                description: str_field(field, "description"),
+                args: list_of(field, "args")?,
+                type_ref: TypeRef::parse(field.get("type").unwrap_or(&Value::Null))?,
+            })
+        })
+        .collect::<Result<_, AppError>>()?;
+    let mut related: Vec<String> = list("interfaces")
+        .iter()
+        .chain(list("possibleTypes"))
+        .filter_map(|related| str_field(related, "name"))
+        .collect();
+    related.sort();
+    Ok(SchemaType {
+        name: str_field(value, "name").ok_or_else(|| invalid("a type without a name"))?,
+        kind: str_field(value, "kind").unwrap_or_default(),
+        description: str_field(value, "description"),
+        fields,
+        input_fields: list_of(value, "inputFields")?,
+        enum_values: list("enumValues")
+            .iter()
+            .filter_map(|value| str_field(value, "name"))
+            .collect(),
+        related,
+    })
+}
+
+fn list_of(value: &Value, key: &str) -> Result<Vec<InputValue>, AppError> {
+    value
+        .get(key)
+        .and_then(Value::as_array)
+        .map(Vec::as_slice)
+        .unwrap_or_default()
+        .iter()
+        .map(|input| {
+            Ok(InputValue {
+                name: str_field(input, "name")
+                    .ok_or_else(|| invalid("an argument without a name"))?,
+                description: str_field(input, "description"),
+                type_ref: TypeRef::parse(input.get("type").unwrap_or(&Value::Null))?,
+                default_value: str_field(input, "defaultValue"),
+            })
+        })
+        .collect()
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(str::to_string)
+}
+
+fn invalid(what: &str) -> AppError {
+    AppError::ParseError(format!("Invalid introspection result: {what}"))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Name(String),
+    Punct(char),
+    Spread,
+    Variable,
+    /// String or number literal
+    Literal,
+}
+
+#[derive(Debug, Clone)]
+struct Spanned {
+    token: Token,
+    start: usize,
+}
+
+/// Split a query into tokens, skipping whitespace, commas and comments;
+/// fails on an unterminated string or an unexpected character
+fn tokenize(query: &str) -> Result<Vec<Spanned>, Diagnostic> {
+    let mut tokens = Vec::new();
+    let mut chars = query.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() || c == ',' || c == '\u{feff}' => continue,
+            '#' => {
+                while chars.next_if(|(_, c)| *c != '\n').is_some() {}
+                continue;
+            }
+            '{' | '}' | '(' | ')' | '[' | ']' | ':' | '@' | '=' | '!' | '|' | '&' => {
+                Token::Punct(c)
+            }
+            '.' => {
+                if chars.next_if(|(_, c)| *c == '.').is_none()
+                    || chars.next_if(|(_, c)| *c == '.').is_none()
+                {
+                    return Err(Diagnostic::new("Expected '...'", start));
+                }
+                Token::Spread
+            }
+            '$' => {
+                while chars.next_if(|(_, c)| is_name_char(*c)).is_some() {}
+                Token::Variable
+            }
+            '"' => {
+                let block = query[start..].starts_with("\"\"\"");
+                if block {
+                    chars.next();
+                    chars.next();
+                }
+                let mut closed = false;
+                while let Some((offset, c)) = chars.next() {
+                    if c == '\\' && !block {
+                        chars.next();
+                    } else if c == '"' && (!block || query[offset..].starts_with("\"\"\"")) {
+                        if block {
+                            chars.next();
+                            chars.next();
+                        }
+                        closed = true;
+                        break;
+                    } else if c == '\n' && !block {
+                        break;
+                    }
+                }
+                if !closed {
+                    return Err(Diagnostic::new("Unterminated string", start));
+                }
+                Token::Literal
+            }
+            c if c == '-' || c.is_ascii_digit() => {
+                while chars
+                    .next_if(|(_, c)| c.is_ascii_alphanumeric() || matches!(c, '.' | '+' | '-'))
+                    .is_some()
+                {}
+                Token::Literal
+            }
+            c if is_name_char(c) => {
+                let mut end = start + c.len_utf8();
+                while let Some((offset, c)) = chars.next_if(|(_, c)| is_name_char(*c)) {
+                    end = offset + c.len_utf8();
+                }
+                Token::Name(query[start..end].to_string())
+            }
+            c => {
+                return Err(Diagnostic::new(
+                    format!("Unexpected character '{c}'"),
+                    start,
+                ))
+            }
+        };
+        tokens.push(Spanned { token, start });
+    }
+    Ok(tokens)
+}
+
+fn is_name_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Whether the document contains a `subscription` operation (those are
+/// sent over a WebSocket instead of a POST)
+pub fn is_subscription(query: &str) -> bool {
+    let Ok(tokens) = tokenize(query) else {
+        return false;
+    };
+    let mut depth = 0usize;
+    tokens.iter().any(|spanned| {
+        match &spanned.token {
+            Token::Punct('{' | '(' | '[') => depth += 1,
+            Token::Punct('}' | ')' | ']') => depth = depth.saturating_sub(1),
+            Token::Name(name) => return depth == 0 && name == "subscription",
+            _ => {}
+        }
+        false
+    })
+}
+
+/// A validation problem at a byte offset of the query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub offset: usize,
+}
+
+impl Diagnostic {
+    fn new(message: impl Into<String>, offset: usize) -> Self {
+        Self {
+            message: message.into(),
+            offset,
+        }
+    }
+
+    /// 1-based line and column of the problem in `query`
+    pub fn line_column(&self, query: &str) -> (usize, usize) {
+        let before = &query[..self.offset.min(query.len())];
+        let line_start = before.rfind('\n').map(|index| index + 1).unwrap_or(0);
+        (
+            before.matches('\n').count() + 1,
+            before[line_start..].chars().count() + 1,
+        )
+    }
+}
+
+/// Check a query document against the schema: syntax, unknown fields and
+/// arguments, missing required arguments and missing / invalid selections
+pub fn validate(schema: &Schema, query: &str) -> Vec<Diagnostic> {
+    let tokens = match tokenize(query) {
+        Ok(tokens) => tokens,
+        Err(diagnostic) => return vec![diagnostic],
+    };
+    let mut validator = Validator {
+        schema,
+        tokens,
+        pos: 0,
+        end: query.len(),
+        errors: Vec::new(),
+    };
+    validator.document();
+    validator.errors
+}
+
+struct Validator<'a> {
+    schema: &'a Schema,
+    tokens: Vec<Spanned>,
+    pos: usize,
+    /// Offset used for errors at the end of the query
+    end: usize,
+    errors: Vec<Diagnostic>,
+}
+
+impl Validator<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|spanned| &spanned.token)
+    }
+
+    fn offset(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|spanned| spanned.start)
+            .unwrap_or(self.end)
+    }
+
+    fn error(&mut self, message: impl Into<String>) {
+        let offset = self.offset();
+        self.errors.push(Diagnostic::new(message, offset));
+    }
+
+    fn eat(&mut self, punct: char) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn name(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Name(name)) => {
+                let name = name.clone();
+                self.pos += 1;
+                Some(name)
+            }
+            _ => None,
+        }
+    }
+
+    fn document(&mut self) {
+        while let Some(token) = self.peek().cloned() {
+            match token {
+                Token::Name(keyword)
+                    if matches!(keyword.as_str(), "query" | "mutation" | "subscription") =>
+                {
+                    self.pos += 1;
+                    self.name();
+                    if self.peek() == Some(&Token::Punct('(')) {
+                        self.skip_balanced();
+                    }
+                    self.directives();
+                    let root = self.schema.root_type(&keyword).map(str::to_string);
+                    if root.is_none() {
+                        self.error(format!("The schema has no {keyword} type"));
+                    }
+                    self.selection_set(root.as_deref());
+                }
+                Token::Name(keyword) if keyword == "fragment" => {
+                    self.pos += 1;
+                    self.name();
+                    let type_name = self.type_condition();
+                    self.directives();
+                    self.selection_set(type_name.as_deref());
+                }
+                Token::Punct('{') => {
+                    let root = self.schema.query_type.clone();
+                    self.selection_set(Some(&root));
+                }
+                _ => {
+                    self.error("Expected an operation or a fragment");
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    /// `on Type`; returns the type if the schema knows it
+    fn type_condition(&mut self) -> Option<String> {
+        if self.peek() != Some(&Token::Name("on".to_string())) {
+            self.error("Expected 'on' and a type name");
+            return None;
+        }
+        self.pos += 1;
+        let offset = self.offset();
+        let name = self.name()?;
+        if self.schema.get_type(&name).is_none() {
+            self.errors
+                .push(Diagnostic::new(format!("Unknown type '{name}'"), offset));
+            return None;
+        }
+        Some(name)
+    }
+
+    /// `parent` is `None` when the type is unknown (nothing is checked then)
+    fn selection_set(&mut self, parent: Option<&str>) {
+        if !self.eat('{') {
+            self.error("Expected '{'");
+            return;
+        }
+        loop {
+            match self.peek() {
+                None => {
+                    self.error("Missing closing '}'");
+                    return;
+                }
+                Some(Token::Punct('}')) => {
+                    self.pos += 1;
+                    return;
+                }
+                Some(Token::Spread) => {
+                    self.pos += 1;
+                    match self.peek() {
+                        Some(Token::Name(name)) if name == "on" => {
+                            let type_name = self.type_condition();
+                            self.directives();
+                            self.selection_set(type_name.as_deref());
+                        }
+                        // Named fragment spread
+                        Some(Token::Name(_)) => {
+                            self.pos += 1;
+                            self.directives();
+                        }
+                        _ => {
+                            self.directives();
+                            self.selection_set(parent);
+                        }
+                    }
+                }
+                Some(Token::Name(_)) => self.field(parent),
+                Some(_) => {
+                    self.error("Expected a field name");
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn field(&mut self, parent: Option<&str>) {
+        let mut offset = self.offset();
+        let mut name = self.name().unwrap_or_default();
+        // `alias: field`
+        if self.eat(':') {
+            offset = self.offset();
+            match self.name() {
+                Some(field) => name = field,
+                None => {
+                    self.error("Expected a field name after the alias");
+                    return;
+                }
+            }
+        }
+
+        let definition = parent.and_then(|parent| self.schema.field(parent, &name));
+        if let (Some(parent), None) = (parent, definition) {
+            if !name.starts_with("__") {
+                self.errors.push(Diagnostic::new(
+                    format!("Cannot query field '{name}' on type '{parent}'"),
+                    offset,
+                ));
+            }
+        }
+        let definition = definition.cloned();
+
+        if self.peek() == Some(&Token::Punct('(')) {
+            self.arguments(definition.as_ref(), offset);
+        } else if let Some(definition) = &definition {
+            self.missing_arguments(definition, &[], offset);
+        }
+        self.directives();
+
+        let field_type = definition
+            .as_ref()
+            .map(|field| field.type_ref.named().to_string());
+        let composite = field_type
+            .as_deref()
+            .and_then(|field_type| self.schema.is_composite(field_type));
+        if self.peek() == Some(&Token::Punct('{')) {
+            if composite == Some(false) {
+                self.errors.push(Diagnostic::new(
+                    format!(
+                        "Field '{name}' of type '{}' cannot have a selection of subfields",
+                        field_type.as_deref().unwrap_or_default()
+                    ),
+                    offset,
+                ));
+            }
+            let nested = field_type.filter(|_| composite == Some(true));
+            self.selection_set(nested.as_deref());
+        } else if composite == Some(true) {
+            self.errors.push(Diagnostic::new(
+                format!(
+                    "Field '{name}' of type '{}' must have a selection of subfields",
+                    field_type.as_deref().unwrap_or_default()
+                ),
+                offset,
+            ));
+        }
+    }
+
+    fn arguments(&mut self, definition: Option<&Field>, field_offset: usize) {
+        self.pos += 1;
+        let mut given = Vec::new();
+        loop {
+            match self.peek() {
+                None => {
+                    self.error("Missing closing ')'");
+                    return;
+                }
+                Some(Token::Punct(')')) => {
+                    self.pos += 1;
+                    break;
+                }
+                Some(Token::Name(_)) => {
+                    let offset = self.offset();
+                    let name = self.name().unwrap_or_default();
+                    if let Some(definition) = definition {
+                        if !definition.args.iter().any(|arg| arg.name == name) {
+                            self.errors.push(Diagnostic::new(
+                                format!("Unknown argument '{name}' on field '{}'", definition.name),
+                                offset,
+                            ));
+                        }
+                    }
+                    given.push(name);
+                    if !self.eat(':') {
+                        self.error("Expected ':' after the argument name");
+                        continue;
+                    }
+                    self.value();
+                }
+                Some(_) => {
+                    self.error("Expected an argument name");
+                    self.pos += 1;
+                }
+            }
+        }
+        if let Some(definition) = definition {
+            self.missing_arguments(definition, &given, field_offset);
+        }
+    }
+
+    fn missing_arguments(&mut self, definition: &Field, given: &[String], offset: usize) {
+        for arg in &definition.args {
+            if arg.type_ref.is_non_null()
+                && arg.default_value.is_none()
+                && !given.contains(&arg.name)
+            {
+                self.errors.push(Diagnostic::new(
+                    format!(
+                        "Field '{}' is missing the required argument '{}: {}'",
+                        definition.name, arg.name, arg.type_ref
+                    ),
+                    offset,
+                ));
+            }
+        }
+    }
+
+    fn value(&mut self) {
+        match self.peek() {
+            Some(Token::Variable | Token::Literal | Token::Name(_)) => self.pos += 1,
+            Some(Token::Punct('[' | '{')) => self.skip_balanced(),
+            _ => self.error("Expected a value"),
+        }
+    }
+
+    fn directives(&mut self) {
+        while self.eat('@') {
+            if self.name().is_none() {
+                self.error("Expected a directive name");
+            }
+            if self.peek() == Some(&Token::Punct('(')) {
+                self.skip_balanced();
+            }
+        }
+    }
+
+    /// Skip a `(...)`, `[...]` or `{...}` group including nested groups
+    fn skip_balanced(&mut self) {
+        let mut depth = 0usize;
+        while let Some(token) = self.peek() {
+            match token {
+                Token::Punct('(' | '[' | '{') => depth += 1,
+                Token::Punct(')' | ']' | '}') => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+            if depth == 0 {
+                return;
+            }
+        }
+        self.error("Missing closing bracket");
+    }
+}
+
+/// A completion offered at the cursor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    pub label: String,
+    /// Type of the field or argument, or the kind of keyword / type
+    pub detail: String,
+    /// Text that replaces the partly typed word
+    pub insert: String,
+}
+
+/// Completions at byte offset `cursor`, filtered by the partly typed word
+/// before it. Returns that word's length (the text to replace) as well.
+pub fn completions(schema: &Schema, query: &str, cursor: usize) -> (usize, Vec<Completion>) {
+    let cursor = cursor.min(query.len());
+    let before = &query[..cursor];
+    let prefix_start = before
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_name_char(*c))
+        .last()
+        .map(|(offset, _)| offset)
+        .unwrap_or(cursor);
+    let prefix = &before[prefix_start..];
+    // Inside a string or comment, or after an invalid character
+    let Ok(tokens) = tokenize(&before[..prefix_start]) else {
+        return (0, Vec::new());
+    };
+    if before[..prefix_start]
+        .rsplit('\n')
+        .next()
+        .is_some_and(|line| line.contains('#'))
+    {
+        return (0, Vec::new());
+    }
+
+    let candidates = match scan_context(schema, &tokens) {
+        Context::TopLevel => ["query", "mutation", "subscription", "fragment"]
+            .iter()
+            .map(|keyword| Completion {
+                label: keyword.to_string(),
+                detail: "keyword".to_string(),
+                insert: keyword.to_string(),
+            })
+            .collect(),
+        Context::TypeName => schema
+            .type_names()
+            .into_iter()
+            .filter_map(|name| schema.get_type(name))
+            .filter(|schema_type| schema_type.is_composite())
+            .map(|schema_type| Completion {
+                label: schema_type.name.clone(),
+                detail: schema_type.kind.to_lowercase(),
+                insert: schema_type.name.clone(),
+            })
+            .collect(),
+        Context::Fields(type_name) => {
+            let mut fields: Vec<Completion> = schema
+                .get_type(&type_name)
+                .map(|schema_type| schema_type.fields.as_slice())
+                .unwrap_or_default()
+                .iter()
+                .map(|field| Completion {
+                    label: field.name.clone(),
+                    detail: field.type_ref.to_string(),
+                    insert: field.name.clone(),
+                })
+                .collect();
+            fields.push(Completion {
+                label: "__typename".to_string(),
+                detail: "String!".to_string(),
+                insert: "__typename".to_string(),
+            });
+            fields
+        }
+        Context::Arguments(field) => field
+            .args
+            .iter()
+            .map(|arg| Completion {
+                label: arg.name.clone(),
+                detail: arg.type_ref.to_string(),
+                insert: format!("{}: ", arg.name),
+            })
+            .collect(),
+        Context::EnumValue(type_name) => schema
+            .get_type(&type_name)
+            .map(|schema_type| schema_type.enum_values.as_slice())
+            .unwrap_or_default()
+            .iter()
+            .map(|value| Completion {
+                label: value.clone(),
+                detail: type_name.clone(),
+                insert: value.clone(),
+            })
+            .collect(),
+        Context::None => Vec::new(),
+    };
+
+    let lower = prefix.to_lowercase();
+    let completions = candidates
+        .into_iter()
+        .filter(|completion| completion.label.to_lowercase().starts_with(&lower))
+        .collect();
+    (prefix.len(), completions)
+}
+
+#[derive(Debug, PartialEq)]
+enum Context {
+    TopLevel,
+    TypeName,
+    Fields(String),
+    Arguments(Field),
+    EnumValue(String),
+    /// Unknown type, variable definitions, object values...
+    None,
+}
+
+enum Frame {
+    /// Selection set of a type (`None` if the type is unknown)
+    Selection(Option<String>),
+    Arguments {
+        field: Option<Field>,
+        /// Argument whose value comes next
+        value_of: Option<String>,
+    },
+    /// Variable definitions, list or object values
+    Other,
+}
+
+/// Walk the tokens before the cursor and work out what can be typed there
+fn scan_context(schema: &Schema, tokens: &[Spanned]) -> Context {
+    let mut stack: Vec<Frame> = Vec::new();
+    // Type of the selection set opened by the next `{`
+    let mut pending: Option<Option<String>> = None;
+    // Field whose arguments a `(` would open
+    let mut last_field: Option<Field> = None;
+
+    for (index, spanned) in tokens.iter().enumerate() {
+        let previous = index
+            .checked_sub(1)
+            .and_then(|index| tokens.get(index))
+            .map(|spanned| &spanned.token);
+        match &spanned.token {
+            Token::Name(name) => {
+                if previous == Some(&Token::Name("on".to_string())) {
+                    pending = Some(schema.get_type(name).map(|_| name.clone()));
+                    continue;
+                }
+                if previous == Some(&Token::Punct('@')) {
+                    last_field = None;
+                    continue;
+                }
+                match stack.last_mut() {
+                    None => match name.as_str() {
+                        "query" | "mutation" | "subscription" => {
+                            pending = Some(schema.root_type(name).map(str::to_string));
+                        }
+                        _ => {}
+                    },
+                    Some(Frame::Selection(parent)) => {
+                        if name == "on" && previous == Some(&Token::Spread) {
+                            continue;
+                        }
+                        last_field = parent
+                            .as_deref()
+                            .and_then(|parent| schema.field(parent, name))
+                            .cloned();
+                        pending = Some(
+                            last_field
+                                .as_ref()
+                                .map(|field| field.type_ref.named().to_string())
+                                .filter(|name| schema.is_composite(name) == Some(true)),
+                        );
+                    }
+                    Some(Frame::Arguments { value_of, .. }) => {
+                        if previous == Some(&Token::Punct(':')) {
+                            *value_of = None;
+                        }
+                    }
+                    Some(Frame::Other) => {}
+                }
+            }
+            Token::Punct('{') => match stack.last() {
+                None => {
+                    let root = pending.take().unwrap_or(Some(schema.query_type.clone()));
+                    stack.push(Frame::Selection(root));
+                }
+                Some(Frame::Selection(parent)) => {
+                    // `... {` keeps the parent type
+                    let nested = if previous == Some(&Token::Spread) {
+                        parent.clone()
+                    } else {
+                        pending.take().flatten()
+                    };
+                    stack.push(Frame::Selection(nested));
+                }
+                _ => stack.push(Frame::Other),
+            },
+            Token::Punct('(') => match stack.last() {
+                Some(Frame::Selection(_)) => {
+                    // `@include(...)` is a directive, not the field's arguments
+                    let is_directive = index >= 2 && tokens[index - 2].token == Token::Punct('@');
+                    stack.push(if is_directive {
+                        Frame::Other
+                    } else {
+                        Frame::Arguments {
+                            field: last_field.clone(),
+                            value_of: None,
+                        }
+                    });
+                }
+                _ => stack.push(Frame::Other),
+            },
+            Token::Punct('[') => stack.push(Frame::Other),
+            Token::Punct('}' | ')' | ']') => {
+                stack.pop();
+                if let Some(Frame::Arguments { value_of, .. }) = stack.last_mut() {
+                    *value_of = None;
+                }
+            }
+            Token::Punct(':') => {
+                if let (Some(Frame::Arguments { value_of, .. }), Some(Token::Name(name))) =
+                    (stack.last_mut(), previous)
+                {
+                    *value_of = Some(name.clone());
+                }
+            }
+            Token::Variable | Token::Literal => {
+                if let Some(Frame::Arguments { value_of, .. }) = stack.last_mut() {
+                    *value_of = None;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let last = tokens.last().map(|spanned| &spanned.token);
+    if last == Some(&Token::Spread) || last == Some(&Token::Name("on".to_string())) {
+        return Context::TypeName;
+    }
+    match stack.last() {
+        None if last.is_none() || last == Some(&Token::Punct('}')) => Context::TopLevel,
+        None => Context::None,
+        Some(Frame::Selection(Some(parent))) => Context::Fields(parent.clone()),
+        Some(Frame::Arguments {
+            field: Some(field),
+            value_of,
+        }) => match value_of {
+            None => Context::Arguments(field.clone()),
+            Some(arg) => field
+                .args
+                .iter()
+                .find(|candidate| candidate.name == *arg)
+                .map(|arg| arg.type_ref.named())
+                .filter(|type_name| {
+                    schema
+                        .get_type(type_name)
+                        .is_some_and(|schema_type| schema_type.kind == "ENUM")
+                })
+                .map(|type_name| Context::EnumValue(type_name.to_string()))
+                .unwrap_or(Context::None),
+        },
+        _ => Context::None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const INTROSPECTION: &str = r#"{"data": {"__schema": {
+        "queryType": {"name": "Query"}, "mutationType": null, "subscriptionType": null,
+        "types": [
+            {"kind": "OBJECT", "name": "Query", "fields": [
+                {"name": "user", "args": [
+                    {"name": "id", "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "ID"}}}
+                ], "type": {"kind": "OBJECT", "name": "User"}},
+                {"name": "users", "args": [
+                    {"name": "role", "type": {"kind": "ENUM", "name": "Role"}},
+                    {"name": "first", "type": {"kind": "SCALAR", "name": "Int"}, "defaultValue": "10"}
+                ], "type": {"kind": "NON_NULL", "ofType": {"kind": "LIST", "ofType":
+                    {"kind": "NON_NULL", "ofType": {"kind": "OBJECT", "name": "User"}}}}}
+            ]},
+            {"kind": "OBJECT", "name": "User", "description": "A person", "fields": [
+                {"name": "id", "args": [], "type": {"kind": "NON_NULL", "ofType": {"kind": "SCALAR", "name": "ID"}}},
+                {"name": "name", "args": [], "type": {"kind": "SCALAR", "name": "String"}},
+                {"name": "friends", "args": [], "type": {"kind": "LIST", "ofType": {"kind": "OBJECT", "name": "User"}}}
+            ]},
+            {"kind": "ENUM", "name": "Role", "enumValues": [{"name": "ADMIN"}, {"name": "GUEST"}]},
+            {"kind": "SCALAR", "name": "ID"},
+            {"kind": "SCALAR", "name": "Int"},
+            {"kind": "SCALAR", "name": "String"},
+            {"kind": "OBJECT", "name": "__Schema", "fields": []}
+        ]
+    }}}"#;
+
+    fn schema() -> Schema {
+        Schema::from_introspection(INTROSPECTION).unwrap()
+    }
+
+    fn labels(query: &str) -> Vec<String> {
+        let cursor = query.find('|').unwrap();
+        let query = query.replace('|', "");
+        completions(&schema(), &query, cursor)
+            .1
+            .into_iter()
+            .map(|completion| completion.label)
+            .collect()
+    }
+
+    fn messages(query: &str) -> Vec<String> {
+        validate(&schema(), query)
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_from_introspection() {
+        let schema = schema();
+        assert_eq!(schema.query_type, "Query");
+        assert_eq!(schema.root_type("mutation"), None);
+        assert_eq!(
+            schema.type_names(),
+            ["ID", "Int", "Query", "Role", "String", "User"]
+        );
+        let users = schema.get_type("Query").unwrap().field("users").unwrap();
+        assert_eq!(users.type_ref.to_string(), "[User!]!");
+        assert_eq!(users.type_ref.named(), "User");
+        assert_eq!(users.args[1].default_value.as_deref(), Some("10"));
+
+        let error =
+            Schema::from_introspection(r#"{"errors":[{"message":"Introspection is disabled"}]}"#)
+                .unwrap_err();
+        assert!(error.to_string().contains("Introspection is disabled"));
+        assert!(Schema::from_introspection("<html>").is_err());
+    }
+
+    #[test]
+    fn test_field_completions() {
+        assert_eq!(labels("{ us|"), ["user", "users"]);
+        assert_eq!(
+            labels("query Q { user(id: 1) { |"),
+            ["id", "name", "friends", "__typename"]
+        );
+        assert_eq!(labels("{ users { friends { na| } }"), ["name"]);
+        assert_eq!(labels("{ me: user(id: 1) { f|"), ["friends"]);
+        assert_eq!(labels("{ user(id: 1) { ... on |"), ["Query", "User"]);
+        assert_eq!(
+            labels("|"),
+            ["query", "mutation", "subscription", "fragment"]
+        );
+        assert!(labels("{ user(id: 1) { name(| } }").is_empty());
+        assert!(labels("{ user(id: \"us|").is_empty());
+    }
+
+    #[test]
+    fn test_argument_completions() {
+        assert_eq!(labels("{ users(|"), ["role", "first"]);
+        assert_eq!(labels("{ users(first: 5 r|"), ["role"]);
+        assert_eq!(labels("{ users(role: |"), ["ADMIN", "GUEST"]);
+        let (replace, completions) = completions(&schema(), "{ users(fi", 10);
+        assert_eq!(replace, 2);
+        assert_eq!(completions[0].insert, "first: ");
+        assert_eq!(completions[0].detail, "Int");
+    }
+
+    #[test]
+    fn test_is_subscription() {
+        assert!(is_subscription("# live\nsubscription OnTick { tick }"));
+        assert!(is_subscription(
+            "fragment F on User { id } subscription { user { ...F } }"
+        ));
+        assert!(!is_subscription("query { subscription { id } }"));
+        assert!(!is_subscription("{ users { id } }"));
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(
+            messages("query Q($id: ID!) { user(id: $id) { id name friends { name } } }").is_empty()
+        );
+        assert!(messages("{ users(role: ADMIN) { ...UserFields ... on User { id } } } fragment UserFields on User { name }").is_empty());
+        assert_eq!(
+            messages("{ user(id: 1, name: \"x\") { email } users }"),
+            [
+                "Unknown argument 'name' on field 'user'",
+                "Cannot query field 'email' on type 'User'",
+                "Field 'users' of type 'User' must have a selection of subfields",
+            ]
+        );
+        assert_eq!(
+            messages("{ user { name { first } } }"),
+            [
+                "Field 'user' is missing the required argument 'id: ID!'",
+                "Field 'name' of type 'String' cannot have a selection of subfields",
+            ]
+        );
+        assert_eq!(messages("{ users { id }"), ["Missing closing '}'"]);
+        assert_eq!(
+            messages("mutation { x }"),
+            ["The schema has no mutation type"]
+        );
+        assert_eq!(
+            messages("{ user(id: \"1) { id } }"),
+            ["Unterminated string"]
+        );
+
+        let query = "{\n  user(id: 1) {\n    email\n  }\n}";
+        let diagnostics = validate(&schema(), query);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line_column(query), (3, 5));
+    }
+}
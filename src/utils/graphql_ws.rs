@@ -0,0 +1,191 @@
+//! GraphQL over WebSocket (the `graphql-transport-ws` subprotocol used by
+//! graphql-ws): the JSON messages exchanged to run subscriptions.
+
+use crate::errors::AppError;
+use serde_json::Value;
+
+/// Subprotocol requested in the WebSocket handshake
+pub const SUBPROTOCOL: &str = "graphql-transport-ws";
+
+/// Messages sent by the client
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClientMessage {
+    /// First message on the connection; the payload usually carries auth
+    ConnectionInit(Option<Value>),
+    Subscribe {
+        id: String,
+        query: String,
+        variables: Option<Value>,
+    },
+    /// Stop the subscription `id`
+    Complete(String),
+    Ping,
+    Pong,
+}
+
+impl ClientMessage {
+    pub fn encode(&self) -> String {
+        let message = match self {
+            ClientMessage::ConnectionInit(None) => {
+                serde_json::json!({ "type": "connection_init" })
+            }
+            ClientMessage::ConnectionInit(Some(payload)) => {
+                serde_json::json!({ "type": "connection_init", "payload": payload })
+            }
+            ClientMessage::Subscribe {
+                id,
+                query,
+                variables,
+            } => {
+                let mut payload = serde_json::json!({ "query": query });
+                if let Some(variables) = variables {
+                    payload["variables"] = variables.clone();
+                }
+                serde_json::json!({ "id": id, "type": "subscribe", "payload": payload })
+            }
+            ClientMessage::Complete(id) => serde_json::json!({ "id": id, "type": "complete" }),
+            ClientMessage::Ping => serde_json::json!({ "type": "ping" }),
+            ClientMessage::Pong => serde_json::json!({ "type": "pong" }),
+        };
+        message.to_string()
+    }
+}
+
+/// Messages sent by the server
+#[derive(Debug, Clone, PartialEq)]
+pub enum ServerMessage {
+    ConnectionAck(Option<Value>),
+    /// One event of subscription `id` (`{"data": ..., "errors": ...}`)
+    Next {
+        id: String,
+        payload: Value,
+    },
+    /// The subscription failed; `errors` is the GraphQL errors array
+    Error {
+        id: String,
+        errors: Value,
+    },
+    /// The server ended subscription `id`
+    Complete(String),
+    Ping(Option<Value>),
+    Pong(Option<Value>),
+}
+
+impl ServerMessage {
+    pub fn decode(text: &str) -> Result<Self, AppError> {
+        let message: Value = serde_json::from_str(text)
+            .map_err(|e| AppError::ParseError(format!("graphql-ws message is not JSON: {e}")))?;
+        let payload = message
+            .get("payload")
+            .filter(|payload| !payload.is_null())
+            .cloned();
+        let id = || {
+            message
+                .get("id")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .ok_or_else(|| AppError::ParseError("graphql-ws message without an id".to_string()))
+        };
+        match message.get("type").and_then(Value::as_str) {
+            Some("connection_ack") => Ok(ServerMessage::ConnectionAck(payload)),
+            Some("next") => Ok(ServerMessage::Next {
+                id: id()?,
+                payload: payload.unwrap_or(Value::Null),
+            }),
+            Some("error") => Ok(ServerMessage::Error {
+                id: id()?,
+                errors: payload.unwrap_or(Value::Null),
+            }),
+            Some("complete") => Ok(ServerMessage::Complete(id()?)),
+            Some("ping") => Ok(ServerMessage::Ping(payload)),
+            Some("pong") => Ok(ServerMessage::Pong(payload)),
+            other => Err(AppError::ParseError(format!(
+                "Unknown graphql-ws message type {}",
+                other.unwrap_or("(missing)")
+            ))),
+        }
+    }
+}
+
+/// WebSocket URL for an HTTP GraphQL endpoint (`http` → `ws`, `https` →
+/// `wss`); `ws://` and `wss://` URLs are kept as they are
+pub fn websocket_url(url: &str) -> String {
+    let url = url.trim();
+    if let Some(rest) = url.strip_prefix("https://") {
+        format!("wss://{rest}")
+    } else if let Some(rest) = url.strip_prefix("http://") {
+        format!("ws://{rest}")
+    } else if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("ws://{url}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_client_messages() {
+        assert_eq!(
+            ClientMessage::ConnectionInit(None).encode(),
+            r#"{"type":"connection_init"}"#
+        );
+        let subscribe = ClientMessage::Subscribe {
+            id: "1".to_string(),
+            query: "subscription { tick }".to_string(),
+            variables: Some(serde_json::from_str(r#"{"every":5}"#).unwrap()),
+        };
+        assert_eq!(
+            subscribe.encode(),
+            r#"{"id":"1","payload":{"query":"subscription { tick }","variables":{"every":5}},"type":"subscribe"}"#
+        );
+        assert_eq!(
+            ClientMessage::Complete("1".to_string()).encode(),
+            r#"{"id":"1","type":"complete"}"#
+        );
+    }
+
+    #[test]
+    fn test_decode_server_messages() {
+        assert_eq!(
+            ServerMessage::decode(r#"{"type":"connection_ack"}"#).unwrap(),
+            ServerMessage::ConnectionAck(None)
+        );
+        let next =
+            ServerMessage::decode(r#"{"id":"1","type":"next","payload":{"data":{"tick":3}}}"#)
+                .unwrap();
+        assert_eq!(
+            next,
+            ServerMessage::Next {
+                id: "1".to_string(),
+                payload: serde_json::from_str(r#"{"data":{"tick":3}}"#).unwrap(),
+            }
+        );
+        assert_eq!(
+            ServerMessage::decode(r#"{"id":"1","type":"complete"}"#).unwrap(),
+            ServerMessage::Complete("1".to_string())
+        );
+        assert!(ServerMessage::decode(r#"{"type":"next","payload":{}}"#).is_err());
+        assert!(ServerMessage::decode(r#"{"type":"data"}"#).is_err());
+        assert!(ServerMessage::decode("not json").is_err());
+    }
+
+    #[test]
+    fn test_websocket_url() {
+        assert_eq!(
+            websocket_url("https://api.example.com/graphql"),
+            "wss://api.example.com/graphql"
+        );
+        assert_eq!(
+            websocket_url("http://localhost:4000/"),
+            "ws://localhost:4000/"
+        );
+        assert_eq!(
+            websocket_url("wss://example.com/ws"),
+            "wss://example.com/ws"
+        );
+        assert_eq!(websocket_url("localhost:4000"), "ws://localhost:4000");
+    }
+}
@@ -0,0 +1,204 @@
+//! gRPC message framing and call status (the parts of the protocol that sit
+//! on top of HTTP/2).
+
+use crate::errors::AppError;
+
+/// Largest message accepted from a server
+pub const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// Length-prefixed message: compressed flag (always 0 here), 4-byte
+/// big-endian length, then the serialized protobuf
+pub fn encode_frame(message: &[u8]) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(message.len() + 5);
+    frame.push(0);
+    frame.extend((message.len() as u32).to_be_bytes());
+    frame.extend_from_slice(message);
+    frame
+}
+
+/// Splits the response body into messages as HTTP/2 data frames arrive; a
+/// message may span several data frames and one frame may hold several
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+}
+
+impl FrameDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add received bytes and return every message completed by them
+    pub fn push(&mut self, data: &[u8]) -> Result<Vec<Vec<u8>>, AppError> {
+        self.buffer.extend_from_slice(data);
+        let mut messages = Vec::new();
+        while self.buffer.len() >= 5 {
+            if self.buffer[0] != 0 {
+                return Err(AppError::ParseError(
+                    "Received a compressed gRPC message; compression is not supported".to_string(),
+                ));
+            }
+            let length = u32::from_be_bytes([
+                self.buffer[1],
+                self.buffer[2],
+                self.buffer[3],
+                self.buffer[4],
+            ]) as usize;
+            if length > MAX_MESSAGE_BYTES {
+                return Err(AppError::ParseError(format!(
+                    "gRPC message of {length} bytes exceeds the {MAX_MESSAGE_BYTES} byte limit"
+                )));
+            }
+            if self.buffer.len() < 5 + length {
+                break;
+            }
+            messages.push(self.buffer[5..5 + length].to_vec());
+            self.buffer.drain(..5 + length);
+        }
+        Ok(messages)
+    }
+
+    /// Whether a partially received message is left over
+    pub fn has_partial(&self) -> bool {
+        !self.buffer.is_empty()
+    }
+}
+
+/// Names of the gRPC status codes
+pub fn status_name(code: u32) -> &'static str {
+    match code {
+        0 => "OK",
+        1 => "CANCELLED",
+        2 => "UNKNOWN",
+        3 => "INVALID_ARGUMENT",
+        4 => "DEADLINE_EXCEEDED",
+        5 => "NOT_FOUND",
+        6 => "ALREADY_EXISTS",
+        7 => "PERMISSION_DENIED",
+        8 => "RESOURCE_EXHAUSTED",
+        9 => "FAILED_PRECONDITION",
+        10 => "ABORTED",
+        11 => "OUT_OF_RANGE",
+        12 => "UNIMPLEMENTED",
+        13 => "INTERNAL",
+        14 => "UNAVAILABLE",
+        15 => "DATA_LOSS",
+        16 => "UNAUTHENTICATED",
+        _ => "UNKNOWN_CODE",
+    }
+}
+
+/// Final status of a call, from the `grpc-status` / `grpc-message` trailers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GrpcStatus {
+    pub code: u32,
+    pub message: String,
+}
+
+impl GrpcStatus {
+    /// Read the status from trailers (or from the headers of a
+    /// trailers-only response); `None` when `grpc-status` is missing
+    pub fn from_metadata(metadata: &[(String, String)]) -> Option<Self> {
+        let find = |name: &str| {
+            metadata
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .map(|(_, value)| value.as_str())
+        };
+        let code = find("grpc-status")?.trim().parse().ok()?;
+        let message = find("grpc-message").map(percent_decode).unwrap_or_default();
+        Some(Self { code, message })
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.code == 0
+    }
+}
+
+impl std::fmt::Display for GrpcStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.code, status_name(self.code))?;
+        if !self.message.is_empty() {
+            write!(f, ": {}", self.message)?;
+        }
+        Ok(())
+    }
+}
+
+/// `grpc-message` is percent-encoded UTF-8; malformed escapes are kept as is
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let (high, low) = (bytes[i + 1], bytes[i + 2]);
+            if high.is_ascii_hexdigit() && low.is_ascii_hexdigit() {
+                out.push((hex_value(high) << 4) | hex_value(low));
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+fn hex_value(digit: u8) -> u8 {
+    match digit {
+        b'0'..=b'9' => digit - b'0',
+        b'a'..=b'f' => digit - b'a' + 10,
+        _ => digit - b'A' + 10,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frame_round_trip() {
+        let frame = encode_frame(b"\x0a\x03Ann");
+        assert_eq!(frame, b"\x00\x00\x00\x00\x05\x0a\x03Ann");
+
+        let mut decoder = FrameDecoder::new();
+        let mut data = frame.clone();
+        data.extend(encode_frame(b""));
+        data.extend(&frame[..3]);
+        assert_eq!(
+            decoder.push(&data).unwrap(),
+            vec![b"\x0a\x03Ann".to_vec(), Vec::new()]
+        );
+        assert!(decoder.has_partial());
+        assert_eq!(
+            decoder.push(&frame[3..]).unwrap(),
+            vec![b"\x0a\x03Ann".to_vec()]
+        );
+        assert!(!decoder.has_partial());
+    }
+
+    #[test]
+    fn test_decoder_rejects_compressed_and_oversized() {
+        assert!(FrameDecoder::new().push(b"\x01\x00\x00\x00\x00").is_err());
+        assert!(FrameDecoder::new().push(b"\x00\xff\xff\xff\xff").is_err());
+    }
+
+    #[test]
+    fn test_status_from_trailers() {
+        let trailers = vec![
+            ("Grpc-Status".to_string(), "5".to_string()),
+            (
+                "grpc-message".to_string(),
+                "user%20%E2%9C%93 not found%".to_string(),
+            ),
+        ];
+        let status = GrpcStatus::from_metadata(&trailers).unwrap();
+        assert_eq!(status.message, "user ✓ not found%");
+        assert!(!status.is_ok());
+        assert_eq!(status.to_string(), "5 NOT_FOUND: user ✓ not found%");
+        assert!(GrpcStatus::from_metadata(&[]).is_none());
+        let ok = GrpcStatus::from_metadata(&[("grpc-status".to_string(), "0".to_string())]);
+        assert_eq!(ok.unwrap().to_string(), "0 OK");
+    }
+}
@@ -0,0 +1,194 @@
+//! Running local commands - request hooks, whose output is substituted into
+//! requests as `{{name}}` variables, and commands the response is piped to.
+
+use crate::errors::AppError;
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+/// How long a command may run before it is killed
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run `command` through the platform shell, feeding `stdin` to it, and
+/// return its standard output without the trailing newline
+pub fn run_hook(
+    command: &str,
+    stdin: Option<&[u8]>,
+    timeout: Duration,
+) -> Result<String, AppError> {
+    let mut shell = if cfg!(windows) {
+        let mut shell = Command::new("cmd");
+        shell.arg("/C");
+        shell
+    } else {
+        let mut shell = Command::new("sh");
+        shell.arg("-c");
+        shell
+    };
+    shell.arg(command);
+    run_command(shell, stdin, timeout)
+}
+
+/// Run a program directly (without a shell), like [`run_hook`]
+pub fn run_command(
+    mut command: Command,
+    stdin: Option<&[u8]>,
+    timeout: Duration,
+) -> Result<String, AppError> {
+    let mut child = command
+        .stdin(if stdin.is_some() {
+            Stdio::piped()
+        } else {
+            Stdio::null()
+        })
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::ValidationError(format!("Could not run the command: {e}")))?;
+
+    // Feed and drain the pipes from other threads so a chatty command can't block
+    let writer = child.stdin.take().zip(stdin).map(|(mut pipe, input)| {
+        let input = input.to_vec();
+        std::thread::spawn(move || {
+            // The command may exit without reading all of its input
+            let _ = pipe.write_all(&input);
+        })
+    });
+    let drain = |pipe: Option<Box<dyn Read + Send>>| {
+        std::thread::spawn(move || {
+            let mut output = Vec::new();
+            if let Some(mut pipe) = pipe {
+                let _ = pipe.read_to_end(&mut output);
+            }
+            output
+        })
+    };
+    let stdout = drain(
+        child
+            .stdout
+            .take()
+            .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>),
+    );
+    let stderr = drain(
+        child
+            .stderr
+            .take()
+            .map(|pipe| Box::new(pipe) as Box<dyn Read + Send>),
+    );
+
+    let started = Instant::now();
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if started.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(AppError::ValidationError(format!(
+                    "The command did not finish within {} s",
+                    timeout.as_secs()
+                )));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(10)),
+            Err(e) => {
+                return Err(AppError::ValidationError(format!(
+                    "The command failed: {e}"
+                )));
+            }
+        }
+    };
+    if let Some(writer) = writer {
+        let _ = writer.join();
+    }
+    let stdout = stdout.join().unwrap_or_default();
+    let stderr = stderr.join().unwrap_or_default();
+
+    if !status.success() {
+        let message = String::from_utf8_lossy(&stderr);
+        return Err(AppError::ValidationError(format!(
+            "The command exited with {status}: {}",
+            message.trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string())
+}
+
+/// Whether `name` can be used as `{{name}}`
+pub fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Replace `{{name}}` (spaces inside the braces are allowed) with the value
+/// of a known variable; unknown names are left as written
+pub fn substitute(text: &str, variables: &HashMap<String, String>) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let value = after
+            .find("}}")
+            .and_then(|end| Some((variables.get(after[..end].trim())?, end)));
+        match value {
+            Some((value, end)) => {
+                result.push_str(value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                result.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute() {
+        let variables = HashMap::from([
+            ("token".to_string(), "abc".to_string()),
+            ("user-id".to_string(), "42".to_string()),
+        ]);
+        assert_eq!(
+            substitute("Bearer {{token}} for {{ user-id }}", &variables),
+            "Bearer abc for 42"
+        );
+        assert_eq!(
+            substitute("{{unknown}} {{$uuid}} {{token", &variables),
+            "{{unknown}} {{$uuid}} {{token"
+        );
+        assert_eq!(substitute("{{{{token}}}}", &variables), "{{abc}}");
+
+        assert!(is_variable_name("access_token-2"));
+        assert!(!is_variable_name(""));
+        assert!(!is_variable_name("two words"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_hook() {
+        assert_eq!(
+            run_hook("printf 'tok\\n'", None, HOOK_TIMEOUT).unwrap(),
+            "tok"
+        );
+        assert_eq!(
+            run_hook("tr a-z A-Z", Some(b"body"), HOOK_TIMEOUT).unwrap(),
+            "BODY"
+        );
+
+        let error = run_hook("echo nope >&2; exit 3", None, HOOK_TIMEOUT).unwrap_err();
+        assert!(error.to_string().contains("nope"));
+        let error = run_hook("sleep 5", None, Duration::from_millis(100)).unwrap_err();
+        assert!(error.to_string().contains("did not finish"));
+    }
+}
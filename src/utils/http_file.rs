@@ -0,0 +1,161 @@
+//! Parsing `.http` request files as written for the JetBrains HTTP client
+//! and the VS Code REST Client.
+
+use crate::errors::AppError;
+use crate::models::{HttpMethod, Request};
+use crate::utils::curl::parse_curl;
+use crate::utils::hooks::substitute;
+use std::collections::HashMap;
+
+/// Parse the requests of a `.http` file.
+///
+/// Requests are separated by `###` lines. Each starts with a request line
+/// (`POST https://host/path HTTP/1.1`, the method defaults to GET), followed
+/// by headers and, after a blank line, the body. `#` and `//` lines before
+/// the request line are comments, and `@name = value` lines define
+/// variables used as `{{name}}`.
+pub fn parse_http_file(text: &str) -> Result<Vec<Request>, AppError> {
+    let mut variables = HashMap::new();
+    let mut requests = Vec::new();
+    let mut block: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        if line.starts_with("###") {
+            requests.extend(parse_block(&block, &mut variables)?);
+            block.clear();
+        } else {
+            block.push(line);
+        }
+    }
+    requests.extend(parse_block(&block, &mut variables)?);
+    Ok(requests)
+}
+
+/// Parse text piped or pasted into the app: a curl command or `.http`
+/// content with one or more requests
+pub fn parse_request_text(text: &str) -> Result<Vec<Request>, AppError> {
+    let trimmed = text.trim_start();
+    let requests = if trimmed.starts_with("curl ") || trimmed.starts_with("curl\t") {
+        vec![parse_curl(trimmed.trim_end())?]
+    } else {
+        parse_http_file(text)?
+    };
+    if requests.is_empty() {
+        return Err(AppError::ParseError(
+            "No curl command or HTTP request found".to_string(),
+        ));
+    }
+    Ok(requests)
+}
+
+// One request between `###` separators, `None` when it only has comments
+// and variables
+fn parse_block(
+    lines: &[&str],
+    variables: &mut HashMap<String, String>,
+) -> Result<Option<Request>, AppError> {
+    let mut lines = lines.iter().map(|line| line.trim_end_matches('\r'));
+    let request_line = loop {
+        let Some(line) = lines.next() else {
+            return Ok(None);
+        };
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with("//") {
+            continue;
+        }
+        if let Some((name, value)) = line.strip_prefix('@').and_then(|rest| rest.split_once('=')) {
+            let value = substitute(value.trim(), variables);
+            variables.insert(name.trim().to_string(), value);
+            continue;
+        }
+        break substitute(line, variables);
+    };
+
+    let mut parts = request_line.split_whitespace();
+    let first = parts.next().unwrap_or_default();
+    let (method, url) = match HttpMethod::from_str(first) {
+        Ok(method) => (method, parts.next().unwrap_or_default()),
+        Err(_) => (HttpMethod::GET, first),
+    };
+    if url.is_empty() {
+        return Err(AppError::ParseError(format!(
+            "Request line \"{request_line}\" has no URL"
+        )));
+    }
+    let mut request = Request::new(method, url);
+
+    for line in lines.by_ref() {
+        if line.trim().is_empty() {
+            break;
+        }
+        let (key, value) = line.split_once(':').ok_or_else(|| {
+            AppError::ParseError(format!(
+                "Header \"{line}\" should look like \"Name: value\""
+            ))
+        })?;
+        request.add_header(key.trim(), substitute(value.trim(), variables));
+    }
+
+    let body: Vec<&str> = lines.collect();
+    let body = substitute(body.join("\n").trim_end(), variables);
+    if !body.is_empty() {
+        request.set_body(body);
+    }
+    Ok(Some(request))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_http_file() {
+        let text = "@host = https://api.example.com\r\n\
+# List users
+GET {{host}}/users HTTP/1.1
+Accept: application/json
+
+###
+// Create one
+POST {{host}}/users
+Content-Type: application/json
+
+{
+  \"name\": \"Ada\"
+}
+
+### empty
+
+{{host}}/health
+";
+        let requests = parse_http_file(text).unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[0].method, HttpMethod::GET);
+        assert_eq!(requests[0].url, "https://api.example.com/users");
+        assert_eq!(
+            requests[0].headers,
+            vec![("Accept".to_string(), "application/json".to_string())]
+        );
+        assert!(requests[0].body.is_none());
+        assert_eq!(requests[1].method, HttpMethod::POST);
+        assert_eq!(
+            requests[1].body.as_deref(),
+            Some("{\n  \"name\": \"Ada\"\n}")
+        );
+        assert_eq!(requests[2].url, "https://api.example.com/health");
+
+        assert!(parse_http_file("POST\n").is_err());
+        assert!(parse_http_file("GET https://a.example\nnot a header\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_request_text() {
+        let requests =
+            parse_request_text("  curl -X DELETE https://api.example.com/users/1\n").unwrap();
+        assert_eq!(requests[0].method, HttpMethod::DELETE);
+        assert_eq!(
+            parse_request_text("PUT https://api.example.com/users/1").unwrap()[0].method,
+            HttpMethod::PUT
+        );
+        assert!(parse_request_text("# nothing here\n").is_err());
+    }
+}
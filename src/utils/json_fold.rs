@@ -0,0 +1,270 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+/// A foldable object or array spanning `start_line..=end_line`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FoldRegion {
+    pub start_line: usize,
+    pub end_line: usize,
+}
+
+impl FoldRegion {
+    /// Number of lines hidden when folded
+    pub fn hidden_lines(&self) -> usize {
+        self.end_line - self.start_line
+    }
+}
+
+/// Find the objects and arrays in pretty-printed JSON that can be folded.
+///
+/// Only lines that *end* with `{` or `[` open a region, which matches the
+/// layout produced by `serde_json::to_string_pretty`. Brackets inside
+/// strings are ignored. Regions are returned ordered by start line.
+pub fn fold_regions(text: &str) -> Vec<FoldRegion> {
+    let mut regions = Vec::new();
+    // (bracket, line it was opened on, whether it ends that line)
+    let mut stack: Vec<(char, usize, bool)> = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let mut in_string = false;
+        let mut escaped = false;
+        let trimmed_end = line.trim_end();
+        let last_index = trimmed_end.char_indices().last().map(|(i, _)| i);
+
+        for (i, c) in trimmed_end.char_indices() {
+            if in_string {
+                match c {
+                    _ if escaped => escaped = false,
+                    '\\' => escaped = true,
+                    '"' => in_string = false,
+                    _ => {}
+                }
+                continue;
+            }
+            match c {
+                '"' => in_string = true,
+                '{' | '[' => stack.push((c, line_index, Some(i) == last_index)),
+                '}' | ']' => {
+                    if let Some((_, start_line, opens_line)) = stack.pop() {
+                        if opens_line && line_index > start_line {
+                            regions.push(FoldRegion {
+                                start_line,
+                                end_line: line_index,
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    regions.sort_by_key(|region| region.start_line);
+    regions
+}
+
+/// One line of the folded text and where it came from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayLine {
+    pub source_line: usize,
+    /// Char offset of the line in the folded text
+    pub display_start: usize,
+    /// Char offset of the line in the original text
+    pub source_start: usize,
+    /// Chars copied from the original line (the summary of a folded
+    /// line is not counted)
+    pub len: usize,
+    pub folded: bool,
+}
+
+/// Text with some regions collapsed into one-line summaries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FoldedText {
+    pub text: String,
+    pub lines: Vec<DisplayLine>,
+}
+
+impl FoldedText {
+    /// Collapse the regions whose start line is in `folded`.
+    ///
+    /// A folded region is shown as its first line followed by
+    /// `… N lines ` and the closing line, e.g. `"items": [ … 2000 lines ],`.
+    pub fn new(text: &str, regions: &[FoldRegion], folded: &BTreeSet<usize>) -> Self {
+        let source_lines: Vec<&str> = text.lines().collect();
+        let mut source_starts = Vec::with_capacity(source_lines.len());
+        let mut offset = 0;
+        for line in &source_lines {
+            source_starts.push(offset);
+            offset += line.chars().count() + 1;
+        }
+
+        let folded_regions: BTreeMap<usize, &FoldRegion> = regions
+            .iter()
+            .filter(|region| folded.contains(&region.start_line))
+            .map(|region| (region.start_line, region))
+            .collect();
+
+        let mut output = String::new();
+        let mut lines = Vec::new();
+        let mut display_offset = 0;
+        let mut line_index = 0;
+
+        while line_index < source_lines.len() {
+            let line = source_lines[line_index];
+            let region = folded_regions.get(&line_index);
+
+            let len = line.chars().count();
+            let mut display_line = line.to_string();
+            let next_line = match region {
+                Some(region) => {
+                    let closing = source_lines
+                        .get(region.end_line)
+                        .map_or("", |line| line.trim());
+                    display_line.push_str(&format!(
+                        " … {} lines {}",
+                        region.hidden_lines(),
+                        closing
+                    ));
+                    region.end_line + 1
+                }
+                None => line_index + 1,
+            };
+
+            if !lines.is_empty() {
+                output.push('\n');
+                display_offset += 1;
+            }
+            lines.push(DisplayLine {
+                source_line: line_index,
+                display_start: display_offset,
+                source_start: source_starts[line_index],
+                len,
+                folded: region.is_some(),
+            });
+            display_offset += display_line.chars().count();
+            output.push_str(&display_line);
+            line_index = next_line;
+        }
+
+        Self {
+            text: output,
+            lines,
+        }
+    }
+
+    /// Map a char offset in the folded text back to the original text
+    pub fn source_offset(&self, display_offset: usize) -> usize {
+        let index = self
+            .lines
+            .partition_point(|line| line.display_start <= display_offset)
+            .saturating_sub(1);
+        match self.lines.get(index) {
+            Some(line) => line.source_start + (display_offset - line.display_start).min(line.len),
+            None => display_offset,
+        }
+    }
+
+    /// Map a range of the original text into the folded text.
+    ///
+    /// Returns `None` when either end is hidden inside a folded region.
+    pub fn display_range(&self, source: &Range<usize>) -> Option<Range<usize>> {
+        let start = self.display_offset(source.start)?;
+        let end = self.display_offset(source.end)?;
+        Some(start..end)
+    }
+
+    fn display_offset(&self, source_offset: usize) -> Option<usize> {
+        let index = self
+            .lines
+            .partition_point(|line| line.source_start <= source_offset)
+            .saturating_sub(1);
+        let line = self.lines.get(index)?;
+        let within = source_offset - line.source_start;
+        (within <= line.len).then_some(line.display_start + within)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const JSON: &str = r#"{
+  "name": "John",
+  "tags": [
+    "a",
+    "b"
+  ],
+  "address": {
+    "city": "{not a bracket"
+  }
+}"#;
+
+    #[test]
+    fn test_fold_regions() {
+        let regions = fold_regions(JSON);
+        assert_eq!(
+            regions,
+            vec![
+                FoldRegion {
+                    start_line: 0,
+                    end_line: 9
+                },
+                FoldRegion {
+                    start_line: 2,
+                    end_line: 5
+                },
+                FoldRegion {
+                    start_line: 6,
+                    end_line: 8
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_single_line_containers_are_not_foldable() {
+        assert!(fold_regions(r#"{"a": [1, 2]}"#).is_empty());
+        assert!(fold_regions("{\n  \"a\": []\n}").len() == 1);
+    }
+
+    #[test]
+    fn test_no_folds_keeps_text() {
+        let folded = FoldedText::new(JSON, &fold_regions(JSON), &BTreeSet::new());
+        assert_eq!(folded.text, JSON);
+        assert_eq!(folded.lines.len(), 10);
+    }
+
+    #[test]
+    fn test_fold_array() {
+        let regions = fold_regions(JSON);
+        let folded = FoldedText::new(JSON, &regions, &BTreeSet::from([2]));
+        let lines: Vec<&str> = folded.text.lines().collect();
+        assert_eq!(lines[2], r#"  "tags": [ … 3 lines ],"#);
+        assert_eq!(lines[3], r#"  "address": {"#);
+        assert_eq!(lines.len(), 7);
+    }
+
+    // Char offset of the first match of `needle`
+    fn char_offset(text: &str, needle: &str) -> usize {
+        text[..text.find(needle).unwrap()].chars().count()
+    }
+
+    #[test]
+    fn test_offset_mapping() {
+        let regions = fold_regions(JSON);
+        let folded = FoldedText::new(JSON, &regions, &BTreeSet::from([2]));
+
+        // "address" is after the folded region
+        let source_start = char_offset(JSON, "\"address\"");
+        let display_start = char_offset(&folded.text, "\"address\"");
+        assert_eq!(folded.source_offset(display_start), source_start);
+        assert_eq!(
+            folded.display_range(&(source_start..source_start + 9)),
+            Some(display_start..display_start + 9)
+        );
+
+        // "a" inside the folded array is hidden
+        let hidden = char_offset(JSON, "\"a\"");
+        assert_eq!(folded.display_range(&(hidden..hidden + 3)), None);
+    }
+}
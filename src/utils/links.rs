@@ -0,0 +1,213 @@
+//! Find something to follow under the cursor in a response body: an absolute
+//! URL, a path (HATEOAS `href`s are usually relative to the API origin) or
+//! the value of an id field.
+
+/// A followable reference found in a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Link {
+    /// `http://` or `https://` URL
+    Url(String),
+    /// Absolute path such as `/users/2`
+    Path(String),
+    /// Value of an `id` / `*_id` / `*Id` field
+    Id(String),
+}
+
+/// Link at character `offset` of `text` (the character under the pointer)
+pub fn link_at(text: &str, offset: usize) -> Option<Link> {
+    let chars: Vec<char> = text.chars().collect();
+
+    if let Some((start, end)) = token_bounds(&chars, offset, is_wide_delimiter) {
+        let wide: String = chars[start..end].iter().collect();
+        let wide = wide.trim_end_matches([',', '.', ';', ':', ')', ']', '}']);
+        if (wide.starts_with("http://") || wide.starts_with("https://")) && wide.len() > 8 {
+            return Some(Link::Url(wide.to_string()));
+        }
+        if wide.starts_with('/') && !wide.starts_with("//") && wide.len() > 1 {
+            return Some(Link::Path(wide.to_string()));
+        }
+    }
+
+    let (start, end) = token_bounds(&chars, offset, |c| !is_id_char(c))?;
+    let value: String = chars[start..end].iter().collect();
+    let key = key_before(&chars[..start])?;
+    is_id_key(&key).then_some(Link::Id(value))
+}
+
+impl Link {
+    /// Full URL to request, relative to the URL the response came from
+    pub fn resolve(&self, base_url: &str) -> Option<String> {
+        match self {
+            Link::Url(url) => Some(url.clone()),
+            Link::Path(path) => Some(format!("{}{path}", origin(base_url)?)),
+            Link::Id(id) => {
+                origin(base_url)?;
+                let base = base_url
+                    .split(['?', '#'])
+                    .next()
+                    .unwrap_or_default()
+                    .trim_end_matches('/');
+                let (parent, last) = base.rsplit_once('/')?;
+                // `/users/1` already names an item - a sibling id replaces it
+                if last == id {
+                    Some(base.to_string())
+                } else if looks_like_id(last) && !parent.ends_with('/') {
+                    Some(format!("{parent}/{id}"))
+                } else {
+                    Some(format!("{base}/{id}"))
+                }
+            }
+        }
+    }
+}
+
+/// `scheme://authority` of a URL
+pub fn origin(url: &str) -> Option<&str> {
+    let url = url.trim();
+    let scheme_end = url.find("://")? + 3;
+    let end = url[scheme_end..]
+        .find(['/', '?', '#'])
+        .map_or(url.len(), |index| scheme_end + index);
+    (end > scheme_end).then(|| &url[..end])
+}
+
+/// Numbers and UUID-like segments
+pub fn looks_like_id(segment: &str) -> bool {
+    !segment.is_empty()
+        && (segment.chars().all(|c| c.is_ascii_digit())
+            || (segment.len() >= 16 && segment.chars().all(|c| c.is_ascii_hexdigit() || c == '-')))
+}
+
+fn is_wide_delimiter(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '"' | '\'' | '<' | '>' | '`')
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '-' | '_' | '.')
+}
+
+/// Bounds of the token under `offset`; the pointer may also sit just after
+/// the token's last character
+fn token_bounds(
+    chars: &[char],
+    offset: usize,
+    is_delimiter: impl Fn(char) -> bool,
+) -> Option<(usize, usize)> {
+    let offset = match chars.get(offset) {
+        Some(c) if !is_delimiter(*c) => offset,
+        _ => offset
+            .checked_sub(1)
+            .filter(|offset| !is_delimiter(chars[*offset]))?,
+    };
+    let start = chars[..offset]
+        .iter()
+        .rposition(|c| is_delimiter(*c))
+        .map_or(0, |index| index + 1);
+    let end = chars[offset..]
+        .iter()
+        .position(|c| is_delimiter(*c))
+        .map_or(chars.len(), |index| offset + index);
+    Some((start, end))
+}
+
+/// The JSON key in front of a value: `"key": "value"` or `"key": 42`
+fn key_before(before: &[char]) -> Option<String> {
+    let mut rest = before;
+    let skip_spaces = |rest: &mut &[char]| {
+        while rest.last().is_some_and(|c| c.is_whitespace()) {
+            *rest = &rest[..rest.len() - 1];
+        }
+    };
+    if rest.last() == Some(&'"') {
+        rest = &rest[..rest.len() - 1];
+    }
+    skip_spaces(&mut rest);
+    if rest.last() != Some(&':') {
+        return None;
+    }
+    rest = &rest[..rest.len() - 1];
+    skip_spaces(&mut rest);
+    if rest.last() != Some(&'"') {
+        return None;
+    }
+    rest = &rest[..rest.len() - 1];
+    let start = rest.iter().rposition(|c| *c == '"')?;
+    Some(rest[start + 1..].iter().collect())
+}
+
+fn is_id_key(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    lower == "id" || lower.ends_with("_id") || lower.ends_with("-id") || key.ends_with("Id")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(text: &str) -> Option<Link> {
+        link_at(&text.replace('|', ""), text.find('|').unwrap())
+    }
+
+    #[test]
+    fn test_link_at() {
+        assert_eq!(
+            link(r#"{"next": "https://api.example.com/users?pa|ge=2",}"#),
+            Some(Link::Url(
+                "https://api.example.com/users?page=2".to_string()
+            ))
+        );
+        assert_eq!(
+            link(r#"{"_links": {"self": {"href": "/users/|2"}}}"#),
+            Some(Link::Path("/users/2".to_string()))
+        );
+        assert_eq!(
+            link(r#"<a href="/docs|">docs</a>"#),
+            Some(Link::Path("/docs".to_string()))
+        );
+        assert_eq!(
+            link(r#"[{"id": 4|2, "name": "Ann"}]"#),
+            Some(Link::Id("42".to_string()))
+        );
+        assert_eq!(
+            link(r#"{"owner_id" : "u-1|7"}"#),
+            Some(Link::Id("u-17".to_string()))
+        );
+        // Pointer just after the value
+        assert_eq!(link(r#"{"userId": 7|}"#), Some(Link::Id("7".to_string())));
+        assert_eq!(link(r#"{"name": "An|n"}"#), None);
+        assert_eq!(link(r#"{"count": 4|2}"#), None);
+        assert_eq!(link("plain te|xt"), None);
+    }
+
+    #[test]
+    fn test_resolve() {
+        let base = "https://api.example.com/v1/users?page=2";
+        assert_eq!(
+            Link::Path("/v1/users/2".to_string()).resolve(base).unwrap(),
+            "https://api.example.com/v1/users/2"
+        );
+        assert_eq!(
+            Link::Id("42".to_string()).resolve(base).unwrap(),
+            "https://api.example.com/v1/users/42"
+        );
+        assert_eq!(
+            Link::Id("42".to_string())
+                .resolve("https://api.example.com/v1/users/7")
+                .unwrap(),
+            "https://api.example.com/v1/users/42"
+        );
+        assert_eq!(
+            Link::Id("7".to_string())
+                .resolve("https://api.example.com/v1/users/7/")
+                .unwrap(),
+            "https://api.example.com/v1/users/7"
+        );
+        assert_eq!(
+            Link::Url("http://other.test/x".to_string())
+                .resolve(base)
+                .unwrap(),
+            "http://other.test/x"
+        );
+        assert_eq!(Link::Path("/x".to_string()).resolve("not a url"), None);
+    }
+}
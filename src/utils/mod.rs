@@ -1 +1,38 @@
+pub mod assertions;
+pub mod base64;
+pub mod binary;
+pub mod cbor;
+pub mod clipboard;
+pub mod conditional;
+pub mod csv;
+pub mod curl;
+pub mod export_encryption;
+pub mod faker;
+pub mod format_rules;
 pub mod formatter;
+pub mod graphql;
+pub mod graphql_ws;
+pub mod grpc;
+pub mod hooks;
+pub mod http_file;
+pub mod jose;
+pub mod json_fold;
+pub mod links;
+pub mod msgpack;
+pub mod multipart;
+pub mod name_template;
+pub mod ndjson;
+pub mod openapi;
+pub mod paste;
+pub mod pdf;
+pub mod protobuf;
+pub mod range;
+pub mod reorder;
+pub mod response_diff;
+pub mod sniff;
+pub mod socketio;
+pub mod spec_drift;
+pub mod spec_infer;
+pub mod status_codes;
+pub mod websocket;
+pub mod whitespace;
@@ -0,0 +1,147 @@
+//! MessagePack decoding (https://github.com/msgpack/msgpack/blob/master/spec.md).
+
+use crate::errors::AppError;
+use crate::utils::binary::{too_deep, ByteReader, DataValue, MAX_DEPTH};
+
+/// Decode a single MessagePack value that spans the whole input
+pub fn decode(data: &[u8]) -> Result<DataValue, AppError> {
+    let mut reader = ByteReader::new(data);
+    let value = read_value(&mut reader, 0)?;
+    if !reader.is_empty() {
+        return Err(AppError::ParseError(format!(
+            "Unexpected data after the MessagePack value at byte {}",
+            reader.position()
+        )));
+    }
+    Ok(value)
+}
+
+fn read_value(reader: &mut ByteReader, depth: usize) -> Result<DataValue, AppError> {
+    if depth > MAX_DEPTH {
+        return Err(too_deep());
+    }
+    let start = reader.position();
+    let marker = reader.u8()?;
+    let value = match marker {
+        0x00..=0x7f => DataValue::Integer(i128::from(marker)),
+        0x80..=0x8f => read_map(reader, usize::from(marker & 0x0f), depth)?,
+        0x90..=0x9f => read_array(reader, usize::from(marker & 0x0f), depth)?,
+        0xa0..=0xbf => DataValue::String(reader.utf8(usize::from(marker & 0x1f))?),
+        0xc0 => DataValue::Null,
+        0xc2 => DataValue::Bool(false),
+        0xc3 => DataValue::Bool(true),
+        0xc4..=0xc6 => {
+            let len = read_len(reader, marker - 0xc4)?;
+            DataValue::Bytes(reader.take(len)?.to_vec())
+        }
+        0xc7..=0xc9 => {
+            let len = read_len(reader, marker - 0xc7)?;
+            let kind = reader.u8()? as i8;
+            DataValue::Ext(kind, reader.take(len)?.to_vec())
+        }
+        0xca => DataValue::Float(f64::from(f32::from_be_bytes(reader.array()?))),
+        0xcb => DataValue::Float(f64::from_be_bytes(reader.array()?)),
+        0xcc..=0xcf => DataValue::Integer(i128::from(reader.uint_be(1 << (marker - 0xcc))?)),
+        0xd0 => DataValue::Integer(i128::from(i8::from_be_bytes(reader.array()?))),
+        0xd1 => DataValue::Integer(i128::from(i16::from_be_bytes(reader.array()?))),
+        0xd2 => DataValue::Integer(i128::from(i32::from_be_bytes(reader.array()?))),
+        0xd3 => DataValue::Integer(i128::from(i64::from_be_bytes(reader.array()?))),
+        0xd4..=0xd8 => {
+            let kind = reader.u8()? as i8;
+            DataValue::Ext(kind, reader.take(1 << (marker - 0xd4))?.to_vec())
+        }
+        0xd9..=0xdb => {
+            let len = read_len(reader, marker - 0xd9)?;
+            DataValue::String(reader.utf8(len)?)
+        }
+        0xdc | 0xdd => {
+            // Every element takes at least one byte
+            let len = reader.uint_be(2 << (marker - 0xdc))?;
+            let len = reader.checked_len(len)?;
+            read_array(reader, len, depth)?
+        }
+        0xde | 0xdf => {
+            let len = reader.uint_be(2 << (marker - 0xde))?;
+            let len = reader.checked_len(len)?;
+            read_map(reader, len, depth)?
+        }
+        0xe0..=0xff => DataValue::Integer(i128::from(marker as i8)),
+        0xc1 => {
+            return Err(AppError::ParseError(format!(
+                "Invalid MessagePack marker 0xc1 at byte {start}"
+            )))
+        }
+    };
+    Ok(value)
+}
+
+/// Byte length stored in 1, 2 or 4 bytes (`width` 0, 1 or 2)
+fn read_len(reader: &mut ByteReader, width: u8) -> Result<usize, AppError> {
+    let len = reader.uint_be(1 << width)?;
+    reader.checked_len(len)
+}
+
+fn read_array(reader: &mut ByteReader, len: usize, depth: usize) -> Result<DataValue, AppError> {
+    let mut items = Vec::with_capacity(len.min(reader.remaining()));
+    for _ in 0..len {
+        items.push(read_value(reader, depth + 1)?);
+    }
+    Ok(DataValue::Array(items))
+}
+
+fn read_map(reader: &mut ByteReader, len: usize, depth: usize) -> Result<DataValue, AppError> {
+    let mut entries = Vec::with_capacity(len.min(reader.remaining()));
+    for _ in 0..len {
+        let key = read_value(reader, depth + 1)?;
+        let value = read_value(reader, depth + 1)?;
+        entries.push((key, value));
+    }
+    Ok(DataValue::Map(entries))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_map() {
+        // {"compact": true, "schema": 0, "list": [1, -1, 300, 1.5], "bin": b"ab"}
+        let data = [
+            0x84, 0xa7, b'c', b'o', b'm', b'p', b'a', b'c', b't', 0xc3, 0xa6, b's', b'c', b'h',
+            b'e', b'm', b'a', 0x00, 0xa4, b'l', b'i', b's', b't', 0x94, 0x01, 0xff, 0xcd, 0x01,
+            0x2c, 0xcb, 0x3f, 0xf8, 0, 0, 0, 0, 0, 0, 0xa3, b'b', b'i', b'n', 0xc4, 0x02, b'a',
+            b'b',
+        ];
+        assert_eq!(
+            decode(&data).unwrap().to_json_compact(),
+            r#"{"compact":true,"schema":0,"list":[1,-1,300,1.5],"bin":"YWI="}"#
+        );
+    }
+
+    #[test]
+    fn test_decode_ext_and_wide_ints() {
+        let data = [
+            0x92, 0xd6, 0xff, 0, 0, 0, 1, 0xd3, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+        ];
+        assert_eq!(
+            decode(&data).unwrap(),
+            DataValue::Array(vec![
+                DataValue::Ext(-1, vec![0, 0, 0, 1]),
+                DataValue::Integer(-2)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(decode(&[0xc1]).is_err());
+        // Truncated string
+        assert!(decode(&[0xa3, b'a']).is_err());
+        // Array claiming more elements than bytes left
+        assert!(decode(&[0xdd, 0xff, 0xff, 0xff, 0xff]).is_err());
+        // Trailing data
+        assert!(decode(&[0xc0, 0xc0]).is_err());
+        // Deep nesting
+        assert!(decode(&[0x91; 1000]).is_err());
+    }
+}
@@ -0,0 +1,433 @@
+//! `multipart/form-data` bodies. A form-data value starting with `@` attaches
+//! the file at that path, as in `curl -F 'avatar=@me.png'`; the file is read
+//! when the request is sent. A part's Content-Type is detected for files and
+//! left out for text values unless the entry sets one.
+
+use crate::errors::AppError;
+use crate::utils::sniff::{is_text, sniff};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Bytes read from the start of a file to detect its type and show a snippet
+const PREVIEW_BYTES: u64 = 4096;
+
+/// Characters of text shown in a part preview
+const SNIPPET_CHARS: usize = 120;
+
+/// Path of the file a form-data value attaches, if it does
+pub fn file_path(value: &str) -> Option<&Path> {
+    let path = value.strip_prefix('@')?.trim();
+    (!path.is_empty()).then(|| Path::new(path))
+}
+
+/// A boundary that won't appear in the parts
+pub fn new_boundary() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_nanos())
+        .unwrap_or_default();
+    format!("----postman-gpui-{nanos:x}")
+}
+
+/// The boundary of a `multipart/form-data` Content-Type
+pub fn boundary_of(content_type: &str) -> Option<&str> {
+    let mut params = content_type.split(';');
+    if !params
+        .next()?
+        .trim()
+        .eq_ignore_ascii_case("multipart/form-data")
+    {
+        return None;
+    }
+    params
+        .find_map(|param| {
+            let (key, value) = param.split_once('=')?;
+            key.trim()
+                .eq_ignore_ascii_case("boundary")
+                .then(|| value.trim().trim_matches('"'))
+        })
+        .filter(|boundary| !boundary.is_empty())
+}
+
+/// Content-Type of an attached file, from its extension or else its first bytes
+pub fn content_type_for(path: &Path, head: &[u8]) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|extension| extension.to_str())
+        .map(str::to_ascii_lowercase);
+    let by_extension = match extension.as_deref() {
+        Some("json") => Some("application/json"),
+        Some("txt" | "log" | "md") => Some("text/plain"),
+        Some("csv") => Some("text/csv"),
+        Some("xml") => Some("application/xml"),
+        Some("html" | "htm") => Some("text/html"),
+        Some("png") => Some("image/png"),
+        Some("jpg" | "jpeg") => Some("image/jpeg"),
+        Some("gif") => Some("image/gif"),
+        Some("webp") => Some("image/webp"),
+        Some("svg") => Some("image/svg+xml"),
+        Some("pdf") => Some("application/pdf"),
+        Some("zip") => Some("application/zip"),
+        Some("gz") => Some("application/gzip"),
+        _ => None,
+    };
+    by_extension.unwrap_or_else(|| sniff(head))
+}
+
+fn snippet(text: &str) -> String {
+    let mut snippet: String = text
+        .chars()
+        .take(SNIPPET_CHARS)
+        .map(|c| if c.is_whitespace() { ' ' } else { c })
+        .collect();
+    if text.chars().nth(SNIPPET_CHARS).is_some() {
+        snippet.push('…');
+    }
+    snippet
+}
+
+/// One form-data entry to send as a part
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormPart {
+    pub name: String,
+    /// Text value, or `@path` to attach a file
+    pub value: String,
+    /// Content-Type replacing the detected one (or sent with a text value)
+    pub content_type: Option<String>,
+}
+
+impl FormPart {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            value: value.into(),
+            content_type: None,
+        }
+    }
+
+    pub fn with_content_type(mut self, content_type: Option<String>) -> Self {
+        self.content_type = content_type.filter(|content_type| !content_type.trim().is_empty());
+        self
+    }
+}
+
+/// What a part is shown as
+#[derive(Debug, Clone, PartialEq)]
+pub enum PreviewKind {
+    /// The start of a text value or text file
+    Text(String),
+    /// An image file, shown as a thumbnail
+    Image(PathBuf),
+    Binary,
+    /// The file can't be read - sending would fail
+    Unreadable(String),
+}
+
+/// Summary of one part, so users can check they attached the right file
+#[derive(Debug, Clone, PartialEq)]
+pub struct PartPreview {
+    pub name: String,
+    /// File name sent in the Content-Disposition, for file parts
+    pub file_name: Option<String>,
+    pub size: u64,
+    pub content_type: String,
+    /// Whether the Content-Type is sent in the part's headers (always for files)
+    pub content_type_sent: bool,
+    pub kind: PreviewKind,
+}
+
+impl PartPreview {
+    pub fn new(part: &FormPart) -> Self {
+        let name = &part.name;
+        let value = &part.value;
+        let Some(path) = file_path(value) else {
+            return Self {
+                name: name.to_string(),
+                file_name: None,
+                size: value.len() as u64,
+                content_type: part
+                    .content_type
+                    .clone()
+                    .unwrap_or_else(|| "text/plain".to_string()),
+                content_type_sent: part.content_type.is_some(),
+                kind: PreviewKind::Text(snippet(value)),
+            };
+        };
+
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned());
+        let (size, content_type, kind) = match read_head(path) {
+            Ok((size, head)) => {
+                let content_type = content_type_for(path, &head);
+                let kind = if content_type.starts_with("image/") {
+                    PreviewKind::Image(path.to_path_buf())
+                } else if is_text(&head) {
+                    PreviewKind::Text(snippet(&String::from_utf8_lossy(&head)))
+                } else {
+                    PreviewKind::Binary
+                };
+                (size, content_type, kind)
+            }
+            Err(e) => (
+                0,
+                content_type_for(path, &[]),
+                PreviewKind::Unreadable(e.to_string()),
+            ),
+        };
+        Self {
+            name: name.to_string(),
+            file_name,
+            size,
+            content_type: part
+                .content_type
+                .clone()
+                .unwrap_or_else(|| content_type.to_string()),
+            content_type_sent: true,
+            kind,
+        }
+    }
+}
+
+/// File size and the first `PREVIEW_BYTES`
+fn read_head(path: &Path) -> std::io::Result<(u64, Vec<u8>)> {
+    let file = std::fs::File::open(path)?;
+    let size = file.metadata()?.len();
+    let mut head = Vec::new();
+    file.take(PREVIEW_BYTES).read_to_end(&mut head)?;
+    Ok((size, head))
+}
+
+/// Quote a name for a Content-Disposition parameter
+fn escape(name: &str) -> String {
+    name.replace('"', "%22")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Delimiter and headers of one part, with its file name for file parts
+fn part_head(
+    boundary: &str,
+    name: &str,
+    file_name: Option<&str>,
+    content_type: Option<&str>,
+) -> String {
+    let mut head = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"{}\"",
+        escape(name)
+    );
+    if let Some(file_name) = file_name {
+        head.push_str(&format!("; filename=\"{}\"", escape(file_name)));
+    }
+    head.push_str("\r\n");
+    if let Some(content_type) = content_type {
+        head.push_str(&format!("Content-Type: {content_type}\r\n"));
+    }
+    head.push_str("\r\n");
+    head
+}
+
+/// Build the body for the parts, reading attached files
+pub fn encode(parts: &[FormPart], boundary: &str) -> Result<Vec<u8>, AppError> {
+    let mut body = Vec::new();
+    for part in parts {
+        let name = &part.name;
+        match file_path(&part.value) {
+            Some(path) => {
+                let bytes = std::fs::read(path)
+                    .map_err(|e| AppError::FileError(format!("{}: {e}", path.display())))?;
+                let file_name = path
+                    .file_name()
+                    .map(|file_name| file_name.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let head = &bytes[..bytes.len().min(PREVIEW_BYTES as usize)];
+                let content_type = part
+                    .content_type
+                    .as_deref()
+                    .unwrap_or_else(|| content_type_for(path, head));
+                body.extend_from_slice(
+                    part_head(boundary, name, Some(&file_name), Some(content_type)).as_bytes(),
+                );
+                body.extend_from_slice(&bytes);
+            }
+            None => {
+                body.extend_from_slice(
+                    part_head(boundary, name, None, part.content_type.as_deref()).as_bytes(),
+                );
+                body.extend_from_slice(part.value.as_bytes());
+            }
+        }
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--\r\n").as_bytes());
+    Ok(body)
+}
+
+/// Length of the body `encode` builds, taken from the previews so no file
+/// has to be read
+pub fn encoded_len(parts: &[PartPreview], boundary: &str) -> u64 {
+    let parts: u64 = parts
+        .iter()
+        .map(|part| {
+            let content_type = part.content_type_sent.then_some(part.content_type.as_str());
+            part_head(
+                boundary,
+                &part.name,
+                part.file_name.as_deref(),
+                content_type,
+            )
+            .len() as u64
+                + part.size
+                + 2
+        })
+        .sum();
+    parts + format!("--{boundary}--\r\n").len() as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::TempDir;
+
+    #[test]
+    fn test_file_values_and_boundaries() {
+        assert_eq!(file_path("@ ./me.png"), Some(Path::new("./me.png")));
+        assert_eq!(file_path("@"), None);
+        assert_eq!(file_path("me@example.com"), None);
+
+        assert_eq!(
+            boundary_of("multipart/form-data; boundary=\"abc\""),
+            Some("abc")
+        );
+        assert_eq!(
+            boundary_of("Multipart/Form-Data;charset=utf-8; Boundary=x1"),
+            Some("x1")
+        );
+        assert_eq!(boundary_of("multipart/form-data"), None);
+        assert_eq!(boundary_of("application/x-www-form-urlencoded"), None);
+        assert!(new_boundary().starts_with("----postman-gpui-"));
+    }
+
+    #[test]
+    fn test_content_type_detection() {
+        let png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR";
+        assert_eq!(
+            content_type_for(Path::new("a.JSON"), b""),
+            "application/json"
+        );
+        assert_eq!(content_type_for(Path::new("upload"), png), "image/png");
+        assert_eq!(
+            content_type_for(Path::new("upload"), b"RIFF\0\0\0\0WEBPVP8 "),
+            "image/webp"
+        );
+        assert_eq!(
+            content_type_for(Path::new("notes"), "héllo".as_bytes()),
+            "text/plain"
+        );
+        // A multi-byte character cut off by the preview limit is still text
+        assert_eq!(
+            content_type_for(Path::new("notes"), &"é".as_bytes()[..1]),
+            "text/plain"
+        );
+        assert_eq!(
+            content_type_for(Path::new("blob"), b"\0\x01\x02"),
+            "application/octet-stream"
+        );
+    }
+
+    #[test]
+    fn test_part_previews() {
+        let dir = TempDir::new("multipart-preview");
+        let text_path = dir.join("notes");
+        let image_path = dir.join("avatar.png");
+        let binary_path = dir.join("data.bin");
+        std::fs::write(&text_path, "line one\nline two").unwrap();
+        std::fs::write(&image_path, b"\x89PNG\r\n\x1a\n").unwrap();
+        std::fs::write(&binary_path, [0u8, 159, 146, 150]).unwrap();
+
+        let text = PartPreview::new(&FormPart::new("notes", format!("@{}", text_path.display())));
+        assert_eq!(text.file_name.as_deref(), Some("notes"));
+        assert_eq!(text.size, 17);
+        assert_eq!(text.content_type, "text/plain");
+        assert_eq!(
+            text.kind,
+            PreviewKind::Text("line one line two".to_string())
+        );
+
+        let image = PartPreview::new(&FormPart::new(
+            "avatar",
+            format!("@{}", image_path.display()),
+        ));
+        assert_eq!(image.content_type, "image/png");
+        assert_eq!(image.kind, PreviewKind::Image(image_path.clone()));
+
+        let binary = PartPreview::new(&FormPart::new(
+            "data",
+            format!("@{}", binary_path.display()),
+        ));
+        assert_eq!(binary.content_type, "application/octet-stream");
+        assert_eq!(binary.kind, PreviewKind::Binary);
+
+        let missing = PartPreview::new(&FormPart::new(
+            "gone",
+            format!("@{}", dir.join("gone.pdf").display()),
+        ));
+        assert_eq!(missing.content_type, "application/pdf");
+        assert!(matches!(missing.kind, PreviewKind::Unreadable(_)));
+
+        let value = PartPreview::new(&FormPart::new("title", "x".repeat(200)));
+        assert_eq!(value.file_name, None);
+        assert_eq!(value.size, 200);
+        assert!(!value.content_type_sent);
+        assert!(matches!(&value.kind, PreviewKind::Text(snippet) if snippet.ends_with('…')));
+
+        let typed = PartPreview::new(
+            &FormPart::new("avatar", format!("@{}", image_path.display()))
+                .with_content_type(Some("image/x-custom".to_string())),
+        );
+        assert_eq!(typed.content_type, "image/x-custom");
+    }
+
+    #[test]
+    fn test_encode_parts() {
+        let dir = TempDir::new("multipart-encode");
+        let path = dir.join("hello.txt");
+        std::fs::write(&path, "hi").unwrap();
+
+        let parts = vec![
+            FormPart::new("title", "My \"doc\""),
+            FormPart::new("file", format!("@{}", path.display())),
+            FormPart::new("meta", "{\n  \"tags\": []\n}")
+                .with_content_type(Some("application/json".to_string())),
+        ];
+        let body = encode(&parts, "XYZ").unwrap();
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--XYZ\r\n\
+             Content-Disposition: form-data; name=\"title\"\r\n\r\n\
+             My \"doc\"\r\n\
+             --XYZ\r\n\
+             Content-Disposition: form-data; name=\"file\"; filename=\"hello.txt\"\r\n\
+             Content-Type: text/plain\r\n\r\n\
+             hi\r\n\
+             --XYZ\r\n\
+             Content-Disposition: form-data; name=\"meta\"\r\n\
+             Content-Type: application/json\r\n\r\n\
+             {\n  \"tags\": []\n}\r\n\
+             --XYZ--\r\n"
+        );
+
+        let previews: Vec<PartPreview> = parts.iter().map(PartPreview::new).collect();
+        assert_eq!(
+            encoded_len(&previews, "XYZ"),
+            encode(&parts, "XYZ").unwrap().len() as u64
+        );
+
+        let missing = vec![FormPart::new("file", "@/no/such/file")];
+        assert!(matches!(
+            encode(&missing, "XYZ"),
+            Err(AppError::FileError(_))
+        ));
+    }
+}
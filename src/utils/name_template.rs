@@ -0,0 +1,213 @@
+//! Templates for naming history entries, e.g. `{method} {path} [{status}]`.
+//!
+//! A variable is written in braces and may end in `:N` to keep only its
+//! first N characters (`{url:40}`); `{{` and `}}` stand for literal braces.
+
+use crate::errors::AppError;
+use chrono::{DateTime, Local};
+
+/// Template used until the user sets one: the URL, cut to 40 characters
+pub const DEFAULT_HISTORY_NAME_TEMPLATE: &str = "{url:40}";
+
+/// Variables a template can use: `{duration}` is the response time in
+/// milliseconds and `{time}` the local time the response came in (`HH:MM`)
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "method", "url", "host", "path", "query", "status", "duration", "time",
+];
+
+/// What a history entry's name is made from
+#[derive(Debug, Clone, PartialEq)]
+pub struct NameFields<'a> {
+    pub method: &'a str,
+    pub url: &'a str,
+    pub status: u16,
+    pub duration_ms: u128,
+    pub time: DateTime<Local>,
+}
+
+impl NameFields<'_> {
+    fn value(&self, variable: &str) -> Option<String> {
+        let (host, path, query) = split_url(self.url);
+        Some(match variable {
+            "method" => self.method.to_string(),
+            "url" => self.url.to_string(),
+            "host" => host.to_string(),
+            "path" => path.to_string(),
+            "query" => query.to_string(),
+            "status" => self.status.to_string(),
+            "duration" => self.duration_ms.to_string(),
+            "time" => self.time.format("%H:%M").to_string(),
+            _ => return None,
+        })
+    }
+}
+
+// One piece of a parsed template
+enum Part<'a> {
+    Text(String),
+    Variable(&'a str, Option<usize>),
+}
+
+fn parse(template: &str) -> Result<Vec<Part<'_>>, AppError> {
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut rest = template;
+    while let Some(c) = rest.chars().next() {
+        if rest.starts_with("{{") || rest.starts_with("}}") {
+            text.push(c);
+            rest = &rest[2..];
+            continue;
+        }
+        if c == '}' {
+            return Err(AppError::ValidationError(
+                "Unmatched \"}\" in the name template - write \"}}\" for a brace".to_string(),
+            ));
+        }
+        if c != '{' {
+            text.push(c);
+            rest = &rest[c.len_utf8()..];
+            continue;
+        }
+
+        let end = rest.find('}').ok_or_else(|| {
+            AppError::ValidationError("Unclosed \"{\" in the name template".to_string())
+        })?;
+        let spec = &rest[1..end];
+        let (name, width) = match spec.split_once(':') {
+            Some((name, width)) => match width.trim().parse::<usize>() {
+                Ok(width) if width > 0 => (name.trim(), Some(width)),
+                _ => {
+                    return Err(AppError::ValidationError(format!(
+                        "\"{{{spec}}}\": the width after \":\" should be a positive number"
+                    )))
+                }
+            },
+            None => (spec.trim(), None),
+        };
+        if !TEMPLATE_VARIABLES.contains(&name) {
+            return Err(AppError::ValidationError(format!(
+                "Unknown variable \"{{{name}}}\" - use one of {}",
+                TEMPLATE_VARIABLES
+                    .iter()
+                    .map(|known| format!("{{{known}}}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )));
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(std::mem::take(&mut text)));
+        }
+        parts.push(Part::Variable(name, width));
+        rest = &rest[end + 1..];
+    }
+    if !text.is_empty() {
+        parts.push(Part::Text(text));
+    }
+    Ok(parts)
+}
+
+/// Check that `template` only uses known variables and is well formed
+pub fn validate(template: &str) -> Result<(), AppError> {
+    parse(template).map(|_| ())
+}
+
+/// The name `template` gives an entry. Falls back to the default template
+/// if `template` is invalid or renders to nothing
+pub fn render(template: &str, fields: &NameFields) -> String {
+    let parts = parse(template).unwrap_or_default();
+    let mut name = String::new();
+    for part in parts {
+        match part {
+            Part::Text(text) => name.push_str(&text),
+            Part::Variable(variable, width) => {
+                let value = fields.value(variable).unwrap_or_default();
+                name.push_str(&truncate(&value, width));
+            }
+        }
+    }
+    let name = name.trim();
+    if name.is_empty() && template != DEFAULT_HISTORY_NAME_TEMPLATE {
+        return render(DEFAULT_HISTORY_NAME_TEMPLATE, fields);
+    }
+    name.to_string()
+}
+
+// Keep the first `width` characters, marking the cut with "..."
+fn truncate(value: &str, width: Option<usize>) -> String {
+    match width {
+        Some(width) if value.chars().count() > width => {
+            let kept: String = value.chars().take(width).collect();
+            format!("{kept}...")
+        }
+        _ => value.to_string(),
+    }
+}
+
+// (host, path, query) of a URL; the path is "/" when the URL has none
+fn split_url(url: &str) -> (&str, &str, &str) {
+    let url = url.trim();
+    let url = url.split('#').next().unwrap_or_default();
+    let after_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let (before_query, query) = after_scheme.split_once('?').unwrap_or((after_scheme, ""));
+    match before_query.find('/') {
+        Some(slash) => (&before_query[..slash], &before_query[slash..], query),
+        None => (before_query, "/", query),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn fields(url: &str) -> NameFields<'_> {
+        NameFields {
+            method: "GET",
+            url,
+            status: 404,
+            duration_ms: 87,
+            time: Local.with_ymd_and_hms(2024, 5, 1, 9, 5, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_variables() {
+        let sent = fields("https://api.example.com:8443/v1/users?page=2#top");
+        assert_eq!(
+            render("{method} {path} [{status}]", &sent),
+            "GET /v1/users [404]"
+        );
+        assert_eq!(
+            render("{host}?{query} {duration}ms at {time}", &sent),
+            "api.example.com:8443?page=2 87ms at 09:05"
+        );
+        assert_eq!(render("{{{status}}}", &sent), "{404}");
+        assert_eq!(render("{path}", &fields("example.com")), "/");
+    }
+
+    #[test]
+    fn test_default_template_truncates_url() {
+        let short = "https://api.example.com/users";
+        assert_eq!(render(DEFAULT_HISTORY_NAME_TEMPLATE, &fields(short)), short);
+
+        let long = "https://api.example.com/v1/organizations/42/members";
+        assert_eq!(
+            render(DEFAULT_HISTORY_NAME_TEMPLATE, &fields(long)),
+            "https://api.example.com/v1/organizations..."
+        );
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(validate("{method} {path:30}").is_ok());
+        assert!(validate("{verb}").is_err());
+        assert!(validate("{path:0}").is_err());
+        assert!(validate("{path").is_err());
+        assert!(validate("path}").is_err());
+
+        // Invalid or empty templates still give the entry a name
+        let url = "https://api.example.com/users";
+        assert_eq!(render("{verb}", &fields(url)), url);
+        assert_eq!(render("  ", &fields(url)), url);
+    }
+}
@@ -0,0 +1,170 @@
+//! Newline-delimited JSON (NDJSON / JSON Lines), parsed incrementally as
+//! chunks of a streamed response arrive.
+
+use serde_json::Value;
+
+/// Longest single-line preview shown for a collapsed record
+pub const MAX_SUMMARY_LENGTH: usize = 200;
+
+/// Whether a `Content-Type` header value is newline-delimited JSON
+pub fn is_ndjson_content_type(content_type: &str) -> bool {
+    let mime = content_type
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    matches!(
+        mime.as_str(),
+        "application/x-ndjson"
+            | "application/ndjson"
+            | "application/jsonl"
+            | "application/x-jsonlines"
+            | "application/jsonlines"
+            | "application/json-lines"
+    )
+}
+
+/// One line of the stream
+#[derive(Debug, Clone, PartialEq)]
+pub struct NdjsonRecord {
+    /// Line as received, without the line ending
+    pub line: String,
+    /// Parsed value, or the parse error message
+    pub value: Result<Value, String>,
+}
+
+impl NdjsonRecord {
+    pub fn parse(line: &str) -> Self {
+        Self {
+            line: line.to_string(),
+            value: serde_json::from_str(line).map_err(|e| e.to_string()),
+        }
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.value.is_ok()
+    }
+
+    /// Single-line preview, truncated to [`MAX_SUMMARY_LENGTH`] characters
+    pub fn summary(&self) -> String {
+        let line = match &self.value {
+            Ok(value) => value.to_string(),
+            Err(_) => self.line.clone(),
+        };
+        if line.chars().count() > MAX_SUMMARY_LENGTH {
+            let truncated: String = line.chars().take(MAX_SUMMARY_LENGTH).collect();
+            format!("{truncated}...")
+        } else {
+            line
+        }
+    }
+
+    /// Pretty-printed JSON for the expanded view (the raw line if invalid)
+    pub fn pretty(&self) -> String {
+        match &self.value {
+            Ok(value) => serde_json::to_string_pretty(value).unwrap_or_else(|_| self.line.clone()),
+            Err(_) => self.line.clone(),
+        }
+    }
+
+    /// Case-insensitive substring match on the line; an empty filter matches all
+    pub fn matches(&self, filter: &str) -> bool {
+        let filter = filter.trim();
+        filter.is_empty()
+            || self
+                .line
+                .to_lowercase()
+                .contains(filter.to_lowercase().as_str())
+    }
+}
+
+/// Splits incoming bytes into records. A partial last line is kept until
+/// the next chunk (or [`NdjsonParser::finish`]), so chunks may end anywhere,
+/// even inside a multi-byte character.
+#[derive(Debug, Default)]
+pub struct NdjsonParser {
+    pending: Vec<u8>,
+}
+
+impl NdjsonParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed a chunk, returning the records it completes
+    pub fn push(&mut self, chunk: &[u8]) -> Vec<NdjsonRecord> {
+        self.pending.extend_from_slice(chunk);
+        let Some(last_newline) = self.pending.iter().rposition(|byte| *byte == b'\n') else {
+            return Vec::new();
+        };
+        let rest = self.pending.split_off(last_newline + 1);
+        let complete = std::mem::replace(&mut self.pending, rest);
+        parse_lines(&String::from_utf8_lossy(&complete))
+    }
+
+    /// End of the stream: the unterminated last line, if any
+    pub fn finish(&mut self) -> Option<NdjsonRecord> {
+        let rest = std::mem::take(&mut self.pending);
+        parse_lines(&String::from_utf8_lossy(&rest)).pop()
+    }
+}
+
+/// Parse a complete body; blank lines are skipped
+pub fn parse_lines(body: &str) -> Vec<NdjsonRecord> {
+    body.lines()
+        .map(|line| line.trim_end_matches('\r'))
+        .filter(|line| !line.trim().is_empty())
+        .map(NdjsonRecord::parse)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_type() {
+        assert!(is_ndjson_content_type("application/x-ndjson"));
+        assert!(is_ndjson_content_type("Application/JSONL; charset=utf-8"));
+        assert!(!is_ndjson_content_type("application/json"));
+    }
+
+    #[test]
+    fn test_parser_handles_split_chunks() {
+        let mut parser = NdjsonParser::new();
+        let records = parser.push(b"{\"id\":1}\n{\"name\":\"caf");
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].value, Ok(serde_json::json!({"id": 1})));
+        // "é" split across two chunks
+        let records = parser.push(&[0xc3]);
+        assert!(records.is_empty());
+        let records = parser.push(&[0xa9, b'"', b'}', b'\r', b'\n', b'\n', b'[', b'1']);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].value, Ok(serde_json::json!({"name": "café"})));
+
+        let last = parser.finish().unwrap();
+        assert_eq!(last.line, "[1");
+        assert!(!last.is_valid());
+        assert!(parser.finish().is_none());
+    }
+
+    #[test]
+    fn test_record_summary_and_filter() {
+        let records =
+            parse_lines("{\"level\":\"error\",\"msg\":\"Disk full\"}\n\n{\"level\":\"info\"}\n");
+        assert_eq!(records.len(), 2);
+        assert_eq!(
+            records[0].summary(),
+            r#"{"level":"error","msg":"Disk full"}"#
+        );
+        assert!(records[0].pretty().contains("\n  \"msg\": \"Disk full\""));
+        assert!(records[0].matches("disk"));
+        assert!(!records[1].matches("disk"));
+        assert!(records[1].matches(" "));
+
+        let long = NdjsonRecord::parse(&format!("\"{}\"", "x".repeat(500)));
+        assert!(long.summary().ends_with("..."));
+        assert_eq!(long.summary().chars().count(), MAX_SUMMARY_LENGTH + 3);
+    }
+}
@@ -0,0 +1,898 @@
+//! OpenAPI documents (3.x, and Swagger 2.0 for older APIs): the operations
+//! they describe, example values generated from their schemas and requests
+//! built from filled-in parameters.
+
+use crate::errors::AppError;
+use crate::models::{HttpMethod, Request};
+use serde_json::Value;
+use std::path::Path;
+
+/// Nesting limit when following `$ref`s and generating examples, so that
+/// recursive schemas terminate
+const MAX_SCHEMA_DEPTH: usize = 8;
+
+// Path item keys that are operations, in the order they are listed
+const OPERATION_METHODS: [(&str, HttpMethod); 7] = [
+    ("get", HttpMethod::GET),
+    ("post", HttpMethod::POST),
+    ("put", HttpMethod::PUT),
+    ("patch", HttpMethod::PATCH),
+    ("delete", HttpMethod::DELETE),
+    ("head", HttpMethod::HEAD),
+    ("options", HttpMethod::OPTIONS),
+];
+
+/// Where a parameter goes in the request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterLocation {
+    Path,
+    Query,
+    Header,
+    Cookie,
+}
+
+impl ParameterLocation {
+    fn parse(location: &str) -> Option<Self> {
+        match location {
+            "path" => Some(ParameterLocation::Path),
+            "query" => Some(ParameterLocation::Query),
+            "header" => Some(ParameterLocation::Header),
+            "cookie" => Some(ParameterLocation::Cookie),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ParameterLocation::Path => "path",
+            ParameterLocation::Query => "query",
+            ParameterLocation::Header => "header",
+            ParameterLocation::Cookie => "cookie",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Parameter {
+    pub name: String,
+    pub location: ParameterLocation,
+    pub required: bool,
+    pub description: Option<String>,
+    /// Schema with `$ref`s resolved at the top level
+    pub schema: Value,
+}
+
+impl Parameter {
+    /// Short type label such as `integer`, `string (uuid)` or `array`
+    pub fn type_label(&self) -> String {
+        schema_type_label(&self.schema)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RequestBody {
+    pub content_type: String,
+    pub required: bool,
+    pub schema: Value,
+}
+
+/// A documented response
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseSpec {
+    /// `200`, a range such as `2XX`, or `default`
+    pub status: String,
+    /// JSON body schema, when the response declares one
+    pub schema: Option<Value>,
+}
+
+impl ResponseSpec {
+    fn matches(&self, status: u16) -> bool {
+        let pattern = self.status.as_bytes();
+        pattern.len() == 3
+            && pattern[1..].eq_ignore_ascii_case(b"XX")
+            && pattern[0] == status.to_string().as_bytes()[0]
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Operation {
+    pub method: HttpMethod,
+    /// Path template such as `/users/{id}`
+    pub path: String,
+    pub operation_id: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    /// First tag of the operation (`default` when it has none)
+    pub tag: String,
+    pub deprecated: bool,
+    pub parameters: Vec<Parameter>,
+    pub request_body: Option<RequestBody>,
+    pub responses: Vec<ResponseSpec>,
+}
+
+impl Operation {
+    /// Summary, else operation id, else `METHOD /path`
+    pub fn title(&self) -> String {
+        self.summary
+            .clone()
+            .or_else(|| self.operation_id.clone())
+            .unwrap_or_else(|| format!("{} {}", self.method, self.path))
+    }
+
+    /// `METHOD /path` - the key the operation is reported under
+    pub fn endpoint(&self) -> String {
+        format!("{} {}", self.method, self.path)
+    }
+
+    /// Documented response for `status`: the exact code, then its range,
+    /// then `default`
+    pub fn response_for(&self, status: u16) -> Option<&ResponseSpec> {
+        let code = status.to_string();
+        self.responses
+            .iter()
+            .find(|response| response.status == code)
+            .or_else(|| {
+                self.responses
+                    .iter()
+                    .find(|response| response.matches(status))
+            })
+            .or_else(|| {
+                self.responses
+                    .iter()
+                    .find(|response| response.status == "default")
+            })
+    }
+
+    /// Whether `path` (without query) is an instance of this operation's
+    /// template; the template may be preceded by the server's base path
+    fn matches_path(&self, path: &str) -> bool {
+        let template: Vec<&str> = self.path.split('/').filter(|s| !s.is_empty()).collect();
+        let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+        segments.len() >= template.len()
+            && segments[segments.len() - template.len()..]
+                .iter()
+                .zip(&template)
+                .all(|(segment, part)| {
+                    (part.starts_with('{') && part.ends_with('}')) || segment == part
+                })
+    }
+
+    /// Build the request against `base_url`; `values` are the parameter
+    /// values in the order of [`Operation::parameters`] (empty values are
+    /// left out) and `body` is sent with the declared content type
+    pub fn build_request(
+        &self,
+        base_url: &str,
+        values: &[String],
+        body: Option<&str>,
+    ) -> Result<Request, AppError> {
+        let mut path = self.path.clone();
+        let mut query = form_urlencoded::Serializer::new(String::new());
+        let mut has_query = false;
+        let mut headers = Vec::new();
+        let mut cookies = Vec::new();
+
+        for (index, parameter) in self.parameters.iter().enumerate() {
+            let value = values.get(index).map(|value| value.trim()).unwrap_or("");
+            if value.is_empty() {
+                if parameter.required {
+                    return Err(AppError::ValidationError(format!(
+                        "Missing required {} parameter '{}'",
+                        parameter.location.as_str(),
+                        parameter.name
+                    )));
+                }
+                continue;
+            }
+            match parameter.location {
+                ParameterLocation::Path => {
+                    path = path.replace(
+                        &format!("{{{}}}", parameter.name),
+                        &encode_path_segment(value),
+                    );
+                }
+                ParameterLocation::Query => {
+                    query.append_pair(&parameter.name, value);
+                    has_query = true;
+                }
+                ParameterLocation::Header => headers.push((parameter.name.clone(), value)),
+                ParameterLocation::Cookie => cookies.push(format!("{}={value}", parameter.name)),
+            }
+        }
+
+        let mut url = format!("{}{path}", base_url.trim().trim_end_matches('/'));
+        if has_query {
+            url.push(if url.contains('?') { '&' } else { '?' });
+            url.push_str(&query.finish());
+        }
+        let mut request = Request::new(self.method, url);
+        for (key, value) in headers {
+            request.add_header(key, value);
+        }
+        if !cookies.is_empty() {
+            request.add_header("Cookie", cookies.join("; "));
+        }
+
+        match (
+            body.map(str::trim).filter(|body| !body.is_empty()),
+            &self.request_body,
+        ) {
+            (Some(body), request_body) => {
+                if let Some(request_body) = request_body {
+                    request.add_header("Content-Type", &request_body.content_type);
+                }
+                request.set_body(body);
+            }
+            (None, Some(request_body)) if request_body.required => {
+                return Err(AppError::ValidationError(
+                    "This operation requires a request body".to_string(),
+                ));
+            }
+            (None, _) => {}
+        }
+        Ok(request)
+    }
+}
+
+/// A parsed API description
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApiSpec {
+    pub title: String,
+    pub version: String,
+    /// Base URLs, with server variables replaced by their defaults
+    pub servers: Vec<String>,
+    pub operations: Vec<Operation>,
+    // The whole document, for `$ref` lookups
+    document: Value,
+}
+
+impl ApiSpec {
+    /// Parse a JSON or YAML document
+    pub fn parse(text: &str) -> Result<Self, AppError> {
+        let document: Value = if text.trim_start().starts_with('{') {
+            serde_json::from_str(text)
+                .map_err(|e| AppError::ParseError(format!("Invalid OpenAPI JSON: {e}")))?
+        } else {
+            serde_yaml::from_str(text)
+                .map_err(|e| AppError::ParseError(format!("Invalid OpenAPI YAML: {e}")))?
+        };
+        Self::from_document(document)
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AppError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    fn from_document(document: Value) -> Result<Self, AppError> {
+        let swagger = document.get("swagger").and_then(Value::as_str).is_some();
+        if !swagger && document.get("openapi").and_then(Value::as_str).is_none() {
+            return Err(AppError::ParseError(
+                "Not an OpenAPI document (no `openapi` or `swagger` version)".to_string(),
+            ));
+        }
+        let info = document.get("info");
+        let text = |key: &str| {
+            info.and_then(|info| info.get(key))
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string()
+        };
+        let mut spec = Self {
+            title: text("title"),
+            version: text("version"),
+            servers: if swagger {
+                swagger_servers(&document)
+            } else {
+                openapi_servers(&document)
+            },
+            operations: Vec::new(),
+            document: Value::Null,
+        };
+
+        let mut operations = Vec::new();
+        if let Some(paths) = document.get("paths").and_then(Value::as_object) {
+            for (path, item) in paths {
+                let item = resolve_in(&document, item);
+                let shared = item.get("parameters");
+                for (key, method) in OPERATION_METHODS {
+                    if let Some(operation) = item.get(key) {
+                        operations.push(parse_operation(
+                            &document, swagger, method, path, shared, operation,
+                        ));
+                    }
+                }
+            }
+        }
+        spec.operations = operations;
+        spec.document = document;
+        Ok(spec)
+    }
+
+    /// Operation indices grouped by tag, tags in the order the document
+    /// declares them and then in order of first use
+    pub fn tags(&self) -> Vec<(String, Vec<usize>)> {
+        let mut groups: Vec<(String, Vec<usize>)> = self
+            .document
+            .get("tags")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag.get("name").and_then(Value::as_str))
+            .map(|name| (name.to_string(), Vec::new()))
+            .collect();
+        for (index, operation) in self.operations.iter().enumerate() {
+            match groups.iter_mut().find(|(tag, _)| *tag == operation.tag) {
+                Some((_, indices)) => indices.push(index),
+                None => groups.push((operation.tag.clone(), vec![index])),
+            }
+        }
+        groups.retain(|(_, indices)| !indices.is_empty());
+        groups
+    }
+
+    /// Operation a request was made to, matched by method and path; literal
+    /// path segments win over template parameters (`/users/me` before
+    /// `/users/{id}`)
+    pub fn find_operation(&self, method: HttpMethod, url: &str) -> Option<&Operation> {
+        let without_scheme = url.split_once("://").map_or(url, |(_, rest)| rest);
+        let path = without_scheme
+            .find('/')
+            .map_or("", |index| &without_scheme[index..]);
+        let path = path.split(['?', '#']).next().unwrap_or_default();
+        self.operations
+            .iter()
+            .filter(|operation| operation.method == method && operation.matches_path(path))
+            .max_by_key(|operation| {
+                let literal = operation
+                    .path
+                    .split('/')
+                    .filter(|part| !part.is_empty() && !part.starts_with('{'))
+                    .count();
+                (literal, std::cmp::Reverse(operation.path.len()))
+            })
+    }
+
+    /// Follow a local `$ref` (`#/components/schemas/User`)
+    pub fn resolve<'a>(&'a self, value: &'a Value) -> &'a Value {
+        resolve_in(&self.document, value)
+    }
+
+    /// Example value for a schema: its `example` / `default` / first `enum`
+    /// value when given, otherwise a placeholder of the right shape
+    pub fn example(&self, schema: &Value) -> Value {
+        self.example_at(schema, 0)
+    }
+
+    fn example_at(&self, schema: &Value, depth: usize) -> Value {
+        let schema = self.resolve(schema);
+        if depth > MAX_SCHEMA_DEPTH {
+            return Value::Null;
+        }
+        if let Some(value) = explicit_example(schema) {
+            return value;
+        }
+        if let Some(all_of) = schema.get("allOf").and_then(Value::as_array) {
+            let mut merged = serde_json::Map::new();
+            for part in all_of {
+                if let Value::Object(fields) = self.example_at(part, depth + 1) {
+                    merged.extend(fields);
+                }
+            }
+            return Value::Object(merged);
+        }
+        if let Some(first) = ["oneOf", "anyOf"]
+            .iter()
+            .find_map(|key| schema.get(key).and_then(Value::as_array)?.first())
+        {
+            return self.example_at(first, depth + 1);
+        }
+
+        match schema_type(schema) {
+            Some("object") | None if schema.get("properties").is_some() => {
+                let mut object = serde_json::Map::new();
+                if let Some(properties) = schema.get("properties").and_then(Value::as_object) {
+                    for (name, property) in properties {
+                        object.insert(name.clone(), self.example_at(property, depth + 1));
+                    }
+                }
+                Value::Object(object)
+            }
+            Some("object") => Value::Object(serde_json::Map::new()),
+            Some("array") => Value::Array(
+                schema
+                    .get("items")
+                    .map(|items| vec![self.example_at(items, depth + 1)])
+                    .unwrap_or_default(),
+            ),
+            Some("integer") => {
+                Value::from(schema.get("minimum").and_then(Value::as_i64).unwrap_or(0))
+            }
+            Some("number") => {
+                Value::from(schema.get("minimum").and_then(Value::as_f64).unwrap_or(0.0))
+            }
+            Some("boolean") => Value::Bool(false),
+            Some("string") => Value::String(
+                match schema.get("format").and_then(Value::as_str) {
+                    Some("date-time") => "2024-01-01T00:00:00Z",
+                    Some("date") => "2024-01-01",
+                    Some("email") => "user@example.com",
+                    Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                    Some("uri") | Some("url") => "https://example.com",
+                    _ => "string",
+                }
+                .to_string(),
+            ),
+            _ => Value::Null,
+        }
+    }
+}
+
+fn parse_operation(
+    document: &Value,
+    swagger: bool,
+    method: HttpMethod,
+    path: &str,
+    shared: Option<&Value>,
+    operation: &Value,
+) -> Operation {
+    let text = |key: &str| {
+        operation
+            .get(key)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+    };
+
+    // Operation parameters override path-level ones with the same name and location
+    let mut parameters: Vec<Parameter> = Vec::new();
+    let mut swagger_body = None;
+    let declared = shared
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .chain(
+            operation
+                .get("parameters")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten(),
+        );
+    for parameter in declared {
+        let parameter = resolve_in(document, parameter);
+        let location = parameter.get("in").and_then(Value::as_str).unwrap_or("");
+        if swagger && location == "body" {
+            swagger_body = Some(RequestBody {
+                content_type: swagger_consumes(document, operation),
+                required: parameter.get("required").and_then(Value::as_bool) == Some(true),
+                schema: parameter.get("schema").cloned().unwrap_or(Value::Null),
+            });
+            continue;
+        }
+        let (Some(name), Some(location)) = (
+            parameter.get("name").and_then(Value::as_str),
+            ParameterLocation::parse(location),
+        ) else {
+            continue;
+        };
+        let parsed = Parameter {
+            name: name.to_string(),
+            location,
+            required: location == ParameterLocation::Path
+                || parameter.get("required").and_then(Value::as_bool) == Some(true),
+            description: parameter
+                .get("description")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            // Swagger 2 puts the type on the parameter itself
+            schema: resolve_in(document, parameter.get("schema").unwrap_or(parameter)).clone(),
+        };
+        match parameters
+            .iter_mut()
+            .find(|existing| existing.name == parsed.name && existing.location == location)
+        {
+            Some(existing) => *existing = parsed,
+            None => parameters.push(parsed),
+        }
+    }
+
+    let request_body = if swagger {
+        swagger_body
+    } else {
+        operation
+            .get("requestBody")
+            .map(|body| resolve_in(document, body))
+            .and_then(|body| {
+                let content = body.get("content").and_then(Value::as_object)?;
+                let (content_type, media) = content
+                    .iter()
+                    .find(|(content_type, _)| content_type.contains("json"))
+                    .or_else(|| content.iter().next())?;
+                Some(RequestBody {
+                    content_type: content_type.clone(),
+                    required: body.get("required").and_then(Value::as_bool) == Some(true),
+                    schema: media.get("schema").cloned().unwrap_or(Value::Null),
+                })
+            })
+    };
+
+    let responses = operation
+        .get("responses")
+        .and_then(Value::as_object)
+        .into_iter()
+        .flatten()
+        .map(|(status, response)| {
+            let response = resolve_in(document, response);
+            let schema = if swagger {
+                response.get("schema").cloned()
+            } else {
+                response
+                    .get("content")
+                    .and_then(Value::as_object)
+                    .and_then(|content| {
+                        content
+                            .iter()
+                            .find(|(content_type, _)| content_type.contains("json"))
+                    })
+                    .and_then(|(_, media)| media.get("schema").cloned())
+            };
+            ResponseSpec {
+                status: status.clone(),
+                schema,
+            }
+        })
+        .collect();
+
+    Operation {
+        method,
+        path: path.to_string(),
+        operation_id: text("operationId"),
+        summary: text("summary"),
+        description: text("description"),
+        tag: operation
+            .get("tags")
+            .and_then(Value::as_array)
+            .and_then(|tags| tags.first())
+            .and_then(Value::as_str)
+            .unwrap_or("default")
+            .to_string(),
+        deprecated: operation.get("deprecated").and_then(Value::as_bool) == Some(true),
+        parameters,
+        request_body,
+        responses,
+    }
+}
+
+fn resolve_in<'a>(document: &'a Value, mut value: &'a Value) -> &'a Value {
+    for _ in 0..MAX_SCHEMA_DEPTH {
+        let Some(pointer) = value
+            .get("$ref")
+            .and_then(Value::as_str)
+            .and_then(|reference| reference.strip_prefix('#'))
+        else {
+            break;
+        };
+        match document.pointer(pointer) {
+            Some(target) => value = target,
+            None => break,
+        }
+    }
+    value
+}
+
+fn openapi_servers(document: &Value) -> Vec<String> {
+    document
+        .get("servers")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|server| {
+            let mut url = server.get("url")?.as_str()?.to_string();
+            if let Some(variables) = server.get("variables").and_then(Value::as_object) {
+                for (name, variable) in variables {
+                    if let Some(default) = variable.get("default").and_then(Value::as_str) {
+                        url = url.replace(&format!("{{{name}}}"), default);
+                    }
+                }
+            }
+            Some(url)
+        })
+        .collect()
+}
+
+fn swagger_servers(document: &Value) -> Vec<String> {
+    let Some(host) = document.get("host").and_then(Value::as_str) else {
+        return Vec::new();
+    };
+    let base_path = document
+        .get("basePath")
+        .and_then(Value::as_str)
+        .unwrap_or("");
+    let schemes: Vec<&str> = document
+        .get("schemes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let schemes = if schemes.is_empty() {
+        vec!["https"]
+    } else {
+        schemes
+    };
+    schemes
+        .into_iter()
+        .map(|scheme| format!("{scheme}://{host}{base_path}"))
+        .collect()
+}
+
+fn swagger_consumes(document: &Value, operation: &Value) -> String {
+    operation
+        .get("consumes")
+        .or_else(|| document.get("consumes"))
+        .and_then(Value::as_array)
+        .and_then(|types| types.first())
+        .and_then(Value::as_str)
+        .unwrap_or("application/json")
+        .to_string()
+}
+
+fn explicit_example(schema: &Value) -> Option<Value> {
+    schema
+        .get("example")
+        .or_else(|| schema.get("default"))
+        .or_else(|| schema.get("const"))
+        .or_else(|| schema.get("enum").and_then(Value::as_array)?.first())
+        .cloned()
+}
+
+/// `type` of a schema; OpenAPI 3.1 allows a list such as `["string", "null"]`
+pub(crate) fn schema_type(schema: &Value) -> Option<&str> {
+    match schema.get("type")? {
+        Value::String(name) => Some(name),
+        Value::Array(names) => names
+            .iter()
+            .filter_map(Value::as_str)
+            .find(|name| *name != "null"),
+        _ => None,
+    }
+}
+
+fn schema_type_label(schema: &Value) -> String {
+    let name = schema_type(schema).unwrap_or("any");
+    match schema.get("format").and_then(Value::as_str) {
+        Some(format) => format!("{name} ({format})"),
+        None => name.to_string(),
+    }
+}
+
+/// Percent-encode a path parameter value, keeping RFC 3986 unreserved characters
+fn encode_path_segment(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PETS: &str = r##"{
+        "openapi": "3.0.3",
+        "info": {"title": "Pets", "version": "1.2"},
+        "servers": [{"url": "https://{region}.pets.test/v1", "variables": {"region": {"default": "eu"}}}],
+        "tags": [{"name": "pets"}, {"name": "unused"}],
+        "paths": {
+            "/pets/{petId}": {
+                "parameters": [{"name": "petId", "in": "path", "schema": {"type": "integer"}}],
+                "get": {
+                    "tags": ["pets"],
+                    "summary": "Get a pet",
+                    "responses": {
+                        "200": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}},
+                        "4XX": {"description": "Client error"}
+                    },
+                    "parameters": [
+                        {"$ref": "#/components/parameters/Verbose"},
+                        {"name": "X-Trace", "in": "header", "schema": {"type": "string"}}
+                    ]
+                },
+                "put": {
+                    "tags": ["pets"],
+                    "operationId": "updatePet",
+                    "requestBody": {
+                        "required": true,
+                        "content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}
+                    }
+                }
+            },
+            "/health": {"get": {}}
+        },
+        "components": {
+            "parameters": {
+                "Verbose": {"name": "verbose", "in": "query", "schema": {"type": "boolean"}}
+            },
+            "schemas": {
+                "Pet": {
+                    "type": "object",
+                    "properties": {
+                        "name": {"type": "string", "example": "Rex"},
+                        "born": {"type": "string", "format": "date"},
+                        "kind": {"type": "string", "enum": ["dog", "cat"]},
+                        "tags": {"type": "array", "items": {"type": "string"}},
+                        "owner": {"$ref": "#/components/schemas/Pet"}
+                    }
+                }
+            }
+        }
+    }"##;
+
+    #[test]
+    fn test_parse_spec() {
+        let spec = ApiSpec::parse(PETS).unwrap();
+        assert_eq!(spec.title, "Pets");
+        assert_eq!(spec.servers, ["https://eu.pets.test/v1"]);
+        assert_eq!(spec.operations.len(), 3);
+
+        let get = spec
+            .operations
+            .iter()
+            .find(|operation| operation.method == HttpMethod::GET && operation.tag == "pets")
+            .unwrap();
+        assert_eq!(get.title(), "Get a pet");
+        let names: Vec<&str> = get.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, ["petId", "verbose", "X-Trace"]);
+        assert!(get.parameters[0].required);
+        assert_eq!(get.parameters[1].type_label(), "boolean");
+
+        let tags: Vec<(String, usize)> = spec
+            .tags()
+            .into_iter()
+            .map(|(tag, indices)| (tag, indices.len()))
+            .collect();
+        assert_eq!(tags, [("pets".to_string(), 2), ("default".to_string(), 1)]);
+
+        assert!(ApiSpec::parse(r#"{"info": {}}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_swagger_2() {
+        let spec = ApiSpec::parse(
+            r#"{
+                "swagger": "2.0",
+                "info": {"title": "Old", "version": "1"},
+                "host": "old.test",
+                "basePath": "/api",
+                "schemes": ["http"],
+                "paths": {"/items": {"post": {
+                    "parameters": [
+                        {"name": "item", "in": "body", "required": true, "schema": {"type": "object"}},
+                        {"name": "dry", "in": "query", "type": "boolean"}
+                    ]
+                }}}
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(spec.servers, ["http://old.test/api"]);
+        let operation = &spec.operations[0];
+        assert_eq!(operation.parameters.len(), 1);
+        assert_eq!(operation.parameters[0].type_label(), "boolean");
+        let body = operation.request_body.as_ref().unwrap();
+        assert_eq!(body.content_type, "application/json");
+        assert!(body.required);
+    }
+
+    #[test]
+    fn test_example_from_schema() {
+        let spec = ApiSpec::parse(PETS).unwrap();
+        let put = spec
+            .operations
+            .iter()
+            .find(|operation| operation.method == HttpMethod::PUT)
+            .unwrap();
+        let example = spec.example(&put.request_body.as_ref().unwrap().schema);
+        assert_eq!(example["name"], Value::from("Rex"));
+        assert_eq!(example["born"], Value::from("2024-01-01"));
+        assert_eq!(example["kind"], Value::from("dog"));
+        assert_eq!(example["tags"], Value::Array(vec![Value::from("string")]));
+        // The recursive `owner` stops at the depth limit
+        assert!(example["owner"]["owner"].is_object());
+    }
+
+    #[test]
+    fn test_build_request() {
+        let spec = ApiSpec::parse(PETS).unwrap();
+        let get = &spec.operations[1];
+        assert_eq!(get.path, "/pets/{petId}");
+
+        let values = ["7 a".to_string(), "true".to_string(), "abc".to_string()];
+        let request = get
+            .build_request("https://eu.pets.test/v1/", &values, None)
+            .unwrap();
+        assert_eq!(request.method, HttpMethod::GET);
+        assert_eq!(
+            request.url,
+            "https://eu.pets.test/v1/pets/7%20a?verbose=true"
+        );
+        assert_eq!(
+            request.headers,
+            [("X-Trace".to_string(), "abc".to_string())]
+        );
+
+        let error = get
+            .build_request("https://eu.pets.test/v1", &[], None)
+            .unwrap_err();
+        assert!(error.to_string().contains("petId"));
+
+        let put = &spec.operations[2];
+        assert!(put
+            .build_request("https://x.test", &["1".to_string()], None)
+            .is_err());
+        let request = put
+            .build_request(
+                "https://x.test",
+                &["1".to_string()],
+                Some(r#"{"name":"Rex"}"#),
+            )
+            .unwrap();
+        assert_eq!(request.body.as_deref(), Some(r#"{"name":"Rex"}"#));
+        assert_eq!(request.headers[0].1, "application/json");
+    }
+
+    #[test]
+    fn test_find_operation() {
+        let spec = ApiSpec::parse(
+            r#"{
+                "openapi": "3.0.0",
+                "info": {"title": "Users", "version": "1"},
+                "paths": {
+                    "/users/{id}": {"get": {"responses": {"default": {"description": "Any"}}}},
+                    "/users/me": {"get": {}},
+                    "/users": {"post": {}}
+                }
+            }"#,
+        )
+        .unwrap();
+        let find = |method, url| {
+            spec.find_operation(method, url)
+                .map(|operation| operation.path.as_str())
+        };
+        assert_eq!(
+            find(HttpMethod::GET, "https://x.test/v1/users/7?full=1"),
+            Some("/users/{id}")
+        );
+        assert_eq!(
+            find(HttpMethod::GET, "https://x.test/v1/users/me"),
+            Some("/users/me")
+        );
+        assert_eq!(
+            find(HttpMethod::POST, "https://x.test/users"),
+            Some("/users")
+        );
+        assert_eq!(find(HttpMethod::DELETE, "https://x.test/users/7"), None);
+        assert_eq!(find(HttpMethod::GET, "https://x.test/teams/7"), None);
+
+        let pets = ApiSpec::parse(PETS).unwrap();
+        let get = &pets.operations[1];
+        assert!(get.response_for(200).unwrap().schema.is_some());
+        assert_eq!(get.response_for(404).unwrap().status, "4XX");
+        assert!(get.response_for(500).is_none());
+        let any = spec.find_operation(HttpMethod::GET, "/users/1").unwrap();
+        assert_eq!(any.response_for(503).unwrap().status, "default");
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        let spec = ApiSpec::parse(
+            "openapi: 3.1.0\ninfo:\n  title: Tiny\n  version: '1'\npaths:\n  /ping:\n    get:\n      summary: Ping\n",
+        )
+        .unwrap();
+        assert_eq!(spec.title, "Tiny");
+        assert_eq!(spec.operations[0].title(), "Ping");
+        assert!(spec.servers.is_empty());
+    }
+}
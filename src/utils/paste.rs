@@ -0,0 +1,89 @@
+//! Guarding the body editor against large clipboard pastes. Inserting and
+//! laying out megabytes of text in one go blocks the UI, so a large paste is
+//! inserted in chunks while its progress is shown, and a very large one is
+//! offered as an attached body instead: the text is kept in memory and sent
+//! as the body without being laid out in the editor.
+
+use std::ops::Range;
+
+/// Pastes from this size on are inserted in chunks
+pub const LARGE_PASTE_BYTES: usize = 256 * 1024;
+
+/// Pastes from this size on are offered as an attached body first
+pub const ATTACHED_PASTE_BYTES: usize = 2 * 1024 * 1024;
+
+// A large paste is inserted in about this many chunks, none smaller than
+// `MIN_CHUNK_BYTES`
+const PASTE_CHUNKS: usize = 16;
+const MIN_CHUNK_BYTES: usize = 64 * 1024;
+
+/// How a paste is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasteSize {
+    /// Inserted right away
+    Small,
+    /// Inserted in chunks with progress shown
+    Large,
+    /// Offered as an attached body, or pasted in chunks anyway
+    Huge,
+}
+
+impl PasteSize {
+    pub fn of(text: &str) -> Self {
+        match text.len() {
+            len if len >= ATTACHED_PASTE_BYTES => PasteSize::Huge,
+            len if len >= LARGE_PASTE_BYTES => PasteSize::Large,
+            _ => PasteSize::Small,
+        }
+    }
+}
+
+/// Byte ranges `text` is inserted in, one after another; each ends on a
+/// character boundary
+pub fn chunk_ranges(text: &str) -> Vec<Range<usize>> {
+    let size = (text.len() / PASTE_CHUNKS).max(MIN_CHUNK_BYTES);
+    let mut ranges = Vec::new();
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = (start + size).min(text.len());
+        while !text.is_char_boundary(end) {
+            end += 1;
+        }
+        ranges.push(start..end);
+        start = end;
+    }
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paste_size() {
+        assert_eq!(PasteSize::of("{}"), PasteSize::Small);
+        assert_eq!(
+            PasteSize::of(&"a".repeat(LARGE_PASTE_BYTES)),
+            PasteSize::Large
+        );
+        assert_eq!(
+            PasteSize::of(&"a".repeat(ATTACHED_PASTE_BYTES)),
+            PasteSize::Huge
+        );
+    }
+
+    #[test]
+    fn test_chunks_cover_text_on_char_boundaries() {
+        let text = "é".repeat(300_000);
+        let ranges = chunk_ranges(&text);
+        assert!(ranges.len() > 1);
+        assert_eq!(ranges.first().unwrap().start, 0);
+        assert_eq!(ranges.last().unwrap().end, text.len());
+        for pair in ranges.windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        let joined: String = ranges.iter().map(|range| &text[range.clone()]).collect();
+        assert_eq!(joined, text);
+        assert!(chunk_ranges("").is_empty());
+    }
+}
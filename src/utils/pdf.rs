@@ -0,0 +1,307 @@
+//! A small PDF writer for plain-text reports, such as an exported response:
+//! monospaced text on A4 pages using the standard Courier fonts, so no font
+//! has to be embedded. Those fonts only cover WinAnsi (Western European)
+//! text, so a document with other characters is refused rather than written
+//! with them replaced.
+
+use crate::errors::AppError;
+
+// A4 in points, with the text area inside the margins
+const PAGE_WIDTH: usize = 595;
+const PAGE_HEIGHT: usize = 842;
+const MARGIN: usize = 48;
+const FONT_SIZE: usize = 9;
+const LINE_HEIGHT: usize = 11;
+const FOOTER_FONT_SIZE: usize = 7;
+
+/// Characters that fit on a line: Courier glyphs are 0.6 em wide
+pub const LINE_CHARS: usize = (PAGE_WIDTH - 2 * MARGIN) * 10 / (FONT_SIZE * 6);
+/// Lines that fit on a page, leaving room for the page number
+pub const PAGE_LINES: usize = (PAGE_HEIGHT - 2 * MARGIN) / LINE_HEIGHT - 1;
+
+/// Spaces a tab is expanded to
+const TAB_WIDTH: usize = 4;
+
+/// A plain-text document laid out on A4 pages; long lines wrap and headings
+/// are set in bold
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextDocument {
+    title: String,
+    // (text, bold) of every laid out line
+    lines: Vec<(String, bool)>,
+}
+
+impl TextDocument {
+    /// A document whose title is printed first and in each page footer
+    pub fn new(title: impl Into<String>) -> Self {
+        let title = title.into();
+        let mut document = Self {
+            title: title.clone(),
+            lines: Vec::new(),
+        };
+        document.heading(&title);
+        document
+    }
+
+    /// A bold line, after a blank line unless the page is empty
+    pub fn heading(&mut self, text: &str) {
+        if !self.lines.is_empty() {
+            self.blank();
+        }
+        self.push(text, true);
+    }
+
+    /// Text in the regular font, one line per line of `text`
+    pub fn text(&mut self, text: &str) {
+        for line in text.lines() {
+            self.push(line, false);
+        }
+    }
+
+    pub fn blank(&mut self) {
+        self.lines.push((String::new(), false));
+    }
+
+    /// Number of pages the document takes
+    pub fn page_count(&self) -> usize {
+        self.lines.len().div_ceil(PAGE_LINES).max(1)
+    }
+
+    fn push(&mut self, line: &str, bold: bool) {
+        let line = line.replace('\t', &" ".repeat(TAB_WIDTH));
+        let chars: Vec<char> = line.trim_end().chars().collect();
+        if chars.is_empty() {
+            self.lines.push((String::new(), bold));
+            return;
+        }
+        for chunk in chars.chunks(LINE_CHARS) {
+            self.lines.push((chunk.iter().collect(), bold));
+        }
+    }
+
+    /// The document as the bytes of a PDF file, an error naming the first
+    /// character the standard fonts can't show if there is one
+    pub fn to_pdf(&self) -> Result<Vec<u8>, AppError> {
+        let unsupported = std::iter::once(self.title.as_str())
+            .chain(self.lines.iter().map(|(text, _)| text.as_str()))
+            .flat_map(str::chars)
+            .find(|c| win_ansi(*c).is_none());
+        if let Some(c) = unsupported {
+            return Err(AppError::ValidationError(format!(
+                "\"{c}\" (U+{:04X}) can't be written to a PDF - its fonts only cover Western \
+                 European text",
+                c as u32
+            )));
+        }
+
+        let page_count = self.page_count();
+        // Objects: 1 catalog, 2 page tree, 3-4 fonts, 5 info, then a page
+        // and its content stream for every page
+        let first_page = 6;
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {page_count} >>",
+                (0..page_count)
+                    .map(|page| format!("{} 0 R", first_page + page * 2))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Courier-Bold /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+            format!(
+                "<< /Title ({}) /Producer (Postman GPUI) >>",
+                pdf_string(&self.title)
+            ),
+        ];
+
+        let pages: Vec<&[(String, bool)]> = if self.lines.is_empty() {
+            vec![&[]]
+        } else {
+            self.lines.chunks(PAGE_LINES).collect()
+        };
+        for (index, lines) in pages.iter().enumerate() {
+            let content = self.page_content(lines, index + 1, page_count);
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                first_page + index * 2 + 1
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{content}\nendstream",
+                content.len()
+            ));
+        }
+
+        let mut pdf = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (index, object) in objects.iter().enumerate() {
+            offsets.push(pdf.len());
+            pdf.push_str(&format!("{} 0 obj\n{object}\nendobj\n", index + 1));
+        }
+        let xref = pdf.len();
+        pdf.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            pdf.push_str(&format!("{offset:010} 00000 n \n"));
+        }
+        pdf.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R /Info 5 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+            objects.len() + 1
+        ));
+        Ok(pdf.into_bytes())
+    }
+
+    // Content stream of one page: its lines from the top-left corner of the
+    // text area, then the footer
+    fn page_content(&self, lines: &[(String, bool)], page: usize, page_count: usize) -> String {
+        let top = PAGE_HEIGHT - MARGIN - FONT_SIZE;
+        let mut content = format!("BT\n{LINE_HEIGHT} TL\n{MARGIN} {top} Td\n");
+        let mut font = None;
+        for (text, bold) in lines {
+            let wanted = if *bold { "F2" } else { "F1" };
+            if font != Some(wanted) {
+                content.push_str(&format!("/{wanted} {FONT_SIZE} Tf\n"));
+                font = Some(wanted);
+            }
+            content.push_str(&format!("({}) Tj T*\n", pdf_string(text)));
+        }
+        content.push_str("ET\n");
+
+        let footer = format!("{} - page {page} of {page_count}", self.title);
+        let footer: String = footer.chars().take(LINE_CHARS * 9 / 7).collect();
+        content.push_str(&format!(
+            "BT\n0.5 g\n/F1 {FOOTER_FONT_SIZE} Tf\n{MARGIN} {} Td\n({}) Tj\nET",
+            MARGIN / 2,
+            pdf_string(&footer)
+        ));
+        content
+    }
+}
+
+/// Characters WinAnsi encodes in 0x80-0x9f, where Latin-1 has control
+/// characters
+const WIN_ANSI_EXTRA: [(char, u8); 27] = [
+    ('€', 0x80),
+    ('‚', 0x82),
+    ('ƒ', 0x83),
+    ('„', 0x84),
+    ('…', 0x85),
+    ('†', 0x86),
+    ('‡', 0x87),
+    ('ˆ', 0x88),
+    ('‰', 0x89),
+    ('Š', 0x8a),
+    ('‹', 0x8b),
+    ('Œ', 0x8c),
+    ('Ž', 0x8e),
+    ('‘', 0x91),
+    ('’', 0x92),
+    ('“', 0x93),
+    ('”', 0x94),
+    ('•', 0x95),
+    ('–', 0x96),
+    ('—', 0x97),
+    ('˜', 0x98),
+    ('™', 0x99),
+    ('š', 0x9a),
+    ('›', 0x9b),
+    ('œ', 0x9c),
+    ('ž', 0x9e),
+    ('Ÿ', 0x9f),
+];
+
+/// Byte of `c` in WinAnsi encoding, the one the standard fonts use; `None`
+/// for characters they can't show
+fn win_ansi(c: char) -> Option<u8> {
+    match c {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => Some(c as u8),
+        _ => WIN_ANSI_EXTRA
+            .iter()
+            .find(|(extra, _)| *extra == c)
+            .map(|(_, byte)| *byte),
+    }
+}
+
+/// Text as the inside of a PDF string literal in WinAnsi encoding: `(`, `)`
+/// and `\` are escaped and bytes above ASCII are written as octal escapes.
+/// `to_pdf` refuses text with other characters before it gets here
+fn pdf_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match (c, win_ansi(c)) {
+            ('(' | ')' | '\\', _) => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            (' '..='~', _) => escaped.push(c),
+            (_, Some(byte)) => escaped.push_str(&format!("\\{byte:03o}")),
+            (_, None) => escaped.push('?'),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdf_structure() {
+        let mut document = TextDocument::new("GET https://api.example.com/users");
+        document.heading("Status");
+        document.text("200 OK");
+        let pdf = String::from_utf8(document.to_pdf().unwrap()).unwrap();
+
+        assert!(pdf.starts_with("%PDF-1.4\n"));
+        assert!(pdf.ends_with("%%EOF\n"));
+        assert!(pdf.contains("/F2 9 Tf\n(Status) Tj T*\n/F1 9 Tf\n(200 OK) Tj T*"));
+        assert!(pdf.contains("page 1 of 1"));
+
+        // startxref points at the cross-reference table, whose entries
+        // point at the objects
+        let xref: usize = pdf
+            .rsplit("startxref\n")
+            .next()
+            .and_then(|tail| tail.lines().next())
+            .and_then(|offset| offset.parse().ok())
+            .unwrap();
+        assert!(pdf[xref..].starts_with("xref\n"));
+        let first_object: usize = pdf[xref..].lines().nth(3).unwrap()[..10].parse().unwrap();
+        assert!(pdf[first_object..].starts_with("1 0 obj"));
+    }
+
+    #[test]
+    fn test_long_text_wraps_and_paginates() {
+        let mut document = TextDocument::new("Report");
+        document.text(&"x".repeat(LINE_CHARS * 2 + 1));
+        assert_eq!(document.lines.len(), 4);
+
+        document.text(&"line\n".repeat(PAGE_LINES));
+        assert_eq!(document.page_count(), 2);
+        let pdf = String::from_utf8(document.to_pdf().unwrap()).unwrap();
+        assert!(pdf.contains("/Count 2"));
+        assert!(pdf.contains("page 2 of 2"));
+    }
+
+    #[test]
+    fn test_pdf_string_escapes() {
+        assert_eq!(pdf_string(r"f(x) \ y"), r"f\(x\) \\ y");
+        assert_eq!(pdf_string("café – 5 €"), "caf\\351 \\226 5 \\200");
+    }
+
+    #[test]
+    fn test_text_the_fonts_cant_show_is_refused() {
+        let mut document = TextDocument::new("Report");
+        document.text("{\"name\": \"café\"}");
+        assert!(document.to_pdf().is_ok());
+
+        document.text("{\"name\": \"東京\"}");
+        let error = document.to_pdf().unwrap_err().to_string();
+        assert!(error.contains("U+6771"), "{error}");
+    }
+}
@@ -0,0 +1,1196 @@
+//! Protocol Buffers decoding, with or without a schema.
+//!
+//! Without a schema fields are keyed by number and length-delimited values are
+//! guessed (text, nested message or bytes), like `protoc --decode_raw`. With a
+//! descriptor set (`protoc --include_imports --descriptor_set_out=api.desc`)
+//! fields get their names, types and enum value names, and JSON can be
+//! encoded back to a message (gRPC request bodies).
+
+use crate::errors::AppError;
+use crate::utils::base64;
+use crate::utils::binary::{too_deep, ByteReader, DataValue, MAX_DEPTH};
+use serde_json::Value;
+use std::collections::HashMap;
+
+// Field types from `FieldDescriptorProto.Type`
+const TYPE_DOUBLE: u64 = 1;
+const TYPE_FLOAT: u64 = 2;
+const TYPE_INT64: u64 = 3;
+const TYPE_UINT64: u64 = 4;
+const TYPE_INT32: u64 = 5;
+const TYPE_FIXED64: u64 = 6;
+const TYPE_FIXED32: u64 = 7;
+const TYPE_BOOL: u64 = 8;
+const TYPE_STRING: u64 = 9;
+const TYPE_GROUP: u64 = 10;
+const TYPE_MESSAGE: u64 = 11;
+const TYPE_BYTES: u64 = 12;
+const TYPE_UINT32: u64 = 13;
+const TYPE_ENUM: u64 = 14;
+const TYPE_SFIXED32: u64 = 15;
+const TYPE_SFIXED64: u64 = 16;
+const TYPE_SINT32: u64 = 17;
+const TYPE_SINT64: u64 = 18;
+
+/// `FieldDescriptorProto.Label.LABEL_REPEATED`
+const LABEL_REPEATED: u64 = 3;
+
+/// A field as it appears on the wire
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum WireValue<'a> {
+    Varint(u64),
+    Fixed64(u64),
+    Bytes(&'a [u8]),
+    /// Contents of a (deprecated) group, without the end tag
+    Group(&'a [u8]),
+    EndGroup,
+    Fixed32(u32),
+}
+
+/// Decode a message without a schema; fields are keyed by their number
+pub fn decode_raw(data: &[u8]) -> Result<DataValue, AppError> {
+    decode_raw_message(data, 0)
+}
+
+/// Message types loaded from a `FileDescriptorSet`
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorPool {
+    /// Keyed by fully qualified name without the leading dot
+    messages: HashMap<String, MessageDescriptor>,
+    enums: HashMap<String, HashMap<i32, String>>,
+    methods: Vec<MethodDescriptor>,
+}
+
+/// An RPC method of a service in the descriptor set
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodDescriptor {
+    /// gRPC request path, `/package.Service/Method`
+    pub path: String,
+    /// Message types, fully qualified without the leading dot
+    pub input_type: String,
+    pub output_type: String,
+    pub client_streaming: bool,
+    pub server_streaming: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct MessageDescriptor {
+    fields: Vec<FieldDescriptor>,
+    /// Synthetic entry type of a `map<K, V>` field
+    map_entry: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+struct FieldDescriptor {
+    name: String,
+    number: u32,
+    kind: u64,
+    repeated: bool,
+    /// Message or enum type, fully qualified without the leading dot
+    type_name: String,
+}
+
+impl DescriptorPool {
+    /// Load the message types of a serialized `google.protobuf.FileDescriptorSet`
+    pub fn from_descriptor_set(data: &[u8]) -> Result<Self, AppError> {
+        let mut pool = Self::default();
+        for (number, value) in fields(data)? {
+            if number == 1 {
+                pool.add_file(bytes(value)?)?;
+            }
+        }
+        if pool.messages.is_empty() {
+            return Err(AppError::ParseError(
+                "No message types found in the descriptor set".to_string(),
+            ));
+        }
+        Ok(pool)
+    }
+
+    /// Fully qualified names of all message types, sorted
+    pub fn message_names(&self) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .messages
+            .iter()
+            .filter(|(_, message)| !message.map_entry)
+            .map(|(name, _)| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Full name of a message type given its full name (leading dot optional)
+    /// or an unambiguous short name
+    pub fn resolve(&self, name: &str) -> Option<&str> {
+        let name = name.trim().trim_start_matches('.');
+        if let Some((full, _)) = self.messages.get_key_value(name) {
+            return Some(full);
+        }
+        let suffix = format!(".{name}");
+        let mut matches = self.messages.keys().filter(|full| full.ends_with(&suffix));
+        match (matches.next(), matches.next()) {
+            (Some(full), None) => Some(full),
+            _ => None,
+        }
+    }
+
+    /// Service methods, sorted by path
+    pub fn methods(&self) -> &[MethodDescriptor] {
+        &self.methods
+    }
+
+    /// Look up a method by its path (`/package.Service/Method`, leading slash
+    /// optional), `package.Service.Method`, or an unambiguous `Service/Method`
+    pub fn method(&self, name: &str) -> Option<&MethodDescriptor> {
+        let name = name.trim().trim_start_matches('/');
+        let dotted = |method: &MethodDescriptor| method.path[1..].replacen('/', ".", 1);
+        if let Some(method) = self
+            .methods
+            .iter()
+            .find(|method| method.path[1..] == *name || dotted(method) == name)
+        {
+            return Some(method);
+        }
+        let mut matches = self
+            .methods
+            .iter()
+            .filter(|method| method.path.ends_with(&format!(".{name}")));
+        match (matches.next(), matches.next()) {
+            (Some(method), None) => Some(method),
+            _ => None,
+        }
+    }
+
+    /// Decode `data` as the message type `message`
+    pub fn decode(&self, message: &str, data: &[u8]) -> Result<DataValue, AppError> {
+        let full = self.resolve(message).ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "Unknown or ambiguous message type '{message}' in the descriptor set"
+            ))
+        })?;
+        self.decode_message(full, data, 0)
+    }
+
+    /// Encode a JSON object as the message type `message`. Keys are the field
+    /// names from the .proto file or their lowerCamelCase JSON names; 64-bit
+    /// integers may be strings, enums names or numbers, bytes base64.
+    pub fn encode(&self, message: &str, value: &Value) -> Result<Vec<u8>, AppError> {
+        let full = self.resolve(message).ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "Unknown or ambiguous message type '{message}' in the descriptor set"
+            ))
+        })?;
+        let mut out = Vec::new();
+        self.encode_message(full, value, &mut out, 0)?;
+        Ok(out)
+    }
+
+    fn add_file(&mut self, data: &[u8]) -> Result<(), AppError> {
+        let mut package = String::new();
+        let mut messages = Vec::new();
+        let mut enums = Vec::new();
+        let mut services = Vec::new();
+        for (number, value) in fields(data)? {
+            match number {
+                2 => package = string(value)?,
+                4 => messages.push(bytes(value)?),
+                5 => enums.push(bytes(value)?),
+                6 => services.push(bytes(value)?),
+                _ => {}
+            }
+        }
+        for message in messages {
+            self.add_message(&package, message, 0)?;
+        }
+        for enum_type in enums {
+            self.add_enum(&package, enum_type)?;
+        }
+        for service in services {
+            self.add_service(&package, service)?;
+        }
+        self.methods.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(())
+    }
+
+    fn add_service(&mut self, package: &str, data: &[u8]) -> Result<(), AppError> {
+        let mut name = String::new();
+        let mut methods = Vec::new();
+        for (number, value) in fields(data)? {
+            match number {
+                1 => name = string(value)?,
+                2 => methods.push(bytes(value)?),
+                _ => {}
+            }
+        }
+        let service = qualify(package, &name);
+        for data in methods {
+            let mut method = MethodDescriptor {
+                path: String::new(),
+                input_type: String::new(),
+                output_type: String::new(),
+                client_streaming: false,
+                server_streaming: false,
+            };
+            for (number, value) in fields(data)? {
+                match (number, value) {
+                    (1, value) => method.path = format!("/{service}/{}", string(value)?),
+                    (2, value) => {
+                        method.input_type = string(value)?.trim_start_matches('.').to_string()
+                    }
+                    (3, value) => {
+                        method.output_type = string(value)?.trim_start_matches('.').to_string()
+                    }
+                    (5, WireValue::Varint(flag)) => method.client_streaming = flag != 0,
+                    (6, WireValue::Varint(flag)) => method.server_streaming = flag != 0,
+                    _ => {}
+                }
+            }
+            self.methods.push(method);
+        }
+        Ok(())
+    }
+
+    fn add_message(&mut self, scope: &str, data: &[u8], depth: usize) -> Result<(), AppError> {
+        if depth > MAX_DEPTH {
+            return Err(too_deep());
+        }
+        let mut name = String::new();
+        let mut descriptor = MessageDescriptor::default();
+        let mut nested = Vec::new();
+        let mut enums = Vec::new();
+        for (number, value) in fields(data)? {
+            match number {
+                1 => name = string(value)?,
+                2 => descriptor.fields.push(parse_field(bytes(value)?)?),
+                3 => nested.push(bytes(value)?),
+                4 => enums.push(bytes(value)?),
+                // MessageOptions.map_entry
+                7 => {
+                    descriptor.map_entry = fields(bytes(value)?)?
+                        .into_iter()
+                        .any(|(number, value)| number == 7 && value == WireValue::Varint(1));
+                }
+                _ => {}
+            }
+        }
+        let full = qualify(scope, &name);
+        for message in nested {
+            self.add_message(&full, message, depth + 1)?;
+        }
+        for enum_type in enums {
+            self.add_enum(&full, enum_type)?;
+        }
+        self.messages.insert(full, descriptor);
+        Ok(())
+    }
+
+    fn add_enum(&mut self, scope: &str, data: &[u8]) -> Result<(), AppError> {
+        let mut name = String::new();
+        let mut values = HashMap::new();
+        for (number, value) in fields(data)? {
+            match number {
+                1 => name = string(value)?,
+                2 => {
+                    let mut value_name = String::new();
+                    let mut value_number = 0;
+                    for (number, value) in fields(bytes(value)?)? {
+                        match (number, value) {
+                            (1, value) => value_name = string(value)?,
+                            (2, WireValue::Varint(value)) => value_number = value as i32,
+                            _ => {}
+                        }
+                    }
+                    values.insert(value_number, value_name);
+                }
+                _ => {}
+            }
+        }
+        self.enums.insert(qualify(scope, &name), values);
+        Ok(())
+    }
+
+    fn decode_message(&self, full: &str, data: &[u8], depth: usize) -> Result<DataValue, AppError> {
+        if depth > MAX_DEPTH {
+            return Err(too_deep());
+        }
+        let Some(descriptor) = self.messages.get(full) else {
+            // Type missing from the set (e.g. built without --include_imports)
+            return decode_raw_message(data, depth);
+        };
+        let mut reader = ByteReader::new(data);
+        let mut fields = FieldMap::default();
+        while !reader.is_empty() {
+            let (number, value) = next_field(&mut reader, depth)?;
+            let Some(field) = descriptor
+                .fields
+                .iter()
+                .find(|field| field.number == number)
+            else {
+                fields.push(number.to_string(), raw_value(value, depth)?, Shape::Guess);
+                continue;
+            };
+            let shape = if !field.repeated {
+                Shape::Last
+            } else if self.is_map_entry(field) {
+                Shape::MapEntries
+            } else {
+                Shape::Repeated
+            };
+            match value {
+                WireValue::Bytes(packed) if field.repeated && is_packable(field.kind) => {
+                    let mut packed = ByteReader::new(packed);
+                    while !packed.is_empty() {
+                        let value = match field.kind {
+                            TYPE_DOUBLE | TYPE_FIXED64 | TYPE_SFIXED64 => {
+                                WireValue::Fixed64(u64::from_le_bytes(packed.array()?))
+                            }
+                            TYPE_FLOAT | TYPE_FIXED32 | TYPE_SFIXED32 => {
+                                WireValue::Fixed32(u32::from_le_bytes(packed.array()?))
+                            }
+                            _ => WireValue::Varint(read_varint(&mut packed)?),
+                        };
+                        let value = self.decode_field(field, value, depth)?;
+                        fields.push(field.name.clone(), value, shape);
+                    }
+                }
+                value => {
+                    let value = self.decode_field(field, value, depth)?;
+                    fields.push(field.name.clone(), value, shape);
+                }
+            }
+        }
+        Ok(fields.into_value())
+    }
+
+    fn encode_message(
+        &self,
+        full: &str,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<(), AppError> {
+        if depth > MAX_DEPTH {
+            return Err(too_deep());
+        }
+        let descriptor = self.messages.get(full).ok_or_else(|| {
+            AppError::ValidationError(format!(
+                "Message type '{full}' is missing from the descriptor set"
+            ))
+        })?;
+        let Value::Object(object) = value else {
+            return Err(AppError::ValidationError(format!(
+                "Expected a JSON object for {full}, got {value}"
+            )));
+        };
+        for (key, value) in object {
+            let field = descriptor
+                .fields
+                .iter()
+                .find(|field| field.name == *key || json_name(&field.name) == *key)
+                .ok_or_else(|| {
+                    AppError::ValidationError(format!("{full} has no field named '{key}'"))
+                })?;
+            if value.is_null() {
+                continue;
+            }
+            if self.is_map_entry(field) {
+                let Value::Object(entries) = value else {
+                    return Err(invalid_value(field, value));
+                };
+                let entry_type = &self.messages[&field.type_name];
+                let entry_field = |number| entry_type.fields.iter().find(|f| f.number == number);
+                let (Some(key_field), Some(value_field)) = (entry_field(1), entry_field(2)) else {
+                    return Err(invalid_value(field, value));
+                };
+                for (key, value) in entries {
+                    let mut entry = Vec::new();
+                    // Map keys are always JSON strings; scalars accept those
+                    self.encode_field(key_field, &Value::String(key.clone()), &mut entry, depth)?;
+                    self.encode_field(value_field, value, &mut entry, depth)?;
+                    write_bytes_field(out, field.number, &entry);
+                }
+            } else if field.repeated {
+                let Value::Array(items) = value else {
+                    return Err(invalid_value(field, value));
+                };
+                if is_packable(field.kind) {
+                    if !items.is_empty() {
+                        let mut packed = Vec::new();
+                        for item in items {
+                            self.encode_scalar(field, item, &mut packed)?;
+                        }
+                        write_bytes_field(out, field.number, &packed);
+                    }
+                } else {
+                    for item in items {
+                        self.encode_field(field, item, out, depth)?;
+                    }
+                }
+            } else {
+                self.encode_field(field, value, out, depth)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_field(
+        &self,
+        field: &FieldDescriptor,
+        value: &Value,
+        out: &mut Vec<u8>,
+        depth: usize,
+    ) -> Result<(), AppError> {
+        match field.kind {
+            TYPE_MESSAGE => {
+                let mut nested = Vec::new();
+                self.encode_message(&field.type_name, value, &mut nested, depth + 1)?;
+                write_bytes_field(out, field.number, &nested);
+            }
+            TYPE_STRING => {
+                let text = value.as_str().ok_or_else(|| invalid_value(field, value))?;
+                write_bytes_field(out, field.number, text.as_bytes());
+            }
+            TYPE_BYTES => {
+                let data = value
+                    .as_str()
+                    .and_then(base64::decode)
+                    .ok_or_else(|| invalid_value(field, value))?;
+                write_bytes_field(out, field.number, &data);
+            }
+            TYPE_GROUP => {
+                return Err(AppError::ValidationError(format!(
+                    "Field '{}' is a group, which cannot be encoded",
+                    field.name
+                )))
+            }
+            kind => {
+                let wire_type = match kind {
+                    TYPE_DOUBLE | TYPE_FIXED64 | TYPE_SFIXED64 => 1,
+                    TYPE_FLOAT | TYPE_FIXED32 | TYPE_SFIXED32 => 5,
+                    _ => 0,
+                };
+                write_varint(out, (u64::from(field.number) << 3) | wire_type);
+                self.encode_scalar(field, value, out)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// A numeric, bool or enum value without its key (also the element of a
+    /// packed field)
+    fn encode_scalar(
+        &self,
+        field: &FieldDescriptor,
+        value: &Value,
+        out: &mut Vec<u8>,
+    ) -> Result<(), AppError> {
+        let invalid = || invalid_value(field, value);
+        match field.kind {
+            TYPE_DOUBLE => out.extend(json_f64(value).ok_or_else(invalid)?.to_le_bytes()),
+            TYPE_FLOAT => out.extend((json_f64(value).ok_or_else(invalid)? as f32).to_le_bytes()),
+            TYPE_INT64 => write_varint(out, json_i64(value).ok_or_else(invalid)? as u64),
+            TYPE_UINT64 => write_varint(out, json_u64(value).ok_or_else(invalid)?),
+            TYPE_INT32 => {
+                let number = json_i64(value)
+                    .and_then(|number| i32::try_from(number).ok())
+                    .ok_or_else(invalid)?;
+                // Negative int32 values are sign-extended to 64 bits
+                write_varint(out, i64::from(number) as u64);
+            }
+            TYPE_UINT32 => {
+                let number = json_u64(value)
+                    .and_then(|number| u32::try_from(number).ok())
+                    .ok_or_else(invalid)?;
+                write_varint(out, u64::from(number));
+            }
+            TYPE_FIXED64 => out.extend(json_u64(value).ok_or_else(invalid)?.to_le_bytes()),
+            TYPE_SFIXED64 => out.extend(json_i64(value).ok_or_else(invalid)?.to_le_bytes()),
+            TYPE_FIXED32 => {
+                let number = json_u64(value)
+                    .and_then(|number| u32::try_from(number).ok())
+                    .ok_or_else(invalid)?;
+                out.extend(number.to_le_bytes());
+            }
+            TYPE_SFIXED32 => {
+                let number = json_i64(value)
+                    .and_then(|number| i32::try_from(number).ok())
+                    .ok_or_else(invalid)?;
+                out.extend(number.to_le_bytes());
+            }
+            TYPE_SINT32 => {
+                let number = json_i64(value)
+                    .and_then(|number| i32::try_from(number).ok())
+                    .ok_or_else(invalid)?;
+                write_varint(out, zigzag_encode(i64::from(number)));
+            }
+            TYPE_SINT64 => write_varint(out, zigzag_encode(json_i64(value).ok_or_else(invalid)?)),
+            TYPE_BOOL => {
+                let flag = match value {
+                    Value::Bool(flag) => *flag,
+                    Value::String(text) => text.parse().map_err(|_| invalid())?,
+                    _ => return Err(invalid()),
+                };
+                write_varint(out, u64::from(flag));
+            }
+            TYPE_ENUM => {
+                let number = match value.as_str() {
+                    Some(name) => self
+                        .enums
+                        .get(&field.type_name)
+                        .and_then(|values| {
+                            values
+                                .iter()
+                                .find(|(_, value_name)| *value_name == name)
+                                .map(|(number, _)| *number)
+                        })
+                        .or_else(|| name.parse().ok()),
+                    None => json_i64(value).and_then(|number| i32::try_from(number).ok()),
+                }
+                .ok_or_else(invalid)?;
+                write_varint(out, i64::from(number) as u64);
+            }
+            _ => return Err(invalid()),
+        }
+        Ok(())
+    }
+
+    fn is_map_entry(&self, field: &FieldDescriptor) -> bool {
+        field.kind == TYPE_MESSAGE
+            && self
+                .messages
+                .get(&field.type_name)
+                .is_some_and(|message| message.map_entry)
+    }
+
+    fn decode_field(
+        &self,
+        field: &FieldDescriptor,
+        value: WireValue,
+        depth: usize,
+    ) -> Result<DataValue, AppError> {
+        let decoded = match (field.kind, value) {
+            (TYPE_DOUBLE, WireValue::Fixed64(bits)) => DataValue::Float(f64::from_bits(bits)),
+            (TYPE_FLOAT, WireValue::Fixed32(bits)) => {
+                DataValue::Float(f64::from(f32::from_bits(bits)))
+            }
+            (TYPE_INT64, WireValue::Varint(value)) => DataValue::Integer(i128::from(value as i64)),
+            (TYPE_UINT64, WireValue::Varint(value)) | (TYPE_FIXED64, WireValue::Fixed64(value)) => {
+                DataValue::Integer(i128::from(value))
+            }
+            (TYPE_INT32, WireValue::Varint(value)) => DataValue::Integer(i128::from(value as i32)),
+            (TYPE_UINT32, WireValue::Varint(value)) => DataValue::Integer(i128::from(value as u32)),
+            (TYPE_FIXED32, WireValue::Fixed32(value)) => DataValue::Integer(i128::from(value)),
+            (TYPE_SFIXED32, WireValue::Fixed32(value)) => {
+                DataValue::Integer(i128::from(value as i32))
+            }
+            (TYPE_SFIXED64, WireValue::Fixed64(value)) => {
+                DataValue::Integer(i128::from(value as i64))
+            }
+            (TYPE_SINT32, WireValue::Varint(value)) => {
+                DataValue::Integer(i128::from(zigzag(value) as i32))
+            }
+            (TYPE_SINT64, WireValue::Varint(value)) => DataValue::Integer(i128::from(zigzag(value))),
+            (TYPE_BOOL, WireValue::Varint(value)) => DataValue::Bool(value != 0),
+            (TYPE_ENUM, WireValue::Varint(value)) => {
+                let number = value as i32;
+                match self
+                    .enums
+                    .get(&field.type_name)
+                    .and_then(|values| values.get(&number))
+                {
+                    Some(name) => DataValue::String(name.clone()),
+                    None => DataValue::Integer(i128::from(number)),
+                }
+            }
+            (TYPE_STRING, WireValue::Bytes(bytes)) => match std::str::from_utf8(bytes) {
+                Ok(text) => DataValue::String(text.to_string()),
+                Err(_) => DataValue::Bytes(bytes.to_vec()),
+            },
+            (TYPE_BYTES, WireValue::Bytes(bytes)) => DataValue::Bytes(bytes.to_vec()),
+            (TYPE_MESSAGE, WireValue::Bytes(bytes)) | (TYPE_GROUP, WireValue::Group(bytes)) => {
+                self.decode_message(&field.type_name, bytes, depth + 1)?
+            }
+            (_, value) => {
+                return Err(AppError::ParseError(format!(
+                    "Field '{}' (#{}) has an unexpected wire type {}; is the descriptor for this message?",
+                    field.name,
+                    field.number,
+                    wire_type_name(value)
+                )))
+            }
+        };
+        Ok(decoded)
+    }
+}
+
+fn parse_field(data: &[u8]) -> Result<FieldDescriptor, AppError> {
+    let mut field = FieldDescriptor::default();
+    for (number, value) in fields(data)? {
+        match (number, value) {
+            (1, value) => field.name = string(value)?,
+            (3, WireValue::Varint(number)) => field.number = number as u32,
+            (4, WireValue::Varint(label)) => field.repeated = label == LABEL_REPEATED,
+            (5, WireValue::Varint(kind)) => field.kind = kind,
+            (6, value) => field.type_name = string(value)?.trim_start_matches('.').to_string(),
+            _ => {}
+        }
+    }
+    Ok(field)
+}
+
+fn qualify(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_string()
+    } else {
+        format!("{scope}.{name}")
+    }
+}
+
+fn is_packable(kind: u64) -> bool {
+    !matches!(kind, TYPE_STRING | TYPE_GROUP | TYPE_MESSAGE | TYPE_BYTES)
+}
+
+/// The lowerCamelCase name protoc gives a field in JSON (`user_id` -> `userId`)
+fn json_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut upper = false;
+    for c in name.chars() {
+        if c == '_' {
+            upper = true;
+        } else if upper {
+            out.push(c.to_ascii_uppercase());
+            upper = false;
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn invalid_value(field: &FieldDescriptor, value: &Value) -> AppError {
+    AppError::ValidationError(format!("Invalid value for field '{}': {value}", field.name))
+}
+
+fn json_i64(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(number) => number.as_i64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(number) => number.as_u64(),
+        Value::String(text) => text.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Numbers, numeric strings and `"NaN"` / `"Infinity"` / `"-Infinity"`
+fn json_f64(value: &Value) -> Option<f64> {
+    match value {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) => match text.trim() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            text => text.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn write_bytes_field(out: &mut Vec<u8>, number: u32, data: &[u8]) {
+    write_varint(out, (u64::from(number) << 3) | 2);
+    write_varint(out, data.len() as u64);
+    out.extend_from_slice(data);
+}
+
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn zigzag(value: u64) -> i64 {
+    (value >> 1) as i64 ^ -((value & 1) as i64)
+}
+
+fn wire_type_name(value: WireValue) -> &'static str {
+    match value {
+        WireValue::Varint(_) => "varint",
+        WireValue::Fixed64(_) => "64-bit",
+        WireValue::Bytes(_) => "length-delimited",
+        WireValue::Group(_) | WireValue::EndGroup => "group",
+        WireValue::Fixed32(_) => "32-bit",
+    }
+}
+
+/// How the values of one field are combined
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shape {
+    /// Singular field: the last value wins
+    Last,
+    Repeated,
+    /// `map<K, V>` field: entries become an object
+    MapEntries,
+    /// Unknown field: an array only if it occurs more than once
+    Guess,
+}
+
+/// Decoded fields in the order they first appear
+#[derive(Default)]
+struct FieldMap {
+    fields: Vec<(String, Vec<DataValue>, Shape)>,
+}
+
+impl FieldMap {
+    fn push(&mut self, name: String, value: DataValue, shape: Shape) {
+        match self
+            .fields
+            .iter_mut()
+            .find(|(existing, _, _)| *existing == name)
+        {
+            Some((_, values, _)) => values.push(value),
+            None => self.fields.push((name, vec![value], shape)),
+        }
+    }
+
+    fn into_value(self) -> DataValue {
+        DataValue::Map(
+            self.fields
+                .into_iter()
+                .map(|(name, mut values, shape)| {
+                    let value = match shape {
+                        Shape::Last => values.pop().unwrap_or(DataValue::Null),
+                        Shape::Guess if values.len() == 1 => values.remove(0),
+                        Shape::MapEntries => {
+                            DataValue::Map(values.into_iter().map(map_entry).collect())
+                        }
+                        Shape::Repeated | Shape::Guess => DataValue::Array(values),
+                    };
+                    (DataValue::String(name), value)
+                })
+                .collect(),
+        )
+    }
+}
+
+/// `{"key": k, "value": v}` of a decoded map entry as a `(k, v)` pair
+fn map_entry(entry: DataValue) -> (DataValue, DataValue) {
+    let mut key = DataValue::Null;
+    let mut value = DataValue::Null;
+    if let DataValue::Map(fields) = entry {
+        for (name, field) in fields {
+            match name {
+                DataValue::String(name) if name == "key" => key = field,
+                DataValue::String(name) if name == "value" => value = field,
+                _ => {}
+            }
+        }
+    }
+    (key, value)
+}
+
+fn decode_raw_message(data: &[u8], depth: usize) -> Result<DataValue, AppError> {
+    if depth > MAX_DEPTH {
+        return Err(too_deep());
+    }
+    let mut reader = ByteReader::new(data);
+    let mut fields = FieldMap::default();
+    while !reader.is_empty() {
+        let (number, value) = next_field(&mut reader, depth)?;
+        fields.push(number.to_string(), raw_value(value, depth)?, Shape::Guess);
+    }
+    Ok(fields.into_value())
+}
+
+fn raw_value(value: WireValue, depth: usize) -> Result<DataValue, AppError> {
+    Ok(match value {
+        WireValue::Varint(value) | WireValue::Fixed64(value) => {
+            DataValue::Integer(i128::from(value))
+        }
+        WireValue::Fixed32(value) => DataValue::Integer(i128::from(value)),
+        WireValue::Bytes(bytes) => guess_length_delimited(bytes, depth),
+        WireValue::Group(bytes) => decode_raw_message(bytes, depth + 1)?,
+        WireValue::EndGroup => DataValue::Null,
+    })
+}
+
+/// Text if it reads as text, else a nested message if it parses as one, else bytes
+fn guess_length_delimited(bytes: &[u8], depth: usize) -> DataValue {
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        if !text
+            .chars()
+            .any(|c| c.is_control() && !matches!(c, '\n' | '\r' | '\t'))
+        {
+            return DataValue::String(text.to_string());
+        }
+    }
+    decode_raw_message(bytes, depth + 1).unwrap_or_else(|_| DataValue::Bytes(bytes.to_vec()))
+}
+
+/// All top-level fields of a message
+fn fields(data: &[u8]) -> Result<Vec<(u32, WireValue<'_>)>, AppError> {
+    let mut reader = ByteReader::new(data);
+    let mut fields = Vec::new();
+    while !reader.is_empty() {
+        fields.push(next_field(&mut reader, 0)?);
+    }
+    Ok(fields)
+}
+
+fn bytes<'a>(value: WireValue<'a>) -> Result<&'a [u8], AppError> {
+    match value {
+        WireValue::Bytes(bytes) => Ok(bytes),
+        value => Err(AppError::ParseError(format!(
+            "Expected a length-delimited descriptor field, found {}",
+            wire_type_name(value)
+        ))),
+    }
+}
+
+fn string(value: WireValue) -> Result<String, AppError> {
+    String::from_utf8(bytes(value)?.to_vec())
+        .map_err(|_| AppError::ParseError("Invalid UTF-8 in descriptor".to_string()))
+}
+
+/// Next field of a message; a stray end-group tag is an error
+fn next_field<'a>(
+    reader: &mut ByteReader<'a>,
+    depth: usize,
+) -> Result<(u32, WireValue<'a>), AppError> {
+    let start = reader.position();
+    match read_field(reader, depth)? {
+        (number, WireValue::EndGroup) => Err(AppError::ParseError(format!(
+            "Unexpected end of group #{number} at byte {start}"
+        ))),
+        field => Ok(field),
+    }
+}
+
+fn read_field<'a>(
+    reader: &mut ByteReader<'a>,
+    depth: usize,
+) -> Result<(u32, WireValue<'a>), AppError> {
+    let start = reader.position();
+    let key = read_varint(reader)?;
+    let number = u32::try_from(key >> 3)
+        .ok()
+        .filter(|number| *number != 0)
+        .ok_or_else(|| AppError::ParseError(format!("Invalid field number at byte {start}")))?;
+    let value = match key & 0x07 {
+        0 => WireValue::Varint(read_varint(reader)?),
+        1 => WireValue::Fixed64(u64::from_le_bytes(reader.array()?)),
+        2 => {
+            let len = read_varint(reader)?;
+            let len = reader.checked_len(len)?;
+            WireValue::Bytes(reader.take(len)?)
+        }
+        3 => WireValue::Group(read_group(reader, number, depth)?),
+        4 => WireValue::EndGroup,
+        5 => WireValue::Fixed32(u32::from_le_bytes(reader.array()?)),
+        wire_type => {
+            return Err(AppError::ParseError(format!(
+                "Invalid wire type {wire_type} at byte {start}"
+            )))
+        }
+    };
+    Ok((number, value))
+}
+
+/// Skip to the end tag of group `number`, returning the bytes in between
+fn read_group<'a>(
+    reader: &mut ByteReader<'a>,
+    number: u32,
+    depth: usize,
+) -> Result<&'a [u8], AppError> {
+    if depth > MAX_DEPTH {
+        return Err(too_deep());
+    }
+    let start = reader.position();
+    loop {
+        let end = reader.position();
+        match read_field(reader, depth + 1)? {
+            (end_number, WireValue::EndGroup) if end_number == number => {
+                return Ok(&reader.since(start)[..end - start]);
+            }
+            (end_number, WireValue::EndGroup) => {
+                return Err(AppError::ParseError(format!(
+                    "Group #{number} closed by end tag #{end_number} at byte {end}"
+                )))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_varint(reader: &mut ByteReader) -> Result<u64, AppError> {
+    let start = reader.position();
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = reader.u8()?;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(AppError::ParseError(format!(
+        "Varint longer than 10 bytes at byte {start}"
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn varint(mut value: u64) -> Vec<u8> {
+        let mut out = Vec::new();
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                return out;
+            }
+            out.push(byte | 0x80);
+        }
+    }
+
+    fn varint_field(number: u32, value: u64) -> Vec<u8> {
+        let mut out = varint(u64::from(number) << 3);
+        out.extend(varint(value));
+        out
+    }
+
+    fn bytes_field(number: u32, data: &[u8]) -> Vec<u8> {
+        let mut out = varint((u64::from(number) << 3) | 2);
+        out.extend(varint(data.len() as u64));
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn field_descriptor(
+        name: &str,
+        number: u64,
+        kind: u64,
+        repeated: bool,
+        type_name: &str,
+    ) -> Vec<u8> {
+        let mut out = bytes_field(1, name.as_bytes());
+        out.extend(varint_field(3, number));
+        out.extend(varint_field(4, if repeated { LABEL_REPEATED } else { 1 }));
+        out.extend(varint_field(5, kind));
+        if !type_name.is_empty() {
+            out.extend(bytes_field(6, type_name.as_bytes()));
+        }
+        out
+    }
+
+    /// package demo;
+    /// enum Role { GUEST = 0; ADMIN = 1; }
+    /// message User {
+    ///   string name = 1; sint32 delta = 2; repeated int32 scores = 3;
+    ///   Role role = 4; map<string, int64> tags = 5; User friend = 6;
+    /// }
+    /// service Users {
+    ///   rpc Get (User) returns (User); rpc Chat (stream User) returns (stream User);
+    /// }
+    fn descriptor_set() -> Vec<u8> {
+        let mut tags_entry = bytes_field(1, b"TagsEntry");
+        tags_entry.extend(bytes_field(
+            2,
+            &field_descriptor("key", 1, TYPE_STRING, false, ""),
+        ));
+        tags_entry.extend(bytes_field(
+            2,
+            &field_descriptor("value", 2, TYPE_INT64, false, ""),
+        ));
+        tags_entry.extend(bytes_field(7, &varint_field(7, 1)));
+
+        let mut user = bytes_field(1, b"User");
+        user.extend(bytes_field(
+            2,
+            &field_descriptor("name", 1, TYPE_STRING, false, ""),
+        ));
+        user.extend(bytes_field(
+            2,
+            &field_descriptor("delta", 2, TYPE_SINT32, false, ""),
+        ));
+        user.extend(bytes_field(
+            2,
+            &field_descriptor("scores", 3, TYPE_INT32, true, ""),
+        ));
+        user.extend(bytes_field(
+            2,
+            &field_descriptor("role", 4, TYPE_ENUM, false, ".demo.Role"),
+        ));
+        user.extend(bytes_field(
+            2,
+            &field_descriptor("tags", 5, TYPE_MESSAGE, true, ".demo.User.TagsEntry"),
+        ));
+        user.extend(bytes_field(
+            2,
+            &field_descriptor("friend", 6, TYPE_MESSAGE, false, ".demo.User"),
+        ));
+        user.extend(bytes_field(3, &tags_entry));
+
+        let mut admin = bytes_field(1, b"ADMIN");
+        admin.extend(varint_field(2, 1));
+        let mut role = bytes_field(1, b"Role");
+        role.extend(bytes_field(2, &admin));
+
+        let mut file = bytes_field(1, b"demo.proto");
+        file.extend(bytes_field(2, b"demo"));
+        file.extend(bytes_field(4, &user));
+        file.extend(bytes_field(5, &role));
+        file.extend(bytes_field(6, &users_service()));
+        bytes_field(1, &file)
+    }
+
+    fn users_service() -> Vec<u8> {
+        let mut get = bytes_field(1, b"Get");
+        get.extend(bytes_field(2, b".demo.User"));
+        get.extend(bytes_field(3, b".demo.User"));
+        let mut chat = bytes_field(1, b"Chat");
+        chat.extend(bytes_field(2, b".demo.User"));
+        chat.extend(bytes_field(3, b".demo.User"));
+        chat.extend(varint_field(5, 1));
+        chat.extend(varint_field(6, 1));
+
+        let mut service = bytes_field(1, b"Users");
+        service.extend(bytes_field(2, &get));
+        service.extend(bytes_field(2, &chat));
+        service
+    }
+
+    fn user_message() -> Vec<u8> {
+        let mut tag = bytes_field(1, b"env");
+        tag.extend(varint_field(2, 7));
+
+        let mut message = bytes_field(1, b"Ann");
+        message.extend(varint_field(2, 3)); // zigzag(-2)
+        message.extend(bytes_field(3, &[0x01, 0x02])); // packed [1, 2]
+        message.extend(varint_field(3, 3)); // unpacked 3
+        message.extend(varint_field(4, 1));
+        message.extend(bytes_field(5, &tag));
+        message.extend(bytes_field(6, &bytes_field(1, b"Bob")));
+        message.extend(varint_field(99, 5)); // unknown field
+        message
+    }
+
+    #[test]
+    fn test_decode_with_descriptor() {
+        let pool = DescriptorPool::from_descriptor_set(&descriptor_set()).unwrap();
+        assert_eq!(pool.message_names(), ["demo.User"]);
+        assert_eq!(pool.resolve("User"), Some("demo.User"));
+        assert_eq!(pool.resolve(".demo.User"), Some("demo.User"));
+        assert_eq!(pool.resolve("Missing"), None);
+
+        let decoded = pool.decode("demo.User", &user_message()).unwrap();
+        assert_eq!(
+            decoded.to_json_compact(),
+            r#"{"name":"Ann","delta":-2,"scores":[1,2,3],"role":"ADMIN","tags":{"env":7},"friend":{"name":"Bob"},"99":5}"#
+        );
+    }
+
+    #[test]
+    fn test_services() {
+        let pool = DescriptorPool::from_descriptor_set(&descriptor_set()).unwrap();
+        let paths: Vec<&str> = pool.methods().iter().map(|m| m.path.as_str()).collect();
+        assert_eq!(paths, ["/demo.Users/Chat", "/demo.Users/Get"]);
+
+        let chat = pool.method("demo.Users/Chat").unwrap();
+        assert_eq!(chat.input_type, "demo.User");
+        assert!(chat.client_streaming && chat.server_streaming);
+        assert_eq!(
+            pool.method("/demo.Users/Get").unwrap().path,
+            "/demo.Users/Get"
+        );
+        assert_eq!(
+            pool.method("demo.Users.Get").unwrap().path,
+            "/demo.Users/Get"
+        );
+        assert_eq!(pool.method("Users/Get").unwrap().path, "/demo.Users/Get");
+        assert!(!pool.method("Users/Get").unwrap().server_streaming);
+        assert!(pool.method("Users/Delete").is_none());
+    }
+
+    #[test]
+    fn test_encode_round_trip() {
+        let pool = DescriptorPool::from_descriptor_set(&descriptor_set()).unwrap();
+        let json: Value = serde_json::from_str(
+            r#"{"name":"Ann","delta":-2,"scores":[1,2,3],"role":"ADMIN","tags":{"env":"7"},"friend":{"name":"Bob","role":0}}"#,
+        )
+        .unwrap();
+        let encoded = pool.encode("User", &json).unwrap();
+        assert_eq!(
+            pool.decode("User", &encoded).unwrap().to_json_compact(),
+            r#"{"delta":-2,"friend":{"name":"Bob","role":0},"name":"Ann","role":"ADMIN","scores":[1,2,3],"tags":{"env":7}}"#
+        );
+        // Repeated scalars are packed
+        let scores: Value = serde_json::from_str(r#"{"scores":[1,2]}"#).unwrap();
+        assert_eq!(
+            pool.encode("User", &scores).unwrap(),
+            bytes_field(3, &[1, 2])
+        );
+    }
+
+    #[test]
+    fn test_encode_errors() {
+        let pool = DescriptorPool::from_descriptor_set(&descriptor_set()).unwrap();
+        let encode = |json: &str| pool.encode("User", &serde_json::from_str(json).unwrap());
+        assert!(encode(r#"{"nickname":"x"}"#).is_err());
+        assert!(encode(r#"{"name":5}"#).is_err());
+        assert!(encode(r#"{"delta":3000000000}"#).is_err());
+        assert!(encode(r#"{"role":"OWNER"}"#).is_err());
+        assert!(encode("[1]").is_err());
+        assert!(pool.encode("Missing", &Value::Null).is_err());
+    }
+
+    #[test]
+    fn test_decode_raw() {
+        let decoded = decode_raw(&user_message()).unwrap();
+        assert_eq!(
+            decoded.to_json_compact(),
+            r#"{"1":"Ann","2":3,"3":["AQI=",3],"4":1,"5":{"1":"env","2":7},"6":{"1":"Bob"},"99":5}"#
+        );
+    }
+
+    #[test]
+    fn test_wrong_descriptor_and_bad_input() {
+        let pool = DescriptorPool::from_descriptor_set(&descriptor_set()).unwrap();
+        // Field 1 (name: string) sent as a varint
+        assert!(pool.decode("User", &varint_field(1, 5)).is_err());
+        // Truncated length-delimited field
+        assert!(decode_raw(&[0x0a, 0x05, b'a']).is_err());
+        // Unterminated varint
+        assert!(decode_raw(&[0x08, 0xff]).is_err());
+        // Stray end group
+        assert!(decode_raw(&[0x0c]).is_err());
+        assert!(DescriptorPool::from_descriptor_set(&[]).is_err());
+    }
+
+    #[test]
+    fn test_groups() {
+        // group #2 { 1: 150 } followed by 3: 1
+        let data = [0x13, 0x08, 0x96, 0x01, 0x14, 0x18, 0x01];
+        assert_eq!(
+            decode_raw(&data).unwrap().to_json_compact(),
+            r#"{"2":{"1":150},"3":1}"#
+        );
+        // group #2 closed by #3
+        assert!(decode_raw(&[0x13, 0x1c]).is_err());
+    }
+}
@@ -0,0 +1,189 @@
+use crate::errors::AppError;
+
+/// A byte range for the `Range` request header (RFC 9110 §14.1.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ByteRange {
+    /// `bytes=start-end`, or `bytes=start-` when `end` is open
+    FromTo { start: u64, end: Option<u64> },
+    /// `bytes=-length` - the last `length` bytes of the resource
+    Suffix(u64),
+}
+
+impl ByteRange {
+    /// Build a range from the start/end fields of the range builder.
+    ///
+    /// With `suffix` set, `end` holds the number of trailing bytes to fetch
+    /// and `start` is ignored. An empty `end` leaves the range open.
+    pub fn from_inputs(start: &str, end: &str, suffix: bool) -> Result<Self, AppError> {
+        let start = start.trim();
+        let end = end.trim();
+
+        if suffix {
+            let length = parse_bytes(end, "suffix length")?;
+            if length == 0 {
+                return Err(AppError::ValidationError(
+                    "Suffix length must be greater than 0".to_string(),
+                ));
+            }
+            return Ok(ByteRange::Suffix(length));
+        }
+
+        let start = if start.is_empty() {
+            0
+        } else {
+            parse_bytes(start, "start")?
+        };
+        let end = if end.is_empty() {
+            None
+        } else {
+            Some(parse_bytes(end, "end")?)
+        };
+        if end.is_some_and(|end| end < start) {
+            return Err(AppError::ValidationError(format!(
+                "Range end must not be before start ({start})"
+            )));
+        }
+        Ok(ByteRange::FromTo { start, end })
+    }
+
+    /// Value for the `Range` header, e.g. `bytes=0-499`
+    pub fn header_value(&self) -> String {
+        match self {
+            ByteRange::FromTo {
+                start,
+                end: Some(end),
+            } => format!("bytes={start}-{end}"),
+            ByteRange::FromTo { start, end: None } => format!("bytes={start}-"),
+            ByteRange::Suffix(length) => format!("bytes=-{length}"),
+        }
+    }
+}
+
+fn parse_bytes(value: &str, field: &str) -> Result<u64, AppError> {
+    value.parse().map_err(|_| {
+        AppError::ValidationError(format!("Range {field} must be a whole number of bytes"))
+    })
+}
+
+/// A parsed `Content-Range` response header (RFC 9110 §14.4).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentRange {
+    pub unit: String,
+    /// First and last byte (inclusive); `None` for `bytes */length` (416 responses)
+    pub range: Option<(u64, u64)>,
+    /// Total size of the resource; `None` when the server sent `*`
+    pub complete_length: Option<u64>,
+}
+
+impl ContentRange {
+    /// Parse `bytes 0-499/1234`, `bytes 0-499/*` or `bytes */1234`
+    pub fn parse(value: &str) -> Option<Self> {
+        let (unit, rest) = value.trim().split_once(' ')?;
+        let (range, length) = rest.trim().split_once('/')?;
+
+        let range = match range.trim() {
+            "*" => None,
+            range => {
+                let (first, last) = range.split_once('-')?;
+                let first: u64 = first.trim().parse().ok()?;
+                let last: u64 = last.trim().parse().ok()?;
+                if last < first {
+                    return None;
+                }
+                Some((first, last))
+            }
+        };
+        let complete_length = match length.trim() {
+            "*" => None,
+            length => Some(length.parse().ok()?),
+        };
+        if range.is_none() && complete_length.is_none() {
+            return None;
+        }
+
+        Some(Self {
+            unit: unit.to_string(),
+            range,
+            complete_length,
+        })
+    }
+
+    /// Number of bytes included in the response
+    pub fn len(&self) -> Option<u64> {
+        self.range.map(|(first, last)| last - first + 1)
+    }
+
+    /// Whether the response carries no bytes (`bytes */length`)
+    pub fn is_empty(&self) -> bool {
+        self.range.is_none()
+    }
+
+    /// Human readable summary, e.g. `bytes 0-499 of 1234 (500 bytes)`
+    pub fn describe(&self) -> String {
+        let total = self
+            .complete_length
+            .map_or_else(|| "unknown size".to_string(), |length| length.to_string());
+        match (self.range, self.len()) {
+            (Some((first, last)), Some(len)) => {
+                format!("{} {first}-{last} of {total} ({len} bytes)", self.unit)
+            }
+            _ => format!("no {} returned, resource size is {total}", self.unit),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_range_header_value() {
+        let range = ByteRange::from_inputs("0", "499", false).unwrap();
+        assert_eq!(range.header_value(), "bytes=0-499");
+
+        let range = ByteRange::from_inputs("500", "", false).unwrap();
+        assert_eq!(range.header_value(), "bytes=500-");
+
+        let range = ByteRange::from_inputs("", " 200 ", true).unwrap();
+        assert_eq!(range, ByteRange::Suffix(200));
+        assert_eq!(range.header_value(), "bytes=-200");
+    }
+
+    #[test]
+    fn test_byte_range_rejects_invalid_input() {
+        assert!(ByteRange::from_inputs("10", "5", false).is_err());
+        assert!(ByteRange::from_inputs("abc", "", false).is_err());
+        assert!(ByteRange::from_inputs("-1", "", false).is_err());
+        assert!(ByteRange::from_inputs("", "", true).is_err());
+        assert!(ByteRange::from_inputs("", "0", true).is_err());
+    }
+
+    #[test]
+    fn test_content_range_parse() {
+        let range = ContentRange::parse("bytes 0-499/1234").unwrap();
+        assert_eq!(range.range, Some((0, 499)));
+        assert_eq!(range.complete_length, Some(1234));
+        assert_eq!(range.len(), Some(500));
+        assert_eq!(range.describe(), "bytes 0-499 of 1234 (500 bytes)");
+
+        let range = ContentRange::parse("bytes 100-199/*").unwrap();
+        assert_eq!(range.complete_length, None);
+        assert_eq!(
+            range.describe(),
+            "bytes 100-199 of unknown size (100 bytes)"
+        );
+
+        let range = ContentRange::parse("bytes */1234").unwrap();
+        assert!(range.is_empty());
+        assert_eq!(range.describe(), "no bytes returned, resource size is 1234");
+    }
+
+    #[test]
+    fn test_content_range_parse_invalid() {
+        assert_eq!(ContentRange::parse(""), None);
+        assert_eq!(ContentRange::parse("bytes 0-499"), None);
+        assert_eq!(ContentRange::parse("bytes 500-100/1234"), None);
+        assert_eq!(ContentRange::parse("bytes */*"), None);
+        assert_eq!(ContentRange::parse("bytes a-b/10"), None);
+    }
+}
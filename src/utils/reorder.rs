@@ -0,0 +1,47 @@
+/// Move the item at `from` so it ends up at index `to`, shifting the
+/// items in between. Returns `false` (and leaves `items` untouched) if
+/// either index is out of bounds.
+pub fn move_item<T>(items: &mut Vec<T>, from: usize, to: usize) -> bool {
+    if from >= items.len() || to >= items.len() {
+        return false;
+    }
+    if from != to {
+        let item = items.remove(from);
+        items.insert(to, item);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_down() {
+        let mut items = vec!["a", "b", "c", "d"];
+        assert!(move_item(&mut items, 0, 2));
+        assert_eq!(items, ["b", "c", "a", "d"]);
+    }
+
+    #[test]
+    fn test_move_up() {
+        let mut items = vec!["a", "b", "c", "d"];
+        assert!(move_item(&mut items, 3, 1));
+        assert_eq!(items, ["a", "d", "b", "c"]);
+    }
+
+    #[test]
+    fn test_move_same_index() {
+        let mut items = vec!["a", "b"];
+        assert!(move_item(&mut items, 1, 1));
+        assert_eq!(items, ["a", "b"]);
+    }
+
+    #[test]
+    fn test_move_out_of_bounds() {
+        let mut items = vec!["a", "b"];
+        assert!(!move_item(&mut items, 2, 0));
+        assert!(!move_item(&mut items, 0, 5));
+        assert_eq!(items, ["a", "b"]);
+    }
+}
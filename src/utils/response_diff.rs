@@ -0,0 +1,254 @@
+//! Differences between a response and the golden baseline pinned for its
+//! request: the status, then the JSON body field by field (or the text body
+//! line by line when either side is not JSON).
+
+use serde_json::Value;
+
+/// Stop listing differences after this many
+pub const MAX_CHANGES: usize = 200;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChangeKind {
+    /// The field is new in the response
+    Added(String),
+    /// The response no longer has the field
+    Removed(String),
+    /// The value (or the status) changed from the first to the second
+    Changed(String, String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// `status`, a JSON path like `$.items[0].id`, or `line N`
+    pub path: String,
+    pub kind: ChangeKind,
+}
+
+impl Change {
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            ChangeKind::Added(value) => format!("{} was added: {value}", self.path),
+            ChangeKind::Removed(value) => format!("{} was removed (was {value})", self.path),
+            ChangeKind::Changed(before, after) => {
+                format!("{} changed from {before} to {after}", self.path)
+            }
+        }
+    }
+}
+
+/// Compare a response with its baseline. Paths in `ignored` (e.g.
+/// `$.requestId` or `$.items[].updatedAt`, where `[]` matches every index)
+/// are skipped along with everything below them.
+pub fn diff_responses(
+    baseline_status: u16,
+    baseline_body: &str,
+    status: u16,
+    body: &str,
+    ignored: &[String],
+) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if baseline_status != status {
+        changes.push(Change {
+            path: "status".to_string(),
+            kind: ChangeKind::Changed(baseline_status.to_string(), status.to_string()),
+        });
+    }
+    match (
+        serde_json::from_str::<Value>(baseline_body),
+        serde_json::from_str::<Value>(body),
+    ) {
+        (Ok(before), Ok(after)) => diff_values(&before, &after, "$", ignored, &mut changes),
+        _ => diff_lines(baseline_body, body, &mut changes),
+    }
+    changes.truncate(MAX_CHANGES);
+    changes
+}
+
+fn diff_values(
+    before: &Value,
+    after: &Value,
+    path: &str,
+    ignored: &[String],
+    changes: &mut Vec<Change>,
+) {
+    if changes.len() >= MAX_CHANGES || is_ignored(path, ignored) {
+        return;
+    }
+    match (before, after) {
+        (Value::Object(before), Value::Object(after)) => {
+            for (key, value) in before {
+                let child = format!("{path}.{key}");
+                match after.get(key) {
+                    Some(other) => diff_values(value, other, &child, ignored, changes),
+                    None if !is_ignored(&child, ignored) => changes.push(Change {
+                        path: child,
+                        kind: ChangeKind::Removed(preview(value)),
+                    }),
+                    None => {}
+                }
+            }
+            for (key, value) in after {
+                let child = format!("{path}.{key}");
+                if !before.contains_key(key) && !is_ignored(&child, ignored) {
+                    changes.push(Change {
+                        path: child,
+                        kind: ChangeKind::Added(preview(value)),
+                    });
+                }
+            }
+        }
+        (Value::Array(before), Value::Array(after)) => {
+            for index in 0..before.len().max(after.len()) {
+                let child = format!("{path}[{index}]");
+                match (before.get(index), after.get(index)) {
+                    (Some(value), Some(other)) => {
+                        diff_values(value, other, &child, ignored, changes)
+                    }
+                    (Some(value), None) if !is_ignored(&child, ignored) => changes.push(Change {
+                        path: child,
+                        kind: ChangeKind::Removed(preview(value)),
+                    }),
+                    (None, Some(value)) if !is_ignored(&child, ignored) => changes.push(Change {
+                        path: child,
+                        kind: ChangeKind::Added(preview(value)),
+                    }),
+                    _ => {}
+                }
+            }
+        }
+        (before, after) if before != after => changes.push(Change {
+            path: path.to_string(),
+            kind: ChangeKind::Changed(preview(before), preview(after)),
+        }),
+        _ => {}
+    }
+}
+
+// Line-by-line comparison of bodies that are not both JSON
+fn diff_lines(before: &str, after: &str, changes: &mut Vec<Change>) {
+    let before: Vec<&str> = before.lines().collect();
+    let after: Vec<&str> = after.lines().collect();
+    for index in 0..before.len().max(after.len()) {
+        if changes.len() >= MAX_CHANGES {
+            return;
+        }
+        let path = format!("line {}", index + 1);
+        let kind = match (before.get(index), after.get(index)) {
+            (Some(old), Some(new)) if old != new => {
+                ChangeKind::Changed(old.to_string(), new.to_string())
+            }
+            (Some(old), None) => ChangeKind::Removed(old.to_string()),
+            (None, Some(new)) => ChangeKind::Added(new.to_string()),
+            _ => continue,
+        };
+        changes.push(Change { path, kind });
+    }
+}
+
+// `path` or one of its parents is ignored; `[]` in a rule matches any index
+fn is_ignored(path: &str, ignored: &[String]) -> bool {
+    let generic = strip_indexes(path);
+    ignored.iter().any(|rule| {
+        let rule = rule.trim();
+        !rule.is_empty()
+            && [path, generic.as_str()].iter().any(|candidate| {
+                candidate.strip_prefix(rule).is_some_and(|rest| {
+                    rest.is_empty() || rest.starts_with('.') || rest.starts_with('[')
+                })
+            })
+    })
+}
+
+// `$.items[3].id` -> `$.items[].id`
+fn strip_indexes(path: &str) -> String {
+    let mut generic = String::with_capacity(path.len());
+    let mut in_index = false;
+    for c in path.chars() {
+        match c {
+            '[' => {
+                in_index = true;
+                generic.push('[');
+            }
+            ']' => {
+                in_index = false;
+                generic.push(']');
+            }
+            _ if in_index => {}
+            c => generic.push(c),
+        }
+    }
+    generic
+}
+
+// Short JSON text of a value for the change list
+fn preview(value: &Value) -> String {
+    let text = value.to_string();
+    if text.chars().count() > 60 {
+        format!("{}…", text.chars().take(60).collect::<String>())
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_json_responses() {
+        let baseline = r#"{"id": 1, "name": "Ada", "tags": ["a"], "meta": {"at": "09:00"}}"#;
+        let response = r#"{"id": 1, "name": "Grace", "tags": ["a", "b"], "meta": {"at": "10:00"}, "new": true}"#;
+        let changes = diff_responses(200, baseline, 201, response, &[]);
+        let mut described: Vec<String> = changes.iter().map(Change::describe).collect();
+        described.sort();
+        assert_eq!(
+            described,
+            [
+                "$.meta.at changed from \"09:00\" to \"10:00\"",
+                "$.name changed from \"Ada\" to \"Grace\"",
+                "$.new was added: true",
+                "$.tags[1] was added: \"b\"",
+                "status changed from 200 to 201",
+            ]
+        );
+
+        let ignored = vec!["$.meta".to_string(), "$.name".to_string()];
+        assert_eq!(
+            diff_responses(200, baseline, 200, response, &ignored).len(),
+            2
+        );
+        assert!(diff_responses(200, baseline, 200, baseline, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_ignored_paths_match_every_index() {
+        let baseline = r#"{"items": [{"id": 1, "at": 1}, {"id": 2, "at": 1}]}"#;
+        let response = r#"{"items": [{"id": 1, "at": 5}, {"id": 2, "at": 6}]}"#;
+        let ignored = vec!["$.items[].at".to_string()];
+        assert!(diff_responses(200, baseline, 200, response, &ignored).is_empty());
+        // A rule only covers whole path segments
+        let ignored = vec!["$.item".to_string()];
+        assert_eq!(
+            diff_responses(200, baseline, 200, response, &ignored).len(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_diff_text_responses() {
+        let changes = diff_responses(200, "ok\nsame", 200, "OK\nsame\nextra", &[]);
+        assert_eq!(
+            changes,
+            vec![
+                Change {
+                    path: "line 1".to_string(),
+                    kind: ChangeKind::Changed("ok".to_string(), "OK".to_string()),
+                },
+                Change {
+                    path: "line 3".to_string(),
+                    kind: ChangeKind::Added("extra".to_string()),
+                },
+            ]
+        );
+    }
+}
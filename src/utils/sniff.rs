@@ -0,0 +1,213 @@
+//! Telling what a file or response body is from its content, for when the
+//! Content-Type is missing or wrong - e.g. JSON sent as `text/plain`, or an
+//! HTML error page sent as `application/json`. The first bytes are checked
+//! for image and archive signatures; text is parsed as JSON or recognised
+//! as XML / HTML markup.
+
+/// How a response body is read, which decides how it is formatted and
+/// previewed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BodyKind {
+    Json,
+    Xml,
+    Html,
+    Image,
+    Text,
+    Binary,
+}
+
+impl BodyKind {
+    pub fn label(self) -> &'static str {
+        match self {
+            BodyKind::Json => "JSON",
+            BodyKind::Xml => "XML",
+            BodyKind::Html => "HTML",
+            BodyKind::Image => "Image",
+            BodyKind::Text => "Text",
+            BodyKind::Binary => "Binary",
+        }
+    }
+
+    /// Kinds a body can be viewed as when the detected one is wrong
+    pub fn choices() -> [BodyKind; 5] {
+        [
+            BodyKind::Json,
+            BodyKind::Xml,
+            BodyKind::Html,
+            BodyKind::Image,
+            BodyKind::Text,
+        ]
+    }
+
+    pub fn from_label(label: &str) -> Option<BodyKind> {
+        Self::choices()
+            .into_iter()
+            .find(|kind| kind.label() == label)
+    }
+
+    /// Kind a Content-Type names; `None` for types that say nothing about
+    /// the body such as `text/plain` or `application/octet-stream`
+    pub fn from_content_type(content_type: &str) -> Option<BodyKind> {
+        let mime = content_type
+            .split(';')
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_ascii_lowercase();
+        if mime.starts_with("image/") {
+            return Some(BodyKind::Image);
+        }
+        match mime.as_str() {
+            "application/json" | "text/json" => Some(BodyKind::Json),
+            "text/html" | "application/xhtml+xml" => Some(BodyKind::Html),
+            "application/xml" | "text/xml" => Some(BodyKind::Xml),
+            _ if mime.ends_with("+json") => Some(BodyKind::Json),
+            _ if mime.ends_with("+xml") => Some(BodyKind::Xml),
+            _ => None,
+        }
+    }
+}
+
+/// Content type the first bytes of a file or body point to: a known
+/// signature, markup, else `text/plain` for text and
+/// `application/octet-stream` for anything else
+pub fn sniff(head: &[u8]) -> &'static str {
+    const SIGNATURES: [(&[u8], &str); 6] = [
+        (b"\x89PNG\r\n\x1a\n", "image/png"),
+        (b"\xff\xd8\xff", "image/jpeg"),
+        (b"GIF8", "image/gif"),
+        (b"%PDF-", "application/pdf"),
+        (b"PK\x03\x04", "application/zip"),
+        (b"\x1f\x8b", "application/gzip"),
+    ];
+    if let Some((_, content_type)) = SIGNATURES
+        .iter()
+        .find(|(signature, _)| head.starts_with(signature))
+    {
+        return content_type;
+    }
+    if head.len() >= 12 && head.starts_with(b"RIFF") && &head[8..12] == b"WEBP" {
+        return "image/webp";
+    }
+    if !is_text(head) {
+        return "application/octet-stream";
+    }
+    let text = String::from_utf8_lossy(head);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    let opening: String = text
+        .chars()
+        .take(15)
+        .collect::<String>()
+        .to_ascii_lowercase();
+    if opening.starts_with("<!doctype html") || opening.starts_with("<html") {
+        "text/html"
+    } else if opening.starts_with("<?xml") {
+        "application/xml"
+    } else {
+        "text/plain"
+    }
+}
+
+/// UTF-8 without NUL bytes; a character cut off at the end still counts
+pub fn is_text(head: &[u8]) -> bool {
+    let utf8 = match std::str::from_utf8(head) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    };
+    utf8 && !head.contains(&0)
+}
+
+/// How to read `body`: the kind its Content-Type names when the body
+/// agrees, otherwise what the content looks like
+pub fn detect(content_type: Option<&str>, body: &[u8]) -> BodyKind {
+    let sniffed = sniff_kind(body);
+    match (content_type.and_then(BodyKind::from_content_type), sniffed) {
+        (Some(declared), sniffed) if declared == sniffed => declared,
+        // Markup the sniffer can't tell apart, e.g. an HTML fragment
+        (Some(declared @ (BodyKind::Xml | BodyKind::Html)), BodyKind::Xml | BodyKind::Html) => {
+            declared
+        }
+        (_, sniffed) => sniffed,
+    }
+}
+
+fn sniff_kind(body: &[u8]) -> BodyKind {
+    let content_type = sniff(body);
+    if content_type.starts_with("image/") {
+        return BodyKind::Image;
+    }
+    match content_type {
+        "text/html" => return BodyKind::Html,
+        "application/xml" => return BodyKind::Xml,
+        "text/plain" => {}
+        _ => return BodyKind::Binary,
+    }
+    let text = String::from_utf8_lossy(body);
+    let text = text.trim_start_matches('\u{feff}').trim();
+    if (text.starts_with('{') || text.starts_with('['))
+        && serde_json::from_str::<serde_json::Value>(text).is_ok()
+    {
+        BodyKind::Json
+    } else if text.starts_with('<') && text.ends_with('>') {
+        BodyKind::Xml
+    } else {
+        BodyKind::Text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sniff_signatures_and_markup() {
+        assert_eq!(sniff(b"\x89PNG\r\n\x1a\n\0\0"), "image/png");
+        assert_eq!(sniff(b"RIFF\0\0\0\0WEBPVP8 "), "image/webp");
+        assert_eq!(sniff(&[0, 159, 146, 150]), "application/octet-stream");
+        assert_eq!(sniff(b"\xef\xbb\xbf<!DOCTYPE html><html>"), "text/html");
+        assert_eq!(sniff(b"  <?xml version=\"1.0\"?><a/>"), "application/xml");
+        assert_eq!(sniff(b"line one\nline two"), "text/plain");
+    }
+
+    #[test]
+    fn test_detect_trusts_content_type_the_body_agrees_with() {
+        let json = br#"{"id": 1}"#;
+        assert_eq!(detect(Some("application/json"), json), BodyKind::Json);
+        assert_eq!(
+            detect(Some("application/problem+json"), json),
+            BodyKind::Json
+        );
+        // Wrong or missing Content-Type
+        assert_eq!(
+            detect(Some("text/plain; charset=utf-8"), json),
+            BodyKind::Json
+        );
+        assert_eq!(detect(None, json), BodyKind::Json);
+        assert_eq!(
+            detect(Some("application/json"), b"<html><body>502</body></html>"),
+            BodyKind::Html
+        );
+        assert_eq!(detect(Some("image/png"), b"Not found"), BodyKind::Text);
+
+        // An HTML fragment stays HTML when the server says so
+        let fragment = b"<div><p>Hi</p></div>";
+        assert_eq!(detect(Some("text/html"), fragment), BodyKind::Html);
+        assert_eq!(detect(None, fragment), BodyKind::Xml);
+        assert_eq!(detect(None, b"\x89PNG\r\n\x1a\n"), BodyKind::Image);
+        assert_eq!(detect(None, b"[not json"), BodyKind::Text);
+        assert_eq!(detect(None, &[0, 1, 2]), BodyKind::Binary);
+    }
+
+    #[test]
+    fn test_kind_labels() {
+        for kind in BodyKind::choices() {
+            assert_eq!(BodyKind::from_label(kind.label()), Some(kind));
+        }
+        assert_eq!(BodyKind::from_label("Auto"), None);
+        assert_eq!(
+            BodyKind::from_content_type("image/svg+xml"),
+            Some(BodyKind::Image)
+        );
+        assert_eq!(BodyKind::from_content_type("text/csv"), None);
+    }
+}
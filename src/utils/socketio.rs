@@ -0,0 +1,488 @@
+//! Socket.IO (protocol v5) framing over Engine.IO v4 text frames.
+//!
+//! Transport-agnostic: [`SocketIoSession`] takes the text frames received on
+//! a WebSocket and returns the frames to send back, so it can sit on top of
+//! any WebSocket connection.
+
+use crate::errors::AppError;
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Default namespace
+pub const ROOT_NAMESPACE: &str = "/";
+
+/// Engine.IO packet types (the first character of every frame)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnginePacketType {
+    Open,
+    Close,
+    Ping,
+    Pong,
+    Message,
+    Upgrade,
+    Noop,
+}
+
+impl EnginePacketType {
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '0' => EnginePacketType::Open,
+            '1' => EnginePacketType::Close,
+            '2' => EnginePacketType::Ping,
+            '3' => EnginePacketType::Pong,
+            '4' => EnginePacketType::Message,
+            '5' => EnginePacketType::Upgrade,
+            '6' => EnginePacketType::Noop,
+            _ => return None,
+        })
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            EnginePacketType::Open => '0',
+            EnginePacketType::Close => '1',
+            EnginePacketType::Ping => '2',
+            EnginePacketType::Pong => '3',
+            EnginePacketType::Message => '4',
+            EnginePacketType::Upgrade => '5',
+            EnginePacketType::Noop => '6',
+        }
+    }
+}
+
+/// Socket.IO packet types
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketType {
+    Connect,
+    Disconnect,
+    Event,
+    Ack,
+    ConnectError,
+    BinaryEvent,
+    BinaryAck,
+}
+
+impl PacketType {
+    fn from_char(c: char) -> Option<Self> {
+        Some(match c {
+            '0' => PacketType::Connect,
+            '1' => PacketType::Disconnect,
+            '2' => PacketType::Event,
+            '3' => PacketType::Ack,
+            '4' => PacketType::ConnectError,
+            '5' => PacketType::BinaryEvent,
+            '6' => PacketType::BinaryAck,
+            _ => return None,
+        })
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            PacketType::Connect => '0',
+            PacketType::Disconnect => '1',
+            PacketType::Event => '2',
+            PacketType::Ack => '3',
+            PacketType::ConnectError => '4',
+            PacketType::BinaryEvent => '5',
+            PacketType::BinaryAck => '6',
+        }
+    }
+}
+
+/// One Socket.IO packet: `<type>[<attachments>-][<namespace>,][<ack id>][<json>]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packet {
+    pub kind: PacketType,
+    pub namespace: String,
+    pub ack_id: Option<u64>,
+    /// Number of binary attachments (binary packets only)
+    pub attachments: usize,
+    pub data: Option<Value>,
+}
+
+impl Packet {
+    pub fn new(kind: PacketType, namespace: impl Into<String>) -> Self {
+        Self {
+            kind,
+            namespace: namespace.into(),
+            ack_id: None,
+            attachments: 0,
+            data: None,
+        }
+    }
+
+    /// `socket.emit(event, ...args)`
+    pub fn event(namespace: impl Into<String>, event: &str, args: Vec<Value>) -> Self {
+        let mut data = vec![Value::String(event.to_string())];
+        data.extend(args);
+        Self {
+            data: Some(Value::Array(data)),
+            ..Self::new(PacketType::Event, namespace)
+        }
+    }
+
+    pub fn with_ack_id(mut self, ack_id: u64) -> Self {
+        self.ack_id = Some(ack_id);
+        self
+    }
+
+    /// Event name and arguments of an event packet
+    pub fn event_parts(&self) -> Option<(&str, &[Value])> {
+        match (self.kind, self.data.as_ref()?) {
+            (PacketType::Event | PacketType::BinaryEvent, Value::Array(items)) => {
+                let (name, args) = items.split_first()?;
+                Some((name.as_str()?, args))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let mut out = String::new();
+        out.push(self.kind.as_char());
+        if matches!(self.kind, PacketType::BinaryEvent | PacketType::BinaryAck) {
+            out.push_str(&format!("{}-", self.attachments));
+        }
+        if self.namespace != ROOT_NAMESPACE && !self.namespace.is_empty() {
+            out.push_str(&self.namespace);
+            out.push(',');
+        }
+        if let Some(ack_id) = self.ack_id {
+            out.push_str(&ack_id.to_string());
+        }
+        if let Some(data) = &self.data {
+            out.push_str(&data.to_string());
+        }
+        out
+    }
+
+    pub fn decode(text: &str) -> Result<Self, AppError> {
+        let invalid = |reason: &str| AppError::ParseError(format!("{reason}: {text}"));
+        let mut chars = text.chars();
+        let kind = chars
+            .next()
+            .and_then(PacketType::from_char)
+            .ok_or_else(|| invalid("Unknown Socket.IO packet type"))?;
+        let mut rest = chars.as_str();
+
+        let mut attachments = 0;
+        if matches!(kind, PacketType::BinaryEvent | PacketType::BinaryAck) {
+            let (count, after) = rest
+                .split_once('-')
+                .ok_or_else(|| invalid("Missing attachment count"))?;
+            attachments = count
+                .parse()
+                .map_err(|_| invalid("Invalid attachment count"))?;
+            rest = after;
+        }
+
+        let mut namespace = ROOT_NAMESPACE.to_string();
+        if rest.starts_with('/') {
+            let end = rest.find(',').unwrap_or(rest.len());
+            namespace = rest[..end].to_string();
+            rest = rest.get(end + 1..).unwrap_or_default();
+        }
+
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let ack_id = if digits > 0 {
+            Some(
+                rest[..digits]
+                    .parse()
+                    .map_err(|_| invalid("Invalid ack id"))?,
+            )
+        } else {
+            None
+        };
+        rest = &rest[digits..];
+
+        let data = if rest.is_empty() {
+            None
+        } else {
+            Some(
+                serde_json::from_str(rest)
+                    .map_err(|e| AppError::ParseError(format!("Invalid packet data: {e}")))?,
+            )
+        };
+
+        Ok(Self {
+            kind,
+            namespace,
+            ack_id,
+            attachments,
+            data,
+        })
+    }
+}
+
+/// What the session saw in a received frame
+#[derive(Debug, Clone, PartialEq)]
+pub enum SocketIoEvent {
+    /// Engine.IO handshake (`sid`, `pingInterval`, ... as sent by the server)
+    Opened(Value),
+    /// A namespace accepted the connection
+    Connected {
+        namespace: String,
+        sid: Option<String>,
+    },
+    ConnectError {
+        namespace: String,
+        message: String,
+    },
+    Disconnected {
+        namespace: String,
+    },
+    Event {
+        namespace: String,
+        name: String,
+        args: Vec<Value>,
+        /// Set when the server expects an acknowledgement
+        ack_id: Option<u64>,
+    },
+    /// Reply to an event emitted with an ack
+    Ack {
+        namespace: String,
+        /// The event the ack answers, if it was sent by this session
+        event: Option<String>,
+        args: Vec<Value>,
+    },
+    /// The server closed the Engine.IO session
+    Closed,
+}
+
+/// Client side of a Socket.IO connection
+#[derive(Debug, Default)]
+pub struct SocketIoSession {
+    next_ack_id: u64,
+    // Ack id -> event name of emits still waiting for an acknowledgement
+    pending_acks: BTreeMap<u64, String>,
+}
+
+impl SocketIoSession {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Frame that connects to `namespace`, with an optional auth payload
+    pub fn connect(&self, namespace: &str, auth: Option<Value>) -> String {
+        let mut packet = Packet::new(PacketType::Connect, namespace);
+        packet.data = auth;
+        engine_message(&packet)
+    }
+
+    pub fn disconnect(&self, namespace: &str) -> String {
+        engine_message(&Packet::new(PacketType::Disconnect, namespace))
+    }
+
+    /// Frame that emits an event; with `want_ack` the returned id matches the
+    /// later [`SocketIoEvent::Ack`]
+    pub fn emit(
+        &mut self,
+        namespace: &str,
+        event: &str,
+        args: Vec<Value>,
+        want_ack: bool,
+    ) -> (String, Option<u64>) {
+        let mut packet = Packet::event(namespace, event, args);
+        let ack_id = want_ack.then(|| {
+            let id = self.next_ack_id;
+            self.next_ack_id += 1;
+            self.pending_acks.insert(id, event.to_string());
+            id
+        });
+        packet.ack_id = ack_id;
+        (engine_message(&packet), ack_id)
+    }
+
+    /// Frame answering an event the server sent with an ack id
+    pub fn ack(&self, namespace: &str, ack_id: u64, args: Vec<Value>) -> String {
+        let mut packet = Packet::new(PacketType::Ack, namespace).with_ack_id(ack_id);
+        packet.data = Some(Value::Array(args));
+        engine_message(&packet)
+    }
+
+    pub fn pending_acks(&self) -> usize {
+        self.pending_acks.len()
+    }
+
+    /// Handle a received text frame. Returns the event it carried (if any)
+    /// and a frame to send back (the pong for a ping).
+    pub fn receive(
+        &mut self,
+        frame: &str,
+    ) -> Result<(Option<SocketIoEvent>, Option<String>), AppError> {
+        let mut chars = frame.chars();
+        let kind = chars
+            .next()
+            .and_then(EnginePacketType::from_char)
+            .ok_or_else(|| AppError::ParseError(format!("Unknown Engine.IO packet: {frame}")))?;
+        let payload = chars.as_str();
+
+        let result = match kind {
+            EnginePacketType::Open => {
+                let handshake = serde_json::from_str(payload).map_err(|e| {
+                    AppError::ParseError(format!("Invalid Engine.IO handshake: {e}"))
+                })?;
+                (Some(SocketIoEvent::Opened(handshake)), None)
+            }
+            EnginePacketType::Ping => (
+                None,
+                Some(format!("{}{payload}", EnginePacketType::Pong.as_char())),
+            ),
+            EnginePacketType::Close => (Some(SocketIoEvent::Closed), None),
+            EnginePacketType::Message => (self.receive_packet(Packet::decode(payload)?), None),
+            EnginePacketType::Pong | EnginePacketType::Upgrade | EnginePacketType::Noop => {
+                (None, None)
+            }
+        };
+        Ok(result)
+    }
+
+    fn receive_packet(&mut self, packet: Packet) -> Option<SocketIoEvent> {
+        let namespace = packet.namespace.clone();
+        let event = match packet.kind {
+            PacketType::Connect => SocketIoEvent::Connected {
+                namespace,
+                sid: packet
+                    .data
+                    .as_ref()
+                    .and_then(|data| data.get("sid"))
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+            },
+            PacketType::ConnectError => SocketIoEvent::ConnectError {
+                namespace,
+                message: match &packet.data {
+                    Some(Value::Object(object)) => object
+                        .get("message")
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                    Some(Value::String(message)) => message.clone(),
+                    other => other.as_ref().map(Value::to_string).unwrap_or_default(),
+                },
+            },
+            PacketType::Disconnect => SocketIoEvent::Disconnected { namespace },
+            PacketType::Event | PacketType::BinaryEvent => {
+                let (name, args) = packet.event_parts()?;
+                SocketIoEvent::Event {
+                    namespace,
+                    name: name.to_string(),
+                    args: args.to_vec(),
+                    ack_id: packet.ack_id,
+                }
+            }
+            PacketType::Ack | PacketType::BinaryAck => SocketIoEvent::Ack {
+                namespace,
+                event: packet.ack_id.and_then(|id| self.pending_acks.remove(&id)),
+                args: match packet.data {
+                    Some(Value::Array(args)) => args,
+                    Some(other) => vec![other],
+                    None => Vec::new(),
+                },
+            },
+        };
+        Some(event)
+    }
+}
+
+/// Wrap a Socket.IO packet in an Engine.IO message frame
+fn engine_message(packet: &Packet) -> String {
+    format!("{}{}", EnginePacketType::Message.as_char(), packet.encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_packet_round_trip() {
+        let cases = [
+            "0",
+            "0/admin,{\"token\":\"abc\"}",
+            "2[\"hello\",1]",
+            "2/chat,12[\"msg\",{\"text\":\"hi\"}]",
+            "3/chat,12[\"ok\"]",
+            "51-[\"upload\",{\"_placeholder\":true,\"num\":0}]",
+            "1/admin,",
+        ];
+        for text in cases {
+            assert_eq!(Packet::decode(text).unwrap().encode(), text);
+        }
+
+        let packet = Packet::decode("2/chat,12[\"msg\",{\"text\":\"hi\"}]").unwrap();
+        assert_eq!(packet.namespace, "/chat");
+        assert_eq!(packet.ack_id, Some(12));
+        let (name, args) = packet.event_parts().unwrap();
+        assert_eq!(name, "msg");
+        assert_eq!(args, [json!({"text": "hi"})]);
+
+        assert!(Packet::decode("9").is_err());
+        assert!(Packet::decode("2[oops").is_err());
+    }
+
+    #[test]
+    fn test_session_handshake_and_ping() {
+        let mut session = SocketIoSession::new();
+        let (event, reply) = session
+            .receive(r#"0{"sid":"e1","pingInterval":25000,"pingTimeout":20000}"#)
+            .unwrap();
+        assert!(matches!(event, Some(SocketIoEvent::Opened(_))));
+        assert!(reply.is_none());
+        assert_eq!(session.connect("/", None), "40");
+        assert_eq!(
+            session.connect("/admin", Some(json!({"token": "t"}))),
+            r#"40/admin,{"token":"t"}"#
+        );
+
+        assert_eq!(session.receive("2").unwrap(), (None, Some("3".to_string())));
+        assert_eq!(
+            session.receive(r#"40{"sid":"s1"}"#).unwrap().0,
+            Some(SocketIoEvent::Connected {
+                namespace: "/".to_string(),
+                sid: Some("s1".to_string())
+            })
+        );
+        assert_eq!(
+            session
+                .receive(r#"44/admin,{"message":"Not authorized"}"#)
+                .unwrap()
+                .0,
+            Some(SocketIoEvent::ConnectError {
+                namespace: "/admin".to_string(),
+                message: "Not authorized".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_session_events_and_acks() {
+        let mut session = SocketIoSession::new();
+        let (frame, ack_id) = session.emit("/", "join", vec![json!("room1")], true);
+        assert_eq!(frame, r#"420["join","room1"]"#);
+        assert_eq!(ack_id, Some(0));
+        assert_eq!(session.pending_acks(), 1);
+
+        assert_eq!(
+            session.receive(r#"430[{"joined":true}]"#).unwrap().0,
+            Some(SocketIoEvent::Ack {
+                namespace: "/".to_string(),
+                event: Some("join".to_string()),
+                args: vec![json!({"joined": true})]
+            })
+        );
+        assert_eq!(session.pending_acks(), 0);
+
+        assert_eq!(
+            session.receive(r#"427["ping?",1]"#).unwrap().0,
+            Some(SocketIoEvent::Event {
+                namespace: "/".to_string(),
+                name: "ping?".to_string(),
+                args: vec![json!(1)],
+                ack_id: Some(7)
+            })
+        );
+        assert_eq!(session.ack("/", 7, vec![json!("pong")]), r#"437["pong"]"#);
+        assert_eq!(session.receive("1").unwrap().0, Some(SocketIoEvent::Closed));
+    }
+}
@@ -0,0 +1,360 @@
+//! Spec drift: where live responses differ from the schemas an imported
+//! OpenAPI spec declares for them, collected per endpoint.
+
+use crate::utils::openapi::{schema_type, ApiSpec, Operation};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// Nesting limit when walking a response and its (possibly recursive) schema
+const MAX_DRIFT_DEPTH: usize = 16;
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DriftKind {
+    /// The response has a field its schema does not list
+    UndocumentedField,
+    /// The JSON type differs from the declared one
+    TypeMismatch { expected: String, actual: String },
+    /// The spec documents no response for this status code
+    UndocumentedStatus(u16),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Drift {
+    /// Location in the body, e.g. `$.items[].owner.name` (`[]` stands for
+    /// every array element)
+    pub path: String,
+    pub kind: DriftKind,
+}
+
+impl Drift {
+    pub fn describe(&self) -> String {
+        match &self.kind {
+            DriftKind::UndocumentedField => format!("{} is not in the schema", self.path),
+            DriftKind::TypeMismatch { expected, actual } => {
+                format!("{} is {actual}, the schema says {expected}", self.path)
+            }
+            DriftKind::UndocumentedStatus(status) => {
+                format!("Status {status} is not documented")
+            }
+        }
+    }
+}
+
+/// Compare one response with what `operation` documents for its status
+pub fn check_response(
+    spec: &ApiSpec,
+    operation: &Operation,
+    status: u16,
+    body: &str,
+) -> Vec<Drift> {
+    let Some(response) = operation.response_for(status) else {
+        return vec![Drift {
+            path: "$".to_string(),
+            kind: DriftKind::UndocumentedStatus(status),
+        }];
+    };
+    let Some(schema) = &response.schema else {
+        return Vec::new();
+    };
+    if body.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let mut drifts = Vec::new();
+    match serde_json::from_str::<Value>(body) {
+        Ok(value) => compare(spec, schema, &value, "$", 0, &mut drifts),
+        Err(_) => drifts.push(Drift {
+            path: "$".to_string(),
+            kind: DriftKind::TypeMismatch {
+                expected: "JSON".to_string(),
+                actual: "not JSON".to_string(),
+            },
+        }),
+    }
+    drifts.sort();
+    drifts.dedup();
+    drifts
+}
+
+fn compare(
+    spec: &ApiSpec,
+    schema: &Value,
+    value: &Value,
+    path: &str,
+    depth: usize,
+    drifts: &mut Vec<Drift>,
+) {
+    let schema = spec.resolve(schema);
+    if depth > MAX_DRIFT_DEPTH || (value.is_null() && is_nullable(schema)) {
+        return;
+    }
+
+    // The value has to fit one of the alternatives - report the closest one
+    if let Some(branches) = ["oneOf", "anyOf"]
+        .iter()
+        .find_map(|key| schema.get(key).and_then(Value::as_array))
+    {
+        let closest = branches
+            .iter()
+            .map(|branch| {
+                let mut found = Vec::new();
+                compare(spec, branch, value, path, depth + 1, &mut found);
+                found
+            })
+            .min_by_key(Vec::len);
+        drifts.extend(closest.unwrap_or_default());
+        return;
+    }
+
+    let mut properties = BTreeMap::new();
+    let mut additional = Additional::Closed;
+    let declared = collect_object(spec, schema, &mut properties, &mut additional, depth);
+    let expected = schema_type(schema).or_else(|| {
+        (declared || !properties.is_empty() || schema.get("allOf").is_some()).then_some("object")
+    });
+    let Some(expected) = expected else {
+        // No type declared - anything goes
+        return;
+    };
+    let actual = json_type(value);
+    if expected != actual && !(expected == "number" && actual == "integer") {
+        drifts.push(Drift {
+            path: path.to_string(),
+            kind: DriftKind::TypeMismatch {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            },
+        });
+        return;
+    }
+
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                let field_path = format!("{path}.{key}");
+                match (properties.get(key.as_str()), &additional) {
+                    (Some(field_schema), _) | (None, Additional::Schema(field_schema)) => {
+                        compare(spec, field_schema, field, &field_path, depth + 1, drifts)
+                    }
+                    (None, Additional::Open) => {}
+                    (None, Additional::Closed) => drifts.push(Drift {
+                        path: field_path,
+                        kind: DriftKind::UndocumentedField,
+                    }),
+                }
+            }
+        }
+        Value::Array(items) => {
+            if let Some(item_schema) = schema.get("items") {
+                let item_path = format!("{path}[]");
+                for item in items {
+                    compare(spec, item_schema, item, &item_path, depth + 1, drifts);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// What an object schema says about fields it does not list
+enum Additional<'a> {
+    Closed,
+    Open,
+    Schema(&'a Value),
+}
+
+/// Gather the properties of an object schema, including those of its
+/// `allOf` parts; returns whether any part declares object structure
+fn collect_object<'a>(
+    spec: &'a ApiSpec,
+    schema: &'a Value,
+    properties: &mut BTreeMap<&'a str, &'a Value>,
+    additional: &mut Additional<'a>,
+    depth: usize,
+) -> bool {
+    let schema = spec.resolve(schema);
+    if depth > MAX_DRIFT_DEPTH {
+        return false;
+    }
+    let mut declared = false;
+    if let Some(fields) = schema.get("properties").and_then(Value::as_object) {
+        declared = true;
+        for (name, field) in fields {
+            properties.insert(name.as_str(), field);
+        }
+    }
+    match schema.get("additionalProperties") {
+        Some(Value::Bool(true)) => *additional = Additional::Open,
+        Some(field) if field.is_object() => {
+            if matches!(additional, Additional::Closed) {
+                *additional = Additional::Schema(field);
+            }
+        }
+        _ => {}
+    }
+    for part in schema
+        .get("allOf")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        declared |= collect_object(spec, part, properties, additional, depth + 1);
+    }
+    declared
+}
+
+fn is_nullable(schema: &Value) -> bool {
+    schema.get("nullable").and_then(Value::as_bool) == Some(true)
+        || schema
+            .get("type")
+            .and_then(Value::as_array)
+            .is_some_and(|types| types.iter().any(|name| name.as_str() == Some("null")))
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(number) if number.is_i64() || number.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Drift found for one endpoint across all checked responses
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EndpointDrift {
+    /// Responses compared
+    pub checks: usize,
+    /// Each difference and the number of responses that showed it
+    pub drifts: BTreeMap<Drift, usize>,
+}
+
+/// Drift accumulated per endpoint (`GET /pets/{id}`) over a session
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DriftReport {
+    endpoints: BTreeMap<String, EndpointDrift>,
+}
+
+impl DriftReport {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn record(&mut self, endpoint: &str, drifts: Vec<Drift>) {
+        let entry = self.endpoints.entry(endpoint.to_string()).or_default();
+        entry.checks += 1;
+        for drift in drifts {
+            *entry.drifts.entry(drift).or_default() += 1;
+        }
+    }
+
+    pub fn endpoints(&self) -> impl Iterator<Item = (&String, &EndpointDrift)> {
+        self.endpoints.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// Distinct differences over all endpoints
+    pub fn drift_count(&self) -> usize {
+        self.endpoints
+            .values()
+            .map(|endpoint| endpoint.drifts.len())
+            .sum()
+    }
+
+    pub fn clear(&mut self) {
+        self.endpoints.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = r##"{
+        "openapi": "3.0.3",
+        "info": {"title": "Pets", "version": "1"},
+        "paths": {
+            "/pets/{id}": {"get": {"responses": {
+                "200": {"content": {"application/json": {"schema": {"$ref": "#/components/schemas/Pet"}}}},
+                "404": {"description": "Not found"}
+            }}}
+        },
+        "components": {"schemas": {
+            "Named": {"type": "object", "properties": {"name": {"type": "string"}}},
+            "Pet": {
+                "allOf": [{"$ref": "#/components/schemas/Named"}],
+                "properties": {
+                    "id": {"type": "integer"},
+                    "weight": {"type": "number"},
+                    "owner": {"type": "string", "nullable": true},
+                    "tags": {"type": "array", "items": {"type": "string"}},
+                    "extra": {"type": "object", "additionalProperties": {"type": "integer"}}
+                }
+            }
+        }}
+    }"##;
+
+    fn drifts(status: u16, body: &str) -> Vec<String> {
+        let spec = ApiSpec::parse(SPEC).unwrap();
+        let operation = &spec.operations[0];
+        check_response(&spec, operation, status, body)
+            .iter()
+            .map(Drift::describe)
+            .collect()
+    }
+
+    #[test]
+    fn test_matching_response_has_no_drift() {
+        let body = r#"{"id": 1, "name": "Rex", "weight": 3, "owner": null, "tags": ["a"], "extra": {"x": 1}}"#;
+        assert!(drifts(200, body).is_empty());
+        // Documented without a schema
+        assert!(drifts(404, "Not found").is_empty());
+    }
+
+    #[test]
+    fn test_drift_is_reported() {
+        let body = r#"{"id": "1", "name": "Rex", "color": "brown", "tags": ["a", 2, 3], "extra": {"x": "y"}}"#;
+        assert_eq!(
+            drifts(200, body),
+            [
+                "$.color is not in the schema",
+                "$.extra.x is string, the schema says integer",
+                "$.id is string, the schema says integer",
+                "$.tags[] is integer, the schema says string",
+            ]
+        );
+        assert_eq!(drifts(500, "{}"), ["Status 500 is not documented"]);
+        assert_eq!(
+            drifts(200, "<html>"),
+            ["$ is not JSON, the schema says JSON"]
+        );
+    }
+
+    #[test]
+    fn test_report_accumulates_per_endpoint() {
+        let drift = Drift {
+            path: "$.color".to_string(),
+            kind: DriftKind::UndocumentedField,
+        };
+        let mut report = DriftReport::new();
+        report.record("GET /pets/{id}", vec![drift.clone()]);
+        report.record("GET /pets/{id}", vec![drift.clone()]);
+        report.record("GET /health", Vec::new());
+
+        let endpoints: Vec<_> = report.endpoints().collect();
+        assert_eq!(endpoints.len(), 2);
+        let (_, pets) = endpoints[1];
+        assert_eq!(pets.checks, 2);
+        assert_eq!(pets.drifts[&drift], 2);
+        assert_eq!(report.drift_count(), 1);
+
+        report.clear();
+        assert!(report.is_empty());
+    }
+}
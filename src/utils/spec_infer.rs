@@ -0,0 +1,565 @@
+//! Contract stubs: an OpenAPI 3 skeleton inferred from recorded traffic.
+//!
+//! The history entries sent to one origin are grouped by method and path,
+//! numbers and UUIDs in a path becoming parameters (`/users/42` →
+//! `/users/{userId}`). Each operation lists the query parameters seen, the
+//! request body schema and one response per status, with schemas merged
+//! from every sample body.
+
+use crate::errors::AppError;
+use crate::http::response::header_value;
+use crate::models::HistoryEntry;
+use crate::utils::links::{looks_like_id, origin};
+use crate::utils::status_codes::reason_phrase;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+use std::collections::BTreeMap;
+
+/// Version of the generated document
+const OPENAPI_VERSION: &str = "3.0.3";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Kind {
+    /// No sample yet, or only `null`
+    Unknown,
+    Bool,
+    Integer,
+    Number,
+    String(Option<&'static str>),
+    Array(Box<Schema>),
+    /// Properties with how many samples had them, and the sample count
+    Object(BTreeMap<String, (Schema, usize)>, usize),
+    /// Samples of different types
+    Any,
+}
+
+/// JSON schema merged from sample values
+#[derive(Debug, Clone, PartialEq)]
+struct Schema {
+    kind: Kind,
+    nullable: bool,
+}
+
+impl Schema {
+    fn unknown() -> Self {
+        Self {
+            kind: Kind::Unknown,
+            nullable: false,
+        }
+    }
+
+    fn of(value: &Value) -> Self {
+        let kind = match value {
+            Value::Null => {
+                return Self {
+                    kind: Kind::Unknown,
+                    nullable: true,
+                }
+            }
+            Value::Bool(_) => Kind::Bool,
+            Value::Number(number) if number.is_i64() || number.is_u64() => Kind::Integer,
+            Value::Number(_) => Kind::Number,
+            Value::String(text) => Kind::String(string_format(text)),
+            Value::Array(items) => Kind::Array(Box::new(
+                items
+                    .iter()
+                    .fold(Self::unknown(), |schema, item| schema.merge(Self::of(item))),
+            )),
+            Value::Object(fields) => Kind::Object(
+                fields
+                    .iter()
+                    .map(|(key, value)| (key.clone(), (Self::of(value), 1)))
+                    .collect(),
+                1,
+            ),
+        };
+        Self {
+            kind,
+            nullable: false,
+        }
+    }
+
+    fn merge(self, other: Self) -> Self {
+        let nullable = self.nullable || other.nullable;
+        let kind = match (self.kind, other.kind) {
+            (Kind::Unknown, kind) | (kind, Kind::Unknown) => kind,
+            (Kind::Integer, Kind::Number) | (Kind::Number, Kind::Integer) => Kind::Number,
+            (Kind::String(first), Kind::String(second)) => {
+                Kind::String(if first == second { first } else { None })
+            }
+            (Kind::Array(first), Kind::Array(second)) => {
+                Kind::Array(Box::new(first.merge(*second)))
+            }
+            (Kind::Object(mut properties, samples), Kind::Object(others, other_samples)) => {
+                for (key, (schema, seen)) in others {
+                    let merged = match properties.remove(&key) {
+                        Some((existing, count)) => (existing.merge(schema), count + seen),
+                        None => (schema, seen),
+                    };
+                    properties.insert(key, merged);
+                }
+                Kind::Object(properties, samples + other_samples)
+            }
+            (first, second) if first == second => first,
+            _ => Kind::Any,
+        };
+        Self { kind, nullable }
+    }
+
+    fn to_json(&self) -> Value {
+        let mut schema = match &self.kind {
+            Kind::Unknown | Kind::Any => json!({}),
+            Kind::Bool => json!({ "type": "boolean" }),
+            Kind::Integer => json!({ "type": "integer" }),
+            Kind::Number => json!({ "type": "number" }),
+            Kind::String(None) => json!({ "type": "string" }),
+            Kind::String(Some(format)) => json!({ "type": "string", "format": format }),
+            Kind::Array(items) => json!({ "type": "array", "items": items.to_json() }),
+            Kind::Object(properties, samples) => {
+                let required: Vec<&String> = properties
+                    .iter()
+                    .filter(|(_, (_, seen))| seen == samples)
+                    .map(|(key, _)| key)
+                    .collect();
+                let mut schema = json!({
+                    "type": "object",
+                    "properties": properties
+                        .iter()
+                        .map(|(key, (schema, _))| (key.clone(), schema.to_json()))
+                        .collect::<Map<String, Value>>(),
+                });
+                if !required.is_empty() {
+                    schema["required"] = json!(required);
+                }
+                schema
+            }
+        };
+        if self.nullable {
+            schema["nullable"] = json!(true);
+        }
+        schema
+    }
+}
+
+// Formats worth declaring for a string sample
+fn string_format(text: &str) -> Option<&'static str> {
+    if chrono::DateTime::parse_from_rfc3339(text).is_ok() {
+        Some("date-time")
+    } else if is_uuid(text) {
+        Some("uuid")
+    } else {
+        None
+    }
+}
+
+fn is_uuid(text: &str) -> bool {
+    let groups: Vec<&str> = text.split('-').collect();
+    groups.len() == 5
+        && groups
+            .iter()
+            .zip([8, 4, 4, 4, 12])
+            .all(|(group, len)| group.len() == len && group.chars().all(|c| c.is_ascii_hexdigit()))
+}
+
+/// Schema of a body; JSON is described field by field, anything else is a
+/// string of the declared content type
+#[derive(Debug, Clone, PartialEq)]
+struct Body {
+    content_type: String,
+    schema: Schema,
+}
+
+impl Body {
+    fn of(headers: &[(String, String)], body: &str) -> Option<Self> {
+        if body.trim().is_empty() {
+            return None;
+        }
+        let declared = header_value(headers, "Content-Type")
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .filter(|value| !value.is_empty());
+        Some(match serde_json::from_str::<Value>(body) {
+            Ok(value) => Self {
+                content_type: declared.unwrap_or_else(|| "application/json".to_string()),
+                schema: Schema::of(&value),
+            },
+            Err(_) => Self {
+                content_type: declared.unwrap_or_else(|| "text/plain".to_string()),
+                schema: Schema {
+                    kind: Kind::String(None),
+                    nullable: false,
+                },
+            },
+        })
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            content_type: self.content_type,
+            schema: self.schema.merge(other.schema),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        json!({ &self.content_type: { "schema": self.schema.to_json() } })
+    }
+}
+
+fn merge_body(existing: Option<Body>, sample: Option<Body>) -> Option<Body> {
+    match (existing, sample) {
+        (Some(existing), Some(sample)) => Some(existing.merge(sample)),
+        (existing, sample) => existing.or(sample),
+    }
+}
+
+/// What was seen for one method and path
+#[derive(Debug, Default)]
+struct Observed {
+    samples: usize,
+    /// Path parameters with the schema of their values
+    path_parameters: Vec<(String, Schema)>,
+    /// Query parameters with their value schema and how often they were sent
+    query: BTreeMap<String, (Schema, usize)>,
+    request_body: Option<Body>,
+    responses: BTreeMap<u16, Option<Body>>,
+}
+
+/// An inferred OpenAPI document
+#[derive(Debug, Serialize)]
+pub struct SpecStub {
+    openapi: &'static str,
+    info: Value,
+    servers: Vec<Value>,
+    paths: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl SpecStub {
+    /// Number of method + path pairs described
+    pub fn operation_count(&self) -> usize {
+        self.paths.values().map(BTreeMap::len).sum()
+    }
+
+    pub fn to_json(&self) -> Result<String, AppError> {
+        serde_json::to_string_pretty(self).map_err(|e| AppError::ParseError(e.to_string()))
+    }
+}
+
+/// Infer the API of `origin` (`https://host[:port]`) from the history
+/// entries sent to it; entries to other origins are left out
+pub fn infer_spec(origin_url: &str, entries: &[HistoryEntry]) -> SpecStub {
+    let mut operations: BTreeMap<(String, String), Observed> = BTreeMap::new();
+    let mut recorded = 0;
+    for entry in entries {
+        let url = entry.request.url.trim();
+        if origin(url) != Some(origin_url) {
+            continue;
+        }
+        recorded += 1;
+        let rest = url[origin_url.len()..]
+            .split('#')
+            .next()
+            .unwrap_or_default();
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let (template, values) = path_template(path);
+
+        let observed = operations
+            .entry((template, entry.request.method.to_string().to_lowercase()))
+            .or_default();
+        observed.samples += 1;
+        for (index, (name, value)) in values.into_iter().enumerate() {
+            let schema = parameter_schema(&value);
+            match observed.path_parameters.get_mut(index) {
+                Some((_, existing)) => *existing = existing.clone().merge(schema),
+                None => observed.path_parameters.push((name, schema)),
+            }
+        }
+        let mut seen = Vec::new();
+        for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+            let name = name.into_owned();
+            let schema = parameter_schema(&value);
+            let repeated = seen.contains(&name);
+            let (existing, count) = observed
+                .query
+                .entry(name.clone())
+                .or_insert_with(|| (Schema::unknown(), 0));
+            *existing = existing.clone().merge(schema);
+            if !repeated {
+                *count += 1;
+                seen.push(name);
+            }
+        }
+        let request_body = entry
+            .request
+            .body
+            .as_deref()
+            .and_then(|body| Body::of(&entry.request.headers, body));
+        observed.request_body = merge_body(observed.request_body.take(), request_body);
+        if let Some(response) = &entry.response {
+            let sample = Body::of(&response.headers, &response.body);
+            let existing = observed.responses.remove(&response.status).flatten();
+            observed
+                .responses
+                .insert(response.status, merge_body(existing, sample));
+        }
+    }
+
+    let mut paths: BTreeMap<String, BTreeMap<String, Value>> = BTreeMap::new();
+    for ((path, method), observed) in operations {
+        let operation = describe_operation(&path, &method, &observed);
+        paths.entry(path).or_default().insert(method, operation);
+    }
+    let host = origin_url
+        .split_once("://")
+        .map_or(origin_url, |(_, host)| host);
+    SpecStub {
+        openapi: OPENAPI_VERSION,
+        info: json!({
+            "title": format!("{host} (inferred)"),
+            "version": "0.1.0",
+            "description": format!(
+                "Inferred from {recorded} recorded requests on {}",
+                chrono::Local::now().format("%Y-%m-%d")
+            ),
+        }),
+        servers: vec![json!({ "url": origin_url })],
+        paths,
+    }
+}
+
+fn describe_operation(path: &str, method: &str, observed: &Observed) -> Value {
+    let mut parameters: Vec<Value> = observed
+        .path_parameters
+        .iter()
+        .map(|(name, schema)| {
+            json!({ "name": name, "in": "path", "required": true, "schema": schema.to_json() })
+        })
+        .collect();
+    parameters.extend(observed.query.iter().map(|(name, (schema, count))| {
+        json!({
+            "name": name,
+            "in": "query",
+            "required": *count == observed.samples,
+            "schema": schema.to_json(),
+        })
+    }));
+
+    let mut responses: Map<String, Value> = observed
+        .responses
+        .iter()
+        .map(|(status, body)| {
+            let mut response = json!({ "description": reason_phrase(*status) });
+            if let Some(body) = body {
+                response["content"] = body.to_json();
+            }
+            (status.to_string(), response)
+        })
+        .collect();
+    if responses.is_empty() {
+        responses.insert(
+            "default".to_string(),
+            json!({ "description": "No response was recorded" }),
+        );
+    }
+
+    let mut operation = json!({
+        "summary": format!("{} {path}", method.to_uppercase()),
+        "responses": responses,
+    });
+    if !parameters.is_empty() {
+        operation["parameters"] = json!(parameters);
+    }
+    if let Some(body) = &observed.request_body {
+        operation["requestBody"] = json!({ "content": body.to_json() });
+    }
+    operation
+}
+
+/// `/users/42/posts` → `/users/{userId}/posts` and the parameter values
+fn path_template(path: &str) -> (String, Vec<(String, String)>) {
+    let mut template = String::new();
+    let mut values: Vec<(String, String)> = Vec::new();
+    let mut previous: Option<&str> = None;
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        template.push('/');
+        if looks_like_id(segment) {
+            let base = match previous {
+                Some(resource) => format!("{}Id", singular(resource)),
+                None => "id".to_string(),
+            };
+            let mut name = base.clone();
+            let mut number = 2;
+            while values.iter().any(|(existing, _)| *existing == name) {
+                name = format!("{base}{number}");
+                number += 1;
+            }
+            template.push_str(&format!("{{{name}}}"));
+            values.push((name, segment.to_string()));
+            previous = None;
+        } else {
+            template.push_str(segment);
+            previous = Some(segment);
+        }
+    }
+    if template.is_empty() {
+        template.push('/');
+    }
+    (template, values)
+}
+
+// `users` → `user`, `categories` → `category`, keeping camelCase names valid
+fn singular(resource: &str) -> String {
+    let word: String = resource
+        .split(['-', '_', '.'])
+        .enumerate()
+        .map(|(index, part)| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) if index > 0 => first.to_uppercase().chain(chars).collect(),
+                _ => part.to_string(),
+            }
+        })
+        .collect();
+    if let Some(stem) = word.strip_suffix("ies") {
+        format!("{stem}y")
+    } else if word.len() > 1 && word.ends_with('s') && !word.ends_with("ss") {
+        word[..word.len() - 1].to_string()
+    } else {
+        word
+    }
+}
+
+// Schema of a path or query value: numbers and booleans are recognized
+fn parameter_schema(value: &str) -> Schema {
+    let kind = if value.parse::<i64>().is_ok() {
+        Kind::Integer
+    } else if value.parse::<f64>().is_ok() {
+        Kind::Number
+    } else if value == "true" || value == "false" {
+        Kind::Bool
+    } else {
+        Kind::String(string_format(value))
+    };
+    Schema {
+        kind,
+        nullable: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{HttpMethod, Request, SavedResponse};
+    use crate::utils::openapi::ApiSpec;
+
+    fn entry(method: &str, url: &str, status: u16, body: &str) -> HistoryEntry {
+        let mut entry = HistoryEntry::new(Request::new(method, url), url.to_string());
+        entry.response = Some(SavedResponse::new(status, body));
+        entry
+    }
+
+    #[test]
+    fn test_path_templates() {
+        assert_eq!(
+            path_template("/users/42/posts/7"),
+            (
+                "/users/{userId}/posts/{postId}".to_string(),
+                vec![
+                    ("userId".to_string(), "42".to_string()),
+                    ("postId".to_string(), "7".to_string()),
+                ]
+            )
+        );
+        assert_eq!(path_template("/categories/3").0, "/categories/{categoryId}");
+        assert_eq!(
+            path_template("/order-items/3").0,
+            "/order-items/{orderItemId}"
+        );
+        assert_eq!(path_template("/42/42").0, "/{id}/{id2}");
+        assert_eq!(path_template("").0, "/");
+    }
+
+    #[test]
+    fn test_merged_schemas() {
+        let first = Schema::of(&serde_json::json!({"id": 1, "name": "Ada", "tags": []}));
+        let second = Schema::of(&serde_json::json!({"id": 2.5, "name": null, "tags": ["x"]}));
+        assert_eq!(
+            first.merge(second).to_json(),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "id": { "type": "number" },
+                    "name": { "type": "string", "nullable": true },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["id", "name", "tags"],
+            })
+        );
+
+        let partial = Schema::of(&serde_json::json!({"id": 1})).merge(Schema::of(
+            &serde_json::json!({"id": "a", "at": "2024-05-01T10:00:00Z"}),
+        ));
+        assert_eq!(
+            partial.to_json(),
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "at": { "type": "string", "format": "date-time" },
+                    "id": {},
+                },
+                "required": ["id"],
+            })
+        );
+    }
+
+    #[test]
+    fn test_infer_spec_from_history() {
+        let entries = vec![
+            entry(
+                "GET",
+                "https://api.example.com/users/1?fields=name",
+                200,
+                r#"{"id": 1}"#,
+            ),
+            entry(
+                "GET",
+                "https://api.example.com/users/2",
+                404,
+                r#"{"error": "gone"}"#,
+            ),
+            entry(
+                "GET",
+                "https://api.example.com/users/me",
+                200,
+                r#"{"id": 9}"#,
+            ),
+            entry("GET", "https://other.example.com/users/1", 200, "{}"),
+        ];
+        let mut created = entry("POST", "https://api.example.com/users", 201, "created");
+        created.request.set_body(r#"{"name": "Ada"}"#);
+        let mut entries = entries;
+        entries.push(created);
+
+        let stub = infer_spec("https://api.example.com", &entries);
+        assert_eq!(stub.operation_count(), 3);
+        let json = stub.to_json().unwrap();
+        assert!(json.contains("\"text/plain\""));
+
+        // The stub is a valid spec the API explorer can import
+        let spec = ApiSpec::parse(&json).unwrap();
+        assert_eq!(spec.servers, ["https://api.example.com"]);
+        let operation = spec
+            .find_operation(HttpMethod::GET, "https://api.example.com/users/5")
+            .unwrap();
+        assert_eq!(operation.path, "/users/{userId}");
+        let parameters: Vec<(&str, bool)> = operation
+            .parameters
+            .iter()
+            .map(|parameter| (parameter.name.as_str(), parameter.required))
+            .collect();
+        assert_eq!(parameters, [("userId", true), ("fields", false)]);
+        assert!(operation.response_for(404).is_some());
+        assert!(spec
+            .find_operation(HttpMethod::POST, "https://api.example.com/users")
+            .is_some_and(|operation| operation.request_body.is_some()));
+    }
+}
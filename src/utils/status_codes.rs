@@ -0,0 +1,461 @@
+/// Explanation of an HTTP status code shown when the user clicks the
+/// response status.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatusExplanation {
+    pub code: u16,
+    pub reason: &'static str,
+    /// Specification section that defines the code (e.g. "RFC 9110 §15.5.5")
+    pub reference: &'static str,
+    pub meaning: &'static str,
+    pub likely_causes: &'static [&'static str],
+    pub next_steps: &'static [&'static str],
+}
+
+/// Look up the explanation for a status code.
+///
+/// Codes that are not in the table fall back to a generic explanation of
+/// their class (1xx-5xx). Returns `None` for codes outside 100..=599.
+pub fn explain_status(code: u16) -> Option<StatusExplanation> {
+    known_status(code).or_else(|| class_fallback(code))
+}
+
+/// Get the reason phrase for a status code ("Not Found" for 404).
+pub fn reason_phrase(code: u16) -> &'static str {
+    explain_status(code).map(|e| e.reason).unwrap_or("Unknown")
+}
+
+fn known_status(code: u16) -> Option<StatusExplanation> {
+    let (reason, reference, meaning, likely_causes, next_steps): (
+        &'static str,
+        &'static str,
+        &'static str,
+        &'static [&'static str],
+        &'static [&'static str],
+    ) = match code {
+        100 => (
+            "Continue",
+            "RFC 9110 §15.2.1",
+            "The server received the request headers and the client should send the body.",
+            &["The request carried an \"Expect: 100-continue\" header"],
+            &["Nothing to do - the final status follows once the body is sent"],
+        ),
+        101 => (
+            "Switching Protocols",
+            "RFC 9110 §15.2.2",
+            "The server agrees to switch to the protocol named in the Upgrade header.",
+            &["The request asked for a WebSocket or HTTP/2 upgrade"],
+            &["Use a client that speaks the upgraded protocol to continue"],
+        ),
+        200 => (
+            "OK",
+            "RFC 9110 §15.3.1",
+            "The request succeeded and the body contains the requested representation.",
+            &["Everything worked as expected"],
+            &["Check the body matches what you expected"],
+        ),
+        201 => (
+            "Created",
+            "RFC 9110 §15.3.2",
+            "The request succeeded and a new resource was created.",
+            &["A POST or PUT created a resource"],
+            &["Look for a Location header pointing at the new resource"],
+        ),
+        202 => (
+            "Accepted",
+            "RFC 9110 §15.3.3",
+            "The request was accepted for processing, but processing is not finished.",
+            &["The server queued the work as a background job"],
+            &[
+                "Poll the status URL returned by the server, if any",
+                "Do not assume the work has succeeded yet",
+            ],
+        ),
+        204 => (
+            "No Content",
+            "RFC 9110 §15.3.5",
+            "The request succeeded and there is intentionally no response body.",
+            &["Typical for DELETE or PUT requests that return nothing"],
+            &["An empty body is expected - this is not an error"],
+        ),
+        206 => (
+            "Partial Content",
+            "RFC 9110 §15.3.7",
+            "The server is returning only the byte range(s) asked for in the Range header.",
+            &["The request contained a Range header"],
+            &["Check the Content-Range header to see which bytes were returned"],
+        ),
+        301 => (
+            "Moved Permanently",
+            "RFC 9110 §15.4.2",
+            "The resource has a new permanent URL given in the Location header.",
+            &[
+                "The API moved to a new path or host",
+                "http:// was redirected to https://",
+            ],
+            &["Update the request URL to the Location header value"],
+        ),
+        302 => (
+            "Found",
+            "RFC 9110 §15.4.3",
+            "The resource is temporarily available at the URL in the Location header.",
+            &["A login page or load balancer redirected the request"],
+            &["Follow the Location header, or check whether you need to authenticate"],
+        ),
+        303 => (
+            "See Other",
+            "RFC 9110 §15.4.4",
+            "The result is available at another URL that should be fetched with GET.",
+            &["A form POST redirected to a result page"],
+            &["Send a GET to the URL in the Location header"],
+        ),
+        304 => (
+            "Not Modified",
+            "RFC 9110 §15.4.5",
+            "The cached copy is still valid, so the server sent no body.",
+            &["The request carried If-None-Match or If-Modified-Since headers"],
+            &["Remove the conditional headers to get the full body"],
+        ),
+        307 => (
+            "Temporary Redirect",
+            "RFC 9110 §15.4.8",
+            "Repeat the same request (same method and body) against the Location URL.",
+            &["The endpoint is temporarily served elsewhere"],
+            &["Resend the request to the Location header value"],
+        ),
+        308 => (
+            "Permanent Redirect",
+            "RFC 9110 §15.4.9",
+            "Like 301, but the method and body must not change when following it.",
+            &["The API moved permanently"],
+            &["Update the request URL to the Location header value"],
+        ),
+        400 => (
+            "Bad Request",
+            "RFC 9110 §15.5.1",
+            "The server could not understand the request because it is malformed.",
+            &[
+                "Invalid JSON in the request body",
+                "Missing or wrongly typed fields",
+                "Content-Type does not match the body",
+            ],
+            &[
+                "Validate the body JSON",
+                "Compare the fields with the API documentation",
+                "Read the error message in the response body",
+            ],
+        ),
+        401 => (
+            "Unauthorized",
+            "RFC 9110 §15.5.2",
+            "The request lacks valid authentication credentials.",
+            &[
+                "Missing Authorization header",
+                "Expired or mistyped token",
+                "Wrong authentication scheme (e.g. Basic vs Bearer)",
+            ],
+            &[
+                "Check the Authorization header value",
+                "Refresh or regenerate the token",
+                "Inspect the WWW-Authenticate response header",
+            ],
+        ),
+        403 => (
+            "Forbidden",
+            "RFC 9110 §15.5.4",
+            "The server understood who you are but refuses to allow the request.",
+            &[
+                "The account lacks the required role or scope",
+                "IP allow-list or CORS policy rejected the request",
+            ],
+            &[
+                "Verify the token's scopes or the user's permissions",
+                "Try with a more privileged account",
+            ],
+        ),
+        404 => (
+            "Not Found",
+            "RFC 9110 §15.5.5",
+            "The server has no resource at this URL.",
+            &[
+                "Typo in the path",
+                "Wrong base URL or API version prefix",
+                "The resource id does not exist (or was deleted)",
+            ],
+            &[
+                "Double-check the URL path and trailing slashes",
+                "List the collection to confirm the id exists",
+            ],
+        ),
+        405 => (
+            "Method Not Allowed",
+            "RFC 9110 §15.5.6",
+            "The resource exists but does not support this HTTP method.",
+            &["Using POST on a read-only endpoint, or GET on a write-only one"],
+            &["Check the Allow response header for the supported methods"],
+        ),
+        406 => (
+            "Not Acceptable",
+            "RFC 9110 §15.5.7",
+            "The server cannot produce a response matching the Accept headers.",
+            &["The Accept header asks for a format the server does not offer"],
+            &["Relax or remove the Accept header"],
+        ),
+        408 => (
+            "Request Timeout",
+            "RFC 9110 §15.5.9",
+            "The server gave up waiting for the client to finish sending the request.",
+            &["Slow network or a very large upload"],
+            &["Retry the request", "Check the network connection"],
+        ),
+        409 => (
+            "Conflict",
+            "RFC 9110 §15.5.10",
+            "The request conflicts with the current state of the resource.",
+            &[
+                "Creating a resource that already exists",
+                "Concurrent modification of the same resource",
+            ],
+            &["Fetch the current state and retry with up-to-date data"],
+        ),
+        410 => (
+            "Gone",
+            "RFC 9110 §15.5.11",
+            "The resource was removed permanently and will not come back.",
+            &["The endpoint or resource was deleted or deprecated"],
+            &["Look for a replacement endpoint in the API changelog"],
+        ),
+        411 => (
+            "Length Required",
+            "RFC 9110 §15.5.12",
+            "The server requires a Content-Length header.",
+            &["The body was sent without a Content-Length"],
+            &["Send the body with an explicit Content-Length"],
+        ),
+        412 => (
+            "Precondition Failed",
+            "RFC 9110 §15.5.13",
+            "A conditional header (If-Match, If-Unmodified-Since) did not match.",
+            &["The resource changed since you last fetched its ETag"],
+            &["Fetch the resource again and retry with the new ETag"],
+        ),
+        413 => (
+            "Content Too Large",
+            "RFC 9110 §15.5.14",
+            "The request body is larger than the server is willing to process.",
+            &["Uploading a file or payload above the server limit"],
+            &["Reduce the body size or use a chunked/multipart upload"],
+        ),
+        414 => (
+            "URI Too Long",
+            "RFC 9110 §15.5.15",
+            "The URL is longer than the server is willing to interpret.",
+            &["Too many or too large query parameters"],
+            &["Move the parameters into a POST body"],
+        ),
+        415 => (
+            "Unsupported Media Type",
+            "RFC 9110 §15.5.16",
+            "The server does not accept the body format sent.",
+            &["Missing or wrong Content-Type header"],
+            &["Set Content-Type to the format the API expects (often application/json)"],
+        ),
+        416 => (
+            "Range Not Satisfiable",
+            "RFC 9110 §15.5.17",
+            "The requested byte range lies outside the resource.",
+            &["The Range header asks for bytes beyond the end of the resource"],
+            &["Check the resource size in the Content-Range response header"],
+        ),
+        422 => (
+            "Unprocessable Content",
+            "RFC 9110 §15.5.21",
+            "The body is well-formed but contains semantically invalid data.",
+            &["Validation failed (e.g. invalid email, value out of range)"],
+            &["Read the validation errors in the response body and fix the fields"],
+        ),
+        428 => (
+            "Precondition Required",
+            "RFC 6585 §3",
+            "The server requires the request to be conditional.",
+            &["Updates must carry an If-Match header"],
+            &["Fetch the resource's ETag and send it in If-Match"],
+        ),
+        429 => (
+            "Too Many Requests",
+            "RFC 6585 §4",
+            "The client sent too many requests in a given amount of time.",
+            &["Rate limit exceeded"],
+            &[
+                "Wait for the time given in the Retry-After header",
+                "Reduce the request rate",
+            ],
+        ),
+        431 => (
+            "Request Header Fields Too Large",
+            "RFC 6585 §5",
+            "The request headers are too large for the server.",
+            &["Huge cookies or tokens in the headers"],
+            &["Remove unneeded headers or shrink the cookies"],
+        ),
+        500 => (
+            "Internal Server Error",
+            "RFC 9110 §15.6.1",
+            "The server hit an unexpected condition while handling the request.",
+            &[
+                "A bug or unhandled exception on the server",
+                "Input the server did not anticipate",
+            ],
+            &[
+                "Check the server logs",
+                "Retry with a minimal request to isolate the trigger",
+            ],
+        ),
+        501 => (
+            "Not Implemented",
+            "RFC 9110 §15.6.2",
+            "The server does not support the functionality required by the request.",
+            &["The HTTP method is not implemented on this server"],
+            &["Use a different method or endpoint"],
+        ),
+        502 => (
+            "Bad Gateway",
+            "RFC 9110 §15.6.3",
+            "A gateway or proxy got an invalid response from the upstream server.",
+            &[
+                "The upstream service crashed or is restarting",
+                "Proxy misconfiguration",
+            ],
+            &["Retry after a moment", "Check the upstream service health"],
+        ),
+        503 => (
+            "Service Unavailable",
+            "RFC 9110 §15.6.4",
+            "The server is temporarily unable to handle the request.",
+            &["Maintenance or overload"],
+            &["Honor the Retry-After header and try again later"],
+        ),
+        504 => (
+            "Gateway Timeout",
+            "RFC 9110 §15.6.5",
+            "A gateway or proxy did not get a timely response from the upstream server.",
+            &["The upstream service is slow or unreachable"],
+            &[
+                "Retry the request",
+                "Check whether the operation is unusually slow on the server",
+            ],
+        ),
+        505 => (
+            "HTTP Version Not Supported",
+            "RFC 9110 §15.6.6",
+            "The server does not support the HTTP version used in the request.",
+            &["Client and server disagree on the protocol version"],
+            &["Retry using HTTP/1.1"],
+        ),
+        _ => return None,
+    };
+
+    Some(StatusExplanation {
+        code,
+        reason,
+        reference,
+        meaning,
+        likely_causes,
+        next_steps,
+    })
+}
+
+fn class_fallback(code: u16) -> Option<StatusExplanation> {
+    let (reason, meaning, likely_causes, next_steps): (
+        &'static str,
+        &'static str,
+        &'static [&'static str],
+        &'static [&'static str],
+    ) = match code {
+        100..=199 => (
+            "Informational",
+            "An interim response - the final response is still to come.",
+            &["Protocol-level negotiation"],
+            &["Wait for the final response"],
+        ),
+        200..=299 => (
+            "Success",
+            "The request was received, understood and accepted.",
+            &["The request succeeded"],
+            &["Check the body for details"],
+        ),
+        300..=399 => (
+            "Redirection",
+            "Further action is needed to complete the request.",
+            &["The resource is available elsewhere"],
+            &["Look at the Location header"],
+        ),
+        400..=499 => (
+            "Client Error",
+            "The request contains an error on the client side.",
+            &["Malformed request, missing credentials or a wrong URL"],
+            &["Read the response body for the server's error message"],
+        ),
+        500..=599 => (
+            "Server Error",
+            "The server failed to fulfill an apparently valid request.",
+            &["A problem on the server side"],
+            &["Retry later and check the server logs"],
+        ),
+        _ => return None,
+    };
+
+    Some(StatusExplanation {
+        code,
+        reason,
+        reference: "RFC 9110 §15",
+        meaning,
+        likely_causes,
+        next_steps,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_explain_known_status() {
+        let explanation = explain_status(404).unwrap();
+        assert_eq!(explanation.code, 404);
+        assert_eq!(explanation.reason, "Not Found");
+        assert_eq!(explanation.reference, "RFC 9110 §15.5.5");
+        assert!(!explanation.likely_causes.is_empty());
+        assert!(!explanation.next_steps.is_empty());
+    }
+
+    #[test]
+    fn test_explain_rfc_6585_status() {
+        let explanation = explain_status(429).unwrap();
+        assert_eq!(explanation.reason, "Too Many Requests");
+        assert!(explanation.reference.starts_with("RFC 6585"));
+    }
+
+    #[test]
+    fn test_explain_unknown_status_falls_back_to_class() {
+        let explanation = explain_status(299).unwrap();
+        assert_eq!(explanation.code, 299);
+        assert_eq!(explanation.reason, "Success");
+
+        let explanation = explain_status(599).unwrap();
+        assert_eq!(explanation.reason, "Server Error");
+    }
+
+    #[test]
+    fn test_explain_out_of_range_status() {
+        assert!(explain_status(0).is_none());
+        assert!(explain_status(99).is_none());
+        assert!(explain_status(600).is_none());
+    }
+
+    #[test]
+    fn test_reason_phrase() {
+        assert_eq!(reason_phrase(200), "OK");
+        assert_eq!(reason_phrase(503), "Service Unavailable");
+        assert_eq!(reason_phrase(0), "Unknown");
+    }
+}
@@ -0,0 +1,459 @@
+//! WebSocket (RFC 6455) client handshake and framing.
+//!
+//! Only the byte-level protocol lives here; the connection itself is in
+//! `http::websocket`.
+
+use crate::errors::AppError;
+use crate::utils::base64;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Largest message accepted from a server
+pub const MAX_MESSAGE_BYTES: usize = 16 * 1024 * 1024;
+
+/// GUID appended to the client key to derive `Sec-WebSocket-Accept`
+const ACCEPT_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opcode {
+    Continuation,
+    Text,
+    Binary,
+    Close,
+    Ping,
+    Pong,
+}
+
+impl Opcode {
+    fn from_u8(value: u8) -> Option<Self> {
+        Some(match value {
+            0x0 => Opcode::Continuation,
+            0x1 => Opcode::Text,
+            0x2 => Opcode::Binary,
+            0x8 => Opcode::Close,
+            0x9 => Opcode::Ping,
+            0xa => Opcode::Pong,
+            _ => return None,
+        })
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            Opcode::Continuation => 0x0,
+            Opcode::Text => 0x1,
+            Opcode::Binary => 0x2,
+            Opcode::Close => 0x8,
+            Opcode::Ping => 0x9,
+            Opcode::Pong => 0xa,
+        }
+    }
+
+    fn is_control(self) -> bool {
+        matches!(self, Opcode::Close | Opcode::Ping | Opcode::Pong)
+    }
+}
+
+/// A complete message (fragments already joined)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Binary(Vec<u8>),
+    Ping(Vec<u8>),
+    Pong(Vec<u8>),
+    /// Close code and reason, if the peer sent them
+    Close(Option<(u16, String)>),
+}
+
+/// Random bytes for handshake keys and frame masks (masking only has to be
+/// unpredictable to intermediaries, not cryptographically strong)
+fn random_u64() -> u64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|time| time.as_nanos())
+            .unwrap_or_default(),
+    );
+    hasher.finish()
+}
+
+/// A fresh `Sec-WebSocket-Key`
+pub fn handshake_key() -> String {
+    let mut key = random_u64().to_le_bytes().to_vec();
+    key.extend(random_u64().to_le_bytes());
+    base64::encode(&key)
+}
+
+/// `Sec-WebSocket-Accept` the server must answer for `key`
+pub fn accept_key(key: &str) -> String {
+    base64::encode(&sha1(format!("{key}{ACCEPT_GUID}").as_bytes()))
+}
+
+/// HTTP/1.1 upgrade request for `path` on `host`
+pub fn handshake_request(
+    host: &str,
+    path: &str,
+    key: &str,
+    protocols: &[&str],
+    headers: &[(String, String)],
+) -> String {
+    let mut request = format!(
+        "GET {path} HTTP/1.1\r\nHost: {host}\r\nUpgrade: websocket\r\nConnection: Upgrade\r\n\
+         Sec-WebSocket-Key: {key}\r\nSec-WebSocket-Version: 13\r\n"
+    );
+    if !protocols.is_empty() {
+        request.push_str(&format!(
+            "Sec-WebSocket-Protocol: {}\r\n",
+            protocols.join(", ")
+        ));
+    }
+    for (name, value) in headers {
+        request.push_str(&format!("{name}: {value}\r\n"));
+    }
+    request.push_str("\r\n");
+    request
+}
+
+/// Check the server's handshake response (status line and headers, without
+/// the blank line); returns the negotiated subprotocol
+pub fn verify_handshake(response: &str, key: &str) -> Result<Option<String>, AppError> {
+    let mut lines = response.lines();
+    let status_line = lines.next().unwrap_or_default();
+    let status = status_line.split_whitespace().nth(1).unwrap_or_default();
+    if status != "101" {
+        return Err(AppError::NetworkError(format!(
+            "WebSocket upgrade refused: {}",
+            status_line.trim()
+        )));
+    }
+    let headers: Vec<(&str, &str)> = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .collect();
+    let header = |name: &str| {
+        headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    };
+    if header("Sec-WebSocket-Accept") != Some(accept_key(key).as_str()) {
+        return Err(AppError::NetworkError(
+            "WebSocket upgrade failed: wrong Sec-WebSocket-Accept".to_string(),
+        ));
+    }
+    Ok(header("Sec-WebSocket-Protocol").map(str::to_string))
+}
+
+/// Encode one final frame; client frames must be masked (`mask` is `None`
+/// only for server frames, e.g. in tests)
+pub fn encode_frame(opcode: Opcode, payload: &[u8], mask: Option<[u8; 4]>) -> Vec<u8> {
+    let mut frame = Vec::with_capacity(payload.len() + 14);
+    frame.push(0x80 | opcode.as_u8());
+    let mask_bit = if mask.is_some() { 0x80 } else { 0 };
+    match payload.len() {
+        len @ 0..=125 => frame.push(mask_bit | len as u8),
+        len @ 126..=0xffff => {
+            frame.push(mask_bit | 126);
+            frame.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            frame.push(mask_bit | 127);
+            frame.extend((len as u64).to_be_bytes());
+        }
+    }
+    match mask {
+        Some(mask) => {
+            frame.extend(mask);
+            frame.extend(
+                payload
+                    .iter()
+                    .enumerate()
+                    .map(|(index, byte)| byte ^ mask[index % 4]),
+            );
+        }
+        None => frame.extend_from_slice(payload),
+    }
+    frame
+}
+
+/// Masked client frame with a random mask
+pub fn client_frame(opcode: Opcode, payload: &[u8]) -> Vec<u8> {
+    encode_frame(opcode, payload, Some((random_u64() as u32).to_le_bytes()))
+}
+
+/// Close frame payload: status code then UTF-8 reason
+pub fn close_payload(code: u16, reason: &str) -> Vec<u8> {
+    let mut payload = code.to_be_bytes().to_vec();
+    payload.extend_from_slice(reason.as_bytes());
+    payload
+}
+
+/// Splits received bytes into messages, joining fragmented ones; control
+/// frames may arrive between the fragments of a message
+#[derive(Debug, Default)]
+pub struct FrameDecoder {
+    buffer: Vec<u8>,
+    // Opcode and payload of a message still waiting for its final fragment
+    fragments: Option<(Opcode, Vec<u8>)>,
+}
+
+impl FrameDecoder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add received bytes and return every message completed by them
+    pub fn push(&mut self, data: &[u8]) -> Result<Vec<Message>, AppError> {
+        self.buffer.extend_from_slice(data);
+        let mut messages = Vec::new();
+        while let Some((fin, opcode, payload)) = self.next_frame()? {
+            if opcode.is_control() {
+                messages.push(control_message(opcode, payload)?);
+                continue;
+            }
+            let (opcode, payload) = match (opcode, self.fragments.take()) {
+                (Opcode::Continuation, Some((first, mut joined))) => {
+                    joined.extend(payload);
+                    (first, joined)
+                }
+                (Opcode::Continuation, None) => {
+                    return Err(protocol_error("continuation frame without a message"))
+                }
+                (_, Some(_)) => return Err(protocol_error("new message inside a fragmented one")),
+                (opcode, None) => (opcode, payload),
+            };
+            if payload.len() > MAX_MESSAGE_BYTES {
+                return Err(AppError::ParseError(format!(
+                    "WebSocket message exceeds the {MAX_MESSAGE_BYTES} byte limit"
+                )));
+            }
+            if !fin {
+                self.fragments = Some((opcode, payload));
+                continue;
+            }
+            messages.push(if opcode == Opcode::Text {
+                Message::Text(
+                    String::from_utf8(payload)
+                        .map_err(|_| protocol_error("text message is not UTF-8"))?,
+                )
+            } else {
+                Message::Binary(payload)
+            });
+        }
+        Ok(messages)
+    }
+
+    /// Take one complete frame off the buffer: (fin, opcode, unmasked payload)
+    fn next_frame(&mut self) -> Result<Option<(bool, Opcode, Vec<u8>)>, AppError> {
+        if self.buffer.len() < 2 {
+            return Ok(None);
+        }
+        let fin = self.buffer[0] & 0x80 != 0;
+        let opcode = Opcode::from_u8(self.buffer[0] & 0x0f)
+            .ok_or_else(|| protocol_error("unknown opcode"))?;
+        let masked = self.buffer[1] & 0x80 != 0;
+        let (length, mut offset) = match self.buffer[1] & 0x7f {
+            126 if self.buffer.len() >= 4 => (
+                u16::from_be_bytes([self.buffer[2], self.buffer[3]]) as u64,
+                4,
+            ),
+            127 if self.buffer.len() >= 10 => {
+                let mut length = [0u8; 8];
+                length.copy_from_slice(&self.buffer[2..10]);
+                (u64::from_be_bytes(length), 10)
+            }
+            126 | 127 => return Ok(None),
+            length => (length as u64, 2),
+        };
+        if length > MAX_MESSAGE_BYTES as u64 {
+            return Err(AppError::ParseError(format!(
+                "WebSocket frame of {length} bytes exceeds the {MAX_MESSAGE_BYTES} byte limit"
+            )));
+        }
+        let length = length as usize;
+        let mask = if masked {
+            if self.buffer.len() < offset + 4 {
+                return Ok(None);
+            }
+            offset += 4;
+            Some([
+                self.buffer[offset - 4],
+                self.buffer[offset - 3],
+                self.buffer[offset - 2],
+                self.buffer[offset - 1],
+            ])
+        } else {
+            None
+        };
+        if self.buffer.len() < offset + length {
+            return Ok(None);
+        }
+        let mut payload: Vec<u8> = self.buffer.drain(..offset + length).skip(offset).collect();
+        if let Some(mask) = mask {
+            for (index, byte) in payload.iter_mut().enumerate() {
+                *byte ^= mask[index % 4];
+            }
+        }
+        Ok(Some((fin, opcode, payload)))
+    }
+}
+
+fn control_message(opcode: Opcode, payload: Vec<u8>) -> Result<Message, AppError> {
+    Ok(match opcode {
+        Opcode::Ping => Message::Ping(payload),
+        Opcode::Pong => Message::Pong(payload),
+        _ => Message::Close(match payload.len() {
+            0 => None,
+            1 => return Err(protocol_error("close frame with a 1-byte payload")),
+            _ => Some((
+                u16::from_be_bytes([payload[0], payload[1]]),
+                String::from_utf8_lossy(&payload[2..]).into_owned(),
+            )),
+        }),
+    })
+}
+
+fn protocol_error(what: &str) -> AppError {
+    AppError::ParseError(format!("WebSocket protocol error: {what}"))
+}
+
+/// SHA-1, needed only to check `Sec-WebSocket-Accept`
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut state: [u32; 5] = [
+        0x6745_2301,
+        0xefcd_ab89,
+        0x98ba_dcfe,
+        0x1032_5476,
+        0xc3d2_e1f0,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend(((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = state;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5a82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ed9_eba1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8f1b_bcdc),
+                _ => (b ^ c ^ d, 0xca62_c1d6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (value, add) in state.iter_mut().zip([a, b, c, d, e]) {
+            *value = value.wrapping_add(add);
+        }
+    }
+
+    let mut digest = [0u8; 20];
+    for (chunk, value) in digest.chunks_mut(4).zip(state) {
+        chunk.copy_from_slice(&value.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_accept_key() {
+        // Example from RFC 6455 §1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+        assert_eq!(base64::decode(&handshake_key()).unwrap().len(), 16);
+
+        let response = "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\n\
+                        sec-websocket-accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n\
+                        Sec-WebSocket-Protocol: graphql-transport-ws";
+        assert_eq!(
+            verify_handshake(response, "dGhlIHNhbXBsZSBub25jZQ==").unwrap(),
+            Some("graphql-transport-ws".to_string())
+        );
+        assert!(verify_handshake(response, "b3RoZXIga2V5").is_err());
+        assert!(verify_handshake("HTTP/1.1 403 Forbidden", "a2V5").is_err());
+    }
+
+    #[test]
+    fn test_handshake_request() {
+        let request = handshake_request(
+            "api.example.com",
+            "/graphql",
+            "a2V5",
+            &["graphql-transport-ws"],
+            &[("Authorization".to_string(), "Bearer t".to_string())],
+        );
+        assert!(request.starts_with("GET /graphql HTTP/1.1\r\nHost: api.example.com\r\n"));
+        assert!(request.contains("Sec-WebSocket-Protocol: graphql-transport-ws\r\n"));
+        assert!(request.ends_with("Authorization: Bearer t\r\n\r\n"));
+    }
+
+    #[test]
+    fn test_frame_round_trip() {
+        // Masked "Hello" from RFC 6455 §5.7
+        let frame = encode_frame(Opcode::Text, b"Hello", Some([0x37, 0xfa, 0x21, 0x3d]));
+        assert_eq!(
+            frame,
+            [0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58]
+        );
+        let mut decoder = FrameDecoder::new();
+        assert_eq!(
+            decoder.push(&frame).unwrap(),
+            [Message::Text("Hello".to_string())]
+        );
+
+        let long = vec![7u8; 70_000];
+        let frame = encode_frame(Opcode::Binary, &long, None);
+        assert_eq!(&frame[..2], [0x82, 127]);
+        assert!(decoder.push(&frame[..1000]).unwrap().is_empty());
+        assert_eq!(
+            decoder.push(&frame[1000..]).unwrap(),
+            [Message::Binary(long)]
+        );
+    }
+
+    #[test]
+    fn test_fragments_and_control_frames() {
+        let mut data = vec![0x01, 0x03, b'H', b'e', b'l'];
+        data.extend(encode_frame(Opcode::Ping, b"!", None));
+        data.extend([0x80, 0x02, b'l', b'o']);
+        data.extend(encode_frame(
+            Opcode::Close,
+            &close_payload(1000, "bye"),
+            None,
+        ));
+        assert_eq!(
+            FrameDecoder::new().push(&data).unwrap(),
+            [
+                Message::Ping(b"!".to_vec()),
+                Message::Text("Hello".to_string()),
+                Message::Close(Some((1000, "bye".to_string()))),
+            ]
+        );
+        assert!(FrameDecoder::new().push(&[0x80, 0x00]).is_err());
+        assert!(FrameDecoder::new().push(&[0x83, 0x00]).is_err());
+    }
+}
@@ -0,0 +1,121 @@
+//! Making spaces, tabs and line endings visible in response bodies.
+
+/// How a line of text ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    /// The last line, without a line break after it
+    None,
+}
+
+impl LineEnding {
+    /// Marker drawn after the line
+    pub fn marker(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "␊",
+            LineEnding::CrLf => "␍␊",
+            LineEnding::None => "",
+        }
+    }
+}
+
+/// The lines of `text` as [`str::lines`] splits them, each with the line
+/// ending that followed it
+pub fn lines_with_endings(text: &str) -> Vec<(&str, LineEnding)> {
+    let mut lines = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        match rest.split_once('\n') {
+            Some((line, after)) => {
+                lines.push(match line.strip_suffix('\r') {
+                    Some(line) => (line, LineEnding::CrLf),
+                    None => (line, LineEnding::Lf),
+                });
+                rest = after;
+            }
+            None => {
+                lines.push((rest, LineEnding::None));
+                break;
+            }
+        }
+    }
+    lines
+}
+
+/// The marker shown in place of a whitespace character, if it gets one
+pub fn visible_char(c: char) -> Option<char> {
+    match c {
+        ' ' => Some('·'),
+        '\t' => Some('→'),
+        '\r' => Some('␍'),
+        '\u{a0}' => Some('⍽'),
+        _ => None,
+    }
+}
+
+/// The line with its whitespace replaced by markers, one for one, so
+/// character offsets stay the same
+pub fn visible_whitespace(line: &str) -> String {
+    line.chars().map(|c| visible_char(c).unwrap_or(c)).collect()
+}
+
+/// The line endings used in `text`: `LF`, `CRLF` or `Mixed`, or `None` if
+/// it is a single line
+pub fn line_ending_summary(text: &str) -> Option<&'static str> {
+    let mut lf = false;
+    let mut crlf = false;
+    for (_, ending) in lines_with_endings(text) {
+        match ending {
+            LineEnding::Lf => lf = true,
+            LineEnding::CrLf => crlf = true,
+            LineEnding::None => {}
+        }
+    }
+    match (lf, crlf) {
+        (true, true) => Some("Mixed"),
+        (true, false) => Some("LF"),
+        (false, true) => Some("CRLF"),
+        (false, false) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lines_match_str_lines() {
+        for text in ["", "a", "a\n", "a\r\nb", "a\n\nb\r\n", "a\r", "a\rb\n"] {
+            let lines: Vec<&str> = lines_with_endings(text)
+                .into_iter()
+                .map(|(line, _)| line)
+                .collect();
+            assert_eq!(lines, text.lines().collect::<Vec<_>>(), "{text:?}");
+        }
+        assert_eq!(
+            lines_with_endings("a\r\nb\nc"),
+            vec![
+                ("a", LineEnding::CrLf),
+                ("b", LineEnding::Lf),
+                ("c", LineEnding::None)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visible_whitespace_keeps_char_count() {
+        let line = "\tkey = \"a b\"\r";
+        let visible = visible_whitespace(line);
+        assert_eq!(visible, "→key·=·\"a·b\"␍");
+        assert_eq!(visible.chars().count(), line.chars().count());
+    }
+
+    #[test]
+    fn test_line_ending_summary() {
+        assert_eq!(line_ending_summary("one line"), None);
+        assert_eq!(line_ending_summary("a\nb\n"), Some("LF"));
+        assert_eq!(line_ending_summary("a\r\nb"), Some("CRLF"));
+        assert_eq!(line_ending_summary("a\r\nb\nc"), Some("Mixed"));
+    }
+}